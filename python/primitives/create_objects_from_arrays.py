@@ -0,0 +1,54 @@
+import math
+
+import numpy as np
+
+from savant_rs.utils import gen_empty_frame
+
+f = gen_empty_frame()
+
+boxes = np.array([
+    [10.0, 20.0, 30.0, 40.0, float("nan")],
+    [50.0, 60.0, 70.0, 80.0, 45.0],
+], dtype=np.float32)
+confidences = np.array([0.9, 0.5], dtype=np.float32)
+track_ids = np.array([7, 8], dtype=np.int64)
+
+objects = f.create_objects_from_arrays("detector", ["person", "car"], boxes, confidences, track_ids)
+assert [o.label for o in objects] == ["person", "car"]
+assert len(set(o.id for o in objects)) == 2
+assert all(o.namespace == "detector" for o in objects)
+
+person, car = objects
+assert person.detection_box.xc == 10.0 and person.detection_box.height == 40.0
+assert person.detection_box.angle is None
+assert car.detection_box.angle == 45.0
+assert math.isclose(person.confidence, 0.9, rel_tol=1e-6)
+assert car.track_id == 8
+assert car.track_box.width == 70.0
+
+objects = f.create_objects_from_arrays("detector", ["dog"], boxes[:1], confidences[:1])
+assert objects[0].track_id is None
+assert len(f.get_all_objects()) == 3
+
+
+def expect_value_error(*args):
+    try:
+        f.create_objects_from_arrays(*args)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("ValueError expected")
+
+
+# the number of labels does not match the number of rows
+expect_value_error("detector", ["person"], boxes, confidences)
+expect_value_error("detector", ["person", "car"], boxes, confidences[:1])
+expect_value_error("detector", ["person", "car"], boxes, confidences, track_ids[:1])
+# the rows must have five columns
+expect_value_error("detector", ["person", "car"], boxes[:, :4].copy(), confidences)
+# wrong element types
+expect_value_error("detector", ["person", "car"], boxes.astype(np.float64), confidences)
+expect_value_error("detector", ["person", "car"], boxes, confidences.astype(np.float64))
+expect_value_error("detector", ["person", "car"], boxes, confidences, track_ids.astype(np.int32))
+
+assert len(f.get_all_objects()) == 3
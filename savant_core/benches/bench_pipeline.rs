@@ -168,10 +168,12 @@ fn bench_pipeline2_with_jaeger_no_sampling(b: &mut Bencher) -> Result<()> {
         endpoint: "http://localhost:4317".to_string(),
         tls: None,
         timeout: None,
+        sampler: None,
     };
     let config = TelemetryConfiguration {
         context_propagation_format: Some(ContextPropagationFormat::Jaeger),
         tracer: Some(tracer_config),
+        logger: None,
     };
     init(&config);
     let (mut pipeline, stages) = get_pipeline(false)?;
@@ -0,0 +1,3 @@
+pub mod association;
+pub mod line_crossing;
+pub mod track_manager;
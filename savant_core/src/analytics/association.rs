@@ -0,0 +1,197 @@
+use crate::primitives::object::{BorrowedVideoObject, ObjectOperations};
+use crate::primitives::{BBoxMetricType, RBBox};
+
+/// Solves the rectangular assignment problem minimizing the total cost with the Hungarian
+/// algorithm. Every row is assigned to a distinct column when there are no more rows than
+/// columns, otherwise every column is assigned to a distinct row. Returns the column of every
+/// row.
+///
+pub fn hungarian(costs: &[Vec<f64>]) -> Vec<Option<usize>> {
+    let rows = costs.len();
+    let cols = costs.first().map_or(0, |r| r.len());
+    if rows == 0 || cols == 0 {
+        return vec![None; rows];
+    }
+    if rows > cols {
+        let transposed = (0..cols)
+            .map(|c| costs.iter().map(|r| r[c]).collect())
+            .collect::<Vec<Vec<f64>>>();
+        let mut assignment = vec![None; rows];
+        for (c, r) in hungarian(&transposed).into_iter().enumerate() {
+            if let Some(r) = r {
+                assignment[r] = Some(c);
+            }
+        }
+        return assignment;
+    }
+
+    // potentials and matching are 1-based, the column 0 is the auxiliary one
+    let mut u = vec![0.0; rows + 1];
+    let mut v = vec![0.0; cols + 1];
+    let mut matched_row = vec![0; cols + 1];
+    let mut way = vec![0; cols + 1];
+    for row in 1..=rows {
+        matched_row[0] = row;
+        let mut col0 = 0;
+        let mut min_slack = vec![f64::INFINITY; cols + 1];
+        let mut used = vec![false; cols + 1];
+        loop {
+            used[col0] = true;
+            let row0 = matched_row[col0];
+            let mut delta = f64::INFINITY;
+            let mut col1 = 0;
+            for col in 1..=cols {
+                if used[col] {
+                    continue;
+                }
+                let slack = costs[row0 - 1][col - 1] - u[row0] - v[col];
+                if slack < min_slack[col] {
+                    min_slack[col] = slack;
+                    way[col] = col0;
+                }
+                if min_slack[col] < delta {
+                    delta = min_slack[col];
+                    col1 = col;
+                }
+            }
+            for col in 0..=cols {
+                if used[col] {
+                    u[matched_row[col]] += delta;
+                    v[col] -= delta;
+                } else {
+                    min_slack[col] -= delta;
+                }
+            }
+            col0 = col1;
+            if matched_row[col0] == 0 {
+                break;
+            }
+        }
+        loop {
+            let col1 = way[col0];
+            matched_row[col0] = matched_row[col1];
+            col0 = col1;
+            if col0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assignment = vec![None; rows];
+    for col in 1..=cols {
+        if matched_row[col] != 0 {
+            assignment[matched_row[col] - 1] = Some(col - 1);
+        }
+    }
+    assignment
+}
+
+fn metric(a: &RBBox, b: &RBBox, metric: BBoxMetricType) -> Option<f32> {
+    match metric {
+        BBoxMetricType::IoU => a.iou(b),
+        BBoxMetricType::IoSelf => a.ios(b),
+        BBoxMetricType::IoOther => a.ioo(b),
+    }
+    .ok()
+}
+
+/// Associates the new objects with the previous ones by their detection boxes, maximizing the
+/// total metric over the pairs with the metric above the threshold. Every previous object is
+/// associated with at most one new object.
+///
+/// Returns the id of the associated previous object (if any) and the id of every new object, in
+/// the order of the new objects.
+///
+pub fn associate(
+    prev_objects: &[BorrowedVideoObject],
+    new_objects: &[BorrowedVideoObject],
+    metric_type: BBoxMetricType,
+    threshold: f32,
+) -> Vec<(Option<i64>, i64)> {
+    let prev_boxes = prev_objects
+        .iter()
+        .map(|o| o.get_detection_box())
+        .collect::<Vec<_>>();
+    let new_boxes = new_objects
+        .iter()
+        .map(|o| o.get_detection_box())
+        .collect::<Vec<_>>();
+    // the pairs below the threshold cost the same as the unassigned objects
+    let costs = new_boxes
+        .iter()
+        .map(|n| {
+            prev_boxes
+                .iter()
+                .map(|p| match metric(n, p, metric_type) {
+                    Some(m) if m > threshold => -(m as f64),
+                    _ => 0.0,
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<f64>>>();
+    hungarian(&costs)
+        .into_iter()
+        .zip(new_objects)
+        .enumerate()
+        .map(|(i, (col, o))| {
+            let prev = col
+                .filter(|c| costs[i][*c] < 0.0)
+                .map(|c| prev_objects[c].get_id());
+            (prev, o.get_id())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::object::IdCollisionResolutionPolicy;
+    use crate::test::{gen_empty_frame, gen_object};
+
+    #[test]
+    fn test_hungarian() {
+        let costs = vec![
+            vec![4.0, 1.0, 3.0],
+            vec![2.0, 0.0, 5.0],
+            vec![3.0, 2.0, 2.0],
+        ];
+        assert_eq!(hungarian(&costs), vec![Some(1), Some(0), Some(2)]);
+
+        let wide = vec![vec![5.0, 1.0, 4.0]];
+        assert_eq!(hungarian(&wide), vec![Some(1)]);
+
+        let tall = vec![vec![5.0], vec![1.0], vec![4.0]];
+        assert_eq!(hungarian(&tall), vec![None, Some(0), None]);
+
+        assert!(hungarian(&[]).is_empty());
+        assert_eq!(hungarian(&[vec![]]), vec![None]);
+    }
+
+    #[test]
+    fn test_associate() {
+        let frame = gen_empty_frame();
+        let boxes = [
+            (1, RBBox::ltrb(10.0, 0.0, 20.0, 10.0)),
+            (2, RBBox::ltrb(16.0, 0.0, 26.0, 10.0)),
+            (3, RBBox::ltrb(12.0, 0.0, 22.0, 10.0)),
+            (4, RBBox::ltrb(8.0, 0.0, 18.0, 10.0)),
+            (5, RBBox::ltrb(100.0, 100.0, 110.0, 110.0)),
+        ];
+        for (id, bbox) in boxes {
+            let mut object = gen_object(id);
+            object.detection_box = bbox;
+            frame
+                .add_object(object, IdCollisionResolutionPolicy::Error)
+                .unwrap();
+        }
+        let prev = frame.access_objects_with_id(&[1, 2]);
+        let new = frame.access_objects_with_id(&[3, 4, 5]);
+        // the greedy matching would pair 3 with its best match 1 and leave 4 unmatched
+        let pairs = associate(&prev, &new, BBoxMetricType::IoU, 0.2);
+        assert_eq!(pairs, vec![(Some(2), 3), (Some(1), 4), (None, 5)]);
+
+        let pairs = associate(&prev, &new, BBoxMetricType::IoU, 0.9);
+        assert_eq!(pairs, vec![(None, 3), (None, 4), (None, 5)]);
+        assert!(associate(&prev, &[], BBoxMetricType::IoU, 0.1).is_empty());
+    }
+}
@@ -0,0 +1,392 @@
+use crate::match_query::MatchQuery;
+use crate::primitives::attribute_value::AttributeValue;
+use crate::primitives::frame::VideoFrameProxy;
+use crate::primitives::object::ObjectOperations;
+use crate::primitives::{Point, PolygonalArea, Segment, WithAttributes};
+use anyhow::bail;
+use hashbrown::{HashMap, HashSet};
+
+pub const LINE_CROSSING_ATTRIBUTE_NAMESPACE: &str = "line_crossing";
+
+/// The point of the object box which is tracked against the lines and areas.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnchorPoint {
+    #[default]
+    Center,
+    /// The middle of the bottom edge, suitable for people and vehicles on the ground plane.
+    BottomCenter,
+}
+
+/// The direction of a line crossing relative to the line going from `begin` to `end`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrossingDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrossingEventKind {
+    Crossed(CrossingDirection),
+    Entered,
+    Exited,
+}
+
+impl CrossingEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrossingEventKind::Crossed(CrossingDirection::LeftToRight) => "crossed_left_to_right",
+            CrossingEventKind::Crossed(CrossingDirection::RightToLeft) => "crossed_right_to_left",
+            CrossingEventKind::Entered => "entered",
+            CrossingEventKind::Exited => "exited",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossingEvent {
+    pub source_id: String,
+    pub object_id: i64,
+    pub track_id: i64,
+    /// The name of the line or the area.
+    pub zone: String,
+    pub kind: CrossingEventKind,
+}
+
+#[derive(Debug)]
+struct TrackState {
+    position: Point,
+    last_seen: u64,
+    inside: HashSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct StreamState {
+    frame_counter: u64,
+    tracks: HashMap<i64, TrackState>,
+    counts: HashMap<(String, CrossingEventKind), u64>,
+}
+
+/// Keeps per-stream, per-track positions and produces line crossing and area enter/exit events
+/// for the tracked objects of consecutive frames. Every object with events gets a persistent
+/// attribute in the `line_crossing` namespace per event kind, holding the names of the zones.
+///
+#[derive(Debug)]
+pub struct LineCrossingCounter {
+    lines: Vec<(String, Segment)>,
+    areas: Vec<(String, PolygonalArea)>,
+    anchor: AnchorPoint,
+    query: MatchQuery,
+    track_ttl: u64,
+    streams: HashMap<String, StreamState>,
+}
+
+impl LineCrossingCounter {
+    /// Creates the counter; the state of a track is dropped when it is not seen for `track_ttl`
+    /// frames of its stream.
+    ///
+    pub fn new(anchor: AnchorPoint, track_ttl: u64) -> Self {
+        Self {
+            lines: Vec::new(),
+            areas: Vec::new(),
+            anchor,
+            query: MatchQuery::Idle,
+            track_ttl,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Only the objects matching the query are counted.
+    ///
+    pub fn with_query(mut self, query: MatchQuery) -> Self {
+        self.query = query;
+        self
+    }
+
+    fn check_zone_name(&self, name: &str) -> anyhow::Result<()> {
+        if self.lines.iter().any(|(n, _)| n == name) || self.areas.iter().any(|(n, _)| n == name) {
+            bail!("Zone {} is already defined", name)
+        }
+        Ok(())
+    }
+
+    pub fn add_line(&mut self, name: &str, line: Segment) -> anyhow::Result<()> {
+        self.check_zone_name(name)?;
+        self.lines.push((name.to_string(), line));
+        Ok(())
+    }
+
+    pub fn add_area(&mut self, name: &str, area: PolygonalArea) -> anyhow::Result<()> {
+        self.check_zone_name(name)?;
+        self.areas.push((name.to_string(), area));
+        Ok(())
+    }
+
+    /// Processes the next frame of its stream and returns the produced events.
+    ///
+    pub fn process_frame(&mut self, frame: &VideoFrameProxy) -> Vec<CrossingEvent> {
+        let source_id = frame.get_source_id();
+        let stream = self.streams.entry(source_id.clone()).or_default();
+        stream.frame_counter += 1;
+        let now = stream.frame_counter;
+        let mut events = Vec::new();
+
+        for mut object in frame.access_objects(&self.query) {
+            let Some(track_id) = object.get_track_id() else {
+                continue;
+            };
+            let bbox = object
+                .get_track_box()
+                .unwrap_or_else(|| object.get_detection_box());
+            let position = match self.anchor {
+                AnchorPoint::Center => Point::new(bbox.get_xc(), bbox.get_yc()),
+                AnchorPoint::BottomCenter => {
+                    Point::new(bbox.get_xc(), bbox.get_yc() + bbox.get_height() / 2.0)
+                }
+            };
+            let mut inside = HashSet::new();
+            for (name, area) in self.areas.iter_mut() {
+                if area.contains(&position) {
+                    inside.insert(name.clone());
+                }
+            }
+
+            let mut kinds = Vec::new();
+            if let Some(state) = stream.tracks.get(&track_id) {
+                for (name, line) in &self.lines {
+                    if let Some(direction) = crossing_direction(line, &state.position, &position) {
+                        kinds.push((name.clone(), CrossingEventKind::Crossed(direction)));
+                    }
+                }
+                for (name, _) in &self.areas {
+                    match (state.inside.contains(name), inside.contains(name)) {
+                        (false, true) => kinds.push((name.clone(), CrossingEventKind::Entered)),
+                        (true, false) => kinds.push((name.clone(), CrossingEventKind::Exited)),
+                        _ => {}
+                    }
+                }
+            }
+            stream.tracks.insert(
+                track_id,
+                TrackState {
+                    position,
+                    last_seen: now,
+                    inside,
+                },
+            );
+
+            let mut zones_by_kind: HashMap<CrossingEventKind, Vec<String>> = HashMap::new();
+            for (zone, kind) in kinds {
+                *stream.counts.entry((zone.clone(), kind)).or_insert(0) += 1;
+                zones_by_kind.entry(kind).or_default().push(zone.clone());
+                events.push(CrossingEvent {
+                    source_id: source_id.clone(),
+                    object_id: object.get_id(),
+                    track_id,
+                    zone,
+                    kind,
+                });
+            }
+            for (kind, zones) in zones_by_kind {
+                object.set_persistent_attribute(
+                    LINE_CROSSING_ATTRIBUTE_NAMESPACE,
+                    kind.as_str(),
+                    &None,
+                    false,
+                    zones
+                        .iter()
+                        .map(|z| AttributeValue::string(z, None))
+                        .collect(),
+                );
+            }
+        }
+
+        let ttl = self.track_ttl;
+        stream
+            .tracks
+            .retain(|_, state| now - state.last_seen <= ttl);
+        events
+    }
+
+    /// Returns the number of events of the kind produced for the zone in the stream.
+    ///
+    pub fn get_count(&self, source_id: &str, zone: &str, kind: CrossingEventKind) -> u64 {
+        self.streams
+            .get(source_id)
+            .and_then(|s| s.counts.get(&(zone.to_string(), kind)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Drops the state and counters of the stream, e.g. on the end of stream.
+    ///
+    pub fn reset_stream(&mut self, source_id: &str) {
+        self.streams.remove(source_id);
+    }
+}
+
+fn cross(o: &Point, a: &Point, b: &Point) -> f32 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+/// Detects if the movement from `from` to `to` crosses the line. The points lying on the line
+/// are considered to be on its left side.
+///
+fn crossing_direction(line: &Segment, from: &Point, to: &Point) -> Option<CrossingDirection> {
+    let from_right = cross(&line.begin, &line.end, from) > 0.0;
+    let to_right = cross(&line.begin, &line.end, to) > 0.0;
+    if from_right == to_right {
+        return None;
+    }
+    // the movement must pass between the ends of the line, not beyond them
+    let e1 = cross(from, to, &line.begin);
+    let e2 = cross(from, to, &line.end);
+    if e1 * e2 > 0.0 {
+        return None;
+    }
+    // image coordinates: y grows downwards, so a positive cross product is on the right side
+    Some(if from_right {
+        CrossingDirection::RightToLeft
+    } else {
+        CrossingDirection::LeftToRight
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::object::{IdCollisionResolutionPolicy, VideoObjectBuilder};
+    use crate::primitives::RBBox;
+    use crate::test::gen_empty_frame;
+
+    fn frame_with_track(track_id: i64, xc: f32, yc: f32) -> VideoFrameProxy {
+        let frame = gen_empty_frame();
+        let object = VideoObjectBuilder::default()
+            .id(1)
+            .detection_box(RBBox::new(xc, yc, 10.0, 10.0, None))
+            .track_id(Some(track_id))
+            .track_box(Some(RBBox::new(xc, yc, 10.0, 10.0, None)))
+            .attributes(Vec::default())
+            .confidence(None)
+            .namespace("detector".to_string())
+            .label("person".to_string())
+            .build()
+            .unwrap();
+        frame
+            .add_object(object, IdCollisionResolutionPolicy::Error)
+            .unwrap();
+        frame
+    }
+
+    fn counter() -> LineCrossingCounter {
+        let mut counter = LineCrossingCounter::new(AnchorPoint::Center, 2);
+        counter
+            .add_line(
+                "gate",
+                Segment::new(Point::new(50.0, 0.0), Point::new(50.0, 100.0)),
+            )
+            .unwrap();
+        counter
+            .add_area(
+                "zone",
+                PolygonalArea::new(
+                    vec![
+                        Point::new(60.0, 0.0),
+                        Point::new(100.0, 0.0),
+                        Point::new(100.0, 100.0),
+                        Point::new(60.0, 100.0),
+                    ],
+                    None,
+                ),
+            )
+            .unwrap();
+        counter
+    }
+
+    #[test]
+    fn test_duplicate_zone() {
+        let mut counter = counter();
+        assert!(counter
+            .add_line(
+                "zone",
+                Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 1.0))
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_crossing_and_areas() {
+        let mut counter = counter();
+        assert!(counter
+            .process_frame(&frame_with_track(7, 40.0, 50.0))
+            .is_empty());
+
+        let frame = frame_with_track(7, 70.0, 50.0);
+        let events = counter.process_frame(&frame);
+        assert_eq!(events.len(), 2);
+        let kinds = events.iter().map(|e| e.kind).collect::<Vec<_>>();
+        assert!(kinds.contains(&CrossingEventKind::Entered));
+        let crossed = kinds
+            .iter()
+            .find(|k| matches!(k, CrossingEventKind::Crossed(_)))
+            .copied()
+            .unwrap();
+        let attr = frame
+            .get_object(1)
+            .unwrap()
+            .get_attribute(LINE_CROSSING_ATTRIBUTE_NAMESPACE, crossed.as_str())
+            .unwrap();
+        assert_eq!(attr.values.len(), 1);
+
+        let events = counter.process_frame(&frame_with_track(7, 30.0, 50.0));
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().any(|e| e.kind == CrossingEventKind::Exited));
+        let back = events
+            .iter()
+            .find(|e| matches!(e.kind, CrossingEventKind::Crossed(_)))
+            .unwrap();
+        assert_ne!(back.kind, crossed);
+
+        assert_eq!(counter.get_count("test", "gate", crossed), 1);
+        assert_eq!(
+            counter.get_count("test", "zone", CrossingEventKind::Entered),
+            1
+        );
+        counter.reset_stream("test");
+        assert_eq!(counter.get_count("test", "gate", crossed), 0);
+    }
+
+    #[test]
+    fn test_track_expiration() {
+        let mut counter = counter();
+        counter.process_frame(&frame_with_track(1, 40.0, 50.0));
+        for _ in 0..3 {
+            counter.process_frame(&gen_empty_frame());
+        }
+        // the state of the track is dropped, so there is no movement to check
+        assert!(counter
+            .process_frame(&frame_with_track(1, 70.0, 50.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_crossing_direction() {
+        let line = Segment::new(Point::new(0.0, 0.0), Point::new(0.0, 10.0));
+        // the line goes downwards, so its right side is at the negative x
+        let left = Point::new(1.0, 5.0);
+        let right = Point::new(-1.0, 5.0);
+        assert_eq!(
+            crossing_direction(&line, &left, &right),
+            Some(CrossingDirection::LeftToRight)
+        );
+        assert_eq!(
+            crossing_direction(&line, &right, &left),
+            Some(CrossingDirection::RightToLeft)
+        );
+        assert!(crossing_direction(&line, &left, &Point::new(2.0, 5.0)).is_none());
+        assert!(
+            crossing_direction(&line, &Point::new(1.0, 20.0), &Point::new(-1.0, 20.0)).is_none()
+        );
+    }
+}
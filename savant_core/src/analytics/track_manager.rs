@@ -0,0 +1,478 @@
+use crate::match_query::MatchQuery;
+use crate::primitives::attribute_value::AttributeValue;
+use crate::primitives::frame::VideoFrameProxy;
+use crate::primitives::object::ObjectOperations;
+use crate::primitives::track_history::TrackHistory;
+use crate::primitives::{RBBox, WithAttributes};
+use hashbrown::HashMap;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackManagerConfig {
+    /// The number of observations after which a track is considered born.
+    pub min_hits: u64,
+    /// The number of consecutive frames without observations after which a track dies.
+    pub max_missed: u64,
+}
+
+impl Default for TrackManagerConfig {
+    fn default() -> Self {
+        Self {
+            min_hits: 3,
+            max_missed: 30,
+        }
+    }
+}
+
+/// The lifecycle state of a track.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackState {
+    /// The track is observed, but has not reached `min_hits` observations yet.
+    Tentative,
+    /// The confirmed track is observed in the latest frame of its stream.
+    Confirmed,
+    /// The confirmed track is missed in the latest frame of its stream, but not for long enough
+    /// to die.
+    Lost,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackEventKind {
+    Born,
+    Died,
+}
+
+/// The values of an object attribute accumulated over the observations of a track.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackAttribute {
+    pub namespace: String,
+    pub name: String,
+    /// The pts and the values of the latest observations having the attribute, from the oldest
+    /// to the newest.
+    pub observations: VecDeque<(i64, Vec<AttributeValue>)>,
+}
+
+/// The aggregated information about a track.
+///
+#[derive(Debug, Clone)]
+pub struct TrackAggregate {
+    pub source_id: String,
+    pub track_id: i64,
+    pub namespace: String,
+    pub label: String,
+    pub first_seen_pts: i64,
+    pub last_seen_pts: i64,
+    pub hits: u64,
+    /// Whether the track reached `min_hits` observations.
+    pub confirmed: bool,
+    pub state: TrackState,
+    pub max_confidence: Option<f32>,
+    /// The detection box of the observation with the highest confidence; the larger box wins
+    /// between the equally confident ones. Useful to select the best crop of the object.
+    pub best_bbox: RBBox,
+    pub best_bbox_pts: i64,
+    /// The accumulated attributes, see [`TrackManager::with_attributes`].
+    pub attributes: Vec<TrackAttribute>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackEvent {
+    pub kind: TrackEventKind,
+    pub track: TrackAggregate,
+}
+
+#[derive(Debug)]
+struct TrackEntry {
+    aggregate: TrackAggregate,
+    best_score: (f32, f32),
+    first_seen: u64,
+    last_seen: u64,
+    history: Option<TrackHistory>,
+}
+
+#[derive(Debug, Default)]
+struct StreamTracks {
+    frame_counter: u64,
+    tracks: HashMap<i64, TrackEntry>,
+}
+
+/// Follows the tracks reported by an external tracker, produces the birth and death events and
+/// keeps the per-track aggregates.
+///
+#[derive(Debug)]
+pub struct TrackManager {
+    config: TrackManagerConfig,
+    query: MatchQuery,
+    history_capacity: Option<usize>,
+    max_age: Option<u64>,
+    attributes: Vec<(String, String)>,
+    attribute_capacity: usize,
+    streams: HashMap<String, StreamTracks>,
+}
+
+impl TrackManager {
+    pub fn new(config: TrackManagerConfig) -> Self {
+        Self {
+            config,
+            query: MatchQuery::Idle,
+            history_capacity: None,
+            max_age: None,
+            attributes: Vec::new(),
+            attribute_capacity: 0,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Only the objects matching the query are tracked.
+    ///
+    pub fn with_query(mut self, query: MatchQuery) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// Keeps the latest `capacity` detection boxes of every track and attaches the history to the
+    /// tracked objects, so the track queries (`track.speed`, `track.direction`, `track.age`) can be
+    /// evaluated against them.
+    ///
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "Track history capacity must be positive");
+        self.history_capacity = Some(capacity);
+        self
+    }
+
+    /// Limits the lifetime of the tracks: a track dies when `frames` frames of its stream passed
+    /// since its first observation, even if it is still observed. The object reappearing with the
+    /// same track id starts a new track.
+    ///
+    pub fn with_max_age(mut self, frames: u64) -> Self {
+        self.max_age = Some(frames);
+        self
+    }
+
+    /// Accumulates the values of the listed object attributes in the track aggregates, keeping
+    /// the latest `capacity` observations of every attribute.
+    ///
+    pub fn with_attributes(mut self, attributes: Vec<(String, String)>, capacity: usize) -> Self {
+        assert!(capacity > 0, "Track attribute capacity must be positive");
+        self.attributes = attributes;
+        self.attribute_capacity = capacity;
+        self
+    }
+
+    pub fn get_config(&self) -> TrackManagerConfig {
+        self.config
+    }
+
+    /// Ingests the tracked objects of the next frame of its stream. Returns the births of the
+    /// tracks confirmed by the frame and the deaths of the tracks missed for too long.
+    ///
+    pub fn process_frame(&mut self, frame: &VideoFrameProxy) -> Vec<TrackEvent> {
+        let source_id = frame.get_source_id();
+        let pts = frame.get_pts();
+        let stream = self.streams.entry(source_id.clone()).or_default();
+        stream.frame_counter += 1;
+        let now = stream.frame_counter;
+        let mut events = Vec::new();
+
+        for mut object in frame.access_objects(&self.query) {
+            let Some(track_id) = object.get_track_id() else {
+                continue;
+            };
+            let bbox = object.get_detection_box();
+            let confidence = object.get_confidence();
+            let score = (
+                confidence.unwrap_or(0.0),
+                bbox.get_width() * bbox.get_height(),
+            );
+            let entry = stream.tracks.entry(track_id).or_insert_with(|| TrackEntry {
+                aggregate: TrackAggregate {
+                    source_id: source_id.clone(),
+                    track_id,
+                    namespace: object.get_namespace(),
+                    label: object.get_label(),
+                    first_seen_pts: pts,
+                    last_seen_pts: pts,
+                    hits: 0,
+                    confirmed: false,
+                    state: TrackState::Tentative,
+                    max_confidence: None,
+                    best_bbox: bbox.copy(),
+                    best_bbox_pts: pts,
+                    attributes: self
+                        .attributes
+                        .iter()
+                        .map(|(namespace, name)| TrackAttribute {
+                            namespace: namespace.clone(),
+                            name: name.clone(),
+                            observations: VecDeque::new(),
+                        })
+                        .collect(),
+                },
+                best_score: score,
+                first_seen: now,
+                last_seen: now,
+                history: None,
+            });
+            if let Some(capacity) = self.history_capacity {
+                let history = entry
+                    .history
+                    .get_or_insert_with(|| TrackHistory::new(capacity));
+                history.push(pts, &bbox);
+                object.set_track_history(Some(history.clone()));
+            }
+            let aggregate = &mut entry.aggregate;
+            aggregate.hits += 1;
+            aggregate.last_seen_pts = pts;
+            entry.last_seen = now;
+            if let Some(c) = confidence {
+                aggregate.max_confidence = Some(aggregate.max_confidence.map_or(c, |m| m.max(c)));
+            }
+            if score > entry.best_score {
+                entry.best_score = score;
+                aggregate.best_bbox = bbox.copy();
+                aggregate.best_bbox_pts = pts;
+            }
+            for attribute in &mut aggregate.attributes {
+                let Some(values) = object.get_attribute(&attribute.namespace, &attribute.name)
+                else {
+                    continue;
+                };
+                if attribute.observations.len() == self.attribute_capacity {
+                    attribute.observations.pop_front();
+                }
+                attribute
+                    .observations
+                    .push_back((pts, values.get_values().clone()));
+            }
+            if aggregate.state == TrackState::Lost {
+                aggregate.state = TrackState::Confirmed;
+            }
+            if !aggregate.confirmed && aggregate.hits >= self.config.min_hits {
+                aggregate.confirmed = true;
+                aggregate.state = TrackState::Confirmed;
+                events.push(TrackEvent {
+                    kind: TrackEventKind::Born,
+                    track: aggregate.clone(),
+                });
+            }
+        }
+
+        let max_missed = self.config.max_missed;
+        let max_age = self.max_age;
+        stream.tracks.retain(|_, entry| {
+            let expired = max_age.is_some_and(|age| now - entry.first_seen >= age);
+            if now - entry.last_seen <= max_missed && !expired {
+                if entry.last_seen < now && entry.aggregate.confirmed {
+                    entry.aggregate.state = TrackState::Lost;
+                }
+                return true;
+            }
+            if entry.aggregate.confirmed {
+                events.push(TrackEvent {
+                    kind: TrackEventKind::Died,
+                    track: entry.aggregate.clone(),
+                });
+            }
+            false
+        });
+        events
+    }
+
+    /// Finishes the stream: all its confirmed tracks die, the state is dropped.
+    ///
+    pub fn finish_stream(&mut self, source_id: &str) -> Vec<TrackEvent> {
+        self.streams
+            .remove(source_id)
+            .map(|s| {
+                s.tracks
+                    .into_values()
+                    .filter(|e| e.aggregate.confirmed)
+                    .map(|e| TrackEvent {
+                        kind: TrackEventKind::Died,
+                        track: e.aggregate,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_track(&self, source_id: &str, track_id: i64) -> Option<TrackAggregate> {
+        self.streams
+            .get(source_id)
+            .and_then(|s| s.tracks.get(&track_id))
+            .map(|e| e.aggregate.clone())
+    }
+
+    /// Returns the live tracks of the stream ordered by id.
+    ///
+    pub fn get_tracks(&self, source_id: &str, confirmed_only: bool) -> Vec<TrackAggregate> {
+        let mut tracks = self
+            .streams
+            .get(source_id)
+            .map(|s| {
+                s.tracks
+                    .values()
+                    .filter(|e| !confirmed_only || e.aggregate.confirmed)
+                    .map(|e| e.aggregate.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        tracks.sort_by_key(|t| t.track_id);
+        tracks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::object::{IdCollisionResolutionPolicy, VideoObjectBuilder};
+    use crate::test::gen_empty_frame;
+
+    fn frame(pts: i64, tracks: &[(i64, f32, f32)]) -> VideoFrameProxy {
+        let mut frame = gen_empty_frame();
+        frame.set_pts(pts);
+        for (id, (track_id, confidence, size)) in tracks.iter().enumerate() {
+            let object = VideoObjectBuilder::default()
+                .id(id as i64)
+                .detection_box(RBBox::new(50.0, 50.0, *size, *size, None))
+                .track_id(Some(*track_id))
+                .attributes(Vec::default())
+                .confidence(Some(*confidence))
+                .namespace("detector".to_string())
+                .label("car".to_string())
+                .build()
+                .unwrap();
+            frame
+                .add_object(object, IdCollisionResolutionPolicy::Error)
+                .unwrap();
+        }
+        frame
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let mut manager = TrackManager::new(TrackManagerConfig {
+            min_hits: 2,
+            max_missed: 1,
+        });
+        assert!(manager
+            .process_frame(&frame(0, &[(1, 0.5, 10.0)]))
+            .is_empty());
+        assert_eq!(manager.get_tracks("test", true).len(), 0);
+        assert_eq!(manager.get_tracks("test", false).len(), 1);
+
+        let events = manager.process_frame(&frame(1, &[(1, 0.9, 20.0), (2, 0.3, 5.0)]));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, TrackEventKind::Born);
+        assert_eq!(events[0].track.track_id, 1);
+
+        manager.process_frame(&frame(2, &[(1, 0.7, 30.0)]));
+        let track = manager.get_track("test", 1).unwrap();
+        assert_eq!(track.hits, 3);
+        assert_eq!(track.max_confidence, Some(0.9));
+        assert_eq!(track.best_bbox_pts, 1);
+        assert_eq!(track.best_bbox.get_width(), 20.0);
+        assert_eq!((track.first_seen_pts, track.last_seen_pts), (0, 2));
+
+        // the unconfirmed track 2 disappears silently, track 1 dies
+        manager.process_frame(&frame(3, &[]));
+        let events = manager.process_frame(&frame(4, &[]));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, TrackEventKind::Died);
+        assert_eq!(events[0].track.track_id, 1);
+        assert!(manager.get_tracks("test", false).is_empty());
+    }
+
+    #[test]
+    fn test_states_and_max_age() {
+        let mut manager = TrackManager::new(TrackManagerConfig {
+            min_hits: 2,
+            max_missed: 2,
+        })
+        .with_max_age(4);
+        let state = |m: &TrackManager| m.get_track("test", 1).map(|t| t.state);
+        manager.process_frame(&frame(0, &[(1, 0.5, 10.0)]));
+        assert_eq!(state(&manager), Some(TrackState::Tentative));
+        manager.process_frame(&frame(1, &[(1, 0.5, 10.0)]));
+        assert_eq!(state(&manager), Some(TrackState::Confirmed));
+        manager.process_frame(&frame(2, &[]));
+        assert_eq!(state(&manager), Some(TrackState::Lost));
+        manager.process_frame(&frame(3, &[(1, 0.5, 10.0)]));
+        assert_eq!(state(&manager), Some(TrackState::Confirmed));
+
+        // the track is still observed, but too old
+        let events = manager.process_frame(&frame(4, &[(1, 0.5, 10.0)]));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, TrackEventKind::Died);
+        assert!(manager.get_track("test", 1).is_none());
+    }
+
+    #[test]
+    fn test_attributes() {
+        let mut manager = TrackManager::new(TrackManagerConfig::default())
+            .with_attributes(vec![("classifier".to_string(), "color".to_string())], 2);
+        for (pts, color) in [
+            (0, Some("red")),
+            (1, None),
+            (2, Some("blue")),
+            (3, Some("red")),
+        ] {
+            let f = frame(pts, &[(1, 0.5, 10.0)]);
+            if let Some(color) = color {
+                let mut object = f.get_object(0).unwrap();
+                object.set_persistent_attribute(
+                    "classifier",
+                    "color",
+                    &None,
+                    false,
+                    vec![AttributeValue::string(color, None)],
+                );
+            }
+            manager.process_frame(&f);
+        }
+        let track = manager.get_track("test", 1).unwrap();
+        assert_eq!(track.attributes.len(), 1);
+        let observations = &track.attributes[0].observations;
+        assert_eq!(
+            observations.iter().map(|(pts, _)| *pts).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(observations[1].1, vec![AttributeValue::string("red", None)]);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut manager = TrackManager::new(TrackManagerConfig::default()).with_history(2);
+        let frames = (0..3)
+            .map(|pts| {
+                let f = frame(pts, &[(1, 0.5, 10.0)]);
+                manager.process_frame(&f);
+                f
+            })
+            .collect::<Vec<_>>();
+        let history = frames[2]
+            .get_object(0)
+            .unwrap()
+            .get_track_history()
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get_observations(), 3);
+        assert_eq!(history.get_entries()[0].0, 1);
+    }
+
+    #[test]
+    fn test_finish_stream() {
+        let mut manager = TrackManager::new(TrackManagerConfig {
+            min_hits: 1,
+            max_missed: 10,
+        });
+        let events = manager.process_frame(&frame(0, &[(1, 0.5, 10.0), (2, 0.5, 10.0)]));
+        assert_eq!(events.len(), 2);
+        let events = manager.finish_stream("test");
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.kind == TrackEventKind::Died));
+        assert!(manager.get_track("test", 1).is_none());
+    }
+}
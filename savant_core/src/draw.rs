@@ -1,4 +1,9 @@
+use crate::primitives::attribute_value::AttributeValueVariant;
+use crate::primitives::object::{BorrowedVideoObject, ObjectOperations};
+use crate::primitives::WithAttributes;
 use anyhow::{bail, Result};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
 
 /// Defines the padding for a draw operation.
 ///
@@ -22,7 +27,7 @@ use anyhow::{bail, Result};
 ///   padding = PaddingDraw(1, 2, 3, 4)
 ///
 ///
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct PaddingDraw {
     pub left: i64,
     pub top: i64,
@@ -54,7 +59,7 @@ impl PaddingDraw {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct ColorDraw {
     pub red: i64,
     pub green: i64,
@@ -85,7 +90,7 @@ impl ColorDraw {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct BoundingBoxDraw {
     pub border_color: ColorDraw,
     pub background_color: ColorDraw,
@@ -113,7 +118,7 @@ impl BoundingBoxDraw {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct DotDraw {
     pub color: ColorDraw,
     pub radius: i64,
@@ -128,7 +133,7 @@ impl DotDraw {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum LabelPositionKind {
     /// Margin is relative to the **top** left corner of the text bounding box
     TopLeftInside,
@@ -138,7 +143,7 @@ pub enum LabelPositionKind {
     Center,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct LabelPosition {
     pub position: LabelPositionKind,
     pub margin_x: i64,
@@ -161,7 +166,7 @@ impl LabelPosition {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LabelDraw {
     pub font_color: ColorDraw,
     pub background_color: ColorDraw,
@@ -170,7 +175,7 @@ pub struct LabelDraw {
     pub thickness: i64,
     pub position: LabelPosition,
     pub padding: PaddingDraw,
-    pub format: Vec<String>,
+    pub format: Vec<LabelLine>,
 }
 impl LabelDraw {
     #[allow(clippy::too_many_arguments)]
@@ -196,12 +201,280 @@ impl LabelDraw {
             thickness,
             position,
             padding,
-            format,
+            format: format.into_iter().map(LabelLine::from).collect(),
         })
     }
+
+    pub fn get_format(&self) -> Vec<String> {
+        self.format.iter().map(|l| l.as_str().to_string()).collect()
+    }
+
+    /// Renders the format lines for the object, see [`LabelLine`].
+    ///
+    pub fn render(&self, object: &BorrowedVideoObject) -> Vec<String> {
+        self.format.iter().map(|l| l.render(object)).collect()
+    }
 }
 
-#[derive(Clone, Debug)]
+/// The label format line parsed once as the [`LabelTemplate`]. The lines the template does not
+/// understand, e.g. the Python format specs like `{confidence:.0%}` or `{track_id:03d}`, are
+/// kept and rendered as is, so they can be formatted by the caller.
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct LabelLine {
+    line: String,
+    template: Option<LabelTemplate>,
+}
+
+impl LabelLine {
+    pub fn as_str(&self) -> &str {
+        &self.line
+    }
+
+    pub fn get_template(&self) -> Option<&LabelTemplate> {
+        self.template.as_ref()
+    }
+
+    pub fn render(&self, object: &BorrowedVideoObject) -> String {
+        match &self.template {
+            Some(template) => template.render(object),
+            None => self.line.clone(),
+        }
+    }
+}
+
+impl From<String> for LabelLine {
+    fn from(line: String) -> Self {
+        let template = LabelTemplate::parse(&line).ok();
+        Self { line, template }
+    }
+}
+
+impl From<LabelLine> for String {
+    fn from(line: LabelLine) -> Self {
+        line.line
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TemplateField {
+    Namespace,
+    Label,
+    DrawLabel,
+    Id,
+    ParentId,
+    TrackId,
+    Confidence,
+    Attribute {
+        namespace: String,
+        name: String,
+        index: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TemplatePart {
+    Text(String),
+    Field {
+        field: TemplateField,
+        precision: Option<usize>,
+    },
+}
+
+/// The label line template evaluated against an object. The placeholders are:
+///
+/// * `{namespace}` or `{model}`, `{label}`, `{draw_label}` (the draw label or the label);
+/// * `{id}`, `{parent_id}`, `{track_id}`, `{confidence}`;
+/// * `{attr[namespace/name]}` and `{attr[namespace/name][index]}`, the value of the object
+///   attribute, the first one by default.
+///
+/// A float placeholder accepts the precision spec, e.g. `{confidence:.2f}`, the braces are
+/// escaped as `{{` and `}}`. The missing values are rendered as empty strings.
+///
+/// .. code-block:: text
+///
+///   {label} {confidence:.2f} #{track_id} {attr[classifier/color]}
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabelTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+impl LabelTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => bail!("Unclosed placeholder in the template '{}'", template),
+                        }
+                    }
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Self::parse_placeholder(&placeholder)?);
+                }
+                '}' => bail!("Unmatched '}}' in the template '{}'", template),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Self { parts })
+    }
+
+    fn parse_placeholder(placeholder: &str) -> Result<TemplatePart> {
+        // the attribute names are bracketed, so the spec is searched after the last bracket
+        let spec_from = placeholder.rfind(']').unwrap_or(0);
+        let (name, spec) = match placeholder[spec_from..].find(':') {
+            Some(pos) => (
+                &placeholder[..spec_from + pos],
+                Some(&placeholder[spec_from + pos + 1..]),
+            ),
+            None => (placeholder, None),
+        };
+        let precision = match spec {
+            None => None,
+            Some(spec) => match spec
+                .strip_prefix('.')
+                .and_then(|s| s.strip_suffix('f'))
+                .and_then(|s| s.parse::<usize>().ok())
+            {
+                Some(precision) => Some(precision),
+                None => bail!("Unsupported format spec '{}', use '.Nf'", spec),
+            },
+        };
+        let field = match name.trim() {
+            "namespace" | "model" => TemplateField::Namespace,
+            "label" => TemplateField::Label,
+            "draw_label" => TemplateField::DrawLabel,
+            "id" => TemplateField::Id,
+            "parent_id" => TemplateField::ParentId,
+            "track_id" => TemplateField::TrackId,
+            "confidence" => TemplateField::Confidence,
+            attribute if attribute.starts_with("attr[") => Self::parse_attribute(attribute)?,
+            other => bail!("Unknown placeholder '{}'", other),
+        };
+        Ok(TemplatePart::Field { field, precision })
+    }
+
+    fn parse_attribute(attribute: &str) -> Result<TemplateField> {
+        let rest = &attribute["attr[".len()..];
+        let Some(end) = rest.find(']') else {
+            bail!("Unclosed attribute placeholder '{}'", attribute);
+        };
+        let Some((namespace, name)) = rest[..end].split_once('/') else {
+            bail!(
+                "The attribute must be set as 'namespace/name' in '{}'",
+                attribute
+            );
+        };
+        let index = match &rest[end + 1..] {
+            "" => 0,
+            index => match index
+                .strip_prefix('[')
+                .and_then(|s| s.strip_suffix(']'))
+                .and_then(|s| s.parse::<usize>().ok())
+            {
+                Some(index) => index,
+                None => bail!("Invalid attribute value index in '{}'", attribute),
+            },
+        };
+        Ok(TemplateField::Attribute {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            index,
+        })
+    }
+
+    fn format_float(value: f64, precision: Option<usize>) -> String {
+        match precision {
+            Some(precision) => format!("{:.*}", precision, value),
+            None => value.to_string(),
+        }
+    }
+
+    fn format_value(value: &AttributeValueVariant, precision: Option<usize>) -> String {
+        fn join<T>(values: &[T], f: impl Fn(&T) -> String) -> String {
+            values.iter().map(f).collect::<Vec<_>>().join(", ")
+        }
+        match value {
+            AttributeValueVariant::String(v) => v.clone(),
+            AttributeValueVariant::StringVector(v) => v.join(", "),
+            AttributeValueVariant::Integer(v) => v.to_string(),
+            AttributeValueVariant::IntegerVector(v) => join(v, |v| v.to_string()),
+            AttributeValueVariant::Float(v) => Self::format_float(*v, precision),
+            AttributeValueVariant::FloatVector(v) => join(v, |v| Self::format_float(*v, precision)),
+            AttributeValueVariant::Boolean(v) => v.to_string(),
+            AttributeValueVariant::BooleanVector(v) => join(v, |v| v.to_string()),
+            _ => String::new(),
+        }
+    }
+
+    pub fn render(&self, object: &BorrowedVideoObject) -> String {
+        let mut result = String::new();
+        for part in &self.parts {
+            let (field, precision) = match part {
+                TemplatePart::Text(text) => {
+                    result.push_str(text);
+                    continue;
+                }
+                TemplatePart::Field { field, precision } => (field, *precision),
+            };
+            let value = match field {
+                TemplateField::Namespace => object.get_namespace(),
+                TemplateField::Label => object.get_label(),
+                TemplateField::DrawLabel => object.calculate_draw_label(),
+                TemplateField::Id => object.get_id().to_string(),
+                TemplateField::ParentId => object
+                    .get_parent_id()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                TemplateField::TrackId => object
+                    .get_track_id()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                TemplateField::Confidence => object
+                    .get_confidence()
+                    .map(|v| Self::format_float(v as f64, precision))
+                    .unwrap_or_default(),
+                TemplateField::Attribute {
+                    namespace,
+                    name,
+                    index,
+                } => object
+                    .get_attribute(namespace, name)
+                    .and_then(|a| {
+                        a.get_values()
+                            .get(*index)
+                            .map(|v| Self::format_value(v.get(), precision))
+                    })
+                    .unwrap_or_default(),
+            };
+            result.push_str(&value);
+        }
+        result
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ObjectDraw {
     pub bounding_box: Option<BoundingBoxDraw>,
     pub central_dot: Option<DotDraw>,
@@ -222,6 +495,62 @@ impl ObjectDraw {
             blur,
         }
     }
+
+    /// Checks the values with the constructors of the parts, the deserialized specifications
+    /// are not validated otherwise.
+    ///
+    pub fn validate(&self) -> Result<()> {
+        let check_color = |c: &ColorDraw| ColorDraw::new(c.red, c.green, c.blue, c.alpha);
+        let check_padding = |p: &PaddingDraw| PaddingDraw::new(p.left, p.top, p.right, p.bottom);
+        if let Some(b) = &self.bounding_box {
+            BoundingBoxDraw::new(
+                check_color(&b.border_color)?,
+                check_color(&b.background_color)?,
+                b.thickness,
+                check_padding(&b.padding)?,
+            )?;
+        }
+        if let Some(d) = &self.central_dot {
+            DotDraw::new(check_color(&d.color)?, d.radius)?;
+        }
+        if let Some(l) = &self.label {
+            LabelDraw::new(
+                check_color(&l.font_color)?,
+                check_color(&l.background_color)?,
+                check_color(&l.border_color)?,
+                l.font_scale,
+                l.thickness,
+                LabelPosition::new(
+                    l.position.position,
+                    l.position.margin_x,
+                    l.position.margin_y,
+                )?,
+                check_padding(&l.padding)?,
+                l.get_format(),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(&serde_json::to_value(self).unwrap()).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let spec: Self = serde_json::from_str(json)?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let spec: Self = serde_json::from_value(serde_yaml::from_str(yaml)?)?;
+        spec.validate()?;
+        Ok(spec)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -229,3 +558,273 @@ pub enum DrawLabelKind {
     OwnLabel(String),
     ParentLabel(String),
 }
+
+/// The draw specifications of objects keyed by the namespace and the label. The specification
+/// of an object is looked up in the order:
+///
+/// * the exact `(namespace, label)` pair;
+/// * `(namespace, "*")`, the specification for all labels of the namespace;
+/// * `("*", label)`, the specification for the label in all namespaces;
+/// * the default specification.
+///
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DrawSpecRegistry {
+    #[serde(default)]
+    default: Option<ObjectDraw>,
+    #[serde(default)]
+    specs: HashMap<String, HashMap<String, ObjectDraw>>,
+}
+
+impl DrawSpecRegistry {
+    pub const WILDCARD: &'static str = "*";
+
+    pub fn new(default: Option<ObjectDraw>) -> Self {
+        Self {
+            default,
+            specs: HashMap::new(),
+        }
+    }
+
+    pub fn get_default(&self) -> Option<&ObjectDraw> {
+        self.default.as_ref()
+    }
+
+    pub fn set_default(&mut self, default: Option<ObjectDraw>) {
+        self.default = default;
+    }
+
+    /// Sets the specification for the pair, either of them may be the wildcard. Returns the
+    /// replaced specification.
+    ///
+    pub fn insert(&mut self, namespace: &str, label: &str, spec: ObjectDraw) -> Option<ObjectDraw> {
+        self.specs
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(label.to_string(), spec)
+    }
+
+    pub fn remove(&mut self, namespace: &str, label: &str) -> Option<ObjectDraw> {
+        let labels = self.specs.get_mut(namespace)?;
+        let removed = labels.remove(label);
+        if labels.is_empty() {
+            self.specs.remove(namespace);
+        }
+        removed
+    }
+
+    /// Returns the specification set for the pair exactly, without the fallbacks.
+    ///
+    pub fn get(&self, namespace: &str, label: &str) -> Option<&ObjectDraw> {
+        self.specs.get(namespace)?.get(label)
+    }
+
+    /// Returns the specification for the object with the fallbacks applied.
+    ///
+    pub fn lookup(&self, namespace: &str, label: &str) -> Option<&ObjectDraw> {
+        self.get(namespace, label)
+            .or_else(|| self.get(namespace, Self::WILDCARD))
+            .or_else(|| self.get(Self::WILDCARD, label))
+            .or(self.default.as_ref())
+    }
+
+    /// Returns the `(namespace, label)` pairs having specifications.
+    ///
+    pub fn keys(&self) -> Vec<(String, String)> {
+        self.specs
+            .iter()
+            .flat_map(|(ns, labels)| labels.keys().map(|l| (ns.clone(), l.clone())))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.specs.values().map(|labels| labels.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(default) = &self.default {
+            default.validate()?;
+        }
+        for (ns, labels) in &self.specs {
+            for (label, spec) in labels {
+                if let Err(e) = spec.validate() {
+                    bail!("Invalid draw specification for ({}, {}): {}", ns, label, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(&serde_json::to_value(self).unwrap()).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let registry: Self = serde_json::from_str(json)?;
+        registry.validate()?;
+        Ok(registry)
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let registry: Self = serde_json::from_value(serde_yaml::from_str(yaml)?)?;
+        registry.validate()?;
+        Ok(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(radius: i64) -> ObjectDraw {
+        ObjectDraw::new(
+            None,
+            Some(DotDraw::new(ColorDraw::new(255, 0, 0, 255).unwrap(), radius).unwrap()),
+            None,
+            false,
+        )
+    }
+
+    fn radius(spec: Option<&ObjectDraw>) -> Option<i64> {
+        spec.and_then(|s| s.central_dot).map(|d| d.radius)
+    }
+
+    #[test]
+    fn test_object_draw_serialization() {
+        let spec = ObjectDraw::new(
+            Some(
+                BoundingBoxDraw::new(
+                    ColorDraw::new(0, 255, 0, 255).unwrap(),
+                    ColorDraw::transparent().unwrap(),
+                    2,
+                    PaddingDraw::new(1, 2, 3, 4).unwrap(),
+                )
+                .unwrap(),
+            ),
+            None,
+            Some(
+                LabelDraw::new(
+                    ColorDraw::new(255, 255, 255, 255).unwrap(),
+                    ColorDraw::transparent().unwrap(),
+                    ColorDraw::transparent().unwrap(),
+                    0.5,
+                    1,
+                    LabelPosition::default_position().unwrap(),
+                    PaddingDraw::default_padding(),
+                    vec!["{label}".to_string()],
+                )
+                .unwrap(),
+            ),
+            true,
+        );
+        let restored = ObjectDraw::from_json(&spec.to_json()).unwrap();
+        assert_eq!(restored.to_json(), spec.to_json());
+        let restored = ObjectDraw::from_yaml(&spec.to_yaml()).unwrap();
+        assert_eq!(restored.to_json(), spec.to_json());
+
+        let invalid = spec
+            .to_json()
+            .replace("\"thickness\":2", "\"thickness\":1000");
+        assert!(ObjectDraw::from_json(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_label_template() {
+        use crate::primitives::attribute_value::AttributeValue;
+        use crate::primitives::object::IdCollisionResolutionPolicy;
+        use crate::test::{gen_empty_frame, gen_object};
+
+        let frame = gen_empty_frame();
+        let mut object = gen_object(3);
+        object.confidence = Some(0.8765);
+        object.set_persistent_attribute(
+            "classifier",
+            "color",
+            &None,
+            false,
+            vec![
+                AttributeValue::new(AttributeValueVariant::String("red".to_string()), None),
+                AttributeValue::new(AttributeValueVariant::Float(0.26), None),
+            ],
+        );
+        let object = frame
+            .add_object(object, IdCollisionResolutionPolicy::Error)
+            .unwrap();
+
+        let render = |template: &str| LabelTemplate::parse(template).unwrap().render(&object);
+        assert_eq!(
+            render("{label} {confidence:.2f} #{track_id}"),
+            "face 0.88 #3"
+        );
+        assert_eq!(render("{model}/{id} {parent_id}"), "peoplenet/3 ");
+        assert_eq!(
+            render("{attr[classifier/color]} {attr[classifier/color][1]:.1f}"),
+            "red 0.3"
+        );
+        assert_eq!(render("{attr[classifier/missing]}|{{x}}"), "|{x}");
+
+        for invalid in [
+            "{unknown}",
+            "{label",
+            "label}",
+            "{confidence:x}",
+            "{attr[color]}",
+        ] {
+            assert!(LabelTemplate::parse(invalid).is_err(), "{}", invalid);
+        }
+
+        // the lines which are not templates are rendered as is
+        let format = ["{label} {confidence:.0%}", "{track_id:03d}", "{label}"];
+        let label = LabelDraw::new(
+            ColorDraw::transparent().unwrap(),
+            ColorDraw::transparent().unwrap(),
+            ColorDraw::transparent().unwrap(),
+            1.0,
+            1,
+            LabelPosition::default_position().unwrap(),
+            PaddingDraw::default_padding(),
+            format.iter().map(|l| l.to_string()).collect(),
+        )
+        .unwrap();
+        assert!(label.format[0].get_template().is_none());
+        assert_eq!(
+            label.render(&object),
+            vec!["{label} {confidence:.0%}", "{track_id:03d}", "face"]
+        );
+        assert_eq!(label.get_format(), format);
+    }
+
+    #[test]
+    fn test_registry_fallbacks() {
+        let mut registry = DrawSpecRegistry::new(Some(spec(1)));
+        registry.insert("detector", "person", spec(2));
+        registry.insert("detector", DrawSpecRegistry::WILDCARD, spec(3));
+        registry.insert(DrawSpecRegistry::WILDCARD, "car", spec(4));
+        assert_eq!(registry.len(), 3);
+
+        assert_eq!(radius(registry.lookup("detector", "person")), Some(2));
+        assert_eq!(radius(registry.lookup("detector", "car")), Some(3));
+        assert_eq!(radius(registry.lookup("tracker", "car")), Some(4));
+        assert_eq!(radius(registry.lookup("tracker", "person")), Some(1));
+        assert!(registry.get("tracker", "person").is_none());
+
+        let restored = DrawSpecRegistry::from_yaml(&registry.to_yaml()).unwrap();
+        assert_eq!(radius(restored.lookup("detector", "car")), Some(3));
+        assert_eq!(restored.len(), 3);
+
+        assert_eq!(
+            radius(registry.remove("detector", "person").as_ref()),
+            Some(2)
+        );
+        assert_eq!(radius(registry.lookup("detector", "person")), Some(3));
+        registry.set_default(None);
+        assert!(registry.lookup("tracker", "person").is_none());
+    }
+}
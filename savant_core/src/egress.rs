@@ -0,0 +1,146 @@
+#[cfg(feature = "amqp")]
+pub mod amqp;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+use crate::json_api::ToSerdeJsonValue;
+use crate::match_query::MatchQuery;
+use crate::primitives::attribute::WithAttributes;
+use crate::primitives::frame::VideoFrameProxy;
+use serde_json::Value;
+
+/// Publishes the serialized analytic events to a broker. The namespace and the source id define
+/// the destination: the MQTT topic or the AMQP routing key.
+///
+pub trait EgressPublisher: Send + Sync {
+    fn publish(&self, namespace: &str, source_id: &str, payload: &[u8]) -> anyhow::Result<()>;
+}
+
+/// Selects the analytics published for the namespace: the visible frame attributes of the
+/// namespace and the objects matched by the query.
+///
+#[derive(Debug, Clone)]
+pub struct EgressRule {
+    pub namespace: String,
+    pub query: MatchQuery,
+}
+
+impl EgressRule {
+    pub fn new(namespace: &str, query: MatchQuery) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            query,
+        }
+    }
+
+    /// Builds the JSON event for the frame, `None` when nothing is selected. The video content
+    /// is never included.
+    ///
+    pub fn event(&self, frame: &VideoFrameProxy) -> Option<Value> {
+        let attributes = frame.with_attributes_ref(|attributes| {
+            attributes
+                .iter()
+                .filter(|a| !a.is_hidden && a.namespace == self.namespace)
+                .map(|a| a.to_serde_json_value())
+                .collect::<Vec<_>>()
+        });
+        let objects = frame
+            .access_objects(&self.query)
+            .iter()
+            .map(|o| o.to_serde_json_value())
+            .collect::<Vec<_>>();
+        if attributes.is_empty() && objects.is_empty() {
+            return None;
+        }
+        Some(serde_json::json!({
+            "namespace": self.namespace,
+            "source_id": frame.get_source_id(),
+            "uuid": frame.get_uuid_as_string(),
+            "pts": frame.get_pts(),
+            "attributes": attributes,
+            "objects": objects,
+        }))
+    }
+}
+
+/// Publishes the analytic events of the frames to MQTT or AMQP for the dashboards and the IoT
+/// consumers, one event per matching rule.
+///
+pub struct EgressSink {
+    rules: Vec<EgressRule>,
+    publisher: Box<dyn EgressPublisher>,
+}
+
+impl EgressSink {
+    pub fn new(rules: Vec<EgressRule>, publisher: Box<dyn EgressPublisher>) -> Self {
+        Self { rules, publisher }
+    }
+
+    /// Returns the number of the published events.
+    ///
+    pub fn publish_frame(&self, frame: &VideoFrameProxy) -> anyhow::Result<usize> {
+        let source_id = frame.get_source_id();
+        let mut published = 0;
+        for rule in &self.rules {
+            if let Some(event) = rule.event(frame) {
+                self.publisher.publish(
+                    &rule.namespace,
+                    &source_id,
+                    &serde_json::to_vec(&event)?,
+                )?;
+                published += 1;
+            }
+        }
+        Ok(published)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_query::{eq, MatchQuery};
+    use crate::test::gen_frame;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[derive(Default, Clone)]
+    struct MockPublisher(Arc<Mutex<Vec<(String, String, Value)>>>);
+
+    impl EgressPublisher for MockPublisher {
+        fn publish(&self, namespace: &str, source_id: &str, payload: &[u8]) -> anyhow::Result<()> {
+            self.0.lock().push((
+                namespace.to_string(),
+                source_id.to_string(),
+                serde_json::from_slice(payload)?,
+            ));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_publish_frame() -> anyhow::Result<()> {
+        let publisher = MockPublisher::default();
+        let sink = EgressSink::new(
+            vec![
+                EgressRule::new("test2", MatchQuery::Namespace(eq("test2"))),
+                EgressRule::new("system", MatchQuery::Namespace(eq("none"))),
+                EgressRule::new("missing", MatchQuery::Namespace(eq("missing"))),
+            ],
+            Box::new(publisher.clone()),
+        );
+        let frame = gen_frame();
+        assert_eq!(sink.publish_frame(&frame)?, 2);
+
+        let events = publisher.0.lock();
+        let (namespace, source_id, event) = &events[0];
+        assert_eq!((namespace.as_str(), source_id.as_str()), ("test2", "test"));
+        assert_eq!(event["objects"].as_array().unwrap().len(), 2);
+        assert!(event["attributes"].as_array().unwrap().is_empty());
+        assert!(event.get("content").is_none());
+
+        let (namespace, _, event) = &events[1];
+        assert_eq!(namespace, "system");
+        assert_eq!(event["attributes"].as_array().unwrap().len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::egress::EgressPublisher;
+use log::warn;
+use rumqttc::{Client, MqttOptions, QoS};
+use std::time::Duration;
+
+const REQUEST_QUEUE_SIZE: usize = 64;
+const KEEP_ALIVE: Duration = Duration::from_secs(5);
+
+/// Publishes the events to the MQTT topics `{topic_prefix}/{namespace}/{source_id}` with the
+/// at-least-once delivery. The connection is driven by a background thread, which reconnects
+/// after the failures and stops when the publisher is dropped.
+///
+pub struct MqttPublisher {
+    client: Client,
+    topic_prefix: String,
+}
+
+impl MqttPublisher {
+    pub fn new(host: &str, port: u16, client_id: &str, topic_prefix: &str) -> anyhow::Result<Self> {
+        let mut options = MqttOptions::new(client_id, host, port);
+        options.set_keep_alive(KEEP_ALIVE);
+        let (client, mut connection) = Client::new(options, REQUEST_QUEUE_SIZE);
+        let endpoint = format!("{}:{}", host, port);
+        std::thread::spawn(move || {
+            for notification in connection.iter() {
+                if let Err(e) = notification {
+                    warn!(
+                        target: "savant_rs::egress::mqtt",
+                        "MQTT connection to {} failed: {:?}", endpoint, e
+                    );
+                    std::thread::sleep(KEEP_ALIVE);
+                }
+            }
+        });
+        Ok(Self {
+            client,
+            topic_prefix: topic_prefix.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub fn topic(&self, namespace: &str, source_id: &str) -> String {
+        if self.topic_prefix.is_empty() {
+            format!("{}/{}", namespace, source_id)
+        } else {
+            format!("{}/{}/{}", self.topic_prefix, namespace, source_id)
+        }
+    }
+}
+
+impl EgressPublisher for MqttPublisher {
+    fn publish(&self, namespace: &str, source_id: &str, payload: &[u8]) -> anyhow::Result<()> {
+        self.client.publish(
+            self.topic(namespace, source_id),
+            QoS::AtLeastOnce,
+            false,
+            payload,
+        )?;
+        Ok(())
+    }
+}
+
+impl Drop for MqttPublisher {
+    fn drop(&mut self) {
+        // the connection thread stops when the disconnection is processed
+        let _ = self.client.disconnect();
+    }
+}
@@ -1,15 +1,28 @@
 use crate::eval_context::GlobalContext;
 use crate::eval_resolvers::{
-    config_resolver_name, env_resolver_name, etcd_resolver_name, utility_resolver_name,
+    config_resolver_name, env_resolver_name, etcd_resolver_name, file_resolver_name,
+    http_resolver_name, redis_resolver_name, utility_resolver_name,
 };
 use evalexpr::{build_operator_tree, Node, Value};
+use hashbrown::HashMap;
 use lazy_static::lazy_static;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use regex::Regex;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 const MAX_JMES_CACHE_SIZE: usize = 1024;
 const MAX_EVAL_EXPR_CACHE_SIZE: usize = 1024;
 const MAX_EVAL_RESULTS_CACHE_SIZE: usize = 1024;
+const MAX_REGEX_CACHE_SIZE: usize = 1024;
+const DEFAULT_RESOLVER_CACHE_SIZE: usize = 4096;
+
+#[derive(Debug, Default)]
+struct ResolverCacheTtl {
+    default: Duration,
+    symbols: HashMap<String, Duration>,
+}
 
 lazy_static! {
     static ref COMPILED_EVAL_EXPR: Mutex<lru::LruCache<String, Arc<Node>>> = Mutex::new(
@@ -19,9 +32,93 @@ lazy_static! {
         Mutex::new(lru::LruCache::new(
             std::num::NonZeroUsize::new(MAX_JMES_CACHE_SIZE).unwrap()
         ));
+    static ref COMPILED_REGEX: Mutex<lru::LruCache<String, Arc<Regex>>> = Mutex::new(
+        lru::LruCache::new(std::num::NonZeroUsize::new(MAX_REGEX_CACHE_SIZE).unwrap())
+    );
     static ref EVAL_RESULTS: Mutex<lru::LruCache<String, (u128, evalexpr::Value)>> = Mutex::new(
         lru::LruCache::new(std::num::NonZeroUsize::new(MAX_EVAL_RESULTS_CACHE_SIZE).unwrap())
     );
+    static ref RESOLVER_RESULTS: Mutex<lru::LruCache<String, (Instant, Value)>> = Mutex::new(
+        lru::LruCache::new(NonZeroUsize::new(DEFAULT_RESOLVER_CACHE_SIZE).unwrap())
+    );
+    static ref RESOLVER_CACHE_TTL: RwLock<ResolverCacheTtl> =
+        RwLock::new(ResolverCacheTtl::default());
+}
+
+/// Configures the cache shared by all eval resolvers. `default_ttl` (milliseconds) applies to the
+/// symbols without their own TTL, 0 disables caching for them. Shrinking the cache evicts the
+/// least recently used entries.
+///
+pub fn configure_resolver_cache(max_entries: usize, default_ttl: u64) {
+    let size = NonZeroUsize::new(max_entries.max(1)).unwrap();
+    RESOLVER_RESULTS.lock().resize(size);
+    RESOLVER_CACHE_TTL.write().default = Duration::from_millis(default_ttl);
+}
+
+/// Sets the cache TTL (milliseconds) for the resolver symbol, e.g. `etcd`; 0 disables caching for
+/// the symbol.
+///
+pub fn set_resolver_cache_ttl(symbol: &str, ttl: u64) {
+    RESOLVER_CACHE_TTL
+        .write()
+        .symbols
+        .insert(symbol.to_string(), Duration::from_millis(ttl));
+}
+
+/// Removes the cached resolver results whose keys start with the prefix. The keys are formed as
+/// `symbol:key`, so `etcd:` drops all etcd values while `etcd:thresholds/` drops a subtree.
+/// Returns the number of removed entries.
+///
+pub fn invalidate_resolver_cache(prefix: &str) -> usize {
+    let mut cache = RESOLVER_RESULTS.lock();
+    let keys = cache
+        .iter()
+        .filter(|(k, _)| k.starts_with(prefix))
+        .map(|(k, _)| k.clone())
+        .collect::<Vec<_>>();
+    for k in &keys {
+        cache.pop(k);
+    }
+    keys.len()
+}
+
+fn resolver_cache_key(symbol: &str, argument: &Value) -> String {
+    match argument {
+        Value::Tuple(args) => match args.as_slice() {
+            [Value::String(key), rest @ ..] => format!("{}:{}|{:?}", symbol, key, rest),
+            _ => format!("{}:{:?}", symbol, argument),
+        },
+        _ => format!("{}:{:?}", symbol, argument),
+    }
+}
+
+pub(crate) fn resolve_cached<F>(symbol: &str, argument: &Value, f: F) -> anyhow::Result<Value>
+where
+    F: FnOnce() -> anyhow::Result<Value>,
+{
+    let ttl = {
+        let ttls = RESOLVER_CACHE_TTL.read();
+        ttls.symbols.get(symbol).copied().unwrap_or(ttls.default)
+    };
+    if ttl.is_zero() {
+        return f();
+    }
+    let key = resolver_cache_key(symbol, argument);
+    {
+        let mut cache = RESOLVER_RESULTS.lock();
+        match cache.get(&key) {
+            Some((stored, v)) if stored.elapsed() < ttl => return Ok(v.clone()),
+            Some(_) => {
+                cache.pop(&key);
+            }
+            None => {}
+        }
+    }
+    let value = f()?;
+    RESOLVER_RESULTS
+        .lock()
+        .put(key, (Instant::now(), value.clone()));
+    Ok(value)
 }
 
 pub fn get_compiled_jmp_filter(query: &str) -> anyhow::Result<Arc<jmespath::Expression>> {
@@ -44,6 +141,16 @@ pub fn get_compiled_eval_expr(query: &str) -> anyhow::Result<Arc<Node>> {
     Ok(c)
 }
 
+pub fn get_compiled_regex(pattern: &str) -> anyhow::Result<Arc<Regex>> {
+    let mut compiled_regex = COMPILED_REGEX.lock();
+    if let Some(c) = compiled_regex.get(pattern) {
+        return Ok(c.clone());
+    }
+    let c = Arc::new(Regex::new(pattern)?);
+    compiled_regex.put(pattern.to_string(), c.clone());
+    Ok(c)
+}
+
 pub fn eval_expr(query: &str, ttl: u64) -> anyhow::Result<(Value, bool)> {
     let expr = get_compiled_eval_expr(query)?;
     let now = std::time::SystemTime::now()
@@ -64,6 +171,9 @@ pub fn eval_expr(query: &str, ttl: u64) -> anyhow::Result<(Value, bool)> {
     let mut context = GlobalContext::new(&[
         utility_resolver_name(),
         etcd_resolver_name(),
+        redis_resolver_name(),
+        http_resolver_name(),
+        file_resolver_name(),
         config_resolver_name(),
         env_resolver_name(),
     ]);
@@ -81,6 +191,36 @@ pub fn eval_expr(query: &str, ttl: u64) -> anyhow::Result<(Value, bool)> {
 #[cfg(test)]
 mod tests {
     use crate::eval_resolvers::register_env_resolver;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_resolver_cache() -> anyhow::Result<()> {
+        use super::*;
+        set_resolver_cache_ttl("test_cached", 10_000);
+        let calls = Cell::new(0i64);
+        let resolve = |key: &str| {
+            resolve_cached(
+                "test_cached",
+                &Value::Tuple(vec![Value::from(key), Value::from(0)]),
+                || {
+                    calls.set(calls.get() + 1);
+                    Ok(Value::from(calls.get()))
+                },
+            )
+        };
+        assert_eq!(resolve("a/b")?, Value::from(1));
+        assert_eq!(resolve("a/b")?, Value::from(1));
+        assert_eq!(resolve("a/c")?, Value::from(2));
+        assert_eq!(invalidate_resolver_cache("test_cached:a/b"), 1);
+        assert_eq!(resolve("a/b")?, Value::from(3));
+        assert_eq!(resolve("a/c")?, Value::from(2));
+        assert_eq!(invalidate_resolver_cache("test_cached:"), 2);
+
+        set_resolver_cache_ttl("test_cached", 0);
+        assert_eq!(resolve("a/b")?, Value::from(4));
+        assert_eq!(resolve("a/b")?, Value::from(5));
+        Ok(())
+    }
 
     #[test]
     fn test_eval_expr() {
@@ -1,8 +1,14 @@
 pub const ENV_FUNC: &str = "env";
 pub const CONFIG_FUNC: &str = "config";
 const ETCD_FUNC: &str = "etcd";
-
-pub use resolvers::{EtcdCredentials, EvalWithResolvers, TlsConfig};
+const REDIS_FUNC: &str = "redis";
+const HTTP_FUNC: &str = "http";
+const FILE_FUNC: &str = "file";
+
+pub use resolvers::{
+    EtcdCredentials, EvalWithResolvers, FileResolverConfig, HttpResolverConfig,
+    RedisResolverConfig, TlsConfig,
+};
 pub use singleton::*;
 pub use utils::*;
 
@@ -39,25 +45,45 @@ mod utils {
     pub fn etcd_resolver_name() -> &'static str {
         "etcd-resolver"
     }
+
+    #[inline(always)]
+    pub fn redis_resolver_name() -> &'static str {
+        "redis-resolver"
+    }
+
+    #[inline(always)]
+    pub fn http_resolver_name() -> &'static str {
+        "http-resolver"
+    }
+
+    #[inline(always)]
+    pub fn file_resolver_name() -> &'static str {
+        "file-resolver"
+    }
 }
 
 pub(crate) mod resolvers {
+    use crate::eval_cache::resolve_cached;
     use crate::eval_resolvers::{
         cast_str_to_primitive_type, config_resolver_name, env_resolver_name, etcd_resolver_name,
-        get_symbol_resolver, utility_resolver_name, CONFIG_FUNC, ENV_FUNC, ETCD_FUNC,
+        file_resolver_name, get_symbol_resolver, http_resolver_name, redis_resolver_name,
+        utility_resolver_name, CONFIG_FUNC, ENV_FUNC, ETCD_FUNC, FILE_FUNC, HTTP_FUNC, REDIS_FUNC,
     };
+    use crate::layered_config::LayeredConfig;
+    use crate::parameter_storage::TypedParameterStorage;
     use crate::{get_or_init_async_runtime, trace};
-    use anyhow::{bail, Result};
+    use anyhow::{anyhow, bail, Result};
     use etcd_client::{Certificate, Identity, TlsOptions};
-    use etcd_dynamic_state::etcd_api::{EtcdClient, VarPathSpec};
-    use etcd_dynamic_state::parameter_storage::EtcdParameterStorage;
     use evalexpr::{EvalexprError, EvalexprResult, Value};
     use hashbrown::HashMap;
+    use lru::LruCache;
     use parking_lot::{Mutex, RwLock};
     use std::any::Any;
     use std::env;
+    use std::num::NonZeroUsize;
     use std::path::{Path, PathBuf};
     use std::sync::Arc;
+    use std::time::{Duration, Instant, SystemTime};
 
     pub trait EvalWithResolvers {
         fn get_resolvers(&self) -> &'_ [String];
@@ -67,9 +93,10 @@ pub(crate) mod resolvers {
             match res {
                 Some((r, executor)) => {
                     if self.get_resolvers().contains(&r) {
-                        executor
-                            .resolve(identifier, argument)
-                            .map_err(|e| EvalexprError::CustomMessage(e.to_string()))
+                        resolve_cached(identifier, argument, || {
+                            executor.resolve(identifier, argument)
+                        })
+                        .map_err(|e| EvalexprError::CustomMessage(e.to_string()))
                     } else {
                         Err(EvalexprError::FunctionIdentifierNotFound(
                             identifier.to_string(),
@@ -124,17 +151,36 @@ pub(crate) mod resolvers {
         }
     }
 
+    /// Resolves `config(key, default)` from the layered configuration when it is set, then from
+    /// the registered symbols.
+    ///
     pub struct ConfigSymbolResolver {
         pub(super) symbols: RwLock<HashMap<String, String>>,
+        layers: Option<Arc<LayeredConfig>>,
     }
 
     impl ConfigSymbolResolver {
         pub fn new() -> Self {
             Self {
                 symbols: RwLock::new(HashMap::new()),
+                layers: None,
             }
         }
 
+        pub fn with_layers(layers: Arc<LayeredConfig>) -> Self {
+            Self {
+                symbols: RwLock::new(HashMap::new()),
+                layers: Some(layers),
+            }
+        }
+
+        fn get_data(&self, key: &str) -> Option<String> {
+            self.layers
+                .as_ref()
+                .and_then(|layers| layers.get_string(key))
+                .or_else(|| trace!(self.symbols.read_recursive()).get(key).cloned())
+        }
+
         pub fn add_symbol(&mut self, name: String, value: String) {
             trace!(self.symbols.write()).insert(name, value);
         }
@@ -148,12 +194,10 @@ pub(crate) mod resolvers {
                         bail!("The function must be called as config(key, default)");
                     }
                     match expr.as_tuple().unwrap().as_slice() {
-                        [Value::String(key), default] => {
-                            match trace!(self.symbols.read_recursive()).get(key) {
-                                Some(value) => cast_str_to_primitive_type(value, default),
-                                None => Ok(default.clone()),
-                            }
-                        }
+                        [Value::String(key), default] => match self.get_data(key) {
+                            Some(value) => cast_str_to_primitive_type(&value, default),
+                            None => Ok(default.clone()),
+                        },
                         _ => unreachable!(),
                     }
                 }
@@ -233,8 +277,11 @@ pub(crate) mod resolvers {
         }
     }
 
+    /// Reads the values through [`TypedParameterStorage`], so an etcd outage is returned as an
+    /// error and the connection is restored in the background.
+    ///
     pub struct EtcdSymbolResolver {
-        inner: Arc<Mutex<EtcdParameterStorage>>,
+        inner: TypedParameterStorage,
         prefix: String,
     }
 
@@ -244,11 +291,38 @@ pub(crate) mod resolvers {
         pub password: String,
     }
 
+    impl EtcdCredentials {
+        pub fn as_pair(&self) -> (&str, &str) {
+            (self.username.as_str(), self.password.as_str())
+        }
+    }
+
+    /// The TLS configuration of the etcd connection with the PEM contents (not the paths): the
+    /// CA certificate verifying the server and the optional client certificate and key for mTLS,
+    /// which must be set together.
+    ///
     #[derive(Clone)]
     pub struct TlsConfig {
-        pub ca_cert: String,
-        pub client_cert: String,
-        pub client_key: String,
+        pub ca_cert: Option<String>,
+        pub client_cert: Option<String>,
+        pub client_key: Option<String>,
+    }
+
+    impl TlsConfig {
+        pub fn tls_options(&self) -> Result<TlsOptions> {
+            let mut options = TlsOptions::default();
+            if let Some(ca_cert) = &self.ca_cert {
+                options = options.ca_certificate(Certificate::from_pem(ca_cert.as_bytes()));
+            }
+            match (&self.client_cert, &self.client_key) {
+                (Some(cert), Some(key)) => {
+                    options = options.identity(Identity::from_pem(cert.as_bytes(), key.as_bytes()));
+                }
+                (None, None) => {}
+                _ => bail!("The client certificate and key must be set together."),
+            }
+            Ok(options)
+        }
     }
 
     impl EtcdSymbolResolver {
@@ -260,38 +334,21 @@ pub(crate) mod resolvers {
             connect_timeout: u64,
             watch_path_wait_timeout: u64,
         ) -> Result<Self> {
-            assert!(watch_path_wait_timeout > 0);
-            assert!(connect_timeout > 0);
+            if watch_path_wait_timeout == 0 || connect_timeout == 0 {
+                bail!("The etcd connect and watch path wait timeouts must be positive.");
+            }
 
-            let runtime = get_or_init_async_runtime();
-            let credentials = credentials
-                .as_ref()
-                .map(|creds| (creds.username.as_str(), creds.password.as_str()));
-            let tls = tls_config.as_ref().map(|tls| {
-                TlsOptions::default()
-                    .ca_certificate(Certificate::from_pem(tls.ca_cert.as_bytes()))
-                    .identity(Identity::from_pem(
-                        tls.client_cert.as_bytes(),
-                        tls.client_key.as_bytes(),
-                    ))
-            });
-
-            let client = runtime.block_on(EtcdClient::new_with_tls(
+            let parameter_storage = TypedParameterStorage::connect_unwatched(
                 hosts,
-                &credentials,
+                credentials,
+                tls_config,
                 watch_path,
-                60,
                 connect_timeout,
-                tls,
-            ))?;
-
-            let mut parameter_storage = EtcdParameterStorage::with_client(client);
-            parameter_storage.run(runtime)?;
-            parameter_storage.order_data_update(VarPathSpec::Prefix(watch_path.to_string()))?;
-            _ = parameter_storage.wait_for_key(watch_path, watch_path_wait_timeout * 1000); // wait for the first update
+            )?;
+            parameter_storage.wait_for_key(watch_path, watch_path_wait_timeout * 1000)?; // wait for the first update
 
             Ok(Self {
-                inner: Arc::new(Mutex::new(parameter_storage)),
+                inner: parameter_storage,
                 prefix: watch_path.to_string(),
             })
         }
@@ -304,7 +361,7 @@ pub(crate) mod resolvers {
             }
             path.push(key_path);
             let path = path.to_str().unwrap();
-            let data_opt = self.inner.lock().get_data(path)?;
+            let data_opt = self.inner.get_raw(path)?;
 
             match data_opt {
                 Some((_crc, data)) => Ok(Some(String::from_utf8_lossy(&data).to_string())),
@@ -345,14 +402,474 @@ pub(crate) mod resolvers {
             self
         }
     }
+
+    #[derive(Clone, Debug)]
+    pub struct RedisResolverConfig {
+        /// Redis connection url, e.g. `redis://127.0.0.1:6379/0`.
+        pub url: String,
+        /// Prefix prepended to the keys as `prefix:key`, empty prefix means no prefix.
+        pub prefix: String,
+        pub pool_size: u32,
+        /// Connection timeout in seconds.
+        pub connect_timeout: u64,
+        /// How long resolved values are cached locally in milliseconds, 0 disables caching.
+        pub cache_ttl: u64,
+        pub cache_size: usize,
+    }
+
+    impl Default for RedisResolverConfig {
+        fn default() -> Self {
+            Self {
+                url: "redis://127.0.0.1:6379/0".to_string(),
+                prefix: "savant".to_string(),
+                pool_size: 4,
+                connect_timeout: 5,
+                cache_ttl: 1000,
+                cache_size: 1024,
+            }
+        }
+    }
+
+    type RedisCacheEntry = (Instant, Option<String>);
+
+    pub struct RedisSymbolResolver {
+        pool: r2d2::Pool<redis::Client>,
+        prefix: String,
+        cache_ttl: Duration,
+        pub(super) cache: Mutex<LruCache<String, RedisCacheEntry>>,
+    }
+
+    impl RedisSymbolResolver {
+        pub fn new(config: &RedisResolverConfig) -> Result<Self> {
+            assert!(config.connect_timeout > 0);
+            assert!(config.pool_size > 0);
+
+            let client = redis::Client::open(config.url.as_str())?;
+            let pool = r2d2::Pool::builder()
+                .max_size(config.pool_size)
+                .connection_timeout(Duration::from_secs(config.connect_timeout))
+                .build(client)?;
+            Ok(Self::with_pool(pool, config))
+        }
+
+        pub(crate) fn with_pool(
+            pool: r2d2::Pool<redis::Client>,
+            config: &RedisResolverConfig,
+        ) -> Self {
+            let cache_size = NonZeroUsize::new(config.cache_size.max(1)).unwrap();
+            Self {
+                pool,
+                prefix: config.prefix.clone(),
+                cache_ttl: Duration::from_millis(config.cache_ttl),
+                cache: Mutex::new(LruCache::new(cache_size)),
+            }
+        }
+
+        fn full_key(&self, key: &str) -> String {
+            if self.prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}:{}", self.prefix, key)
+            }
+        }
+
+        fn get_cached(&self, key: &str) -> Option<Option<String>> {
+            if self.cache_ttl.is_zero() {
+                return None;
+            }
+            let mut cache = self.cache.lock();
+            match cache.get(key) {
+                Some((fetched, value)) if fetched.elapsed() < self.cache_ttl => Some(value.clone()),
+                Some(_) => {
+                    cache.pop(key);
+                    None
+                }
+                None => None,
+            }
+        }
+
+        fn get_data(&self, key: &str) -> Result<Option<String>> {
+            if let Some(value) = self.get_cached(key) {
+                return Ok(value);
+            }
+            let mut conn = self.pool.get()?;
+            let value: Option<String> = redis::cmd("GET")
+                .arg(self.full_key(key))
+                .query(&mut *conn)?;
+            if !self.cache_ttl.is_zero() {
+                self.cache
+                    .lock()
+                    .put(key.to_string(), (Instant::now(), value.clone()));
+            }
+            Ok(value)
+        }
+    }
+
+    impl SymbolResolver for RedisSymbolResolver {
+        fn resolve(&self, func: &str, expr: &Value) -> Result<Value> {
+            match func {
+                "redis" => {
+                    if !expr.is_tuple() {
+                        bail!("The function must be called as redis(key, default)");
+                    }
+
+                    match expr.as_tuple().unwrap().as_slice() {
+                        [Value::String(key), default] => match self.get_data(key)? {
+                            Some(value) => cast_str_to_primitive_type(&value, default),
+                            None => Ok(default.clone()),
+                        },
+                        _ => unreachable!(),
+                    }
+                }
+                _ => bail!("unknown function: {} called for {:?}", func, expr),
+            }
+        }
+
+        fn exported_symbols(&self) -> Vec<&'static str> {
+            vec![REDIS_FUNC]
+        }
+
+        fn name(&self) -> &'static str {
+            redis_resolver_name()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct HttpResolverConfig {
+        /// Request timeout in milliseconds.
+        pub timeout: u64,
+        /// How long fetched documents are cached locally in milliseconds, 0 disables caching.
+        pub cache_ttl: u64,
+        pub cache_size: usize,
+        /// Values substituted for `{name}` placeholders in url templates.
+        pub variables: HashMap<String, String>,
+        pub headers: HashMap<String, String>,
+    }
+
+    impl Default for HttpResolverConfig {
+        fn default() -> Self {
+            Self {
+                timeout: 5000,
+                cache_ttl: 1000,
+                cache_size: 1024,
+                variables: HashMap::new(),
+                headers: HashMap::new(),
+            }
+        }
+    }
+
+    const HTTP_USAGE: &str =
+        "The function must be called as http(url_template, json_pointer[, default])";
+
+    type HttpCacheEntry = (Instant, Arc<serde_json::Value>);
+
+    pub struct HttpSymbolResolver {
+        client: reqwest::Client,
+        variables: HashMap<String, String>,
+        cache_ttl: Duration,
+        pub(super) cache: Mutex<LruCache<String, HttpCacheEntry>>,
+    }
+
+    impl HttpSymbolResolver {
+        pub fn new(config: &HttpResolverConfig) -> Result<Self> {
+            assert!(config.timeout > 0);
+
+            let mut headers = reqwest::header::HeaderMap::new();
+            for (name, value) in &config.headers {
+                headers.insert(
+                    reqwest::header::HeaderName::from_bytes(name.as_bytes())?,
+                    reqwest::header::HeaderValue::from_str(value)?,
+                );
+            }
+            let client = reqwest::Client::builder()
+                .timeout(Duration::from_millis(config.timeout))
+                .default_headers(headers)
+                .build()?;
+            let cache_size = NonZeroUsize::new(config.cache_size.max(1)).unwrap();
+            Ok(Self {
+                client,
+                variables: config.variables.clone(),
+                cache_ttl: Duration::from_millis(config.cache_ttl),
+                cache: Mutex::new(LruCache::new(cache_size)),
+            })
+        }
+
+        pub(super) fn render_url(&self, template: &str) -> String {
+            self.variables
+                .iter()
+                .fold(template.to_string(), |url, (name, value)| {
+                    url.replace(&format!("{{{}}}", name), value)
+                })
+        }
+
+        fn get_cached(&self, url: &str) -> Option<Arc<serde_json::Value>> {
+            if self.cache_ttl.is_zero() {
+                return None;
+            }
+            let mut cache = self.cache.lock();
+            match cache.get(url) {
+                Some((fetched, doc)) if fetched.elapsed() < self.cache_ttl => Some(doc.clone()),
+                Some(_) => {
+                    cache.pop(url);
+                    None
+                }
+                None => None,
+            }
+        }
+
+        pub(super) fn fetch(&self, url: &str) -> Result<Arc<serde_json::Value>> {
+            if let Some(doc) = self.get_cached(url) {
+                return Ok(doc);
+            }
+            let runtime = get_or_init_async_runtime();
+            let request = async {
+                self.client
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<serde_json::Value>()
+                    .await
+            };
+            // block_on panics when called from an async context, e.g. a resolver evaluated in
+            // a tokio task, so the request is awaited on a dedicated thread there
+            let doc = if tokio::runtime::Handle::try_current().is_ok() {
+                std::thread::scope(|s| s.spawn(|| runtime.block_on(request)).join())
+                    .map_err(|_| anyhow!("http: the request thread panicked for {}", url))??
+            } else {
+                runtime.block_on(request)?
+            };
+            let doc = Arc::new(doc);
+            if !self.cache_ttl.is_zero() {
+                self.cache
+                    .lock()
+                    .put(url.to_string(), (Instant::now(), doc.clone()));
+            }
+            Ok(doc)
+        }
+
+        fn get_data(&self, template: &str, pointer: &str) -> Result<Option<serde_json::Value>> {
+            let doc = self.fetch(&self.render_url(template))?;
+            Ok(doc.pointer(pointer).cloned())
+        }
+    }
+
+    pub(super) fn json_to_value(json: &serde_json::Value) -> Value {
+        match json {
+            serde_json::Value::Null => Value::Empty,
+            serde_json::Value::Bool(b) => Value::Boolean(*b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::Int(i),
+                None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(s) => Value::String(s.clone()),
+            serde_json::Value::Array(a) => Value::Tuple(a.iter().map(json_to_value).collect()),
+            serde_json::Value::Object(_) => Value::String(json.to_string()),
+        }
+    }
+
+    impl SymbolResolver for HttpSymbolResolver {
+        fn resolve(&self, func: &str, expr: &Value) -> Result<Value> {
+            match func {
+                "http" => {
+                    if !expr.is_tuple() {
+                        bail!(HTTP_USAGE);
+                    }
+
+                    match expr.as_tuple().unwrap().as_slice() {
+                        [Value::String(url), Value::String(pointer)] => {
+                            match self.get_data(url, pointer)? {
+                                Some(value) => Ok(json_to_value(&value)),
+                                None => bail!("http: {} is not found in {}", pointer, url),
+                            }
+                        }
+                        [Value::String(url), Value::String(pointer), default] => {
+                            match self.get_data(url, pointer)? {
+                                Some(serde_json::Value::String(value)) => {
+                                    cast_str_to_primitive_type(&value, default)
+                                }
+                                Some(serde_json::Value::Null) | None => Ok(default.clone()),
+                                Some(value) => {
+                                    cast_str_to_primitive_type(&value.to_string(), default)
+                                }
+                            }
+                        }
+                        _ => bail!(HTTP_USAGE),
+                    }
+                }
+                _ => bail!("unknown function: {} called for {:?}", func, expr),
+            }
+        }
+
+        fn exported_symbols(&self) -> Vec<&'static str> {
+            vec![HTTP_FUNC]
+        }
+
+        fn name(&self) -> &'static str {
+            http_resolver_name()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct FileResolverConfig {
+        /// JSON or YAML file, the format is selected by the extension.
+        pub path: String,
+        /// How often the file modification time is checked in milliseconds, 0 checks on every call.
+        pub check_interval: u64,
+    }
+
+    struct FileResolverState {
+        modified: Option<SystemTime>,
+        checked: Instant,
+        doc: serde_json::Value,
+    }
+
+    /// Resolves `file(key, default)` from a JSON/YAML document. The key is either a JSON pointer
+    /// (`/a/b`) or a dotted path (`a.b`). The file is reloaded when its modification time
+    /// changes, [`FileSymbolResolver::reload`] forces reloading (e.g. from a SIGHUP handler).
+    ///
+    pub struct FileSymbolResolver {
+        path: PathBuf,
+        check_interval: Duration,
+        state: RwLock<FileResolverState>,
+    }
+
+    impl FileSymbolResolver {
+        pub fn new(config: &FileResolverConfig) -> Result<Self> {
+            let path = PathBuf::from(&config.path);
+            let (modified, doc) = Self::load(&path)?;
+            Ok(Self {
+                path,
+                check_interval: Duration::from_millis(config.check_interval),
+                state: RwLock::new(FileResolverState {
+                    modified,
+                    checked: Instant::now(),
+                    doc,
+                }),
+            })
+        }
+
+        fn load(path: &Path) -> Result<(Option<SystemTime>, serde_json::Value)> {
+            let modified = std::fs::metadata(path)?.modified().ok();
+            let contents = std::fs::read_to_string(path)?;
+            let doc = match path.extension().and_then(|e| e.to_str()) {
+                Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)?,
+                Some("json") => serde_json::from_str(&contents)?,
+                _ => bail!(
+                    "file: unsupported file format {}, use json or yaml",
+                    path.display()
+                ),
+            };
+            Ok((modified, doc))
+        }
+
+        pub fn reload(&self) -> Result<()> {
+            let (modified, doc) = Self::load(&self.path)?;
+            let mut state = trace!(self.state.write());
+            state.modified = modified;
+            state.checked = Instant::now();
+            state.doc = doc;
+            Ok(())
+        }
+
+        fn reload_if_modified(&self) -> Result<()> {
+            let modified = {
+                let state = trace!(self.state.read_recursive());
+                if state.checked.elapsed() < self.check_interval {
+                    return Ok(());
+                }
+                state.modified
+            };
+            let current = std::fs::metadata(&self.path)?.modified().ok();
+            if current != modified {
+                log::info!(
+                    target: "savant_rs::eval_resolvers::file",
+                    "File {} is modified, reloading",
+                    self.path.display()
+                );
+                return self.reload();
+            }
+            let mut state = trace!(self.state.write());
+            state.checked = Instant::now();
+            Ok(())
+        }
+
+        pub(crate) fn get_data(&self, key: &str) -> Result<Option<serde_json::Value>> {
+            if let Err(e) = self.reload_if_modified() {
+                log::warn!(
+                    target: "savant_rs::eval_resolvers::file",
+                    "Failed to reload {}, the last loaded version is used: {}",
+                    self.path.display(),
+                    e
+                );
+            }
+            let state = trace!(self.state.read_recursive());
+            let value = if key.starts_with('/') {
+                state.doc.pointer(key)
+            } else {
+                key.split('.').try_fold(&state.doc, |v, k| match v {
+                    serde_json::Value::Array(a) => k.parse::<usize>().ok().and_then(|i| a.get(i)),
+                    _ => v.get(k),
+                })
+            };
+            Ok(value.cloned())
+        }
+    }
+
+    impl SymbolResolver for FileSymbolResolver {
+        fn resolve(&self, func: &str, expr: &Value) -> Result<Value> {
+            match func {
+                "file" => {
+                    if !expr.is_tuple() {
+                        bail!("The function must be called as file(key, default)");
+                    }
+
+                    match expr.as_tuple().unwrap().as_slice() {
+                        [Value::String(key), default] => match self.get_data(key)? {
+                            Some(serde_json::Value::String(value)) => {
+                                cast_str_to_primitive_type(&value, default)
+                            }
+                            Some(serde_json::Value::Null) | None => Ok(default.clone()),
+                            Some(value) => cast_str_to_primitive_type(&value.to_string(), default),
+                        },
+                        _ => unreachable!(),
+                    }
+                }
+                _ => bail!("unknown function: {} called for {:?}", func, expr),
+            }
+        }
+
+        fn exported_symbols(&self) -> Vec<&'static str> {
+            vec![FILE_FUNC]
+        }
+
+        fn name(&self) -> &'static str {
+            file_resolver_name()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
 }
 
 pub(crate) mod singleton {
     use crate::eval_resolvers::config_resolver_name;
+    use crate::eval_resolvers::file_resolver_name;
     use crate::eval_resolvers::resolvers::{
         ConfigSymbolResolver, EnvSymbolResolver, EtcdCredentials, EtcdSymbolResolver,
-        SymbolResolver, TlsConfig, UtilityResolver,
+        FileResolverConfig, FileSymbolResolver, HttpResolverConfig, HttpSymbolResolver,
+        RedisResolverConfig, RedisSymbolResolver, SymbolResolver, TlsConfig, UtilityResolver,
     };
+    use crate::layered_config::LayeredConfig;
     use crate::rwlock::SavantRwLock;
     use crate::trace;
     use anyhow::Result;
@@ -411,6 +928,38 @@ pub(crate) mod singleton {
         Ok(())
     }
 
+    pub fn register_redis_resolver(config: &RedisResolverConfig) -> Result<()> {
+        let resolver = RedisSymbolResolver::new(config)?;
+        register_symbol_resolver(Arc::new(resolver) as Arc<dyn SymbolResolver>);
+        Ok(())
+    }
+
+    pub fn register_http_resolver(config: &HttpResolverConfig) -> Result<()> {
+        let resolver = HttpSymbolResolver::new(config)?;
+        register_symbol_resolver(Arc::new(resolver) as Arc<dyn SymbolResolver>);
+        Ok(())
+    }
+
+    pub fn register_file_resolver(config: &FileResolverConfig) -> Result<()> {
+        let resolver = FileSymbolResolver::new(config)?;
+        register_symbol_resolver(Arc::new(resolver) as Arc<dyn SymbolResolver>);
+        Ok(())
+    }
+
+    /// Forces the registered file resolver to reload the file, does nothing if it is not registered.
+    ///
+    pub fn reload_file_resolver() -> Result<()> {
+        let resolver = get_symbol_resolver(file_resolver_name());
+        if let Some((_, resolver)) = resolver {
+            resolver
+                .as_any()
+                .downcast_ref::<FileSymbolResolver>()
+                .expect("Wrong downcast")
+                .reload()?;
+        }
+        Ok(())
+    }
+
     pub fn register_config_resolver(symbols: HashMap<String, String>) {
         let mut resolver = ConfigSymbolResolver::new();
         for (key, value) in symbols {
@@ -419,6 +968,14 @@ pub(crate) mod singleton {
         register_symbol_resolver(Arc::new(resolver) as Arc<dyn SymbolResolver>);
     }
 
+    /// Registers the config resolver reading the values from the layered configuration first,
+    /// the symbols set by [`update_config_resolver`] are used for the keys it does not define.
+    ///
+    pub fn register_layered_config_resolver(layers: Arc<LayeredConfig>) {
+        let resolver = ConfigSymbolResolver::with_layers(layers);
+        register_symbol_resolver(Arc::new(resolver) as Arc<dyn SymbolResolver>);
+    }
+
     pub fn update_config_resolver(symbols: HashMap<String, String>) {
         let r = RESOLVERS.read();
         let resolver = r.get(config_resolver_name());
@@ -451,15 +1008,16 @@ pub(crate) mod singleton {
 #[cfg(test)]
 mod tests {
     use crate::eval_resolvers::resolvers::{
-        ConfigSymbolResolver, EnvSymbolResolver, EtcdSymbolResolver, SymbolResolver,
-        UtilityResolver,
+        ConfigSymbolResolver, EnvSymbolResolver, EtcdSymbolResolver, RedisSymbolResolver,
+        SymbolResolver, UtilityResolver,
     };
     use crate::eval_resolvers::{
         cast_str_to_primitive_type, config_resolver_name, get_symbol_resolver,
-        register_config_resolver, unregister_resolver, update_config_resolver, CONFIG_FUNC,
-        ENV_FUNC, ETCD_FUNC,
+        register_config_resolver, unregister_resolver, update_config_resolver, RedisResolverConfig,
+        TlsConfig, CONFIG_FUNC, ENV_FUNC, ETCD_FUNC, REDIS_FUNC,
     };
     use crate::get_or_init_async_runtime;
+    use crate::layered_config::LayeredConfig;
     use bollard::container::{
         Config, CreateContainerOptions, RemoveContainerOptions, StartContainerOptions,
     };
@@ -471,7 +1029,8 @@ mod tests {
     use futures_util::TryStreamExt;
     use hashbrown::HashMap;
     use std::env;
-    use std::time::Duration;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn test_conversions() {
@@ -492,6 +1051,19 @@ mod tests {
         assert_eq!(r, Value::Float(123.456));
     }
 
+    #[test]
+    fn test_tls_options() {
+        let tls = |client_cert: Option<&str>, client_key: Option<&str>| TlsConfig {
+            ca_cert: Some("ca".to_string()),
+            client_cert: client_cert.map(String::from),
+            client_key: client_key.map(String::from),
+        };
+        assert!(tls(None, None).tls_options().is_ok());
+        assert!(tls(Some("cert"), Some("key")).tls_options().is_ok());
+        assert!(tls(Some("cert"), None).tls_options().is_err());
+        assert!(tls(None, Some("key")).tls_options().is_err());
+    }
+
     #[test]
     fn test_env_resolver() {
         let resolver = EnvSymbolResolver;
@@ -541,6 +1113,30 @@ mod tests {
         assert_eq!(value, default);
     }
 
+    #[test]
+    fn test_layered_config_resolver() {
+        let layers = Arc::new(LayeredConfig::new(HashMap::from([(
+            "layered.key".to_string(),
+            "10".to_string(),
+        )])));
+        let mut resolver = ConfigSymbolResolver::with_layers(layers.clone());
+        resolver.add_symbol("layered.key".to_string(), "1".to_string());
+        resolver.add_symbol("symbol.key".to_string(), "2".to_string());
+        let resolve = |key: &str| {
+            resolver
+                .resolve(
+                    CONFIG_FUNC,
+                    &Value::Tuple(vec![Value::String(key.to_string()), Value::Int(0)]),
+                )
+                .unwrap()
+        };
+        assert_eq!(resolve("layered.key"), Value::Int(10));
+        assert_eq!(resolve("symbol.key"), Value::Int(2));
+        layers.set_default("symbol.key", "20");
+        assert_eq!(resolve("symbol.key"), Value::Int(20));
+        assert_eq!(resolve("missing"), Value::Int(0));
+    }
+
     #[test]
     fn test_update_config_resolver() {
         register_config_resolver(HashMap::from([(
@@ -614,6 +1210,202 @@ mod tests {
         assert_eq!(value, Value::Boolean(true));
     }
 
+    #[test]
+    fn test_redis_resolver_cache() -> anyhow::Result<()> {
+        let config = RedisResolverConfig {
+            url: "redis://127.0.0.1:1/0".to_string(),
+            ..Default::default()
+        };
+        let client = redis::Client::open(config.url.as_str())?;
+        let pool = r2d2::Pool::builder()
+            .connection_timeout(Duration::from_millis(100))
+            .build_unchecked(client);
+        let resolver = RedisSymbolResolver::with_pool(pool, &config);
+        resolver
+            .cache
+            .lock()
+            .put("abc".to_string(), (Instant::now(), Some("1".to_string())));
+        resolver
+            .cache
+            .lock()
+            .put("missing".to_string(), (Instant::now(), None));
+
+        let value = resolver.resolve(
+            REDIS_FUNC,
+            &Value::Tuple(vec![Value::String("abc".to_string()), Value::Int(0)]),
+        )?;
+        assert_eq!(value, Value::Int(1));
+
+        let value = resolver.resolve(
+            REDIS_FUNC,
+            &Value::Tuple(vec![Value::String("missing".to_string()), Value::Int(-1)]),
+        )?;
+        assert_eq!(value, Value::Int(-1));
+
+        // not cached, the server is unreachable
+        assert!(resolver
+            .resolve(
+                REDIS_FUNC,
+                &Value::Tuple(vec![Value::String("xyz".to_string()), Value::Int(0)]),
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_resolver_cache() -> anyhow::Result<()> {
+        let config = HttpResolverConfig {
+            variables: HashMap::from([("api".to_string(), "http://127.0.0.1:1".to_string())]),
+            ..Default::default()
+        };
+        let resolver = HttpSymbolResolver::new(&config)?;
+        assert_eq!(
+            resolver.render_url("{api}/thresholds"),
+            "http://127.0.0.1:1/thresholds"
+        );
+        let doc = serde_json::json!({"person": {"confidence": 0.5, "enabled": true, "name": "p"}});
+        resolver.cache.lock().put(
+            "http://127.0.0.1:1/thresholds".to_string(),
+            (Instant::now(), Arc::new(doc)),
+        );
+
+        let resolve = |args: Vec<Value>| resolver.resolve(HTTP_FUNC, &Value::Tuple(args));
+        let url = Value::String("{api}/thresholds".to_string());
+        assert_eq!(
+            resolve(vec![
+                url.clone(),
+                Value::String("/person/confidence".into())
+            ])?,
+            Value::Float(0.5)
+        );
+        assert_eq!(
+            resolve(vec![
+                url.clone(),
+                Value::String("/person/enabled".into()),
+                Value::Boolean(false)
+            ])?,
+            Value::Boolean(true)
+        );
+        assert_eq!(
+            resolve(vec![
+                url.clone(),
+                Value::String("/person/missing".into()),
+                Value::Int(3)
+            ])?,
+            Value::Int(3)
+        );
+        assert!(resolve(vec![url, Value::String("/person/missing".into())]).is_err());
+        // not cached, the server is unreachable
+        assert!(resolve(vec![
+            Value::String("{api}/other".to_string()),
+            Value::String("/x".into())
+        ])
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_resolver_cache_ttl() -> anyhow::Result<()> {
+        let resolver = HttpSymbolResolver::new(&HttpResolverConfig {
+            cache_ttl: 100,
+            ..Default::default()
+        })?;
+        let url = "http://127.0.0.1:1/thresholds";
+        let doc = Arc::new(serde_json::json!({"confidence": 0.5}));
+        resolver
+            .cache
+            .lock()
+            .put(url.to_string(), (Instant::now(), doc.clone()));
+        assert_eq!(resolver.fetch(url)?, doc);
+
+        // the expired document is evicted and fetched again, the server is unreachable
+        let expired = Instant::now() - Duration::from_millis(200);
+        resolver
+            .cache
+            .lock()
+            .put(url.to_string(), (expired, doc.clone()));
+        assert!(resolver.fetch(url).is_err());
+        assert!(resolver.cache.lock().peek(url).is_none());
+
+        // the caching is disabled with the zero ttl
+        let resolver = HttpSymbolResolver::new(&HttpResolverConfig {
+            cache_ttl: 0,
+            ..Default::default()
+        })?;
+        resolver
+            .cache
+            .lock()
+            .put(url.to_string(), (Instant::now(), doc));
+        assert!(resolver.fetch(url).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_resolver_in_async_context() -> anyhow::Result<()> {
+        let resolver = HttpSymbolResolver::new(&HttpResolverConfig::default())?;
+        let res = get_or_init_async_runtime()
+            .block_on(async { resolver.fetch("http://127.0.0.1:1/thresholds") });
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_resolver() -> anyhow::Result<()> {
+        let path = env::temp_dir().join(format!("file-resolver-{}.yaml", std::process::id()));
+        std::fs::write(&path, "person:\n  confidence: 0.5\n  labels: [a, b]\n")?;
+        let resolver = FileSymbolResolver::new(&FileResolverConfig {
+            path: path.to_string_lossy().to_string(),
+            check_interval: 0,
+        })?;
+        let resolve = |key: &str, default: Value| {
+            resolver.resolve(
+                FILE_FUNC,
+                &Value::Tuple(vec![Value::String(key.to_string()), default]),
+            )
+        };
+        assert_eq!(
+            resolve("person.confidence", Value::Float(0.0))?,
+            Value::Float(0.5)
+        );
+        assert_eq!(
+            resolve("/person/labels/1", Value::String("".into()))?,
+            Value::String("b".into())
+        );
+        assert_eq!(
+            resolve("person.labels.1", Value::String("".into()))?,
+            Value::String("b".into())
+        );
+        assert_eq!(resolve("person.missing", Value::Int(7))?, Value::Int(7));
+
+        std::fs::write(&path, "person:\n  confidence: 0.7\n")?;
+        std::fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(std::time::SystemTime::now() + Duration::from_secs(10))?;
+        assert_eq!(
+            resolve("person.confidence", Value::Float(0.0))?,
+            Value::Float(0.7)
+        );
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_to_value() {
+        let json = serde_json::json!([1, 1.5, "a", null, [true]]);
+        assert_eq!(
+            json_to_value(&json),
+            Value::Tuple(vec![
+                Value::Int(1),
+                Value::Float(1.5),
+                Value::String("a".to_string()),
+                Value::Empty,
+                Value::Tuple(vec![Value::Boolean(true)]),
+            ])
+        );
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_etcd_resolver() -> anyhow::Result<()> {
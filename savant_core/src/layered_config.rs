@@ -0,0 +1,353 @@
+use crate::eval_resolvers::resolvers::FileSymbolResolver;
+use crate::eval_resolvers::FileResolverConfig;
+use crate::parameter_storage::TypedParameterStorage;
+use anyhow::{anyhow, Result};
+use hashbrown::HashMap;
+use parking_lot::{Mutex, RwLock};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// The configuration layers in the order of priority, the later layers override the earlier
+/// ones.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConfigLayer {
+    Defaults,
+    File,
+    Etcd,
+    Env,
+}
+
+/// The source of the configuration values of a layer. The keys are dotted paths, e.g.
+/// `detector.threshold`.
+///
+pub trait ConfigSource: Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<String>>;
+}
+
+/// Reads `detector.threshold` from `{PREFIX}_DETECTOR_THRESHOLD`.
+///
+pub struct EnvSource {
+    prefix: String,
+}
+
+impl EnvSource {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+        }
+    }
+
+    pub fn variable(&self, key: &str) -> String {
+        let name = key
+            .trim_start_matches('/')
+            .replace(['.', '-', '/'], "_")
+            .to_uppercase();
+        if self.prefix.is_empty() {
+            name
+        } else {
+            format!("{}_{}", self.prefix.to_uppercase(), name)
+        }
+    }
+}
+
+impl ConfigSource for EnvSource {
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(std::env::var(self.variable(key)).ok())
+    }
+}
+
+/// Reads the values from a JSON or YAML document, the file is reloaded when modified.
+///
+pub struct FileSource(FileSymbolResolver);
+
+impl FileSource {
+    pub fn new(config: &FileResolverConfig) -> Result<Self> {
+        Ok(Self(FileSymbolResolver::new(config)?))
+    }
+}
+
+impl ConfigSource for FileSource {
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match self.0.get_data(key)? {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(value)) => Some(value),
+            Some(value) => Some(value.to_string()),
+        })
+    }
+}
+
+/// Reads `detector.threshold` from the etcd key `{prefix}/detector/threshold`.
+///
+pub struct EtcdSource {
+    storage: Arc<TypedParameterStorage>,
+    prefix: String,
+}
+
+impl EtcdSource {
+    pub fn new(storage: Arc<TypedParameterStorage>, prefix: &str) -> Self {
+        Self {
+            storage,
+            prefix: prefix.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn path(&self, key: &str) -> String {
+        format!(
+            "{}/{}",
+            self.prefix,
+            key.trim_start_matches('/').replace('.', "/")
+        )
+    }
+}
+
+impl ConfigSource for EtcdSource {
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .storage
+            .get_raw(&self.path(key))?
+            .map(|(_, data)| String::from_utf8_lossy(&data).to_string()))
+    }
+}
+
+pub type ConfigCallback = Arc<dyn Fn(&str, Option<&str>) + Send + Sync>;
+
+struct ConfigWatch {
+    id: u64,
+    key: String,
+    value: Option<String>,
+    callback: ConfigCallback,
+}
+
+/// The configuration resolved through the stack of layers: defaults < file < etcd < env. The
+/// value of a key comes from the highest layer defining it.
+///
+/// The changes are detected by [`LayeredConfig::refresh`] for the watched keys, the callbacks
+/// receive the key and the new value.
+///
+#[derive(Default)]
+pub struct LayeredConfig {
+    defaults: RwLock<HashMap<String, String>>,
+    sources: RwLock<Vec<(ConfigLayer, Box<dyn ConfigSource>)>>,
+    watches: Mutex<Vec<ConfigWatch>>,
+    next_watch_id: AtomicU64,
+}
+
+impl LayeredConfig {
+    pub fn new(defaults: HashMap<String, String>) -> Self {
+        Self {
+            defaults: RwLock::new(defaults),
+            ..Default::default()
+        }
+    }
+
+    /// Adds the source to the layer, the sources of the same layer are queried in the order of
+    /// addition.
+    ///
+    pub fn with_source(self, layer: ConfigLayer, source: Box<dyn ConfigSource>) -> Self {
+        self.add_source(layer, source);
+        self
+    }
+
+    pub fn add_source(&self, layer: ConfigLayer, source: Box<dyn ConfigSource>) {
+        let mut sources = self.sources.write();
+        let pos = sources.partition_point(|(l, _)| *l >= layer);
+        sources.insert(pos, (layer, source));
+    }
+
+    pub fn set_default(&self, key: &str, value: &str) {
+        self.defaults
+            .write()
+            .insert(key.to_string(), value.to_string());
+    }
+
+    /// Returns the value and the layer it comes from. The failing source is skipped, so an
+    /// unavailable etcd does not hide the file and the defaults.
+    ///
+    pub fn resolve(&self, key: &str) -> Option<(ConfigLayer, String)> {
+        for (layer, source) in self.sources.read().iter() {
+            match source.get(key) {
+                Ok(Some(value)) => return Some((*layer, value)),
+                Ok(None) => {}
+                Err(e) => log::warn!(
+                    target: "savant_rs::layered_config",
+                    "Failed to read {} from the {:?} layer: {:?}", key, layer, e
+                ),
+            }
+        }
+        self.defaults
+            .read()
+            .get(key)
+            .map(|value| (ConfigLayer::Defaults, value.clone()))
+    }
+
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.resolve(key).map(|(_, value)| value)
+    }
+
+    pub fn get<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.get_string(key)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<T>()
+                    .map_err(|e| anyhow!("The value {:?} of {} is invalid: {}", value, key, e))
+            })
+            .transpose()
+    }
+
+    pub fn get_or<T>(&self, key: &str, default: T) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+
+    /// Calls the callback when the value of the key changes, returns the id of the watch.
+    ///
+    pub fn watch<F>(&self, key: &str, callback: F) -> u64
+    where
+        F: Fn(&str, Option<&str>) + Send + Sync + 'static,
+    {
+        let id = self.next_watch_id.fetch_add(1, Ordering::SeqCst);
+        let value = self.get_string(key);
+        self.watches.lock().push(ConfigWatch {
+            id,
+            key: key.to_string(),
+            value,
+            callback: Arc::new(callback),
+        });
+        id
+    }
+
+    pub fn unwatch(&self, id: u64) -> bool {
+        let mut watches = self.watches.lock();
+        let len = watches.len();
+        watches.retain(|w| w.id != id);
+        watches.len() != len
+    }
+
+    /// Resolves the watched keys again and calls the callbacks of the changed ones, returns the
+    /// number of the called callbacks.
+    ///
+    pub fn refresh(&self) -> usize {
+        let mut changed = Vec::new();
+        {
+            let mut watches = self.watches.lock();
+            for watch in watches.iter_mut() {
+                let value = self.get_string(&watch.key);
+                if value != watch.value {
+                    watch.value.clone_from(&value);
+                    changed.push((watch.key.clone(), value, watch.callback.clone()));
+                }
+            }
+        }
+        // the callbacks are called without the lock, so they can use the config
+        for (key, value, callback) in &changed {
+            callback(key, value.as_deref());
+        }
+        changed.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MapSource(RwLock<HashMap<String, String>>);
+
+    impl MapSource {
+        fn new(values: &[(&str, &str)]) -> Self {
+            Self(RwLock::new(
+                values
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ))
+        }
+    }
+
+    impl ConfigSource for Arc<MapSource> {
+        fn get(&self, key: &str) -> Result<Option<String>> {
+            Ok(self.0.read().get(key).cloned())
+        }
+    }
+
+    #[test]
+    fn test_layer_priority() -> Result<()> {
+        let defaults = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "1".to_string()),
+            ("c".to_string(), "1".to_string()),
+        ]);
+        let config = LayeredConfig::new(defaults)
+            .with_source(
+                ConfigLayer::Env,
+                Box::new(Arc::new(MapSource::new(&[("c", "3")]))),
+            )
+            .with_source(
+                ConfigLayer::File,
+                Box::new(Arc::new(MapSource::new(&[("b", "2"), ("c", "2")]))),
+            );
+        assert_eq!(
+            config.resolve("a"),
+            Some((ConfigLayer::Defaults, "1".into()))
+        );
+        assert_eq!(config.resolve("b"), Some((ConfigLayer::File, "2".into())));
+        assert_eq!(config.resolve("c"), Some((ConfigLayer::Env, "3".into())));
+        assert_eq!(config.get::<i64>("c")?, Some(3));
+        assert_eq!(config.get_or::<f64>("missing", 0.5)?, 0.5);
+        config.set_default("d", "x");
+        assert!(config.get::<i64>("d").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_env_source() -> Result<()> {
+        let source = EnvSource::new("savant_test");
+        assert_eq!(
+            source.variable("detector.min-confidence"),
+            "SAVANT_TEST_DETECTOR_MIN_CONFIDENCE"
+        );
+        std::env::set_var("SAVANT_TEST_LAYERED_KEY", "42");
+        assert_eq!(source.get("layered.key")?, Some("42".to_string()));
+        assert_eq!(EnvSource::new("").variable("/a/b"), "A_B");
+        Ok(())
+    }
+
+    #[test]
+    fn test_watch_refresh() {
+        let file = Arc::new(MapSource::new(&[("key", "1")]));
+        let config =
+            LayeredConfig::default().with_source(ConfigLayer::File, Box::new(file.clone()));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let id = config.watch("key", {
+            let seen = seen.clone();
+            move |key, value| seen.lock().push((key.to_string(), value.map(String::from)))
+        });
+        assert_eq!(config.refresh(), 0);
+
+        file.0.write().insert("key".to_string(), "2".to_string());
+        assert_eq!(config.refresh(), 1);
+        file.0.write().remove("key");
+        assert_eq!(config.refresh(), 1);
+        assert_eq!(
+            *seen.lock(),
+            vec![
+                ("key".to_string(), Some("2".to_string())),
+                ("key".to_string(), None)
+            ]
+        );
+
+        assert!(config.unwatch(id));
+        file.0.write().insert("key".to_string(), "3".to_string());
+        assert_eq!(config.refresh(), 0);
+    }
+}
@@ -3,22 +3,30 @@ use opentelemetry::global::BoxedTracer;
 use std::sync::OnceLock;
 use tokio::runtime::Runtime;
 
+pub mod analytics;
 pub mod atomic_f32;
 pub mod deadlock_detection;
 pub mod draw;
+pub mod egress;
 pub mod eval_cache;
 pub mod eval_context;
 pub mod eval_resolvers;
 /// A trait to serialize various objects to json.
 pub mod json_api;
+pub mod layered_config;
 pub mod macros;
 pub mod match_query;
 pub mod message;
 pub mod otlp;
+pub mod parameter_storage;
 pub mod pipeline;
 pub mod primitives;
 pub mod protobuf;
+pub mod remux;
+#[cfg(feature = "rendering")]
+pub mod rendering;
 pub mod rwlock;
+pub mod store;
 pub mod symbol_mapper;
 pub mod telemetry;
 pub mod test;
@@ -64,11 +72,17 @@ pub fn get_tracer() -> BoxedTracer {
 
 pub mod rust {
     pub use super::otlp::PropagatedContext;
+    pub use super::pipeline::spec::PipelineSpec;
+    pub use super::pipeline::stage::StageCapacity;
+    pub use super::pipeline::stage::StageOccupancy;
+    pub use super::pipeline::stage::StageOverflowPolicy;
+    pub use super::pipeline::stage::StageRoute;
     pub use super::pipeline::stats::FrameProcessingStatRecord;
     pub use super::pipeline::stats::FrameProcessingStatRecordType;
     pub use super::pipeline::stats::StageLatencyMeasurements;
     pub use super::pipeline::stats::StageLatencyStat;
     pub use super::pipeline::stats::StageProcessingStat;
+    pub use super::pipeline::stats::StageResidencyStats;
     pub use super::pipeline::Pipeline;
     pub use super::pipeline::PipelineConfiguration;
     pub use super::pipeline::PipelineConfigurationBuilder;
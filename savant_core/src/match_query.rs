@@ -1,27 +1,47 @@
-use crate::eval_cache::{get_compiled_eval_expr, get_compiled_jmp_filter};
+use crate::eval_cache::{get_compiled_eval_expr, get_compiled_jmp_filter, get_compiled_regex};
 use crate::eval_context::ObjectContext;
 use crate::eval_resolvers::{
-    config_resolver_name, env_resolver_name, etcd_resolver_name, utility_resolver_name,
+    config_resolver_name, env_resolver_name, etcd_resolver_name, file_resolver_name,
+    http_resolver_name, redis_resolver_name, utility_resolver_name,
 };
 use crate::json_api::ToSerdeJsonValue;
+use crate::primitives::attribute_value::AttributeValueVariant;
 
-use crate::primitives::frame::{VideoFrameContent, VideoFrameTranscodingMethod};
+use crate::primitives::frame::{VideoFrameContent, VideoFrameProxy, VideoFrameTranscodingMethod};
 use crate::primitives::object::private::{SealedWithFrame, SealedWithParent};
 use crate::primitives::object::{BorrowedVideoObject, ObjectAccess, ObjectOperations, VideoObject};
-use crate::primitives::{BBoxMetricType, RBBox, WithAttributes};
+use crate::primitives::{BBoxMetricType, Point, PolygonalArea, RBBox, Segment, WithAttributes};
 use crate::utils::iter::{
     all_with_control_flow, any_with_control_flow, fiter_map_with_control_flow,
     partition_with_control_flow,
 };
+use anyhow::Context as _;
+use geo::{Contains, Intersects};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::ControlFlow;
+use std::sync::Arc;
+
+mod aggregate;
+mod compiled;
+mod parallel;
+mod parser;
+mod trace;
 
 pub use crate::query_and as and;
 pub use crate::query_not as not;
 pub use crate::query_or as or;
 pub use crate::query_stop_if_false as stop_if_false;
 pub use crate::query_stop_if_true as stop_if_true;
+pub use aggregate::{
+    batch_count, batch_group_by, batch_stats, count, group_by, stats, ObjectStats, ValueStats,
+};
+pub use compiled::CompiledQuery;
+pub use parallel::{
+    filter_parallel, filter_parallel_in, partition_parallel, partition_parallel_in, shared_pool,
+};
+pub use trace::QueryTrace;
 
 pub type VideoObjectsProxyBatch = HashMap<i64, Vec<BorrowedVideoObject>>;
 
@@ -101,6 +121,49 @@ impl ExecutableMatchQuery<&i64, ()> for IntExpression {
     }
 }
 
+/// The regular expression compiled when the expression is constructed or deserialized, so an
+/// invalid pattern is rejected before the query is executed.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RegexPattern {
+    pattern: String,
+    regex: Arc<Regex>,
+}
+
+impl RegexPattern {
+    pub fn new(pattern: &str) -> anyhow::Result<Self> {
+        let regex = get_compiled_regex(pattern)
+            .with_context(|| format!("Invalid regular expression {}", pattern))?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn is_match(&self, s: &str) -> bool {
+        self.regex.is_match(s)
+    }
+}
+
+impl TryFrom<String> for RegexPattern {
+    type Error = anyhow::Error;
+
+    fn try_from(pattern: String) -> anyhow::Result<Self> {
+        Self::new(&pattern)
+    }
+}
+
+impl From<RegexPattern> for String {
+    fn from(pattern: RegexPattern) -> Self {
+        pattern.pattern
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename = "str")]
 pub enum StringExpression {
@@ -118,6 +181,10 @@ pub enum StringExpression {
     EndsWith(String),
     #[serde(rename = "one_of")]
     OneOf(Vec<String>),
+    /// Matches if the regular expression matches any part of the string, use `^...$` to match
+    /// the whole string.
+    #[serde(rename = "matches")]
+    Matches(RegexPattern),
 }
 
 impl ExecutableMatchQuery<&str, ()> for StringExpression {
@@ -130,6 +197,7 @@ impl ExecutableMatchQuery<&str, ()> for StringExpression {
             StringExpression::StartsWith(x) => o.starts_with(x),
             StringExpression::EndsWith(x) => o.ends_with(x),
             StringExpression::OneOf(v) => v.iter().any(|e| e.as_str() == o),
+            StringExpression::Matches(x) => x.is_match(o),
         })
     }
 }
@@ -175,6 +243,29 @@ pub enum MatchQuery {
         metric_type: BBoxMetricType,
         threshold_expr: FloatExpression,
     },
+    #[serde(rename = "track.bbox.within_polygon")]
+    TrackBoxWithinPolygon(PolygonalArea),
+    /// Matches if the object has the track history with at least two observations and its
+    /// speed (pixels per observation) matches the expression.
+    #[serde(rename = "track.speed")]
+    TrackSpeed(FloatExpression),
+    /// Matches if the object has the track history with at least two observations and its
+    /// movement direction (degrees, 0 is along the X axis) matches the expression.
+    #[serde(rename = "track.direction")]
+    TrackDirection(FloatExpression),
+    /// Matches if the object has the track history and the number of the track observations
+    /// matches the expression.
+    #[serde(rename = "track.age")]
+    TrackAge(IntExpression),
+
+    // keypoints
+    /// Matches if the object has the keypoint with the name.
+    #[serde(rename = "keypoint.defined")]
+    KeyPointDefined(String),
+    /// Matches if the object has the keypoint with the name and the keypoint confidence matches
+    /// the expression.
+    #[serde(rename = "keypoint.confidence")]
+    KeyPointConfidence(String, FloatExpression),
 
     // parent
     #[serde(rename = "parent.defined")]
@@ -213,6 +304,20 @@ pub enum MatchQuery {
         metric_type: BBoxMetricType,
         threshold_expr: FloatExpression,
     },
+    #[serde(rename = "bbox.within_polygon")]
+    BoxWithinPolygon(PolygonalArea),
+    #[serde(rename = "bbox.intersects_polygon")]
+    BoxIntersectsPolygon(PolygonalArea),
+    /// Matches if the distance between the detection box center and the point matches the
+    /// expression.
+    #[serde(rename = "bbox.center.distance_to_point")]
+    BoxCenterDistanceToPoint {
+        point: Point,
+        threshold_expr: FloatExpression,
+    },
+    /// Matches if the detection box crosses or touches the segment.
+    #[serde(rename = "bbox.crosses_segment")]
+    BoxCrossesSegment { segment: Segment },
     // Attributes
     #[serde(rename = "attribute.exists")]
     AttributeExists(String, String),
@@ -220,6 +325,15 @@ pub enum MatchQuery {
     AttributesEmpty,
     #[serde(rename = "attributes.jmes_query")]
     AttributesJMESQuery(String),
+    /// Matches if any float value of the attribute matches the expression.
+    #[serde(rename = "attribute.float")]
+    AttributeFloat(String, String, FloatExpression),
+    /// Matches if any integer value of the attribute matches the expression.
+    #[serde(rename = "attribute.int")]
+    AttributeInt(String, String, IntExpression),
+    /// Matches if any string value of the attribute matches the expression.
+    #[serde(rename = "attribute.string")]
+    AttributeString(String, String, StringExpression),
 
     // combinators
     #[serde(rename = "and")]
@@ -260,6 +374,65 @@ pub enum MatchQuery {
     FrameAttributesJMESQuery(String),
 }
 
+fn is_match(res: ControlFlow<bool, bool>) -> bool {
+    matches!(res, ControlFlow::Continue(true) | ControlFlow::Break(true))
+}
+
+fn any_attribute_value<F>(o: &VideoObject, namespace: &str, name: &str, f: F) -> bool
+where
+    F: Fn(&AttributeValueVariant) -> bool,
+{
+    o.attributes
+        .iter()
+        .find(|a| a.namespace == namespace && a.name == name)
+        .is_some_and(|a| a.values.iter().any(|v| f(&v.value)))
+}
+
+fn jmes_matches(filter: &jmespath::Expression, json: &serde_json::Value) -> bool {
+    let res = filter.search(json).unwrap();
+    !(res.is_null()
+        || (res.is_array() && res.as_array().unwrap().is_empty())
+        || (res.is_boolean() && !res.as_boolean().unwrap())
+        || (res.is_object()) && res.as_object().unwrap().is_empty())
+}
+
+fn object_attributes_json(o: &VideoObject) -> serde_json::Value {
+    serde_json::json!(o
+        .attributes
+        .iter()
+        .map(|v| v.to_serde_json_value())
+        .collect::<Vec<_>>())
+}
+
+fn frame_attributes_json(frame: &VideoFrameProxy) -> serde_json::Value {
+    serde_json::json!(frame
+        .get_attributes()
+        .iter()
+        .flat_map(|(ns, l)| frame.get_attribute(ns, l))
+        .map(|v| v.to_serde_json_value())
+        .collect::<Vec<_>>())
+}
+
+fn new_object_context(o: &VideoObject) -> ObjectContext<'_> {
+    ObjectContext::new(
+        o,
+        &[
+            utility_resolver_name(),
+            etcd_resolver_name(),
+            redis_resolver_name(),
+            http_resolver_name(),
+            file_resolver_name(),
+            config_resolver_name(),
+            env_resolver_name(),
+        ],
+    )
+}
+
+fn box_within_polygon(bbox: &RBBox, area: &PolygonalArea) -> bool {
+    let bbox = bbox.get_as_polygonal_area();
+    area.polygon().contains(&*bbox.polygon())
+}
+
 impl ExecutableMatchQuery<&VideoObject, ()> for MatchQuery {
     fn execute(&self, o: &VideoObject, _: &mut ()) -> ControlFlow<bool, bool> {
         match self {
@@ -328,6 +501,40 @@ impl ExecutableMatchQuery<&VideoObject, ()> for MatchQuery {
                     };
                     threshold_expr.execute(&metric, &mut ())
                 }),
+            MatchQuery::TrackBoxWithinPolygon(area) => ControlFlow::Continue(
+                o.track_box
+                    .as_ref()
+                    .is_some_and(|t| box_within_polygon(t, area)),
+            ),
+            MatchQuery::TrackSpeed(x) => o
+                .track_history
+                .as_ref()
+                .and_then(|h| h.speed())
+                .map(|v| x.execute(&v, &mut ()))
+                .unwrap_or(ControlFlow::Continue(false)),
+            MatchQuery::TrackDirection(x) => o
+                .track_history
+                .as_ref()
+                .and_then(|h| h.direction())
+                .map(|v| x.execute(&v, &mut ()))
+                .unwrap_or(ControlFlow::Continue(false)),
+            MatchQuery::TrackAge(x) => o
+                .track_history
+                .as_ref()
+                .map(|h| x.execute(&(h.get_observations() as i64), &mut ()))
+                .unwrap_or(ControlFlow::Continue(false)),
+
+            // keypoints
+            MatchQuery::KeyPointDefined(name) => {
+                ControlFlow::Continue(o.keypoints.as_ref().is_some_and(|k| k.get(name).is_some()))
+            }
+            MatchQuery::KeyPointConfidence(name, x) => o
+                .keypoints
+                .as_ref()
+                .and_then(|k| k.get(name))
+                .and_then(|p| p.confidence)
+                .map(|c| x.execute(&c, &mut ()))
+                .unwrap_or(ControlFlow::Continue(false)),
 
             // parent
             MatchQuery::ParentDefined => ControlFlow::Continue(o.parent_id.is_some()),
@@ -364,6 +571,29 @@ impl ExecutableMatchQuery<&VideoObject, ()> for MatchQuery {
                 };
                 threshold_expr.execute(&metric, &mut ())
             }
+            MatchQuery::BoxWithinPolygon(area) => {
+                ControlFlow::Continue(box_within_polygon(&o.detection_box, area))
+            }
+            MatchQuery::BoxIntersectsPolygon(area) => {
+                let bbox = o.detection_box.get_as_polygonal_area();
+                ControlFlow::Continue(area.polygon().intersects(&*bbox.polygon()))
+            }
+            MatchQuery::BoxCenterDistanceToPoint {
+                point,
+                threshold_expr,
+            } => {
+                let dx = o.detection_box.get_xc() - point.x;
+                let dy = o.detection_box.get_yc() - point.y;
+                threshold_expr.execute(&dx.hypot(dy), &mut ())
+            }
+            MatchQuery::BoxCrossesSegment { segment } => {
+                let line = geo::Line::new(
+                    (segment.begin.x as f64, segment.begin.y as f64),
+                    (segment.end.x as f64, segment.end.y as f64),
+                );
+                let bbox = o.detection_box.get_as_polygonal_area();
+                ControlFlow::Continue(bbox.polygon().intersects(&line))
+            }
 
             // attributes
             MatchQuery::AttributeExists(namespace, label) => {
@@ -372,18 +602,31 @@ impl ExecutableMatchQuery<&VideoObject, ()> for MatchQuery {
             MatchQuery::AttributesEmpty => ControlFlow::Continue(o.attributes.is_empty()),
             MatchQuery::AttributesJMESQuery(x) => {
                 let filter = get_compiled_jmp_filter(x).unwrap();
-                let json = &serde_json::json!(o
-                    .attributes
-                    .iter()
-                    .map(|v| v.to_serde_json_value())
-                    .collect::<Vec<_>>());
-                let res = filter.search(json).unwrap();
-                ControlFlow::Continue(
-                    !(res.is_null()
-                        || (res.is_array() && res.as_array().unwrap().is_empty())
-                        || (res.is_boolean() && !res.as_boolean().unwrap())
-                        || (res.is_object()) && res.as_object().unwrap().is_empty()),
-                )
+                ControlFlow::Continue(jmes_matches(&filter, &object_attributes_json(o)))
+            }
+            MatchQuery::AttributeFloat(namespace, name, x) => {
+                let m = |f: &f64| is_match(x.execute(&(*f as f32), &mut ()));
+                ControlFlow::Continue(any_attribute_value(o, namespace, name, |v| match v {
+                    AttributeValueVariant::Float(f) => m(f),
+                    AttributeValueVariant::FloatVector(fs) => fs.iter().any(m),
+                    _ => false,
+                }))
+            }
+            MatchQuery::AttributeInt(namespace, name, x) => {
+                let m = |i: &i64| is_match(x.execute(i, &mut ()));
+                ControlFlow::Continue(any_attribute_value(o, namespace, name, |v| match v {
+                    AttributeValueVariant::Integer(i) => m(i),
+                    AttributeValueVariant::IntegerVector(is) => is.iter().any(m),
+                    _ => false,
+                }))
+            }
+            MatchQuery::AttributeString(namespace, name, x) => {
+                let m = |s: &String| is_match(x.execute(s.as_str(), &mut ()));
+                ControlFlow::Continue(any_attribute_value(o, namespace, name, |v| match v {
+                    AttributeValueVariant::String(s) => m(s),
+                    AttributeValueVariant::StringVector(ss) => ss.iter().any(m),
+                    _ => false,
+                }))
             }
             MatchQuery::Idle => ControlFlow::Continue(true),
             _ => panic!("not implemented"),
@@ -523,23 +766,7 @@ impl ExecutableMatchQuery<&VideoObject, ObjectContext<'_>> for MatchQuery {
                 let parent_frame = parent_frame_opt.unwrap();
 
                 let filter = get_compiled_jmp_filter(x).unwrap();
-                let attributes = parent_frame
-                    .get_attributes()
-                    .iter()
-                    .flat_map(|(ns, l)| parent_frame.get_attribute(ns, l))
-                    .collect::<Vec<_>>();
-
-                let json = &serde_json::json!(attributes
-                    .iter()
-                    .map(|v| v.to_serde_json_value())
-                    .collect::<Vec<_>>());
-                let json_res = filter.search(json).unwrap();
-                let res = !(json_res.is_null()
-                    || (json_res.is_array() && json_res.as_array().unwrap().is_empty())
-                    || (json_res.is_boolean() && !json_res.as_boolean().unwrap())
-                    || (json_res.is_object()) && json_res.as_object().unwrap().is_empty());
-
-                ControlFlow::Continue(res)
+                ControlFlow::Continue(jmes_matches(&filter, &frame_attributes_json(&parent_frame)))
             }
 
             _ => o.with_object_ref(|o| self.execute(o, &mut ())),
@@ -549,16 +776,14 @@ impl ExecutableMatchQuery<&VideoObject, ObjectContext<'_>> for MatchQuery {
 
 impl MatchQuery {
     pub fn execute_with_new_context(&self, o: &VideoObject) -> ControlFlow<bool, bool> {
-        let mut context = ObjectContext::new(
-            o,
-            &[
-                utility_resolver_name(),
-                etcd_resolver_name(),
-                config_resolver_name(),
-                env_resolver_name(),
-            ],
-        );
-        self.execute(o, &mut context)
+        self.execute(o, &mut new_object_context(o))
+    }
+
+    /// Compiles the query for repeated execution, see [`CompiledQuery`]. Fails if a JMESPath
+    /// filter or an eval expression of the query is malformed.
+    ///
+    pub fn compile(&self) -> anyhow::Result<CompiledQuery> {
+        CompiledQuery::new(self)
     }
 
     pub fn to_json_pretty(&self) -> String {
@@ -580,6 +805,133 @@ impl MatchQuery {
     pub fn from_yaml(yaml: &str) -> anyhow::Result<Self> {
         Ok(serde_json::from_value(serde_yaml::from_str(yaml)?)?)
     }
+
+    /// Rewrites the query into an equivalent one which is cheaper to execute: nested `And`/`Or`
+    /// are flattened, `Idle` and double negations are removed, branches which are never
+    /// evaluated are dropped and cheap predicates are moved before expensive ones.
+    ///
+    /// The operands of `And`/`Or` containing `StopIfFalse`/`StopIfTrue` are not reordered, as
+    /// their order defines which of them stops the evaluation.
+    ///
+    pub fn optimize(&self) -> MatchQuery {
+        self.clone().optimized()
+    }
+
+    fn optimized(self) -> MatchQuery {
+        match self {
+            MatchQuery::And(v) => Self::optimized_junction(v, true),
+            MatchQuery::Or(v) => Self::optimized_junction(v, false),
+            MatchQuery::Not(q) => match q.optimized() {
+                MatchQuery::Not(inner) => *inner,
+                q => MatchQuery::Not(Box::new(q)),
+            },
+            MatchQuery::StopIfFalse(q) => MatchQuery::StopIfFalse(Box::new(q.optimized())),
+            MatchQuery::StopIfTrue(q) => MatchQuery::StopIfTrue(Box::new(q.optimized())),
+            MatchQuery::WithChildren(q, n) => MatchQuery::WithChildren(Box::new(q.optimized()), n),
+            q => q,
+        }
+    }
+
+    fn always_false() -> MatchQuery {
+        MatchQuery::Not(Box::new(MatchQuery::Idle))
+    }
+
+    fn is_constant(&self, value: bool) -> bool {
+        match self {
+            MatchQuery::Idle => value,
+            MatchQuery::Not(q) => q.is_constant(!value),
+            _ => false,
+        }
+    }
+
+    fn optimized_junction(operands: Vec<MatchQuery>, is_and: bool) -> MatchQuery {
+        // for `And` true operands are neutral and a false one ends the evaluation, for `Or` vice versa
+        let (neutral, absorbing) = (is_and, !is_and);
+        let mut flat = Vec::with_capacity(operands.len());
+        for q in operands.into_iter().map(MatchQuery::optimized) {
+            match q {
+                MatchQuery::And(v) if is_and => flat.extend(v),
+                MatchQuery::Or(v) if !is_and => flat.extend(v),
+                q => flat.push(q),
+            }
+        }
+        let mut operands = Vec::with_capacity(flat.len());
+        for q in flat {
+            if q.is_constant(neutral) {
+                continue;
+            }
+            let stop = q.is_constant(absorbing);
+            operands.push(q);
+            if stop {
+                break;
+            }
+        }
+        if !operands.iter().any(MatchQuery::controls_flow) {
+            if operands.last().is_some_and(|q| q.is_constant(absorbing)) {
+                return operands.pop().unwrap();
+            }
+            operands.sort_by_key(MatchQuery::cost);
+        }
+        match operands.len() {
+            0 if is_and => MatchQuery::Idle,
+            0 => Self::always_false(),
+            1 => operands.pop().unwrap(),
+            _ if is_and => MatchQuery::And(operands),
+            _ => MatchQuery::Or(operands),
+        }
+    }
+
+    /// Whether the query may stop the evaluation of the enclosing query.
+    ///
+    fn controls_flow(&self) -> bool {
+        match self {
+            MatchQuery::StopIfFalse(_) | MatchQuery::StopIfTrue(_) => true,
+            MatchQuery::And(v) | MatchQuery::Or(v) => v.iter().any(MatchQuery::controls_flow),
+            MatchQuery::Not(q) => q.controls_flow(),
+            _ => false,
+        }
+    }
+
+    /// The relative cost of the query execution.
+    ///
+    fn cost(&self) -> u32 {
+        match self {
+            MatchQuery::Idle => 0,
+            MatchQuery::And(v) | MatchQuery::Or(v) => v.iter().map(MatchQuery::cost).sum(),
+            MatchQuery::Not(q) | MatchQuery::StopIfFalse(q) | MatchQuery::StopIfTrue(q) => q.cost(),
+            MatchQuery::WithChildren(q, _) => 50 + q.cost(),
+            MatchQuery::AttributesJMESQuery(_)
+            | MatchQuery::FrameAttributesJMESQuery(_)
+            | MatchQuery::EvalExpr(_) => 100,
+            MatchQuery::ParentId(_)
+            | MatchQuery::ParentNamespace(_)
+            | MatchQuery::ParentLabel(_)
+            | MatchQuery::FrameSourceId(_)
+            | MatchQuery::FrameIsKeyFrame
+            | MatchQuery::FrameTranscodingIsCopy
+            | MatchQuery::FrameWidth(_)
+            | MatchQuery::FrameHeight(_)
+            | MatchQuery::FrameNoVideo
+            | MatchQuery::FrameAttributeExists(_, _)
+            | MatchQuery::FrameAttributesEmpty => 5,
+            MatchQuery::BoxMetric { .. }
+            | MatchQuery::TrackBoxMetric { .. }
+            | MatchQuery::BoxWithinPolygon(_)
+            | MatchQuery::BoxIntersectsPolygon(_)
+            | MatchQuery::BoxCenterDistanceToPoint { .. }
+            | MatchQuery::BoxCrossesSegment { .. }
+            | MatchQuery::TrackBoxWithinPolygon(_)
+            | MatchQuery::TrackSpeed(_)
+            | MatchQuery::TrackDirection(_)
+            | MatchQuery::KeyPointDefined(_)
+            | MatchQuery::KeyPointConfidence(_, _)
+            | MatchQuery::AttributeExists(_, _)
+            | MatchQuery::AttributeFloat(_, _, _)
+            | MatchQuery::AttributeInt(_, _, _)
+            | MatchQuery::AttributeString(_, _, _) => 3,
+            _ => 1,
+        }
+    }
 }
 
 pub fn filter(objs: &[BorrowedVideoObject], query: &MatchQuery) -> Vec<BorrowedVideoObject> {
@@ -802,6 +1154,13 @@ where
     StringExpression::EndsWith(v.into())
 }
 
+pub fn matches<T>(v: T) -> anyhow::Result<StringExpression>
+where
+    T: Into<String>,
+{
+    Ok(StringExpression::Matches(v.into().try_into()?))
+}
+
 #[macro_export]
 macro_rules! query_not {
     ($arg:expr) => {{
@@ -839,7 +1198,9 @@ mod tests {
     use crate::eval_resolvers::register_env_resolver;
     use crate::match_query::MatchQuery::*;
     use crate::primitives::attribute_value::AttributeValue;
+    use crate::primitives::keypoints::{KeyPoint, KeyPoints};
     use crate::primitives::object::IdCollisionResolutionPolicy;
+    use crate::primitives::track_history::TrackHistory;
     use crate::test::{gen_empty_frame, gen_frame, gen_object, s};
 
     #[test]
@@ -1115,6 +1476,30 @@ mod tests {
             one_of_q.execute(&"random".to_string(), &mut ()),
             ControlFlow::Continue(false)
         ));
+
+        let matches_q: SE = matches("^person_(adult|child)$").unwrap();
+        assert!(matches!(
+            matches_q.execute("person_child", &mut ()),
+            ControlFlow::Continue(true)
+        ));
+        assert!(matches!(
+            matches_q.execute("person_adult_2", &mut ()),
+            ControlFlow::Continue(false)
+        ));
+        assert!(matches!(
+            matches("adult").unwrap().execute("person_adult_2", &mut ()),
+            ControlFlow::Continue(true)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        let error = matches("person_(adult").unwrap_err();
+        assert!(error.to_string().contains("person_(adult"));
+        let json = MatchQuery::Label(matches("^person$").unwrap()).to_json();
+        assert!(serde_json::from_str::<MatchQuery>(&json).is_ok());
+        let json = json.replace("^person$", "person_(adult");
+        assert!(serde_json::from_str::<MatchQuery>(&json).is_err());
     }
 
     #[test]
@@ -1470,6 +1855,211 @@ mod tests {
         ));
     }
 
+    fn area(x1: f32, y1: f32, x2: f32, y2: f32) -> PolygonalArea {
+        PolygonalArea::new(
+            vec![
+                Point::new(x1, y1),
+                Point::new(x2, y1),
+                Point::new(x2, y2),
+                Point::new(x1, y2),
+            ],
+            None,
+        )
+    }
+
+    #[test]
+    fn test_polygon_ops() {
+        // the detection box of gen_object(1) spans (-4, -8) - (6, 12)
+        let o = gen_object(1);
+        let ok =
+            |q: MatchQuery| matches!(q.execute_with_new_context(&o), ControlFlow::Continue(true));
+        assert!(ok(BoxWithinPolygon(area(-10.0, -10.0, 10.0, 20.0))));
+        assert!(!ok(BoxWithinPolygon(area(0.0, 0.0, 50.0, 50.0))));
+        assert!(ok(BoxIntersectsPolygon(area(0.0, 0.0, 50.0, 50.0))));
+        assert!(!ok(BoxIntersectsPolygon(area(20.0, 20.0, 50.0, 50.0))));
+        // the track box spans (95, 190) - (105, 210)
+        assert!(ok(TrackBoxWithinPolygon(area(90.0, 180.0, 110.0, 220.0))));
+        assert!(!ok(TrackBoxWithinPolygon(area(-10.0, -10.0, 10.0, 20.0))));
+
+        // the area is restored from JSON without the cached polygon
+        let q = MatchQuery::from_json(&BoxWithinPolygon(area(-10.0, -10.0, 10.0, 20.0)).to_json())
+            .unwrap();
+        assert!(ok(q));
+    }
+
+    #[test]
+    fn test_track_history_ops() {
+        let mut o = gen_object(1);
+        let ok = |o: &VideoObject, q: MatchQuery| {
+            matches!(q.execute_with_new_context(o), ControlFlow::Continue(true))
+        };
+        assert!(!ok(&o, TrackAge(ge(0))));
+        assert!(!ok(&o, TrackSpeed(ge(0.0))));
+
+        let mut history = TrackHistory::new(5);
+        history.push(0, &RBBox::new(0.0, 0.0, 10.0, 10.0, None));
+        o.set_track_history(Some(history.clone()));
+        assert!(ok(&o, TrackAge(eq(1))));
+        assert!(!ok(&o, TrackSpeed(ge(0.0))));
+
+        history.push(1, &RBBox::new(0.0, 4.0, 10.0, 10.0, None));
+        history.push(2, &RBBox::new(0.0, 8.0, 10.0, 10.0, None));
+        o.set_track_history(Some(history));
+        assert!(ok(&o, TrackAge(eq(3))));
+        assert!(ok(&o, TrackSpeed(eq(4.0))));
+        assert!(ok(&o, TrackDirection(between(80.0, 100.0))));
+        assert!(!ok(&o, TrackDirection(between(-100.0, -80.0))));
+
+        o.clear_track_info();
+        assert!(!ok(&o, TrackAge(ge(0))));
+    }
+
+    #[test]
+    fn test_keypoint_ops() {
+        let mut o = gen_object(1);
+        let ok = |o: &VideoObject, q: MatchQuery| {
+            matches!(q.execute_with_new_context(o), ControlFlow::Continue(true))
+        };
+        assert!(!ok(&o, KeyPointDefined(s("nose"))));
+
+        let keypoints = KeyPoints::new(
+            vec![
+                KeyPoint::new("nose", 1.0, 2.0, Some(0.8)),
+                KeyPoint::new("left_eye", 0.0, 1.0, None),
+            ],
+            vec![(0, 1)],
+        )
+        .unwrap();
+        o.set_keypoints(Some(keypoints));
+        assert!(ok(&o, KeyPointDefined(s("nose"))));
+        assert!(!ok(&o, KeyPointDefined(s("mouth"))));
+        assert!(ok(&o, KeyPointConfidence(s("nose"), gt(0.5))));
+        assert!(!ok(&o, KeyPointConfidence(s("nose"), gt(0.9))));
+        assert!(!ok(&o, KeyPointConfidence(s("left_eye"), ge(0.0))));
+    }
+
+    #[test]
+    fn test_point_and_segment_ops() {
+        // the detection box of gen_object(1) is centered at (1, 2) and spans (-4, -8) - (6, 12)
+        let o = gen_object(1);
+        let ok =
+            |q: MatchQuery| matches!(q.execute_with_new_context(&o), ControlFlow::Continue(true));
+        let distance_to =
+            |x: f32, y: f32, threshold_expr: FloatExpression| BoxCenterDistanceToPoint {
+                point: Point::new(x, y),
+                threshold_expr,
+            };
+        assert!(ok(distance_to(4.0, 6.0, eq(5.0))));
+        assert!(ok(distance_to(1.0, 2.0, lt(0.1))));
+        assert!(!ok(distance_to(100.0, 2.0, lt(50.0))));
+
+        let crosses = |x1: f32, y1: f32, x2: f32, y2: f32| BoxCrossesSegment {
+            segment: Segment::new(Point::new(x1, y1), Point::new(x2, y2)),
+        };
+        assert!(ok(crosses(-10.0, 0.0, 10.0, 0.0)));
+        assert!(ok(crosses(0.0, 0.0, 1.0, 1.0)));
+        assert!(!ok(crosses(-10.0, 20.0, 10.0, 20.0)));
+
+        let q = MatchQuery::from_json(&crosses(-10.0, 0.0, 10.0, 0.0).to_json()).unwrap();
+        assert!(ok(q));
+    }
+
+    #[test]
+    fn test_attribute_value_ops() {
+        let mut o = gen_object(1);
+        o.set_persistent_attribute(
+            "classifier",
+            "age",
+            &None,
+            false,
+            vec![AttributeValue::integer(32, None)],
+        );
+        o.set_persistent_attribute(
+            "classifier",
+            "scores",
+            &None,
+            false,
+            vec![AttributeValue::float_vector(vec![0.1, 0.8], None)],
+        );
+        o.set_persistent_attribute(
+            "lpr",
+            "plate",
+            &None,
+            false,
+            vec![
+                AttributeValue::string("A123BC", None),
+                AttributeValue::string("A128BC", None),
+            ],
+        );
+        let ok =
+            |q: MatchQuery| matches!(q.execute_with_new_context(&o), ControlFlow::Continue(true));
+        assert!(ok(AttributeInt(s("classifier"), s("age"), gt(30))));
+        assert!(!ok(AttributeInt(s("classifier"), s("age"), lt(30))));
+        assert!(ok(AttributeFloat(s("classifier"), s("scores"), gt(0.5))));
+        assert!(!ok(AttributeFloat(s("classifier"), s("scores"), gt(0.9))));
+        assert!(ok(AttributeString(s("lpr"), s("plate"), eq("A128BC"))));
+        assert!(!ok(AttributeString(s("lpr"), s("plate"), eq("B000BC"))));
+        // the value type must match
+        assert!(!ok(AttributeString(s("classifier"), s("age"), eq("32"))));
+        assert!(!ok(AttributeInt(s("unknown"), s("age"), gt(0))));
+    }
+
+    #[test]
+    fn test_optimize() {
+        let jmes = AttributesJMESQuery(s("[?(name=='test')]"));
+        let q = query_and![
+            jmes.clone(),
+            Idle,
+            query_and![Label(eq("face")), query_not!(query_not!(Id(eq(1))))],
+        ];
+        assert_eq!(
+            q.optimize().to_json(),
+            query_and![Label(eq("face")), Id(eq(1)), jmes.clone()].to_json()
+        );
+
+        // the operands after an absorbing constant are never evaluated
+        let q = query_or![Id(eq(1)), Idle, jmes.clone()];
+        assert_eq!(q.optimize().to_json(), Idle.to_json());
+        let q = query_and![Id(eq(1)), query_not!(Idle), jmes.clone()];
+        assert_eq!(q.optimize().to_json(), query_not!(Idle).to_json());
+        assert_eq!(
+            query_or![query_not!(Idle)].optimize().to_json(),
+            query_not!(Idle).to_json()
+        );
+
+        // the operands controlling the flow keep their order
+        let q = query_and![jmes.clone(), query_stop_if_false!(Id(eq(1))), Idle];
+        assert_eq!(
+            q.optimize().to_json(),
+            query_and![jmes, query_stop_if_false!(Id(eq(1)))].to_json()
+        );
+    }
+
+    #[test]
+    fn test_optimize_preserves_results() {
+        let f = gen_frame();
+        let q = query_or![
+            query_and![
+                Namespace(eq("test2")),
+                Idle,
+                query_not!(query_not!(Label(eq("test"))))
+            ],
+            query_and![FrameWidth(eq(1280)), ParentDefined, query_not!(Idle)],
+            query_or![Id(eq(0)), query_not!(Idle)],
+        ];
+        let ids = |q: &MatchQuery| {
+            let mut ids = f
+                .access_objects(q)
+                .iter()
+                .map(|o| o.get_id())
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(&q), vec![0, 1]);
+        assert_eq!(ids(&q), ids(&q.optimize()));
+    }
+
     #[test]
     fn test_frame_ops() {
         let f = gen_frame();
@@ -0,0 +1,196 @@
+use super::{MatchQuery, VideoObjectsProxyBatch};
+use crate::primitives::object::{BorrowedVideoObject, ObjectAccess, ObjectOperations};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::ControlFlow;
+
+/// Calls `f` for every object matching the query, honoring `StopIfFalse`/`StopIfTrue` the same
+/// way [`super::filter`] does.
+///
+fn for_each_matching<F>(objs: &[BorrowedVideoObject], query: &MatchQuery, mut f: F)
+where
+    F: FnMut(&BorrowedVideoObject),
+{
+    for o in objs {
+        match o.with_object_ref(|o| query.execute_with_new_context(o)) {
+            ControlFlow::Continue(true) => f(o),
+            ControlFlow::Continue(false) => continue,
+            ControlFlow::Break(matched) => {
+                if matched {
+                    f(o);
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// Min, max and average of a value over a set of objects.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ValueStats {
+    /// The number of the objects having the value.
+    pub count: usize,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub sum: f64,
+}
+
+impl ValueStats {
+    fn add(&mut self, v: f32) {
+        self.count += 1;
+        self.min = Some(self.min.map_or(v, |m| m.min(v)));
+        self.max = Some(self.max.map_or(v, |m| m.max(v)));
+        self.sum += v as f64;
+    }
+
+    pub fn avg(&self) -> Option<f32> {
+        (self.count > 0).then(|| (self.sum / self.count as f64) as f32)
+    }
+}
+
+/// The statistics of the objects matching a query.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ObjectStats {
+    pub count: usize,
+    /// Computed over the objects with the confidence defined.
+    pub confidence: ValueStats,
+    /// The detection box area.
+    pub area: ValueStats,
+}
+
+/// Counts the objects matching the query without collecting them.
+///
+pub fn count(objs: &[BorrowedVideoObject], query: &MatchQuery) -> usize {
+    let mut count = 0;
+    for_each_matching(objs, query, |_| count += 1);
+    count
+}
+
+/// Groups the objects by the key, e.g. `|o| o.get_label()`.
+///
+pub fn group_by<K, F>(
+    objs: &[BorrowedVideoObject],
+    key_fn: F,
+) -> HashMap<K, Vec<BorrowedVideoObject>>
+where
+    K: Eq + Hash,
+    F: Fn(&BorrowedVideoObject) -> K,
+{
+    let mut groups: HashMap<K, Vec<BorrowedVideoObject>> = HashMap::new();
+    for o in objs {
+        groups.entry(key_fn(o)).or_default().push(o.clone());
+    }
+    groups
+}
+
+/// Computes the confidence and the detection box area statistics of the objects matching the
+/// query.
+///
+pub fn stats(objs: &[BorrowedVideoObject], query: &MatchQuery) -> ObjectStats {
+    let mut stats = ObjectStats::default();
+    for_each_matching(objs, query, |o| {
+        stats.count += 1;
+        if let Some(c) = o.get_confidence() {
+            stats.confidence.add(c);
+        }
+        stats.area.add(o.get_detection_box().get_area());
+    });
+    stats
+}
+
+pub fn batch_count(batch: &VideoObjectsProxyBatch, query: &MatchQuery) -> HashMap<i64, usize> {
+    batch
+        .iter()
+        .map(|(id, objs)| (*id, count(objs, query)))
+        .collect()
+}
+
+pub fn batch_group_by<K, F>(
+    batch: &VideoObjectsProxyBatch,
+    key_fn: F,
+) -> HashMap<i64, HashMap<K, Vec<BorrowedVideoObject>>>
+where
+    K: Eq + Hash,
+    F: Fn(&BorrowedVideoObject) -> K,
+{
+    batch
+        .iter()
+        .map(|(id, objs)| (*id, group_by(objs, &key_fn)))
+        .collect()
+}
+
+pub fn batch_stats(
+    batch: &VideoObjectsProxyBatch,
+    query: &MatchQuery,
+) -> HashMap<i64, ObjectStats> {
+    batch
+        .iter()
+        .map(|(id, objs)| (*id, stats(objs, query)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_query::MatchQuery::*;
+    use crate::match_query::{eq, filter, gt, stop_if_true};
+    use crate::test::gen_frame;
+
+    #[test]
+    fn test_count() {
+        let frame = gen_frame();
+        let objs = frame.get_all_objects();
+        for q in [
+            Idle,
+            Namespace(eq("test2")),
+            Id(gt(5)),
+            stop_if_true!(Id(eq(1))),
+        ] {
+            assert_eq!(count(&objs, &q), filter(&objs, &q).len());
+        }
+    }
+
+    #[test]
+    fn test_group_by() {
+        let frame = gen_frame();
+        let groups = group_by(&frame.get_all_objects(), |o| o.get_namespace());
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["test"].len(), 1);
+        assert_eq!(groups["test2"].len(), 2);
+    }
+
+    #[test]
+    fn test_stats() {
+        let frame = gen_frame();
+        let objs = frame.get_all_objects();
+        let s = stats(&objs, &Namespace(eq("test2")));
+        assert_eq!(s.count, 2);
+        assert_eq!(s.area.count, 2);
+        let areas = filter(&objs, &Namespace(eq("test2")))
+            .iter()
+            .map(|o| o.get_detection_box().get_area())
+            .collect::<Vec<_>>();
+        let min = areas.iter().cloned().fold(f32::MAX, f32::min);
+        assert_eq!(s.area.min, Some(min));
+        assert!(s.area.avg().unwrap() >= min);
+
+        let none = stats(&objs, &Id(gt(10)));
+        assert_eq!(none.count, 0);
+        assert_eq!(none.area.avg(), None);
+    }
+
+    #[test]
+    fn test_batch() {
+        let frame = gen_frame();
+        let mut batch = VideoObjectsProxyBatch::new();
+        batch.insert(1, frame.get_all_objects());
+        batch.insert(2, Vec::new());
+        let counts = batch_count(&batch, &Idle);
+        assert_eq!(counts[&1], 3);
+        assert_eq!(counts[&2], 0);
+        assert_eq!(batch_stats(&batch, &Idle)[&1].count, 3);
+        assert_eq!(batch_group_by(&batch, |o| o.get_label())[&2].len(), 0);
+    }
+}
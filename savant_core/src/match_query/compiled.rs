@@ -0,0 +1,212 @@
+use super::{
+    frame_attributes_json, jmes_matches, new_object_context, object_attributes_json,
+    ExecutableMatchQuery, IntExpression, MatchQuery,
+};
+use crate::eval_context::ObjectContext;
+use crate::primitives::object::private::{SealedWithFrame, SealedWithParent};
+use crate::primitives::object::{BorrowedVideoObject, ObjectAccess, VideoObject};
+use crate::utils::iter::{
+    all_with_control_flow, any_with_control_flow, fiter_map_with_control_flow,
+    partition_with_control_flow,
+};
+use evalexpr::build_operator_tree;
+use std::fmt;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+#[derive(Clone)]
+enum CompiledNode {
+    And(Vec<CompiledNode>),
+    Or(Vec<CompiledNode>),
+    Not(Box<CompiledNode>),
+    StopIfFalse(Box<CompiledNode>),
+    StopIfTrue(Box<CompiledNode>),
+    WithChildren(Box<CompiledNode>, IntExpression),
+    AttributesJMESQuery(Arc<jmespath::Expression<'static>>),
+    FrameAttributesJMESQuery(Arc<jmespath::Expression<'static>>),
+    EvalExpr(Arc<evalexpr::Node>),
+    /// The query which does not need compilation.
+    Query(MatchQuery),
+}
+
+impl CompiledNode {
+    fn new(query: &MatchQuery) -> anyhow::Result<Self> {
+        let compile_all =
+            |v: &[MatchQuery]| -> anyhow::Result<Vec<Self>> { v.iter().map(Self::new).collect() };
+        Ok(match query {
+            MatchQuery::And(v) => Self::And(compile_all(v)?),
+            MatchQuery::Or(v) => Self::Or(compile_all(v)?),
+            MatchQuery::Not(q) => Self::Not(Box::new(Self::new(q)?)),
+            MatchQuery::StopIfFalse(q) => Self::StopIfFalse(Box::new(Self::new(q)?)),
+            MatchQuery::StopIfTrue(q) => Self::StopIfTrue(Box::new(Self::new(q)?)),
+            MatchQuery::WithChildren(q, n) => {
+                Self::WithChildren(Box::new(Self::new(q)?), n.clone())
+            }
+            MatchQuery::AttributesJMESQuery(x) => {
+                Self::AttributesJMESQuery(Arc::new(jmespath::compile(x)?))
+            }
+            MatchQuery::FrameAttributesJMESQuery(x) => {
+                Self::FrameAttributesJMESQuery(Arc::new(jmespath::compile(x)?))
+            }
+            MatchQuery::EvalExpr(x) => Self::EvalExpr(Arc::new(build_operator_tree(x)?)),
+            q => Self::Query(q.clone()),
+        })
+    }
+
+    fn count_matching(&self, objs: &[BorrowedVideoObject]) -> usize {
+        fiter_map_with_control_flow(objs.iter(), |o| {
+            o.with_object_ref(|o| self.execute(o, &mut new_object_context(o)))
+        })
+        .len()
+    }
+}
+
+impl ExecutableMatchQuery<&VideoObject, ObjectContext<'_>> for CompiledNode {
+    fn execute(&self, o: &VideoObject, ctx: &mut ObjectContext) -> ControlFlow<bool, bool> {
+        match self {
+            CompiledNode::And(v) => all_with_control_flow(v.iter(), |x| x.execute(o, ctx)),
+            CompiledNode::Or(v) => any_with_control_flow(v.iter(), |x| x.execute(o, ctx)),
+            CompiledNode::Not(x) => match x.execute(o, ctx) {
+                ControlFlow::Continue(x) => ControlFlow::Continue(!x),
+                ControlFlow::Break(x) => ControlFlow::Break(!x),
+            },
+            CompiledNode::StopIfFalse(x) => match x.execute(o, ctx) {
+                ControlFlow::Continue(true) => ControlFlow::Continue(true),
+                ControlFlow::Continue(false) => ControlFlow::Break(false),
+                ControlFlow::Break(x) => ControlFlow::Break(x),
+            },
+            CompiledNode::StopIfTrue(x) => match x.execute(o, ctx) {
+                ControlFlow::Continue(true) => ControlFlow::Break(true),
+                ControlFlow::Continue(false) => ControlFlow::Continue(false),
+                ControlFlow::Break(x) => ControlFlow::Break(x),
+            },
+            CompiledNode::WithChildren(q, n) => {
+                let v = q.count_matching(&o.get_children()) as i64;
+                n.execute(&v, &mut ())
+            }
+            CompiledNode::AttributesJMESQuery(filter) => {
+                ControlFlow::Continue(jmes_matches(filter, &object_attributes_json(o)))
+            }
+            CompiledNode::FrameAttributesJMESQuery(filter) => match o.get_frame() {
+                Some(frame) => {
+                    ControlFlow::Continue(jmes_matches(filter, &frame_attributes_json(&frame)))
+                }
+                None => ControlFlow::Continue(false),
+            },
+            CompiledNode::EvalExpr(expr) => {
+                ControlFlow::Continue(expr.eval_boolean_with_context_mut(ctx).unwrap())
+            }
+            CompiledNode::Query(q) => q.execute(o, ctx),
+        }
+    }
+}
+
+/// The query prepared for repeated execution: JMESPath filters and eval expressions are parsed
+/// once, when the query is compiled, instead of being looked up in the global compiled
+/// expression caches for every object. Obtained with [`MatchQuery::compile`].
+///
+#[derive(Clone)]
+pub struct CompiledQuery {
+    query: MatchQuery,
+    root: CompiledNode,
+}
+
+impl fmt::Debug for CompiledQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompiledQuery")
+            .field("query", &self.query)
+            .finish()
+    }
+}
+
+impl CompiledQuery {
+    pub(super) fn new(query: &MatchQuery) -> anyhow::Result<Self> {
+        Ok(Self {
+            query: query.clone(),
+            root: CompiledNode::new(query)?,
+        })
+    }
+
+    /// The query the compiled one was built from.
+    ///
+    pub fn get_query(&self) -> &MatchQuery {
+        &self.query
+    }
+
+    pub fn execute_with_new_context(&self, o: &VideoObject) -> ControlFlow<bool, bool> {
+        self.root.execute(o, &mut new_object_context(o))
+    }
+
+    pub fn filter(&self, objs: &[BorrowedVideoObject]) -> Vec<BorrowedVideoObject> {
+        fiter_map_with_control_flow(objs.iter(), |o| {
+            o.with_object_ref(|o| self.execute_with_new_context(o))
+        })
+        .into_iter()
+        .cloned()
+        .collect()
+    }
+
+    pub fn partition(
+        &self,
+        objs: &[BorrowedVideoObject],
+    ) -> (Vec<BorrowedVideoObject>, Vec<BorrowedVideoObject>) {
+        let (a, b) = partition_with_control_flow(objs.iter(), |o| {
+            o.with_object_ref(|o| self.execute_with_new_context(o))
+        });
+        (
+            a.into_iter().cloned().collect(),
+            b.into_iter().cloned().collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::match_query::MatchQuery::*;
+    use crate::match_query::{eq, filter, gt, partition, MatchQuery};
+    use crate::primitives::object::ObjectOperations;
+    use crate::test::gen_frame;
+
+    fn ids(objs: &[crate::primitives::object::BorrowedVideoObject]) -> Vec<i64> {
+        let mut ids = objs.iter().map(|o| o.get_id()).collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_compiled_matches_interpreted() -> anyhow::Result<()> {
+        let frame = gen_frame();
+        let objects = frame.get_all_objects();
+        let queries = vec![
+            Idle,
+            EvalExpr("id == 1 || label == \"test2\"".to_string()),
+            AttributesJMESQuery("[?(name=='test')]".to_string()),
+            FrameAttributesJMESQuery("[?(namespace=='system')]".to_string()),
+            And(vec![Namespace(eq("test2")), Not(Box::new(Id(eq(2))))]),
+            Or(vec![
+                StopIfTrue(Box::new(Id(eq(0)))),
+                EvalExpr("parent.id == 0".to_string()),
+            ]),
+            WithChildren(Box::new(EvalExpr("id > 0".to_string())), gt(1)),
+        ];
+        for q in queries {
+            let compiled = q.compile()?;
+            assert_eq!(compiled.get_query().to_json(), q.to_json());
+            assert_eq!(ids(&compiled.filter(&objects)), ids(&filter(&objects, &q)));
+            let (a, b) = compiled.partition(&objects);
+            let (c, d) = partition(&objects, &q);
+            assert_eq!((ids(&a), ids(&b)), (ids(&c), ids(&d)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        assert!(EvalExpr("id ==".to_string()).compile().is_err());
+        assert!(
+            MatchQuery::Not(Box::new(AttributesJMESQuery("[?(".to_string())))
+                .compile()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use super::MatchQuery;
+use crate::primitives::object::{BorrowedVideoObject, ObjectAccess};
+use crate::utils::iter::{fiter_map_with_control_flow, partition_with_control_flow};
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+lazy_static! {
+    static ref SHARED_POOLS: Mutex<HashMap<usize, Arc<ThreadPool>>> = Mutex::new(HashMap::new());
+}
+
+/// Returns the pool with the number of threads shared by all the callers, the pool is created
+/// on the first request and lives until the process exits.
+///
+pub fn shared_pool(num_threads: usize) -> anyhow::Result<Arc<ThreadPool>> {
+    if num_threads == 0 {
+        anyhow::bail!("The number of threads must be positive");
+    }
+    let mut pools = SHARED_POOLS.lock();
+    if let Some(pool) = pools.get(&num_threads) {
+        return Ok(pool.clone());
+    }
+    let pool = Arc::new(
+        ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(move |i| format!("savant-query-{}-{}", num_threads, i))
+            .build()?,
+    );
+    pools.insert(num_threads, pool.clone());
+    Ok(pool)
+}
+
+/// Executes the query against the chunks of the objects in parallel. The results are in the
+/// order of the objects, so `StopIfFalse`/`StopIfTrue` keep their sequential meaning: the objects
+/// following a stop are evaluated but discarded.
+///
+fn execute_chunks(
+    objs: &[BorrowedVideoObject],
+    query: &MatchQuery,
+    chunk_size: usize,
+) -> Vec<ControlFlow<bool, bool>> {
+    objs.par_chunks(chunk_size.max(1))
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|o| o.with_object_ref(|o| query.execute_with_new_context(o)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Same as [`super::filter`], but the objects are processed in chunks of `chunk_size` on the
+/// global rayon pool.
+///
+pub fn filter_parallel(
+    objs: &[BorrowedVideoObject],
+    query: &MatchQuery,
+    chunk_size: usize,
+) -> Vec<BorrowedVideoObject> {
+    let results = execute_chunks(objs, query, chunk_size);
+    fiter_map_with_control_flow(objs.iter().zip(results), |(_, r)| *r)
+        .into_iter()
+        .map(|(o, _)| o.clone())
+        .collect()
+}
+
+/// Same as [`super::partition`], but the objects are processed in chunks of `chunk_size` on
+/// the global rayon pool.
+///
+pub fn partition_parallel(
+    objs: &[BorrowedVideoObject],
+    query: &MatchQuery,
+    chunk_size: usize,
+) -> (Vec<BorrowedVideoObject>, Vec<BorrowedVideoObject>) {
+    let results = execute_chunks(objs, query, chunk_size);
+    let (a, b) = partition_with_control_flow(objs.iter().zip(results), |(_, r)| *r);
+    (
+        a.into_iter().map(|(o, _)| o.clone()).collect(),
+        b.into_iter().map(|(o, _)| o.clone()).collect(),
+    )
+}
+
+/// Runs [`filter_parallel`] on the dedicated pool instead of the global one.
+///
+pub fn filter_parallel_in(
+    pool: &ThreadPool,
+    objs: &[BorrowedVideoObject],
+    query: &MatchQuery,
+    chunk_size: usize,
+) -> Vec<BorrowedVideoObject> {
+    pool.install(|| filter_parallel(objs, query, chunk_size))
+}
+
+/// Runs [`partition_parallel`] on the dedicated pool instead of the global one.
+///
+pub fn partition_parallel_in(
+    pool: &ThreadPool,
+    objs: &[BorrowedVideoObject],
+    query: &MatchQuery,
+    chunk_size: usize,
+) -> (Vec<BorrowedVideoObject>, Vec<BorrowedVideoObject>) {
+    pool.install(|| partition_parallel(objs, query, chunk_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_query::MatchQuery::*;
+    use crate::match_query::{eq, filter, gt, partition, stop_if_false};
+    use crate::primitives::object::ObjectOperations;
+    use crate::test::fixture::FrameFixtureGenerator;
+
+    fn ids(objs: &[BorrowedVideoObject]) -> Vec<i64> {
+        objs.iter().map(|o| o.get_id()).collect()
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let frame = FrameFixtureGenerator::new(3)
+            .objects_per_frame(200, 200)
+            .unwrap()
+            .next_frame();
+        let objs = frame.get_all_objects();
+        let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        for q in [
+            Idle,
+            Label(eq("person")),
+            stop_if_false!(BoxWidth(gt(20.0))),
+        ] {
+            let expected = ids(&filter(&objs, &q));
+            assert_eq!(ids(&filter_parallel(&objs, &q, 16)), expected);
+            assert_eq!(ids(&filter_parallel_in(&pool, &objs, &q, 7)), expected);
+            let (a, b) = partition(&objs, &q);
+            let (c, d) = partition_parallel_in(&pool, &objs, &q, 0);
+            assert_eq!((ids(&a), ids(&b)), (ids(&c), ids(&d)));
+        }
+    }
+
+    #[test]
+    fn test_shared_pool() {
+        assert!(shared_pool(0).is_err());
+        let pool = shared_pool(3).unwrap();
+        assert_eq!(pool.current_num_threads(), 3);
+        assert!(Arc::ptr_eq(&pool, &shared_pool(3).unwrap()));
+    }
+}
@@ -0,0 +1,492 @@
+use super::{FloatExpression, IntExpression, MatchQuery, StringExpression};
+use anyhow::{anyhow, bail};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Str(String),
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 15] = [
+    "&&", "||", "==", "!=", ">=", "<=", ">", "<", "!", "(", ")", "[", "]", ",", "~",
+];
+
+fn tokenize(expr: &str) -> anyhow::Result<Vec<(usize, Token)>> {
+    let chars = expr.char_indices().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].1.is_ascii_alphanumeric() || "_.".contains(chars[i].1))
+            {
+                i += 1;
+            }
+            let ident = chars[start..i].iter().map(|(_, c)| c).collect();
+            tokens.push((pos, Token::Ident(ident)));
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(|n| n.1.is_ascii_digit()))
+        {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].1.is_ascii_digit() || chars[i].1 == '.') {
+                i += 1;
+            }
+            let number = chars[start..i].iter().map(|(_, c)| c).collect();
+            tokens.push((pos, Token::Number(number)));
+        } else if c == '\'' || c == '"' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => bail!("Unterminated string starting at {}", pos),
+                    Some((_, '\\')) if i + 1 < chars.len() => {
+                        value.push(chars[i + 1].1);
+                        i += 2;
+                    }
+                    Some((_, q)) if *q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some((_, ch)) => {
+                        value.push(*ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push((pos, Token::Str(value)));
+        } else {
+            let rest = &expr[pos..];
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| anyhow!("Unexpected character '{}' at {}", c, pos))?;
+            tokens.push((pos, Token::Op(op)));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+type Constructor = fn(Comparison) -> anyhow::Result<MatchQuery>;
+
+fn flag(name: &str) -> Option<MatchQuery> {
+    Some(match name {
+        "true" => MatchQuery::Idle,
+        "false" => MatchQuery::Not(Box::new(MatchQuery::Idle)),
+        "confidence.defined" => MatchQuery::ConfidenceDefined,
+        "track.defined" => MatchQuery::TrackDefined,
+        "track.bbox.angle.defined" => MatchQuery::TrackBoxAngleDefined,
+        "parent.defined" => MatchQuery::ParentDefined,
+        "bbox.angle.defined" => MatchQuery::BoxAngleDefined,
+        "attributes.empty" => MatchQuery::AttributesEmpty,
+        "frame.is_key_frame" => MatchQuery::FrameIsKeyFrame,
+        "frame.transcoding.is_copy" => MatchQuery::FrameTranscodingIsCopy,
+        "frame.no_video" => MatchQuery::FrameNoVideo,
+        "frame.attributes.empty" => MatchQuery::FrameAttributesEmpty,
+        _ => return None,
+    })
+}
+
+fn field(name: &str) -> Option<Constructor> {
+    let constructor: Constructor = match name {
+        "id" => |c| Ok(MatchQuery::Id(c.int()?)),
+        "namespace" => |c| Ok(MatchQuery::Namespace(c.string()?)),
+        "label" => |c| Ok(MatchQuery::Label(c.string()?)),
+        "confidence" => |c| Ok(MatchQuery::Confidence(c.float()?)),
+        "track.id" => |c| Ok(MatchQuery::TrackId(c.int()?)),
+        "track.bbox.xc" => |c| Ok(MatchQuery::TrackBoxXCenter(c.float()?)),
+        "track.bbox.yc" => |c| Ok(MatchQuery::TrackBoxYCenter(c.float()?)),
+        "track.bbox.width" => |c| Ok(MatchQuery::TrackBoxWidth(c.float()?)),
+        "track.bbox.height" => |c| Ok(MatchQuery::TrackBoxHeight(c.float()?)),
+        "track.bbox.area" => |c| Ok(MatchQuery::TrackBoxArea(c.float()?)),
+        "track.bbox.width_to_height_ratio" => {
+            |c| Ok(MatchQuery::TrackBoxWidthToHeightRatio(c.float()?))
+        }
+        "track.bbox.angle" => |c| Ok(MatchQuery::TrackBoxAngle(c.float()?)),
+        "track.speed" => |c| Ok(MatchQuery::TrackSpeed(c.float()?)),
+        "track.direction" => |c| Ok(MatchQuery::TrackDirection(c.float()?)),
+        "track.age" => |c| Ok(MatchQuery::TrackAge(c.int()?)),
+        "parent.id" => |c| Ok(MatchQuery::ParentId(c.int()?)),
+        "parent.namespace" => |c| Ok(MatchQuery::ParentNamespace(c.string()?)),
+        "parent.label" => |c| Ok(MatchQuery::ParentLabel(c.string()?)),
+        "bbox.xc" => |c| Ok(MatchQuery::BoxXCenter(c.float()?)),
+        "bbox.yc" => |c| Ok(MatchQuery::BoxYCenter(c.float()?)),
+        "bbox.width" => |c| Ok(MatchQuery::BoxWidth(c.float()?)),
+        "bbox.height" => |c| Ok(MatchQuery::BoxHeight(c.float()?)),
+        "bbox.area" => |c| Ok(MatchQuery::BoxArea(c.float()?)),
+        "bbox.width_to_height_ratio" => |c| Ok(MatchQuery::BoxWidthToHeightRatio(c.float()?)),
+        "bbox.angle" => |c| Ok(MatchQuery::BoxAngle(c.float()?)),
+        "frame.source_id" => |c| Ok(MatchQuery::FrameSourceId(c.string()?)),
+        "frame.width" => |c| Ok(MatchQuery::FrameWidth(c.int()?)),
+        "frame.height" => |c| Ok(MatchQuery::FrameHeight(c.int()?)),
+        _ => return None,
+    };
+    Some(constructor)
+}
+
+/// A parsed `field <op> value` comparison.
+///
+struct Comparison {
+    op: &'static str,
+    values: Vec<Token>,
+}
+
+impl Comparison {
+    fn number<T: std::str::FromStr>(t: &Token) -> anyhow::Result<T> {
+        match t {
+            Token::Number(n) => n.parse().map_err(|_| anyhow!("Invalid number {}", n)),
+            t => bail!("Expected a number, got {:?}", t),
+        }
+    }
+
+    fn int(self) -> anyhow::Result<IntExpression> {
+        let v = self
+            .values
+            .iter()
+            .map(Self::number::<i64>)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(match self.op {
+            "==" => IntExpression::EQ(v[0]),
+            "!=" => IntExpression::NE(v[0]),
+            "<" => IntExpression::LT(v[0]),
+            "<=" => IntExpression::LE(v[0]),
+            ">" => IntExpression::GT(v[0]),
+            ">=" => IntExpression::GE(v[0]),
+            "in" => IntExpression::OneOf(v),
+            "between" => IntExpression::Between(v[0], v[1]),
+            op => bail!("Operator {} is not applicable to integers", op),
+        })
+    }
+
+    fn float(self) -> anyhow::Result<FloatExpression> {
+        let v = self
+            .values
+            .iter()
+            .map(Self::number::<f32>)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(match self.op {
+            "==" => FloatExpression::EQ(v[0]),
+            "!=" => FloatExpression::NE(v[0]),
+            "<" => FloatExpression::LT(v[0]),
+            "<=" => FloatExpression::LE(v[0]),
+            ">" => FloatExpression::GT(v[0]),
+            ">=" => FloatExpression::GE(v[0]),
+            "in" => FloatExpression::OneOf(v),
+            "between" => FloatExpression::Between(v[0], v[1]),
+            op => bail!("Operator {} is not applicable to floats", op),
+        })
+    }
+
+    fn string(self) -> anyhow::Result<StringExpression> {
+        let mut v = self
+            .values
+            .into_iter()
+            .map(|t| match t {
+                Token::Str(s) => Ok(s),
+                t => bail!("Expected a string, got {:?}", t),
+            })
+            .collect::<anyhow::Result<Vec<String>>>()?;
+        if self.op == "in" {
+            return Ok(StringExpression::OneOf(v));
+        }
+        let s = v.swap_remove(0);
+        Ok(match self.op {
+            "==" => StringExpression::EQ(s),
+            "!=" => StringExpression::NE(s),
+            "~" => StringExpression::Matches(s.try_into()?),
+            "contains" => StringExpression::Contains(s),
+            "starts_with" => StringExpression::StartsWith(s),
+            "ends_with" => StringExpression::EndsWith(s),
+            op => bail!("Operator {} is not applicable to strings", op),
+        })
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.len, |(p, _)| *p)
+    }
+
+    fn advance(&mut self) -> anyhow::Result<Token> {
+        let t = self
+            .tokens
+            .get(self.pos)
+            .map(|(_, t)| t.clone())
+            .ok_or_else(|| anyhow!("Unexpected end of expression"))?;
+        self.pos += 1;
+        Ok(t)
+    }
+
+    fn accept(&mut self, op: &'static str) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: &'static str) -> anyhow::Result<()> {
+        if !self.accept(op) {
+            bail!("Expected '{}' at {}", op, self.position());
+        }
+        Ok(())
+    }
+
+    fn or(&mut self) -> anyhow::Result<MatchQuery> {
+        let mut operands = vec![self.and()?];
+        while self.accept("||") {
+            operands.push(self.and()?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.pop().unwrap()
+        } else {
+            MatchQuery::Or(operands)
+        })
+    }
+
+    fn and(&mut self) -> anyhow::Result<MatchQuery> {
+        let mut operands = vec![self.unary()?];
+        while self.accept("&&") {
+            operands.push(self.unary()?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.pop().unwrap()
+        } else {
+            MatchQuery::And(operands)
+        })
+    }
+
+    fn unary(&mut self) -> anyhow::Result<MatchQuery> {
+        if self.accept("!") {
+            return Ok(MatchQuery::Not(Box::new(self.unary()?)));
+        }
+        if self.accept("(") {
+            let q = self.or()?;
+            self.expect(")")?;
+            return Ok(q);
+        }
+        self.predicate()
+    }
+
+    fn string_args(&mut self) -> anyhow::Result<Vec<String>> {
+        self.expect("(")?;
+        let mut args = Vec::new();
+        loop {
+            match self.advance()? {
+                Token::Str(s) => args.push(s),
+                t => bail!("Expected a string argument, got {:?}", t),
+            }
+            if !self.accept(",") {
+                break;
+            }
+        }
+        self.expect(")")?;
+        Ok(args)
+    }
+
+    fn comparison(&mut self) -> anyhow::Result<Comparison> {
+        let position = self.position();
+        let op = match self.advance()? {
+            Token::Op(op @ ("==" | "!=" | "<" | "<=" | ">" | ">=" | "~")) => op,
+            Token::Ident(op) => match op.as_str() {
+                "in" => "in",
+                "between" => "between",
+                "contains" => "contains",
+                "starts_with" => "starts_with",
+                "ends_with" => "ends_with",
+                _ => bail!("Unknown operator {} at {}", op, position),
+            },
+            t => bail!("Expected an operator at {}, got {:?}", position, t),
+        };
+        let values = match op {
+            "in" | "between" => {
+                self.expect("[")?;
+                let mut values = vec![self.advance()?];
+                while self.accept(",") {
+                    values.push(self.advance()?);
+                }
+                self.expect("]")?;
+                if op == "between" && values.len() != 2 {
+                    bail!("Operator between requires two values at {}", position);
+                }
+                values
+            }
+            _ => vec![self.advance()?],
+        };
+        Ok(Comparison { op, values })
+    }
+
+    fn predicate(&mut self) -> anyhow::Result<MatchQuery> {
+        let position = self.position();
+        let name = match self.advance()? {
+            Token::Ident(name) => name,
+            t => bail!("Expected a field at {}, got {:?}", position, t),
+        };
+        let attribute = |args: Vec<String>| -> anyhow::Result<(String, String)> {
+            match <[String; 2]>::try_from(args) {
+                Ok([namespace, name]) => Ok((namespace, name)),
+                Err(_) => bail!("Attribute requires namespace and name at {}", position),
+            }
+        };
+        let wrap = |e: anyhow::Error| anyhow!("{} at {}", e, position);
+        match name.as_str() {
+            "attribute.exists" | "frame.attribute.exists" => {
+                let (namespace, attr) = attribute(self.string_args()?)?;
+                return Ok(if name == "attribute.exists" {
+                    MatchQuery::AttributeExists(namespace, attr)
+                } else {
+                    MatchQuery::FrameAttributeExists(namespace, attr)
+                });
+            }
+            "attribute.int" | "attribute.float" | "attribute.string" => {
+                let (namespace, attr) = attribute(self.string_args()?)?;
+                let c = self.comparison()?;
+                return match name.as_str() {
+                    "attribute.int" => Ok(MatchQuery::AttributeInt(
+                        namespace,
+                        attr,
+                        c.int().map_err(wrap)?,
+                    )),
+                    "attribute.float" => Ok(MatchQuery::AttributeFloat(
+                        namespace,
+                        attr,
+                        c.float().map_err(wrap)?,
+                    )),
+                    _ => Ok(MatchQuery::AttributeString(
+                        namespace,
+                        attr,
+                        c.string().map_err(wrap)?,
+                    )),
+                };
+            }
+            "keypoint.defined" | "keypoint.confidence" => {
+                let keypoint = match <[String; 1]>::try_from(self.string_args()?) {
+                    Ok([keypoint]) => keypoint,
+                    Err(_) => bail!("Keypoint requires the keypoint name at {}", position),
+                };
+                return if name == "keypoint.defined" {
+                    Ok(MatchQuery::KeyPointDefined(keypoint))
+                } else {
+                    let c = self.comparison()?;
+                    Ok(MatchQuery::KeyPointConfidence(
+                        keypoint,
+                        c.float().map_err(wrap)?,
+                    ))
+                };
+            }
+            _ => {}
+        }
+        if let Some(q) = flag(&name) {
+            return Ok(q);
+        }
+        let constructor =
+            field(&name).ok_or_else(|| anyhow!("Unknown field {} at {}", name, position))?;
+        constructor(self.comparison()?).map_err(wrap)
+    }
+}
+
+impl MatchQuery {
+    /// Parses the compact expression syntax, e.g.
+    /// `label == 'person' && confidence > 0.5 && bbox.area > 100`.
+    ///
+    /// The comparisons are `field <op> value` with `==`, `!=`, `<`, `<=`, `>`, `>=`,
+    /// `in [v1, v2, ...]` and `between [v1, v2]` applicable to the numeric fields, while the
+    /// string fields support `==`, `!=`, `in`, `contains`, `starts_with`, `ends_with` and `~`
+    /// (regular expression). The attributes are addressed as `attribute.exists('ns', 'name')`,
+    /// `attribute.int('ns', 'name') > 1` (also `attribute.float` and `attribute.string`), the
+    /// keypoints as `keypoint.defined('nose')` and `keypoint.confidence('nose') > 0.5`. The
+    /// flags like `track.defined` are used without a comparison. The comparisons are combined
+    /// with `!`, `&&`, `||` and parentheses.
+    ///
+    pub fn from_expr(expr: &str) -> anyhow::Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(expr)?,
+            pos: 0,
+            len: expr.len(),
+        };
+        let q = parser.or()?;
+        if parser.peek().is_some() {
+            bail!("Unexpected token at {}", parser.position());
+        }
+        Ok(q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::match_query::MatchQuery;
+    use crate::test::gen_frame;
+
+    fn query_json(expr: &str) -> String {
+        MatchQuery::from_expr(expr).unwrap().to_json()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            query_json("label == 'person' && confidence > 0.5 && bbox.area > 100"),
+            r#"{"and":[{"label":{"eq":"person"}},{"confidence":{"gt":0.5}},{"bbox.area":{"gt":100.0}}]}"#
+        );
+        assert_eq!(
+            query_json("!(id in [1, 2]) || namespace ~ \"^peo\" && track.defined"),
+            r#"{"or":[{"not":{"id":{"one_of":[1,2]}}},{"and":[{"namespace":{"matches":"^peo"}},"track.defined"]}]}"#
+        );
+        assert_eq!(
+            query_json("attribute.float('age', 'score') between [0.5, 1]"),
+            r#"{"attribute.float":["age","score",{"between":[0.5,1.0]}]}"#
+        );
+        assert_eq!(
+            query_json("frame.attribute.exists('meta', 'camera')"),
+            r#"{"frame.attribute.exists":["meta","camera"]}"#
+        );
+        assert_eq!(
+            query_json("keypoint.defined('nose') && keypoint.confidence('nose') > 0.5"),
+            r#"{"and":[{"keypoint.defined":"nose"},{"keypoint.confidence":["nose",{"gt":0.5}]}]}"#
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        for expr in [
+            "",
+            "label ==",
+            "label > 'a'",
+            "id == 1.5",
+            "unknown == 1",
+            "(id == 1",
+            "id == 1 id == 2",
+            "label == 'a",
+            "id between [1]",
+            "attribute.exists('ns')",
+            "keypoint.defined('ns', 'nose')",
+            "label ~ 'person_(adult'",
+        ] {
+            assert!(MatchQuery::from_expr(expr).is_err(), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_execute() {
+        let frame = gen_frame();
+        let q = MatchQuery::from_expr("namespace == 'test2' && parent.id == 0").unwrap();
+        assert_eq!(frame.access_objects(&q).len(), 2);
+    }
+}
@@ -0,0 +1,274 @@
+use super::{filter, new_object_context, ExecutableMatchQuery, MatchQuery};
+use crate::eval_context::ObjectContext;
+use crate::json_api::ToSerdeJsonValue;
+use crate::primitives::object::private::{SealedWithFrame, SealedWithParent};
+use crate::primitives::object::{ObjectOperations, VideoObject};
+use crate::primitives::WithAttributes;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::ops::ControlFlow;
+
+/// The record of a (sub-)query evaluation against an object.
+///
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryTrace {
+    /// The operator as named in JSON/YAML, e.g. `and` or `label`.
+    pub operator: String,
+    /// The serialized leaf query, not set for the combinators.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<Value>,
+    pub matched: bool,
+    /// Whether the query stopped the evaluation of the enclosing queries.
+    pub stopped: bool,
+    /// The object value the leaf query was evaluated against, when it is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    /// The traces of the evaluated operands; the operands skipped due to short-circuiting are
+    /// absent.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<QueryTrace>,
+}
+
+impl QueryTrace {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+fn operator_name(q: &MatchQuery) -> String {
+    match serde_json::to_value(q).unwrap() {
+        Value::String(s) => s,
+        Value::Object(m) => m.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+fn flow_result(res: ControlFlow<bool, bool>) -> (bool, bool) {
+    match res {
+        ControlFlow::Continue(v) => (v, false),
+        ControlFlow::Break(v) => (v, true),
+    }
+}
+
+impl MatchQuery {
+    /// Executes the query like [`MatchQuery::execute_with_new_context`] and records which
+    /// sub-queries matched together with the evaluated object values.
+    ///
+    pub fn execute_with_trace(&self, o: &VideoObject) -> (bool, QueryTrace) {
+        let (res, trace) = self.traced(o, &mut new_object_context(o));
+        (flow_result(res).0, trace)
+    }
+
+    fn traced(
+        &self,
+        o: &VideoObject,
+        ctx: &mut ObjectContext,
+    ) -> (ControlFlow<bool, bool>, QueryTrace) {
+        let mut children = Vec::new();
+        let mut traced_child = |q: &MatchQuery, ctx: &mut ObjectContext| {
+            let (res, trace) = q.traced(o, ctx);
+            children.push(trace);
+            res
+        };
+        let (res, is_leaf) = match self {
+            MatchQuery::And(v) => {
+                let mut res = ControlFlow::Continue(true);
+                for q in v {
+                    res = traced_child(q, ctx);
+                    if !matches!(res, ControlFlow::Continue(true)) {
+                        break;
+                    }
+                }
+                (res, false)
+            }
+            MatchQuery::Or(v) => {
+                let mut res = ControlFlow::Continue(false);
+                for q in v {
+                    res = traced_child(q, ctx);
+                    if !matches!(res, ControlFlow::Continue(false)) {
+                        break;
+                    }
+                }
+                (res, false)
+            }
+            MatchQuery::Not(q) => {
+                let res = match traced_child(q, ctx) {
+                    ControlFlow::Continue(x) => ControlFlow::Continue(!x),
+                    ControlFlow::Break(x) => ControlFlow::Break(!x),
+                };
+                (res, false)
+            }
+            MatchQuery::StopIfFalse(q) => {
+                let res = match traced_child(q, ctx) {
+                    ControlFlow::Continue(false) => ControlFlow::Break(false),
+                    res => res,
+                };
+                (res, false)
+            }
+            MatchQuery::StopIfTrue(q) => {
+                let res = match traced_child(q, ctx) {
+                    ControlFlow::Continue(true) => ControlFlow::Break(true),
+                    res => res,
+                };
+                (res, false)
+            }
+            q => (q.execute(o, ctx), true),
+        };
+        let (matched, stopped) = flow_result(res);
+        let trace = QueryTrace {
+            operator: operator_name(self),
+            query: is_leaf.then(|| serde_json::to_value(self).unwrap()),
+            matched,
+            stopped,
+            value: if is_leaf {
+                self.observed_value(o)
+            } else {
+                None
+            },
+            children,
+        };
+        (res, trace)
+    }
+
+    /// The object value the leaf query compares, if it has a single one.
+    ///
+    fn observed_value(&self, o: &VideoObject) -> Option<Value> {
+        let track_box = o.track_box.as_ref();
+        let bbox = &o.detection_box;
+        let attribute = |namespace: &str, name: &str| {
+            o.get_attribute(namespace, name)
+                .map(|a| a.to_serde_json_value())
+        };
+        match self {
+            MatchQuery::Id(_) => Some(json!(o.id)),
+            MatchQuery::Namespace(_) => Some(json!(o.namespace)),
+            MatchQuery::Label(_) => Some(json!(o.label)),
+            MatchQuery::Confidence(_) | MatchQuery::ConfidenceDefined => Some(json!(o.confidence)),
+            MatchQuery::TrackDefined | MatchQuery::TrackId(_) => Some(json!(o.track_id)),
+            MatchQuery::TrackBoxXCenter(_) => track_box.map(|t| json!(t.get_xc())),
+            MatchQuery::TrackBoxYCenter(_) => track_box.map(|t| json!(t.get_yc())),
+            MatchQuery::TrackBoxWidth(_) => track_box.map(|t| json!(t.get_width())),
+            MatchQuery::TrackBoxHeight(_) => track_box.map(|t| json!(t.get_height())),
+            MatchQuery::TrackBoxArea(_) => track_box.map(|t| json!(t.get_width() * t.get_height())),
+            MatchQuery::TrackBoxWidthToHeightRatio(_) => {
+                track_box.map(|t| json!(t.get_width_to_height_ratio()))
+            }
+            MatchQuery::TrackBoxAngle(_) | MatchQuery::TrackBoxAngleDefined => {
+                track_box.map(|t| json!(t.get_angle()))
+            }
+            MatchQuery::TrackSpeed(_) => o
+                .track_history
+                .as_ref()
+                .and_then(|h| h.speed())
+                .map(|v| json!(v)),
+            MatchQuery::TrackDirection(_) => o
+                .track_history
+                .as_ref()
+                .and_then(|h| h.direction())
+                .map(|v| json!(v)),
+            MatchQuery::TrackAge(_) => o
+                .track_history
+                .as_ref()
+                .map(|h| json!(h.get_observations())),
+            MatchQuery::KeyPointDefined(name) | MatchQuery::KeyPointConfidence(name, _) => o
+                .keypoints
+                .as_ref()
+                .and_then(|k| k.get(name))
+                .map(|p| json!(p)),
+            MatchQuery::ParentDefined | MatchQuery::ParentId(_) => Some(json!(o.parent_id)),
+            MatchQuery::ParentNamespace(_) => o.get_parent().map(|p| json!(p.get_namespace())),
+            MatchQuery::ParentLabel(_) => o.get_parent().map(|p| json!(p.get_label())),
+            MatchQuery::WithChildren(q, _) => Some(json!(filter(&o.get_children(), q).len())),
+            MatchQuery::BoxXCenter(_) => Some(json!(bbox.get_xc())),
+            MatchQuery::BoxYCenter(_) => Some(json!(bbox.get_yc())),
+            MatchQuery::BoxWidth(_) => Some(json!(bbox.get_width())),
+            MatchQuery::BoxHeight(_) => Some(json!(bbox.get_height())),
+            MatchQuery::BoxArea(_) => Some(json!(bbox.get_width() * bbox.get_height())),
+            MatchQuery::BoxWidthToHeightRatio(_) => Some(json!(bbox.get_width_to_height_ratio())),
+            MatchQuery::BoxAngle(_) | MatchQuery::BoxAngleDefined => Some(json!(bbox.get_angle())),
+            MatchQuery::BoxCenterDistanceToPoint { point, .. } => {
+                let dx = bbox.get_xc() - point.x;
+                let dy = bbox.get_yc() - point.y;
+                Some(json!(dx.hypot(dy)))
+            }
+            MatchQuery::AttributeExists(namespace, name)
+            | MatchQuery::AttributeFloat(namespace, name, _)
+            | MatchQuery::AttributeInt(namespace, name, _)
+            | MatchQuery::AttributeString(namespace, name, _) => attribute(namespace, name),
+            MatchQuery::FrameSourceId(_) => o.get_frame().map(|f| json!(f.get_source_id())),
+            MatchQuery::FrameWidth(_) => o.get_frame().map(|f| json!(f.get_width())),
+            MatchQuery::FrameHeight(_) => o.get_frame().map(|f| json!(f.get_height())),
+            MatchQuery::FrameIsKeyFrame => o.get_frame().map(|f| json!(f.get_keyframe())),
+            MatchQuery::FrameAttributeExists(namespace, name) => o
+                .get_frame()
+                .and_then(|f| f.get_attribute(namespace, name))
+                .map(|a| a.to_serde_json_value()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::match_query::MatchQuery::*;
+    use crate::match_query::{eq, gt, MatchQuery};
+    use crate::test::gen_object;
+
+    #[test]
+    fn test_trace() {
+        let o = gen_object(1);
+        let q = And(vec![
+            Label(eq("face")),
+            Or(vec![Confidence(gt(0.9)), Not(Box::new(Id(eq(2))))]),
+            Namespace(eq("other")),
+            Id(eq(1)),
+        ]);
+        let (matched, trace) = q.execute_with_trace(&o);
+        assert!(!matched);
+        assert_eq!(trace.operator, "and");
+        assert!(trace.query.is_none());
+        // the last operand is not evaluated
+        assert_eq!(trace.children.len(), 3);
+
+        let label = &trace.children[0];
+        assert!(label.matched);
+        assert_eq!(label.value, Some(serde_json::json!("face")));
+
+        let or = &trace.children[1];
+        assert!(or.matched);
+        assert_eq!(or.children.len(), 2);
+        assert!(!or.children[0].matched);
+        assert_eq!(or.children[0].value, Some(serde_json::json!(0.5)));
+        assert_eq!(or.children[1].children[0].operator, "id");
+
+        let namespace = &trace.children[2];
+        assert!(!namespace.matched);
+        assert_eq!(namespace.value, Some(serde_json::json!("peoplenet")));
+
+        let json: serde_json::Value = serde_json::from_str(&trace.to_json()).unwrap();
+        assert_eq!(json["children"][2]["query"]["namespace"]["eq"], "other");
+    }
+
+    #[test]
+    fn test_trace_stop() {
+        let o = gen_object(1);
+        let q = MatchQuery::Or(vec![StopIfTrue(Box::new(Id(eq(1)))), Idle]);
+        let (matched, trace) = q.execute_with_trace(&o);
+        assert!(matched);
+        assert!(trace.stopped);
+        assert_eq!(trace.children.len(), 1);
+        assert!(trace.children[0].stopped);
+        assert!(!trace.children[0].children[0].stopped);
+        assert_eq!(
+            matched,
+            matches!(
+                q.execute_with_new_context(&o),
+                std::ops::ControlFlow::Continue(true) | std::ops::ControlFlow::Break(true)
+            )
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub mod label_filter;
+pub mod validation;
 
 use crate::otlp::PropagatedContext;
 use crate::primitives::eos::EndOfStream;
@@ -12,6 +13,7 @@ use crate::protobuf::{deserialize, serialize};
 use crate::trace;
 use lazy_static::lazy_static;
 use lru::LruCache;
+use opentelemetry::Context;
 use parking_lot::{const_mutex, Mutex};
 
 lazy_static! {
@@ -228,6 +230,29 @@ impl Message {
     pub fn get_span_context(&self) -> &PropagatedContext {
         &self.meta.span_context
     }
+    pub fn attach_trace_context(&mut self, context: &Context) {
+        self.meta.span_context = PropagatedContext::inject(context);
+    }
+    /// Returns the context of the message, the context attached to the video frame when the
+    /// message has none.
+    ///
+    pub fn extract_trace_context(&self) -> Context {
+        self.trace_context()
+            .map(|context| context.extract())
+            .unwrap_or_default()
+    }
+    pub fn child_span(&self, name: &str) -> Context {
+        self.trace_context()
+            .map(|context| context.child_span(name))
+            .unwrap_or_default()
+    }
+    fn trace_context(&self) -> Option<PropagatedContext> {
+        if !self.meta.span_context.is_empty() {
+            return Some(self.meta.span_context.clone());
+        }
+        self.as_video_frame()
+            .and_then(|frame| frame.get_trace_context())
+    }
     pub fn is_unknown(&self) -> bool {
         matches!(self.payload, MessageEnvelope::Unknown(_))
     }
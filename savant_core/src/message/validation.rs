@@ -0,0 +1,346 @@
+use crate::message::Message;
+use crate::primitives::attribute_value::AttributeValue;
+use crate::primitives::frame::VideoFrameProxy;
+use crate::primitives::object::ObjectOperations;
+use crate::primitives::{RBBox, WithAttributes};
+use crate::trace;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::fmt;
+use std::num::NonZeroUsize;
+
+pub const VALIDATION_ATTRIBUTE_NAMESPACE: &str = "savant";
+pub const VALIDATION_ATTRIBUTE_NAME: &str = "validation_violations";
+
+const MAX_TRACKED_SOURCES: usize = 1024;
+const BBOX_EPSILON: f32 = 0.5;
+
+/// Defines how the reader treats semantically invalid frames.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Frames are not validated.
+    #[default]
+    Disabled,
+    /// Fixable violations are fixed, all violations are logged and annotated with a temporary
+    /// frame attribute (`savant/validation_violations`).
+    Lenient,
+    /// Frames with violations are rejected.
+    Strict,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    NonPositiveFrameDimensions {
+        source_id: String,
+        width: i64,
+        height: i64,
+    },
+    NegativeObjectDimensions {
+        source_id: String,
+        object_id: i64,
+        width: f32,
+        height: f32,
+    },
+    BBoxOutOfFrame {
+        source_id: String,
+        object_id: i64,
+    },
+    NonMonotonicPts {
+        source_id: String,
+        previous: i64,
+        current: i64,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::NonPositiveFrameDimensions {
+                source_id,
+                width,
+                height,
+            } => write!(
+                f,
+                "source {}: frame dimensions {}x{} are not positive",
+                source_id, width, height
+            ),
+            Violation::NegativeObjectDimensions {
+                source_id,
+                object_id,
+                width,
+                height,
+            } => write!(
+                f,
+                "source {}: object {} has negative dimensions {}x{}",
+                source_id, object_id, width, height
+            ),
+            Violation::BBoxOutOfFrame {
+                source_id,
+                object_id,
+            } => write!(
+                f,
+                "source {}: object {} bbox is out of the frame bounds",
+                source_id, object_id
+            ),
+            Violation::NonMonotonicPts {
+                source_id,
+                previous,
+                current,
+            } => write!(
+                f,
+                "source {}: pts {} does not follow the previous pts {}",
+                source_id, current, previous
+            ),
+        }
+    }
+}
+
+/// Checks the semantic invariants of received frames: bboxes within the frame bounds,
+/// non-negative dimensions and monotonic pts per source.
+///
+#[derive(Debug)]
+pub struct MessageValidator {
+    mode: ValidationMode,
+    last_pts: Mutex<LruCache<String, i64>>,
+}
+
+impl MessageValidator {
+    pub fn new(mode: ValidationMode) -> Self {
+        Self {
+            mode,
+            last_pts: Mutex::new(LruCache::new(
+                NonZeroUsize::new(MAX_TRACKED_SOURCES).unwrap(),
+            )),
+        }
+    }
+
+    pub fn mode(&self) -> ValidationMode {
+        self.mode
+    }
+
+    /// Validates the message according to the mode. Returns the found violations; in the strict
+    /// mode the message must be rejected if they are not empty, in the lenient mode the message
+    /// is already fixed and annotated. Only the pts of the accepted frames become the reference
+    /// for the next frames of the source.
+    ///
+    pub fn validate(&self, message: &Message) -> Vec<Violation> {
+        if self.mode == ValidationMode::Disabled {
+            return vec![];
+        }
+        if let Some(eos) = message.as_end_of_stream() {
+            trace!(self.last_pts.lock()).pop(&eos.source_id);
+            return vec![];
+        }
+        let mut frames = Vec::new();
+        if let Some(frame) = message.as_video_frame() {
+            frames.push(frame);
+        }
+        if let Some(batch) = message.as_video_frame_batch() {
+            let mut batch_frames = batch.frames().iter().collect::<Vec<_>>();
+            batch_frames.sort_by_key(|(id, _)| **id);
+            frames.extend(batch_frames.into_iter().map(|(_, f)| f.clone()));
+        }
+        let mut violations = Vec::new();
+        // the pts of the batch frames are chained locally and stored once the verdict is known
+        let mut frame_pts: Vec<(String, i64)> = Vec::new();
+        for frame in frames.iter_mut() {
+            let source_id = frame.get_source_id();
+            let previous = frame_pts
+                .iter()
+                .rev()
+                .find(|(s, _)| *s == source_id)
+                .map(|(_, pts)| *pts)
+                .or_else(|| trace!(self.last_pts.lock()).peek(&source_id).copied());
+            violations.extend(self.validate_frame(frame, previous));
+            frame_pts.push((source_id, frame.get_pts()));
+        }
+        if self.mode == ValidationMode::Lenient || violations.is_empty() {
+            let mut last_pts = trace!(self.last_pts.lock());
+            for (source_id, pts) in frame_pts {
+                last_pts.put(source_id, pts);
+            }
+        }
+        violations
+    }
+
+    fn validate_frame(&self, frame: &mut VideoFrameProxy, previous: Option<i64>) -> Vec<Violation> {
+        let fix = self.mode == ValidationMode::Lenient;
+        let source_id = frame.get_source_id();
+        let (width, height) = (frame.get_width(), frame.get_height());
+        let mut violations = Vec::new();
+
+        let pts = frame.get_pts();
+        if let Some(previous) = previous {
+            if pts <= previous {
+                violations.push(Violation::NonMonotonicPts {
+                    source_id: source_id.clone(),
+                    previous,
+                    current: pts,
+                });
+            }
+        }
+
+        if width <= 0 || height <= 0 {
+            violations.push(Violation::NonPositiveFrameDimensions {
+                source_id: source_id.clone(),
+                width,
+                height,
+            });
+        }
+
+        for mut o in frame.get_all_objects() {
+            let object_id = o.get_id();
+            let mut bbox = o.get_detection_box();
+            if bbox.get_width() < 0.0 || bbox.get_height() < 0.0 {
+                violations.push(Violation::NegativeObjectDimensions {
+                    source_id: source_id.clone(),
+                    object_id,
+                    width: bbox.get_width(),
+                    height: bbox.get_height(),
+                });
+                if fix {
+                    bbox = RBBox::new(
+                        bbox.get_xc(),
+                        bbox.get_yc(),
+                        bbox.get_width().abs(),
+                        bbox.get_height().abs(),
+                        bbox.get_angle(),
+                    );
+                    o.set_detection_box(bbox.clone());
+                }
+            }
+            if width <= 0 || height <= 0 {
+                continue;
+            }
+            let (w, h) = (width as f32, height as f32);
+            let Ok((l, t, r, b)) = bbox.get_wrapping_bbox().as_ltrb() else {
+                continue;
+            };
+            if l < -BBOX_EPSILON
+                || t < -BBOX_EPSILON
+                || r > w + BBOX_EPSILON
+                || b > h + BBOX_EPSILON
+            {
+                violations.push(Violation::BBoxOutOfFrame {
+                    source_id: source_id.clone(),
+                    object_id,
+                });
+                // rotated boxes cannot be clipped without changing their geometry
+                if fix && bbox.get_angle().unwrap_or(0.0) == 0.0 {
+                    o.set_detection_box(RBBox::ltrb(
+                        l.clamp(0.0, w),
+                        t.clamp(0.0, h),
+                        r.clamp(0.0, w),
+                        b.clamp(0.0, h),
+                    ));
+                }
+            }
+        }
+
+        if fix && !violations.is_empty() {
+            for v in &violations {
+                log::warn!(target: "savant_rs::message::validation", "{}", v);
+            }
+            frame.set_temporary_attribute(
+                VALIDATION_ATTRIBUTE_NAMESPACE,
+                VALIDATION_ATTRIBUTE_NAME,
+                &None,
+                true,
+                violations
+                    .iter()
+                    .map(|v| AttributeValue::string(&v.to_string(), None))
+                    .collect(),
+            );
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::eos::EndOfStream;
+    use crate::primitives::object::{IdCollisionResolutionPolicy, VideoObjectBuilder};
+    use crate::test::gen_frame;
+
+    fn frame_with_object(pts: i64, bbox: RBBox) -> VideoFrameProxy {
+        let mut frame = gen_frame();
+        frame.set_pts(pts);
+        let object = VideoObjectBuilder::default()
+            .id(10)
+            .detection_box(bbox)
+            .attributes(Vec::default())
+            .confidence(None)
+            .namespace("validation".to_string())
+            .label("object".to_string())
+            .build()
+            .unwrap();
+        frame
+            .add_object(object, IdCollisionResolutionPolicy::Error)
+            .unwrap();
+        frame
+    }
+
+    #[test]
+    fn test_disabled() {
+        let validator = MessageValidator::new(ValidationMode::Disabled);
+        let frame = frame_with_object(0, RBBox::ltrb(-10.0, 0.0, 10.0, 10.0));
+        assert!(validator.validate(&Message::video_frame(&frame)).is_empty());
+    }
+
+    #[test]
+    fn test_strict() {
+        let validator = MessageValidator::new(ValidationMode::Strict);
+        let frame = frame_with_object(100, RBBox::ltrb(10.0, 10.0, 20.0, 20.0));
+        assert!(validator.validate(&Message::video_frame(&frame)).is_empty());
+
+        let frame = frame_with_object(50, RBBox::ltrb(-10.0, 0.0, 10.0, 10.0));
+        let violations = validator.validate(&Message::video_frame(&frame));
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(
+            violations[0],
+            Violation::NonMonotonicPts {
+                previous: 100,
+                current: 50,
+                ..
+            }
+        ));
+        assert!(matches!(
+            violations[1],
+            Violation::BBoxOutOfFrame { object_id: 10, .. }
+        ));
+        // strict mode does not touch the frame
+        let bbox = frame.get_object(10).unwrap().get_detection_box();
+        assert_eq!(bbox.get_xc(), 0.0);
+        assert!(frame
+            .get_attribute(VALIDATION_ATTRIBUTE_NAMESPACE, VALIDATION_ATTRIBUTE_NAME)
+            .is_none());
+
+        // the rejected frames do not become the reference
+        let frame = frame_with_object(200, RBBox::ltrb(-10.0, 0.0, 10.0, 10.0));
+        assert_eq!(validator.validate(&Message::video_frame(&frame)).len(), 1);
+        let frame = frame_with_object(150, RBBox::ltrb(10.0, 10.0, 20.0, 20.0));
+        assert!(validator.validate(&Message::video_frame(&frame)).is_empty());
+
+        let eos = Message::end_of_stream(EndOfStream::new(frame.get_source_id()));
+        assert!(validator.validate(&eos).is_empty());
+        let frame = frame_with_object(0, RBBox::ltrb(10.0, 10.0, 20.0, 20.0));
+        assert!(validator.validate(&Message::video_frame(&frame)).is_empty());
+    }
+
+    #[test]
+    fn test_lenient() {
+        let validator = MessageValidator::new(ValidationMode::Lenient);
+        let frame = frame_with_object(0, RBBox::ltrb(-10.0, 0.0, 10.0, 730.0));
+        let violations = validator.validate(&Message::video_frame(&frame));
+        assert_eq!(violations.len(), 1);
+
+        let bbox = frame.get_object(10).unwrap().get_detection_box();
+        assert_eq!(bbox.as_ltrb().unwrap(), (0.0, 0.0, 10.0, 720.0));
+        let attr = frame
+            .get_attribute(VALIDATION_ATTRIBUTE_NAMESPACE, VALIDATION_ATTRIBUTE_NAME)
+            .unwrap();
+        assert_eq!(attr.values.len(), 1);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::metrics::{get_or_create_counter_family, get_or_create_gauge_family};
+use crate::pipeline::stage::STAGE_FUNCTION_LATENCY_BUCKETS_US;
+use crate::pipeline::PipelineStageFunctionOrder;
 use crate::rust::FrameProcessingStatRecordType;
 use crate::webserver::get_registered_pipelines;
 use log::debug;
@@ -28,6 +30,8 @@ impl PipelineMetricBuilder {
         let stage_performance_label_names = ["record_type", "stage_name"].as_slice();
         let stage_latency_label_names =
             ["record_type", "destination_stage_name", "source_stage_name"].as_slice();
+        let stage_function_label_names = ["stage_name", "function"].as_slice();
+        let stage_function_bucket_label_names = ["stage_name", "function", "le"].as_slice();
 
         let registered_pipelines = get_registered_pipelines().await;
         debug!(
@@ -35,6 +39,11 @@ impl PipelineMetricBuilder {
             registered_pipelines.len()
         );
         for p in registered_pipelines {
+            Self::build_stage_function_metrics(
+                &p,
+                stage_function_label_names,
+                stage_function_bucket_label_names,
+            )?;
             let stats = p.get_stat_records(1);
             if stats.is_empty() {
                 debug!("No stats for pipeline {:?}", p.get_name());
@@ -215,4 +224,95 @@ impl PipelineMetricBuilder {
         }
         Ok(())
     }
+
+    fn build_stage_function_metrics(
+        p: &crate::pipeline::implementation::Pipeline,
+        label_names: &[&str],
+        bucket_label_names: &[&str],
+    ) -> anyhow::Result<()> {
+        let function_stats = p.get_stage_function_stats();
+        if function_stats.is_empty() {
+            return Ok(());
+        }
+        let pipeline_name = p.get_name().into_iter().collect::<Vec<_>>();
+        let extra_label_names = if pipeline_name.is_empty() {
+            [].as_slice()
+        } else {
+            ["pipeline_name"].as_slice()
+        };
+        let label_names = [label_names, extra_label_names].concat();
+        let bucket_label_names = [bucket_label_names, extra_label_names].concat();
+
+        let calls = get_or_create_counter_family(
+            "stage_function_calls",
+            Some("Number of stage function invocations"),
+            &label_names,
+            None,
+        );
+        let errors = get_or_create_counter_family(
+            "stage_function_errors",
+            Some("Number of stage function invocations finished with an error"),
+            &label_names,
+            None,
+        );
+        let panics = get_or_create_counter_family(
+            "stage_function_panics",
+            Some("Number of stage function invocations finished with a panic"),
+            &label_names,
+            None,
+        );
+        let avg_latency = get_or_create_gauge_family(
+            "stage_function_avg_latency",
+            Some("Average latency of the stage function (us)"),
+            &label_names,
+            None,
+        );
+        let max_latency = get_or_create_gauge_family(
+            "stage_function_max_latency",
+            Some("Maximum latency of the stage function (us)"),
+            &label_names,
+            None,
+        );
+        let latency_buckets = get_or_create_counter_family(
+            "stage_function_latency_bucket",
+            Some("Cumulative histogram of the stage function latency (us)"),
+            &bucket_label_names,
+            None,
+        );
+
+        for (stage_name, order, stat) in function_stats {
+            let function = match order {
+                PipelineStageFunctionOrder::Ingress => "ingress",
+                PipelineStageFunctionOrder::Egress => "egress",
+            };
+            let labels = [stage_name.as_str(), function]
+                .into_iter()
+                .chain(pipeline_name.iter().map(|s| s.as_str()))
+                .collect::<Vec<_>>();
+            calls.lock().set(stat.calls, &labels)?;
+            errors.lock().set(stat.errors, &labels)?;
+            panics.lock().set(stat.panics, &labels)?;
+            avg_latency
+                .lock()
+                .set(stat.avg_latency().as_micros() as f64, &labels)?;
+            max_latency
+                .lock()
+                .set(stat.max_latency.as_micros() as f64, &labels)?;
+
+            let mut cumulative = 0;
+            let bounds = STAGE_FUNCTION_LATENCY_BUCKETS_US
+                .iter()
+                .map(|b| b.to_string())
+                .chain(std::iter::once("+Inf".to_string()));
+            for (bound, count) in bounds.zip(stat.latency_histogram.iter()) {
+                cumulative += count;
+                let bucket_labels = [stage_name.as_str(), function, bound.as_str()]
+                    .into_iter()
+                    .chain(pipeline_name.iter().map(|s| s.as_str()))
+                    .collect::<Vec<_>>();
+                latency_buckets.lock().set(cumulative, &bucket_labels)?;
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,8 +1,17 @@
+use crate::get_tracer;
+use crate::primitives::attribute_value::{AttributeValue, AttributeValueVariant};
+use crate::primitives::Attribute;
 use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::{SpanBuilder, TraceContextExt, Tracer};
 use opentelemetry::{global, Context};
 use std::cell::RefCell;
 use std::collections::HashMap;
 
+/// The hidden persistent attribute carrying the propagation context of a frame.
+///
+pub const TRACE_CONTEXT_NAMESPACE: &str = "savant";
+pub const TRACE_CONTEXT_NAME: &str = "trace_context";
+
 thread_local! {
     static CURRENT_CONTEXTS: RefCell<Vec<Context>> = RefCell::new(vec![Context::default()]);
 }
@@ -76,4 +85,83 @@ impl PropagatedContext {
     pub fn extract(&self) -> Context {
         global::get_text_map_propagator(|propagator| propagator.extract(self))
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Starts the span named `name` with the extracted context as the parent. When the context
+    /// carries no valid span, the default context is returned and no span is started, like the
+    /// pipeline does for the unsampled frames.
+    ///
+    pub fn child_span(&self, name: &str) -> Context {
+        let parent = self.extract();
+        if !parent.span().span_context().is_valid() {
+            return Context::default();
+        }
+        let span =
+            get_tracer().build_with_context(SpanBuilder::from_name(name.to_string()), &parent);
+        Context::current_with_span(span)
+    }
+
+    /// The entries are stored as `key=value` strings sorted by the key.
+    ///
+    pub fn to_attribute(&self) -> Attribute {
+        let mut entries = self
+            .0
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>();
+        entries.sort();
+        Attribute::persistent(
+            TRACE_CONTEXT_NAMESPACE,
+            TRACE_CONTEXT_NAME,
+            vec![AttributeValue::string_vector(entries, None)],
+            &None,
+            true,
+        )
+    }
+
+    pub fn from_attribute(attribute: &Attribute) -> Option<Self> {
+        let AttributeValueVariant::StringVector(entries) = attribute.get_values().first()?.get()
+        else {
+            return None;
+        };
+        Some(Self(
+            entries
+                .iter()
+                .filter_map(|e| e.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute_round_trip() {
+        let context = PropagatedContext(HashMap::from([
+            (
+                "traceparent".to_string(),
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_string(),
+            ),
+            ("tracestate".to_string(), "vendor=value".to_string()),
+        ]));
+        let attribute = context.to_attribute();
+        assert!(attribute.is_hidden);
+        assert!(attribute.is_persistent);
+        let restored = PropagatedContext::from_attribute(&attribute).unwrap();
+        assert_eq!(restored.0, context.0);
+    }
+
+    #[test]
+    fn test_child_span_without_parent() {
+        let context = PropagatedContext::new();
+        assert!(context.is_empty());
+        let child = context.child_span("child");
+        assert!(!child.span().span_context().is_valid());
+    }
 }
@@ -0,0 +1,1137 @@
+pub mod coordination;
+
+use crate::eval_resolvers::{EtcdCredentials, TlsConfig};
+use crate::get_or_init_async_runtime;
+use anyhow::{anyhow, bail, Result};
+use etcd_client::{
+    Client, ConnectOptions, EventType, GetOptions, PutOptions, WatchOptions, WatchStream, Watcher,
+};
+use etcd_dynamic_state::etcd_api::{EtcdClient, Operation, VarPathSpec};
+use etcd_dynamic_state::parameter_storage::EtcdParameterStorage;
+use hashbrown::HashMap;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::any::TypeId;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const LEASE_TIMEOUT: i64 = 60;
+const CACHE_MAGIC: &[u8; 4] = b"SVPC";
+const CACHE_VERSION: u8 = 1;
+const CACHE_HEADER_SIZE: usize = 5;
+const CACHE_WRITE_DELAY: Duration = Duration::from_millis(100);
+
+/// Remembers the checksums of the values last read by key and by type, so the readers decoding
+/// the same key into different types detect the changes independently.
+///
+#[derive(Default)]
+struct ChecksumTracker(Mutex<HashMap<(String, TypeId), u32>>);
+
+impl ChecksumTracker {
+    fn is_changed<T: 'static>(&self, key: &str, checksum: u32) -> bool {
+        self.0.lock().get(&(key.to_string(), TypeId::of::<T>())) != Some(&checksum)
+    }
+
+    fn update<T: 'static>(&self, key: &str, checksum: u32) -> bool {
+        let previous = self
+            .0
+            .lock()
+            .insert((key.to_string(), TypeId::of::<T>()), checksum);
+        previous != Some(checksum)
+    }
+}
+
+/// The change of a watched key, `new` is `None` when the key is deleted and `old` is `None` when
+/// it is created.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterChange {
+    pub key: String,
+    pub old: Option<Vec<u8>>,
+    pub new: Option<Vec<u8>>,
+}
+
+pub type ParameterCallback = Arc<dyn Fn(&ParameterChange) + Send + Sync>;
+
+/// The active subscription created by [`TypedParameterStorage::subscribe`], the watch is
+/// cancelled when the subscription is dropped. When the watch fails, it is established again
+/// from the revision after the last delivered change, so no change is lost unless etcd has
+/// compacted it.
+///
+pub struct Subscription {
+    task: JoinHandle<()>,
+    healthy: Arc<AtomicBool>,
+}
+
+impl Subscription {
+    /// Returns `false` while the failed watch is being established again.
+    ///
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The module instance announced by [`TypedParameterStorage::register_presence`].
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceInfo {
+    pub name: String,
+    pub version: String,
+    pub host: String,
+}
+
+impl PresenceInfo {
+    /// The instance of the module on this host with the current library version.
+    ///
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: crate::version(),
+            host: nix::unistd::gethostname()
+                .map(|host| host.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// The registered presence, the key is removed when the presence is dropped.
+///
+pub struct Presence {
+    task: JoinHandle<()>,
+    lease: Arc<AtomicI64>,
+    client: Client,
+}
+
+impl Drop for Presence {
+    fn drop(&mut self) {
+        self.task.abort();
+        let lease = self.lease.load(Ordering::SeqCst);
+        let mut client = self.client.clone();
+        get_or_init_async_runtime().spawn(async move {
+            if let Err(e) = client.lease_revoke(lease).await {
+                warn!(
+                    target: "savant_rs::parameter_storage",
+                    "Failed to revoke the presence lease {}: {:?}", lease, e
+                );
+            }
+        });
+    }
+}
+
+fn keep_alive_interval(ttl: i64) -> Duration {
+    Duration::from_millis((ttl.max(1) * 1000 / 3) as u64)
+}
+
+async fn put_with_lease(client: &mut Client, key: &str, value: &[u8], ttl: i64) -> Result<i64> {
+    let lease = client.lease_grant(ttl, None).await?.id();
+    client
+        .put(key, value, Some(PutOptions::new().with_lease(lease)))
+        .await?;
+    Ok(lease)
+}
+
+async fn keep_lease(client: &mut Client, lease: i64, ttl: i64) -> Result<()> {
+    let (mut keeper, mut stream) = client.lease_keep_alive(lease).await?;
+    loop {
+        keeper.keep_alive().await?;
+        match stream.message().await? {
+            Some(response) if response.ttl() > 0 => {}
+            _ => bail!("The lease {} has expired.", lease),
+        }
+        tokio::time::sleep(keep_alive_interval(ttl)).await;
+    }
+}
+
+/// Renews the lease and registers the key again with a new lease when the lease is lost, e.g.
+/// after the network partition longer than the TTL.
+///
+async fn keep_presence(
+    mut client: Client,
+    key: String,
+    value: Vec<u8>,
+    ttl: i64,
+    lease: Arc<AtomicI64>,
+) {
+    loop {
+        if let Err(e) = keep_lease(&mut client, lease.load(Ordering::SeqCst), ttl).await {
+            warn!(
+                target: "savant_rs::parameter_storage",
+                "The presence {} is lost: {:?}", key, e
+            );
+        }
+        tokio::time::sleep(keep_alive_interval(ttl)).await;
+        match put_with_lease(&mut client, &key, &value, ttl).await {
+            Ok(id) => lease.store(id, Ordering::SeqCst),
+            Err(e) => warn!(
+                target: "savant_rs::parameter_storage",
+                "Failed to register the presence {} again: {:?}", key, e
+            ),
+        }
+    }
+}
+
+fn watch_options(start_revision: Option<i64>) -> WatchOptions {
+    let options = WatchOptions::new().with_prefix().with_prev_key();
+    match start_revision {
+        Some(revision) => options.with_start_revision(revision),
+        None => options,
+    }
+}
+
+/// Delivers the changes from the stream until it fails. `revision` is advanced past every
+/// delivered change, so the watch can be resumed from it.
+///
+async fn deliver_changes(
+    stream: &mut WatchStream,
+    callback: &ParameterCallback,
+    revision: &mut Option<i64>,
+) -> Result<()> {
+    loop {
+        let Some(response) = stream.message().await? else {
+            bail!("The watch stream is closed.");
+        };
+        for event in response.events() {
+            let Some(kv) = event.kv() else {
+                continue;
+            };
+            let change = ParameterChange {
+                key: String::from_utf8_lossy(kv.key()).to_string(),
+                old: event.prev_kv().map(|prev| prev.value().to_vec()),
+                new: match event.event_type() {
+                    EventType::Put => Some(kv.value().to_vec()),
+                    EventType::Delete => None,
+                },
+            };
+            callback(&change);
+            *revision = Some(kv.mod_revision() + 1);
+        }
+        if response.canceled() {
+            if response.compact_revision() > 0 {
+                // the changes before the compacted revision are not available anymore
+                *revision = Some(response.compact_revision());
+                bail!(
+                    "The watch is cancelled, the changes before the revision {} are compacted.",
+                    response.compact_revision()
+                );
+            }
+            bail!("The watch is cancelled: {}", response.cancel_reason());
+        }
+    }
+}
+
+/// Delivers the changes of the keys with the prefix and establishes the watch again with the
+/// backoff when it fails, e.g. after an etcd outage.
+///
+async fn keep_watch(
+    mut client: Client,
+    prefix: String,
+    mut revision: Option<i64>,
+    watcher: Watcher,
+    mut stream: WatchStream,
+    callback: ParameterCallback,
+    healthy: Arc<AtomicBool>,
+) {
+    // the watch is cancelled when the watcher is dropped with the task
+    let mut _watcher = watcher;
+    loop {
+        if let Err(e) = deliver_changes(&mut stream, &callback, &mut revision).await {
+            warn!(
+                target: "savant_rs::parameter_storage",
+                "Watch of {} failed: {:?}", prefix, e
+            );
+        }
+        healthy.store(false, Ordering::SeqCst);
+        let mut attempt = 0;
+        loop {
+            tokio::time::sleep(reconnect_backoff(attempt)).await;
+            match client
+                .watch(prefix.as_str(), Some(watch_options(revision)))
+                .await
+            {
+                Ok((watcher, watch_stream)) => {
+                    _watcher = watcher;
+                    stream = watch_stream;
+                    break;
+                }
+                Err(e) => {
+                    attempt += 1;
+                    warn!(
+                        target: "savant_rs::parameter_storage",
+                        "Failed to watch {} again, attempt {}: {:?}", prefix, attempt, e
+                    );
+                }
+            }
+        }
+        healthy.store(true, Ordering::SeqCst);
+        info!(
+            target: "savant_rs::parameter_storage",
+            "Watch of {} is established again from the revision {:?}", prefix, revision
+        );
+    }
+}
+
+/// Saves the snapshot after the changes notified through the channel, waiting for
+/// [`CACHE_WRITE_DELAY`] to write a burst of changes once. Stops when the subscription updating
+/// the snapshot is dropped.
+///
+fn write_cache(
+    snapshot: Arc<Mutex<ParameterSnapshot>>,
+    changes: crossbeam::channel::Receiver<()>,
+    path: PathBuf,
+) {
+    while changes.recv().is_ok() {
+        thread::sleep(CACHE_WRITE_DELAY);
+        while changes.try_recv().is_ok() {}
+        let snapshot = snapshot.lock().clone();
+        if let Err(e) = snapshot.save(&path) {
+            warn!(
+                target: "savant_rs::parameter_storage",
+                "Failed to update the parameter cache {}: {:?}", path.display(), e
+            );
+        }
+    }
+}
+
+fn take<'a>(buf: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8]> {
+    let chunk = buf
+        .get(*pos..*pos + len)
+        .ok_or_else(|| anyhow!("The parameter cache is truncated."))?;
+    *pos += len;
+    Ok(chunk)
+}
+
+fn read_len(buf: &[u8], pos: &mut usize) -> Result<usize> {
+    Ok(u32::from_le_bytes(take(buf, pos, 4)?.try_into()?) as usize)
+}
+
+/// The last-known parameter values persisted to disk, so the node boots with the cached
+/// configuration when etcd is unreachable. The file is written to a temporary path and renamed,
+/// the checksum at the end detects the truncated or damaged files.
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParameterSnapshot {
+    values: HashMap<String, Vec<u8>>,
+}
+
+impl ParameterSnapshot {
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        self.values.get(key).map(Vec::as_slice)
+    }
+
+    pub fn insert(&mut self, key: &str, value: Vec<u8>) {
+        self.values.insert(key.to_string(), value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn apply(&mut self, change: &ParameterChange) {
+        match &change.new {
+            Some(value) => self.insert(&change.key, value.clone()),
+            None => {
+                self.values.remove(&change.key);
+            }
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut keys = self.values.keys().collect::<Vec<_>>();
+        keys.sort();
+        let mut buf = Vec::from(&CACHE_MAGIC[..]);
+        buf.push(CACHE_VERSION);
+        buf.extend((keys.len() as u32).to_le_bytes());
+        for key in keys {
+            let value = &self.values[key];
+            buf.extend((key.len() as u32).to_le_bytes());
+            buf.extend(key.as_bytes());
+            buf.extend((value.len() as u32).to_le_bytes());
+            buf.extend(value);
+        }
+        let checksum = crc32fast::hash(&buf[CACHE_HEADER_SIZE..]);
+        buf.extend(checksum.to_le_bytes());
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> Result<Self> {
+        if buf.len() < CACHE_HEADER_SIZE + 8 || &buf[..4] != CACHE_MAGIC || buf[4] != CACHE_VERSION
+        {
+            bail!("The parameter cache has an unsupported format.");
+        }
+        let (body, checksum) = buf.split_at(buf.len() - 4);
+        let body = &body[CACHE_HEADER_SIZE..];
+        if crc32fast::hash(body) != u32::from_le_bytes(checksum.try_into()?) {
+            bail!("The parameter cache is damaged, the checksum does not match.");
+        }
+        let mut pos = 0;
+        let count = read_len(body, &mut pos)?;
+        let mut snapshot = Self::default();
+        for _ in 0..count {
+            let key_len = read_len(body, &mut pos)?;
+            let key = String::from_utf8(take(body, &mut pos, key_len)?.to_vec())?;
+            let value_len = read_len(body, &mut pos)?;
+            let value = take(body, &mut pos, value_len)?.to_vec();
+            snapshot.values.insert(key, value);
+        }
+        Ok(snapshot)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, self.encode())?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// The state of the etcd connection of [`TypedParameterStorage`].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    /// The storage failed and is being restored, `attempt` counts the failed reconnections.
+    Reconnecting {
+        attempt: u32,
+    },
+    /// The storage failed and cannot be restored because the connection settings are unknown.
+    Disconnected,
+    /// The values are loaded from the local cache, etcd is not used until it becomes reachable.
+    Cached,
+}
+
+#[derive(Clone)]
+struct ConnectSettings {
+    hosts: Vec<String>,
+    credentials: Option<EtcdCredentials>,
+    tls_config: Option<TlsConfig>,
+    watch_path: String,
+    connect_timeout: u64,
+}
+
+impl ConnectSettings {
+    fn new(
+        hosts: &[&str],
+        credentials: &Option<EtcdCredentials>,
+        tls_config: &Option<TlsConfig>,
+        watch_path: &str,
+        connect_timeout: u64,
+    ) -> Self {
+        Self {
+            hosts: hosts.iter().map(|host| host.to_string()).collect(),
+            credentials: credentials.clone(),
+            tls_config: tls_config.clone(),
+            watch_path: watch_path.to_string(),
+            connect_timeout,
+        }
+    }
+
+    fn options(&self) -> Result<ConnectOptions> {
+        let mut options =
+            ConnectOptions::new().with_connect_timeout(Duration::from_secs(self.connect_timeout));
+        if let Some((username, password)) = self.credentials.as_ref().map(EtcdCredentials::as_pair)
+        {
+            options = options.with_user(username, password);
+        }
+        if let Some(tls) = self.tls_config.as_ref().map(TlsConfig::tls_options) {
+            options = options.with_tls(tls?);
+        }
+        Ok(options)
+    }
+}
+
+struct EtcdConnection {
+    storage: EtcdParameterStorage,
+    writer: EtcdClient,
+}
+
+impl EtcdConnection {
+    fn open(settings: &ConnectSettings) -> Result<Self> {
+        let runtime = get_or_init_async_runtime();
+        let hosts = settings
+            .hosts
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let credentials = settings.credentials.as_ref().map(EtcdCredentials::as_pair);
+        let tls = settings
+            .tls_config
+            .as_ref()
+            .map(TlsConfig::tls_options)
+            .transpose()?;
+        let client = || {
+            runtime.block_on(EtcdClient::new_with_tls(
+                &hosts,
+                &credentials,
+                &settings.watch_path,
+                LEASE_TIMEOUT,
+                settings.connect_timeout,
+                tls.clone(),
+            ))
+        };
+        let mut storage = EtcdParameterStorage::with_client(client()?);
+        storage.run(runtime)?;
+        storage.order_data_update(VarPathSpec::Prefix(settings.watch_path.clone()))?;
+        Ok(Self {
+            storage,
+            writer: client()?,
+        })
+    }
+}
+
+fn reconnect_backoff(attempt: u32) -> Duration {
+    Duration::from_millis(500 << attempt.min(6)).min(Duration::from_secs(30))
+}
+
+/// Keeps the etcd connection and replaces it when the monitor task of [`EtcdParameterStorage`]
+/// has stopped, e.g. on an etcd outage. The stopped storage panics on access, so it is checked
+/// before every call and the outage is returned as an error instead of taking the process down.
+///
+struct EtcdBackend {
+    connection: Mutex<Option<EtcdConnection>>,
+    state: Mutex<ConnectionState>,
+    settings: Option<ConnectSettings>,
+}
+
+impl EtcdBackend {
+    fn new(connection: EtcdConnection, settings: Option<ConnectSettings>) -> Arc<Self> {
+        Arc::new(Self {
+            connection: Mutex::new(Some(connection)),
+            state: Mutex::new(ConnectionState::Connected),
+            settings,
+        })
+    }
+
+    /// Creates the backend without the connection, [`EtcdBackend::restart`] connects it in the
+    /// background.
+    ///
+    fn detached(settings: ConnectSettings) -> Arc<Self> {
+        Arc::new(Self {
+            connection: Mutex::new(None),
+            state: Mutex::new(ConnectionState::Disconnected),
+            settings: Some(settings),
+        })
+    }
+
+    fn state(&self) -> ConnectionState {
+        *self.state.lock()
+    }
+
+    fn call<R>(self: &Arc<Self>, f: impl FnOnce(&mut EtcdConnection) -> Result<R>) -> Result<R> {
+        let mut connection = self.connection.lock();
+        let Some(active) = connection.as_mut() else {
+            bail!(
+                "The etcd parameter storage is not available: {:?}.",
+                self.state()
+            );
+        };
+        if !active.storage.is_active() {
+            *connection = None;
+            drop(connection);
+            self.restart();
+            bail!("The etcd parameter storage has failed, the connection is being restored.")
+        }
+        f(active)
+    }
+
+    fn restart(self: &Arc<Self>) {
+        let Some(settings) = self.settings.clone() else {
+            warn!(
+                target: "savant_rs::parameter_storage",
+                "The etcd parameter storage has failed and cannot be restored."
+            );
+            *self.state.lock() = ConnectionState::Disconnected;
+            return;
+        };
+        *self.state.lock() = ConnectionState::Reconnecting { attempt: 0 };
+        let backend = Arc::downgrade(self);
+        thread::spawn(move || Self::reconnect(backend, settings));
+    }
+
+    fn reconnect(backend: Weak<Self>, settings: ConnectSettings) {
+        let mut attempt = 0;
+        loop {
+            thread::sleep(reconnect_backoff(attempt));
+            let Some(backend) = backend.upgrade() else {
+                return;
+            };
+            match EtcdConnection::open(&settings) {
+                Ok(connection) => {
+                    *backend.connection.lock() = Some(connection);
+                    *backend.state.lock() = ConnectionState::Connected;
+                    info!(
+                        target: "savant_rs::parameter_storage",
+                        "The etcd parameter storage is restored after {} failed attempts.", attempt
+                    );
+                    return;
+                }
+                Err(e) => {
+                    attempt += 1;
+                    *backend.state.lock() = ConnectionState::Reconnecting { attempt };
+                    warn!(
+                        target: "savant_rs::parameter_storage",
+                        "Failed to restore the etcd parameter storage, attempt {}: {:?}", attempt, e
+                    );
+                }
+            }
+        }
+    }
+}
+
+enum ParameterSource {
+    Etcd(Arc<EtcdBackend>),
+    /// The values loaded from the cache, replaced by the backend once it connects to etcd.
+    Cache(ParameterSnapshot, Option<Arc<EtcdBackend>>),
+}
+
+/// The etcd parameter storage with the values decoded from and encoded to JSON or protobuf.
+/// The values are read from the watched copy kept by [`EtcdParameterStorage`] and written with a
+/// separate client; the changes are pushed to the subscribers by the watch client.
+///
+/// The failures of the storage are returned as errors and the connection is restored in the
+/// background, see [`TypedParameterStorage::state`]. When etcd is unreachable at startup, the
+/// storage can be loaded from the local cache: the values are read-only and
+/// [`TypedParameterStorage::is_stale`] returns `true` until the storage connects to etcd in the
+/// background. The subscriptions, the presence and the cache itself are available only for the
+/// storage connected at startup.
+///
+/// The methods block on the shared async runtime, so they must not be called from its tasks.
+///
+pub struct TypedParameterStorage {
+    source: ParameterSource,
+    watch_client: Option<Client>,
+    cache_subscription: Option<Subscription>,
+    checksums: ChecksumTracker,
+}
+
+impl TypedParameterStorage {
+    /// The storage built from the running clients is not restored when it fails, use
+    /// [`TypedParameterStorage::connect`] for that.
+    ///
+    pub fn new(storage: EtcdParameterStorage, writer: EtcdClient) -> Self {
+        Self::with_source(ParameterSource::Etcd(EtcdBackend::new(
+            EtcdConnection { storage, writer },
+            None,
+        )))
+    }
+
+    fn with_source(source: ParameterSource) -> Self {
+        Self {
+            source,
+            watch_client: None,
+            cache_subscription: None,
+            checksums: ChecksumTracker::default(),
+        }
+    }
+
+    /// Loads the read-only storage from the cache written by
+    /// [`TypedParameterStorage::enable_cache`].
+    ///
+    pub fn from_cache(path: &Path) -> Result<Self> {
+        Self::load_cache(path, None)
+    }
+
+    /// Loads the storage from the cache; with the settings, keeps connecting to etcd in the
+    /// background with the backoff and switches to it once connected.
+    ///
+    fn load_cache(path: &Path, settings: Option<ConnectSettings>) -> Result<Self> {
+        let snapshot = ParameterSnapshot::load(path)?;
+        let backend = settings.map(|settings| {
+            let backend = EtcdBackend::detached(settings);
+            backend.restart();
+            backend
+        });
+        Ok(Self::with_source(ParameterSource::Cache(snapshot, backend)))
+    }
+
+    /// Returns the backend serving the requests, the storage loaded from the cache has one only
+    /// while it is connected to etcd.
+    ///
+    fn backend(&self) -> Option<&Arc<EtcdBackend>> {
+        match &self.source {
+            ParameterSource::Etcd(backend) => Some(backend),
+            ParameterSource::Cache(_, backend) => backend
+                .as_ref()
+                .filter(|backend| backend.state() == ConnectionState::Connected),
+        }
+    }
+
+    /// Returns `true` when the values come from the local cache because etcd is unreachable.
+    ///
+    pub fn is_stale(&self) -> bool {
+        self.backend().is_none()
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        self.backend()
+            .map_or(ConnectionState::Cached, |backend| backend.state())
+    }
+
+    fn with_connection<R>(&self, f: impl FnOnce(&mut EtcdConnection) -> Result<R>) -> Result<R> {
+        match self.backend() {
+            Some(backend) => backend.call(f),
+            None => bail!("The parameters are loaded from the cache, they cannot be changed."),
+        }
+    }
+
+    /// Sets the client watching the keys for [`TypedParameterStorage::subscribe`] and keeping the
+    /// presence leases.
+    ///
+    pub fn with_watch_client(mut self, client: Client) -> Self {
+        self.watch_client = Some(client);
+        self
+    }
+
+    /// Connects to etcd and starts watching the keys under the prefix. The timeout is in
+    /// seconds.
+    ///
+    pub fn connect(
+        hosts: &[&str],
+        credentials: &Option<EtcdCredentials>,
+        tls_config: &Option<TlsConfig>,
+        watch_path: &str,
+        connect_timeout: u64,
+    ) -> Result<Self> {
+        let settings =
+            ConnectSettings::new(hosts, credentials, tls_config, watch_path, connect_timeout);
+        let options = settings.options()?;
+        let storage = Self::open(settings)?;
+        let watch_client =
+            get_or_init_async_runtime().block_on(Client::connect(hosts, Some(options)))?;
+        Ok(storage.with_watch_client(watch_client))
+    }
+
+    /// Connects to etcd like [`TypedParameterStorage::connect`] without the watch client, so
+    /// only the values can be read and written.
+    ///
+    pub(crate) fn connect_unwatched(
+        hosts: &[&str],
+        credentials: &Option<EtcdCredentials>,
+        tls_config: &Option<TlsConfig>,
+        watch_path: &str,
+        connect_timeout: u64,
+    ) -> Result<Self> {
+        Self::open(ConnectSettings::new(
+            hosts,
+            credentials,
+            tls_config,
+            watch_path,
+            connect_timeout,
+        ))
+    }
+
+    fn open(settings: ConnectSettings) -> Result<Self> {
+        let connection = EtcdConnection::open(&settings)?;
+        Ok(Self::with_source(ParameterSource::Etcd(EtcdBackend::new(
+            connection,
+            Some(settings),
+        ))))
+    }
+
+    /// Waits up to `timeout_ms` milliseconds until the watched copy has the key.
+    ///
+    pub(crate) fn wait_for_key(&self, key: &str, timeout_ms: u64) -> Result<()> {
+        self.with_connection(|connection| {
+            _ = connection.storage.wait_for_key(key, timeout_ms);
+            Ok(())
+        })
+    }
+
+    /// Connects to etcd and keeps the cache of the watched keys up to date; when etcd is
+    /// unreachable, loads the stale values from the cache instead and keeps connecting to etcd
+    /// in the background, see [`TypedParameterStorage::is_stale`].
+    ///
+    pub fn connect_with_cache(
+        hosts: &[&str],
+        credentials: &Option<EtcdCredentials>,
+        tls_config: &Option<TlsConfig>,
+        watch_path: &str,
+        connect_timeout: u64,
+        cache_path: &Path,
+    ) -> Result<Self> {
+        match Self::connect(hosts, credentials, tls_config, watch_path, connect_timeout) {
+            Ok(mut storage) => {
+                storage.enable_cache(watch_path, cache_path)?;
+                Ok(storage)
+            }
+            Err(e) => {
+                warn!(
+                    target: "savant_rs::parameter_storage",
+                    "etcd is unreachable: {:?}, the parameters are loaded from the cache {}",
+                    e,
+                    cache_path.display()
+                );
+                let settings = ConnectSettings::new(
+                    hosts,
+                    credentials,
+                    tls_config,
+                    watch_path,
+                    connect_timeout,
+                );
+                Self::load_cache(cache_path, Some(settings)).map_err(|cache_error| {
+                    anyhow!(
+                        "etcd is unreachable: {}, the cache is not loaded: {}",
+                        e,
+                        cache_error
+                    )
+                })
+            }
+        }
+    }
+
+    /// Writes the values of the keys with the prefix to the cache and rewrites it after the
+    /// changes, the bursts of changes are written once.
+    ///
+    pub fn enable_cache(&mut self, prefix: &str, path: &Path) -> Result<()> {
+        let Some(client) = &self.watch_client else {
+            bail!("The storage has no watch client, the cache cannot be maintained.");
+        };
+        let mut client = client.clone();
+        let response = get_or_init_async_runtime()
+            .block_on(client.get(prefix, Some(GetOptions::new().with_prefix())))?;
+        let mut snapshot = ParameterSnapshot::default();
+        for kv in response.kvs() {
+            snapshot.insert(&String::from_utf8_lossy(kv.key()), kv.value().to_vec());
+        }
+        snapshot.save(path)?;
+
+        let revision = response.header().map(|h| h.revision() + 1);
+        let snapshot = Arc::new(Mutex::new(snapshot));
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let writer_snapshot = snapshot.clone();
+        let path = PathBuf::from(path);
+        thread::spawn(move || write_cache(writer_snapshot, receiver, path));
+        // the file is written by the writer thread, so the watch task does not block on the disk
+        self.cache_subscription = Some(self.watch(prefix, revision, move |change| {
+            snapshot.lock().apply(change);
+            let _ = sender.send(());
+        })?);
+        Ok(())
+    }
+
+    /// Calls the callback on every change of the keys with the prefix. The callback is called
+    /// from the async runtime, so it must not block.
+    ///
+    pub fn subscribe<F>(&self, prefix: &str, callback: F) -> Result<Subscription>
+    where
+        F: Fn(&ParameterChange) + Send + Sync + 'static,
+    {
+        self.watch(prefix, None, callback)
+    }
+
+    fn watch<F>(
+        &self,
+        prefix: &str,
+        start_revision: Option<i64>,
+        callback: F,
+    ) -> Result<Subscription>
+    where
+        F: Fn(&ParameterChange) + Send + Sync + 'static,
+    {
+        let Some(client) = &self.watch_client else {
+            bail!("The storage has no watch client, subscriptions are not available.");
+        };
+        let mut client = client.clone();
+        let runtime = get_or_init_async_runtime();
+        let (watcher, stream) =
+            runtime.block_on(client.watch(prefix, Some(watch_options(start_revision))))?;
+        let callback: ParameterCallback = Arc::new(callback);
+        let healthy = Arc::new(AtomicBool::new(true));
+        let task = runtime.spawn(keep_watch(
+            client,
+            prefix.to_string(),
+            start_revision,
+            watcher,
+            stream,
+            callback,
+            healthy.clone(),
+        ));
+        Ok(Subscription { task, healthy })
+    }
+
+    /// Announces the module instance with the key leased for `ttl` seconds. The lease is renewed
+    /// in the background until the returned presence is dropped, so the key disappears when the
+    /// instance dies.
+    ///
+    pub fn register_presence(&self, key: &str, ttl: i64, info: &PresenceInfo) -> Result<Presence> {
+        let Some(client) = &self.watch_client else {
+            bail!("The storage has no watch client, the presence cannot be registered.");
+        };
+        if ttl < 2 {
+            bail!("The presence TTL must be at least 2 seconds.");
+        }
+        let mut client = client.clone();
+        let value = serde_json::to_vec(info)?;
+        let runtime = get_or_init_async_runtime();
+        let lease = runtime.block_on(put_with_lease(&mut client, key, &value, ttl))?;
+        let lease = Arc::new(AtomicI64::new(lease));
+        let task = runtime.spawn(keep_presence(
+            client.clone(),
+            key.to_string(),
+            value,
+            ttl,
+            lease.clone(),
+        ));
+        Ok(Presence {
+            task,
+            lease,
+            client,
+        })
+    }
+
+    /// Returns the checksum and the raw value of the key.
+    ///
+    pub fn get_raw(&self, key: &str) -> Result<Option<(u32, Vec<u8>)>> {
+        if let (ParameterSource::Cache(snapshot, _), None) = (&self.source, self.backend()) {
+            return Ok(snapshot
+                .get(key)
+                .map(|data| (crc32fast::hash(data), data.to_vec())));
+        }
+        self.with_connection(|connection| {
+            Ok(connection
+                .storage
+                .get_data(key)?
+                .map(|(checksum, data)| (checksum, data.to_vec())))
+        })
+    }
+
+    pub fn set_raw(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        self.with_connection(|connection| {
+            get_or_init_async_runtime().block_on(connection.writer.kv_operations(vec![
+                Operation::Set {
+                    key: key.to_string(),
+                    value,
+                    with_lease: false,
+                },
+            ]))?;
+            Ok(())
+        })
+    }
+
+    fn get_decoded<T: 'static>(
+        &self,
+        key: &str,
+        only_changed: bool,
+        decode: impl FnOnce(&[u8]) -> Result<T>,
+    ) -> Result<Option<T>> {
+        let Some((checksum, data)) = self.get_raw(key)? else {
+            return Ok(None);
+        };
+        if only_changed && !self.checksums.is_changed::<T>(key, checksum) {
+            return Ok(None);
+        }
+        // the value failing to decode is reported again on the next read
+        let value = decode(&data)?;
+        self.checksums.update::<T>(key, checksum);
+        Ok(Some(value))
+    }
+
+    pub fn get_json<T: DeserializeOwned + 'static>(&self, key: &str) -> Result<Option<T>> {
+        self.get_decoded(key, false, |data| Ok(serde_json::from_slice(data)?))
+    }
+
+    /// Returns the value only when it changed since the last read of the key as `T`.
+    ///
+    pub fn get_json_if_changed<T: DeserializeOwned + 'static>(
+        &self,
+        key: &str,
+    ) -> Result<Option<T>> {
+        self.get_decoded(key, true, |data| Ok(serde_json::from_slice(data)?))
+    }
+
+    pub fn set_json<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        self.set_raw(key, serde_json::to_vec(value)?)
+    }
+
+    pub fn get_protobuf<T: prost::Message + Default + 'static>(
+        &self,
+        key: &str,
+    ) -> Result<Option<T>> {
+        self.get_decoded(key, false, |data| Ok(T::decode(data)?))
+    }
+
+    /// Returns the value only when it changed since the last read of the key as `T`.
+    ///
+    pub fn get_protobuf_if_changed<T: prost::Message + Default + 'static>(
+        &self,
+        key: &str,
+    ) -> Result<Option<T>> {
+        self.get_decoded(key, true, |data| Ok(T::decode(data)?))
+    }
+
+    pub fn set_protobuf<T: prost::Message>(&self, key: &str, value: &T) -> Result<()> {
+        self.set_raw(key, value.encode_to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_tracker() {
+        let tracker = ChecksumTracker::default();
+        assert!(tracker.is_changed::<String>("key", 1));
+        assert!(tracker.update::<String>("key", 1));
+        assert!(!tracker.is_changed::<String>("key", 1));
+        assert!(!tracker.update::<String>("key", 1));
+        // the other type has not seen the value yet
+        assert!(tracker.update::<i64>("key", 1));
+        assert!(tracker.update::<String>("key", 2));
+        assert!(tracker.update::<String>("other", 2));
+    }
+
+    fn test_snapshot() -> ParameterSnapshot {
+        let mut snapshot = ParameterSnapshot::default();
+        snapshot.insert("/savant/config", br#"{"threshold":0.5}"#.to_vec());
+        snapshot.insert("/savant/empty", vec![]);
+        snapshot
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() -> Result<()> {
+        let snapshot = test_snapshot();
+        let encoded = snapshot.encode();
+        assert_eq!(ParameterSnapshot::decode(&encoded)?, snapshot);
+
+        let mut damaged = encoded.clone();
+        damaged[CACHE_HEADER_SIZE + 6] ^= 0xff;
+        assert!(ParameterSnapshot::decode(&damaged).is_err());
+        assert!(ParameterSnapshot::decode(&encoded[..encoded.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_apply() {
+        let mut snapshot = test_snapshot();
+        snapshot.apply(&ParameterChange {
+            key: "/savant/empty".to_string(),
+            old: Some(vec![]),
+            new: None,
+        });
+        snapshot.apply(&ParameterChange {
+            key: "/savant/new".to_string(),
+            old: None,
+            new: Some(b"1".to_vec()),
+        });
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot.get("/savant/new"), Some(&b"1"[..]));
+        assert!(snapshot.get("/savant/empty").is_none());
+    }
+
+    #[test]
+    fn test_cache_writer() -> Result<()> {
+        let dir = Path::new("/tmp/test/parameter_storage");
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join("writer.bin");
+        std::fs::remove_file(&path).unwrap_or_default();
+        let snapshot = Arc::new(Mutex::new(test_snapshot()));
+        let (sender, receiver) = crossbeam::channel::unbounded();
+        let writer = {
+            let (snapshot, path) = (snapshot.clone(), path.clone());
+            thread::spawn(move || write_cache(snapshot, receiver, path))
+        };
+        for i in 0..10 {
+            snapshot.lock().insert("/savant/counter", vec![i]);
+            sender.send(())?;
+        }
+        // the writer stops when the sender is dropped
+        drop(sender);
+        writer.join().unwrap();
+        let saved = ParameterSnapshot::load(&path)?;
+        assert_eq!(saved.get("/savant/counter"), Some(&[9u8][..]));
+        assert_eq!(saved, *snapshot.lock());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconnect_backoff() {
+        assert_eq!(reconnect_backoff(0), Duration::from_millis(500));
+        assert_eq!(reconnect_backoff(2), Duration::from_secs(2));
+        assert_eq!(reconnect_backoff(6), Duration::from_secs(30));
+        assert_eq!(reconnect_backoff(100), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_presence_info() -> Result<()> {
+        let info = PresenceInfo::new("detector");
+        assert_eq!(info.version, crate::version());
+        let value = serde_json::to_vec(&info)?;
+        assert_eq!(serde_json::from_slice::<PresenceInfo>(&value)?, info);
+        assert_eq!(keep_alive_interval(3), Duration::from_secs(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_cache() -> Result<()> {
+        let dir = Path::new("/tmp/test/parameter_storage");
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join("cache.bin");
+        test_snapshot().save(&path)?;
+
+        let storage = TypedParameterStorage::from_cache(&path)?;
+        assert!(storage.is_stale());
+        assert_eq!(storage.state(), ConnectionState::Cached);
+        let value = storage.get_json::<serde_json::Value>("/savant/config")?;
+        assert_eq!(value.unwrap()["threshold"], 0.5);
+        assert!(storage
+            .get_json_if_changed::<serde_json::Value>("/savant/config")?
+            .is_none());
+        assert!(storage.get_raw("/savant/missing")?.is_none());
+        for _ in 0..2 {
+            assert!(storage
+                .get_json_if_changed::<serde_json::Value>("/savant/empty")
+                .is_err());
+        }
+        assert!(storage.set_raw("/savant/config", vec![]).is_err());
+        assert!(storage.subscribe("/savant", |_| {}).is_err());
+        assert!(storage
+            .register_presence("/savant/modules/test", 10, &PresenceInfo::new("test"))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_reconnect() -> Result<()> {
+        let dir = Path::new("/tmp/test/parameter_storage");
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join("cache-reconnect.bin");
+        test_snapshot().save(&path)?;
+
+        let settings = ConnectSettings::new(&["127.0.0.1:1"], &None, &None, "/savant", 1);
+        let storage = TypedParameterStorage::load_cache(&path, Some(settings))?;
+        let ParameterSource::Cache(_, Some(backend)) = &storage.source else {
+            unreachable!("The storage must keep the backend");
+        };
+        assert!(matches!(
+            backend.state(),
+            ConnectionState::Reconnecting { .. }
+        ));
+        // the values are served from the cache while etcd is unreachable
+        assert!(storage.is_stale());
+        assert_eq!(storage.state(), ConnectionState::Cached);
+        assert!(storage.get_raw("/savant/config")?.is_some());
+        assert!(storage.set_raw("/savant/config", vec![]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use super::{keep_lease, TypedParameterStorage};
+use crate::get_or_init_async_runtime;
+use anyhow::{anyhow, bail, Result};
+use etcd_client::{Client, LeaderKey, LockOptions, ResignOptions};
+use log::warn;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// The lease renewed in the background and revoked on drop. The lock and the election keys are
+/// attached to the lease, so they are released with it, also when the process dies.
+///
+struct HeldLease {
+    id: i64,
+    client: Client,
+    task: JoinHandle<()>,
+    lost: Arc<AtomicBool>,
+}
+
+impl HeldLease {
+    fn grant(storage: &TypedParameterStorage, ttl: i64) -> Result<Self> {
+        let Some(client) = &storage.watch_client else {
+            bail!("The storage has no watch client, the lease cannot be granted.");
+        };
+        if ttl < 2 {
+            bail!("The lease TTL must be at least 2 seconds.");
+        }
+        let mut client = client.clone();
+        let runtime = get_or_init_async_runtime();
+        let id = runtime.block_on(client.lease_grant(ttl, None))?.id();
+        let lost = Arc::new(AtomicBool::new(false));
+        let task = runtime.spawn({
+            let mut client = client.clone();
+            let lost = lost.clone();
+            async move {
+                // unlike the presence, the lost lease is not granted again: the lock could be
+                // taken by the other holder in between
+                if let Err(e) = keep_lease(&mut client, id, ttl).await {
+                    warn!(
+                        target: "savant_rs::parameter_storage",
+                        "The lease {} is lost: {:?}", id, e
+                    );
+                }
+                lost.store(true, Ordering::SeqCst);
+            }
+        });
+        Ok(Self {
+            id,
+            client,
+            task,
+            lost,
+        })
+    }
+
+    fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for HeldLease {
+    fn drop(&mut self) {
+        self.task.abort();
+        let id = self.id;
+        let mut client = self.client.clone();
+        get_or_init_async_runtime().spawn(async move {
+            if let Err(e) = client.lease_revoke(id).await {
+                warn!(
+                    target: "savant_rs::parameter_storage",
+                    "Failed to revoke the lease {}: {:?}", id, e
+                );
+            }
+        });
+    }
+}
+
+/// The distributed lock for the exclusive tasks, e.g. the single writer to an archive. The lock
+/// is released when dropped or when the lease expires.
+///
+pub struct EtcdLock {
+    key: Vec<u8>,
+    lease: HeldLease,
+}
+
+impl EtcdLock {
+    /// Waits until the lock is acquired.
+    ///
+    pub fn acquire(storage: &TypedParameterStorage, name: &str, ttl: i64) -> Result<Self> {
+        Self::try_acquire(storage, name, ttl, None)?
+            .ok_or_else(|| anyhow!("The lock {} is not acquired.", name))
+    }
+
+    /// Returns `None` when the lock is not acquired within the timeout.
+    ///
+    pub fn try_acquire(
+        storage: &TypedParameterStorage,
+        name: &str,
+        ttl: i64,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Self>> {
+        let lease = HeldLease::grant(storage, ttl)?;
+        let mut client = lease.client.clone();
+        let options = LockOptions::new().with_lease(lease.id);
+        let lock = client.lock(name, Some(options));
+        let response = get_or_init_async_runtime().block_on(async move {
+            match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, lock).await.ok(),
+                None => Some(lock.await),
+            }
+        });
+        match response {
+            Some(response) => Ok(Some(Self {
+                key: response?.key().to_vec(),
+                lease,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns `false` when the lease has expired, so the lock may be held by another owner.
+    ///
+    pub fn is_held(&self) -> bool {
+        !self.lease.is_lost()
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn release(self) -> Result<()> {
+        let mut client = self.lease.client.clone();
+        get_or_init_async_runtime().block_on(client.unlock(self.key.clone()))?;
+        Ok(())
+    }
+}
+
+/// The leader election: the campaign blocks until the candidate becomes the leader, the
+/// leadership is resigned when dropped or when the lease expires.
+///
+pub struct LeaderElection {
+    leader: LeaderKey,
+    lease: HeldLease,
+}
+
+impl LeaderElection {
+    /// Waits until the candidate announcing the value becomes the leader of the election.
+    ///
+    pub fn campaign(
+        storage: &TypedParameterStorage,
+        name: &str,
+        value: &[u8],
+        ttl: i64,
+    ) -> Result<Self> {
+        let lease = HeldLease::grant(storage, ttl)?;
+        let mut client = lease.client.clone();
+        let response =
+            get_or_init_async_runtime().block_on(client.campaign(name, value, lease.id))?;
+        let Some(leader) = response.leader().cloned() else {
+            bail!("The campaign of {} returned no leader key.", name);
+        };
+        Ok(Self { leader, lease })
+    }
+
+    /// Returns the value announced by the current leader of the election.
+    ///
+    pub fn leader(storage: &TypedParameterStorage, name: &str) -> Result<Option<Vec<u8>>> {
+        let Some(client) = &storage.watch_client else {
+            bail!("The storage has no watch client, the leader cannot be read.");
+        };
+        let mut client = client.clone();
+        let response = get_or_init_async_runtime().block_on(client.leader(name))?;
+        Ok(response.kv().map(|kv| kv.value().to_vec()))
+    }
+
+    pub fn is_leader(&self) -> bool {
+        !self.lease.is_lost()
+    }
+
+    /// Gives up the leadership, the next candidate becomes the leader.
+    ///
+    pub fn resign(self) -> Result<()> {
+        let mut client = self.lease.client.clone();
+        let options = ResignOptions::new().with_leader(self.leader.clone());
+        get_or_init_async_runtime().block_on(client.resign(Some(options)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameter_storage::ParameterSnapshot;
+    use std::path::Path;
+
+    #[test]
+    fn test_requires_etcd() -> Result<()> {
+        let dir = Path::new("/tmp/test/parameter_storage");
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join("coordination.bin");
+        ParameterSnapshot::default().save(&path)?;
+        let storage = TypedParameterStorage::from_cache(&path)?;
+        assert!(EtcdLock::acquire(&storage, "archive", 10).is_err());
+        assert!(LeaderElection::campaign(&storage, "archive", b"node-1", 10).is_err());
+        assert!(LeaderElection::leader(&storage, "archive").is_err());
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use std::time::SystemTime;
 use anyhow::Result;
 use hashbrown::HashMap;
 use opentelemetry::Context;
+use serde::{Deserialize, Serialize};
 
 pub use implementation::PipelineConfiguration;
 pub use implementation::PipelineConfigurationBuilder;
@@ -19,6 +20,9 @@ use crate::webserver::{register_pipeline, unregister_pipeline};
 
 const MAX_TRACKED_STREAMS: usize = 8192; // defines how many streams are tracked for the frame ordering
 
+pub mod ordered_egress;
+pub mod snapshot;
+pub mod spec;
 pub mod stage;
 pub mod stage_function_loader;
 pub mod stage_plugin_sample;
@@ -36,7 +40,7 @@ pub trait PipelineStageFunction: Send {
     ) -> Result<()>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PipelineStageFunctionOrder {
     Ingress,
     Egress,
@@ -51,7 +55,83 @@ pub struct PluginParams {
 pub type PipelineStageFunctionFactory =
     fn(name: &str, parameters: PluginParams) -> *mut (dyn PipelineStageFunction);
 
-#[derive(Clone, Debug, PartialEq)]
+/// The name of the symbol every plugin library must export to be loaded. The symbol is
+/// generated with the [`export_plugin_abi`](crate::export_plugin_abi) macro.
+///
+pub const PLUGIN_ABI_SYMBOL: &str = "savant_plugin_abi";
+
+pub type PluginAbiFunction = fn() -> PluginAbi;
+
+/// Describes the version of savant_core a plugin was built against and the layout of the
+/// structures crossing the host/plugin boundary. The host refuses to load plugins which
+/// report an ABI different from its own.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginAbi {
+    pub version: &'static str,
+    pub layout_hash: u64,
+}
+
+impl PluginAbi {
+    /// Must stay `const`: when expanded in a plugin, the value is computed at the plugin
+    /// compile time, so it reflects the savant_core the plugin was built with.
+    ///
+    pub const fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            layout_hash: plugin_layout_hash(),
+        }
+    }
+}
+
+const fn plugin_layout_hash() -> u64 {
+    use std::mem::{align_of, size_of};
+    const LAYOUT: [usize; 16] = [
+        size_of::<PluginParams>(),
+        align_of::<PluginParams>(),
+        size_of::<PipelinePayload>(),
+        align_of::<PipelinePayload>(),
+        size_of::<VideoFrameProxy>(),
+        align_of::<VideoFrameProxy>(),
+        size_of::<VideoFrameBatch>(),
+        align_of::<VideoFrameBatch>(),
+        size_of::<VideoFrameUpdate>(),
+        align_of::<VideoFrameUpdate>(),
+        size_of::<AttributeValue>(),
+        align_of::<AttributeValue>(),
+        size_of::<Pipeline>(),
+        align_of::<Pipeline>(),
+        size_of::<PipelineStage>(),
+        align_of::<PipelineStage>(),
+    ];
+    // FNV-1a
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < LAYOUT.len() {
+        hash ^= LAYOUT[i] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// Exports the `savant_plugin_abi` symbol required by
+/// [`load_stage_function_plugin`](crate::pipeline::stage_function_loader::load_stage_function_plugin).
+/// Must be invoked once in every plugin library.
+///
+#[macro_export]
+macro_rules! export_plugin_abi {
+    () => {
+        #[no_mangle]
+        pub fn savant_plugin_abi() -> $crate::pipeline::PluginAbi {
+            const ABI: $crate::pipeline::PluginAbi = $crate::pipeline::PluginAbi::current();
+            ABI
+        }
+    };
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PipelineStagePayloadType {
     Frame,
     Batch,
@@ -95,6 +175,12 @@ impl Pipeline {
         Ok(p)
     }
 
+    /// Creates the pipeline from the JSON or YAML specification, see [`spec::PipelineSpec`].
+    ///
+    pub fn from_spec(spec: &str, configuration: PipelineConfiguration) -> Result<Self> {
+        spec::PipelineSpec::from_yaml(spec)?.build(configuration)
+    }
+
     pub fn set_name(&self, name: String) -> Result<()> {
         self.0.set_name(name)
     }
@@ -182,6 +268,43 @@ impl Pipeline {
         self.0.get_stage_queue_len(stage)
     }
 
+    pub fn get_stage_occupancy(&self, stage: &str) -> Result<stage::StageOccupancy> {
+        self.0.get_stage_occupancy(stage)
+    }
+
+    pub fn get_occupancy(&self) -> Vec<stage::StageOccupancy> {
+        self.0.get_occupancy()
+    }
+
+    pub fn stage_stats(&self, stage: &str) -> Result<stats::StageResidencyStats> {
+        self.0.stage_stats(stage)
+    }
+
+    pub fn snapshot(&self) -> snapshot::PipelineSnapshot {
+        self.0.snapshot()
+    }
+
+    /// Restores the payloads of the snapshot, see [`PipelineSnapshot`](snapshot::PipelineSnapshot).
+    /// The temporary attributes, the track histories of the objects and the change journals
+    /// are not kept in the snapshot.
+    ///
+    pub fn restore(&self, snapshot: &snapshot::PipelineSnapshot) -> Result<usize> {
+        self.0.restore(snapshot)
+    }
+
+    /// Saves the frames in processing to the file, so they can be restored with
+    /// [`Pipeline::restore_snapshot`] after a restart.
+    ///
+    pub fn save_snapshot(&self, path: &str) -> Result<()> {
+        self.snapshot().save(std::path::Path::new(path))
+    }
+
+    pub fn restore_snapshot(&self, path: &str) -> Result<usize> {
+        self.restore(&snapshot::PipelineSnapshot::load(std::path::Path::new(
+            path,
+        ))?)
+    }
+
     pub fn get_independent_frame(&self, frame_id: i64) -> Result<(VideoFrameProxy, Context)> {
         self.0.get_independent_frame(frame_id)
     }
@@ -226,6 +349,13 @@ impl Pipeline {
         self.0.access_objects(frame_id, query)
     }
 
+    /// Returns the destination of the first route of the object's stage whose query matches
+    /// any object of the frame (or of the batch), `None` when no route matches.
+    ///
+    pub fn route(&self, id: i64) -> Result<Option<String>> {
+        self.0.route(id)
+    }
+
     pub fn get_id_locations_len(&self) -> usize {
         self.0.get_id_locations_len()
     }
@@ -233,6 +363,12 @@ impl Pipeline {
     pub fn get_keyframe_history(&self, frame: &VideoFrameProxy) -> Option<Vec<(u128, i64)>> {
         self.0.get_keyframe_history(frame)
     }
+
+    pub fn get_stage_function_stats(
+        &self,
+    ) -> Vec<(String, PipelineStageFunctionOrder, stage::StageFunctionStat)> {
+        self.0.get_stage_function_stats()
+    }
 }
 
 impl Drop for Pipeline {
@@ -246,29 +382,49 @@ pub(super) mod implementation {
     use std::num::NonZeroUsize;
     use std::sync::atomic::{AtomicI64, Ordering};
     use std::sync::OnceLock;
-    use std::time::SystemTime;
+    use std::time::{Instant, SystemTime};
 
     use anyhow::{anyhow, bail, Result};
     use derive_builder::Builder;
-    use hashbrown::HashMap;
+    use hashbrown::{HashMap, HashSet};
     use lru::LruCache;
     use opentelemetry::trace::{SpanBuilder, TraceContextExt, Tracer};
     use opentelemetry::{Context, KeyValue};
+    use parking_lot::RwLockWriteGuard;
 
     use crate::get_tracer;
     use crate::match_query::MatchQuery;
-    use crate::pipeline::stage::PipelineStage;
-    use crate::pipeline::stats::{FrameProcessingStatRecord, Stats};
+    use crate::otlp::PropagatedContext;
+    use crate::pipeline::snapshot::{
+        FrameSnapshot, PayloadSnapshot, PipelineSnapshot, StageSnapshot,
+    };
+    use crate::pipeline::stage::{
+        PayloadMap, PipelineStage, StageCapacity, StageFunctionStat, StageOccupancy,
+        StageOverflowPolicy, StageRoute,
+    };
+    use crate::pipeline::stats::{FrameProcessingStatRecord, StageResidencyStats, Stats};
     use crate::pipeline::{
-        PipelinePayload, PipelineStageFunction, PipelineStagePayloadType, MAX_TRACKED_STREAMS,
+        PipelinePayload, PipelineStageFunction, PipelineStageFunctionOrder,
+        PipelineStagePayloadType, MAX_TRACKED_STREAMS,
     };
     use crate::primitives::frame::VideoFrameProxy;
     use crate::primitives::frame_batch::VideoFrameBatch;
     use crate::primitives::frame_update::VideoFrameUpdate;
-    use crate::primitives::object::BorrowedVideoObject;
+    use crate::primitives::object::{BorrowedVideoObject, ObjectOperations};
     use crate::rwlock::SavantRwLock;
+    use savant_protobuf::generated;
 
     const DEFAULT_ROOT_SPAN_NAME: &str = "video_pipeline";
+    const OBJECT_COUNT_METRIC_NAME: &str = "pipeline_object_count";
+    const FRAME_LOCATION_SHARDS: usize = 16;
+
+    type RestoredFrame = (
+        i64,
+        VideoFrameProxy,
+        Vec<VideoFrameUpdate>,
+        PropagatedContext,
+    );
+    type RestoredStage = (usize, Vec<(i64, bool, Vec<RestoredFrame>)>);
 
     #[derive(Builder, Default, Debug, Clone)]
     pub struct PipelineConfiguration {
@@ -282,6 +438,84 @@ pub(super) mod implementation {
         pub collection_history: usize,
         #[builder(default = "60")]
         pub keyframe_history: usize,
+        #[builder(default = "None")]
+        pub stage_function_quarantine_threshold: Option<usize>,
+        /// When set, the objects of the frames entering the stage are counted per
+        /// `(source_id, namespace, label)` and exported with the `pipeline_object_count` metric.
+        ///
+        #[builder(default = "None")]
+        pub object_count_metrics_stage: Option<String>,
+        /// The capacities of the stages by the stage name, the stages not listed are unbounded.
+        ///
+        #[builder(default)]
+        pub stage_capacity: HashMap<String, StageCapacity>,
+        /// When set, every payload leaving a stage exports a `{stage}/residency` span covering
+        /// the time it spent in the stage.
+        ///
+        #[builder(default = "false")]
+        pub stage_residency_spans: bool,
+        /// The routes of the stages by the stage name, checked in order by
+        /// [`Pipeline::route`](super::Pipeline::route).
+        ///
+        #[builder(default)]
+        pub stage_routes: HashMap<String, Vec<StageRoute>>,
+    }
+
+    /// The index of the stage holding every object (frame or batch), sharded by the object id,
+    /// so the threads working on different objects do not contend for the same lock.
+    ///
+    #[derive(Debug)]
+    struct FrameLocations {
+        shards: Vec<SavantRwLock<HashMap<i64, usize>>>,
+    }
+
+    impl Default for FrameLocations {
+        fn default() -> Self {
+            Self {
+                shards: (0..FRAME_LOCATION_SHARDS)
+                    .map(|_| SavantRwLock::new(HashMap::new()))
+                    .collect(),
+            }
+        }
+    }
+
+    impl FrameLocations {
+        fn shard(&self, id: i64) -> &SavantRwLock<HashMap<i64, usize>> {
+            &self.shards[id.rem_euclid(FRAME_LOCATION_SHARDS as i64) as usize]
+        }
+
+        fn get(&self, id: i64) -> Option<usize> {
+            self.shard(id).read().get(&id).copied()
+        }
+
+        fn insert(&self, id: i64, index: usize) {
+            self.shard(id).write().insert(id, index);
+        }
+
+        fn remove(&self, id: i64) -> Option<usize> {
+            self.shard(id).write().remove(&id)
+        }
+
+        fn len(&self) -> usize {
+            self.shards.iter().map(|s| s.read().len()).sum()
+        }
+    }
+
+    /// Locks the payloads of the source and the destination stages of a move. The destination
+    /// never precedes the source, so the stages are always locked in the order of their ids; a
+    /// move within the same stage locks it once.
+    ///
+    fn lock_stages<'a>(
+        source: &'a PipelineStage,
+        dest: &'a PipelineStage,
+    ) -> (
+        RwLockWriteGuard<'a, PayloadMap>,
+        Option<RwLockWriteGuard<'a, PayloadMap>>,
+    ) {
+        debug_assert!(source.id <= dest.id);
+        let source_bind = source.lock_payload();
+        let dest_bind = (dest.id != source.id).then(|| dest.lock_payload());
+        (source_bind, dest_bind)
     }
 
     #[derive(Debug)]
@@ -291,7 +525,7 @@ pub(super) mod implementation {
         frame_counter: AtomicI64,
         root_spans: SavantRwLock<HashMap<i64, Context>>,
         stages: Vec<PipelineStage>,
-        frame_locations: SavantRwLock<HashMap<i64, usize>>,
+        frame_locations: FrameLocations,
         frame_ordering: SavantRwLock<LruCache<String, i64>>,
         keyframe_tracking: SavantRwLock<LruCache<u64, u128>>,
         keyframe_history: SavantRwLock<LruCache<u64, VecDeque<(u128, i64)>>>,
@@ -309,7 +543,7 @@ pub(super) mod implementation {
                 frame_counter: AtomicI64::new(0),
                 root_spans: SavantRwLock::new(HashMap::new()),
                 stages: Vec::new(),
-                frame_locations: SavantRwLock::new(HashMap::new()),
+                frame_locations: FrameLocations::default(),
                 frame_ordering: SavantRwLock::new(LruCache::new(
                     NonZeroUsize::try_from(MAX_TRACKED_STREAMS).unwrap(),
                 )),
@@ -360,13 +594,18 @@ pub(super) mod implementation {
                 bail!("Stage with name {} already exists", name)
             }
 
-            let stage = PipelineStage::new(
+            let mut stage = PipelineStage::new(
                 self.stages.len(),
                 name,
                 stage_type,
                 ingress_function,
                 egress_function,
             );
+            stage.set_function_quarantine_threshold(
+                self.configuration.stage_function_quarantine_threshold,
+            );
+            stage.set_capacity(self.configuration.stage_capacity.get(&stage.name).cloned());
+            stage.set_residency_spans(self.configuration.stage_residency_spans);
             let stat = stage.get_stat();
             self.stats.add_stage_stats(stat);
             self.stages.push(stage);
@@ -383,6 +622,34 @@ pub(super) mod implementation {
             )>,
             configuration: PipelineConfiguration,
         ) -> Result<Self> {
+            for (name, capacity) in &configuration.stage_capacity {
+                if !stages.iter().any(|(n, ..)| n == name) {
+                    bail!("Capacity is configured for the unknown stage {}", name)
+                }
+                if capacity.capacity == 0 {
+                    bail!("The capacity of the stage {} must be greater than 0", name)
+                }
+            }
+            for (name, routes) in &configuration.stage_routes {
+                let index = stages.iter().position(|(n, ..)| n == name).ok_or_else(|| {
+                    anyhow!("Routes are configured for the unknown stage {}", name)
+                })?;
+                for route in routes {
+                    match stages.iter().position(|(n, ..)| n == &route.destination) {
+                        None => bail!(
+                            "The stage {} routes to the unknown stage {}",
+                            name,
+                            route.destination
+                        ),
+                        Some(dest) if dest <= index => bail!(
+                            "The stage {} routes to the stage {} which does not follow it",
+                            name,
+                            route.destination
+                        ),
+                        _ => {}
+                    }
+                }
+            }
             let stats = Stats::new(
                 configuration.collection_history,
                 configuration.frame_period,
@@ -413,7 +680,20 @@ pub(super) mod implementation {
         }
 
         pub fn get_id_locations_len(&self) -> usize {
-            self.frame_locations.read().len()
+            self.frame_locations.len()
+        }
+
+        pub fn get_stage_function_stats(
+            &self,
+        ) -> Vec<(String, PipelineStageFunctionOrder, StageFunctionStat)> {
+            self.stages
+                .iter()
+                .flat_map(|s| {
+                    s.get_function_stats()
+                        .into_iter()
+                        .map(|(order, stat)| (s.name.clone(), order, stat))
+                })
+                .collect()
         }
 
         pub fn set_root_span_name(&self, name: String) -> Result<()> {
@@ -443,6 +723,21 @@ pub(super) mod implementation {
                 .get_or_init(|| DEFAULT_ROOT_SPAN_NAME.to_owned())
         }
 
+        fn create_root_span(&self, id: i64, parent_ctx: &Context) {
+            if !parent_ctx.span().span_context().is_valid() {
+                self.root_spans.write().insert(id, Context::default());
+            } else {
+                let span = get_tracer().build_with_context(
+                    SpanBuilder::from_name(self.get_root_span_name().clone()),
+                    parent_ctx,
+                );
+
+                self.root_spans
+                    .write()
+                    .insert(id, Context::current_with_span(span));
+            }
+        }
+
         fn get_stage_span(&self, id: i64, span_name: String) -> Context {
             let bind = self.root_spans.read();
             let ctx = bind.get(&id).unwrap();
@@ -475,16 +770,9 @@ pub(super) mod implementation {
         }
 
         pub fn add_frame_update(&self, frame_id: i64, update: VideoFrameUpdate) -> Result<()> {
-            let cur_stage = self.get_stage_for_id(frame_id)?;
-            if let Some(stage) = self.stages.get(cur_stage) {
-                stage.add_frame_update(frame_id, update)
-            } else {
-                bail!(
-                    "Stage ID={} not found when adding update to frame {}",
-                    cur_stage,
-                    frame_id
-                )
-            }
+            self.with_stage_of(frame_id, |stage| {
+                stage.add_frame_update(frame_id, update.clone())
+            })
         }
 
         pub fn add_batched_frame_update(
@@ -493,17 +781,9 @@ pub(super) mod implementation {
             frame_id: i64,
             update: VideoFrameUpdate,
         ) -> Result<()> {
-            let cur_stage = self.get_stage_for_id(batch_id)?;
-            if let Some(stage) = self.stages.get(cur_stage) {
-                stage.add_batched_frame_update(batch_id, frame_id, update)
-            } else {
-                bail!(
-                    "Stage ID={} not found when adding update to frame {} in batch {}",
-                    cur_stage,
-                    frame_id,
-                    batch_id
-                )
-            }
+            self.with_stage_of(batch_id, |stage| {
+                stage.add_batched_frame_update(batch_id, frame_id, update.clone())
+            })
         }
 
         fn find_stage(
@@ -573,67 +853,62 @@ pub(super) mod implementation {
             ) {
                 bail!("Stage does not accept batched frames")
             }
+            let (index, stage) = self.find_stage(stage_name, 0)?;
+            let (mut bind, _, dropped) = self.lock_admitted(stage, stage, |_| Ok(1))?;
 
             self.frame_counter.fetch_add(1, Ordering::SeqCst);
             let id_counter = self.id_counter.fetch_add(1, Ordering::SeqCst) + 1;
             let source_id = frame.get_source_id();
 
-            if !parent_ctx.span().span_context().is_valid() {
-                self.root_spans
-                    .write()
-                    .insert(id_counter, Context::default());
-            } else {
-                let span = get_tracer().build_with_context(
-                    SpanBuilder::from_name(self.get_root_span_name().clone()),
-                    &parent_ctx,
-                );
-
-                self.root_spans
-                    .write()
-                    .insert(id_counter, Context::current_with_span(span));
-            }
+            self.create_root_span(id_counter, &parent_ctx);
             let source_id_compatibility_hash = frame.stream_compatibility_hash();
-            let mut ordering = self.frame_ordering.write();
-            let prev_ordering_seq = ordering.get(&source_id);
-            if let Some(prev) = prev_ordering_seq {
-                frame.set_previous_frame_seq_id(Some(*prev));
-            } else {
-                frame.set_previous_frame_seq_id(None);
-            }
-            ordering.put(source_id, id_counter);
+            {
+                let mut ordering = self.frame_ordering.write();
+                let prev_ordering_seq = ordering.get(&source_id);
+                if let Some(prev) = prev_ordering_seq {
+                    frame.set_previous_frame_seq_id(Some(*prev));
+                } else {
+                    frame.set_previous_frame_seq_id(None);
+                }
+                ordering.put(source_id, id_counter);
 
-            let mut keyframe_tracking = self.keyframe_tracking.write();
-            let mut keyframe_history = self.keyframe_history.write();
-            let last_keyframe = keyframe_tracking.get(&source_id_compatibility_hash);
-            if let Some(last) = last_keyframe {
-                frame.set_previous_keyframe(Some(*last));
-            } else {
-                frame.set_previous_keyframe(None);
-            }
-            if let Some(true) = frame.get_keyframe() {
-                keyframe_tracking.put(source_id_compatibility_hash, frame.get_uuid_u128());
-            }
+                let mut keyframe_tracking = self.keyframe_tracking.write();
+                let mut keyframe_history = self.keyframe_history.write();
+                let last_keyframe = keyframe_tracking.get(&source_id_compatibility_hash);
+                if let Some(last) = last_keyframe {
+                    frame.set_previous_keyframe(Some(*last));
+                } else {
+                    frame.set_previous_keyframe(None);
+                }
+                if let Some(true) = frame.get_keyframe() {
+                    keyframe_tracking.put(source_id_compatibility_hash, frame.get_uuid_u128());
+                }
 
-            let history_entry = keyframe_history.get_mut(&source_id_compatibility_hash);
-            if let Some(h) = history_entry {
-                h.push_back((frame.get_uuid_u128(), frame.get_pts()));
-                if h.len() > self.configuration.keyframe_history {
-                    h.pop_front();
+                let history_entry = keyframe_history.get_mut(&source_id_compatibility_hash);
+                if let Some(h) = history_entry {
+                    h.push_back((frame.get_uuid_u128(), frame.get_pts()));
+                    if h.len() > self.configuration.keyframe_history {
+                        h.pop_front();
+                    }
+                } else {
+                    keyframe_history.put(
+                        source_id_compatibility_hash,
+                        VecDeque::from(vec![(frame.get_uuid_u128(), frame.get_pts())]),
+                    );
                 }
-            } else {
-                keyframe_history.put(
-                    source_id_compatibility_hash,
-                    VecDeque::from(vec![(frame.get_uuid_u128(), frame.get_pts())]),
-                );
             }
 
             let ctx = self.get_stage_span(id_counter, format!("add/{}", stage_name));
             let frame_payload =
                 PipelinePayload::Frame(frame, Vec::new(), ctx, None, SystemTime::now());
 
-            let (index, stage) = self.find_stage(stage_name, 0)?;
-            stage.add_frame_payload(id_counter, frame_payload)?;
-            self.frame_locations.write().insert(id_counter, index);
+            if let PipelinePayload::Frame(frame, ..) = &frame_payload {
+                self.record_object_counts(stage_name, [frame]);
+            }
+            stage.insert_payloads(&mut bind, [(id_counter, frame_payload)])?;
+            self.frame_locations.insert(id_counter, index);
+            drop(bind);
+            self.release_dropped(stage, dropped);
 
             log::trace!(target: "savant_rs::pipeline", "Added frame {} to stage {}", id_counter, stage_name);
             Ok(id_counter)
@@ -657,6 +932,43 @@ pub(super) mod implementation {
             Ok(())
         }
 
+        fn record_object_counts<'a>(
+            &self,
+            stage_name: &str,
+            frames: impl IntoIterator<Item = &'a VideoFrameProxy>,
+        ) {
+            if self.configuration.object_count_metrics_stage.as_deref() != Some(stage_name) {
+                return;
+            }
+            let mut counts: HashMap<(String, String, String), u64> = HashMap::new();
+            for frame in frames {
+                let source_id = frame.get_source_id();
+                for o in frame.get_all_objects() {
+                    *counts
+                        .entry((source_id.clone(), o.get_namespace(), o.get_label()))
+                        .or_insert(0) += 1;
+                }
+            }
+            if counts.is_empty() {
+                return;
+            }
+            let pipeline_name = self.get_name().unwrap_or_default();
+            let counter = crate::metrics::get_or_create_counter_family(
+                OBJECT_COUNT_METRIC_NAME,
+                Some("Number of objects passed through the designated pipeline stage"),
+                &["pipeline_name", "source_id", "namespace", "label"],
+                None,
+            );
+            let mut counter = counter.lock();
+            for ((source_id, namespace, label), count) in counts {
+                if let Err(e) =
+                    counter.inc(count, &[&pipeline_name, &source_id, &namespace, &label])
+                {
+                    log::warn!(target: "savant_rs::pipeline", "Failed to update the object count metric: {}", e);
+                }
+            }
+        }
+
         fn add_frame_json(&self, frame: &VideoFrameProxy, ctx: &Context) {
             if self.configuration.append_frame_meta_to_otlp_span {
                 let json = frame.get_json();
@@ -665,56 +977,47 @@ pub(super) mod implementation {
         }
 
         pub fn delete(&self, id: i64) -> Result<HashMap<i64, Context>> {
-            let stage = self
-                .frame_locations
-                .write()
-                .remove(&id)
-                .ok_or(anyhow::anyhow!("Object {} location not found", id))?;
-
             self.stats.kick_off();
 
-            if let Some(stage) = self.stages.get(stage) {
+            let (stage, removed) = self.with_stage_of(id, |stage| {
                 log::trace!(target: "savant_rs::pipeline", "Delete object {} from the stage {}", id, stage.name);
-                let removed = stage.delete(id)?;
-                if removed.is_none() {
-                    bail!("Object {} is not found in the stage {}", id, stage.name)
+                match stage.delete(id)? {
+                    Some(payload) => Ok((stage, payload)),
+                    None => bail!("Object {} is not found in the stage {}", id, stage.name),
                 }
+            })?;
+            self.frame_locations.remove(id);
 
-                let mut bind = self.root_spans.write();
-                match removed.unwrap() {
-                    PipelinePayload::Frame(frame, _, ctx, _, _) => {
-                        self.stats.register_frame(frame.get_object_count());
-                        self.add_frame_json(&frame, &ctx);
-                        ctx.span().end();
-                        let root_ctx = bind.remove(&id).unwrap();
-                        Ok(HashMap::from([(id, root_ctx)]))
-                    }
-                    PipelinePayload::Batch(batch, _, contexts, _, _) => Ok({
-                        let mut bind = self.root_spans.write();
-                        contexts
-                            .into_iter()
-                            .map(|(frame_id, ctx)| {
-                                let frame_opt = batch.get(frame_id);
-                                if let Some(frame) = frame_opt {
-                                    self.stats.register_frame(frame.get_object_count());
-                                    self.add_frame_json(&frame, &ctx);
-                                } else {
-                                    bail!(
-                                        "Frame {} not found in batch {} in the stage {}",
-                                        frame_id,
-                                        id,
-                                        stage.name
-                                    )
-                                }
-                                ctx.span().end();
-                                let root_ctx = bind.remove(&id).unwrap();
-                                Ok((id, root_ctx))
-                            })
-                            .collect::<Result<HashMap<_, _>, _>>()?
-                    }),
+            let mut bind = self.root_spans.write();
+            match removed {
+                PipelinePayload::Frame(frame, _, ctx, _, _) => {
+                    self.stats.register_frame(frame.get_object_count());
+                    self.add_frame_json(&frame, &ctx);
+                    ctx.span().end();
+                    let root_ctx = bind.remove(&id).unwrap();
+                    Ok(HashMap::from([(id, root_ctx)]))
                 }
-            } else {
-                bail!("Stage ID={} not found (when removing object {})", stage, id)
+                PipelinePayload::Batch(batch, _, contexts, _, _) => contexts
+                    .into_iter()
+                    .map(|(frame_id, ctx)| {
+                        let frame_opt = batch.get(frame_id);
+                        if let Some(frame) = frame_opt {
+                            self.stats.register_frame(frame.get_object_count());
+                            self.add_frame_json(&frame, &ctx);
+                        } else {
+                            bail!(
+                                "Frame {} not found in batch {} in the stage {}",
+                                frame_id,
+                                id,
+                                stage.name
+                            )
+                        }
+                        ctx.span().end();
+                        self.frame_locations.remove(frame_id);
+                        let root_ctx = bind.remove(&frame_id).unwrap();
+                        Ok((frame_id, root_ctx))
+                    })
+                    .collect::<Result<HashMap<_, _>, _>>(),
             }
         }
 
@@ -723,35 +1026,425 @@ pub(super) mod implementation {
             Ok(stage.len())
         }
 
-        fn get_stage_for_id(&self, id: i64) -> Result<usize> {
-            let bind = self.frame_locations.read();
-            if let Some(stage) = bind.get(&id) {
-                Ok(*stage)
-            } else {
-                bail!("Object {} location not found.", id)
+        pub fn get_stage_occupancy(&self, stage: &str) -> Result<StageOccupancy> {
+            let (_, stage) = self.find_stage(stage, 0)?;
+            Ok(stage.get_occupancy())
+        }
+
+        pub fn get_occupancy(&self) -> Vec<StageOccupancy> {
+            self.stages.iter().map(|s| s.get_occupancy()).collect()
+        }
+
+        pub fn stage_stats(&self, stage: &str) -> Result<StageResidencyStats> {
+            let (_, stage) = self.find_stage(stage, 0)?;
+            Ok(stage.get_residency_stats())
+        }
+
+        /// Captures the payloads of all the stages. The stages are locked together, in the order
+        /// the moves lock them, so no payload is captured twice or missed while being moved.
+        ///
+        pub fn snapshot(&self) -> PipelineSnapshot {
+            let binds = self
+                .stages
+                .iter()
+                .map(|stage| stage.lock_payload())
+                .collect::<Vec<_>>();
+            let root_spans = self.root_spans.read();
+            let frame_snapshot = |id: i64, frame: &VideoFrameProxy, updates| FrameSnapshot {
+                id,
+                frame: Some(generated::VideoFrame::from(frame)),
+                updates,
+                root_context: root_spans
+                    .get(&id)
+                    .map(|ctx| PropagatedContext::inject(ctx).0)
+                    .unwrap_or_default(),
+            };
+            let stages = self
+                .stages
+                .iter()
+                .zip(binds.iter())
+                .map(|(stage, bind)| {
+                    let mut payloads = bind
+                        .iter()
+                        .map(|(id, payload)| match payload {
+                            PipelinePayload::Frame(frame, updates, ..) => PayloadSnapshot {
+                                id: *id,
+                                batch: false,
+                                frames: vec![frame_snapshot(
+                                    *id,
+                                    frame,
+                                    updates
+                                        .iter()
+                                        .map(generated::VideoFrameUpdate::from)
+                                        .collect(),
+                                )],
+                            },
+                            PipelinePayload::Batch(batch, updates, ..) => PayloadSnapshot {
+                                id: *id,
+                                batch: true,
+                                frames: batch
+                                    .frames
+                                    .iter()
+                                    .map(|(frame_id, frame)| {
+                                        let frame_updates = updates
+                                            .iter()
+                                            .filter(|(update_frame_id, _)| {
+                                                update_frame_id == frame_id
+                                            })
+                                            .map(|(_, update)| {
+                                                generated::VideoFrameUpdate::from(update)
+                                            })
+                                            .collect();
+                                        frame_snapshot(*frame_id, frame, frame_updates)
+                                    })
+                                    .collect(),
+                            },
+                        })
+                        .collect::<Vec<_>>();
+                    payloads.sort_by_key(|p| p.id);
+                    StageSnapshot {
+                        name: stage.name.clone(),
+                        payloads,
+                    }
+                })
+                .collect();
+            PipelineSnapshot {
+                id_counter: self.id_counter.load(Ordering::SeqCst),
+                stages,
+            }
+        }
+
+        /// Adds the payloads of the snapshot to the stages with the same names, keeping their
+        /// ids, and returns the number of the restored frames. The pipeline must be empty. The
+        /// whole snapshot is decoded and checked (the stages, their payload types and
+        /// capacities, the uniqueness of the ids) before anything is added, and the stages stay
+        /// locked while the payloads are added, so a failure leaves the pipeline empty. The
+        /// ingress functions of the stages are called for the restored payloads.
+        ///
+        /// The frames are stored with protobuf, so the restored frames and objects do not keep
+        /// the temporary attributes, the track histories of the objects and the change journals.
+        ///
+        pub fn restore(&self, snapshot: &PipelineSnapshot) -> Result<usize> {
+            let decoded = self.decode_snapshot(snapshot)?;
+
+            let mut binds = self
+                .stages
+                .iter()
+                .map(|stage| stage.lock_payload())
+                .collect::<Vec<_>>();
+            if self.frame_locations.len() > 0 || binds.iter().any(|bind| !bind.is_empty()) {
+                bail!("The snapshot can only be restored to an empty pipeline")
+            }
+            let mut ids = Vec::new();
+            let ordering = match self.insert_restored(&mut binds, decoded, &mut ids) {
+                Ok(ordering) => ordering,
+                Err(e) => {
+                    binds.iter_mut().for_each(|bind| bind.clear());
+                    let mut root_spans = self.root_spans.write();
+                    for id in ids {
+                        self.frame_locations.remove(id);
+                        if let Some(root_ctx) = root_spans.remove(&id) {
+                            root_ctx.span().end();
+                        }
+                    }
+                    return Err(e);
+                }
+            };
+            drop(binds);
+
+            let restored = ordering.len();
+            let max_id = ordering.iter().map(|(id, _)| *id).max().unwrap_or_default();
+            let mut frame_ordering = self.frame_ordering.write();
+            for (id, source_id) in ordering {
+                frame_ordering.put(source_id, id);
+            }
+            self.id_counter
+                .fetch_max(max_id.max(snapshot.id_counter), Ordering::SeqCst);
+            log::info!(target: "savant_rs::pipeline", "Restored {} frames from the snapshot", restored);
+            Ok(restored)
+        }
+
+        fn decode_snapshot(&self, snapshot: &PipelineSnapshot) -> Result<Vec<RestoredStage>> {
+            let mut ids = HashSet::new();
+            let mut stage_payloads = HashMap::new();
+            let mut decoded = Vec::with_capacity(snapshot.stages.len());
+            for stage_snapshot in &snapshot.stages {
+                let (index, stage) = self.find_stage(&stage_snapshot.name, 0)?;
+                let count = stage_payloads.entry(index).or_insert(0);
+                *count += stage_snapshot.payloads.len();
+                if let Some(capacity) = stage.get_capacity() {
+                    if *count > capacity.capacity {
+                        bail!(
+                            "The snapshot holds {} payloads for the stage {} with the capacity {}",
+                            count,
+                            stage.name,
+                            capacity.capacity
+                        )
+                    }
+                }
+                let mut payloads = Vec::with_capacity(stage_snapshot.payloads.len());
+                for payload in &stage_snapshot.payloads {
+                    let expected = if payload.batch {
+                        PipelineStagePayloadType::Batch
+                    } else {
+                        PipelineStagePayloadType::Frame
+                    };
+                    if stage.stage_type != expected {
+                        bail!(
+                            "Payload {} of type {:?} cannot be restored to the stage {} of type {:?}",
+                            payload.id,
+                            expected,
+                            stage.name,
+                            stage.stage_type
+                        )
+                    }
+                    if !payload.batch
+                        && (payload.frames.len() != 1 || payload.frames[0].id != payload.id)
+                    {
+                        bail!(
+                            "Frame payload {} must hold the frame with the same id",
+                            payload.id
+                        )
+                    }
+                    if payload.batch && !ids.insert(payload.id) {
+                        bail!("Id {} is used more than once in the snapshot", payload.id)
+                    }
+                    let frames = payload
+                        .frames
+                        .iter()
+                        .map(|f| {
+                            if !ids.insert(f.id) {
+                                bail!("Id {} is used more than once in the snapshot", f.id)
+                            }
+                            let frame = f
+                                .frame
+                                .as_ref()
+                                .ok_or_else(|| anyhow!("Frame {} has no content", f.id))?;
+                            let updates = f
+                                .updates
+                                .iter()
+                                .map(VideoFrameUpdate::try_from)
+                                .collect::<Result<Vec<_>, _>>()?;
+                            Ok((
+                                f.id,
+                                VideoFrameProxy::try_from(frame)?,
+                                updates,
+                                PropagatedContext(f.root_context.clone()),
+                            ))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    payloads.push((payload.id, payload.batch, frames));
+                }
+                decoded.push((index, payloads));
+            }
+            Ok(decoded)
+        }
+
+        /// Adds the decoded payloads to the locked stages, the ids of the added payloads and
+        /// frames are collected to roll back on failure. Returns the ids of the restored frames
+        /// with their sources, in the order of the ids.
+        ///
+        fn insert_restored(
+            &self,
+            binds: &mut [RwLockWriteGuard<'_, PayloadMap>],
+            decoded: Vec<RestoredStage>,
+            ids: &mut Vec<i64>,
+        ) -> Result<Vec<(i64, String)>> {
+            let mut ordering = Vec::new();
+            for (index, payloads) in decoded {
+                let stage = &self.stages[index];
+                let mut stage_payloads = Vec::with_capacity(payloads.len());
+                for (id, is_batch, frames) in payloads {
+                    let mut batch = VideoFrameBatch::with_capacity(frames.len());
+                    let mut batch_updates = Vec::new();
+                    let mut contexts = HashMap::with_capacity(frames.len());
+                    for (frame_id, frame, updates, root_context) in frames {
+                        ids.push(frame_id);
+                        ordering.push((frame_id, frame.get_source_id()));
+                        self.create_root_span(frame_id, &root_context.extract());
+                        contexts.insert(
+                            frame_id,
+                            self.get_stage_span(frame_id, format!("restore/{}", stage.name)),
+                        );
+                        self.frame_locations.insert(frame_id, index);
+                        batch.add(frame_id, frame);
+                        batch_updates.extend(updates.into_iter().map(|u| (frame_id, u)));
+                    }
+                    let payload = if is_batch {
+                        ids.push(id);
+                        self.frame_locations.insert(id, index);
+                        PipelinePayload::Batch(
+                            batch,
+                            batch_updates,
+                            contexts,
+                            None,
+                            vec![SystemTime::now()],
+                        )
+                    } else {
+                        let frame = batch.frames.remove(&id).expect("Checked while decoding");
+                        let ctx = contexts.remove(&id).expect("Checked while decoding");
+                        let updates = batch_updates.into_iter().map(|(_, u)| u).collect();
+                        PipelinePayload::Frame(frame, updates, ctx, None, SystemTime::now())
+                    };
+                    stage_payloads.push((id, payload));
+                }
+                stage.insert_payloads(&mut binds[index], stage_payloads)?;
+            }
+            ordering.sort_by_key(|(id, _)| *id);
+            Ok(ordering)
+        }
+
+        /// Checks the room for `count` payloads entering the locked stage according to its
+        /// overflow policy. Returns the payloads dropped to make room, or `None` when the caller
+        /// must wait for the room.
+        ///
+        fn try_admit(
+            stage: &PipelineStage,
+            bind: &mut PayloadMap,
+            count: usize,
+        ) -> Result<Option<Vec<(i64, PipelinePayload)>>> {
+            let Some(capacity) = stage.get_capacity() else {
+                return Ok(Some(Vec::new()));
+            };
+            if count > capacity.capacity {
+                stage.record_rejected();
+                bail!(
+                    "{} payloads cannot enter the stage {} with the capacity {}",
+                    count,
+                    stage.name,
+                    capacity.capacity
+                )
+            }
+            let len = bind.len();
+            if len + count <= capacity.capacity {
+                return Ok(Some(Vec::new()));
+            }
+            match capacity.policy {
+                StageOverflowPolicy::Reject => {
+                    stage.record_rejected();
+                    bail!(
+                        "The stage {} is full: {} of {} payloads",
+                        stage.name,
+                        len,
+                        capacity.capacity
+                    )
+                }
+                StageOverflowPolicy::Block => Ok(None),
+                StageOverflowPolicy::DropOldest => Ok(Some(
+                    stage.discard_oldest(bind, len + count - capacity.capacity),
+                )),
             }
         }
 
+        /// Locks the stages of a move (a frame is added to the stage passed as both) once the
+        /// destination has room for the entering payloads. The room is checked and taken under
+        /// the destination lock, so concurrent producers cannot overfill the stage; with the
+        /// `Block` policy the locks are released while waiting and the room is checked again.
+        /// `count` gets the number of the entering payloads from the locked source. The payloads
+        /// dropped to make room must be passed to [`Pipeline::release_dropped`] once the locks
+        /// are released.
+        ///
+        #[allow(clippy::type_complexity)]
+        fn lock_admitted<'a, F>(
+            &self,
+            source: &'a PipelineStage,
+            dest: &'a PipelineStage,
+            count: F,
+        ) -> Result<(
+            RwLockWriteGuard<'a, PayloadMap>,
+            Option<RwLockWriteGuard<'a, PayloadMap>>,
+            Vec<(i64, PipelinePayload)>,
+        )>
+        where
+            F: Fn(&PayloadMap) -> Result<usize>,
+        {
+            let timeout = dest.get_capacity().and_then(|c| c.block_timeout);
+            let deadline = timeout.map(|t| Instant::now() + t);
+            loop {
+                let (mut source_bind, mut dest_bind) = lock_stages(source, dest);
+                let count = count(&source_bind)?;
+                let bind = dest_bind.as_deref_mut().unwrap_or(&mut source_bind);
+                if let Some(dropped) = Self::try_admit(dest, bind, count)? {
+                    return Ok((source_bind, dest_bind, dropped));
+                }
+                drop(dest_bind);
+                drop(source_bind);
+                if !dest.wait_for_vacancy(count, deadline) {
+                    dest.record_rejected();
+                    bail!(
+                        "The stage {} is still full after waiting for {:?}",
+                        dest.name,
+                        timeout.unwrap_or_default()
+                    )
+                }
+            }
+        }
+
+        fn release_dropped(&self, stage: &PipelineStage, dropped: Vec<(i64, PipelinePayload)>) {
+            if dropped.is_empty() {
+                return;
+            }
+            let mut root_spans = self.root_spans.write();
+            for (id, payload) in dropped {
+                log::warn!(target: "savant_rs::pipeline", "The stage {} is full, payload {} is dropped", stage.name, id);
+                self.frame_locations.remove(id);
+                let contexts = match payload {
+                    PipelinePayload::Frame(_, _, ctx, _, _) => vec![(id, ctx)],
+                    PipelinePayload::Batch(_, _, contexts, _, _) => contexts.into_iter().collect(),
+                };
+                for (frame_id, ctx) in contexts {
+                    ctx.span().end();
+                    self.frame_locations.remove(frame_id);
+                    if let Some(root_ctx) = root_spans.remove(&frame_id) {
+                        root_ctx.span().end();
+                    }
+                }
+            }
+        }
+
+        fn get_stage_for_id(&self, id: i64) -> Result<usize> {
+            self.frame_locations
+                .get(id)
+                .ok_or_else(|| anyhow!("Object {} location not found.", id))
+        }
+
         fn get_stages_for_ids(&self, ids: &[i64]) -> Result<Vec<(i64, usize)>> {
-            let bind = self.frame_locations.read();
-            let mut results = Vec::with_capacity(ids.len());
-            for id in ids {
-                let val = bind.get(id);
-                if val.is_none() {
-                    bail!("Object {} location not found.", id)
+            ids.iter()
+                .map(|id| Ok((*id, self.get_stage_for_id(*id)?)))
+                .collect()
+        }
+
+        /// Runs the operation on the stage holding the object. A concurrent move may relocate
+        /// the object after its location is read, the operation is repeated on the new stage
+        /// then. The moves update the locations while holding the stages, so when the object is
+        /// not found in the stage, the location read afterwards is up to date.
+        ///
+        fn with_stage_of<'a, T, F>(&'a self, id: i64, mut f: F) -> Result<T>
+        where
+            F: FnMut(&'a PipelineStage) -> Result<T>,
+        {
+            let mut index = self.get_stage_for_id(id)?;
+            loop {
+                let stage = self.stages.get(index).ok_or_else(|| {
+                    anyhow!(
+                        "Stage ID={} not found (when accessing object {})",
+                        index,
+                        id
+                    )
+                })?;
+                let res = f(stage);
+                if res.is_ok() {
+                    return res;
+                }
+                match self.frame_locations.get(id) {
+                    Some(current) if current != index => index = current,
+                    _ => return res,
                 }
-                results.push((*id, *val.unwrap()));
             }
-            Ok(results)
         }
 
         pub fn get_independent_frame(&self, frame_id: i64) -> Result<(VideoFrameProxy, Context)> {
-            let stage = self.get_stage_for_id(frame_id)?;
-            if let Some(stage) = self.stages.get(stage) {
-                stage.get_independent_frame(frame_id)
-            } else {
-                bail!("Stage not found (when getting frame {})", frame_id)
-            }
+            self.with_stage_of(frame_id, |stage| stage.get_independent_frame(frame_id))
         }
 
         pub fn get_batched_frame(
@@ -759,61 +1452,27 @@ pub(super) mod implementation {
             batch_id: i64,
             frame_id: i64,
         ) -> Result<(VideoFrameProxy, Context)> {
-            let stage = self.get_stage_for_id(batch_id)?;
-            if let Some(stage) = self.stages.get(stage) {
+            self.with_stage_of(batch_id, |stage| {
                 stage.get_batched_frame(batch_id, frame_id)
-            } else {
-                bail!(
-                    "Stage not found (when getting frame {} from batch {})",
-                    frame_id,
-                    batch_id
-                )
-            }
+            })
         }
 
         pub fn get_batch(&self, batch_id: i64) -> Result<(VideoFrameBatch, HashMap<i64, Context>)> {
-            let stage = self.get_stage_for_id(batch_id)?;
-            if let Some(stage) = self.stages.get(stage) {
-                stage.get_batch(batch_id)
-            } else {
-                bail!(
-                    "Stage ID={} not found (when getting batch {})",
-                    stage,
-                    batch_id
-                )
-            }
+            self.with_stage_of(batch_id, |stage| stage.get_batch(batch_id))
         }
 
         pub fn apply_updates(&self, id: i64) -> Result<()> {
-            let stage = self.get_stage_for_id(id)?;
-            if let Some(stage) = self.stages.get(stage) {
-                stage.apply_updates(id)
-            } else {
-                bail!(
-                    "Stage ID={} not found (when applying updates to object {})",
-                    stage,
-                    id
-                )
-            }
+            self.with_stage_of(id, |stage| stage.apply_updates(id))
         }
 
         pub fn clear_updates(&self, id: i64) -> Result<()> {
-            let stage = self.get_stage_for_id(id)?;
-            if let Some(stage) = self.stages.get(stage) {
-                stage.clear_updates(id)
-            } else {
-                bail!(
-                    "Stage ID={} not found (when clearing updates to object {})",
-                    stage,
-                    id
-                )
-            }
+            self.with_stage_of(id, |stage| stage.clear_updates(id))
         }
 
         fn update_frame_locations(&self, ids: &[i64], index: usize) {
-            self.frame_locations
-                .write()
-                .extend(ids.iter().map(|id| (*id, index)));
+            for id in ids {
+                self.frame_locations.insert(*id, index);
+            }
         }
 
         fn check_ids_in_the_same_stage(&self, ids: &[i64]) -> Result<usize> {
@@ -861,18 +1520,22 @@ pub(super) mod implementation {
                     source_stage.name, source_stage.stage_type, dest_stage.name, dest_stage.stage_type)
             }
 
-            let removed_objects = source_stage_opt
-                .as_ref()
-                .expect("Stage must be defined according to the previous check")
-                .delete_many(&object_ids)?;
-
-            self.update_frame_locations(&object_ids, dest_index);
+            // the payloads and their locations are changed while both stages are locked
+            let entering = if dest_index != source_index {
+                object_ids.len()
+            } else {
+                0
+            };
+            let (mut source_bind, mut dest_bind, dropped) =
+                self.lock_admitted(source_stage, dest_stage, |_| Ok(entering))?;
+            let removed_objects = source_stage.take_payloads(&mut source_bind, &object_ids)?;
 
             let mut payloads = Vec::with_capacity(removed_objects.len());
             for (id, payload) in removed_objects {
                 let payload = match payload {
                     PipelinePayload::Frame(frame, updates, ctx, source_index, time) => {
                         self.add_frame_json(&frame, &ctx);
+                        self.record_object_counts(dest_stage_name, [&frame]);
                         ctx.span().end();
                         let ctx = self.get_stage_span(id, format!("stage/{}", dest_stage_name));
                         PipelinePayload::Frame(frame, updates, ctx, source_index, time)
@@ -891,13 +1554,22 @@ pub(super) mod implementation {
                                 .get_stage_span(*frame_id, format!("stage/{}", dest_stage_name));
                             new_contexts.insert(*frame_id, ctx);
                         }
+                        self.record_object_counts(dest_stage_name, batch.frames.values());
                         PipelinePayload::Batch(batch, updates, new_contexts, source_index, times)
                     }
                 };
                 payloads.push((id, payload));
             }
 
-            dest_stage.add_payloads(payloads)?;
+            dest_stage.insert_payloads(
+                dest_bind.as_deref_mut().unwrap_or(&mut source_bind),
+                payloads,
+            )?;
+            self.update_frame_locations(&object_ids, dest_index);
+            drop(dest_bind);
+            drop(source_bind);
+            source_stage.notify_vacancy();
+            self.release_dropped(dest_stage, dropped);
 
             Ok(())
         }
@@ -926,10 +1598,11 @@ pub(super) mod implementation {
                 bail!("Source stage {} must contain independent frames and destination stage must contain batched frames", source_stage.name)
             }
 
+            let (mut source_bind, mut dest_bind, dropped) =
+                self.lock_admitted(source_stage, dest_stage, |_| Ok(1))?;
+            let removed_frames = source_stage.take_payloads(&mut source_bind, &frame_ids)?;
             let batch_id = self.id_counter.fetch_add(1, Ordering::SeqCst) + 1;
 
-            self.update_frame_locations(&frame_ids, dest_index);
-
             let default_size = frame_ids.len();
 
             let mut batch = VideoFrameBatch::with_capacity(default_size);
@@ -938,27 +1611,21 @@ pub(super) mod implementation {
 
             let mut last_stage: Option<String> = None;
             let mut last_times: Vec<SystemTime> = Vec::with_capacity(batch.frames.len());
-            for id in frame_ids {
-                if let Some(payload) = source_stage_opt
-                    .as_ref()
-                    .expect("Stage must be defined according to the previous check")
-                    .delete(id)?
-                {
-                    match payload {
-                        PipelinePayload::Frame(frame, updates, ctx, ls, lt) => {
-                            last_stage = ls;
-                            last_times.push(lt);
-                            batch.add(id, frame);
-                            contexts.insert(id, ctx);
-                            for update in updates {
-                                batch_updates.push((id, update));
-                            }
+            for (id, payload) in removed_frames {
+                match payload {
+                    PipelinePayload::Frame(frame, updates, ctx, ls, lt) => {
+                        last_stage = ls;
+                        last_times.push(lt);
+                        batch.add(id, frame);
+                        contexts.insert(id, ctx);
+                        for update in updates {
+                            batch_updates.push((id, update));
                         }
-                        _ => bail!(
-                            "Source stage {} must contain independent frames",
-                            source_stage.name
-                        ),
                     }
+                    _ => bail!(
+                        "Source stage {} must contain independent frames",
+                        source_stage.name
+                    ),
                 }
             }
 
@@ -977,10 +1644,19 @@ pub(super) mod implementation {
                 })
                 .collect::<Result<HashMap<_, _>, _>>()?;
 
+            self.record_object_counts(dest_stage_name, batch.frames.values());
             let payload =
                 PipelinePayload::Batch(batch, batch_updates, contexts, last_stage, last_times);
-            dest_stage.add_batch_payload(batch_id, payload)?;
-            self.frame_locations.write().insert(batch_id, dest_index);
+            dest_stage.insert_payloads(
+                dest_bind.as_deref_mut().unwrap_or(&mut source_bind),
+                [(batch_id, payload)],
+            )?;
+            self.update_frame_locations(&frame_ids, dest_index);
+            self.frame_locations.insert(batch_id, dest_index);
+            drop(dest_bind);
+            drop(source_bind);
+            source_stage.notify_vacancy();
+            self.release_dropped(dest_stage, dropped);
             log::trace!(target: "savant_rs::pipeline", "Created batch {} to stage {}", batch_id, dest_stage_name);
             Ok(batch_id)
         }
@@ -1008,27 +1684,30 @@ pub(super) mod implementation {
             {
                 bail!("Source stage {} must contain batched frames and destination stage must contain independent frames", source_stage.name)
             }
-
-            let (batch, updates, mut contexts, last_stage, last_times) = if let Some(payload) =
-                source_stage_opt
-                    .as_ref()
-                    .expect("The stage must be defined according to the previous check")
-                    .delete(batch_id)?
+            // the batch size is read under the source lock, so it cannot change before the move
+            let (mut source_bind, mut dest_bind, dropped) =
+                self.lock_admitted(source_stage, dest_stage, |bind| match bind.get(&batch_id) {
+                    Some(PipelinePayload::Batch(batch, ..)) => Ok(batch.frames.len()),
+                    Some(_) => bail!("Source stage {} must contain batch", source_stage.name),
+                    None => bail!(
+                        "Batch {} not found in the stage {}",
+                        batch_id,
+                        source_stage.name
+                    ),
+                })?;
+            let (batch, updates, mut contexts, last_stage, last_times) = match source_stage
+                .take_payloads(&mut source_bind, &[batch_id])?
+                .pop()
             {
-                match payload {
-                    PipelinePayload::Batch(batch, updates, contexts, last_stage, last_times) => {
-                        (batch, updates, contexts, last_stage, last_times)
-                    }
-                    _ => bail!("Source stage {} must contain batch", source_stage.name),
-                }
-            } else {
-                bail!("Batch not found in source stage {}", source_stage.name)
+                Some((
+                    _,
+                    PipelinePayload::Batch(batch, updates, contexts, last_stage, last_times),
+                )) => (batch, updates, contexts, last_stage, last_times),
+                _ => bail!("Source stage {} must contain batch", source_stage.name),
             };
 
-            self.frame_locations.write().remove(&batch_id);
-
             let frame_ids = batch.frames.keys().cloned().collect::<Vec<_>>();
-            self.update_frame_locations(&frame_ids, dest_index);
+            self.record_object_counts(dest_stage_name, batch.frames.values());
 
             let mut payloads = HashMap::with_capacity(batch.frames.len());
             for (frame_id, frame) in batch.frames {
@@ -1065,7 +1744,16 @@ pub(super) mod implementation {
                 }
             }
 
-            dest_stage.add_payloads(payloads)?;
+            dest_stage.insert_payloads(
+                dest_bind.as_deref_mut().unwrap_or(&mut source_bind),
+                payloads,
+            )?;
+            self.frame_locations.remove(batch_id);
+            self.update_frame_locations(&frame_ids, dest_index);
+            drop(dest_bind);
+            drop(source_bind);
+            source_stage.notify_vacancy();
+            self.release_dropped(dest_stage, dropped);
 
             Ok(frame_ids)
         }
@@ -1075,15 +1763,22 @@ pub(super) mod implementation {
             frame_id: i64,
             query: &MatchQuery,
         ) -> Result<HashMap<i64, Vec<BorrowedVideoObject>>> {
-            let stage = self.get_stage_for_id(frame_id)?;
-            let stage_opt = self.stages.get(stage);
-            if stage_opt.is_none() {
-                bail!("Stage ID={} not found", stage);
-            }
+            self.with_stage_of(frame_id, |stage| stage.access_objects(frame_id, query))
+        }
 
-            stage_opt
-                .map(|stage| stage.access_objects(frame_id, query))
-                .unwrap()
+        pub fn route(&self, id: i64) -> Result<Option<String>> {
+            self.with_stage_of(id, |stage| {
+                let Some(routes) = self.configuration.stage_routes.get(&stage.name) else {
+                    return Ok(None);
+                };
+                for route in routes {
+                    let matched = stage.access_objects(id, &route.query)?;
+                    if matched.values().any(|objects| !objects.is_empty()) {
+                        return Ok(Some(route.destination.clone()));
+                    }
+                }
+                Ok(None)
+            })
         }
     }
 
@@ -1128,23 +1823,27 @@ pub(super) mod implementation {
     #[cfg(test)]
     mod tests {
         use std::sync::atomic::Ordering;
-        use std::sync::Once;
         use std::thread::sleep;
         use std::time::Duration;
 
+        use hashbrown::HashMap;
         use opentelemetry::trace::TraceContextExt;
 
-        use crate::pipeline::implementation::{create_test_pipeline, PipelineStagePayloadType};
+        use crate::metrics::get_counter_family;
+
+        use crate::pipeline::implementation::{
+            create_test_pipeline, Pipeline, PipelineConfigurationBuilder, PipelineStagePayloadType,
+        };
+        use crate::pipeline::stage::{PipelineStage, StageCapacity, StageOverflowPolicy};
+        use crate::pipeline::{PipelinePayload, PipelineStageFunction, PipelineStageFunctionOrder};
         use crate::primitives::attribute_value::AttributeValue;
         use crate::primitives::frame_update::VideoFrameUpdate;
         use crate::primitives::{Attribute, WithAttributes};
-        use crate::telemetry::{init, TelemetryConfiguration};
+        use crate::telemetry::{TelemetryConfiguration, TelemetryScope};
         use crate::test::gen_frame;
 
-        static INIT: Once = Once::new();
-
-        fn init_telemetry() {
-            INIT.call_once(|| init(&TelemetryConfiguration::no_op()))
+        fn init_telemetry() -> TelemetryScope {
+            TelemetryScope::new(&TelemetryConfiguration::no_op())
         }
 
         #[test]
@@ -1191,6 +1890,268 @@ pub(super) mod implementation {
             Ok(())
         }
 
+        fn create_bounded_pipeline(
+            stage: &str,
+            policy: StageOverflowPolicy,
+            block_timeout: Option<Duration>,
+        ) -> anyhow::Result<Pipeline> {
+            let stages = ["input", "proc1", "output"]
+                .into_iter()
+                .zip([
+                    PipelineStagePayloadType::Frame,
+                    PipelineStagePayloadType::Batch,
+                    PipelineStagePayloadType::Frame,
+                ])
+                .map(|(name, stage_type)| (name.to_string(), stage_type, None, None))
+                .collect();
+            Pipeline::new(
+                stages,
+                PipelineConfigurationBuilder::default()
+                    .stage_capacity(HashMap::from([(
+                        stage.to_string(),
+                        StageCapacity {
+                            capacity: 2,
+                            policy,
+                            block_timeout,
+                        },
+                    )]))
+                    .build()?,
+            )
+        }
+
+        #[test]
+        fn test_stage_capacity_reject() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline("input", StageOverflowPolicy::Reject, None)?;
+            pipeline.add_frame("input", gen_frame())?;
+            pipeline.add_frame("input", gen_frame())?;
+            assert!(pipeline.add_frame("input", gen_frame()).is_err());
+            let occupancy = pipeline.get_stage_occupancy("input")?;
+            assert_eq!(occupancy.queue_len, 2);
+            assert_eq!(occupancy.capacity, Some(2));
+            assert_eq!(occupancy.rejected, 1);
+            assert_eq!(pipeline.get_id_locations_len(), 2);
+            assert_eq!(pipeline.get_occupancy().len(), 3);
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_drop_oldest() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline("input", StageOverflowPolicy::DropOldest, None)?;
+            let first = pipeline.add_frame("input", gen_frame())?;
+            pipeline.add_frame("input", gen_frame())?;
+            pipeline.add_frame("input", gen_frame())?;
+            assert!(pipeline.get_independent_frame(first).is_err());
+            let occupancy = pipeline.get_stage_occupancy("input")?;
+            assert_eq!(occupancy.queue_len, 2);
+            assert_eq!(occupancy.dropped, 1);
+            assert_eq!(pipeline.get_id_locations_len(), 2);
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_block() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline(
+                "input",
+                StageOverflowPolicy::Block,
+                Some(Duration::from_millis(10)),
+            )?;
+            pipeline.add_frame("input", gen_frame())?;
+            pipeline.add_frame("input", gen_frame())?;
+            assert!(pipeline.add_frame("input", gen_frame()).is_err());
+            assert_eq!(pipeline.get_stage_occupancy("input")?.rejected, 1);
+
+            let pipeline = create_bounded_pipeline(
+                "input",
+                StageOverflowPolicy::Block,
+                Some(Duration::from_secs(5)),
+            )?;
+            let first = pipeline.add_frame("input", gen_frame())?;
+            pipeline.add_frame("input", gen_frame())?;
+            std::thread::scope(|s| {
+                s.spawn(|| {
+                    sleep(Duration::from_millis(20));
+                    pipeline.delete(first).unwrap();
+                });
+                pipeline.add_frame("input", gen_frame())
+            })?;
+            assert_eq!(pipeline.get_stage_queue_len("input")?, 2);
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_move() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline("proc1", StageOverflowPolicy::Reject, None)?;
+            let ids = (0..4)
+                .map(|_| pipeline.add_frame("input", gen_frame()))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            pipeline.move_and_pack_frames("proc1", vec![ids[0]])?;
+            pipeline.move_and_pack_frames("proc1", vec![ids[1]])?;
+            assert!(pipeline
+                .move_and_pack_frames("proc1", vec![ids[2], ids[3]])
+                .is_err());
+            assert_eq!(pipeline.get_stage_queue_len("input")?, 2);
+            pipeline.get_independent_frame(ids[2])?;
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_concurrent_reject() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline("input", StageOverflowPolicy::Reject, None)?;
+            let barrier = std::sync::Barrier::new(8);
+            let added = std::thread::scope(|s| {
+                let workers = (0..8)
+                    .map(|_| {
+                        s.spawn(|| {
+                            barrier.wait();
+                            pipeline.add_frame("input", gen_frame()).is_ok()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                workers
+                    .into_iter()
+                    .filter(|w| matches!(w.join(), Ok(true)))
+                    .count()
+            });
+            assert_eq!(added, 2);
+            let occupancy = pipeline.get_stage_occupancy("input")?;
+            assert_eq!(occupancy.queue_len, 2);
+            assert_eq!(occupancy.rejected, 6);
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_concurrent_block() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline("input", StageOverflowPolicy::Block, None)?;
+            let (tx, rx) = std::sync::mpsc::channel();
+            let max_len = std::thread::scope(|s| {
+                for _ in 0..4 {
+                    let tx = tx.clone();
+                    let pipeline = &pipeline;
+                    s.spawn(move || {
+                        for _ in 0..25 {
+                            let id = pipeline.add_frame("input", gen_frame()).unwrap();
+                            tx.send(id).unwrap();
+                        }
+                    });
+                }
+                drop(tx);
+                let mut max_len = 0;
+                for id in rx {
+                    max_len = max_len.max(pipeline.get_stage_queue_len("input").unwrap());
+                    pipeline.delete(id).unwrap();
+                }
+                max_len
+            });
+            assert!(max_len <= 2);
+            assert_eq!(pipeline.get_stage_queue_len("input")?, 0);
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_concurrent_drop_oldest() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline("input", StageOverflowPolicy::DropOldest, None)?;
+            std::thread::scope(|s| {
+                for _ in 0..4 {
+                    s.spawn(|| {
+                        for _ in 0..25 {
+                            pipeline.add_frame("input", gen_frame()).unwrap();
+                            assert!(pipeline.get_stage_queue_len("input").unwrap() <= 2);
+                        }
+                    });
+                }
+            });
+            let occupancy = pipeline.get_stage_occupancy("input")?;
+            assert_eq!(occupancy.queue_len, 2);
+            assert_eq!(occupancy.dropped, 98);
+            assert_eq!(pipeline.get_id_locations_len(), 2);
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_concurrent_move() -> anyhow::Result<()> {
+            let pipeline = create_bounded_pipeline("proc1", StageOverflowPolicy::Reject, None)?;
+            let ids = (0..8)
+                .map(|_| pipeline.add_frame("input", gen_frame()))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let barrier = std::sync::Barrier::new(ids.len());
+            let moved = std::thread::scope(|s| {
+                let workers = ids
+                    .iter()
+                    .map(|id| {
+                        let barrier = &barrier;
+                        let pipeline = &pipeline;
+                        s.spawn(move || {
+                            barrier.wait();
+                            pipeline.move_and_pack_frames("proc1", vec![*id]).is_ok()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                workers
+                    .into_iter()
+                    .filter(|w| matches!(w.join(), Ok(true)))
+                    .count()
+            });
+            assert_eq!(moved, 2);
+            assert_eq!(pipeline.get_stage_queue_len("proc1")?, 2);
+            assert_eq!(pipeline.get_stage_queue_len("input")?, 6);
+            assert_eq!(pipeline.get_stage_occupancy("proc1")?.rejected, 6);
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_capacity_unknown_stage() {
+            assert!(create_bounded_pipeline("missing", StageOverflowPolicy::Reject, None).is_err());
+        }
+
+        #[test]
+        fn test_stage_stats() -> anyhow::Result<()> {
+            let _t = init_telemetry();
+            let pipeline = create_test_pipeline()?;
+            pipeline.set_sampling_period(1)?;
+            let first = pipeline.add_frame("input", gen_frame())?;
+            pipeline.add_frame("input", gen_frame())?;
+            sleep(Duration::from_millis(5));
+            pipeline.move_and_pack_frames("proc1", vec![first])?;
+
+            let stats = pipeline.stage_stats("input")?;
+            assert_eq!(stats.queue_depth, 1);
+            assert_eq!(stats.exited, 1);
+            assert!(stats.p50_latency >= Duration::from_millis(5));
+            assert_eq!(stats.p50_latency, stats.p95_latency);
+            assert_eq!(pipeline.stage_stats("proc1")?.exited, 0);
+            assert!(pipeline.stage_stats("missing").is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn test_stage_residency_spans() -> anyhow::Result<()> {
+            let _t = init_telemetry();
+            let stages = ["input", "output"]
+                .into_iter()
+                .map(|name| {
+                    (
+                        name.to_string(),
+                        PipelineStagePayloadType::Frame,
+                        None,
+                        None,
+                    )
+                })
+                .collect();
+            let pipeline = Pipeline::new(
+                stages,
+                PipelineConfigurationBuilder::default()
+                    .stage_residency_spans(true)
+                    .build()?,
+            )?;
+            pipeline.set_sampling_period(1)?;
+            let id = pipeline.add_frame("input", gen_frame())?;
+            pipeline.move_as_is("output", vec![id])?;
+            pipeline.delete(id)?;
+            assert_eq!(pipeline.stage_stats("input")?.exited, 1);
+            assert_eq!(pipeline.stage_stats("output")?.exited, 1);
+            Ok(())
+        }
+
         #[test]
         fn test_frame_to_batch() -> anyhow::Result<()> {
             let pipeline = create_test_pipeline()?;
@@ -1249,6 +2210,77 @@ pub(super) mod implementation {
             Ok(())
         }
 
+        #[test]
+        fn test_delete_batch() -> anyhow::Result<()> {
+            let pipeline = create_test_pipeline()?;
+            let first = pipeline.add_frame("input", gen_frame())?;
+            let second = pipeline.add_frame("input", gen_frame())?;
+            let batch_id = pipeline.move_and_pack_frames("proc1", vec![first, second])?;
+            let contexts = pipeline.delete(batch_id)?;
+            assert_eq!(contexts.len(), 2);
+            assert!(contexts.contains_key(&first) && contexts.contains_key(&second));
+            assert_eq!(pipeline.get_id_locations_len(), 0);
+            assert!(pipeline.root_spans.read().is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn test_concurrent_moves() -> anyhow::Result<()> {
+            let pipeline = create_test_pipeline()?;
+            std::thread::scope(|s| {
+                let workers = (0..4)
+                    .map(|_| {
+                        s.spawn(|| -> anyhow::Result<()> {
+                            for _ in 0..50 {
+                                let id = pipeline.add_frame("input", gen_frame())?;
+                                let batch_id = pipeline.move_and_pack_frames("proc1", vec![id])?;
+                                pipeline.get_batched_frame(batch_id, id)?;
+                                pipeline.move_as_is("proc2", vec![batch_id])?;
+                                pipeline.move_and_unpack_batch("output", batch_id)?;
+                                pipeline.get_independent_frame(id)?;
+                                pipeline.delete(id)?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                workers
+                    .into_iter()
+                    .try_for_each(|w| w.join().expect("Worker panicked"))
+            })?;
+            for stage in ["input", "proc1", "proc2", "output"] {
+                assert_eq!(pipeline.get_stage_queue_len(stage)?, 0);
+            }
+            assert_eq!(pipeline.get_id_locations_len(), 0);
+            Ok(())
+        }
+
+        #[test]
+        fn test_concurrent_move_of_same_frame() -> anyhow::Result<()> {
+            let pipeline = create_test_pipeline()?;
+            let id = pipeline.add_frame("input", gen_frame())?;
+            let barrier = std::sync::Barrier::new(2);
+            let moved = std::thread::scope(|s| {
+                let handles = (0..2)
+                    .map(|_| {
+                        s.spawn(|| {
+                            barrier.wait();
+                            pipeline.move_as_is("output", vec![id]).is_ok()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .filter(|h| matches!(h.join(), Ok(true)))
+                    .count()
+            });
+            assert_eq!(moved, 1);
+            assert_eq!(pipeline.get_stage_queue_len("input")?, 0);
+            assert_eq!(pipeline.get_stage_queue_len("output")?, 1);
+            pipeline.get_independent_frame(id)?;
+            Ok(())
+        }
+
         #[test]
         fn test_frame_to_frame() -> anyhow::Result<()> {
             let pipeline = create_test_pipeline()?;
@@ -1300,7 +2332,7 @@ pub(super) mod implementation {
 
         #[test]
         fn test_sampling() -> anyhow::Result<()> {
-            init_telemetry();
+            let _telemetry = init_telemetry();
 
             let pipeline = create_test_pipeline()?;
             pipeline.set_sampling_period(2)?;
@@ -1326,7 +2358,7 @@ pub(super) mod implementation {
 
         #[test]
         fn test_no_tracing() -> anyhow::Result<()> {
-            init_telemetry();
+            let _telemetry = init_telemetry();
 
             let pipeline = create_test_pipeline()?;
             pipeline.set_sampling_period(0)?;
@@ -1344,7 +2376,7 @@ pub(super) mod implementation {
 
         #[test]
         fn test_tracing_every() -> anyhow::Result<()> {
-            init_telemetry();
+            let _telemetry = init_telemetry();
 
             let pipeline = create_test_pipeline()?;
             pipeline.set_sampling_period(1)?;
@@ -1360,6 +2392,53 @@ pub(super) mod implementation {
             Ok(())
         }
 
+        #[test]
+        fn test_object_count_metrics() -> anyhow::Result<()> {
+            let pipeline = Pipeline::new(
+                vec![
+                    (
+                        "input".to_string(),
+                        PipelineStagePayloadType::Frame,
+                        None,
+                        None,
+                    ),
+                    (
+                        "proc".to_string(),
+                        PipelineStagePayloadType::Batch,
+                        None,
+                        None,
+                    ),
+                ],
+                PipelineConfigurationBuilder::default()
+                    .object_count_metrics_stage(Some("proc".to_string()))
+                    .build()?,
+            )?;
+            pipeline.set_name("object-count-test".to_string())?;
+            let id1 = pipeline.add_frame("input", gen_frame())?;
+            let id2 = pipeline.add_frame("input", gen_frame())?;
+            // the objects are counted when entering the designated stage only
+            assert!(get_counter_family("pipeline_object_count")
+                .map(|c| c
+                    .lock()
+                    .get(&["object-count-test", "test", "test", "test2"])
+                    .unwrap())
+                .unwrap_or_default()
+                .is_none());
+
+            pipeline.move_and_pack_frames("proc", vec![id1, id2])?;
+            let counter = get_counter_family("pipeline_object_count").unwrap();
+            let counter = counter.lock();
+            assert_eq!(
+                counter.get(&["object-count-test", "test", "test", "test2"])?,
+                Some(2)
+            );
+            assert_eq!(
+                counter.get(&["object-count-test", "test", "test2", "test"])?,
+                Some(2)
+            );
+            Ok(())
+        }
+
         #[test]
         fn test_stats() -> anyhow::Result<()> {
             let pipeline = create_test_pipeline()?;
@@ -1384,5 +2463,90 @@ pub(super) mod implementation {
             dbg!(&records);
             Ok(())
         }
+
+        struct FailingFunction {
+            pipeline: Option<Pipeline>,
+        }
+
+        impl PipelineStageFunction for FailingFunction {
+            fn set_pipeline(&mut self, pipeline: Pipeline) {
+                self.pipeline = Some(pipeline);
+            }
+            fn get_pipeline(&self) -> &Option<Pipeline> {
+                &self.pipeline
+            }
+            fn call(
+                &self,
+                id: i64,
+                _: &PipelineStage,
+                _: PipelineStageFunctionOrder,
+                _: &mut PipelinePayload,
+            ) -> anyhow::Result<()> {
+                anyhow::bail!("Payload {} is rejected", id)
+            }
+        }
+
+        fn create_restore_pipeline(
+            output_ingress: Option<Box<dyn PipelineStageFunction>>,
+            input_capacity: Option<usize>,
+        ) -> anyhow::Result<Pipeline> {
+            let mut configuration = PipelineConfigurationBuilder::default().build()?;
+            if let Some(capacity) = input_capacity {
+                configuration.stage_capacity.insert(
+                    "input".to_string(),
+                    StageCapacity {
+                        capacity,
+                        policy: StageOverflowPolicy::Reject,
+                        block_timeout: None,
+                    },
+                );
+            }
+            Pipeline::new(
+                vec![
+                    (
+                        "input".to_string(),
+                        PipelineStagePayloadType::Frame,
+                        None,
+                        None,
+                    ),
+                    (
+                        "output".to_string(),
+                        PipelineStagePayloadType::Frame,
+                        output_ingress,
+                        None,
+                    ),
+                ],
+                configuration,
+            )
+        }
+
+        #[test]
+        fn test_failed_restore_leaves_pipeline_empty() -> anyhow::Result<()> {
+            let _telemetry = init_telemetry();
+            let pipeline = create_restore_pipeline(None, None)?;
+            pipeline.add_frame("input", gen_frame())?;
+            pipeline.add_frame("input", gen_frame())?;
+            let id = pipeline.add_frame("input", gen_frame())?;
+            pipeline.move_as_is("output", vec![id])?;
+            let snapshot = pipeline.snapshot();
+
+            let restored =
+                create_restore_pipeline(Some(Box::new(FailingFunction { pipeline: None })), None)?;
+            assert!(restored.restore(&snapshot).is_err());
+            assert_eq!(restored.get_stage_queue_len("input")?, 0);
+            assert_eq!(restored.get_stage_queue_len("output")?, 0);
+            assert_eq!(restored.get_id_locations_len(), 0);
+            assert!(restored.root_spans.read().is_empty());
+
+            // the snapshot does not fit the capacity of the input stage
+            let restored = create_restore_pipeline(None, Some(1))?;
+            let error = restored.restore(&snapshot).unwrap_err().to_string();
+            assert!(error.contains("input"));
+            assert_eq!(restored.get_id_locations_len(), 0);
+
+            let restored = create_restore_pipeline(None, Some(2))?;
+            assert_eq!(restored.restore(&snapshot)?, 3);
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,317 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use hashbrown::HashMap;
+
+use crate::primitives::frame::VideoFrameProxy;
+
+#[derive(Debug)]
+struct PendingFrame {
+    previous: Option<i64>,
+    frame: VideoFrameProxy,
+    arrived: Instant,
+}
+
+#[derive(Debug, Default)]
+struct SourceQueue {
+    last_released: Option<i64>,
+    pending: BTreeMap<i64, PendingFrame>,
+}
+
+/// The frame follows the released ones when it starts the source or its previous frame is
+/// released (or skipped).
+///
+fn follows(previous: Option<i64>, last_released: Option<i64>) -> bool {
+    match (previous, last_released) {
+        (None, _) => true,
+        (Some(previous), Some(last)) => previous <= last,
+        (Some(_), None) => false,
+    }
+}
+
+impl SourceQueue {
+    fn release(
+        &mut self,
+        now: Instant,
+        gap_timeout: Duration,
+        released: &mut Vec<(i64, VideoFrameProxy)>,
+    ) -> u64 {
+        let mut gaps = 0;
+        while let Some(entry) = self.pending.first_entry() {
+            let pending = entry.get();
+            if !follows(pending.previous, self.last_released) {
+                if now.duration_since(pending.arrived) < gap_timeout {
+                    break;
+                }
+                gaps += 1;
+                log::debug!(
+                    target: "savant_rs::pipeline::ordered_egress",
+                    "Frame {} of source {} is released after waiting {:?} for the previous frame {:?}",
+                    entry.key(),
+                    pending.frame.get_source_id(),
+                    gap_timeout,
+                    pending.previous
+                );
+            }
+            let (id, pending) = entry.remove_entry();
+            self.last_released = Some(self.last_released.map_or(id, |last| last.max(id)));
+            released.push((id, pending.frame));
+        }
+        gaps
+    }
+}
+
+/// Releases the frames leaving the pipeline in the order they were added to it, per source.
+/// Batching stages may reorder the frames, the buffer holds a frame until the previous frame of
+/// the same source (as recorded by the pipeline in `previous_frame_seq_id`) is released.
+///
+/// A frame which waits for longer than the gap timeout is released anyway, so a frame deleted
+/// from the pipeline does not stall its source. The frames arriving after their successors were
+/// released this way are released immediately. When it is known that the frames will not
+/// arrive, [`OrderedEgress::skip_until`] releases their successors without waiting.
+///
+#[derive(Debug)]
+pub struct OrderedEgress {
+    gap_timeout: Duration,
+    sources: HashMap<String, SourceQueue>,
+    gaps: u64,
+}
+
+impl OrderedEgress {
+    pub fn new(gap_timeout: Duration) -> Self {
+        Self {
+            gap_timeout,
+            sources: HashMap::new(),
+            gaps: 0,
+        }
+    }
+
+    /// Buffers the frame with the pipeline id and returns the frames which can be released now,
+    /// in order.
+    ///
+    pub fn push(&mut self, id: i64, frame: VideoFrameProxy) -> Vec<(i64, VideoFrameProxy)> {
+        self.push_at(id, frame, Instant::now())
+    }
+
+    fn push_at(
+        &mut self,
+        id: i64,
+        frame: VideoFrameProxy,
+        now: Instant,
+    ) -> Vec<(i64, VideoFrameProxy)> {
+        let queue = self.sources.entry(frame.get_source_id()).or_default();
+        if matches!(queue.last_released, Some(last) if id < last) {
+            log::warn!(
+                target: "savant_rs::pipeline::ordered_egress",
+                "Frame {} of source {} arrived after the newer frames were released",
+                id,
+                frame.get_source_id()
+            );
+            return vec![(id, frame)];
+        }
+        queue.pending.insert(
+            id,
+            PendingFrame {
+                previous: frame.get_previous_frame_seq_id(),
+                frame,
+                arrived: now,
+            },
+        );
+        let mut released = Vec::new();
+        self.gaps += queue.release(now, self.gap_timeout, &mut released);
+        released
+    }
+
+    /// Returns the frames released because the frames they wait for did not arrive within the
+    /// gap timeout. Must be called periodically when no frames are pushed.
+    ///
+    pub fn poll(&mut self) -> Vec<(i64, VideoFrameProxy)> {
+        self.poll_at(Instant::now())
+    }
+
+    fn poll_at(&mut self, now: Instant) -> Vec<(i64, VideoFrameProxy)> {
+        let mut released = Vec::new();
+        for queue in self.sources.values_mut() {
+            self.gaps += queue.release(now, self.gap_timeout, &mut released);
+        }
+        released
+    }
+
+    /// Releases all the buffered frames of the source in order, e.g. at the end of the stream.
+    ///
+    /// The last released frame is remembered, so the frames of the resumed stream, which the
+    /// pipeline chains to the frames before the end of the stream unless
+    /// [`crate::pipeline::Pipeline::clear_source_ordering`] is called, are not held.
+    ///
+    pub fn flush(&mut self, source_id: &str) -> Vec<(i64, VideoFrameProxy)> {
+        let Some(queue) = self.sources.get_mut(source_id) else {
+            return Vec::new();
+        };
+        let released = std::mem::take(&mut queue.pending)
+            .into_iter()
+            .map(|(id, pending)| (id, pending.frame))
+            .collect::<Vec<_>>();
+        if let Some((last, _)) = released.last() {
+            queue.last_released = Some(queue.last_released.map_or(*last, |l| l.max(*last)));
+        }
+        released
+    }
+
+    /// Treats the frames of the source up to the pipeline id as released, so the following
+    /// frames do not wait for them, e.g. when the buffer is attached to the running pipeline and
+    /// the first frames of the source never pass through it. Returns the frames which can be
+    /// released now, in order.
+    ///
+    pub fn skip_until(&mut self, source_id: &str, id: i64) -> Vec<(i64, VideoFrameProxy)> {
+        let queue = self.sources.entry(source_id.to_string()).or_default();
+        queue.last_released = Some(queue.last_released.map_or(id, |last| last.max(id)));
+        let mut released = Vec::new();
+        self.gaps += queue.release(Instant::now(), self.gap_timeout, &mut released);
+        released
+    }
+
+    /// The number of the buffered frames.
+    ///
+    pub fn len(&self) -> usize {
+        self.sources.values().map(|q| q.pending.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of the frames released without their previous frame.
+    ///
+    pub fn gaps(&self) -> u64 {
+        self.gaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::pipeline::implementation::create_test_pipeline;
+    use crate::pipeline::ordered_egress::OrderedEgress;
+    use crate::primitives::frame::VideoFrameProxy;
+    use crate::test::gen_frame;
+
+    fn ids(frames: Vec<(i64, VideoFrameProxy)>) -> Vec<i64> {
+        frames.into_iter().map(|(id, _)| id).collect()
+    }
+
+    fn add_frames(source_ids: &[&str]) -> anyhow::Result<Vec<(i64, VideoFrameProxy)>> {
+        let pipeline = create_test_pipeline()?;
+        source_ids
+            .iter()
+            .map(|source_id| {
+                let mut frame = gen_frame();
+                frame.set_source_id(source_id);
+                let id = pipeline.add_frame("input", frame)?;
+                let (frame, _) = pipeline.get_independent_frame(id)?;
+                Ok((id, frame))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reordering() -> anyhow::Result<()> {
+        let frames = add_frames(&["a", "a", "a"])?;
+        let mut egress = OrderedEgress::new(Duration::from_secs(10));
+        assert!(egress.push(frames[2].0, frames[2].1.clone()).is_empty());
+        assert!(egress.push(frames[1].0, frames[1].1.clone()).is_empty());
+        assert_eq!(egress.len(), 2);
+        let released = egress.push(frames[0].0, frames[0].1.clone());
+        assert_eq!(ids(released), vec![frames[0].0, frames[1].0, frames[2].0]);
+        assert!(egress.is_empty());
+        assert_eq!(egress.gaps(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sources_are_independent() -> anyhow::Result<()> {
+        let frames = add_frames(&["a", "b", "a", "b"])?;
+        let mut egress = OrderedEgress::new(Duration::from_secs(10));
+        assert!(egress.push(frames[2].0, frames[2].1.clone()).is_empty());
+        assert_eq!(
+            ids(egress.push(frames[1].0, frames[1].1.clone())),
+            vec![frames[1].0]
+        );
+        assert_eq!(
+            ids(egress.push(frames[3].0, frames[3].1.clone())),
+            vec![frames[3].0]
+        );
+        assert_eq!(
+            ids(egress.push(frames[0].0, frames[0].1.clone())),
+            vec![frames[0].0, frames[2].0]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_gap_timeout() -> anyhow::Result<()> {
+        let frames = add_frames(&["a", "a", "a"])?;
+        let timeout = Duration::from_millis(100);
+        let mut egress = OrderedEgress::new(timeout);
+        let start = Instant::now();
+        assert!(egress
+            .push_at(frames[1].0, frames[1].1.clone(), start)
+            .is_empty());
+        assert!(egress.poll_at(start + timeout / 2).is_empty());
+        assert_eq!(ids(egress.poll_at(start + timeout)), vec![frames[1].0]);
+        assert_eq!(egress.gaps(), 1);
+
+        // the late frame is released as is, the next one follows the released frame
+        assert_eq!(
+            ids(egress.push_at(frames[0].0, frames[0].1.clone(), start + timeout)),
+            vec![frames[0].0]
+        );
+        assert_eq!(
+            ids(egress.push_at(frames[2].0, frames[2].1.clone(), start + timeout)),
+            vec![frames[2].0]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_flush() -> anyhow::Result<()> {
+        let frames = add_frames(&["a", "a", "a"])?;
+        let mut egress = OrderedEgress::new(Duration::from_secs(10));
+        egress.push(frames[2].0, frames[2].1.clone());
+        egress.push(frames[1].0, frames[1].1.clone());
+        assert_eq!(ids(egress.flush("a")), vec![frames[1].0, frames[2].0]);
+        assert!(egress.is_empty());
+        assert!(egress.flush("a").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resume_after_flush() -> anyhow::Result<()> {
+        let frames = add_frames(&["a", "a", "a", "a"])?;
+        let mut egress = OrderedEgress::new(Duration::from_secs(10));
+        egress.push(frames[0].0, frames[0].1.clone());
+        egress.push(frames[1].0, frames[1].1.clone());
+        // the end of the stream
+        assert!(egress.flush("a").is_empty());
+        // the resumed stream is chained to the frames before the end of the stream
+        assert_eq!(frames[2].1.get_previous_frame_seq_id(), Some(frames[1].0));
+        assert_eq!(
+            ids(egress.push(frames[2].0, frames[2].1.clone())),
+            vec![frames[2].0]
+        );
+        assert_eq!(egress.gaps(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_until() -> anyhow::Result<()> {
+        let frames = add_frames(&["a", "a", "a"])?;
+        let mut egress = OrderedEgress::new(Duration::from_secs(10));
+        // the first frames of the source never pass through the buffer
+        assert!(egress.push(frames[2].0, frames[2].1.clone()).is_empty());
+        assert_eq!(ids(egress.skip_until("a", frames[1].0)), vec![frames[2].0]);
+        assert!(egress.is_empty());
+        assert_eq!(egress.gaps(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use prost::Message;
+use savant_protobuf::generated;
+
+/// The in-flight state of a pipeline: the frames and batches of every stage with their pending
+/// updates, see [`Pipeline::snapshot`](super::Pipeline::snapshot). Stored as a protobuf
+/// message, so a module can save it before a planned restart and restore it afterwards.
+///
+#[derive(Clone, PartialEq, Message)]
+pub struct PipelineSnapshot {
+    /// The last id issued by the pipeline.
+    #[prost(int64, tag = "1")]
+    pub id_counter: i64,
+    #[prost(message, repeated, tag = "2")]
+    pub stages: Vec<StageSnapshot>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct StageSnapshot {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(message, repeated, tag = "2")]
+    pub payloads: Vec<PayloadSnapshot>,
+}
+
+/// A frame or a batch. A frame payload holds a single frame with the same id as the payload.
+///
+#[derive(Clone, PartialEq, Message)]
+pub struct PayloadSnapshot {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(bool, tag = "2")]
+    pub batch: bool,
+    #[prost(message, repeated, tag = "3")]
+    pub frames: Vec<FrameSnapshot>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct FrameSnapshot {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(message, optional, tag = "2")]
+    pub frame: Option<generated::VideoFrame>,
+    /// The updates not applied to the frame yet, in order.
+    #[prost(message, repeated, tag = "3")]
+    pub updates: Vec<generated::VideoFrameUpdate>,
+    /// The propagated context of the root span, empty when the frame is not traced.
+    #[prost(map = "string, string", tag = "4")]
+    pub root_context: HashMap<String, String>,
+}
+
+impl PipelineSnapshot {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.encode_to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self::decode(bytes)?)
+    }
+
+    /// The number of the frames in all the stages.
+    ///
+    pub fn frame_count(&self) -> usize {
+        self.stages
+            .iter()
+            .flat_map(|s| s.payloads.iter())
+            .map(|p| p.frames.len())
+            .sum()
+    }
+
+    /// Writes the snapshot to a temporary file next to the path, syncs it to the disk and
+    /// renames it, so a crash while saving does not leave a truncated snapshot behind.
+    ///
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(&self.to_bytes())?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::pipeline::implementation::create_test_pipeline;
+    use crate::pipeline::snapshot::PipelineSnapshot;
+    use crate::primitives::attribute_value::AttributeValue;
+    use crate::primitives::frame_update::VideoFrameUpdate;
+    use crate::primitives::{Attribute, WithAttributes};
+    use crate::test::gen_frame;
+
+    fn get_update(name: &str) -> VideoFrameUpdate {
+        let mut update = VideoFrameUpdate::default();
+        update.add_frame_attribute(Attribute::persistent(
+            "update",
+            name,
+            vec![AttributeValue::string("1".into(), None)],
+            &None,
+            false,
+        ));
+        update
+    }
+
+    #[test]
+    fn test_snapshot_restore() -> anyhow::Result<()> {
+        let pipeline = create_test_pipeline()?;
+        let single = pipeline.add_frame("input", gen_frame())?;
+        pipeline.add_frame_update(single, get_update("single"))?;
+        let first = pipeline.add_frame("input", gen_frame())?;
+        let second = pipeline.add_frame("input", gen_frame())?;
+        let batch_id = pipeline.move_and_pack_frames("proc1", vec![first, second])?;
+        pipeline.add_batched_frame_update(batch_id, second, get_update("batched"))?;
+
+        let path = env::temp_dir().join(format!("pipeline-snapshot-{}.pb", std::process::id()));
+        let snapshot = pipeline.snapshot();
+        assert_eq!(snapshot.frame_count(), 3);
+        snapshot.save(&path)?;
+        let snapshot = PipelineSnapshot::load(&path)?;
+        std::fs::remove_file(&path)?;
+
+        let restored = create_test_pipeline()?;
+        assert_eq!(restored.restore(&snapshot)?, 3);
+        assert_eq!(restored.get_stage_queue_len("input")?, 1);
+        assert_eq!(restored.get_stage_queue_len("proc1")?, 1);
+        assert_eq!(restored.get_id_locations_len(), 4);
+
+        restored.apply_updates(single)?;
+        let (frame, _) = restored.get_independent_frame(single)?;
+        assert!(frame.get_attribute("update", "single").is_some());
+        restored.apply_updates(batch_id)?;
+        let (frame, _) = restored.get_batched_frame(batch_id, second)?;
+        assert!(frame.get_attribute("update", "batched").is_some());
+        let (frame, _) = restored.get_batched_frame(batch_id, first)?;
+        assert!(frame.get_attribute("update", "batched").is_none());
+
+        // the ids and the frame ordering continue after the restored frames
+        let frame = gen_frame();
+        let next = restored.add_frame("input", frame.clone())?;
+        assert!(next > batch_id);
+        assert_eq!(frame.get_previous_frame_seq_id(), Some(second));
+
+        restored.move_and_unpack_batch("output", batch_id)?;
+        restored.delete(first)?;
+        restored.delete(second)?;
+
+        assert!(restored.restore(&snapshot).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_validation() -> anyhow::Result<()> {
+        let pipeline = create_test_pipeline()?;
+        let id = pipeline.add_frame("input", gen_frame())?;
+        pipeline.move_and_pack_frames("proc1", vec![id])?;
+        let mut snapshot = pipeline.snapshot();
+
+        snapshot.stages[1].name = "output".to_string();
+        assert!(create_test_pipeline()?.restore(&snapshot).is_err());
+
+        snapshot.stages[1].name = "missing".to_string();
+        let restored = create_test_pipeline()?;
+        assert!(restored.restore(&snapshot).is_err());
+        assert_eq!(restored.get_id_locations_len(), 0);
+
+        // the ids must be unique and must not collide with the frames of the pipeline
+        snapshot.stages[1].name = "proc1".to_string();
+        let mut duplicate = snapshot.clone();
+        duplicate.stages[1].payloads[0]
+            .frames
+            .push(snapshot.stages[1].payloads[0].frames[0].clone());
+        assert!(restored.restore(&duplicate).is_err());
+        assert_eq!(restored.get_id_locations_len(), 0);
+
+        restored.add_frame("input", gen_frame())?;
+        assert!(restored.restore(&snapshot).is_err());
+        assert_eq!(restored.get_id_locations_len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use hashbrown::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::pipeline::stage::{StageCapacity, StageOverflowPolicy, StageRoute};
+use crate::pipeline::stage_function_loader::create_registered_stage_function;
+use crate::pipeline::{
+    Pipeline, PipelineConfiguration, PipelineStageFunction, PipelineStagePayloadType, PluginParams,
+};
+use crate::primitives::attribute_value::AttributeValue;
+
+/// A stage function provided by a registered plugin, see
+/// [`register_plugin`](super::stage_function_loader::register_plugin).
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StageFunctionSpec {
+    pub plugin: String,
+    pub function: String,
+    #[serde(default)]
+    pub params: HashMap<String, AttributeValue>,
+}
+
+impl StageFunctionSpec {
+    fn create(&self) -> anyhow::Result<Box<dyn PipelineStageFunction>> {
+        create_registered_stage_function(
+            &self.plugin,
+            &self.function,
+            PluginParams {
+                params: self.params.clone(),
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StageCapacitySpec {
+    pub limit: usize,
+    #[serde(default = "StageCapacitySpec::default_policy")]
+    pub policy: StageOverflowPolicy,
+    #[serde(default)]
+    pub block_timeout_ms: Option<u64>,
+}
+
+impl StageCapacitySpec {
+    fn default_policy() -> StageOverflowPolicy {
+        StageOverflowPolicy::Block
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StageSpec {
+    pub name: String,
+    pub payload: PipelineStagePayloadType,
+    #[serde(default)]
+    pub capacity: Option<StageCapacitySpec>,
+    #[serde(default)]
+    pub ingress: Option<StageFunctionSpec>,
+    #[serde(default)]
+    pub egress: Option<StageFunctionSpec>,
+    #[serde(default)]
+    pub routes: Vec<StageRoute>,
+}
+
+/// The pipeline topology: the stages in order, their payload types, queue limits, stage
+/// functions and routes. The specification is a JSON or YAML document:
+///
+/// ```yaml
+/// name: detector
+/// stages:
+///   - name: input
+///     payload: frame
+///     capacity: { limit: 100, policy: drop_oldest }
+///     routes:
+///       - destination: people
+///         query: { label: { eq: person } }
+///   - name: people
+///     payload: frame
+/// ```
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineSpec {
+    /// The name of the root spans of the frames.
+    pub name: String,
+    pub stages: Vec<StageSpec>,
+}
+
+impl PipelineSpec {
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parses the YAML (or JSON, which is a subset of YAML) specification.
+    ///
+    pub fn from_yaml(yaml: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_value(serde_yaml::from_str(yaml)?)?)
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(&serde_json::to_value(self).unwrap()).unwrap()
+    }
+
+    /// Checks the specification, the errors name the offending stage.
+    ///
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.stages.is_empty() {
+            bail!("The pipeline {} has no stages", self.name)
+        }
+        let mut names = HashSet::with_capacity(self.stages.len());
+        for (index, stage) in self.stages.iter().enumerate() {
+            if stage.name.is_empty() {
+                bail!("Stage #{}: the name must not be empty", index)
+            }
+            if !names.insert(stage.name.as_str()) {
+                bail!("Stage {}: the name is used more than once", stage.name)
+            }
+            if matches!(&stage.capacity, Some(c) if c.limit == 0) {
+                bail!(
+                    "Stage {}: the capacity limit must be greater than 0",
+                    stage.name
+                )
+            }
+        }
+        for (index, stage) in self.stages.iter().enumerate() {
+            for route in &stage.routes {
+                match self.stages.iter().position(|s| s.name == route.destination) {
+                    None => bail!(
+                        "Stage {}: the route destination {} is not defined",
+                        stage.name,
+                        route.destination
+                    ),
+                    Some(dest) if dest <= index => bail!(
+                        "Stage {}: the route destination {} must follow the stage",
+                        stage.name,
+                        route.destination
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the specification and creates the pipeline. The queue limits and routes of the
+    /// specification replace the ones set in the configuration for the same stages.
+    ///
+    pub fn build(&self, mut configuration: PipelineConfiguration) -> anyhow::Result<Pipeline> {
+        self.validate()?;
+        let mut stages = Vec::with_capacity(self.stages.len());
+        for stage in &self.stages {
+            let create = |f: &Option<StageFunctionSpec>, order: &str| {
+                f.as_ref().map(|f| f.create()).transpose().map_err(|e| {
+                    anyhow!(
+                        "Stage {}: failed to create the {} function: {}",
+                        stage.name,
+                        order,
+                        e
+                    )
+                })
+            };
+            let ingress = create(&stage.ingress, "ingress")?;
+            let egress = create(&stage.egress, "egress")?;
+            if let Some(capacity) = &stage.capacity {
+                configuration.stage_capacity.insert(
+                    stage.name.clone(),
+                    StageCapacity {
+                        capacity: capacity.limit,
+                        policy: capacity.policy,
+                        block_timeout: capacity.block_timeout_ms.map(Duration::from_millis),
+                    },
+                );
+            }
+            if !stage.routes.is_empty() {
+                configuration
+                    .stage_routes
+                    .insert(stage.name.clone(), stage.routes.clone());
+            }
+            stages.push((stage.name.clone(), stage.payload.clone(), ingress, egress));
+        }
+        let pipeline = Pipeline::new(stages, configuration)?;
+        pipeline.set_root_span_name(self.name.clone())?;
+        Ok(pipeline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::match_query::{eq, MatchQuery};
+    use crate::pipeline::spec::PipelineSpec;
+    use crate::pipeline::stage::StageOverflowPolicy;
+    use crate::pipeline::{PipelineConfigurationBuilder, PipelineStagePayloadType};
+    use crate::primitives::object::IdCollisionResolutionPolicy;
+    use crate::test::{gen_frame, gen_object};
+
+    const SPEC: &str = r#"
+name: test
+stages:
+  - name: input
+    payload: frame
+    capacity:
+      limit: 10
+      policy: reject
+  - name: batch
+    payload: batch
+  - name: output
+    payload: frame
+"#;
+
+    #[test]
+    fn test_from_yaml() -> anyhow::Result<()> {
+        let spec = PipelineSpec::from_yaml(SPEC)?;
+        assert_eq!(spec.stages.len(), 3);
+        assert_eq!(spec.stages[1].payload, PipelineStagePayloadType::Batch);
+        let capacity = spec.stages[0].capacity.as_ref().unwrap();
+        assert_eq!(capacity.limit, 10);
+        assert_eq!(capacity.policy, StageOverflowPolicy::Reject);
+
+        let pipeline = spec.build(PipelineConfigurationBuilder::default().build()?)?;
+        assert_eq!(pipeline.get_root_span_name(), "test");
+        assert_eq!(pipeline.get_stage_occupancy("input")?.capacity, Some(10));
+        let id = pipeline.add_frame("input", gen_frame())?;
+        let batch_id = pipeline.move_and_pack_frames("batch", vec![id])?;
+        pipeline.move_and_unpack_batch("output", batch_id)?;
+
+        let json = serde_json::to_string(&spec)?;
+        assert_eq!(PipelineSpec::from_json(&json)?.stages.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validation() {
+        let error = |yaml: &str| {
+            PipelineSpec::from_yaml(yaml)
+                .and_then(|spec| spec.validate())
+                .unwrap_err()
+                .to_string()
+        };
+        let duplicate = r#"
+name: test
+stages:
+  - { name: input, payload: frame }
+  - { name: input, payload: frame }
+"#;
+        assert!(error(duplicate).starts_with("Stage input:"));
+
+        let zero_capacity = r#"
+name: test
+stages:
+  - { name: input, payload: frame, capacity: { limit: 0 } }
+"#;
+        assert!(error(zero_capacity).starts_with("Stage input:"));
+
+        let backward = r#"
+name: test
+stages:
+  - { name: input, payload: frame }
+  - name: output
+    payload: frame
+    routes:
+      - { destination: input, query: pass }
+"#;
+        assert!(error(backward).starts_with("Stage output:"));
+
+        let unknown = r#"
+name: test
+stages:
+  - name: input
+    payload: frame
+    routes:
+      - { destination: missing, query: pass }
+"#;
+        assert!(error(unknown).starts_with("Stage input:"));
+
+        let missing_plugin = r#"
+name: test
+stages:
+  - name: input
+    payload: frame
+    ingress: { plugin: missing, function: counter }
+"#;
+        let res = PipelineSpec::from_yaml(missing_plugin)
+            .and_then(|spec| spec.build(PipelineConfigurationBuilder::default().build()?));
+        assert!(res.unwrap_err().to_string().starts_with("Stage input:"));
+    }
+
+    #[test]
+    fn test_routes() -> anyhow::Result<()> {
+        let query = serde_json::to_value(MatchQuery::Label(eq("person")))?;
+        let spec = serde_json::json!({
+            "name": "test",
+            "stages": [
+                {
+                    "name": "input",
+                    "payload": "frame",
+                    "routes": [{"destination": "people", "query": query}]
+                },
+                {"name": "people", "payload": "frame"},
+                {"name": "other", "payload": "frame"}
+            ]
+        });
+        let pipeline = PipelineSpec::from_json(&spec.to_string())?
+            .build(PipelineConfigurationBuilder::default().build()?)?;
+
+        let frame = gen_frame();
+        let id = pipeline.add_frame("input", frame.clone())?;
+        let empty_id = pipeline.add_frame("input", gen_frame())?;
+        assert_eq!(pipeline.route(empty_id)?, None);
+
+        let mut object = gen_object(100);
+        object.label = "person".to_string();
+        frame.add_object(object, IdCollisionResolutionPolicy::GenerateNewId)?;
+        assert_eq!(pipeline.route(id)?.as_deref(), Some("people"));
+
+        pipeline.move_as_is("people", vec![id])?;
+        assert_eq!(pipeline.route(id)?, None);
+        Ok(())
+    }
+}
@@ -1,16 +1,23 @@
+use std::any::Any;
 use std::fmt::{Debug, Formatter};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::bail;
 use hashbrown::{HashMap, HashSet};
-use opentelemetry::trace::TraceContextExt;
-use opentelemetry::Context;
-use parking_lot::Mutex;
+use opentelemetry::trace::{Span, SpanBuilder, Status, TraceContextExt, Tracer};
+use opentelemetry::{Context, KeyValue};
+use parking_lot::{Condvar, Mutex, RwLockWriteGuard};
+use serde::{Deserialize, Serialize};
 
+use crate::get_tracer;
 use crate::match_query::MatchQuery;
 use crate::pipeline::implementation::Pipeline;
-use crate::pipeline::stats::{StageLatencyStat, StageProcessingStat, StageStats};
+use crate::pipeline::stats::{
+    StageLatencyStat, StageProcessingStat, StageResidencyStat, StageResidencyStats, StageStats,
+};
 use crate::pipeline::{
     PipelinePayload, PipelineStageFunction, PipelineStageFunctionOrder, PipelineStagePayloadType,
 };
@@ -20,6 +27,121 @@ use crate::primitives::frame_update::VideoFrameUpdate;
 use crate::primitives::object::BorrowedVideoObject;
 use crate::rwlock::SavantRwLock;
 
+#[derive(Debug, thiserror::Error)]
+pub enum StageFunctionError {
+    #[error("{order:?} function of stage {stage} panicked on payload {id}: {message}")]
+    Panicked {
+        stage: String,
+        order: PipelineStageFunctionOrder,
+        id: i64,
+        message: String,
+    },
+}
+
+/// Upper bounds (in microseconds) of the stage function latency histogram buckets. The last
+/// histogram bucket counts the calls exceeding the largest bound.
+///
+pub const STAGE_FUNCTION_LATENCY_BUCKETS_US: [u64; 8] =
+    [10, 50, 100, 500, 1_000, 5_000, 10_000, 50_000];
+
+#[derive(Debug, Clone, Default)]
+pub struct StageFunctionStat {
+    pub calls: u64,
+    pub errors: u64,
+    pub panics: u64,
+    pub accumulated_latency: Duration,
+    pub max_latency: Duration,
+    pub latency_histogram: [u64; STAGE_FUNCTION_LATENCY_BUCKETS_US.len() + 1],
+}
+
+impl StageFunctionStat {
+    fn record(&mut self, latency: Duration, failed: bool, panicked: bool) {
+        self.calls += 1;
+        if failed {
+            self.errors += 1;
+        }
+        if panicked {
+            self.panics += 1;
+        }
+        self.accumulated_latency += latency;
+        self.max_latency = self.max_latency.max(latency);
+        let us = latency.as_micros() as u64;
+        let bucket = STAGE_FUNCTION_LATENCY_BUCKETS_US
+            .iter()
+            .position(|b| us <= *b)
+            .unwrap_or(STAGE_FUNCTION_LATENCY_BUCKETS_US.len());
+        self.latency_histogram[bucket] += 1;
+    }
+
+    pub fn avg_latency(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((self.accumulated_latency.as_nanos() / self.calls as u128) as u64)
+        }
+    }
+}
+
+/// What happens to the payloads entering a stage which is full.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageOverflowPolicy {
+    /// The caller waits until the downstream code removes payloads from the stage.
+    Block,
+    /// The payloads which entered the stage first are discarded to make room.
+    DropOldest,
+    /// The operation fails, the payloads stay where they were.
+    Reject,
+}
+
+/// The maximum number of payloads (frames or batches) a stage holds.
+///
+#[derive(Debug, Clone)]
+pub struct StageCapacity {
+    pub capacity: usize,
+    pub policy: StageOverflowPolicy,
+    /// When set, a blocked operation fails after the timeout, otherwise it waits indefinitely.
+    pub block_timeout: Option<Duration>,
+}
+
+/// Sends the objects matching the query to the destination stage, see
+/// [`Pipeline::route`](crate::pipeline::Pipeline::route).
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageRoute {
+    pub destination: String,
+    pub query: MatchQuery,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageOccupancy {
+    pub stage_name: String,
+    pub queue_len: usize,
+    pub capacity: Option<usize>,
+    pub dropped: u64,
+    pub rejected: u64,
+}
+
+#[derive(Debug, Default)]
+struct StageFunctionHealth {
+    consecutive_failures: AtomicUsize,
+    quarantined: AtomicBool,
+    stat: Mutex<StageFunctionStat>,
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+pub(crate) type PayloadMap = HashMap<i64, PipelinePayload>;
+
 pub struct PipelineStage {
     pub id: usize,
     pub name: String,
@@ -28,6 +150,16 @@ pub struct PipelineStage {
     pub stat: StageStats,
     ingress_function: Option<Box<dyn PipelineStageFunction>>,
     egress_function: Option<Box<dyn PipelineStageFunction>>,
+    ingress_health: StageFunctionHealth,
+    egress_health: StageFunctionHealth,
+    quarantine_threshold: Option<usize>,
+    capacity: Option<StageCapacity>,
+    vacancy: Condvar,
+    vacancy_lock: Mutex<()>,
+    dropped: AtomicU64,
+    rejected: AtomicU64,
+    residency: Mutex<StageResidencyStat>,
+    residency_spans: bool,
 }
 
 impl Debug for PipelineStage {
@@ -40,6 +172,14 @@ impl Debug for PipelineStage {
             .field("stat", &self.stat)
             .field("ingress_function", &self.ingress_function.is_some())
             .field("egress_function", &self.egress_function.is_some())
+            .field("ingress_health", &self.ingress_health)
+            .field("egress_health", &self.egress_health)
+            .field("quarantine_threshold", &self.quarantine_threshold)
+            .field("capacity", &self.capacity)
+            .field("dropped", &self.dropped)
+            .field("rejected", &self.rejected)
+            .field("residency", &self.residency)
+            .field("residency_spans", &self.residency_spans)
             .finish()
     }
 }
@@ -63,6 +203,16 @@ impl PipelineStage {
             ))),
             ingress_function,
             egress_function,
+            ingress_health: StageFunctionHealth::default(),
+            egress_health: StageFunctionHealth::default(),
+            quarantine_threshold: None,
+            capacity: None,
+            vacancy: Condvar::new(),
+            vacancy_lock: Mutex::new(()),
+            dropped: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            residency: Mutex::new(StageResidencyStat::default()),
+            residency_spans: false,
         }
     }
 
@@ -70,6 +220,256 @@ impl PipelineStage {
         self.stat.clone()
     }
 
+    /// When set, a stage function failing (returning an error or panicking) the given number
+    /// of times in a row is quarantined: it is not called anymore until the quarantine is reset.
+    ///
+    pub fn set_function_quarantine_threshold(&mut self, threshold: Option<usize>) {
+        self.quarantine_threshold = threshold;
+    }
+
+    /// Limits the number of payloads the stage holds, unlimited when not set.
+    ///
+    pub fn set_capacity(&mut self, capacity: Option<StageCapacity>) {
+        self.capacity = capacity;
+    }
+
+    pub fn get_capacity(&self) -> Option<&StageCapacity> {
+        self.capacity.as_ref()
+    }
+
+    /// When set, a span covering the time the payload spent in the stage is exported when the
+    /// payload leaves the stage.
+    ///
+    pub fn set_residency_spans(&mut self, enabled: bool) {
+        self.residency_spans = enabled;
+    }
+
+    pub fn get_residency_stats(&self) -> StageResidencyStats {
+        let queue_depth = self.len();
+        self.residency.lock().summary(&self.name, queue_depth)
+    }
+
+    fn record_exit(&self, payload: &PipelinePayload, queue_depth: usize) {
+        let (entered, contexts) = match payload {
+            PipelinePayload::Frame(_, _, ctx, _, time) => (Some(*time), vec![ctx]),
+            PipelinePayload::Batch(_, _, contexts, _, times) => {
+                (times.first().copied(), contexts.values().collect())
+            }
+        };
+        let Some(entered) = entered else {
+            return;
+        };
+        self.residency
+            .lock()
+            .record(Instant::now(), entered.elapsed().unwrap_or_default());
+
+        if !self.residency_spans {
+            return;
+        }
+        for ctx in contexts {
+            if !ctx.span().span_context().is_valid() {
+                continue;
+            }
+            let mut span = get_tracer().build_with_context(
+                SpanBuilder::from_name(format!("{}/residency", self.name))
+                    .with_start_time(entered)
+                    .with_attributes(vec![
+                        KeyValue::new("savant.stage", self.name.clone()),
+                        KeyValue::new("savant.queue_depth", queue_depth as i64),
+                    ]),
+                ctx,
+            );
+            span.end();
+        }
+    }
+
+    pub fn get_occupancy(&self) -> StageOccupancy {
+        StageOccupancy {
+            stage_name: self.name.clone(),
+            queue_len: self.len(),
+            capacity: self.capacity.as_ref().map(|c| c.capacity),
+            dropped: self.dropped.load(Ordering::Acquire),
+            rejected: self.rejected.load(Ordering::Acquire),
+        }
+    }
+
+    pub(crate) fn record_rejected(&self) {
+        self.rejected.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn has_vacancy(&self, count: usize) -> bool {
+        match &self.capacity {
+            Some(c) => self.len() + count <= c.capacity,
+            None => true,
+        }
+    }
+
+    pub(crate) fn notify_vacancy(&self) {
+        if self.capacity.is_some() {
+            let _guard = self.vacancy_lock.lock();
+            self.vacancy.notify_all();
+        }
+    }
+
+    /// Waits until the stage has room for the given number of payloads. Returns `false` when the
+    /// deadline passes first. Another producer may take the room before the caller locks the
+    /// stage, so the caller checks the room again under the lock.
+    ///
+    pub fn wait_for_vacancy(&self, count: usize, deadline: Option<Instant>) -> bool {
+        let mut guard = self.vacancy_lock.lock();
+        while !self.has_vacancy(count) {
+            match deadline {
+                Some(deadline) => {
+                    if self.vacancy.wait_until(&mut guard, deadline).timed_out() {
+                        return self.has_vacancy(count);
+                    }
+                }
+                None => self.vacancy.wait(&mut guard),
+            }
+        }
+        true
+    }
+
+    /// Removes the payloads which entered the locked stage first without calling the egress
+    /// function, counts them as dropped.
+    ///
+    pub(crate) fn discard_oldest(
+        &self,
+        bind: &mut PayloadMap,
+        count: usize,
+    ) -> Vec<(i64, PipelinePayload)> {
+        let mut entries = bind
+            .iter()
+            .map(|(id, payload)| {
+                let entered = match payload {
+                    PipelinePayload::Frame(_, _, _, _, time) => Some(*time),
+                    PipelinePayload::Batch(_, _, _, _, times) => times.first().copied(),
+                };
+                (entered, *id)
+            })
+            .collect::<Vec<_>>();
+        entries.sort_unstable();
+        let dropped = entries
+            .into_iter()
+            .take(count)
+            .filter_map(|(_, id)| bind.remove(&id).map(|p| (id, p)))
+            .collect::<Vec<_>>();
+        self.dropped
+            .fetch_add(dropped.len() as u64, Ordering::AcqRel);
+        self.stat.lock().0.queue_length = bind.len();
+        dropped
+    }
+
+    fn function_health(&self, order: PipelineStageFunctionOrder) -> &StageFunctionHealth {
+        match order {
+            PipelineStageFunctionOrder::Ingress => &self.ingress_health,
+            PipelineStageFunctionOrder::Egress => &self.egress_health,
+        }
+    }
+
+    pub fn is_function_quarantined(&self, order: PipelineStageFunctionOrder) -> bool {
+        self.function_health(order)
+            .quarantined
+            .load(Ordering::Acquire)
+    }
+
+    /// Returns the call statistics of the stage functions defined for the stage.
+    ///
+    pub fn get_function_stats(&self) -> Vec<(PipelineStageFunctionOrder, StageFunctionStat)> {
+        [
+            (PipelineStageFunctionOrder::Ingress, &self.ingress_function),
+            (PipelineStageFunctionOrder::Egress, &self.egress_function),
+        ]
+        .into_iter()
+        .filter(|(_, f)| f.is_some())
+        .map(|(order, _)| (order, self.function_health(order).stat.lock().clone()))
+        .collect()
+    }
+
+    pub fn reset_function_quarantine(&self, order: PipelineStageFunctionOrder) {
+        let health = self.function_health(order);
+        health.consecutive_failures.store(0, Ordering::Release);
+        health.quarantined.store(false, Ordering::Release);
+    }
+
+    fn call_function(
+        &self,
+        order: PipelineStageFunctionOrder,
+        id: i64,
+        payload: &mut PipelinePayload,
+    ) -> anyhow::Result<()> {
+        let function = match order {
+            PipelineStageFunctionOrder::Ingress => &self.ingress_function,
+            PipelineStageFunctionOrder::Egress => &self.egress_function,
+        };
+        let Some(function) = function else {
+            return Ok(());
+        };
+        let health = self.function_health(order);
+        if health.quarantined.load(Ordering::Acquire) {
+            log::trace!(
+                target: "savant_rs::pipeline",
+                "{:?} function of stage {} is quarantined, payload {} is passed as is",
+                order,
+                self.name,
+                id
+            );
+            return Ok(());
+        }
+
+        let span_ctx = match payload {
+            PipelinePayload::Frame(_, _, ctx, _, _) => Pipeline::get_nested_span(
+                format!("{}/{:?}-function", self.name, order).to_lowercase(),
+                ctx,
+            ),
+            // batched frames have individual contexts, no single span can be attributed
+            PipelinePayload::Batch(..) => Context::default(),
+        };
+        let span_guard = span_ctx.attach();
+
+        let started = Instant::now();
+        let outcome =
+            std::panic::catch_unwind(AssertUnwindSafe(|| function.call(id, self, order, payload)));
+        let elapsed = started.elapsed();
+        let panicked = outcome.is_err();
+        let res = outcome.unwrap_or_else(|panic| {
+            Err(StageFunctionError::Panicked {
+                stage: self.name.clone(),
+                order,
+                id,
+                message: panic_message(&*panic),
+            }
+            .into())
+        });
+        if let Err(e) = &res {
+            Context::current()
+                .span()
+                .set_status(Status::error(e.to_string()));
+        }
+        drop(span_guard);
+
+        health.stat.lock().record(elapsed, res.is_err(), panicked);
+
+        match &res {
+            Ok(_) => health.consecutive_failures.store(0, Ordering::Release),
+            Err(e) => {
+                let failures = health.consecutive_failures.fetch_add(1, Ordering::AcqRel) + 1;
+                if matches!(self.quarantine_threshold, Some(t) if failures >= t) {
+                    health.quarantined.store(true, Ordering::Release);
+                    log::error!(
+                        target: "savant_rs::pipeline",
+                        "{:?} function of stage {} is quarantined after {} consecutive failures, last error: {}",
+                        order,
+                        self.name,
+                        failures,
+                        e
+                    );
+                }
+            }
+        }
+        res
+    }
+
     fn with_payload_item_mut<F, T>(&self, id: i64, f: F) -> anyhow::Result<T>
     where
         F: FnOnce(&mut PipelinePayload) -> T,
@@ -152,59 +552,70 @@ impl PipelineStage {
             .sum::<usize>();
     }
 
+    /// Locks the payloads of the stage for an operation spanning several stages. The stages
+    /// must be locked in the order of their ids to avoid deadlocks.
+    ///
+    pub(crate) fn lock_payload(&self) -> RwLockWriteGuard<'_, PayloadMap> {
+        self.payload.write()
+    }
+
     pub fn add_payloads<I>(&self, payloads: I) -> anyhow::Result<()>
     where
         I: IntoIterator<Item = (i64, PipelinePayload)>,
     {
-        self.with_payload_mut(|bind| {
-            for (id, mut payload) in payloads {
-                if let Some(ingress_function) = &self.ingress_function {
-                    ingress_function.call(
-                        id,
-                        self,
-                        PipelineStageFunctionOrder::Ingress,
-                        &mut payload,
-                    )?;
-                }
-                if bind.contains_key(&id) {
-                    bail!("Payload {} already exists", id)
-                }
-                let payload = match payload {
-                    PipelinePayload::Frame(f, updates, context, last_stage, last_time) => {
-                        if self.stage_type == PipelineStagePayloadType::Batch {
-                            bail!("Payload must be a batch")
-                        } else {
-                            self.update_processing_stats_for_frame(&f);
-                            self.update_latency_stats(last_stage, vec![last_time]);
-                        }
-                        PipelinePayload::Frame(
-                            f,
-                            updates,
-                            context,
-                            Some(self.name.clone()),
-                            SystemTime::now(),
-                        )
+        self.with_payload_mut(|bind| self.insert_payloads(bind, payloads))
+    }
+
+    /// Adds the payloads to the locked stage, calling the ingress function for each of them.
+    ///
+    pub(crate) fn insert_payloads<I>(
+        &self,
+        bind: &mut PayloadMap,
+        payloads: I,
+    ) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = (i64, PipelinePayload)>,
+    {
+        for (id, mut payload) in payloads {
+            self.call_function(PipelineStageFunctionOrder::Ingress, id, &mut payload)?;
+            if bind.contains_key(&id) {
+                bail!("Payload {} already exists", id)
+            }
+            let payload = match payload {
+                PipelinePayload::Frame(f, updates, context, last_stage, last_time) => {
+                    if self.stage_type == PipelineStagePayloadType::Batch {
+                        bail!("Payload must be a batch")
+                    } else {
+                        self.update_processing_stats_for_frame(&f);
+                        self.update_latency_stats(last_stage, vec![last_time]);
                     }
-                    PipelinePayload::Batch(b, updates, contexts, last_stage, last_times) => {
-                        if self.stage_type == PipelineStagePayloadType::Frame {
-                            bail!("Payload must be a frame")
-                        } else {
-                            self.update_processing_stats_for_batch(&b);
-                            self.update_latency_stats(last_stage, last_times);
-                        }
-                        PipelinePayload::Batch(
-                            b,
-                            updates,
-                            contexts,
-                            Some(self.name.clone()),
-                            vec![SystemTime::now()],
-                        )
+                    PipelinePayload::Frame(
+                        f,
+                        updates,
+                        context,
+                        Some(self.name.clone()),
+                        SystemTime::now(),
+                    )
+                }
+                PipelinePayload::Batch(b, updates, contexts, last_stage, last_times) => {
+                    if self.stage_type == PipelineStagePayloadType::Frame {
+                        bail!("Payload must be a frame")
+                    } else {
+                        self.update_processing_stats_for_batch(&b);
+                        self.update_latency_stats(last_stage, last_times);
                     }
-                };
-                bind.insert(id, payload);
-            }
-            Ok(())
-        })
+                    PipelinePayload::Batch(
+                        b,
+                        updates,
+                        contexts,
+                        Some(self.name.clone()),
+                        vec![SystemTime::now()],
+                    )
+                }
+            };
+            bind.insert(id, payload);
+        }
+        Ok(())
     }
 
     pub fn add_frame_payload(&self, frame_id: i64, payload: PipelinePayload) -> anyhow::Result<()> {
@@ -221,14 +632,11 @@ impl PipelineStage {
                     self.update_latency_stats(last_stage, vec![last_time]);
                     let mut payload =
                         PipelinePayload::Frame(f, u, c, Some(self.name.clone()), SystemTime::now());
-                    if let Some(ingress_function) = &self.ingress_function {
-                        ingress_function.call(
-                            frame_id,
-                            self,
-                            PipelineStageFunctionOrder::Ingress,
-                            &mut payload,
-                        )?;
-                    }
+                    self.call_function(
+                        PipelineStageFunctionOrder::Ingress,
+                        frame_id,
+                        &mut payload,
+                    )?;
                     bind.insert(frame_id, payload);
                 }
             }
@@ -255,14 +663,11 @@ impl PipelineStage {
                         Some(self.name.clone()),
                         vec![SystemTime::now()],
                     );
-                    if let Some(ingress_function) = &self.ingress_function {
-                        ingress_function.call(
-                            batch_id,
-                            self,
-                            PipelineStageFunctionOrder::Ingress,
-                            &mut payload,
-                        )?;
-                    }
+                    self.call_function(
+                        PipelineStageFunctionOrder::Ingress,
+                        batch_id,
+                        &mut payload,
+                    )?;
                     bind.insert(batch_id, payload);
                 }
             }
@@ -271,42 +676,62 @@ impl PipelineStage {
     }
 
     pub fn delete(&self, id: i64) -> anyhow::Result<Option<PipelinePayload>> {
-        self.with_payload_mut(|bind| {
+        let res = self.with_payload_mut(|bind| {
             let mut res = bind.remove(&id);
-            if let Some(egress_function) = &self.egress_function {
-                if let Some(payload) = res.as_mut() {
-                    egress_function.call(id, self, PipelineStageFunctionOrder::Egress, payload)?;
-                }
+            if let Some(payload) = res.as_mut() {
+                self.call_function(PipelineStageFunctionOrder::Egress, id, payload)?;
             }
-            if res.is_some() {
+            if let Some(payload) = res.as_ref() {
+                self.record_exit(payload, bind.len());
                 let mut stats_bind = self.stat.lock();
                 stats_bind.0.queue_length = bind.len();
             }
             Ok(res)
-        })
+        });
+        // notified out of the payload lock, the waiters take it to check the length
+        self.notify_vacancy();
+        res
+    }
+
+    /// Removes the payloads from the locked stage, calling the egress function for each of them.
+    /// Fails without removing anything when any of the payloads is not in the stage.
+    ///
+    pub(crate) fn take_payloads(
+        &self,
+        bind: &mut PayloadMap,
+        ids: &[i64],
+    ) -> anyhow::Result<Vec<(i64, PipelinePayload)>> {
+        if let Some(id) = ids.iter().find(|id| !bind.contains_key(*id)) {
+            bail!("Payload {} not found in stage {}", id, self.name)
+        }
+        let mut removed = Vec::with_capacity(ids.len());
+        for id in ids {
+            let mut p = bind.remove(id).expect("Presence is checked above");
+            self.call_function(PipelineStageFunctionOrder::Egress, *id, &mut p)?;
+            self.record_exit(&p, bind.len());
+            removed.push((*id, p));
+        }
+        self.stat.lock().0.queue_length = bind.len();
+        Ok(removed)
     }
 
     pub fn delete_many(&self, ids: &[i64]) -> anyhow::Result<Vec<(i64, PipelinePayload)>> {
-        self.with_payload_mut(|bind| {
+        let res = self.with_payload_mut(|bind| {
             let mut removed = Vec::with_capacity(ids.len());
             for id in ids {
                 let v = bind.remove(id);
                 if let Some(mut p) = v {
-                    if let Some(egress_function) = &self.egress_function {
-                        egress_function.call(
-                            *id,
-                            self,
-                            PipelineStageFunctionOrder::Egress,
-                            &mut p,
-                        )?;
-                    }
+                    self.call_function(PipelineStageFunctionOrder::Egress, *id, &mut p)?;
+                    self.record_exit(&p, bind.len());
                     removed.push((*id, p));
                 }
             }
             let mut stats_bind = self.stat.lock();
             stats_bind.0.queue_length = bind.len();
             Ok(removed)
-        })
+        });
+        self.notify_vacancy();
+        res
     }
 
     pub fn len(&self) -> usize {
@@ -453,15 +878,18 @@ impl PipelineStage {
 
 #[cfg(test)]
 mod tests {
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
 
     use anyhow::Result;
     use hashbrown::HashMap;
     use opentelemetry::Context;
 
     use crate::match_query::MatchQuery;
-    use crate::pipeline::stage::PipelineStage;
-    use crate::pipeline::{PipelinePayload, PipelineStagePayloadType};
+    use crate::pipeline::stage::{PipelineStage, StageFunctionStat};
+    use crate::pipeline::{
+        Pipeline, PipelinePayload, PipelineStageFunction, PipelineStageFunctionOrder,
+        PipelineStagePayloadType,
+    };
     use crate::primitives::frame_batch::VideoFrameBatch;
     use crate::primitives::frame_update::VideoFrameUpdate;
     use crate::primitives::{Attribute, WithAttributes};
@@ -928,4 +1356,80 @@ mod tests {
         assert_eq!(objects.get(&3).unwrap().len(), 3);
         Ok(())
     }
+
+    struct PanickingFunction {
+        pipeline: Option<Pipeline>,
+    }
+
+    impl PipelineStageFunction for PanickingFunction {
+        fn set_pipeline(&mut self, pipeline: Pipeline) {
+            self.pipeline = Some(pipeline);
+        }
+        fn get_pipeline(&self) -> &Option<Pipeline> {
+            &self.pipeline
+        }
+        fn call(
+            &self,
+            _: i64,
+            _: &PipelineStage,
+            _: PipelineStageFunctionOrder,
+            _: &mut PipelinePayload,
+        ) -> Result<()> {
+            panic!("plugin failure")
+        }
+    }
+
+    fn frame_payload() -> PipelinePayload {
+        PipelinePayload::Frame(
+            gen_frame(),
+            Vec::default(),
+            Context::default(),
+            None,
+            SystemTime::now(),
+        )
+    }
+
+    #[test]
+    fn test_panicking_function_quarantine() -> Result<()> {
+        let mut stage = PipelineStage::new(
+            0,
+            "stage".to_string(),
+            PipelineStagePayloadType::Frame,
+            Some(Box::new(PanickingFunction { pipeline: None })),
+            None,
+        );
+        stage.set_function_quarantine_threshold(Some(2));
+
+        let err = stage.add_frame_payload(1, frame_payload()).unwrap_err();
+        assert!(err.to_string().contains("plugin failure"));
+        assert!(!stage.is_function_quarantined(PipelineStageFunctionOrder::Ingress));
+
+        assert!(stage.add_frame_payload(2, frame_payload()).is_err());
+        assert!(stage.is_function_quarantined(PipelineStageFunctionOrder::Ingress));
+
+        stage.add_frame_payload(3, frame_payload())?;
+        assert_eq!(stage.len(), 1);
+
+        stage.reset_function_quarantine(PipelineStageFunctionOrder::Ingress);
+        assert!(stage.add_frame_payload(4, frame_payload()).is_err());
+
+        let stats = stage.get_function_stats();
+        assert_eq!(stats.len(), 1);
+        let (order, stat) = &stats[0];
+        assert_eq!(*order, PipelineStageFunctionOrder::Ingress);
+        assert_eq!(stat.calls, 3);
+        assert_eq!(stat.errors, 3);
+        assert_eq!(stat.panics, 3);
+        assert_eq!(stat.latency_histogram.iter().sum::<u64>(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_avg_latency() {
+        let mut stat = StageFunctionStat::default();
+        assert_eq!(stat.avg_latency(), Duration::ZERO);
+        stat.calls = u32::MAX as u64 * 4;
+        stat.accumulated_latency = Duration::from_micros(stat.calls * 3);
+        assert_eq!(stat.avg_latency(), Duration::from_micros(3));
+    }
 }
@@ -1,10 +1,117 @@
-use crate::pipeline::{PipelineStageFunction, PluginParams};
+use crate::pipeline::{
+    PipelineStageFunction, PluginAbi, PluginAbiFunction, PluginParams, PLUGIN_ABI_SYMBOL,
+};
+use anyhow::{anyhow, bail};
 use hashbrown::HashMap;
 use lazy_static::lazy_static;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 lazy_static! {
     static ref LIBRARIES: Mutex<HashMap<String, libloading::Library>> = Mutex::new(HashMap::new());
+    static ref MANIFESTS: Mutex<HashMap<String, PluginManifest>> = Mutex::new(HashMap::new());
+}
+
+/// A stage function exported by a plugin library.
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginFunctionManifest {
+    /// The name the function is registered under.
+    pub name: String,
+    /// The exported factory symbol, see [`PipelineStageFunctionFactory`](super::PipelineStageFunctionFactory).
+    pub init: String,
+}
+
+/// Describes a plugin library and the stage functions it exports. Manifests are JSON or YAML
+/// files discovered by [`register_plugins_from_dir`].
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    /// Path to the shared library, relative paths are resolved against the manifest directory.
+    pub library: String,
+    /// The version of the plugin itself.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The savant_core version the plugin is built against, when set it must match the host
+    /// version, so incompatible plugins are rejected before their libraries are loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub savant_core_version: Option<String>,
+    pub functions: Vec<PluginFunctionManifest>,
+}
+
+impl PluginManifest {
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_yaml(yaml: &str) -> anyhow::Result<Self> {
+        Ok(serde_yaml::from_str(yaml)?)
+    }
+
+    pub fn find_function(&self, name: &str) -> Option<&PluginFunctionManifest> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn check_version(&self) -> anyhow::Result<()> {
+        let host_version = PluginAbi::current().version;
+        match self.savant_core_version.as_deref() {
+            Some(version) if version != host_version => bail!(
+                "Plugin {} is built against savant_core {}, but the host uses {}",
+                self.name,
+                version,
+                host_version
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn check_plugin_abi(libname: &str, plugin_abi: &PluginAbi) -> anyhow::Result<()> {
+    let host_abi = PluginAbi::current();
+    if plugin_abi.version != host_abi.version {
+        bail!(
+            "Plugin {} is built against savant_core {}, but the host uses {}",
+            libname,
+            plugin_abi.version,
+            host_abi.version
+        )
+    }
+    if plugin_abi.layout_hash != host_abi.layout_hash {
+        bail!(
+            "Plugin {} has incompatible structure layout (hash {:#x}, host hash {:#x}), rebuild it with the same toolchain and savant_core",
+            libname,
+            plugin_abi.layout_hash,
+            host_abi.layout_hash
+        )
+    }
+    Ok(())
+}
+
+fn verify_plugin_abi(libname: &str, lib: &libloading::Library) -> anyhow::Result<()> {
+    let symbol = unsafe { lib.get::<PluginAbiFunction>(PLUGIN_ABI_SYMBOL.as_bytes()) };
+    let abi = symbol.map_err(|e| {
+        anyhow!(
+            "Plugin {} does not export the `{}` symbol, use savant_core::export_plugin_abi!(): {}",
+            libname,
+            PLUGIN_ABI_SYMBOL,
+            e
+        )
+    })?;
+    check_plugin_abi(libname, &abi())
+}
+
+fn ensure_library_loaded(
+    libs: &mut HashMap<String, libloading::Library>,
+    libname: &str,
+) -> anyhow::Result<()> {
+    if !libs.contains_key(libname) {
+        let lib = unsafe { libloading::Library::new(libname)? };
+        verify_plugin_abi(libname, &lib)?;
+        libs.insert(libname.to_string(), lib);
+    }
+    Ok(())
 }
 
 pub fn load_stage_function_plugin(
@@ -14,10 +121,7 @@ pub fn load_stage_function_plugin(
     params: PluginParams,
 ) -> anyhow::Result<Box<dyn PipelineStageFunction>> {
     let mut libs = LIBRARIES.lock();
-    if !libs.contains_key(libname) {
-        let lib = unsafe { libloading::Library::new(libname)? };
-        libs.insert(libname.to_string(), lib);
-    }
+    ensure_library_loaded(&mut libs, libname)?;
     let lib = libs
         .get(libname)
         .expect("Library must be available according to the code logic");
@@ -26,3 +130,244 @@ pub fn load_stage_function_plugin(
     let raw = init(plugin_name, params);
     Ok(unsafe { Box::from_raw(raw) })
 }
+
+fn parse_manifest(path: &Path) -> anyhow::Result<Option<PluginManifest>> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    let manifest = match ext {
+        "json" => PluginManifest::from_json(&std::fs::read_to_string(path)?)?,
+        "yaml" | "yml" => PluginManifest::from_yaml(&std::fs::read_to_string(path)?)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(manifest))
+}
+
+/// Scans the directory for plugin manifests (`*.json`, `*.yaml`, `*.yml`), loads the libraries
+/// they reference and registers their functions. Returns the names of the registered plugins.
+/// All the manifests are validated before anything is registered: when one of them fails,
+/// none of the plugins is registered.
+///
+pub fn register_plugins_from_dir(dir: &str) -> anyhow::Result<Vec<String>> {
+    let dir = Path::new(dir);
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    let mut manifests = Vec::new();
+    for path in paths.iter().filter(|p| p.is_file()) {
+        let mut manifest = match parse_manifest(path) {
+            Ok(Some(m)) => m,
+            Ok(None) => continue,
+            Err(e) => bail!("Failed to parse plugin manifest {}: {}", path.display(), e),
+        };
+        manifest
+            .check_version()
+            .map_err(|e| anyhow!("Incompatible plugin manifest {}: {}", path.display(), e))?;
+        if let Some((other, _)) = manifests.iter().find(|(_, m)| m.name == manifest.name) {
+            bail!(
+                "Plugin {} is declared in both {} and {}",
+                manifest.name,
+                other.display(),
+                path.display()
+            )
+        }
+        let library = Path::new(&manifest.library);
+        if library.is_relative() {
+            manifest.library = dir.join(library).to_string_lossy().to_string();
+        }
+        manifests.push((path.as_path(), manifest));
+    }
+
+    register_plugin_batch(manifests.iter().map(|(_, m)| m.clone()).collect())?;
+    for (path, manifest) in &manifests {
+        log::info!(
+            target: "savant_rs::pipeline::plugins",
+            "Registered plugin {} from manifest {}",
+            manifest.name,
+            path.display()
+        );
+    }
+    Ok(manifests.into_iter().map(|(_, m)| m.name).collect())
+}
+
+/// Loads the library referenced by the manifest and registers its functions.
+///
+pub fn register_plugin(manifest: PluginManifest) -> anyhow::Result<()> {
+    register_plugin_batch(vec![manifest])
+}
+
+/// Registers either all the plugins or, on error, none of them. The libraries loaded for the
+/// failed batch are unloaded.
+///
+fn register_plugin_batch(batch: Vec<PluginManifest>) -> anyhow::Result<()> {
+    let mut manifests = MANIFESTS.lock();
+    let mut libs = LIBRARIES.lock();
+    let mut loaded = Vec::new();
+    let result = batch.iter().try_for_each(|manifest| {
+        if manifests.contains_key(&manifest.name) {
+            bail!("Plugin {} is already registered", manifest.name)
+        }
+        manifest.check_version()?;
+        if !libs.contains_key(&manifest.library) {
+            ensure_library_loaded(&mut libs, &manifest.library)?;
+            loaded.push(manifest.library.clone());
+        }
+        check_plugin_functions(&libs, manifest)
+    });
+    if let Err(e) = result {
+        for library in loaded {
+            libs.remove(&library);
+        }
+        return Err(e);
+    }
+    for manifest in batch {
+        manifests.insert(manifest.name.clone(), manifest);
+    }
+    Ok(())
+}
+
+fn check_plugin_functions(
+    libs: &HashMap<String, libloading::Library>,
+    manifest: &PluginManifest,
+) -> anyhow::Result<()> {
+    let lib = libs
+        .get(&manifest.library)
+        .expect("Library must be available according to the code logic");
+    for f in &manifest.functions {
+        unsafe { lib.get::<super::PipelineStageFunctionFactory>(f.init.as_bytes()) }.map_err(
+            |e| {
+                anyhow!(
+                    "Plugin {} does not export function {} ({}): {}",
+                    manifest.name,
+                    f.name,
+                    f.init,
+                    e
+                )
+            },
+        )?;
+    }
+    Ok(())
+}
+
+pub fn list_registered_plugins() -> Vec<PluginManifest> {
+    let mut manifests = MANIFESTS.lock().values().cloned().collect::<Vec<_>>();
+    manifests.sort_by(|a, b| a.name.cmp(&b.name));
+    manifests
+}
+
+pub fn get_registered_plugin(name: &str) -> Option<PluginManifest> {
+    MANIFESTS.lock().get(name).cloned()
+}
+
+/// Instantiates a stage function registered with [`register_plugin`] or
+/// [`register_plugins_from_dir`].
+///
+pub fn create_registered_stage_function(
+    plugin: &str,
+    function: &str,
+    params: PluginParams,
+) -> anyhow::Result<Box<dyn PipelineStageFunction>> {
+    let manifest =
+        get_registered_plugin(plugin).ok_or(anyhow!("Plugin {} is not registered", plugin))?;
+    let f = manifest.find_function(function).ok_or(anyhow!(
+        "Plugin {} does not provide function {}",
+        plugin,
+        function
+    ))?;
+    load_stage_function_plugin(&manifest.library, &f.init, function, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_plugin_abi, get_registered_plugin, register_plugins_from_dir, PluginManifest,
+    };
+    use crate::pipeline::PluginAbi;
+    use std::{env, fs};
+
+    #[test]
+    fn test_parse_manifest() -> anyhow::Result<()> {
+        let manifest = PluginManifest::from_yaml(
+            r#"
+name: sample
+library: libsavant_plugin_sample.so
+version: 0.1.0
+savant_core_version: 1.2.3
+functions:
+  - name: counter
+    init: init_plugin
+"#,
+        )?;
+        assert_eq!(manifest.name, "sample");
+        assert_eq!(manifest.version.as_deref(), Some("0.1.0"));
+        assert_eq!(manifest.savant_core_version.as_deref(), Some("1.2.3"));
+        assert!(manifest.check_version().is_err());
+        let own_version_only = PluginManifest {
+            savant_core_version: None,
+            ..manifest.clone()
+        };
+        assert!(own_version_only.check_version().is_ok());
+        assert_eq!(
+            manifest.find_function("counter").unwrap().init,
+            "init_plugin"
+        );
+        assert!(manifest.find_function("missing").is_none());
+
+        let json = serde_json::to_string(&manifest)?;
+        assert_eq!(PluginManifest::from_json(&json)?, manifest);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_plugin_abi() {
+        let abi = PluginAbi::current();
+        assert!(check_plugin_abi("test", &abi).is_ok());
+
+        let other_version = PluginAbi {
+            version: "0.0.0",
+            ..abi
+        };
+        assert!(check_plugin_abi("test", &other_version).is_err());
+
+        let other_layout = PluginAbi {
+            layout_hash: abi.layout_hash.wrapping_add(1),
+            ..abi
+        };
+        assert!(check_plugin_abi("test", &other_layout).is_err());
+    }
+
+    #[test]
+    fn test_register_plugins_from_dir_validates_all() -> anyhow::Result<()> {
+        let dir = env::temp_dir().join(format!("plugin-manifests-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let manifest = |name: &str, version: &str| {
+            format!(
+                "name: {}\nlibrary: libmissing.so\nversion: 0.1.0\nsavant_core_version: {}\nfunctions: []\n",
+                name, version
+            )
+        };
+        fs::write(
+            dir.join("a.yaml"),
+            manifest("validated_a", PluginAbi::current().version),
+        )?;
+        fs::write(dir.join("b.yaml"), manifest("validated_b", "0.0.0"))?;
+
+        let error = register_plugins_from_dir(dir.to_str().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("b.yaml"), "{}", error);
+        assert!(error.to_string().contains("0.0.0"), "{}", error);
+        assert!(get_registered_plugin("validated_a").is_none());
+
+        fs::write(
+            dir.join("b.yaml"),
+            manifest("validated_a", PluginAbi::current().version),
+        )?;
+        let error = register_plugins_from_dir(dir.to_str().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("declared in both"), "{}", error);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
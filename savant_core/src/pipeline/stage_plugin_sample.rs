@@ -3,6 +3,8 @@ use crate::pipeline::{
     Pipeline, PipelinePayload, PipelineStageFunction, PipelineStageFunctionOrder, PluginParams,
 };
 
+crate::export_plugin_abi!();
+
 #[no_mangle]
 pub fn init_plugin_test(_: &str, params: PluginParams) -> *mut dyn PipelineStageFunction {
     let plugin = Plugin {
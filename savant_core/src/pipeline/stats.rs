@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 use std::sync::{Arc, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use hashbrown::HashMap;
 use log::info;
@@ -301,6 +301,71 @@ impl StatsGenerator {
 
 pub type StageStats = Arc<Mutex<(StageProcessingStat, StageLatencyStat)>>;
 
+/// The number of the most recent payloads the residency statistics are computed over.
+///
+pub const STAGE_RESIDENCY_WINDOW: usize = 1024;
+
+/// The time the payloads spend in a stage, from entering it to leaving it, over the sliding
+/// window of the most recent payloads.
+///
+#[derive(Debug, Default)]
+pub struct StageResidencyStat {
+    samples: VecDeque<(Instant, Duration)>,
+    exited: u64,
+}
+
+/// The aggregated residency statistics of a stage.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageResidencyStats {
+    pub stage_name: String,
+    pub queue_depth: usize,
+    /// The total number of the payloads which left the stage.
+    pub exited: u64,
+    pub p50_latency: Duration,
+    pub p95_latency: Duration,
+    /// Payloads per second leaving the stage over the window.
+    pub throughput: f64,
+}
+
+impl StageResidencyStat {
+    pub fn record(&mut self, exited_at: Instant, residency: Duration) {
+        self.exited += 1;
+        self.samples.push_back((exited_at, residency));
+        if self.samples.len() > STAGE_RESIDENCY_WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    fn percentile(sorted: &[Duration], q: f64) -> Duration {
+        if sorted.is_empty() {
+            return Duration::ZERO;
+        }
+        // nearest rank
+        let rank = (q * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+
+    pub fn summary(&self, stage_name: &str, queue_depth: usize) -> StageResidencyStats {
+        let mut sorted = self.samples.iter().map(|(_, d)| *d).collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let throughput = match (self.samples.front(), self.samples.back()) {
+            (Some((first, _)), Some((last, _))) if last > first => {
+                (self.samples.len() - 1) as f64 / last.duration_since(*first).as_secs_f64()
+            }
+            _ => 0.0,
+        };
+        StageResidencyStats {
+            stage_name: stage_name.to_string(),
+            queue_depth,
+            exited: self.exited,
+            p50_latency: Self::percentile(&sorted, 0.5),
+            p95_latency: Self::percentile(&sorted, 0.95),
+            throughput,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Stats {
     collector: Arc<Mutex<StatsCollector>>,
@@ -495,6 +560,37 @@ mod tests {
         Stats::default();
     }
 
+    #[test]
+    fn test_stage_residency_stat() {
+        let mut stat = StageResidencyStat::default();
+        assert_eq!(stat.summary("stage", 0).p95_latency, Duration::ZERO);
+        let start = Instant::now();
+        for i in 1..=100 {
+            stat.record(
+                start + Duration::from_millis(10 * i),
+                Duration::from_millis(i),
+            );
+        }
+        let summary = stat.summary("stage", 3);
+        assert_eq!(summary.queue_depth, 3);
+        assert_eq!(summary.exited, 100);
+        assert_eq!(summary.p50_latency, Duration::from_millis(50));
+        assert_eq!(summary.p95_latency, Duration::from_millis(95));
+        assert!((summary.throughput - 100.0).abs() < 1e-6);
+
+        for _ in 0..STAGE_RESIDENCY_WINDOW {
+            stat.record(start, Duration::from_millis(1));
+        }
+        assert_eq!(
+            stat.summary("stage", 0).p95_latency,
+            Duration::from_millis(1)
+        );
+        assert_eq!(
+            stat.summary("stage", 0).exited,
+            100 + STAGE_RESIDENCY_WINDOW as u64
+        );
+    }
+
     #[test]
     fn test_stats_collector() {
         let mut stats_collector = StatsCollector::new(10);
@@ -10,13 +10,18 @@ pub use bbox::*;
 pub mod any_object;
 pub mod attribute_set;
 pub mod attribute_value;
+pub mod codec;
 pub mod eos;
 pub mod frame;
 pub mod frame_batch;
 pub mod frame_update;
+pub mod journal;
+pub mod keypoints;
 pub mod object;
+pub mod redaction;
 pub mod segment;
 pub mod shutdown;
+pub mod track_history;
 pub mod userdata;
 
 pub use segment::*;
@@ -25,29 +30,41 @@ pub mod rust {
     pub use super::attribute::Attribute;
     pub use super::attribute_set::AttributeSet;
     pub use super::attribute_value::AttributeValue;
+    pub use super::attribute_value::{Tensor, TensorDType};
     pub use super::bbox::BBoxMetricType;
     pub use super::bbox::RBBox;
     pub use super::bbox::RBBoxData;
+    pub use super::codec::CodecInfo;
     pub use super::eos::EndOfStream;
     pub use super::frame::BelongingVideoFrame;
+    pub use super::frame::ClockSyncInfo;
+    pub use super::frame::CropObjectPolicy;
     pub use super::frame::VideoFrameContent;
+    pub use super::frame::VideoFrameContentSlice;
     pub use super::frame::VideoFrameProxy;
     pub use super::frame::VideoFrameTranscodingMethod;
     pub use super::frame::VideoFrameTransformation;
     pub use super::frame_batch::VideoFrameBatch;
     pub use super::frame_update::VideoFrameUpdate;
+    pub use super::journal::ChangeJournal;
+    pub use super::journal::JournalRecord;
+    pub use super::keypoints::KeyPoint;
+    pub use super::keypoints::KeyPoints;
     pub use super::object::BorrowedVideoObject;
     pub use super::object::VideoObject;
     pub use super::object::VideoObjectBBoxTransformation;
     pub use super::object::VideoObjectBuilder;
     pub use super::point::Point;
     pub use super::polygonal_area::PolygonalArea;
+    pub use super::redaction::RedactionSpec;
     pub use super::segment::Intersection;
     pub use super::segment::IntersectionKind;
     pub use super::segment::Segment;
     pub use super::shutdown::Shutdown;
+    pub use super::track_history::TrackHistory;
     pub use super::userdata::UserData;
     pub use crate::message::Message;
     pub use crate::primitives::frame::ExternalFrame;
+    pub use crate::primitives::frame::{ExternalFrameDescriptor, ExternalMemoryType};
     pub use crate::primitives::object::IdCollisionResolutionPolicy;
 }
@@ -1,5 +1,8 @@
 use crate::primitives::any_object::AnyObject;
 use crate::primitives::{Intersection, Point, PolygonalArea, RBBoxData};
+use anyhow::bail;
+use serde::{Serialize, Serializer};
+use std::sync::Arc;
 
 #[derive(Debug, PartialEq, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub enum AttributeValueVariant {
@@ -19,11 +22,175 @@ pub enum AttributeValueVariant {
     Polygon(PolygonalArea),
     PolygonVector(Vec<PolygonalArea>),
     Intersection(Intersection),
+    Tensor(Tensor),
     TemporaryValue(AnyObject),
     #[default]
     None,
 }
 
+/// The element type of a [`Tensor`].
+///
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, serde::Serialize, serde::Deserialize)]
+pub enum TensorDType {
+    UInt8,
+    Int8,
+    UInt16,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+}
+
+impl TensorDType {
+    pub fn item_size(&self) -> usize {
+        match self {
+            TensorDType::UInt8 | TensorDType::Int8 => 1,
+            TensorDType::UInt16 | TensorDType::Int16 => 2,
+            TensorDType::Int32 | TensorDType::Float32 => 4,
+            TensorDType::Int64 | TensorDType::Float64 => 8,
+        }
+    }
+}
+
+/// The primitive types a [`Tensor`] can be built from and read as.
+///
+pub trait TensorElement: Copy {
+    const DTYPE: TensorDType;
+    fn write(&self, buf: &mut Vec<u8>);
+    fn read(bytes: &[u8]) -> Self;
+}
+
+macro_rules! tensor_element {
+    ($t:ty, $dtype:ident) => {
+        impl TensorElement for $t {
+            const DTYPE: TensorDType = TensorDType::$dtype;
+
+            fn write(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.to_ne_bytes());
+            }
+
+            fn read(bytes: &[u8]) -> Self {
+                <$t>::from_ne_bytes(bytes.try_into().unwrap())
+            }
+        }
+    };
+}
+
+tensor_element!(u8, UInt8);
+tensor_element!(i8, Int8);
+tensor_element!(u16, UInt16);
+tensor_element!(i16, Int16);
+tensor_element!(i32, Int32);
+tensor_element!(i64, Int64);
+tensor_element!(f32, Float32);
+tensor_element!(f64, Float64);
+
+fn serialize_shared<S: Serializer>(data: &Arc<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+    data.as_slice().serialize(serializer)
+}
+
+/// A dense row-major array of the native-endian elements, e.g. a re-id embedding. The data is
+/// shared between the clones, so copying the attributes does not copy the tensors.
+///
+/// The tensors are transferred with protobuf as the bytes values with the element type and
+/// the little-endian data.
+///
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "RawTensor")]
+pub struct Tensor {
+    dims: Vec<usize>,
+    dtype: TensorDType,
+    #[serde(serialize_with = "serialize_shared")]
+    data: Arc<Vec<u8>>,
+}
+
+/// The unchecked serde form of [`Tensor`], the deserialized tensors are validated by
+/// [`Tensor::new`].
+///
+#[derive(serde::Deserialize)]
+struct RawTensor {
+    dims: Vec<usize>,
+    dtype: TensorDType,
+    data: Vec<u8>,
+}
+
+impl TryFrom<RawTensor> for Tensor {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawTensor) -> anyhow::Result<Self> {
+        Self::new(raw.dims, raw.dtype, raw.data)
+    }
+}
+
+impl Tensor {
+    /// Creates the tensor from the raw data, its length must match the dimensions and the
+    /// element type.
+    ///
+    pub fn new(dims: Vec<usize>, dtype: TensorDType, data: Vec<u8>) -> anyhow::Result<Self> {
+        let Some(expected) = dims
+            .iter()
+            .try_fold(dtype.item_size(), |size, d| size.checked_mul(*d))
+        else {
+            bail!("Tensor {:?} of {:?} is too large", dims, dtype);
+        };
+        if data.len() != expected {
+            bail!(
+                "Tensor {:?} of {:?} requires {} bytes, got {}",
+                dims,
+                dtype,
+                expected,
+                data.len()
+            );
+        }
+        Ok(Self {
+            dims,
+            dtype,
+            data: Arc::new(data),
+        })
+    }
+
+    pub fn from_values<T: TensorElement>(dims: Vec<usize>, values: &[T]) -> anyhow::Result<Self> {
+        let mut data = Vec::with_capacity(values.len() * T::DTYPE.item_size());
+        for v in values {
+            v.write(&mut data);
+        }
+        Self::new(dims, T::DTYPE, data)
+    }
+
+    /// Returns the elements if the tensor element type is `T`.
+    ///
+    pub fn to_values<T: TensorElement>(&self) -> Option<Vec<T>> {
+        if self.dtype != T::DTYPE {
+            return None;
+        }
+        Some(
+            self.data
+                .chunks_exact(self.dtype.item_size())
+                .map(T::read)
+                .collect(),
+        )
+    }
+
+    pub fn get_dims(&self) -> &[usize] {
+        &self.dims
+    }
+
+    pub fn get_dtype(&self) -> TensorDType {
+        self.dtype
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the reference to the data shared by the clones of the tensor.
+    ///
+    pub fn get_shared_data(&self) -> Arc<Vec<u8>> {
+        self.data.clone()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct AttributeValue {
     pub confidence: Option<f32>,
@@ -110,6 +277,18 @@ impl AttributeValue {
         )
     }
 
+    pub fn tensor(
+        dims: Vec<usize>,
+        dtype: TensorDType,
+        bytes: Vec<u8>,
+        confidence: Option<f32>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            AttributeValueVariant::Tensor(Tensor::new(dims, dtype, bytes)?),
+            confidence,
+        ))
+    }
+
     pub fn get(&self) -> &AttributeValueVariant {
         &self.value
     }
@@ -122,3 +301,47 @@ impl AttributeValue {
         Ok(serde_json::from_str(json)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tensor() {
+        assert!(
+            AttributeValue::tensor(vec![2, 3], TensorDType::Float32, vec![0; 20], None).is_err()
+        );
+        let embedding = (0..512).map(|i| i as f32 / 512.0).collect::<Vec<_>>();
+        let tensor = Tensor::from_values(vec![512], &embedding).unwrap();
+        assert_eq!(tensor.get_data().len(), 2048);
+        assert_eq!(tensor.to_values::<f32>(), Some(embedding));
+        assert!(tensor.to_values::<i32>().is_none());
+
+        let value = AttributeValue::new(AttributeValueVariant::Tensor(tensor.clone()), Some(0.5));
+        let copy = value.clone();
+        match (value.get(), copy.get()) {
+            (AttributeValueVariant::Tensor(a), AttributeValueVariant::Tensor(b)) => {
+                assert!(Arc::ptr_eq(&a.get_shared_data(), &b.get_shared_data()))
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            AttributeValue::from_json(&value.to_json().unwrap()).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_invalid_tensor() {
+        assert!(Tensor::new(vec![usize::MAX, 2], TensorDType::Float32, vec![]).is_err());
+        let json = |dims: &str, data: &str| {
+            format!(r#"{{"dims":{},"dtype":"UInt8","data":{}}}"#, dims, data)
+        };
+        assert!(serde_json::from_str::<Tensor>(&json("[2]", "[1,2]")).is_ok());
+        assert!(serde_json::from_str::<Tensor>(&json("[1000000]", "[1,2]")).is_err());
+        assert!(
+            serde_json::from_str::<Tensor>(&json(&format!("[{},{}]", usize::MAX, 2), "[]"))
+                .is_err()
+        );
+    }
+}
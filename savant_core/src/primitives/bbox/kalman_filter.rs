@@ -0,0 +1,252 @@
+use crate::primitives::RBBox;
+use anyhow::bail;
+use rayon::prelude::*;
+
+const DIMS: usize = 5;
+const ANGLE: usize = 4;
+
+/// The noise parameters of [`KalmanBBoxFilter`]. The center and size deviations are relative to
+/// the box height, the angle deviations are in degrees.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KalmanBBoxFilterConfig {
+    pub std_position: f32,
+    pub std_velocity: f32,
+    pub std_angle: f32,
+    pub std_angle_velocity: f32,
+}
+
+impl Default for KalmanBBoxFilterConfig {
+    fn default() -> Self {
+        Self {
+            std_position: 1.0 / 20.0,
+            std_velocity: 1.0 / 160.0,
+            std_angle: 1.0,
+            std_angle_velocity: 0.1,
+        }
+    }
+}
+
+/// The estimation of a box by [`KalmanBBoxFilter`]: the center, the size and the angle together
+/// with their per-frame velocities.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KalmanBBoxState {
+    /// xc, yc, width, height, angle.
+    position: [f32; DIMS],
+    velocity: [f32; DIMS],
+    /// The variance of the position, the position-velocity covariance and the variance of the
+    /// velocity of every dimension.
+    covariance: [[f32; 3]; DIMS],
+    oriented: bool,
+}
+
+impl KalmanBBoxState {
+    /// The estimated box, the angle is set when the filter was initiated with an oriented box.
+    ///
+    pub fn bbox(&self) -> RBBox {
+        let [xc, yc, width, height, angle] = self.position;
+        RBBox::new(xc, yc, width, height, self.oriented.then_some(angle))
+    }
+
+    /// The per-frame velocities of xc, yc, width, height and angle.
+    ///
+    pub fn velocity(&self) -> [f32; DIMS] {
+        self.velocity
+    }
+}
+
+fn normalize_angle(angle: f32) -> f32 {
+    let angle = angle.rem_euclid(360.0);
+    if angle > 180.0 {
+        angle - 360.0
+    } else {
+        angle
+    }
+}
+
+/// The Kalman filter of boxes with the constant velocity model. The dimensions are filtered
+/// independently, so a prediction or an update costs a few dozens of operations.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KalmanBBoxFilter {
+    config: KalmanBBoxFilterConfig,
+}
+
+impl KalmanBBoxFilter {
+    pub fn new(config: KalmanBBoxFilterConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn get_config(&self) -> KalmanBBoxFilterConfig {
+        self.config
+    }
+
+    /// The position and velocity deviations of every dimension for the box height.
+    ///
+    fn deviations(&self, height: f32) -> [(f32, f32); DIMS] {
+        let c = &self.config;
+        let p = c.std_position * height;
+        let v = c.std_velocity * height;
+        [
+            (p, v),
+            (p, v),
+            (p, v),
+            (p, v),
+            (c.std_angle, c.std_angle_velocity),
+        ]
+    }
+
+    /// Creates the state from the first observation of the box, the velocities are zero.
+    ///
+    pub fn initiate(&self, bbox: &RBBox) -> KalmanBBoxState {
+        let angle = bbox.get_angle();
+        let position = [
+            bbox.get_xc(),
+            bbox.get_yc(),
+            bbox.get_width(),
+            bbox.get_height(),
+            angle.unwrap_or(0.0),
+        ];
+        let mut covariance = [[0.0; 3]; DIMS];
+        for (c, (p, v)) in covariance
+            .iter_mut()
+            .zip(self.deviations(bbox.get_height()))
+        {
+            *c = [(2.0 * p).powi(2), 0.0, (10.0 * v).powi(2)];
+        }
+        KalmanBBoxState {
+            position,
+            velocity: [0.0; DIMS],
+            covariance,
+            oriented: angle.is_some(),
+        }
+    }
+
+    /// Predicts the state for the next frame.
+    ///
+    pub fn predict(&self, state: &KalmanBBoxState) -> KalmanBBoxState {
+        let mut next = *state;
+        let deviations = self.deviations(state.position[3]);
+        for (i, (p, v)) in deviations.into_iter().enumerate() {
+            next.position[i] += state.velocity[i];
+            let [pp, pv, vv] = state.covariance[i];
+            next.covariance[i] = [pp + 2.0 * pv + vv + p * p, pv + vv, vv + v * v];
+        }
+        next.position[ANGLE] = normalize_angle(next.position[ANGLE]);
+        next
+    }
+
+    /// Corrects the state with the observed box.
+    ///
+    pub fn update(&self, state: &KalmanBBoxState, bbox: &RBBox) -> KalmanBBoxState {
+        let mut next = *state;
+        let measurement = [
+            bbox.get_xc(),
+            bbox.get_yc(),
+            bbox.get_width(),
+            bbox.get_height(),
+            bbox.get_angle().unwrap_or(0.0),
+        ];
+        let deviations = self.deviations(state.position[3]);
+        for (i, (p, _)) in deviations.into_iter().enumerate() {
+            let [pp, pv, vv] = state.covariance[i];
+            let innovation = measurement[i] - state.position[i];
+            let innovation = if i == ANGLE {
+                normalize_angle(innovation)
+            } else {
+                innovation
+            };
+            let s = pp + p * p;
+            let (k_position, k_velocity) = (pp / s, pv / s);
+            next.position[i] += k_position * innovation;
+            next.velocity[i] += k_velocity * innovation;
+            next.covariance[i] = [
+                (1.0 - k_position) * pp,
+                (1.0 - k_position) * pv,
+                vv - k_velocity * pv,
+            ];
+        }
+        next.position[ANGLE] = normalize_angle(next.position[ANGLE]);
+        next
+    }
+
+    /// Predicts the states in parallel.
+    ///
+    pub fn predict_batch(&self, states: &[KalmanBBoxState]) -> Vec<KalmanBBoxState> {
+        states.par_iter().map(|s| self.predict(s)).collect()
+    }
+
+    /// Corrects the states with the corresponding boxes in parallel.
+    ///
+    pub fn update_batch(
+        &self,
+        states: &[KalmanBBoxState],
+        bboxes: &[RBBox],
+    ) -> anyhow::Result<Vec<KalmanBBoxState>> {
+        if states.len() != bboxes.len() {
+            bail!(
+                "The number of states {} does not match the number of boxes {}",
+                states.len(),
+                bboxes.len()
+            );
+        }
+        Ok(states
+            .par_iter()
+            .zip(bboxes)
+            .map(|(s, b)| self.update(s, b))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_velocity() {
+        let filter = KalmanBBoxFilter::default();
+        let mut state = filter.initiate(&RBBox::new(0.0, 0.0, 20.0, 40.0, None));
+        for i in 1..=30 {
+            state = filter.predict(&state);
+            let x = 5.0 * i as f32;
+            state = filter.update(&state, &RBBox::new(x, 0.0, 20.0, 40.0, None));
+        }
+        let velocity = state.velocity();
+        assert!((velocity[0] - 5.0).abs() < 0.1, "{:?}", velocity);
+        assert!(velocity[1].abs() < 0.01);
+
+        // the gap is filled by the predictions
+        let predicted = filter.predict(&filter.predict(&state)).bbox();
+        assert!((predicted.get_xc() - 160.0).abs() < 1.0);
+        assert_eq!(predicted.get_angle(), None);
+        assert_eq!(predicted.get_height(), 40.0);
+    }
+
+    #[test]
+    fn test_angle_wrap() {
+        let filter = KalmanBBoxFilter::default();
+        let state = filter.initiate(&RBBox::new(0.0, 0.0, 20.0, 40.0, Some(179.0)));
+        let state = filter.update(
+            &filter.predict(&state),
+            &RBBox::new(0.0, 0.0, 20.0, 40.0, Some(-179.0)),
+        );
+        let angle = state.bbox().get_angle().unwrap();
+        assert!(angle > 179.0 || angle < -179.0, "{}", angle);
+    }
+
+    #[test]
+    fn test_batch() {
+        let filter = KalmanBBoxFilter::default();
+        let boxes = vec![
+            RBBox::new(0.0, 0.0, 20.0, 40.0, None),
+            RBBox::new(100.0, 50.0, 10.0, 10.0, Some(30.0)),
+        ];
+        let states = boxes.iter().map(|b| filter.initiate(b)).collect::<Vec<_>>();
+        let predicted = filter.predict_batch(&states);
+        assert_eq!(predicted[1], filter.predict(&states[1]));
+        let updated = filter.update_batch(&predicted, &boxes).unwrap();
+        assert_eq!(updated[0], filter.update(&predicted[0], &boxes[0]));
+        assert!(filter.update_batch(&predicted, &boxes[..1]).is_err());
+    }
+}
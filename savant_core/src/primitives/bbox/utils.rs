@@ -1,7 +1,8 @@
 use crate::primitives::{BBoxMetricType, RBBox};
+use anyhow::bail;
 use geo::{Area, BooleanOps, MultiPolygon};
 use rayon::iter::ParallelIterator;
-use rayon::prelude::IntoParallelRefIterator;
+use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -94,6 +95,97 @@ pub fn associate_bboxes(
     associations
 }
 
+fn metric_value(a: &RBBox, b: &RBBox, metric: BBoxMetricType) -> f32 {
+    match metric {
+        BBoxMetricType::IoU => a.iou(b),
+        BBoxMetricType::IoSelf => a.ios(b),
+        BBoxMetricType::IoOther => a.ioo(b),
+    }
+    .unwrap_or(0.0)
+}
+
+/// The non-maximum suppression of the boxes, the rotated boxes are compared by their polygons.
+/// The boxes are visited in the order of the decreasing scores, a box is kept unless its IoU
+/// with an already kept box exceeds the threshold.
+///
+/// Returns the indices of the kept boxes in the order of the decreasing scores.
+///
+pub fn nms(boxes: &[&RBBox], scores: &[f32], iou_threshold: f32) -> anyhow::Result<Vec<usize>> {
+    if boxes.len() != scores.len() {
+        bail!(
+            "The number of boxes {} does not match the number of scores {}",
+            boxes.len(),
+            scores.len()
+        );
+    }
+    let mut order = (0..boxes.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+    let mut suppressed = vec![false; boxes.len()];
+    let mut kept = Vec::new();
+    for (pos, &i) in order.iter().enumerate() {
+        if suppressed[i] {
+            continue;
+        }
+        kept.push(i);
+        let overlapping = order[pos + 1..]
+            .par_iter()
+            .filter(|j| !suppressed[**j])
+            .filter(|j| metric_value(boxes[i], boxes[**j], BBoxMetricType::IoU) > iou_threshold)
+            .copied()
+            .collect::<Vec<_>>();
+        for j in overlapping {
+            suppressed[j] = true;
+        }
+    }
+    Ok(kept)
+}
+
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Groups the boxes transitively connected by the metric above the threshold, the asymmetric
+/// metrics connect the boxes when the threshold is exceeded in either direction.
+///
+/// Returns the clusters of the box indices, the indices in a cluster are ascending and the
+/// clusters are ordered by their first indices.
+///
+pub fn cluster_boxes(boxes: &[&RBBox], metric: BBoxMetricType, threshold: f32) -> Vec<Vec<usize>> {
+    let edges = (0..boxes.len())
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            (i + 1..boxes.len())
+                .filter(move |&j| {
+                    metric_value(boxes[i], boxes[j], metric) > threshold
+                        || metric_value(boxes[j], boxes[i], metric) > threshold
+                })
+                .map(move |j| (i, j))
+        })
+        .collect::<Vec<_>>();
+    let mut parents = (0..boxes.len()).collect::<Vec<_>>();
+    for (i, j) in edges {
+        let (ri, rj) = (find_root(&mut parents, i), find_root(&mut parents, j));
+        if ri != rj {
+            parents[ri.max(rj)] = ri.min(rj);
+        }
+    }
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    let mut cluster_of_root = HashMap::new();
+    for i in 0..boxes.len() {
+        let root = find_root(&mut parents, i);
+        let cluster = *cluster_of_root.entry(root).or_insert_with(|| {
+            clusters.push(Vec::new());
+            clusters.len() - 1
+        });
+        clusters[cluster].push(i);
+    }
+    clusters
+}
+
 #[cfg(test)]
 mod tests {
     use crate::primitives::{BBoxMetricType, RBBox};
@@ -177,4 +269,39 @@ mod tests {
         assert!(matches!(lp2_associations.as_slice(), [(1, _), (0, _)]));
         assert!(lp3_associations.is_empty());
     }
+
+    #[test]
+    fn test_nms() {
+        let boxes = [
+            RBBox::new(10.0, 10.0, 10.0, 10.0, None),
+            RBBox::new(11.0, 10.0, 10.0, 10.0, None),
+            RBBox::new(30.0, 10.0, 10.0, 10.0, None),
+            RBBox::new(10.0, 10.0, 10.0, 10.0, Some(45.0)),
+        ];
+        let refs = boxes.iter().collect::<Vec<_>>();
+        let scores = [0.5, 0.9, 0.7, 0.8];
+        // the IoU of 0 and 1 is 0.82, the rotated box 3 has the IoU of 0.68 with 1
+        assert_eq!(super::nms(&refs, &scores, 0.5).unwrap(), vec![1, 2]);
+        assert_eq!(super::nms(&refs, &scores, 0.7).unwrap(), vec![1, 3, 2]);
+        assert_eq!(super::nms(&refs, &scores, 0.85).unwrap(), vec![1, 3, 2, 0]);
+        assert!(super::nms(&refs, &[0.5], 0.5).is_err());
+    }
+
+    #[test]
+    fn test_cluster_boxes() {
+        let boxes = [
+            RBBox::new(10.0, 10.0, 10.0, 10.0, None),
+            RBBox::new(50.0, 10.0, 10.0, 10.0, None),
+            RBBox::new(16.0, 10.0, 10.0, 10.0, None),
+            RBBox::new(22.0, 10.0, 10.0, 10.0, None),
+            RBBox::new(10.0, 10.0, 2.0, 2.0, None),
+        ];
+        let refs = boxes.iter().collect::<Vec<_>>();
+        // 0-2 and 2-3 overlap by 4/16, 0 and 3 are connected through 2
+        let clusters = super::cluster_boxes(&refs, BBoxMetricType::IoU, 0.2);
+        assert_eq!(clusters, vec![vec![0, 2, 3], vec![1], vec![4]]);
+        // the small box 4 lies within 0 entirely
+        let clusters = super::cluster_boxes(&refs, BBoxMetricType::IoSelf, 0.9);
+        assert_eq!(clusters, vec![vec![0, 4], vec![1], vec![2], vec![3]]);
+    }
 }
@@ -0,0 +1,257 @@
+use crate::primitives::frame::VideoFrameProxy;
+use crate::rwlock::SavantRwLock;
+use anyhow::bail;
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use lru::LruCache;
+use parking_lot::Mutex;
+
+/// Describes a codec which can be specified in [`VideoFrameProxy::set_codec`].
+///
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CodecInfo {
+    /// The canonical name of the codec.
+    pub name: String,
+    /// The alternative names which resolve to the codec.
+    pub aliases: Vec<String>,
+    pub fourcc: Option<String>,
+    pub mime_type: String,
+    /// Whether the stream can only be decoded starting from a keyframe.
+    pub requires_keyframes: bool,
+    /// Whether the frames carry raw pixels.
+    pub is_raw: bool,
+}
+
+impl CodecInfo {
+    pub fn new(
+        name: &str,
+        aliases: &[&str],
+        fourcc: Option<&str>,
+        mime_type: &str,
+        requires_keyframes: bool,
+        is_raw: bool,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            fourcc: fourcc.map(String::from),
+            mime_type: mime_type.to_string(),
+            requires_keyframes,
+            is_raw,
+        }
+    }
+}
+
+fn builtin_codecs() -> Vec<CodecInfo> {
+    vec![
+        CodecInfo::new("h264", &["avc"], Some("H264"), "video/h264", true, false),
+        CodecInfo::new("hevc", &["h265"], Some("HEVC"), "video/h265", true, false),
+        CodecInfo::new("av1", &[], Some("AV01"), "video/av1", true, false),
+        CodecInfo::new("vp8", &[], Some("VP80"), "video/vp8", true, false),
+        CodecInfo::new("vp9", &[], Some("VP90"), "video/vp9", true, false),
+        CodecInfo::new(
+            "jpeg",
+            &["jpg", "mjpeg"],
+            Some("MJPG"),
+            "image/jpeg",
+            false,
+            false,
+        ),
+        CodecInfo::new("png", &[], Some("png "), "image/png", false, false),
+        CodecInfo::new("raw-rgba", &[], Some("RGBA"), "video/x-raw", false, true),
+        CodecInfo::new("raw-rgb24", &[], Some("RGB3"), "video/x-raw", false, true),
+        CodecInfo::new("raw-bgr24", &[], Some("BGR3"), "video/x-raw", false, true),
+    ]
+}
+
+const MAX_REPORTED_CODECS: usize = 1024;
+
+lazy_static! {
+    static ref CODECS: SavantRwLock<HashMap<String, CodecInfo>> = SavantRwLock::new(
+        builtin_codecs()
+            .into_iter()
+            .map(|c| (c.name.clone(), c))
+            .collect()
+    );
+    static ref REPORTED_CODECS: Mutex<LruCache<String, ()>> = Mutex::new(LruCache::new(
+        std::num::NonZeroUsize::new(MAX_REPORTED_CODECS).unwrap()
+    ));
+}
+
+/// Adds a codec to the registry or replaces the one with the same name. Fails when the name or an
+/// alias of the codec is already used by another codec.
+///
+pub fn register_codec(info: CodecInfo) -> anyhow::Result<()> {
+    let info = CodecInfo {
+        name: info.name.to_lowercase(),
+        aliases: info.aliases.iter().map(|a| a.to_lowercase()).collect(),
+        ..info
+    };
+    let mut codecs = CODECS.write();
+    for name in std::iter::once(&info.name).chain(info.aliases.iter()) {
+        if let Some(other) = codecs
+            .values()
+            .find(|c| c.name != info.name && (&c.name == name || c.aliases.contains(name)))
+        {
+            bail!(
+                "Codec name {} is already used by codec {}",
+                name,
+                other.name
+            )
+        }
+    }
+    codecs.insert(info.name.clone(), info);
+    Ok(())
+}
+
+/// Looks up the codec by its name or alias, case-insensitively.
+///
+pub fn find_codec(name: &str) -> Option<CodecInfo> {
+    let name = name.to_lowercase();
+    let codecs = CODECS.read();
+    codecs
+        .get(&name)
+        .or_else(|| codecs.values().find(|c| c.aliases.contains(&name)))
+        .cloned()
+}
+
+pub fn find_codec_by_fourcc(fourcc: &str) -> Option<CodecInfo> {
+    CODECS
+        .read()
+        .values()
+        .find(|c| c.fourcc.as_deref() == Some(fourcc))
+        .cloned()
+}
+
+/// Returns the codecs with the mime type; several raw formats share `video/x-raw`.
+///
+pub fn find_codecs_by_mime_type(mime_type: &str) -> Vec<CodecInfo> {
+    CODECS
+        .read()
+        .values()
+        .filter(|c| c.mime_type == mime_type)
+        .cloned()
+        .collect()
+}
+
+pub fn known_codecs() -> Vec<CodecInfo> {
+    let mut codecs = CODECS.read().values().cloned().collect::<Vec<_>>();
+    codecs.sort_by(|a, b| a.name.cmp(&b.name));
+    codecs
+}
+
+/// Checks that the codec is either not set or registered.
+///
+pub fn validate_codec(codec: Option<&str>) -> anyhow::Result<()> {
+    match codec {
+        Some(name) if find_codec(name).is_none() => bail!("Unknown codec: {}", name),
+        _ => Ok(()),
+    }
+}
+
+/// Reports an unknown codec of a frame with a warning the first time the codec value is seen
+/// and at the debug level afterwards, so a stream with an unregistered codec does not flood
+/// the log. Returns `true` when the warning is logged. Only the recently seen codec values are
+/// remembered, so a peer sending random codec names does not grow the memory without bound.
+///
+pub fn report_unknown_codec(source_id: &str, codec: Option<&str>) -> bool {
+    let Err(e) = validate_codec(codec) else {
+        return false;
+    };
+    let first = REPORTED_CODECS
+        .lock()
+        .put(codec.unwrap_or_default().to_string(), ())
+        .is_none();
+    if first {
+        log::warn!(target: "savant_rs::primitives::frame", "Frame from source {}: {}", source_id, e);
+    } else {
+        log::debug!(target: "savant_rs::primitives::frame", "Frame from source {}: {}", source_id, e);
+    }
+    first
+}
+
+/// Resolves an alias to the canonical codec name, unknown names are returned as is.
+///
+pub fn canonical_codec_name(name: &str) -> String {
+    find_codec(name)
+        .map(|c| c.name)
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Decides if the content of the `source` frame must be transcoded to be delivered as the
+/// `target` frame: the codecs differ or the frame dimensions changed.
+///
+pub fn transcoding_required(source: &VideoFrameProxy, target: &VideoFrameProxy) -> bool {
+    let source_codec = source.get_codec().map(|c| canonical_codec_name(&c));
+    let target_codec = target.get_codec().map(|c| canonical_codec_name(&c));
+    source_codec != target_codec
+        || source.get_width() != target.get_width()
+        || source.get_height() != target.get_height()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::gen_frame;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(find_codec("H265").unwrap().name, "hevc");
+        assert_eq!(find_codec_by_fourcc("MJPG").unwrap().name, "jpeg");
+        assert_eq!(find_codecs_by_mime_type("video/x-raw").len(), 3);
+        assert!(find_codec("h264").unwrap().requires_keyframes);
+        assert!(validate_codec(None).is_ok());
+        assert!(validate_codec(Some("avc")).is_ok());
+        assert!(validate_codec(Some("mpeg2")).is_err());
+    }
+
+    #[test]
+    fn test_report_unknown_codec() {
+        assert!(!report_unknown_codec("test", None));
+        assert!(!report_unknown_codec("test", Some("h264")));
+        assert!(report_unknown_codec("test", Some("reported-codec")));
+        assert!(!report_unknown_codec("test", Some("reported-codec")));
+        assert!(!report_unknown_codec("other", Some("reported-codec")));
+        for i in 0..MAX_REPORTED_CODECS * 2 {
+            report_unknown_codec("test", Some(&format!("random-codec-{}", i)));
+        }
+        assert_eq!(REPORTED_CODECS.lock().len(), MAX_REPORTED_CODECS);
+    }
+
+    #[test]
+    fn test_register() -> anyhow::Result<()> {
+        register_codec(CodecInfo::new(
+            "Mpeg4",
+            &["mp4v"],
+            Some("MP4V"),
+            "video/mp4v-es",
+            true,
+            false,
+        ))?;
+        assert_eq!(find_codec("MP4V").unwrap().name, "mpeg4");
+        assert!(register_codec(CodecInfo::new(
+            "xvid",
+            &["mpeg4"],
+            None,
+            "video/mp4v-es",
+            true,
+            false
+        ))
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcoding_required() {
+        let mut source = gen_frame();
+        source.set_codec(Some("h265".to_string()));
+        let mut target = gen_frame();
+        target.set_codec(Some("hevc".to_string()));
+        assert!(!transcoding_required(&source, &target));
+        target.set_width(source.get_width() / 2);
+        assert!(transcoding_required(&source, &target));
+        target.set_width(source.get_width());
+        target.set_codec(Some("jpeg".to_string()));
+        assert!(transcoding_required(&source, &target));
+    }
+}
@@ -2,7 +2,11 @@ use crate::draw::DrawLabelKind;
 use crate::json_api::ToSerdeJsonValue;
 use crate::match_query::{and, IntExpression, MatchQuery, StringExpression};
 use crate::message::Message;
-use crate::primitives::frame_update::VideoFrameUpdate;
+use crate::otlp::{PropagatedContext, TRACE_CONTEXT_NAME, TRACE_CONTEXT_NAMESPACE};
+use crate::primitives::attribute_value::{AttributeValue, AttributeValueVariant};
+use crate::primitives::codec::{find_codec, report_unknown_codec, validate_codec, CodecInfo};
+use crate::primitives::frame_update::{AttributeUpdatePolicy, ObjectMergePolicy, VideoFrameUpdate};
+use crate::primitives::journal::{ChangeJournal, JournalRecord, Journaled};
 use crate::primitives::object::private::{
     SealedObjectOperations, SealedWithFrame, SealedWithParent,
 };
@@ -18,12 +22,14 @@ use crate::utils::uuid_v7::incremental_uuid_v7;
 use crate::version;
 use anyhow::{anyhow, bail};
 use derive_builder::Builder;
-use hashbrown::HashMap;
-use serde_json::Value;
+use hashbrown::{HashMap, HashSet};
+use opentelemetry::Context;
+use serde_json::{json, Value};
 use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::mem;
-use std::sync::{Arc, Weak};
+use std::ops::{Deref, Range};
+use std::sync::{Arc, OnceLock, Weak};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
@@ -46,10 +52,219 @@ impl<'a> StreamCompatibilityInformation<'a> {
     }
 }
 
+pub const CLOCK_SYNC_ATTRIBUTE_NAMESPACE: &str = "savant";
+pub const CLOCK_SYNC_ATTRIBUTE_NAME: &str = "clock_sync";
+
+/// Wall-clock synchronization data of a frame, used to align the frames of multiple cameras in
+/// time. It is kept in a hidden persistent attribute, so it survives protobuf serialization,
+/// batching and frame updates.
+///
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize)]
+pub struct ClockSyncInfo {
+    /// The capture time in nanoseconds since the UNIX epoch, measured by the capturing host.
+    pub capture_time_ns: i64,
+    /// The offset of the capturing host clock from the NTP reference in nanoseconds.
+    pub ntp_offset_ns: Option<i64>,
+    /// The identifier of the clock domain; times from different domains are not comparable.
+    pub clock_domain: Option<String>,
+}
+
+impl ClockSyncInfo {
+    pub fn new(
+        capture_time_ns: i64,
+        ntp_offset_ns: Option<i64>,
+        clock_domain: Option<&str>,
+    ) -> Self {
+        Self {
+            capture_time_ns,
+            ntp_offset_ns,
+            clock_domain: clock_domain.map(String::from),
+        }
+    }
+
+    /// The capture time adjusted to the NTP reference.
+    ///
+    pub fn corrected_capture_time_ns(&self) -> i64 {
+        self.capture_time_ns + self.ntp_offset_ns.unwrap_or(0)
+    }
+
+    fn to_attribute_values(&self) -> Vec<AttributeValue> {
+        vec![
+            AttributeValue::integer(self.capture_time_ns, None),
+            self.ntp_offset_ns
+                .map(|o| AttributeValue::integer(o, None))
+                .unwrap_or_else(AttributeValue::none),
+            self.clock_domain
+                .as_deref()
+                .map(|d| AttributeValue::string(d, None))
+                .unwrap_or_else(AttributeValue::none),
+        ]
+    }
+
+    fn from_attribute_values(values: &[AttributeValue]) -> anyhow::Result<Self> {
+        let [capture_time, ntp_offset, clock_domain] = values else {
+            bail!(
+                "Clock sync attribute must contain 3 values, got {}",
+                values.len()
+            );
+        };
+        let capture_time_ns = match capture_time.get() {
+            AttributeValueVariant::Integer(t) => *t,
+            v => bail!("Capture time must be an integer, got {:?}", v),
+        };
+        let ntp_offset_ns = match ntp_offset.get() {
+            AttributeValueVariant::Integer(o) => Some(*o),
+            AttributeValueVariant::None => None,
+            v => bail!("NTP offset must be an integer or none, got {:?}", v),
+        };
+        let clock_domain = match clock_domain.get() {
+            AttributeValueVariant::String(d) => Some(d.clone()),
+            AttributeValueVariant::None => None,
+            v => bail!("Clock domain must be a string or none, got {:?}", v),
+        };
+        Ok(Self {
+            capture_time_ns,
+            ntp_offset_ns,
+            clock_domain,
+        })
+    }
+}
+
+/// The memory the external frame resides in.
+///
+#[derive(Debug, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum ExternalMemoryType {
+    Host,
+    CudaDevice,
+    CudaPinned,
+    CudaUnified,
+    /// NVIDIA multimedia memory (NvBufSurface).
+    Nvmm,
+    DmaBuf,
+    VaSurface,
+}
+
+/// The layout of an external frame surface, so the frame can be accessed without the out-of-band
+/// knowledge of the producer.
+///
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "RawExternalFrameDescriptor")]
+pub struct ExternalFrameDescriptor {
+    memory_type: ExternalMemoryType,
+    device_id: Option<u32>,
+    fourcc: [u8; 4],
+    plane_strides: Vec<u32>,
+    plane_offsets: Vec<u64>,
+    /// The producer-specific handle of the surface, e.g. the DMA-BUF descriptor or the VA surface
+    /// id.
+    handle: Option<u64>,
+}
+
+/// The unchecked serde form of [`ExternalFrameDescriptor`], the deserialized descriptors are
+/// validated by [`ExternalFrameDescriptor::new`].
+///
+#[derive(serde::Deserialize)]
+struct RawExternalFrameDescriptor {
+    memory_type: ExternalMemoryType,
+    device_id: Option<u32>,
+    fourcc: [u8; 4],
+    plane_strides: Vec<u32>,
+    plane_offsets: Vec<u64>,
+    handle: Option<u64>,
+}
+
+impl TryFrom<RawExternalFrameDescriptor> for ExternalFrameDescriptor {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawExternalFrameDescriptor) -> anyhow::Result<Self> {
+        Self::new(
+            raw.memory_type,
+            raw.device_id,
+            std::str::from_utf8(&raw.fourcc)?,
+            raw.plane_strides,
+            raw.plane_offsets,
+            raw.handle,
+        )
+    }
+}
+
+impl ExternalFrameDescriptor {
+    /// Creates the descriptor, the fourcc must consist of 4 ASCII characters and the offsets,
+    /// when set, must be given for every plane.
+    ///
+    pub fn new(
+        memory_type: ExternalMemoryType,
+        device_id: Option<u32>,
+        fourcc: &str,
+        plane_strides: Vec<u32>,
+        plane_offsets: Vec<u64>,
+        handle: Option<u64>,
+    ) -> anyhow::Result<Self> {
+        let Ok(fourcc) = <[u8; 4]>::try_from(fourcc.as_bytes()) else {
+            bail!("FourCC must consist of 4 characters, got {:?}", fourcc);
+        };
+        if !fourcc.is_ascii() {
+            bail!("FourCC must consist of ASCII characters, got {:?}", fourcc);
+        }
+        if !plane_offsets.is_empty() && plane_offsets.len() != plane_strides.len() {
+            bail!(
+                "The number of plane offsets {} does not match the number of planes {}",
+                plane_offsets.len(),
+                plane_strides.len()
+            );
+        }
+        Ok(Self {
+            memory_type,
+            device_id,
+            fourcc,
+            plane_strides,
+            plane_offsets,
+            handle,
+        })
+    }
+
+    pub fn get_memory_type(&self) -> ExternalMemoryType {
+        self.memory_type
+    }
+
+    pub fn get_device_id(&self) -> Option<u32> {
+        self.device_id
+    }
+
+    pub fn get_fourcc(&self) -> String {
+        String::from_utf8_lossy(&self.fourcc).to_string()
+    }
+
+    /// The fourcc as the little-endian code, as defined by V4L2 and DRM.
+    ///
+    pub fn get_fourcc_code(&self) -> u32 {
+        u32::from_le_bytes(self.fourcc)
+    }
+
+    pub fn get_num_planes(&self) -> usize {
+        self.plane_strides.len()
+    }
+
+    pub fn get_plane_strides(&self) -> &[u32] {
+        &self.plane_strides
+    }
+
+    pub fn get_plane_offsets(&self) -> &[u64] {
+        &self.plane_offsets
+    }
+
+    pub fn get_handle(&self) -> Option<u64> {
+        self.handle
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, serde::Serialize)]
 pub struct ExternalFrame {
     pub method: String,
     pub location: Option<String>,
+    /// Transferred with protobuf as the hidden frame attribute.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descriptor: Option<ExternalFrameDescriptor>,
 }
 
 impl ToSerdeJsonValue for ExternalFrame {
@@ -63,17 +278,96 @@ impl ExternalFrame {
         Self {
             method: method.to_string(),
             location: location.map(String::from),
+            descriptor: None,
         }
     }
+
+    pub fn with_descriptor(mut self, descriptor: ExternalFrameDescriptor) -> Self {
+        self.descriptor = Some(descriptor);
+        self
+    }
 }
 
+/// The internal content is shared between the copies of the content and the frames, it is
+/// copied only when modified through [`VideoFrameContent::get_internal_data_mut`] while shared.
+///
 #[derive(Debug, PartialEq, Clone)]
 pub enum VideoFrameContent {
     External(ExternalFrame),
-    Internal(Vec<u8>),
+    Internal(Arc<Vec<u8>>),
     None,
 }
 
+impl VideoFrameContent {
+    pub fn internal(data: Vec<u8>) -> Self {
+        VideoFrameContent::Internal(Arc::new(data))
+    }
+
+    pub fn get_internal_data(&self) -> Option<&[u8]> {
+        match self {
+            VideoFrameContent::Internal(data) => Some(data.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Returns the internal data for modification, the data is copied first if it is shared
+    /// with other contents.
+    ///
+    pub fn get_internal_data_mut(&mut self) -> Option<&mut Vec<u8>> {
+        match self {
+            VideoFrameContent::Internal(data) => Some(Arc::make_mut(data)),
+            _ => None,
+        }
+    }
+
+    /// Returns the part of the internal data sharing the buffer with the content. `None` is
+    /// returned when the content is not internal or the range is out of bounds.
+    ///
+    pub fn slice_internal(&self, range: Range<usize>) -> Option<VideoFrameContentSlice> {
+        match self {
+            VideoFrameContent::Internal(data)
+                if range.start <= range.end && range.end <= data.len() =>
+            {
+                Some(VideoFrameContentSlice {
+                    data: data.clone(),
+                    range,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The zero-copy part of the internal frame content.
+///
+#[derive(Debug, Clone)]
+pub struct VideoFrameContentSlice {
+    data: Arc<Vec<u8>>,
+    range: Range<usize>,
+}
+
+impl VideoFrameContentSlice {
+    /// Slices the slice further, the range is relative to the slice.
+    ///
+    pub fn slice(&self, range: Range<usize>) -> Option<VideoFrameContentSlice> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+        Some(VideoFrameContentSlice {
+            data: self.data.clone(),
+            range: self.range.start + range.start..self.range.start + range.end,
+        })
+    }
+}
+
+impl Deref for VideoFrameContentSlice {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data[self.range.clone()]
+    }
+}
+
 impl ToSerdeJsonValue for VideoFrameContent {
     fn to_serde_json_value(&self) -> Value {
         match self {
@@ -100,12 +394,51 @@ impl ToSerdeJsonValue for VideoFrameTranscodingMethod {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, serde::Serialize)]
+/// Defines how [`VideoFrameProxy::crop`] treats the objects crossing the border of the region.
+/// The objects located completely outside the region are always dropped.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CropObjectPolicy {
+    /// The boxes are clipped by the region, a clipped rotated box becomes the axis-aligned part
+    /// of its wrapping box.
+    Clip,
+    /// The objects are dropped.
+    Drop,
+}
+
+/// Translates the box to the region coordinates, `None` means the object must be dropped.
+///
+fn crop_box(bbox: &RBBox, roi: (f32, f32, f32, f32), policy: CropObjectPolicy) -> Option<RBBox> {
+    let (left, top, right, bottom) = roi;
+    let (l, t, r, b) = bbox.get_wrapping_bbox().as_ltrb().ok()?;
+    if l >= left && t >= top && r <= right && b <= bottom {
+        let cropped = bbox.copy();
+        cropped.shift(-left, -top);
+        return Some(cropped);
+    }
+    if r <= left || l >= right || b <= top || t >= bottom {
+        return None;
+    }
+    match policy {
+        CropObjectPolicy::Clip => Some(RBBox::ltrb(
+            l.max(left) - left,
+            t.max(top) - top,
+            r.min(right) - left,
+            b.min(bottom) - top,
+        )),
+        CropObjectPolicy::Drop => None,
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 pub enum VideoFrameTransformation {
     InitialSize(u64, u64),
     Scale(u64, u64),
     Padding(u64, u64, u64, u64),
     ResultingSize(u64, u64),
+    /// The region `(left, top, width, height)` the frame is cropped to, see
+    /// [`VideoFrameProxy::crop`].
+    Crop(u64, u64, u64, u64),
 }
 
 impl ToSerdeJsonValue for VideoFrameTransformation {
@@ -114,6 +447,224 @@ impl ToSerdeJsonValue for VideoFrameTransformation {
     }
 }
 
+/// Copy-on-write storage of the frame objects. Readers take a snapshot under the frame lock and
+/// traverse it after the lock is released; a writer copies the map only while a snapshot is
+/// alive. The copy shares the bounding boxes, so the boxes obtained from the objects before the
+/// copy remain attached to them. Cloning the storage makes a deep copy.
+///
+#[derive(Debug, Default)]
+pub(crate) struct FrameObjects(Arc<HashMap<i64, VideoObject>>);
+
+impl FrameObjects {
+    pub(crate) fn snapshot(&self) -> Arc<HashMap<i64, VideoObject>> {
+        self.0.clone()
+    }
+
+    pub(crate) fn make_mut(&mut self) -> &mut HashMap<i64, VideoObject> {
+        if Arc::get_mut(&mut self.0).is_none() {
+            let objects = self
+                .0
+                .iter()
+                .map(|(id, o)| (*id, o.shared_copy()))
+                .collect();
+            self.0 = Arc::new(objects);
+        }
+        Arc::get_mut(&mut self.0).expect("The objects must not be shared after the copy")
+    }
+}
+
+impl Clone for FrameObjects {
+    fn clone(&self) -> Self {
+        Self(Arc::new(self.0.as_ref().clone()))
+    }
+}
+
+impl std::ops::Deref for FrameObjects {
+    type Target = HashMap<i64, VideoObject>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<HashMap<i64, VideoObject>> for FrameObjects {
+    fn from(objects: HashMap<i64, VideoObject>) -> Self {
+        Self(Arc::new(objects))
+    }
+}
+
+#[derive(Debug, Default)]
+struct AttributePositions {
+    len: usize,
+    by_name: HashMap<String, Vec<usize>>,
+    by_hint: HashMap<Option<String>, Vec<usize>>,
+}
+
+/// Lazily built secondary indexes of the frame attributes by name and by hint. The indexes are
+/// dropped on every mutable access to the attributes and rebuilt by the next lookup. Cloning
+/// produces an empty index.
+///
+#[derive(Debug, Default)]
+pub(crate) struct AttributeIndex(OnceLock<AttributePositions>);
+
+impl Clone for AttributeIndex {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl AttributeIndex {
+    pub(crate) fn invalidate(&mut self) {
+        self.0.take();
+    }
+
+    /// Returns the indexes, or `None` if the attributes were resized bypassing
+    /// [`WithAttributes`], so the caller falls back to the full scan.
+    ///
+    fn positions(&self, attributes: &[Attribute]) -> Option<&AttributePositions> {
+        let positions = self.0.get_or_init(|| {
+            let mut positions = AttributePositions {
+                len: attributes.len(),
+                ..Default::default()
+            };
+            for (i, a) in attributes.iter().enumerate() {
+                positions.by_name.entry(a.name.clone()).or_default().push(i);
+                positions.by_hint.entry(a.hint.clone()).or_default().push(i);
+            }
+            positions
+        });
+        (positions.len == attributes.len()).then_some(positions)
+    }
+
+    /// Sets the attribute keeping the built index up to date, so the interleaved lookups and
+    /// insertions do not rebuild it.
+    ///
+    pub(crate) fn set_attribute(
+        &mut self,
+        attributes: &mut Vec<Attribute>,
+        attribute: Attribute,
+    ) -> Option<Attribute> {
+        let Some(positions) = self.0.get_mut().filter(|p| p.len == attributes.len()) else {
+            self.invalidate();
+            return match attributes
+                .iter()
+                .position(|a| a.namespace == attribute.namespace && a.name == attribute.name)
+            {
+                Some(i) => Some(mem::replace(&mut attributes[i], attribute)),
+                None => {
+                    attributes.push(attribute);
+                    None
+                }
+            };
+        };
+        let existing = positions.by_name.get(&attribute.name).and_then(|found| {
+            found
+                .iter()
+                .copied()
+                .find(|i| attributes[*i].namespace == attribute.namespace)
+        });
+        match existing {
+            Some(i) => {
+                if attributes[i].hint != attribute.hint {
+                    if let Some(found) = positions.by_hint.get_mut(&attributes[i].hint) {
+                        found.retain(|p| *p != i);
+                    }
+                    let found = positions.by_hint.entry(attribute.hint.clone()).or_default();
+                    let at = found.partition_point(|p| *p < i);
+                    found.insert(at, i);
+                }
+                Some(mem::replace(&mut attributes[i], attribute))
+            }
+            None => {
+                let i = attributes.len();
+                positions
+                    .by_name
+                    .entry(attribute.name.clone())
+                    .or_default()
+                    .push(i);
+                positions
+                    .by_hint
+                    .entry(attribute.hint.clone())
+                    .or_default()
+                    .push(i);
+                positions.len += 1;
+                attributes.push(attribute);
+                None
+            }
+        }
+    }
+
+    fn collect(attributes: &[Attribute], mut found: Vec<usize>) -> Vec<(String, String)> {
+        found.sort_unstable();
+        found.dedup();
+        found
+            .into_iter()
+            .map(|i| (attributes[i].namespace.clone(), attributes[i].name.clone()))
+            .collect()
+    }
+
+    pub(crate) fn get_attribute<'a>(
+        &self,
+        attributes: &'a [Attribute],
+        namespace: &str,
+        name: &str,
+    ) -> Option<&'a Attribute> {
+        let Some(positions) = self.positions(attributes) else {
+            return attributes
+                .iter()
+                .find(|a| a.namespace == namespace && a.name == name);
+        };
+        positions
+            .by_name
+            .get(name)?
+            .iter()
+            .map(|i| &attributes[*i])
+            .find(|a| a.namespace == namespace)
+    }
+
+    pub(crate) fn find_with_names(
+        &self,
+        attributes: &[Attribute],
+        names: &[&str],
+    ) -> Vec<(String, String)> {
+        let Some(positions) = self.positions(attributes) else {
+            return attributes
+                .iter()
+                .filter(|a| names.contains(&a.name.as_str()))
+                .map(|a| (a.namespace.clone(), a.name.clone()))
+                .collect();
+        };
+        let found = names
+            .iter()
+            .filter_map(|n| positions.by_name.get(*n))
+            .flatten()
+            .copied()
+            .collect();
+        Self::collect(attributes, found)
+    }
+
+    pub(crate) fn find_with_hints(
+        &self,
+        attributes: &[Attribute],
+        hints: &[&Option<&str>],
+    ) -> Vec<(String, String)> {
+        let Some(positions) = self.positions(attributes) else {
+            return attributes
+                .iter()
+                .filter(|a| hints.contains(&&a.hint.as_deref()))
+                .map(|a| (a.namespace.clone(), a.name.clone()))
+                .collect();
+        };
+        let found = hints
+            .iter()
+            .filter_map(|h| positions.by_hint.get(&h.map(String::from)))
+            .flatten()
+            .copied()
+            .collect();
+        Self::collect(attributes, found)
+    }
+}
+
 #[derive(Debug, Clone, Builder)]
 pub struct VideoFrame {
     #[builder(setter(skip))]
@@ -140,12 +691,18 @@ pub struct VideoFrame {
     pub content: Arc<VideoFrameContent>,
     #[builder(setter(skip))]
     pub transformations: Vec<VideoFrameTransformation>,
+    /// Modify through [`WithAttributes`] to keep the attribute index up to date.
     #[builder(setter(skip))]
     pub attributes: Vec<Attribute>,
     #[builder(setter(skip))]
-    pub(crate) objects: HashMap<i64, VideoObject>,
+    pub(crate) attribute_index: AttributeIndex,
+    #[builder(setter(skip))]
+    pub(crate) objects: FrameObjects,
     #[builder(setter(skip))]
     pub(crate) max_object_id: i64,
+    /// The change journal, not transferred with protobuf.
+    #[builder(setter(skip))]
+    pub journal: Option<ChangeJournal>,
 }
 
 const DEFAULT_TRANSFORMATIONS_COUNT: usize = 4;
@@ -176,12 +733,20 @@ impl Default for VideoFrame {
             content: Arc::new(VideoFrameContent::None),
             transformations: Vec::with_capacity(DEFAULT_TRANSFORMATIONS_COUNT),
             attributes: Vec::with_capacity(DEFAULT_ATTRIBUTES_COUNT),
-            objects: HashMap::with_capacity(DEFAULT_OBJECTS_COUNT),
+            attribute_index: AttributeIndex::default(),
+            objects: FrameObjects::from(HashMap::with_capacity(DEFAULT_OBJECTS_COUNT)),
             max_object_id: 0,
+            journal: None,
         }
     }
 }
 
+impl Journaled for VideoFrame {
+    fn journal_mut(&mut self) -> &mut Option<ChangeJournal> {
+        &mut self.journal
+    }
+}
+
 impl ToSerdeJsonValue for VideoFrame {
     fn to_serde_json_value(&self) -> Value {
         let frame_uuid = Uuid::from_u128(self.uuid).to_string();
@@ -236,8 +801,36 @@ impl WithAttributes for VideoFrame {
     where
         F: FnOnce(&mut Vec<Attribute>) -> R,
     {
+        self.attribute_index.invalidate();
         f(&mut self.attributes)
     }
+
+    fn get_attribute(&self, namespace: &str, name: &str) -> Option<Attribute> {
+        self.attribute_index
+            .get_attribute(&self.attributes, namespace, name)
+            .cloned()
+    }
+
+    fn contains_attribute(&self, namespace: &str, name: &str) -> bool {
+        self.attribute_index
+            .get_attribute(&self.attributes, namespace, name)
+            .is_some()
+    }
+
+    fn set_attribute(&mut self, attribute: Attribute) -> Option<Attribute> {
+        self.attribute_index
+            .set_attribute(&mut self.attributes, attribute)
+    }
+
+    fn find_attributes_with_names(&self, names: &[&str]) -> Vec<(String, String)> {
+        self.attribute_index
+            .find_with_names(&self.attributes, names)
+    }
+
+    fn find_attributes_with_hints(&self, hints: &[&Option<&str>]) -> Vec<(String, String)> {
+        self.attribute_index
+            .find_with_hints(&self.attributes, hints)
+    }
 }
 
 impl VideoFrame {
@@ -258,23 +851,27 @@ impl VideoFrame {
     }
 
     pub fn get_objects_mut(&mut self) -> &mut HashMap<i64, VideoObject> {
-        &mut self.objects
+        self.objects.make_mut()
     }
 
     pub fn smart_copy(&self) -> Self {
         let mut frame = self.clone();
-        frame.objects.clear();
-        for (id, o) in self.get_objects() {
-            let mut copy = o.detached_copy();
-            copy.parent_id = o.get_parent_id();
-            frame.objects.insert(*id, copy);
-        }
+        frame.objects = FrameObjects::from(
+            self.get_objects()
+                .iter()
+                .map(|(id, o)| {
+                    let mut copy = o.detached_copy();
+                    copy.parent_id = o.get_parent_id();
+                    (*id, copy)
+                })
+                .collect(),
+        );
         frame
     }
 
     pub fn exclude_all_temporary_attributes(&mut self) {
         self.exclude_temporary_attributes();
-        self.objects.values_mut().for_each(|o| {
+        self.get_objects_mut().values_mut().for_each(|o| {
             o.exclude_temporary_attributes();
         });
     }
@@ -286,7 +883,7 @@ impl VideoFrame {
     ) {
         self.restore_attributes(frame_attributes);
         for (id, attrs) in object_attributes {
-            let o = self.objects.get_mut(&id).unwrap();
+            let o = self.get_objects_mut().get_mut(&id).unwrap();
             o.restore_attributes(attrs);
         }
     }
@@ -361,7 +958,27 @@ impl WithAttributes for VideoFrameProxy {
         F: FnOnce(&mut Vec<Attribute>) -> R,
     {
         let mut bind = trace!(self.inner.write());
-        f(&mut bind.attributes)
+        bind.with_attributes_mut(f)
+    }
+
+    fn get_attribute(&self, namespace: &str, name: &str) -> Option<Attribute> {
+        trace!(self.inner.read_recursive()).get_attribute(namespace, name)
+    }
+
+    fn contains_attribute(&self, namespace: &str, name: &str) -> bool {
+        trace!(self.inner.read_recursive()).contains_attribute(namespace, name)
+    }
+
+    fn set_attribute(&mut self, attribute: Attribute) -> Option<Attribute> {
+        trace!(self.inner.write()).set_attribute(attribute)
+    }
+
+    fn find_attributes_with_names(&self, names: &[&str]) -> Vec<(String, String)> {
+        trace!(self.inner.read_recursive()).find_attributes_with_names(names)
+    }
+
+    fn find_attributes_with_hints(&self, hints: &[&Option<&str>]) -> Vec<(String, String)> {
+        trace!(self.inner.read_recursive()).find_attributes_with_hints(hints)
     }
 }
 
@@ -398,6 +1015,60 @@ impl VideoFrameProxy {
         }
     }
 
+    /// Produces the copy of the frame limited to the axis-aligned region: the objects are
+    /// translated to the region coordinates, the objects outside the region are dropped and the
+    /// ones crossing its border are handled according to the policy. An object is dropped when
+    /// either its detection or its track box is dropped.
+    ///
+    /// The region is clamped by the frame size and rounded to whole pixels; the derived frame has
+    /// the size of the region, which is recorded as the [`VideoFrameTransformation::Crop`], so
+    /// the boxes can be mapped back to the source frame. The content is shared with the source
+    /// frame, the pixels are not cropped.
+    ///
+    pub fn crop(&self, roi: &RBBox, policy: CropObjectPolicy) -> anyhow::Result<VideoFrameProxy> {
+        if roi.get_angle().unwrap_or(0.0) != 0.0 {
+            bail!("The crop region must not be rotated");
+        }
+        let (left, top, right, bottom) = roi.as_ltrb()?;
+        let region = (
+            left.max(0.0).round(),
+            top.max(0.0).round(),
+            right.min(self.get_width() as f32).round(),
+            bottom.min(self.get_height() as f32).round(),
+        );
+        let (width, height) = (region.2 - region.0, region.3 - region.1);
+        if width <= 0.0 || height <= 0.0 {
+            bail!("The crop region {:?} is outside the frame", roi);
+        }
+
+        let mut frame = self.smart_copy();
+        let mut dropped = Vec::new();
+        for mut o in frame.get_all_objects() {
+            let detection_box = crop_box(&o.get_detection_box(), region, policy);
+            let track_box = o.get_track_box().map(|b| crop_box(&b, region, policy));
+            match (detection_box, track_box) {
+                (None, _) | (_, Some(None)) => dropped.push(o.get_id()),
+                (Some(detection_box), track_box) => {
+                    o.set_detection_box(detection_box);
+                    if let Some(Some(track_box)) = track_box {
+                        o.set_track_box(track_box);
+                    }
+                }
+            }
+        }
+        frame.delete_objects_with_ids(&dropped);
+
+        frame.set_width(width as i64);
+        frame.set_height(height as i64);
+        frame.add_transformation(VideoFrameTransformation::Crop(
+            region.0 as u64,
+            region.1 as u64,
+            width as u64,
+            height as u64,
+        ))?;
+        Ok(frame)
+    }
+
     pub fn smart_copy(&self) -> Self {
         let inner = trace!(self.inner.read());
         let inner_copy = inner.smart_copy();
@@ -433,11 +1104,18 @@ impl VideoFrameProxy {
             .collect()
     }
 
-    pub fn access_objects(&self, q: &MatchQuery) -> Vec<BorrowedVideoObject> {
+    /// Returns the current objects of the frame. The lock is held only to clone the pointer, so
+    /// the snapshot can be traversed while other threads modify the frame; the modifications
+    /// are not visible in the snapshot.
+    ///
+    pub fn objects_snapshot(&self) -> Arc<HashMap<i64, VideoObject>> {
         let inner = trace!(self.inner.read_recursive());
-        let objects = inner.objects.values().cloned().collect::<Vec<_>>();
-        drop(inner);
-        fiter_map_with_control_flow(objects, |o| q.execute_with_new_context(o))
+        inner.objects.snapshot()
+    }
+
+    pub fn access_objects(&self, q: &MatchQuery) -> Vec<BorrowedVideoObject> {
+        let objects = self.objects_snapshot();
+        fiter_map_with_control_flow(objects.values(), |o| q.execute_with_new_context(o))
             .iter()
             .map(|o| BorrowedVideoObject(self.into(), o.get_id()))
             .collect()
@@ -452,9 +1130,7 @@ impl VideoFrameProxy {
     }
 
     pub fn access_objects_with_id(&self, ids: &[i64]) -> Vec<BorrowedVideoObject> {
-        let inner = trace!(self.inner.read_recursive());
-        let resident_objects = inner.objects.clone();
-        drop(inner);
+        let resident_objects = self.objects_snapshot();
 
         ids.iter()
             .filter_map(|id| {
@@ -469,7 +1145,7 @@ impl VideoFrameProxy {
 
     pub fn delete_objects_with_ids(&self, ids: &[i64]) -> Vec<VideoObject> {
         let mut inner = trace!(self.inner.write());
-        let objects = mem::take(&mut inner.objects);
+        let objects = mem::take(inner.get_objects_mut());
         let (mut retained, removed): (HashMap<i64, VideoObject>, HashMap<i64, VideoObject>) =
             objects.into_iter().partition(|(id, _)| !ids.contains(id));
 
@@ -480,7 +1156,7 @@ impl VideoFrameProxy {
                 }
             }
         });
-        inner.objects = retained;
+        inner.objects = FrameObjects::from(retained);
         drop(inner);
 
         removed
@@ -637,16 +1313,17 @@ impl VideoFrameProxy {
         let new_id = self.get_max_object_id() + 1;
         let mut inner = trace!(self.inner.write());
         object.attach_to_video_frame(self.clone());
-        let assigned_object_id = if inner.objects.contains_key(&object_id) {
+        let objects = inner.get_objects_mut();
+        let assigned_object_id = if objects.contains_key(&object_id) {
             match policy {
                 IdCollisionResolutionPolicy::GenerateNewId => {
                     object.with_object_mut(|o| o.id = new_id);
-                    inner.objects.insert(new_id, object);
+                    objects.insert(new_id, object);
                     new_id
                 }
                 IdCollisionResolutionPolicy::Overwrite => {
-                    inner.objects.remove(&object_id).unwrap();
-                    inner.objects.insert(object_id, object);
+                    objects.remove(&object_id).unwrap();
+                    objects.insert(object_id, object);
                     object_id
                 }
                 IdCollisionResolutionPolicy::Error => {
@@ -654,7 +1331,7 @@ impl VideoFrameProxy {
                 }
             }
         } else {
-            inner.objects.insert(object_id, object);
+            objects.insert(object_id, object);
             object_id
         };
 
@@ -664,14 +1341,102 @@ impl VideoFrameProxy {
         Ok(BorrowedVideoObject(self.into(), assigned_object_id))
     }
 
+    /// Adds the objects the same way the sequential calls of [`VideoFrameProxy::add_object`]
+    /// would, but under a single frame lock. A parent may be one of the objects added before it
+    /// in the list. The objects are validated before the frame is modified, so either all of
+    /// them are added or none.
+    ///
+    pub fn add_objects(
+        &self,
+        objects: Vec<VideoObject>,
+        policy: IdCollisionResolutionPolicy,
+    ) -> anyhow::Result<Vec<BorrowedVideoObject>> {
+        let mut inner = trace!(self.inner.write());
+        let mut max_object_id = inner.max_object_id;
+        let mut added = HashSet::with_capacity(objects.len());
+        let mut assigned_ids = Vec::with_capacity(objects.len());
+        for object in &objects {
+            if let Some(parent_id) = object.parent_id {
+                if !inner.objects.contains_key(&parent_id) && !added.contains(&parent_id) {
+                    bail!(
+                        "Parent object with ID {} does not exist in the frame.",
+                        parent_id
+                    );
+                }
+            }
+            let object_id = object.id;
+            let assigned_object_id =
+                if inner.objects.contains_key(&object_id) || added.contains(&object_id) {
+                    match policy {
+                        IdCollisionResolutionPolicy::GenerateNewId => max_object_id + 1,
+                        IdCollisionResolutionPolicy::Overwrite => object_id,
+                        IdCollisionResolutionPolicy::Error => {
+                            bail!("Object with ID {} already exists in the frame.", object_id);
+                        }
+                    }
+                } else {
+                    object_id
+                };
+            max_object_id = max_object_id.max(assigned_object_id);
+            added.insert(assigned_object_id);
+            assigned_ids.push(assigned_object_id);
+        }
+
+        let frame_objects = inner.get_objects_mut();
+        for (mut object, id) in objects.into_iter().zip(&assigned_ids) {
+            object.id = *id;
+            object.attach_to_video_frame(self.clone());
+            frame_objects.insert(*id, object);
+        }
+        inner.max_object_id = max_object_id;
+        drop(inner);
+
+        Ok(assigned_ids
+            .into_iter()
+            .map(|id| BorrowedVideoObject(self.into(), id))
+            .collect())
+    }
+
     pub fn get_max_object_id(&self) -> i64 {
         let inner = trace!(self.inner.read_recursive());
         inner.max_object_id
     }
 
+    /// Merges the update objects matching the existing ones, returns the unmatched objects.
+    ///
+    fn merge_objects(
+        &self,
+        objects: Vec<(VideoObject, Option<i64>)>,
+        policy: &ObjectMergePolicy,
+    ) -> Vec<(VideoObject, Option<i64>)> {
+        let mut unmatched = Vec::new();
+        for (obj, p) in objects {
+            let existing = match obj.track_id {
+                Some(track_id) => self
+                    .access_objects(&and![
+                        MatchQuery::Namespace(StringExpression::EQ(obj.namespace.clone())),
+                        MatchQuery::TrackId(IntExpression::EQ(track_id))
+                    ])
+                    .into_iter()
+                    .min_by_key(|o| o.get_id()),
+                None => self
+                    .get_object(obj.id)
+                    .filter(|o| o.get_namespace() == obj.namespace),
+            };
+            match existing {
+                Some(mut existing) => policy.merge(&mut existing, &obj),
+                None => unmatched.push((obj, p)),
+            }
+        }
+        unmatched
+    }
+
     pub(crate) fn update_objects(&self, update: &VideoFrameUpdate) -> anyhow::Result<()> {
         use crate::primitives::frame_update::ObjectUpdatePolicy::*;
-        let other_inner = update.objects.clone();
+        let other_inner = match &update.object_merge_policy {
+            Some(policy) => self.merge_objects(update.objects.clone(), policy),
+            None => update.objects.clone(),
+        };
 
         let object_query = |o: &VideoObject| {
             and![
@@ -801,7 +1566,94 @@ impl VideoFrameProxy {
         Ok(())
     }
 
+    pub(crate) fn apply_deletions(&self, update: &VideoFrameUpdate) {
+        if !update.deleted_objects.is_empty() {
+            self.delete_objects_with_ids(&update.deleted_objects);
+        }
+        let mut inner = trace!(self.inner.write());
+        for (namespace, name) in &update.deleted_frame_attributes {
+            inner.delete_attribute(namespace, name);
+        }
+    }
+
+    pub(crate) fn replace_objects(&self, update: &VideoFrameUpdate) -> anyhow::Result<()> {
+        if update.replaced_objects.is_empty() {
+            return Ok(());
+        }
+        // the parents are set afterwards, so they may be placed in any order
+        let objects = update
+            .replaced_objects
+            .iter()
+            .map(|o| {
+                let mut o = o.clone();
+                o.parent_id = None;
+                o.frame = None;
+                o
+            })
+            .collect();
+        self.add_objects(objects, IdCollisionResolutionPolicy::Overwrite)?;
+        for o in &update.replaced_objects {
+            if let Some(parent_id) = o.parent_id {
+                self.set_parent_by_id(o.id, parent_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the update turning this frame into the other one with [`VideoFrameProxy::update`]:
+    /// the missing frame attributes and objects are deleted, the added or changed attributes
+    /// are replaced, and the added or changed objects are placed with their ids. The frame
+    /// properties other than the attributes and the objects are not compared, the attributes
+    /// holding temporary values are always considered changed.
+    ///
+    pub fn diff(&self, other: &VideoFrameProxy) -> VideoFrameUpdate {
+        let mut update = VideoFrameUpdate::default();
+        update.set_frame_attribute_policy(AttributeUpdatePolicy::ReplaceWithForeign);
+
+        let own_attributes = self.with_attributes_ref(|a| a.clone());
+        let other_attributes = other.with_attributes_ref(|a| a.clone());
+        for a in &own_attributes {
+            if !other_attributes
+                .iter()
+                .any(|o| o.namespace == a.namespace && o.name == a.name)
+            {
+                update.add_deleted_frame_attribute(&a.namespace, &a.name);
+            }
+        }
+        for a in other_attributes {
+            if !own_attributes.contains(&a) {
+                update.add_frame_attribute(a);
+            }
+        }
+
+        let own_objects = self.objects_snapshot();
+        let other_objects = other.objects_snapshot();
+        let mut deleted = own_objects
+            .keys()
+            .filter(|id| !other_objects.contains_key(*id))
+            .copied()
+            .collect::<Vec<_>>();
+        deleted.sort_unstable();
+        deleted
+            .into_iter()
+            .for_each(|id| update.add_deleted_object(id));
+
+        let mut replaced = other_objects
+            .values()
+            .filter(|o| own_objects.get(&o.id).is_none_or(|own| !own.content_eq(o)))
+            .collect::<Vec<_>>();
+        replaced.sort_by_key(|o| o.id);
+        for o in replaced {
+            let mut o = o.clone();
+            o.frame = None;
+            update.add_replaced_object(o);
+        }
+        update
+    }
+
     pub fn update(&self, update: &VideoFrameUpdate) -> anyhow::Result<()> {
+        self.apply_deletions(update);
+        self.replace_objects(update)?;
         self.update_frame_attributes(update)?;
         self.update_object_attributes(update)?;
         self.update_objects(update)?;
@@ -823,6 +1675,7 @@ impl VideoFrameProxy {
         dts: Option<i64>,
         duration: Option<i64>,
     ) -> Self {
+        report_unknown_codec(source_id, *codec);
         VideoFrameProxy::from_inner(VideoFrame {
             source_id: source_id.to_string(),
             pts,
@@ -873,6 +1726,7 @@ impl VideoFrameProxy {
 
     pub fn set_source_id(&mut self, source_id: &str) {
         let mut inner = trace!(self.inner.write());
+        inner.record_change("source_id", |f| (json!(f.source_id), json!(source_id)));
         inner.source_id = source_id.to_string();
     }
 
@@ -896,6 +1750,38 @@ impl VideoFrameProxy {
         self.get_uuid().to_string()
     }
 
+    /// Attaches the propagation context of the span to the frame as a hidden persistent
+    /// attribute, so the trace follows the frame through the protobuf serialization.
+    ///
+    pub fn attach_trace_context(&mut self, context: &Context) {
+        self.set_attribute(PropagatedContext::inject(context).to_attribute());
+    }
+
+    pub fn get_trace_context(&self) -> Option<PropagatedContext> {
+        self.with_attributes_ref(|attributes| {
+            attributes
+                .iter()
+                .find(|a| a.namespace == TRACE_CONTEXT_NAMESPACE && a.name == TRACE_CONTEXT_NAME)
+                .and_then(PropagatedContext::from_attribute)
+        })
+    }
+
+    /// Returns the attached context, the default context when nothing is attached.
+    ///
+    pub fn extract_trace_context(&self) -> Context {
+        self.get_trace_context()
+            .map(|context| context.extract())
+            .unwrap_or_default()
+    }
+
+    /// Starts the span which is a child of the attached context.
+    ///
+    pub fn child_span(&self, name: &str) -> Context {
+        self.get_trace_context()
+            .map(|context| context.child_span(name))
+            .unwrap_or_default()
+    }
+
     pub fn get_creation_timestamp_ns(&self) -> u128 {
         trace!(self.inner.read_recursive()).creation_timestamp_ns
     }
@@ -911,6 +1797,7 @@ impl VideoFrameProxy {
     pub fn set_pts(&mut self, pts: i64) {
         assert!(pts >= 0, "pts must be greater than or equal to 0");
         let mut inner = trace!(self.inner.write());
+        inner.record_change("pts", |f| (json!(f.pts), json!(pts)));
         inner.pts = pts;
     }
 
@@ -920,6 +1807,7 @@ impl VideoFrameProxy {
 
     pub fn set_framerate(&mut self, framerate: &str) {
         let mut inner = trace!(self.inner.write());
+        inner.record_change("framerate", |f| (json!(f.framerate), json!(framerate)));
         inner.framerate = framerate.to_string();
     }
 
@@ -930,6 +1818,7 @@ impl VideoFrameProxy {
     pub fn set_width(&mut self, width: i64) {
         assert!(width > 0, "width must be greater than 0");
         let mut inner = trace!(self.inner.write());
+        inner.record_change("width", |f| (json!(f.width), json!(width)));
         inner.width = width;
     }
 
@@ -940,6 +1829,7 @@ impl VideoFrameProxy {
     pub fn set_height(&mut self, height: i64) {
         assert!(height > 0, "height must be greater than 0");
         let mut inner = trace!(self.inner.write());
+        inner.record_change("height", |f| (json!(f.height), json!(height)));
         inner.height = height;
     }
 
@@ -954,6 +1844,7 @@ impl VideoFrameProxy {
             "dts must be greater than or equal to 0"
         );
         let mut inner = trace!(self.inner.write());
+        inner.record_change("dts", |f| (json!(f.dts), json!(dts)));
         inner.dts = dts;
     }
 
@@ -968,6 +1859,7 @@ impl VideoFrameProxy {
             "duration must be greater than or equal to 0"
         );
         let mut inner = trace!(self.inner.write());
+        inner.record_change("duration", |f| (json!(f.duration), json!(duration)));
         inner.duration = duration;
     }
 
@@ -986,19 +1878,53 @@ impl VideoFrameProxy {
         inner.codec.clone()
     }
 
+    /// Sets the codec; unknown codecs are accepted but reported, see [`report_unknown_codec`],
+    /// use [`VideoFrameProxy::try_set_codec`] to reject them.
+    ///
     pub fn set_codec(&mut self, codec: Option<String>) {
+        report_unknown_codec(&self.get_source_id(), codec.as_deref());
         let mut inner = trace!(self.inner.write());
+        inner.record_change("codec", |f| (json!(f.codec), json!(codec)));
         inner.codec = codec;
     }
 
+    pub fn try_set_codec(&mut self, codec: Option<String>) -> anyhow::Result<()> {
+        validate_codec(codec.as_deref())?;
+        let mut inner = trace!(self.inner.write());
+        inner.record_change("codec", |f| (json!(f.codec), json!(codec)));
+        inner.codec = codec;
+        Ok(())
+    }
+
+    pub fn get_codec_info(&self) -> Option<CodecInfo> {
+        self.get_codec().and_then(|c| find_codec(&c))
+    }
+
     pub fn clear_transformations(&mut self) {
         let mut inner = trace!(self.inner.write());
         inner.transformations.clear();
     }
 
-    pub fn add_transformation(&mut self, transformation: VideoFrameTransformation) {
+    /// Records the transformation, the sizes must not be zero.
+    ///
+    pub fn add_transformation(
+        &mut self,
+        transformation: VideoFrameTransformation,
+    ) -> anyhow::Result<()> {
+        match transformation {
+            VideoFrameTransformation::InitialSize(w, h)
+            | VideoFrameTransformation::Scale(w, h)
+            | VideoFrameTransformation::ResultingSize(w, h)
+            | VideoFrameTransformation::Crop(_, _, w, h)
+                if w == 0 || h == 0 =>
+            {
+                bail!("The transformation {:?} has a zero size", transformation)
+            }
+            _ => {}
+        }
         let mut inner = trace!(self.inner.write());
         inner.transformations.push(transformation);
+        Ok(())
     }
 
     pub fn get_transformations(&self) -> Vec<VideoFrameTransformation> {
@@ -1006,6 +1932,71 @@ impl VideoFrameProxy {
         inner.transformations.clone()
     }
 
+    /// The mapping of the source coordinates to the frame coordinates defined by the recorded
+    /// transformations, the scale factors and the offsets `(kx, ky, dx, dy)` such that
+    /// `x = x_source * kx + dx`. The resulting size trims the frame at the right and the bottom
+    /// and does not change the coordinates, the crop shifts them by the region origin.
+    ///
+    fn source_mapping(&self) -> anyhow::Result<(f32, f32, f32, f32)> {
+        let (mut kx, mut ky, mut dx, mut dy) = (1.0, 1.0, 0.0, 0.0);
+        let mut size: Option<(u64, u64)> = None;
+        for t in self.get_transformations() {
+            match t {
+                VideoFrameTransformation::InitialSize(w, h)
+                | VideoFrameTransformation::ResultingSize(w, h) => size = Some((w, h)),
+                VideoFrameTransformation::Scale(w, h) => {
+                    // the transformations restored from protobuf or JSON are not validated
+                    if w == 0 || h == 0 {
+                        bail!("The frame is scaled to the zero size {}x{}", w, h);
+                    }
+                    let Some((sw, sh)) = size.filter(|(sw, sh)| *sw > 0 && *sh > 0) else {
+                        bail!(
+                            "The size of the frame before the scaling to {}x{} is unknown",
+                            w,
+                            h
+                        );
+                    };
+                    let (fx, fy) = (w as f32 / sw as f32, h as f32 / sh as f32);
+                    (kx, ky, dx, dy) = (kx * fx, ky * fy, dx * fx, dy * fy);
+                    size = Some((w, h));
+                }
+                VideoFrameTransformation::Padding(left, top, right, bottom) => {
+                    dx += left as f32;
+                    dy += top as f32;
+                    size = size.map(|(w, h)| (w + left + right, h + top + bottom));
+                }
+                VideoFrameTransformation::Crop(left, top, w, h) => {
+                    dx -= left as f32;
+                    dy -= top as f32;
+                    size = Some((w, h));
+                }
+            }
+        }
+        Ok((kx, ky, dx, dy))
+    }
+
+    /// Maps the box in the frame coordinates to the coordinates of the source frame, undoing the
+    /// recorded scaling, padding and cropping.
+    ///
+    pub fn map_box_to_source(&self, bbox: &RBBox) -> anyhow::Result<RBBox> {
+        let (kx, ky, dx, dy) = self.source_mapping()?;
+        let mapped = bbox.copy();
+        mapped.shift(-dx, -dy);
+        mapped.scale(1.0 / kx, 1.0 / ky);
+        Ok(mapped)
+    }
+
+    /// Maps the box in the coordinates of the source frame to the frame coordinates, applying
+    /// the recorded scaling, padding and cropping.
+    ///
+    pub fn map_box_from_source(&self, bbox: &RBBox) -> anyhow::Result<RBBox> {
+        let (kx, ky, dx, dy) = self.source_mapping()?;
+        let mapped = bbox.copy();
+        mapped.scale(kx, ky);
+        mapped.shift(dx, dy);
+        Ok(mapped)
+    }
+
     pub fn get_keyframe(&self) -> Option<bool> {
         let inner = trace!(self.inner.read_recursive());
         inner.keyframe
@@ -1013,9 +2004,47 @@ impl VideoFrameProxy {
 
     pub fn set_keyframe(&mut self, keyframe: Option<bool>) {
         let mut inner = trace!(self.inner.write());
+        inner.record_change("keyframe", |f| (json!(f.keyframe), json!(keyframe)));
         inner.keyframe = keyframe;
     }
 
+    /// Starts recording the changes made through the setters of the frame and its current
+    /// objects; the objects added later are journaled only if their journals are enabled.
+    ///
+    pub fn enable_journal(&self) {
+        let mut inner = trace!(self.inner.write());
+        inner.journal.get_or_insert_with(ChangeJournal::default);
+        inner.get_objects_mut().values_mut().for_each(|o| {
+            o.journal.get_or_insert_with(ChangeJournal::default);
+        });
+    }
+
+    /// Stops recording the changes of the frame and its objects and drops the records.
+    ///
+    pub fn disable_journal(&self) {
+        let mut inner = trace!(self.inner.write());
+        inner.journal = None;
+        inner
+            .get_objects_mut()
+            .values_mut()
+            .for_each(|o| o.journal = None);
+    }
+
+    /// Returns the records of the frame changes, `None` when the journal is disabled. The object
+    /// changes are kept in the object journals.
+    ///
+    pub fn get_journal(&self) -> Option<Vec<JournalRecord>> {
+        let inner = trace!(self.inner.read_recursive());
+        inner.journal.as_ref().map(|j| j.get_records().to_vec())
+    }
+
+    /// Returns the records of the frame changes and clears the journal, which stays enabled.
+    ///
+    pub fn take_journal(&self) -> Option<Vec<JournalRecord>> {
+        let mut inner = trace!(self.inner.write());
+        inner.journal.as_mut().map(|j| j.take_records())
+    }
+
     pub fn get_content(&self) -> Arc<VideoFrameContent> {
         let inner = trace!(self.inner.read_recursive());
         inner.content.clone()
@@ -1026,9 +2055,33 @@ impl VideoFrameProxy {
         inner.content = Arc::new(content);
     }
 
+    /// Returns the wall-clock synchronization data of the frame. Malformed data is reported as an
+    /// error.
+    ///
+    pub fn get_clock_sync(&self) -> anyhow::Result<Option<ClockSyncInfo>> {
+        self.get_attribute(CLOCK_SYNC_ATTRIBUTE_NAMESPACE, CLOCK_SYNC_ATTRIBUTE_NAME)
+            .map(|a| ClockSyncInfo::from_attribute_values(&a.values))
+            .transpose()
+    }
+
+    pub fn set_clock_sync(&mut self, clock_sync: Option<&ClockSyncInfo>) {
+        match clock_sync {
+            Some(info) => self.set_persistent_attribute(
+                CLOCK_SYNC_ATTRIBUTE_NAMESPACE,
+                CLOCK_SYNC_ATTRIBUTE_NAME,
+                &None,
+                true,
+                info.to_attribute_values(),
+            ),
+            None => {
+                self.delete_attribute(CLOCK_SYNC_ATTRIBUTE_NAMESPACE, CLOCK_SYNC_ATTRIBUTE_NAME);
+            }
+        }
+    }
+
     pub fn clear_objects(&self) {
         let mut frame = trace!(self.inner.write());
-        frame.objects.clear();
+        frame.objects = FrameObjects::default();
     }
 
     // pub fn check_frame_fit(
@@ -1063,6 +2116,10 @@ impl VideoFrameProxy {
 mod tests {
     use crate::draw::DrawLabelKind;
     use crate::match_query::{eq, one_of, MatchQuery};
+    use crate::primitives::frame::{
+        CropObjectPolicy, ExternalFrame, ExternalFrameDescriptor, ExternalMemoryType,
+        VideoFrameContent, VideoFrameTransformation,
+    };
     use crate::primitives::object::private::{SealedWithFrame, SealedWithParent};
     use crate::primitives::object::{
         IdCollisionResolutionPolicy, ObjectOperations, VideoObjectBuilder,
@@ -1275,6 +2332,150 @@ mod tests {
         assert!(removed.get_parent().is_none());
     }
 
+    #[test]
+    fn test_internal_content_sharing() {
+        let mut f = gen_frame();
+        f.set_content(VideoFrameContent::internal(vec![0, 1, 2, 3, 4]));
+        let copy = f.smart_copy();
+        let content = f.get_content();
+        let copy_content = copy.get_content();
+        let (VideoFrameContent::Internal(a), VideoFrameContent::Internal(b)) =
+            (content.as_ref(), copy_content.as_ref())
+        else {
+            panic!("Content must be internal");
+        };
+        assert!(Arc::ptr_eq(a, b));
+
+        let slice = content.slice_internal(1..4).unwrap();
+        assert_eq!(&*slice, &[1, 2, 3]);
+        assert_eq!(&*slice.slice(1..3).unwrap(), &[2, 3]);
+        assert!(slice.slice(2..4).is_none());
+        assert!(content.slice_internal(3..6).is_none());
+
+        let mut modified = copy_content.as_ref().clone();
+        modified.get_internal_data_mut().unwrap()[0] = 10;
+        assert_eq!(modified.get_internal_data().unwrap()[0], 10);
+        assert_eq!(content.get_internal_data().unwrap()[0], 0);
+        assert_eq!(&*slice, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_map_box_to_source() -> anyhow::Result<()> {
+        let mut frame = gen_empty_frame();
+        frame.clear_transformations();
+        assert!(frame
+            .add_transformation(VideoFrameTransformation::Scale(0, 540))
+            .is_err());
+        frame.add_transformation(VideoFrameTransformation::Scale(960, 540))?;
+        assert!(frame
+            .map_box_to_source(&RBBox::new(0.0, 0.0, 1.0, 1.0, None))
+            .is_err());
+
+        frame.clear_transformations();
+        frame.add_transformation(VideoFrameTransformation::InitialSize(1920, 1080))?;
+        frame.add_transformation(VideoFrameTransformation::Scale(960, 540))?;
+        frame.add_transformation(VideoFrameTransformation::Padding(10, 20, 10, 20))?;
+        frame.add_transformation(VideoFrameTransformation::ResultingSize(980, 580))?;
+        let bbox = RBBox::new(490.0, 290.0, 100.0, 50.0, None);
+        let source = frame.map_box_to_source(&bbox)?;
+        assert_eq!(
+            (
+                source.get_xc(),
+                source.get_yc(),
+                source.get_width(),
+                source.get_height()
+            ),
+            (960.0, 540.0, 200.0, 100.0)
+        );
+        assert!(frame.map_box_from_source(&source)?.geometric_eq(&bbox));
+        // the box is copied
+        assert_eq!(bbox.get_xc(), 490.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_crop() {
+        let f = gen_frame();
+        let boxes = [
+            RBBox::ltrb(150.0, 150.0, 200.0, 220.0),
+            RBBox::ltrb(50.0, 150.0, 150.0, 200.0),
+            RBBox::ltrb(500.0, 500.0, 600.0, 600.0),
+        ];
+        for (id, b) in boxes.into_iter().enumerate() {
+            f.get_object(id as i64).unwrap().set_detection_box(b);
+        }
+        f.get_object(0)
+            .unwrap()
+            .set_track_info(1, RBBox::ltrb(140.0, 140.0, 190.0, 210.0));
+
+        let roi = RBBox::ltrb(100.0, 100.0, 400.0, 300.0);
+        let cropped = f.crop(&roi, CropObjectPolicy::Clip).unwrap();
+        assert_eq!((cropped.get_width(), cropped.get_height()), (300, 200));
+        assert_eq!(
+            cropped.get_transformations().last(),
+            Some(&VideoFrameTransformation::Crop(100, 100, 300, 200))
+        );
+        let source = cropped
+            .map_box_to_source(&RBBox::ltrb(50.0, 50.0, 100.0, 120.0))
+            .unwrap();
+        assert_eq!(source.as_ltrb().unwrap(), (150.0, 150.0, 200.0, 220.0));
+        let mut ids = cropped
+            .get_all_objects()
+            .iter()
+            .map(|o| o.get_id())
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec![0, 1]);
+        let o = cropped.get_object(0).unwrap();
+        assert_eq!(
+            o.get_detection_box().as_ltrb().unwrap(),
+            (50.0, 50.0, 100.0, 120.0)
+        );
+        assert_eq!(
+            o.get_track_box().unwrap().as_ltrb().unwrap(),
+            (40.0, 40.0, 90.0, 110.0)
+        );
+        let o = cropped.get_object(1).unwrap();
+        assert_eq!(
+            o.get_detection_box().as_ltrb().unwrap(),
+            (0.0, 50.0, 50.0, 100.0)
+        );
+        assert_eq!(o.get_parent_id(), Some(0));
+        // the source frame is not modified
+        assert_eq!(
+            f.get_object(0)
+                .unwrap()
+                .get_detection_box()
+                .as_ltrb()
+                .unwrap(),
+            (150.0, 150.0, 200.0, 220.0)
+        );
+
+        let cropped = f.crop(&roi, CropObjectPolicy::Drop).unwrap();
+        assert_eq!(cropped.get_object_count(), 1);
+
+        // clamped by the frame
+        let cropped = f
+            .crop(
+                &RBBox::ltrb(1000.0, 600.0, 1500.0, 800.0),
+                CropObjectPolicy::Clip,
+            )
+            .unwrap();
+        assert_eq!((cropped.get_width(), cropped.get_height()), (280, 120));
+        assert!(f
+            .crop(
+                &RBBox::ltrb(1300.0, 0.0, 1500.0, 100.0),
+                CropObjectPolicy::Clip
+            )
+            .is_err());
+        assert!(f
+            .crop(
+                &RBBox::new(200.0, 200.0, 100.0, 100.0, Some(30.0)),
+                CropObjectPolicy::Clip
+            )
+            .is_err());
+    }
+
     #[test]
     fn deep_copy() {
         let mut f = gen_frame();
@@ -1366,4 +2567,294 @@ mod tests {
         let objs = frame.get_all_objects();
         assert_eq!(objs.len(), 1);
     }
+
+    #[test]
+    fn add_objects_bulk() {
+        let frame = gen_frame();
+        let mut child = gen_object(3);
+        child.parent_id = Some(4);
+        let objs = frame
+            .add_objects(
+                vec![gen_object(4), child, gen_object(0)],
+                IdCollisionResolutionPolicy::GenerateNewId,
+            )
+            .unwrap();
+        assert_eq!(
+            objs.iter().map(|o| o.get_id()).collect::<Vec<_>>(),
+            vec![4, 3, 5]
+        );
+        assert_eq!(frame.get_max_object_id(), 5);
+        assert_eq!(frame.get_object(3).unwrap().get_parent_id(), Some(4));
+        assert!(frame.get_object(5).unwrap().get_frame().is_some());
+
+        // nothing is added when an object is rejected
+        let mut orphan = gen_object(7);
+        orphan.parent_id = Some(8);
+        assert!(frame
+            .add_objects(
+                vec![gen_object(6), orphan],
+                IdCollisionResolutionPolicy::Error
+            )
+            .is_err());
+        assert!(frame
+            .add_objects(
+                vec![gen_object(6), gen_object(6)],
+                IdCollisionResolutionPolicy::Error
+            )
+            .is_err());
+        assert!(!frame.object_exists(6));
+
+        frame
+            .add_objects(
+                vec![gen_object(6), gen_object(6)],
+                IdCollisionResolutionPolicy::Overwrite,
+            )
+            .unwrap();
+        assert_eq!(frame.get_all_objects().len(), 7);
+    }
+
+    #[test]
+    fn test_objects_snapshot() {
+        let frame = gen_frame();
+        let bbox = frame.get_object(1).unwrap().get_detection_box();
+        let snapshot = frame.objects_snapshot();
+        let ids = (0..8)
+            .map(|_| {
+                frame
+                    .add_object(gen_object(0), IdCollisionResolutionPolicy::GenerateNewId)
+                    .unwrap()
+                    .get_id()
+            })
+            .collect::<Vec<_>>();
+        frame.delete_objects_with_ids(&[0]);
+        // the snapshot is isolated from the modifications made after it was taken
+        assert_eq!(snapshot.len(), 3);
+        assert!(snapshot.contains_key(&0));
+        assert_eq!(frame.get_all_objects().len(), 10);
+        // the boxes obtained before the copy stay attached to the objects
+        bbox.set_xc(123.0);
+        assert_eq!(
+            frame.get_object(1).unwrap().get_detection_box().get_xc(),
+            123.0
+        );
+
+        let readers = (0..4)
+            .map(|_| {
+                let frame = frame.clone();
+                std::thread::spawn(move || {
+                    (0..100)
+                        .map(|_| frame.access_objects(&MatchQuery::Idle).len())
+                        .min()
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for id in ids {
+            frame
+                .get_object(id)
+                .unwrap()
+                .set_track_info(id, RBBox::new(1.0, 1.0, 1.0, 1.0, None));
+        }
+        for r in readers {
+            assert_eq!(r.join().unwrap(), 10);
+        }
+    }
+
+    #[test]
+    fn test_attribute_index() {
+        let mut frame = gen_empty_frame();
+        for ns in ["detector", "classifier"] {
+            frame.set_persistent_attribute(ns, "age", &Some("model"), false, vec![]);
+            frame.set_persistent_attribute(ns, "color", &None, false, vec![]);
+        }
+        let pairs = |v: &[(&str, &str)]| {
+            v.iter()
+                .map(|(ns, name)| (s(ns), s(name)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            frame.find_attributes_with_names(&["color", "age"]),
+            pairs(&[
+                ("detector", "age"),
+                ("detector", "color"),
+                ("classifier", "age"),
+                ("classifier", "color")
+            ])
+        );
+
+        // the index is updated in place by the setters
+        frame.set_persistent_attribute("detector", "age", &None, false, vec![]);
+        frame.set_persistent_attribute("tracker", "age", &Some("model"), false, vec![]);
+        assert_eq!(
+            frame.find_attributes_with_hints(&[&Some("model")]),
+            pairs(&[("classifier", "age"), ("tracker", "age")])
+        );
+        assert_eq!(
+            frame.find_attributes_with_hints(&[&None]),
+            pairs(&[
+                ("detector", "age"),
+                ("detector", "color"),
+                ("classifier", "color")
+            ])
+        );
+        assert!(frame.contains_attribute("tracker", "age"));
+
+        // and rebuilt after the other modifications
+        frame.delete_attribute("detector", "age");
+        assert!(!frame.contains_attribute("detector", "age"));
+        assert_eq!(frame.find_attributes_with_names(&["age"]).len(), 2);
+        frame.clear_attributes();
+        assert!(frame.find_attributes_with_names(&["age"]).is_empty());
+    }
+
+    #[test]
+    fn test_diff() -> anyhow::Result<()> {
+        let mut frame = gen_frame();
+        // the temporary values never compare equal
+        frame.delete_attribute("test", "test");
+        frame.set_persistent_attribute("meta", "kept", &None, false, vec![]);
+        frame.set_persistent_attribute("meta", "deleted", &None, false, vec![]);
+        assert!(frame.diff(&frame.smart_copy()).is_empty());
+
+        let mut other = frame.smart_copy();
+        other.delete_attribute("meta", "deleted");
+        other.set_persistent_attribute("meta", "added", &None, false, vec![]);
+        other.delete_objects_with_ids(&[2]);
+        other.get_object(1).unwrap().set_label("changed");
+        let mut added = gen_object(10);
+        added.parent_id = Some(0);
+        other.add_object(added, IdCollisionResolutionPolicy::Error)?;
+
+        let update = frame.diff(&other);
+        assert_eq!(update.get_deleted_objects(), &vec![2]);
+        assert_eq!(
+            update.get_deleted_frame_attributes(),
+            &vec![(s("meta"), s("deleted"))]
+        );
+        assert_eq!(update.get_frame_attributes().len(), 1);
+        assert_eq!(
+            update
+                .get_replaced_objects()
+                .iter()
+                .map(|o| o.get_id())
+                .collect::<Vec<_>>(),
+            vec![1, 10]
+        );
+
+        frame.update(&update)?;
+        assert!(frame.diff(&other).is_empty());
+        assert_eq!(frame.get_object(10).unwrap().get_parent_id(), Some(0));
+        assert_eq!(frame.get_max_object_id(), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_frame_descriptor() -> anyhow::Result<()> {
+        assert!(ExternalFrameDescriptor::new(
+            ExternalMemoryType::Nvmm,
+            Some(0),
+            "NV1",
+            vec![1920],
+            vec![],
+            None
+        )
+        .is_err());
+        assert!(ExternalFrameDescriptor::new(
+            ExternalMemoryType::Nvmm,
+            Some(0),
+            "NV12",
+            vec![2048, 2048],
+            vec![0],
+            None
+        )
+        .is_err());
+
+        let descriptor = ExternalFrameDescriptor::new(
+            ExternalMemoryType::DmaBuf,
+            None,
+            "NV12",
+            vec![2048, 2048],
+            vec![0, 2048 * 1080],
+            Some(42),
+        )?;
+        assert_eq!(descriptor.get_fourcc(), "NV12");
+        assert_eq!(descriptor.get_fourcc_code(), 0x3231564e);
+        assert_eq!(descriptor.get_num_planes(), 2);
+
+        let frame = ExternalFrame::new("dmabuf", &None).with_descriptor(descriptor.clone());
+        let json = serde_json::to_value(&frame)?;
+        let restored: ExternalFrameDescriptor = serde_json::from_value(json["descriptor"].clone())?;
+        assert_eq!(restored, descriptor);
+        let mut invalid = json["descriptor"].clone();
+        invalid["plane_offsets"] = serde_json::json!([0]);
+        assert!(serde_json::from_value::<ExternalFrameDescriptor>(invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clock_sync() -> anyhow::Result<()> {
+        use crate::message::Message;
+        use crate::primitives::frame::ClockSyncInfo;
+        use crate::primitives::frame_batch::VideoFrameBatch;
+        use crate::protobuf::{deserialize, serialize};
+
+        let mut frame = gen_frame();
+        assert!(frame.get_clock_sync()?.is_none());
+        let info = ClockSyncInfo::new(1_000_000, Some(-250), Some("cam-rack-1"));
+        frame.set_clock_sync(Some(&info));
+        assert_eq!(info.corrected_capture_time_ns(), 999_750);
+
+        let mut batch = VideoFrameBatch::new();
+        batch.add(1, frame.clone());
+        let m = deserialize(&serialize(&Message::video_frame_batch(&batch))?)?;
+        let restored = m.as_video_frame_batch().unwrap().get(1).unwrap();
+        assert_eq!(restored.get_clock_sync()?, Some(info));
+
+        frame.set_clock_sync(None);
+        assert!(frame.get_clock_sync()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace_context() -> anyhow::Result<()> {
+        use crate::message::Message;
+        use crate::protobuf::{deserialize, serialize};
+        use crate::telemetry::{TelemetryConfiguration, TelemetryScope};
+        use opentelemetry::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+        };
+        use opentelemetry::Context;
+
+        // the scope installs the W3C propagator
+        let _scope = TelemetryScope::new(&TelemetryConfiguration::no_op());
+        let span_context = SpanContext::new(
+            TraceId::from_hex("0af7651916cd43dd8448eb211c80319c")?,
+            SpanId::from_hex("b7ad6b7169203331")?,
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let mut frame = gen_frame();
+        assert!(frame.get_trace_context().is_none());
+        assert!(!frame.extract_trace_context().has_active_span());
+
+        frame.attach_trace_context(&Context::new().with_remote_span_context(span_context.clone()));
+        let message = deserialize(&serialize(&Message::video_frame(&frame))?)?;
+        // the message has no context of its own, the context of the frame is used
+        let extracted = message.extract_trace_context();
+        assert_eq!(
+            extracted.span().span_context().trace_id(),
+            span_context.trace_id()
+        );
+        let restored = message.as_video_frame().unwrap();
+        assert_eq!(
+            restored
+                .extract_trace_context()
+                .span()
+                .span_context()
+                .span_id(),
+            span_context.span_id()
+        );
+        Ok(())
+    }
 }
@@ -1,7 +1,10 @@
-use crate::match_query::MatchQuery;
+use crate::match_query::{MatchQuery, VideoObjectsProxyBatch};
 use crate::primitives::frame::VideoFrameProxy;
-use crate::primitives::object::BorrowedVideoObject;
+use crate::primitives::frame_update::VideoFrameUpdate;
+use crate::primitives::object::VideoObjectBBoxTransformation;
+use anyhow::bail;
 use hashbrown::HashMap;
+use rayon::prelude::*;
 
 const DEFAULT_BATCH_SIZE: usize = 64;
 
@@ -26,16 +29,51 @@ impl VideoFrameBatch {
         Self { frames }
     }
 
-    pub fn access_objects(
-        &self,
-        q: &MatchQuery,
-    ) -> hashbrown::HashMap<i64, Vec<BorrowedVideoObject>> {
-        self.frames
-            .iter()
+    fn par_frames(&self) -> Vec<(&i64, &VideoFrameProxy)> {
+        self.frames.iter().collect()
+    }
+
+    /// Runs the query against the frames in parallel.
+    ///
+    pub fn access_objects(&self, q: &MatchQuery) -> VideoObjectsProxyBatch {
+        self.par_frames()
+            .into_par_iter()
             .map(|(id, frame)| (*id, frame.access_objects(q)))
+            .collect::<Vec<_>>()
+            .into_iter()
             .collect()
     }
 
+    /// Transforms the geometry of the objects of the frames in parallel, see
+    /// [`VideoFrameProxy::transform_geometry`].
+    ///
+    pub fn transform_geometry(&self, ops: &Vec<VideoObjectBBoxTransformation>) {
+        self.par_frames()
+            .into_par_iter()
+            .for_each(|(_, frame)| frame.transform_geometry(ops));
+    }
+
+    /// Applies the updates to the frames with the corresponding ids in parallel, see
+    /// [`VideoFrameProxy::update`]. The ids are checked before any update is applied, the update
+    /// errors are reported for the lowest frame id after all the updates are run.
+    ///
+    pub fn update_all(&self, updates: &HashMap<i64, VideoFrameUpdate>) -> anyhow::Result<()> {
+        if let Some(id) = updates.keys().find(|id| !self.frames.contains_key(*id)) {
+            bail!("Frame with id {} is not in the batch", id);
+        }
+        let mut errors = updates
+            .iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .filter_map(|(id, update)| self.frames[id].update(update).err().map(|e| (*id, e)))
+            .collect::<Vec<_>>();
+        errors.sort_by_key(|(id, _)| *id);
+        match errors.into_iter().next() {
+            Some((id, e)) => Err(e.context(format!("Failed to update frame with id {}", id))),
+            None => Ok(()),
+        }
+    }
+
     pub fn delete_objects(&mut self, q: &MatchQuery) {
         self.frames.iter_mut().for_each(|(_, frame)| {
             frame.delete_objects(q);
@@ -70,3 +108,64 @@ impl VideoFrameBatch {
         &self.frames
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::attribute_value::AttributeValue;
+    use crate::primitives::object::ObjectOperations;
+    use crate::primitives::{Attribute, WithAttributes};
+    use crate::test::gen_frame;
+
+    #[test]
+    fn test_batch_operations() {
+        let mut batch = VideoFrameBatch::new();
+        batch.add(1, gen_frame());
+        batch.add(2, gen_frame());
+
+        let objects = batch.access_objects(&MatchQuery::Idle);
+        assert_eq!(objects.len(), 2);
+        let count = objects[&1].len();
+        assert_eq!(objects[&2].len(), count);
+
+        // the frames are generated alike, so the same object is found in both by its id
+        let id = objects[&1][0].get_id();
+        let object = |frame: i64| {
+            objects[&frame]
+                .iter()
+                .find(|o| o.get_id() == id)
+                .unwrap()
+                .clone()
+        };
+        let before_xc: f32 = object(1).get_detection_box().get_xc();
+        batch.transform_geometry(&vec![VideoObjectBBoxTransformation::Shift(10.0, 0.0)]);
+        let after_xc = object(1).get_detection_box().get_xc();
+        assert_eq!(after_xc, before_xc + 10.0);
+        assert_eq!(object(2).get_detection_box().get_xc(), after_xc);
+
+        let mut update = VideoFrameUpdate::default();
+        update.add_frame_attribute(Attribute::persistent(
+            "batch",
+            "updated",
+            vec![AttributeValue::integer(1, None)],
+            &None,
+            false,
+        ));
+        let mut updates = HashMap::new();
+        updates.insert(2, update.clone());
+        batch.update_all(&updates).unwrap();
+        assert!(batch
+            .get(1)
+            .unwrap()
+            .get_attribute("batch", "updated")
+            .is_none());
+        assert!(batch
+            .get(2)
+            .unwrap()
+            .get_attribute("batch", "updated")
+            .is_some());
+
+        updates.insert(3, update);
+        assert!(batch.update_all(&updates).is_err());
+    }
+}
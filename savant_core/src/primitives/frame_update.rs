@@ -1,5 +1,6 @@
-use crate::primitives::object::VideoObject;
-use crate::primitives::Attribute;
+use crate::primitives::object::{ObjectOperations, VideoObject};
+use crate::primitives::{Attribute, WithAttributes};
+use anyhow::bail;
 
 #[derive(Default, PartialEq, Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum ObjectUpdatePolicy {
@@ -16,6 +17,114 @@ pub enum AttributeUpdatePolicy {
     Error,
 }
 
+/// Defines how a field of an existing object is merged with the field of the matching object
+/// from the update.
+///
+#[derive(Default, PartialEq, Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum MergePolicy {
+    #[default]
+    KeepOriginal,
+    ReplaceWithUpdate,
+    /// The field is taken from the object with the higher confidence, the objects without
+    /// confidence lose.
+    HighestConfidence,
+    /// Applicable to the attributes only: the attributes missing in the existing object are
+    /// added from the update.
+    UnionAttributes,
+}
+
+/// The per-field merge policies applied when an object from the update matches an existing
+/// object of the same namespace: by the track id when the update object is tracked, otherwise by
+/// the object id. The matched objects are merged instead of being added, the unmatched ones are
+/// processed according to [`ObjectUpdatePolicy`].
+///
+#[derive(Default, PartialEq, Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ObjectMergePolicy {
+    /// The label of the object.
+    pub label: MergePolicy,
+    /// The detection box together with the confidence.
+    pub detection_box: MergePolicy,
+    /// The track id together with the track box.
+    pub track: MergePolicy,
+    pub attributes: MergePolicy,
+}
+
+impl ObjectMergePolicy {
+    pub fn new(
+        label: MergePolicy,
+        detection_box: MergePolicy,
+        track: MergePolicy,
+        attributes: MergePolicy,
+    ) -> anyhow::Result<Self> {
+        for (name, policy) in [
+            ("label", label),
+            ("detection_box", detection_box),
+            ("track", track),
+        ] {
+            if policy == MergePolicy::UnionAttributes {
+                bail!(
+                    "UnionAttributes policy is not applicable to the {} field",
+                    name
+                );
+            }
+        }
+        Ok(Self {
+            label,
+            detection_box,
+            track,
+            attributes,
+        })
+    }
+
+    /// Merges the object from the update into the existing object.
+    ///
+    pub fn merge<T: ObjectOperations + WithAttributes>(
+        &self,
+        original: &mut T,
+        update: &VideoObject,
+    ) {
+        let update_wins = |policy: MergePolicy| match policy {
+            MergePolicy::KeepOriginal | MergePolicy::UnionAttributes => false,
+            MergePolicy::ReplaceWithUpdate => true,
+            MergePolicy::HighestConfidence => {
+                match (original.get_confidence(), update.get_confidence()) {
+                    (Some(own), Some(other)) => other > own,
+                    (None, Some(_)) => true,
+                    _ => false,
+                }
+            }
+        };
+        let label = update_wins(self.label);
+        let detection_box = update_wins(self.detection_box);
+        let track = update_wins(self.track);
+        let attributes = update_wins(self.attributes);
+
+        if label {
+            original.set_label(&update.label);
+        }
+        if detection_box {
+            original.set_detection_box(update.get_detection_box().copy());
+            original.set_confidence(update.get_confidence());
+        }
+        if track {
+            match (update.get_track_id(), update.get_track_box()) {
+                (Some(id), Some(track_box)) => original.set_track_info(id, track_box.copy()),
+                _ => original.clear_track_info(),
+            }
+        }
+        if attributes {
+            original.clear_attributes();
+        }
+        if attributes || self.attributes == MergePolicy::UnionAttributes {
+            for attribute in &update.attributes {
+                if !original.contains_attribute(&attribute.namespace, &attribute.name) {
+                    original.set_attribute(attribute.clone());
+                }
+            }
+        }
+    }
+}
+
 /// A video frame update object is used to update state of a frame from external source.
 ///
 /// It contains a list of attributes and a list of objects.
@@ -30,6 +139,18 @@ pub struct VideoFrameUpdate {
     pub(crate) object_attribute_policy: AttributeUpdatePolicy,
     #[serde(skip)]
     pub(crate) object_policy: ObjectUpdatePolicy,
+    /// Transferred with protobuf as a hidden frame attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) object_merge_policy: Option<ObjectMergePolicy>,
+    /// Transferred with protobuf as a hidden frame attribute.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) deleted_frame_attributes: Vec<(String, String)>,
+    /// Transferred with protobuf as a hidden frame attribute.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) deleted_objects: Vec<i64>,
+    /// Transferred with protobuf as a hidden frame attribute.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) replaced_objects: Vec<VideoObject>,
 }
 
 impl Default for VideoFrameUpdate {
@@ -41,6 +162,10 @@ impl Default for VideoFrameUpdate {
             object_policy: ObjectUpdatePolicy::ErrorIfLabelsCollide,
             frame_attribute_policy: AttributeUpdatePolicy::Error,
             object_attribute_policy: AttributeUpdatePolicy::Error,
+            object_merge_policy: None,
+            deleted_frame_attributes: Vec::new(),
+            deleted_objects: Vec::new(),
+            replaced_objects: Vec::new(),
         }
     }
 }
@@ -70,6 +195,14 @@ impl VideoFrameUpdate {
         self.object_policy.clone()
     }
 
+    pub fn set_object_merge_policy(&mut self, p: Option<ObjectMergePolicy>) {
+        self.object_merge_policy = p;
+    }
+
+    pub fn get_object_merge_policy(&self) -> Option<ObjectMergePolicy> {
+        self.object_merge_policy.clone()
+    }
+
     pub fn set_object_attribute_policy(&mut self, p: AttributeUpdatePolicy) {
         self.object_attribute_policy = p;
     }
@@ -94,6 +227,48 @@ impl VideoFrameUpdate {
         &self.objects
     }
 
+    /// Requests the deletion of the frame attribute, the deletions are applied first.
+    ///
+    pub fn add_deleted_frame_attribute(&mut self, namespace: &str, name: &str) {
+        self.deleted_frame_attributes
+            .push((namespace.to_string(), name.to_string()));
+    }
+
+    pub fn get_deleted_frame_attributes(&self) -> &Vec<(String, String)> {
+        &self.deleted_frame_attributes
+    }
+
+    /// Requests the deletion of the object, the deletions are applied first.
+    ///
+    pub fn add_deleted_object(&mut self, object_id: i64) {
+        self.deleted_objects.push(object_id);
+    }
+
+    pub fn get_deleted_objects(&self) -> &Vec<i64> {
+        &self.deleted_objects
+    }
+
+    /// Adds the object which is placed into the frame with its id, replacing the existing
+    /// object with the same id. Unlike [`VideoFrameUpdate::add_object`], the id and the parent
+    /// of the object are kept.
+    ///
+    pub fn add_replaced_object(&mut self, object: VideoObject) {
+        self.replaced_objects.push(object);
+    }
+
+    pub fn get_replaced_objects(&self) -> &Vec<VideoObject> {
+        &self.replaced_objects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_attributes.is_empty()
+            && self.object_attributes.is_empty()
+            && self.objects.is_empty()
+            && self.deleted_frame_attributes.is_empty()
+            && self.deleted_objects.is_empty()
+            && self.replaced_objects.is_empty()
+    }
+
     pub fn to_json(&self, pretty: bool) -> anyhow::Result<String> {
         Ok(if pretty {
             serde_json::to_string_pretty(self)?
@@ -112,12 +287,12 @@ mod tests {
     use crate::match_query::{IntExpression, MatchQuery};
     use crate::primitives::attribute_value::{AttributeValue, AttributeValueVariant};
     use crate::primitives::frame_update::{
-        AttributeUpdatePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
+        AttributeUpdatePolicy, MergePolicy, ObjectMergePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
     };
     use crate::primitives::object::private::SealedWithParent;
-    use crate::primitives::object::ObjectOperations;
-    use crate::primitives::{Attribute, WithAttributes};
-    use crate::test::{gen_frame, gen_object, s};
+    use crate::primitives::object::{IdCollisionResolutionPolicy, ObjectOperations};
+    use crate::primitives::{Attribute, RBBox, WithAttributes};
+    use crate::test::{gen_empty_frame, gen_frame, gen_object, s};
 
     fn get_attributes() -> (Attribute, Attribute) {
         (
@@ -322,6 +497,53 @@ mod tests {
         assert_eq!(f.get_all_objects().len(), 4);
     }
 
+    #[test]
+    fn test_update_merge_objects() {
+        let f = gen_empty_frame();
+        f.add_object(gen_object(1), IdCollisionResolutionPolicy::Error)
+            .unwrap();
+
+        let mut tracked = gen_object(10);
+        tracked.track_id = Some(1);
+        tracked.label = s("person");
+        tracked.confidence = Some(0.9);
+        tracked.detection_box = RBBox::new(5.0, 5.0, 10.0, 10.0, None);
+        tracked.set_persistent_attribute("other", "attribute", &None, false, vec![]);
+        let mut untracked = gen_object(20);
+        untracked.track_id = None;
+
+        let mut upd = VideoFrameUpdate::default();
+        upd.add_object(tracked, None);
+        upd.add_object(untracked, None);
+        upd.set_object_policy(ObjectUpdatePolicy::AddForeignObjects);
+        assert!(ObjectMergePolicy::new(
+            MergePolicy::UnionAttributes,
+            MergePolicy::KeepOriginal,
+            MergePolicy::KeepOriginal,
+            MergePolicy::KeepOriginal,
+        )
+        .is_err());
+        upd.set_object_merge_policy(Some(
+            ObjectMergePolicy::new(
+                MergePolicy::KeepOriginal,
+                MergePolicy::HighestConfidence,
+                MergePolicy::ReplaceWithUpdate,
+                MergePolicy::UnionAttributes,
+            )
+            .unwrap(),
+        ));
+        f.update_objects(&upd).unwrap();
+
+        assert_eq!(f.get_all_objects().len(), 2);
+        let o = f.get_object(1).unwrap();
+        assert_eq!(o.get_label(), s("face"));
+        assert_eq!(o.get_confidence(), Some(0.9));
+        assert_eq!(o.get_detection_box().get_xc(), 5.0);
+        assert_eq!(o.get_track_id(), Some(1));
+        assert!(o.contains_attribute("some", "attribute"));
+        assert!(o.contains_attribute("other", "attribute"));
+    }
+
     #[test]
     fn update_objects_with_parent() {
         let f = gen_frame();
@@ -336,4 +558,22 @@ mod tests {
         let o = f.access_objects(&MatchQuery::ParentId(IntExpression::EQ(1)));
         assert_eq!(o[0].get_parent().unwrap().get_id(), 1);
     }
+
+    #[test]
+    fn test_serde_deletions_and_replaced_objects() {
+        let mut upd = VideoFrameUpdate::default();
+        upd.add_deleted_frame_attribute("meta", "deleted");
+        upd.add_deleted_object(2);
+        upd.add_replaced_object(gen_object(1));
+        let json = upd.to_json(false).unwrap();
+        let restored: VideoFrameUpdate = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.get_deleted_frame_attributes(),
+            &vec![(s("meta"), s("deleted"))]
+        );
+        assert_eq!(restored.get_deleted_objects(), &vec![2]);
+        assert_eq!(restored.get_replaced_objects().len(), 1);
+        assert_eq!(restored.get_replaced_objects()[0].get_id(), 1);
+        assert_eq!(json, restored.to_json(false).unwrap());
+    }
 }
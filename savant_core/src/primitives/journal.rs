@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    static CURRENT_STAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Sets the stage the changes made by the current thread are attributed to in the journals.
+///
+pub fn set_current_stage(stage: Option<&str>) {
+    CURRENT_STAGE.with(|s| *s.borrow_mut() = stage.map(String::from));
+}
+
+pub fn get_current_stage() -> Option<String> {
+    CURRENT_STAGE.with(|s| s.borrow().clone())
+}
+
+/// A single change of a frame or object field.
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    /// The stage set with [`set_current_stage`] by the thread which made the change.
+    pub stage: Option<String>,
+    /// Nanoseconds since the UNIX epoch.
+    pub timestamp_ns: u64,
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// The opt-in log of the changes made to a frame or an object through their setters. The
+/// in-place modifications of the shared bounding boxes are not recorded.
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeJournal {
+    records: Vec<JournalRecord>,
+}
+
+impl ChangeJournal {
+    pub fn record(&mut self, field: &str, old: Value, new: Value) {
+        self.records.push(JournalRecord {
+            stage: get_current_stage(),
+            timestamp_ns: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64,
+            field: field.to_string(),
+            old,
+            new,
+        });
+    }
+
+    pub fn get_records(&self) -> &[JournalRecord] {
+        &self.records
+    }
+
+    pub fn take_records(&mut self) -> Vec<JournalRecord> {
+        std::mem::take(&mut self.records)
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+pub(crate) trait Journaled {
+    fn journal_mut(&mut self) -> &mut Option<ChangeJournal>;
+
+    /// Records the change if the journal is enabled and the value differs, the values are
+    /// evaluated only in that case.
+    ///
+    fn record_change<F>(&mut self, field: &str, values: F)
+    where
+        Self: Sized,
+        F: FnOnce(&Self) -> (Value, Value),
+    {
+        if self.journal_mut().is_none() {
+            return;
+        }
+        let (old, new) = values(self);
+        if old != new {
+            if let Some(journal) = self.journal_mut() {
+                journal.record(field, old, new);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::object::ObjectOperations;
+    use crate::primitives::RBBox;
+    use crate::test::gen_frame;
+
+    #[test]
+    fn test_journal() {
+        let mut frame = gen_frame();
+        assert!(frame.get_journal().is_none());
+        frame.enable_journal();
+        set_current_stage(Some("detector"));
+        frame.set_width(1920);
+        frame.set_width(1920);
+        let mut o = frame.get_object(1).unwrap();
+        o.set_label("car");
+        set_current_stage(None);
+        o.set_detection_box(RBBox::new(1.0, 2.0, 3.0, 4.0, None));
+
+        let records = frame.get_journal().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].field, "width");
+        assert_eq!(records[0].stage.as_deref(), Some("detector"));
+        assert_eq!(records[0].old, serde_json::json!(1280));
+        assert_eq!(records[0].new, serde_json::json!(1920));
+
+        let records = o.take_journal().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].field, "label");
+        assert_eq!(records[0].new, serde_json::json!("car"));
+        assert_eq!(records[1].field, "detection_box");
+        assert!(records[1].stage.is_none());
+        assert_eq!(o.get_journal(), Some(Vec::new()));
+
+        let o = frame.get_object(2).unwrap();
+        assert_eq!(o.get_journal(), Some(Vec::new()));
+        frame.disable_journal();
+        assert!(frame.get_journal().is_none());
+        assert!(o.get_journal().is_none());
+    }
+}
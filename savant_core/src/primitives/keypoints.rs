@@ -0,0 +1,154 @@
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// A named point of an object skeleton, e.g. `left_eye`.
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyPoint {
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub confidence: Option<f32>,
+}
+
+impl KeyPoint {
+    pub fn new(name: &str, x: f32, y: f32, confidence: Option<f32>) -> Self {
+        Self {
+            name: name.to_string(),
+            x,
+            y,
+            confidence,
+        }
+    }
+}
+
+/// The keypoints of an object together with the skeleton connectivity, the edges reference the
+/// points by their indices.
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawKeyPoints")]
+pub struct KeyPoints {
+    points: Vec<KeyPoint>,
+    #[serde(default)]
+    edges: Vec<(usize, usize)>,
+}
+
+/// The unchecked serde form of [`KeyPoints`], the deserialized keypoints are validated by
+/// [`KeyPoints::new`].
+///
+#[derive(Deserialize)]
+struct RawKeyPoints {
+    points: Vec<KeyPoint>,
+    #[serde(default)]
+    edges: Vec<(usize, usize)>,
+}
+
+impl TryFrom<RawKeyPoints> for KeyPoints {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: RawKeyPoints) -> anyhow::Result<Self> {
+        Self::new(raw.points, raw.edges)
+    }
+}
+
+impl KeyPoints {
+    /// Creates the keypoints, the point names must be unique and the edges must reference the
+    /// existing points.
+    ///
+    pub fn new(points: Vec<KeyPoint>, edges: Vec<(usize, usize)>) -> anyhow::Result<Self> {
+        for (i, p) in points.iter().enumerate() {
+            if points[..i].iter().any(|other| other.name == p.name) {
+                bail!("Duplicate keypoint name {}", p.name);
+            }
+        }
+        if let Some((a, b)) = edges
+            .iter()
+            .find(|(a, b)| *a >= points.len() || *b >= points.len())
+        {
+            bail!(
+                "Edge ({}, {}) references a missing keypoint, there are {} keypoints",
+                a,
+                b,
+                points.len()
+            );
+        }
+        Ok(Self { points, edges })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&KeyPoint> {
+        self.points.iter().find(|p| p.name == name)
+    }
+
+    pub fn get_points(&self) -> &[KeyPoint] {
+        &self.points
+    }
+
+    pub fn get_edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// The edges as the pairs of the connected points.
+    ///
+    pub fn get_segments(&self) -> Vec<(&KeyPoint, &KeyPoint)> {
+        self.edges
+            .iter()
+            .map(|(a, b)| (&self.points[*a], &self.points[*b]))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn scale(&mut self, kx: f32, ky: f32) {
+        for p in &mut self.points {
+            p.x *= kx;
+            p.y *= ky;
+        }
+    }
+
+    pub fn shift(&mut self, dx: f32, dy: f32) {
+        for p in &mut self.points {
+            p.x += dx;
+            p.y += dy;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypoints() {
+        let points = vec![
+            KeyPoint::new("left_eye", 10.0, 10.0, Some(0.9)),
+            KeyPoint::new("right_eye", 20.0, 10.0, None),
+            KeyPoint::new("nose", 15.0, 15.0, Some(0.5)),
+        ];
+        assert!(KeyPoints::new(points.clone(), vec![(0, 3)]).is_err());
+        let mut duplicates = points.clone();
+        duplicates.push(KeyPoint::new("nose", 0.0, 0.0, None));
+        assert!(KeyPoints::new(duplicates, vec![]).is_err());
+
+        let mut kp = KeyPoints::new(points, vec![(0, 2), (1, 2)]).unwrap();
+        assert_eq!(kp.len(), 3);
+        assert_eq!(kp.get("nose").unwrap().confidence, Some(0.5));
+        assert!(kp.get("mouth").is_none());
+        assert_eq!(kp.get_segments()[1].0.name, "right_eye");
+
+        kp.scale(2.0, 1.0);
+        kp.shift(1.0, -1.0);
+        assert_eq!(kp.get("right_eye").map(|p| (p.x, p.y)), Some((41.0, 9.0)));
+
+        let json = serde_json::to_string(&kp).unwrap();
+        assert_eq!(serde_json::from_str::<KeyPoints>(&json).unwrap(), kp);
+        let invalid = json.replace("[1,2]", "[1,5]");
+        assert_ne!(invalid, json);
+        assert!(serde_json::from_str::<KeyPoints>(&invalid).is_err());
+    }
+}
@@ -1,12 +1,15 @@
 use anyhow::bail;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::fmt::Debug;
 
 use crate::json_api::ToSerdeJsonValue;
 use crate::primitives::frame::{BelongingVideoFrame, VideoFrameProxy};
+use crate::primitives::journal::{ChangeJournal, JournalRecord, Journaled};
+use crate::primitives::keypoints::KeyPoints;
 use crate::primitives::object::private::{
     SealedObjectOperations, SealedWithFrame, SealedWithParent,
 };
+use crate::primitives::track_history::TrackHistory;
 use crate::primitives::{Attribute, RBBox, WithAttributes};
 
 use super::bbox::BBOX_UNDEFINED;
@@ -53,6 +56,18 @@ pub struct VideoObject {
     pub(crate) namespace_id: Option<i64>,
     #[builder(default)]
     pub(crate) label_id: Option<i64>,
+    /// The recent boxes of the track, not transferred with protobuf.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) track_history: Option<TrackHistory>,
+    /// The skeleton keypoints, transferred with protobuf as the hidden attribute.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) keypoints: Option<KeyPoints>,
+    /// The change journal, not transferred with protobuf.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) journal: Option<ChangeJournal>,
     #[builder(default)]
     #[serde(skip_deserializing, skip_serializing)]
     pub(crate) frame: Option<BelongingVideoFrame>,
@@ -73,6 +88,9 @@ impl Clone for VideoObject {
             track_box: self.track_box.as_ref().map(|tb| tb.copy()),
             namespace_id: self.namespace_id,
             label_id: self.label_id,
+            track_history: self.track_history.clone(),
+            keypoints: self.keypoints.clone(),
+            journal: self.journal.clone(),
             frame: self.frame.clone(),
         }
     }
@@ -95,11 +113,20 @@ impl Default for VideoObject {
             track_box: None,
             namespace_id: None,
             label_id: None,
+            track_history: None,
+            keypoints: None,
+            journal: None,
             frame: None,
         }
     }
 }
 
+impl Journaled for VideoObject {
+    fn journal_mut(&mut self) -> &mut Option<ChangeJournal> {
+        &mut self.journal
+    }
+}
+
 impl VideoObject {
     pub fn set_id(&mut self, id: i64) -> anyhow::Result<()> {
         if self.get_frame().is_some() {
@@ -108,6 +135,47 @@ impl VideoObject {
         self.with_object_mut(|o| o.id = id);
         Ok(())
     }
+
+    /// Compares the object data ignoring the track history, the journal and the frame.
+    ///
+    pub(crate) fn content_eq(&self, other: &VideoObject) -> bool {
+        self.id == other.id
+            && self.namespace == other.namespace
+            && self.label == other.label
+            && self.draw_label == other.draw_label
+            && self.detection_box == other.detection_box
+            && self.attributes == other.attributes
+            && self.confidence == other.confidence
+            && self.parent_id == other.parent_id
+            && self.track_id == other.track_id
+            && self.track_box == other.track_box
+            && self.namespace_id == other.namespace_id
+            && self.label_id == other.label_id
+            && self.keypoints == other.keypoints
+    }
+
+    /// Clones the object sharing the bounding boxes with the original one.
+    ///
+    pub(crate) fn shared_copy(&self) -> Self {
+        Self {
+            id: self.id,
+            namespace: self.namespace.clone(),
+            label: self.label.clone(),
+            draw_label: self.draw_label.clone(),
+            detection_box: self.detection_box.clone(),
+            attributes: self.attributes.clone(),
+            confidence: self.confidence,
+            parent_id: self.parent_id,
+            track_id: self.track_id,
+            track_box: self.track_box.clone(),
+            namespace_id: self.namespace_id,
+            label_id: self.label_id,
+            track_history: self.track_history.clone(),
+            keypoints: self.keypoints.clone(),
+            journal: self.journal.clone(),
+            frame: self.frame.clone(),
+        }
+    }
 }
 
 impl ToSerdeJsonValue for VideoObject {
@@ -169,7 +237,7 @@ impl WithAttributes for BorrowedVideoObject {
         let mut frame = frame.inner.0.write();
         let uuid = frame.uuid;
         let object = frame
-            .objects
+            .get_objects_mut()
             .get_mut(&self.1)
             .unwrap_or_else(|| panic!("Object {} not found in the frame {}", self.1, uuid));
         f(&mut object.attributes)
@@ -303,12 +371,18 @@ where
                         if let Some(t) = object.get_track_box() {
                             t.scale(*kx, *ky);
                         }
+                        if let Some(k) = object.keypoints.as_mut() {
+                            k.scale(*kx, *ky);
+                        }
                     }
                     VideoObjectBBoxTransformation::Shift(dx, dy) => {
                         object.get_detection_box().shift(*dx, *dy);
                         if let Some(t) = object.get_track_box() {
                             t.shift(*dx, *dy);
                         }
+                        if let Some(k) = object.keypoints.as_mut() {
+                            k.shift(*dx, *dy);
+                        }
                     }
                 }
             }
@@ -319,7 +393,10 @@ where
     }
 
     fn set_track_id(&mut self, track_id: Option<i64>) {
-        self.with_object_mut(|o| o.track_id = track_id);
+        self.with_object_mut(|o| {
+            o.record_change("track_id", |o| (json!(o.track_id), json!(track_id)));
+            o.track_id = track_id
+        });
     }
 
     fn get_detection_box(&self) -> RBBox {
@@ -334,6 +411,22 @@ where
         self.with_object_ref(|o| o.confidence)
     }
 
+    fn get_track_history(&self) -> Option<TrackHistory> {
+        self.with_object_ref(|o| o.track_history.clone())
+    }
+
+    fn set_track_history(&mut self, history: Option<TrackHistory>) {
+        self.with_object_mut(|o| o.track_history = history);
+    }
+
+    fn get_keypoints(&self) -> Option<KeyPoints> {
+        self.with_object_ref(|o| o.keypoints.clone())
+    }
+
+    fn set_keypoints(&mut self, keypoints: Option<KeyPoints>) {
+        self.with_object_mut(|o| o.keypoints = keypoints);
+    }
+
     fn get_namespace(&self) -> String {
         self.with_object_ref(|o| o.namespace.clone())
     }
@@ -365,41 +458,91 @@ where
     }
 
     fn set_detection_box(&mut self, bbox: RBBox) {
-        self.with_object_mut(|o| o.detection_box = bbox);
+        self.with_object_mut(|o| {
+            o.record_change("detection_box", |o| (json!(o.detection_box), json!(bbox)));
+            o.detection_box = bbox
+        });
     }
 
     fn set_track_info(&mut self, track_id: i64, bbox: RBBox) {
         self.with_object_mut(|o| {
+            o.record_change("track_id", |o| (json!(o.track_id), json!(track_id)));
+            o.record_change("track_box", |o| (json!(o.track_box), json!(bbox)));
             o.track_box = Some(bbox);
             o.track_id = Some(track_id);
         });
     }
 
     fn set_track_box(&mut self, bbox: RBBox) {
-        self.with_object_mut(|o| o.track_box = Some(bbox));
+        self.with_object_mut(|o| {
+            o.record_change("track_box", |o| (json!(o.track_box), json!(bbox)));
+            o.track_box = Some(bbox)
+        });
     }
 
     fn clear_track_info(&mut self) {
         self.with_object_mut(|o| {
+            o.record_change("track_id", |o| (json!(o.track_id), Value::Null));
+            o.record_change("track_box", |o| (json!(o.track_box), Value::Null));
             o.track_box = None;
             o.track_id = None;
+            o.track_history = None;
         });
     }
 
     fn set_draw_label(&mut self, draw_label: Option<String>) {
-        self.with_object_mut(|o| o.draw_label = draw_label);
+        self.with_object_mut(|o| {
+            o.record_change("draw_label", |o| (json!(o.draw_label), json!(draw_label)));
+            o.draw_label = draw_label
+        });
     }
 
     fn set_namespace(&mut self, namespace: &str) {
-        self.with_object_mut(|o| o.namespace = namespace.to_string());
+        self.with_object_mut(|o| {
+            o.record_change("namespace", |o| (json!(o.namespace), json!(namespace)));
+            o.namespace = namespace.to_string()
+        });
     }
 
     fn set_label(&mut self, label: &str) {
-        self.with_object_mut(|o| o.label = label.to_string());
+        self.with_object_mut(|o| {
+            o.record_change("label", |o| (json!(o.label), json!(label)));
+            o.label = label.to_string()
+        });
     }
 
     fn set_confidence(&mut self, confidence: Option<f32>) {
-        self.with_object_mut(|o| o.confidence = confidence);
+        self.with_object_mut(|o| {
+            o.record_change("confidence", |o| (json!(o.confidence), json!(confidence)));
+            o.confidence = confidence
+        });
+    }
+
+    /// Starts recording the changes made through the setters, keeps the records if the journal
+    /// is already enabled.
+    ///
+    fn enable_journal(&mut self) {
+        self.with_object_mut(|o| {
+            o.journal.get_or_insert_with(ChangeJournal::default);
+        });
+    }
+
+    /// Stops recording the changes and drops the records.
+    ///
+    fn disable_journal(&mut self) {
+        self.with_object_mut(|o| o.journal = None);
+    }
+
+    /// Returns the records, `None` when the journal is disabled.
+    ///
+    fn get_journal(&self) -> Option<Vec<JournalRecord>> {
+        self.with_object_ref(|o| o.journal.as_ref().map(|j| j.get_records().to_vec()))
+    }
+
+    /// Returns the records and clears the journal, which stays enabled.
+    ///
+    fn take_journal(&mut self) -> Option<Vec<JournalRecord>> {
+        self.with_object_mut(|o| o.journal.as_mut().map(|j| j.take_records()))
     }
 
     fn detached_copy(&self) -> VideoObject {
@@ -453,7 +596,7 @@ impl ObjectAccess for BorrowedVideoObject {
         let mut frame = frame.inner.0.write();
         let uuid = frame.uuid;
         let object = frame
-            .objects
+            .get_objects_mut()
             .get_mut(&self.1)
             .unwrap_or_else(|| panic!("Object {} not found in the frame {}", self.1, uuid));
         f(object)
@@ -1,8 +1,13 @@
 use crate::primitives::point::Point;
-use crate::primitives::{Intersection, IntersectionKind, Segment};
+use crate::primitives::{Intersection, IntersectionKind, RBBox, Segment};
 use anyhow::bail;
 use geo::line_intersection::line_intersection;
-use geo::{Contains, EuclideanDistance, Line, LineIntersection, LineString};
+use geo::{
+    Area, BooleanOps, Centroid, Contains, EuclideanDistance, Line, LineIntersection, LineString,
+    MultiPolygon, Simplify,
+};
+use rayon::prelude::*;
+use std::borrow::Cow;
 
 #[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct PolygonalArea {
@@ -63,11 +68,14 @@ impl PolygonalArea {
 
     pub fn crossed_by_segment(&mut self, seg: &Segment) -> Intersection {
         self.build_polygon();
+        self.crossing(self.polygon.as_ref().unwrap(), seg)
+    }
+
+    fn crossing(&self, poly: &geo::Polygon, seg: &Segment) -> Intersection {
         let seg = Line::from([
             (seg.begin.x as f64, seg.begin.y as f64),
             (seg.end.x as f64, seg.end.y as f64),
         ]);
-        let poly = self.polygon.as_ref().unwrap();
 
         let mut intersections = poly
             .exterior()
@@ -118,6 +126,36 @@ impl PolygonalArea {
             .contains(&geo::Point::from((p.x as f64, p.y as f64)))
     }
 
+    /// Tests the points against the area in parallel, the same as [`Self::contains`] per point.
+    ///
+    pub fn contains_many(&self, points: &[Point]) -> Vec<bool> {
+        let polygon = self.polygon();
+        points
+            .par_iter()
+            .map(|p| polygon.contains(&geo::Point::from((p.x as f64, p.y as f64))))
+            .collect()
+    }
+
+    /// Tests the segments against the area in parallel, the same as [`Self::crossed_by_segment`]
+    /// per segment.
+    ///
+    pub fn crossed_by_many(&self, segments: &[Segment]) -> Vec<Intersection> {
+        let polygon = self.polygon();
+        segments
+            .par_iter()
+            .map(|s| self.crossing(&polygon, s))
+            .collect()
+    }
+
+    /// Returns the cached polygon or builds it without caching when the area is immutable.
+    ///
+    pub(crate) fn polygon(&self) -> Cow<'_, geo::Polygon> {
+        match &self.polygon {
+            Some(p) => Cow::Borrowed(p),
+            None => Cow::Owned(Self::gen_polygon(&self.vertices)),
+        }
+    }
+
     pub fn build_polygon(&mut self) {
         let p = self
             .polygon
@@ -125,6 +163,124 @@ impl PolygonalArea {
             .unwrap_or_else(|| Self::gen_polygon(&self.vertices));
         self.polygon.replace(p);
     }
+
+    pub fn area(&self) -> f64 {
+        self.polygon().unsigned_area()
+    }
+
+    /// The center of mass of the area, `None` for the degenerate areas.
+    ///
+    pub fn centroid(&self) -> Option<Point> {
+        self.polygon()
+            .centroid()
+            .map(|c| Point::new(c.x() as f32, c.y() as f32))
+    }
+
+    /// Whether the box lies within the area entirely.
+    ///
+    pub fn contains_bbox(&self, bbox: &RBBox) -> bool {
+        let corners = bbox.get_as_polygonal_area().get_polygon();
+        self.polygon().contains(&corners)
+    }
+
+    /// The union of the areas, the separate parts of the result are returned as separate areas.
+    /// The holes and the edge tags are not kept.
+    ///
+    pub fn union(&self, other: &PolygonalArea) -> Vec<PolygonalArea> {
+        Self::from_multi_polygon(self.polygon().union(&*other.polygon()))
+    }
+
+    /// The intersection of the areas, the separate parts of the result are returned as separate
+    /// areas. The holes and the edge tags are not kept.
+    ///
+    pub fn intersection(&self, other: &PolygonalArea) -> Vec<PolygonalArea> {
+        Self::from_multi_polygon(self.polygon().intersection(&*other.polygon()))
+    }
+
+    /// Grows the area by the distance or shrinks it when the distance is negative. The corners
+    /// are rounded with arcs of `segments` segments per full circle. The separate parts of the
+    /// result are returned as separate areas, the holes and the edge tags are not kept.
+    ///
+    pub fn buffer(&self, distance: f32, segments: usize) -> Vec<PolygonalArea> {
+        let polygon = MultiPolygon::new(vec![self.polygon().into_owned()]);
+        if distance == 0.0 {
+            return Self::from_multi_polygon(polygon);
+        }
+        let band = Self::boundary_band(&polygon.0[0], distance.abs() as f64, segments.max(3));
+        Self::from_multi_polygon(if distance > 0.0 {
+            polygon.union(&band)
+        } else {
+            polygon.difference(&band)
+        })
+    }
+
+    /// Simplifies the area with the Ramer-Douglas-Peucker algorithm, removing the vertices
+    /// closer than `epsilon` to the simplified edges. The edge tags are not kept.
+    ///
+    pub fn simplify(&self, epsilon: f32) -> PolygonalArea {
+        let simplified = self.polygon().simplify(&(epsilon as f64));
+        Self::from_multi_polygon(MultiPolygon::new(vec![simplified]))
+            .pop()
+            .unwrap_or_default()
+    }
+
+    /// The points within the distance from the boundary of the polygon.
+    ///
+    fn boundary_band(polygon: &geo::Polygon, distance: f64, segments: usize) -> MultiPolygon {
+        let mut band = MultiPolygon::new(vec![]);
+        for line in polygon.exterior().lines() {
+            let length = line.dx().hypot(line.dy());
+            if length == 0.0 {
+                continue;
+            }
+            let (nx, ny) = (
+                -line.dy() / length * distance,
+                line.dx() / length * distance,
+            );
+            let (s, e) = (line.start, line.end);
+            let rectangle = geo::Polygon::new(
+                LineString::from(vec![
+                    (s.x + nx, s.y + ny),
+                    (e.x + nx, e.y + ny),
+                    (e.x - nx, e.y - ny),
+                    (s.x - nx, s.y - ny),
+                ]),
+                vec![],
+            );
+            band = band.union(&MultiPolygon::new(vec![rectangle]));
+        }
+        for c in polygon.exterior().coords() {
+            let circle = geo::Polygon::new(
+                LineString::from(
+                    (0..segments)
+                        .map(|i| {
+                            let a = std::f64::consts::TAU * i as f64 / segments as f64;
+                            (c.x + distance * a.cos(), c.y + distance * a.sin())
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                vec![],
+            );
+            band = band.union(&MultiPolygon::new(vec![circle]));
+        }
+        band
+    }
+
+    fn from_multi_polygon(polygons: MultiPolygon) -> Vec<PolygonalArea> {
+        polygons
+            .into_iter()
+            .filter(|p| p.unsigned_area() > crate::EPS as f64)
+            .map(|p| {
+                let coords = &p.exterior().0;
+                // the ring is closed, the last point repeats the first one
+                let vertices = coords[..coords.len().saturating_sub(1)]
+                    .iter()
+                    .map(|c| Point::new(c.x as f32, c.y as f32))
+                    .collect();
+                PolygonalArea::new(vertices, None)
+            })
+            .collect()
+    }
 }
 
 // class methods
@@ -199,7 +355,7 @@ impl PolygonalArea {
 mod tests {
     use super::PolygonalArea;
     use crate::primitives::point::Point;
-    use crate::primitives::{Intersection, IntersectionKind, Segment};
+    use crate::primitives::{Intersection, IntersectionKind, RBBox, Segment};
 
     const UPPER: &str = "upper";
     const RIGHT: &str = "right";
@@ -412,6 +568,131 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_many() {
+        let mut area = PolygonalArea::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(0.0, 4.0),
+            ],
+            Some(vec![
+                Some(UPPER.to_string()),
+                Some(RIGHT.to_string()),
+                Some(LOWER.to_string()),
+                Some(LEFT.to_string()),
+            ]),
+        );
+        let points = (0..100)
+            .map(|i| Point::new(i as f32 / 10.0, 2.0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            area.contains_many(&points),
+            area.contains_many_points(&points)
+        );
+        assert_eq!(
+            area.contains_many(&points).iter().filter(|c| **c).count(),
+            39
+        );
+
+        let segments = (0..100)
+            .map(|i| {
+                Segment::new(
+                    Point::new(-1.0, i as f32 / 10.0),
+                    Point::new(i as f32 / 10.0, 2.0),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            area.crossed_by_many(&segments),
+            area.crossed_by_segments(&segments)
+        );
+    }
+
+    #[test]
+    fn test_measures() {
+        let area = PolygonalArea::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 2.0),
+                Point::new(0.0, 2.0),
+            ],
+            None,
+        );
+        assert_eq!(area.area(), 8.0);
+        assert_eq!(area.centroid(), Some(Point::new(2.0, 1.0)));
+        assert!(area.contains_bbox(&RBBox::ltrb(1.0, 0.5, 3.0, 1.5)));
+        assert!(!area.contains_bbox(&RBBox::ltrb(3.0, 0.5, 5.0, 1.5)));
+    }
+
+    #[test]
+    fn test_boolean_operations() {
+        let square = |x: f32, y: f32| {
+            PolygonalArea::new(
+                vec![
+                    Point::new(x, y),
+                    Point::new(x + 2.0, y),
+                    Point::new(x + 2.0, y + 2.0),
+                    Point::new(x, y + 2.0),
+                ],
+                None,
+            )
+        };
+        let union = square(0.0, 0.0).union(&square(1.0, 1.0));
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].area(), 7.0);
+        assert_eq!(square(0.0, 0.0).union(&square(5.0, 5.0)).len(), 2);
+
+        let intersection = square(0.0, 0.0).intersection(&square(1.0, 1.0));
+        assert_eq!(intersection.len(), 1);
+        assert_eq!(intersection[0].area(), 1.0);
+        assert!(square(0.0, 0.0).intersection(&square(5.0, 5.0)).is_empty());
+    }
+
+    #[test]
+    fn test_buffer() {
+        let area = PolygonalArea::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(0.0, 4.0),
+            ],
+            None,
+        );
+        let grown = area.buffer(1.0, 64);
+        assert_eq!(grown.len(), 1);
+        // the square, four side rectangles and a circle from the four rounded corners
+        let expected = 16.0 + 16.0 + std::f64::consts::PI;
+        assert!((grown[0].area() - expected).abs() < 0.05);
+
+        let shrunk = area.buffer(-1.0, 64);
+        assert_eq!(shrunk.len(), 1);
+        assert!((shrunk[0].area() - 4.0).abs() < 1e-3);
+        assert!(area.buffer(-3.0, 64).is_empty());
+        assert_eq!(area.buffer(0.0, 64)[0].area(), 16.0);
+    }
+
+    #[test]
+    fn test_simplify() {
+        let area = PolygonalArea::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.01),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(0.0, 4.0),
+            ],
+            Some(vec![None; 5]),
+        );
+        let simplified = area.simplify(0.1);
+        assert_eq!(simplified.get_vertices().len(), 4);
+        assert!(simplified.get_tags().is_none());
+        assert_eq!(area.simplify(0.001).get_vertices().len(), 5);
+    }
+
     #[test]
     fn test_self_intersecting() {
         let mut area = PolygonalArea::new(
@@ -0,0 +1,197 @@
+use crate::match_query::MatchQuery;
+use crate::primitives::frame::VideoFrameProxy;
+use crate::primitives::frame_batch::VideoFrameBatch;
+use crate::primitives::object::ObjectOperations;
+use crate::primitives::{Attribute, WithAttributes};
+use std::sync::Arc;
+
+/// The hint set on the masked attributes.
+///
+pub const REDACTED_HINT: &str = "redacted";
+
+/// Selects the attributes by namespace and, optionally, by name.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSelector {
+    pub namespace: String,
+    /// When not set, every attribute of the namespace is selected.
+    pub name: Option<String>,
+}
+
+impl AttributeSelector {
+    pub fn new(namespace: &str, name: Option<&str>) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            name: name.map(String::from),
+        }
+    }
+
+    fn matches(&self, attribute: &Attribute) -> bool {
+        attribute.namespace == self.namespace
+            && match &self.name {
+                Some(name) => name == &attribute.name,
+                None => true,
+            }
+    }
+}
+
+/// Defines how the attributes are redacted.
+///
+#[derive(Debug, Clone, Default)]
+pub struct RedactionSpec {
+    /// The attributes removed from the selected objects.
+    pub remove: Vec<AttributeSelector>,
+    /// The attributes kept on the selected objects with their values dropped and the hint set
+    /// to [`REDACTED_HINT`], so consumers know the information existed.
+    pub mask: Vec<AttributeSelector>,
+    /// Whether the hidden attributes of the frame and all its objects are removed.
+    pub strip_hidden: bool,
+    /// Whether `remove` and `mask` apply to the frame attributes too.
+    pub include_frame_attributes: bool,
+}
+
+impl RedactionSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn remove(mut self, namespace: &str, name: Option<&str>) -> Self {
+        self.remove.push(AttributeSelector::new(namespace, name));
+        self
+    }
+
+    pub fn mask(mut self, namespace: &str, name: Option<&str>) -> Self {
+        self.mask.push(AttributeSelector::new(namespace, name));
+        self
+    }
+
+    pub fn strip_hidden(mut self, strip_hidden: bool) -> Self {
+        self.strip_hidden = strip_hidden;
+        self
+    }
+
+    pub fn include_frame_attributes(mut self, include: bool) -> Self {
+        self.include_frame_attributes = include;
+        self
+    }
+
+    fn redact_attributes(&self, attributes: &mut Vec<Attribute>, selected: bool) {
+        attributes.retain(|a| {
+            !((self.strip_hidden && a.is_hidden)
+                || (selected && self.remove.iter().any(|s| s.matches(a))))
+        });
+        if !selected {
+            return;
+        }
+        for a in attributes
+            .iter_mut()
+            .filter(|a| self.mask.iter().any(|s| s.matches(a)))
+        {
+            a.values = Arc::new(Vec::new());
+            a.hint = Some(REDACTED_HINT.to_string());
+        }
+    }
+}
+
+impl VideoFrameProxy {
+    /// Produces a copy of the frame with the attributes redacted according to the spec: the
+    /// selection rules apply to the objects matching the query. The frame itself is not changed.
+    ///
+    pub fn redact(&self, q: &MatchQuery, spec: &RedactionSpec) -> VideoFrameProxy {
+        let mut copy = self.smart_copy();
+        copy.with_attributes_mut(|attributes| {
+            spec.redact_attributes(attributes, spec.include_frame_attributes)
+        });
+        let selected = copy
+            .access_objects(q)
+            .iter()
+            .map(|o| o.get_id())
+            .collect::<Vec<_>>();
+        for mut object in copy.get_all_objects() {
+            let is_selected = selected.contains(&object.get_id());
+            object
+                .with_attributes_mut(|attributes| spec.redact_attributes(attributes, is_selected));
+        }
+        copy
+    }
+}
+
+impl VideoFrameBatch {
+    /// Produces a copy of the batch with every frame redacted, see [`VideoFrameProxy::redact`].
+    ///
+    pub fn redact(&self, q: &MatchQuery, spec: &RedactionSpec) -> VideoFrameBatch {
+        let mut batch = VideoFrameBatch::with_capacity(self.frames.len());
+        for (id, frame) in &self.frames {
+            batch.add(*id, frame.redact(q, spec));
+        }
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_query::{eq, MatchQuery};
+    use crate::primitives::attribute_value::AttributeValue;
+    use crate::test::gen_frame;
+
+    fn string_value(s: &str) -> Vec<AttributeValue> {
+        vec![AttributeValue::string(s, None)]
+    }
+
+    #[test]
+    fn test_redact_frame() {
+        let mut frame = gen_frame();
+        frame.set_persistent_attribute("meta", "camera", &None, false, string_value("gate"));
+        frame.set_persistent_attribute("meta", "secret", &None, true, string_value("key"));
+        for mut o in frame.get_all_objects() {
+            o.set_persistent_attribute("face", "embedding", &None, false, string_value("e"));
+            o.set_persistent_attribute("plate", "text", &None, false, string_value("AB123"));
+        }
+
+        let spec = RedactionSpec::new()
+            .remove("face", None)
+            .mask("plate", Some("text"))
+            .strip_hidden(true);
+        let redacted = frame.redact(&MatchQuery::Namespace(eq("test2")), &spec);
+
+        assert!(redacted.get_attribute("meta", "camera").is_some());
+        assert!(redacted.get_attribute("meta", "secret").is_none());
+        for o in redacted.get_all_objects() {
+            let selected = o.get_namespace() == "test2";
+            assert_eq!(o.get_attribute("face", "embedding").is_none(), selected);
+            let plate = o.get_attribute("plate", "text").unwrap();
+            assert_eq!(plate.values.is_empty(), selected);
+            assert_eq!(plate.hint.as_deref() == Some(REDACTED_HINT), selected);
+        }
+
+        // the source frame stays intact
+        assert!(frame.get_attribute("meta", "secret").is_some());
+        for o in frame.get_all_objects() {
+            assert!(o.get_attribute("face", "embedding").is_some());
+            assert!(!o.get_attribute("plate", "text").unwrap().values.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_redact_batch() {
+        let mut batch = VideoFrameBatch::new();
+        batch.add(1, gen_frame());
+        batch.add(2, gen_frame());
+        for frame in batch.frames().values() {
+            for mut o in frame.get_all_objects() {
+                o.set_persistent_attribute("face", "embedding", &None, false, string_value("e"));
+            }
+        }
+        let redacted = batch.redact(
+            &MatchQuery::Idle,
+            &RedactionSpec::new().remove("face", None),
+        );
+        for frame in redacted.frames().values() {
+            assert!(frame
+                .get_all_objects()
+                .iter()
+                .all(|o| o.get_attribute("face", "embedding").is_none()));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::primitives::RBBox;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The ring buffer of the latest observed boxes of a track, the oldest observations are evicted
+/// when the capacity is reached. The motion estimations are computed between the oldest and the
+/// newest kept observations.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackHistory {
+    capacity: usize,
+    /// The total number of observations, including the evicted ones.
+    observations: u64,
+    entries: VecDeque<(i64, RBBox)>,
+}
+
+impl TrackHistory {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Track history capacity must be positive");
+        Self {
+            capacity,
+            observations: 0,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records the box observed at the pts.
+    ///
+    pub fn push(&mut self, pts: i64, bbox: &RBBox) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((pts, bbox.copy()));
+        self.observations += 1;
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of times the track was observed, the age of the track in observations.
+    ///
+    pub fn get_observations(&self) -> u64 {
+        self.observations
+    }
+
+    /// The kept observations from the oldest to the newest.
+    ///
+    pub fn get_entries(&self) -> Vec<(i64, RBBox)> {
+        self.entries
+            .iter()
+            .map(|(pts, b)| (*pts, b.copy()))
+            .collect()
+    }
+
+    fn displacement(&self) -> Option<(f32, f32)> {
+        if self.entries.len() < 2 {
+            return None;
+        }
+        let (_, first) = self.entries.front()?;
+        let (_, last) = self.entries.back()?;
+        Some((
+            last.get_xc() - first.get_xc(),
+            last.get_yc() - first.get_yc(),
+        ))
+    }
+
+    /// The average distance the box center moves between two observations, in pixels. Requires
+    /// at least two observations.
+    ///
+    pub fn speed(&self) -> Option<f32> {
+        let (dx, dy) = self.displacement()?;
+        Some(dx.hypot(dy) / (self.entries.len() - 1) as f32)
+    }
+
+    /// The direction of the box center movement in degrees within `(-180, 180]`: 0 is along the
+    /// X axis, 90 is along the Y axis (downwards in the image). Requires at least two observations.
+    ///
+    pub fn direction(&self) -> Option<f32> {
+        let (dx, dy) = self.displacement()?;
+        Some(dy.atan2(dx).to_degrees())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_history() {
+        let mut history = TrackHistory::new(3);
+        assert!(history.speed().is_none());
+        history.push(0, &RBBox::new(0.0, 0.0, 10.0, 10.0, None));
+        assert!(history.direction().is_none());
+        history.push(1, &RBBox::new(100.0, 100.0, 10.0, 10.0, None));
+        history.push(2, &RBBox::new(3.0, 0.0, 10.0, 10.0, None));
+        history.push(3, &RBBox::new(6.0, 0.0, 10.0, 10.0, None));
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get_observations(), 4);
+        assert_eq!(history.get_entries()[0].0, 1);
+
+        let mut history = TrackHistory::new(3);
+        for (pts, x) in [(0, 0.0), (1, 3.0), (2, 6.0)] {
+            history.push(pts, &RBBox::new(x, 10.0 - x / 3.0 * 4.0, 10.0, 10.0, None));
+        }
+        assert_eq!(history.speed(), Some(5.0));
+        let direction = history.direction().unwrap();
+        assert!((direction - (-4.0f32).atan2(3.0).to_degrees()).abs() < 1e-4);
+    }
+}
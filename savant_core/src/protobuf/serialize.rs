@@ -11,6 +11,7 @@ use std::convert::Infallible;
 mod attribute;
 mod attribute_set;
 mod bounding_box;
+mod hidden_attribute;
 mod intersection_kind;
 mod message_envelope;
 mod polygonal_area;
@@ -35,6 +36,16 @@ pub enum Error {
     InvalidVideoFrameParentObject(i64),
     #[error("Failed to convert protobuf enum balue to Rust enum value: {0}")]
     EnumConversionError(i32),
+    #[error("Invalid object merge policy: {0}")]
+    InvalidObjectMergePolicy(String),
+    #[error("Invalid frame update field {0}: {1}")]
+    InvalidUpdateField(String, String),
+    #[error("Invalid frame field {0}: {1}")]
+    InvalidFrameField(String, String),
+    #[error("Invalid object field {0}: {1}")]
+    InvalidObjectField(String, String),
+    #[error("Invalid tensor: {0}")]
+    InvalidTensor(String),
 }
 
 impl From<uuid::Error> for Error {
@@ -1,11 +1,71 @@
 use crate::primitives::any_object::AnyObject;
-use crate::primitives::attribute_value::{AttributeValue, AttributeValueVariant};
+use crate::primitives::attribute_value::{
+    AttributeValue, AttributeValueVariant, Tensor, TensorDType,
+};
 use crate::primitives::{Attribute, IntersectionKind, RBBox};
 use crate::protobuf::serialize;
 use prost::UnknownEnumValue;
 use savant_protobuf::generated;
 use std::sync::Arc;
 
+/// The tensors are transferred as the bytes values whose first dimension is the marker plus
+/// the element type index in [`TENSOR_DTYPES`]. The data is little-endian regardless of the host.
+///
+const TENSOR_DTYPE_MARKER: i64 = i64::MIN;
+const TENSOR_DTYPES: [TensorDType; 8] = [
+    TensorDType::UInt8,
+    TensorDType::Int8,
+    TensorDType::UInt16,
+    TensorDType::Int16,
+    TensorDType::Int32,
+    TensorDType::Int64,
+    TensorDType::Float32,
+    TensorDType::Float64,
+];
+
+/// Converts the native-endian tensor data to little-endian and back.
+///
+fn swap_to_le(data: &[u8], item_size: usize) -> Vec<u8> {
+    if cfg!(target_endian = "little") {
+        return data.to_vec();
+    }
+    data.chunks_exact(item_size)
+        .flat_map(|item| item.iter().rev().copied())
+        .collect()
+}
+
+fn tensor_to_bytes(tensor: &Tensor) -> generated::BytesAttributeValueVariant {
+    let dtype = tensor.get_dtype();
+    let code = TENSOR_DTYPES.iter().position(|d| *d == dtype).unwrap() as i64;
+    generated::BytesAttributeValueVariant {
+        dims: std::iter::once(TENSOR_DTYPE_MARKER + code)
+            .chain(tensor.get_dims().iter().map(|d| *d as i64))
+            .collect(),
+        data: swap_to_le(tensor.get_data(), dtype.item_size()),
+    }
+}
+
+fn tensor_from_bytes(
+    bytes: &generated::BytesAttributeValueVariant,
+) -> Option<Result<Tensor, serialize::Error>> {
+    let code = bytes.dims.first()?.checked_sub(TENSOR_DTYPE_MARKER)?;
+    let dtype = *TENSOR_DTYPES.get(usize::try_from(code).ok()?)?;
+    let dims = bytes.dims[1..]
+        .iter()
+        .map(|d| usize::try_from(*d))
+        .collect::<Result<Vec<_>, _>>();
+    let Ok(dims) = dims else {
+        return Some(Err(serialize::Error::InvalidTensor(format!(
+            "negative dimensions {:?}",
+            &bytes.dims[1..]
+        ))));
+    };
+    Some(
+        Tensor::new(dims, dtype, swap_to_le(&bytes.data, dtype.item_size()))
+            .map_err(|e| serialize::Error::InvalidTensor(e.to_string())),
+    )
+}
+
 impl From<&AttributeValueVariant> for generated::attribute_value::Value {
     fn from(value: &AttributeValueVariant) -> Self {
         match value {
@@ -110,6 +170,9 @@ impl From<&AttributeValueVariant> for generated::attribute_value::Value {
                     },
                 )
             }
+            AttributeValueVariant::Tensor(t) => {
+                generated::attribute_value::Value::Bytes(tensor_to_bytes(t))
+            }
             AttributeValueVariant::TemporaryValue(_) => {
                 generated::attribute_value::Value::Temporary(generated::TemporaryValueVariant {})
             }
@@ -125,9 +188,10 @@ impl TryFrom<&generated::attribute_value::Value> for AttributeValueVariant {
 
     fn try_from(value: &generated::attribute_value::Value) -> Result<Self, Self::Error> {
         Ok(match value {
-            generated::attribute_value::Value::Bytes(b) => {
-                AttributeValueVariant::Bytes(b.dims.clone(), b.data.clone())
-            }
+            generated::attribute_value::Value::Bytes(b) => match tensor_from_bytes(b) {
+                Some(tensor) => AttributeValueVariant::Tensor(tensor?),
+                None => AttributeValueVariant::Bytes(b.dims.clone(), b.data.clone()),
+            },
             generated::attribute_value::Value::String(s) => {
                 AttributeValueVariant::String(s.data.clone())
             }
@@ -256,11 +320,81 @@ impl TryFrom<&generated::Attribute> for Attribute {
 
 #[cfg(test)]
 mod tests {
-    use crate::primitives::attribute_value::{AttributeValue, AttributeValueVariant};
+    use crate::primitives::attribute_value::{
+        AttributeValue, AttributeValueVariant, Tensor, TensorDType,
+    };
     use crate::primitives::{Attribute, IntersectionKind};
+    use crate::protobuf::serialize;
     use savant_protobuf::generated;
     use std::sync::Arc;
 
+    #[test]
+    fn test_attribute_value_variant_tensor() {
+        let values = [1.0f32, 2.0, 3.0, 4.0];
+        let tensor = Tensor::from_values(vec![2, 2], &values).unwrap();
+        let av = AttributeValueVariant::Tensor(tensor.clone());
+        let pb = generated::attribute_value::Value::from(&av);
+        assert_eq!(
+            pb,
+            generated::attribute_value::Value::Bytes(generated::BytesAttributeValueVariant {
+                dims: vec![i64::MIN + 6, 2, 2],
+                data: values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            })
+        );
+        let decoded = AttributeValueVariant::try_from(&pb).unwrap();
+        assert_eq!(decoded, av);
+        let AttributeValueVariant::Tensor(decoded) = decoded else {
+            unreachable!()
+        };
+        assert_eq!(decoded.get_dtype(), TensorDType::Float32);
+        assert_eq!(decoded.get_dims(), &[2, 2]);
+        assert_eq!(decoded.to_values::<f32>().unwrap(), values);
+    }
+
+    #[test]
+    fn test_attribute_value_variant_tensor_dtypes() {
+        for av in [
+            AttributeValueVariant::Tensor(Tensor::from_values(vec![3], &[1u8, 2, 255]).unwrap()),
+            AttributeValueVariant::Tensor(Tensor::from_values(vec![2], &[-1i16, 300]).unwrap()),
+            AttributeValueVariant::Tensor(
+                Tensor::from_values(vec![1, 2], &[i64::MIN, i64::MAX]).unwrap(),
+            ),
+            AttributeValueVariant::Tensor(Tensor::from_values(vec![1], &[0.5f64]).unwrap()),
+        ] {
+            let pb = generated::attribute_value::Value::from(&av);
+            assert_eq!(AttributeValueVariant::try_from(&pb).unwrap(), av);
+        }
+    }
+
+    #[test]
+    fn test_attribute_value_variant_invalid_tensor() {
+        let pb = generated::attribute_value::Value::Bytes(generated::BytesAttributeValueVariant {
+            dims: vec![i64::MIN + 6, 2, 2],
+            data: vec![0; 3],
+        });
+        assert!(matches!(
+            AttributeValueVariant::try_from(&pb),
+            Err(serialize::Error::InvalidTensor(_))
+        ));
+        let pb = generated::attribute_value::Value::Bytes(generated::BytesAttributeValueVariant {
+            dims: vec![i64::MIN, -1],
+            data: vec![],
+        });
+        assert!(matches!(
+            AttributeValueVariant::try_from(&pb),
+            Err(serialize::Error::InvalidTensor(_))
+        ));
+        // the element count overflows
+        let pb = generated::attribute_value::Value::Bytes(generated::BytesAttributeValueVariant {
+            dims: vec![i64::MIN + 6, i64::MAX, i64::MAX],
+            data: vec![],
+        });
+        assert!(matches!(
+            AttributeValueVariant::try_from(&pb),
+            Err(serialize::Error::InvalidTensor(_))
+        ));
+    }
+
     #[test]
     fn test_attribute_value_variant_bytes() {
         let dims = vec![1, 2, 3];
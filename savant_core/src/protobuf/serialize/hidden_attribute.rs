@@ -0,0 +1,42 @@
+use crate::primitives::attribute_value::{AttributeValue, AttributeValueVariant};
+use crate::primitives::Attribute;
+use savant_protobuf::generated;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// The fields the protobuf schema has no place for are transferred as the hidden attributes in
+/// the namespaces below, which are removed when the primitive is restored.
+pub(super) const UPDATE_NAMESPACE: &str = "savant.update";
+pub(super) const FRAME_NAMESPACE: &str = "savant.frame";
+pub(super) const OBJECT_NAMESPACE: &str = "savant.object";
+
+pub(super) fn hidden_attribute(
+    namespace: &str,
+    name: &str,
+    values: Vec<AttributeValue>,
+) -> generated::Attribute {
+    generated::Attribute::from(&Attribute::persistent(namespace, name, values, &None, true))
+}
+
+pub(super) fn json_attribute<T: Serialize>(
+    namespace: &str,
+    name: &str,
+    value: &T,
+) -> generated::Attribute {
+    let json = serde_json::to_string(value).expect("The hidden fields are always serializable");
+    hidden_attribute(namespace, name, vec![AttributeValue::string(&json, None)])
+}
+
+pub(super) fn parse_json_attribute<T: DeserializeOwned>(
+    attribute: &Attribute,
+) -> Result<T, String> {
+    match attribute.get_values().as_slice() {
+        [value] => match value.get() {
+            AttributeValueVariant::String(json) => {
+                serde_json::from_str(json).map_err(|e| e.to_string())
+            }
+            _ => Err("the value is not a string".to_string()),
+        },
+        _ => Err("a single value is expected".to_string()),
+    }
+}
@@ -1,9 +1,13 @@
+use crate::primitives::codec::report_unknown_codec;
 use crate::primitives::frame::{
-    VideoFrame, VideoFrameContent, VideoFrameProxy, VideoFrameTranscodingMethod,
+    ExternalFrame, VideoFrame, VideoFrameContent, VideoFrameProxy, VideoFrameTranscodingMethod,
     VideoFrameTransformation,
 };
 use crate::primitives::object::VideoObject;
 use crate::primitives::Attribute;
+use crate::protobuf::serialize::hidden_attribute::{
+    json_attribute, parse_json_attribute, FRAME_NAMESPACE,
+};
 use crate::protobuf::serialize::Error;
 use hashbrown::{HashMap, HashSet};
 use prost::UnknownEnumValue;
@@ -12,6 +16,12 @@ use std::str::FromStr;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// The protobuf schema has no crop transformation and no external frame descriptor, so the
+/// frames with the crops carry all the transformations and the external frames carry the
+/// descriptor as the JSON hidden frame attributes.
+const TRANSFORMATIONS_NAME: &str = "transformations";
+const EXTERNAL_DESCRIPTOR_NAME: &str = "external_descriptor";
+
 impl From<&VideoFrameProxy> for generated::VideoFrame {
     fn from(vfp: &VideoFrameProxy) -> Self {
         let bind = vfp.get_inner();
@@ -56,6 +66,30 @@ impl From<&Box<VideoFrame>> for generated::VideoFrame {
                 .iter()
                 .filter(|a| a.is_persistent)
                 .map(|a| a.into())
+                .chain(
+                    video_frame
+                        .transformations
+                        .iter()
+                        .any(|t| matches!(t, VideoFrameTransformation::Crop(..)))
+                        .then(|| {
+                            json_attribute(
+                                FRAME_NAMESPACE,
+                                TRANSFORMATIONS_NAME,
+                                &video_frame.transformations,
+                            )
+                        }),
+                )
+                .chain(match &*video_frame.content {
+                    VideoFrameContent::External(ExternalFrame {
+                        descriptor: Some(descriptor),
+                        ..
+                    }) => Some(json_attribute(
+                        FRAME_NAMESPACE,
+                        EXTERNAL_DESCRIPTOR_NAME,
+                        descriptor,
+                    )),
+                    _ => None,
+                })
                 .collect(),
             objects,
             content: Some((&*video_frame.content).into()),
@@ -72,17 +106,31 @@ impl TryFrom<&generated::VideoFrame> for VideoFrame {
     type Error = Error;
 
     fn try_from(value: &generated::VideoFrame) -> Result<Self, Self::Error> {
-        let transformations = value
+        let mut transformations = value
             .transformations
             .iter()
             .map(VideoFrameTransformation::try_from)
             .collect::<Result<Vec<VideoFrameTransformation>, _>>()?;
 
-        let attributes = value
-            .attributes
-            .iter()
-            .map(Attribute::try_from)
-            .collect::<Result<Vec<Attribute>, _>>()?;
+        let mut descriptor = None;
+        let mut attributes = Vec::with_capacity(value.attributes.len());
+        for attribute in &value.attributes {
+            let attribute = Attribute::try_from(attribute)?;
+            if attribute.get_namespace() != FRAME_NAMESPACE {
+                attributes.push(attribute);
+                continue;
+            }
+            let invalid = |e| Error::InvalidFrameField(attribute.get_name().to_string(), e);
+            match attribute.get_name() {
+                TRANSFORMATIONS_NAME => {
+                    transformations = parse_json_attribute(&attribute).map_err(invalid)?
+                }
+                EXTERNAL_DESCRIPTOR_NAME => {
+                    descriptor = Some(parse_json_attribute(&attribute).map_err(invalid)?)
+                }
+                _ => attributes.push(attribute),
+            }
+        }
 
         let objects = value
             .objects
@@ -102,7 +150,20 @@ impl TryFrom<&generated::VideoFrame> for VideoFrame {
             }
         }
 
+        let mut content = VideoFrameContent::from(value.content.as_ref().unwrap());
+        match (&mut content, descriptor) {
+            (_, None) => {}
+            (VideoFrameContent::External(external), descriptor) => external.descriptor = descriptor,
+            _ => {
+                return Err(Error::InvalidFrameField(
+                    EXTERNAL_DESCRIPTOR_NAME.to_string(),
+                    "the frame content is not external".to_string(),
+                ))
+            }
+        }
+
         let max_object_id = objects.keys().max().copied().unwrap_or_default();
+        report_unknown_codec(&value.source_id, value.codec.as_deref());
 
         Ok(VideoFrame {
             previous_frame_seq_id: value.previous_frame_seq_id,
@@ -130,11 +191,13 @@ impl TryFrom<&generated::VideoFrame> for VideoFrame {
             pts: value.pts,
             dts: value.dts,
             duration: value.duration,
-            content: Arc::new(VideoFrameContent::from(value.content.as_ref().unwrap())),
+            content: Arc::new(content),
             transformations,
             attributes,
-            objects,
+            attribute_index: Default::default(),
+            objects: objects.into(),
             max_object_id,
+            journal: None,
         })
     }
 }
@@ -150,7 +213,11 @@ impl TryFrom<&generated::VideoFrame> for VideoFrameProxy {
 #[cfg(test)]
 mod tests {
     use crate::json_api::ToSerdeJsonValue;
-    use crate::primitives::frame::VideoFrameProxy;
+    use crate::primitives::frame::{
+        CropObjectPolicy, ExternalFrame, ExternalFrameDescriptor, ExternalMemoryType,
+        VideoFrameContent, VideoFrameProxy, VideoFrameTransformation,
+    };
+    use crate::primitives::{RBBox, WithAttributes};
     use crate::test::gen_frame;
     use savant_protobuf::generated;
 
@@ -164,4 +231,57 @@ mod tests {
         assert_eq!(restored.inner.read().creation_timestamp_ns, pattern);
         assert_eq!(frame.to_serde_json_value(), restored.to_serde_json_value());
     }
+
+    #[test]
+    fn test_cropped_frame() {
+        let mut frame = gen_frame();
+        frame
+            .add_transformation(VideoFrameTransformation::InitialSize(1280, 720))
+            .unwrap();
+        let cropped = frame
+            .crop(
+                &RBBox::ltrb(100.0, 100.0, 400.0, 300.0),
+                CropObjectPolicy::Clip,
+            )
+            .unwrap();
+        let serialized = generated::VideoFrame::from(&cropped);
+        assert_eq!(
+            VideoFrameTransformation::from(serialized.transformations.last().unwrap()),
+            VideoFrameTransformation::ResultingSize(300, 200)
+        );
+        let restored = VideoFrameProxy::try_from(&serialized).unwrap();
+        assert_eq!(
+            restored.get_transformations(),
+            cropped.get_transformations()
+        );
+        assert!(restored
+            .get_attribute("savant.frame", "transformations")
+            .is_none());
+    }
+
+    #[test]
+    fn test_external_frame_descriptor() {
+        let mut frame = gen_frame();
+        let descriptor = ExternalFrameDescriptor::new(
+            ExternalMemoryType::DmaBuf,
+            None,
+            "NV12",
+            vec![2048, 2048],
+            vec![0, 2048 * 1080],
+            Some(42),
+        )
+        .unwrap();
+        frame.set_content(VideoFrameContent::External(
+            ExternalFrame::new("dmabuf", &None).with_descriptor(descriptor.clone()),
+        ));
+        let serialized = generated::VideoFrame::from(&frame);
+        let restored = VideoFrameProxy::try_from(&serialized).unwrap();
+        match &*restored.get_content() {
+            VideoFrameContent::External(e) => assert_eq!(e.descriptor, Some(descriptor)),
+            _ => unreachable!(),
+        }
+        assert!(restored
+            .get_attribute("savant.frame", "external_descriptor")
+            .is_none());
+    }
 }
@@ -11,7 +11,7 @@ impl From<&VideoFrameContent> for generated::video_frame::Content {
                 })
             }
             VideoFrameContent::Internal(data) => {
-                generated::video_frame::Content::Internal(data.clone())
+                generated::video_frame::Content::Internal(data.to_vec())
             }
             VideoFrameContent::None => {
                 generated::video_frame::Content::None(generated::NoneFrame {})
@@ -27,10 +27,12 @@ impl From<&generated::video_frame::Content> for VideoFrameContent {
                 VideoFrameContent::External(crate::primitives::frame::ExternalFrame {
                     method: e.method.clone(),
                     location: e.location.clone(),
+                    // restored from the hidden frame attribute with the frame
+                    descriptor: None,
                 })
             }
             generated::video_frame::Content::Internal(data) => {
-                VideoFrameContent::Internal(data.clone())
+                VideoFrameContent::internal(data.clone())
             }
             generated::video_frame::Content::None(_) => VideoFrameContent::None,
         }
@@ -48,6 +50,7 @@ mod tests {
             VideoFrameContent::External(crate::primitives::frame::ExternalFrame {
                 method: "method".to_string(),
                 location: Some("location".to_string()),
+                descriptor: None,
             }),
             VideoFrameContent::from(&generated::video_frame::Content::External(
                 generated::ExternalFrame {
@@ -57,7 +60,7 @@ mod tests {
             ))
         );
         assert_eq!(
-            VideoFrameContent::Internal(vec![1, 2, 3]),
+            VideoFrameContent::internal(vec![1, 2, 3]),
             VideoFrameContent::from(&generated::video_frame::Content::Internal(vec![1, 2, 3]))
         );
         assert_eq!(
@@ -76,13 +79,14 @@ mod tests {
                 crate::primitives::frame::ExternalFrame {
                     method: "method".to_string(),
                     location: Some("location".to_string()),
+                    descriptor: None,
                 }
             ))
         );
 
         assert_eq!(
             generated::video_frame::Content::Internal(vec![1, 2, 3]),
-            generated::video_frame::Content::from(&VideoFrameContent::Internal(vec![1, 2, 3]))
+            generated::video_frame::Content::from(&VideoFrameContent::internal(vec![1, 2, 3]))
         );
 
         assert_eq!(
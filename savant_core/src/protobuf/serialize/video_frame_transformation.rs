@@ -36,7 +36,10 @@ impl From<&VideoFrameTransformation> for generated::VideoFrameTransformation {
                     ),
                 ),
             },
-            VideoFrameTransformation::ResultingSize(w, h) => generated::VideoFrameTransformation {
+            // the schema has no crop, the readers which do not restore the transformations from
+            // the hidden frame attribute see the size of the cropped frame
+            VideoFrameTransformation::ResultingSize(w, h)
+            | VideoFrameTransformation::Crop(_, _, w, h) => generated::VideoFrameTransformation {
                 transformation: Some(
                     generated::video_frame_transformation::Transformation::ResultingSize(
                         generated::ResultingSize {
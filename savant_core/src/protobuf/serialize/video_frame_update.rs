@@ -1,13 +1,54 @@
+use crate::primitives::attribute_value::{AttributeValue, AttributeValueVariant};
 use crate::primitives::frame_update::{
-    AttributeUpdatePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
+    AttributeUpdatePolicy, ObjectMergePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
 };
 use crate::primitives::object::VideoObject;
 use crate::primitives::Attribute;
 use crate::protobuf::serialize;
+use crate::protobuf::serialize::hidden_attribute::{
+    hidden_attribute, json_attribute, parse_json_attribute, UPDATE_NAMESPACE,
+};
 use crate::protobuf::serialize::video_object::GeneratedVideoObjectWithForeignParent;
-use prost::UnknownEnumValue;
+use prost::{Message, UnknownEnumValue};
 use savant_protobuf::generated;
 
+/// The protobuf schema has no fields for the object merge policy, the deletions and the
+/// replaced objects, so they are transferred as hidden frame attributes. The merge policy and
+/// the deletions are kept as JSON strings, the replaced objects as the encoded protobuf objects.
+const MERGE_POLICY_NAME: &str = "object_merge_policy";
+const DELETED_FRAME_ATTRIBUTES_NAME: &str = "deleted_frame_attributes";
+const DELETED_OBJECTS_NAME: &str = "deleted_objects";
+const REPLACED_OBJECTS_NAME: &str = "replaced_objects";
+
+fn replaced_objects_attribute(objects: &[VideoObject]) -> generated::Attribute {
+    hidden_attribute(
+        UPDATE_NAMESPACE,
+        REPLACED_OBJECTS_NAME,
+        objects
+            .iter()
+            .map(|o| {
+                AttributeValue::bytes(&[], &generated::VideoObject::from(o).encode_to_vec(), None)
+            })
+            .collect(),
+    )
+}
+
+fn parse_replaced_objects(attribute: &Attribute) -> Result<Vec<VideoObject>, serialize::Error> {
+    attribute
+        .get_values()
+        .iter()
+        .map(|value| match value.get() {
+            AttributeValueVariant::Bytes(_, bytes) => {
+                VideoObject::try_from(&generated::VideoObject::decode(bytes.as_slice())?)
+            }
+            _ => Err(serialize::Error::InvalidUpdateField(
+                REPLACED_OBJECTS_NAME.to_string(),
+                "the value is not bytes".to_string(),
+            )),
+        })
+        .collect()
+}
+
 impl From<AttributeUpdatePolicy> for generated::AttributeUpdatePolicy {
     fn from(p: AttributeUpdatePolicy) -> Self {
         match p {
@@ -71,6 +112,25 @@ impl From<&VideoFrameUpdate> for generated::VideoFrameUpdate {
             .iter()
             .filter(|a| a.is_persistent)
             .map(|a| a.into())
+            .chain(
+                vfu.object_merge_policy
+                    .iter()
+                    .map(|p| json_attribute(UPDATE_NAMESPACE, MERGE_POLICY_NAME, p)),
+            )
+            .chain((!vfu.deleted_frame_attributes.is_empty()).then(|| {
+                json_attribute(
+                    UPDATE_NAMESPACE,
+                    DELETED_FRAME_ATTRIBUTES_NAME,
+                    &vfu.deleted_frame_attributes,
+                )
+            }))
+            .chain((!vfu.deleted_objects.is_empty()).then(|| {
+                json_attribute(UPDATE_NAMESPACE, DELETED_OBJECTS_NAME, &vfu.deleted_objects)
+            }))
+            .chain(
+                (!vfu.replaced_objects.is_empty())
+                    .then(|| replaced_objects_attribute(&vfu.replaced_objects)),
+            )
             .collect();
 
         let object_attributes = vfu
@@ -141,11 +201,36 @@ impl TryFrom<&generated::VideoFrameUpdate> for VideoFrameUpdate {
             })
             .collect::<Result<_, _>>()?;
 
-        let frame_attributes = value
-            .frame_attributes
-            .iter()
-            .map(Attribute::try_from)
-            .collect::<Result<_, _>>()?;
+        let mut object_merge_policy = None;
+        let mut deleted_frame_attributes = Vec::new();
+        let mut deleted_objects = Vec::new();
+        let mut replaced_objects = Vec::new();
+        let mut frame_attributes = Vec::with_capacity(value.frame_attributes.len());
+        for attribute in &value.frame_attributes {
+            let attribute = Attribute::try_from(attribute)?;
+            if attribute.get_namespace() != UPDATE_NAMESPACE {
+                frame_attributes.push(attribute);
+                continue;
+            }
+            let invalid =
+                |e| serialize::Error::InvalidUpdateField(attribute.get_name().to_string(), e);
+            match attribute.get_name() {
+                MERGE_POLICY_NAME => {
+                    object_merge_policy = Some(
+                        parse_json_attribute(&attribute)
+                            .map_err(serialize::Error::InvalidObjectMergePolicy)?,
+                    )
+                }
+                DELETED_FRAME_ATTRIBUTES_NAME => {
+                    deleted_frame_attributes = parse_json_attribute(&attribute).map_err(invalid)?
+                }
+                DELETED_OBJECTS_NAME => {
+                    deleted_objects = parse_json_attribute(&attribute).map_err(invalid)?
+                }
+                REPLACED_OBJECTS_NAME => replaced_objects = parse_replaced_objects(&attribute)?,
+                _ => frame_attributes.push(attribute),
+            }
+        }
 
         let objects = value
             .objects
@@ -160,6 +245,10 @@ impl TryFrom<&generated::VideoFrameUpdate> for VideoFrameUpdate {
             frame_attribute_policy,
             object_attribute_policy,
             object_policy,
+            object_merge_policy,
+            deleted_frame_attributes,
+            deleted_objects,
+            replaced_objects,
         })
     }
 }
@@ -168,11 +257,12 @@ impl TryFrom<&generated::VideoFrameUpdate> for VideoFrameUpdate {
 mod tests {
     use crate::primitives::attribute_value::AttributeValue;
     use crate::primitives::frame_update::{
-        AttributeUpdatePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
+        AttributeUpdatePolicy, MergePolicy, ObjectMergePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
     };
-    use crate::primitives::object::ObjectOperations;
-    use crate::primitives::Attribute;
-    use crate::test::gen_object;
+    use crate::primitives::object::{IdCollisionResolutionPolicy, ObjectOperations};
+    use crate::primitives::{Attribute, WithAttributes};
+    use crate::protobuf::serialize;
+    use crate::test::{gen_frame, gen_object, s};
     use savant_protobuf::generated;
 
     #[test]
@@ -254,5 +344,85 @@ mod tests {
             update.to_json(false).unwrap(),
             restored_update.to_json(false).unwrap()
         );
+        assert!(restored_update.get_object_merge_policy().is_none());
+
+        let policy = ObjectMergePolicy::new(
+            MergePolicy::KeepOriginal,
+            MergePolicy::HighestConfidence,
+            MergePolicy::ReplaceWithUpdate,
+            MergePolicy::UnionAttributes,
+        )
+        .unwrap();
+        update.set_object_merge_policy(Some(policy.clone()));
+        let generated_update = generated::VideoFrameUpdate::from(&update);
+        assert_eq!(generated_update.frame_attributes.len(), 2);
+        let restored_update = VideoFrameUpdate::try_from(&generated_update).unwrap();
+        assert_eq!(restored_update.get_object_merge_policy(), Some(policy));
+        assert_eq!(restored_update.get_frame_attributes().len(), 1);
+        assert_eq!(
+            update.to_json(false).unwrap(),
+            restored_update.to_json(false).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_video_frame_update_diff() -> anyhow::Result<()> {
+        let mut frame = gen_frame();
+        // the temporary values never compare equal
+        frame.delete_attribute("test", "test");
+        frame.set_persistent_attribute("meta", "deleted", &None, false, vec![]);
+        let mut other = frame.smart_copy();
+        other.delete_attribute("meta", "deleted");
+        other.delete_objects_with_ids(&[2]);
+        other.get_object(1).unwrap().set_label("changed");
+        let mut added = gen_object(10);
+        added.parent_id = Some(0);
+        other.add_object(added, IdCollisionResolutionPolicy::Error)?;
+
+        let update = frame.diff(&other);
+        let generated_update = generated::VideoFrameUpdate::from(&update);
+        let restored_update = VideoFrameUpdate::try_from(&generated_update)?;
+        assert_eq!(
+            restored_update.get_deleted_frame_attributes(),
+            &vec![(s("meta"), s("deleted"))]
+        );
+        assert_eq!(restored_update.get_deleted_objects(), &vec![2]);
+        assert_eq!(
+            restored_update
+                .get_replaced_objects()
+                .iter()
+                .map(|o| (o.get_id(), o.get_label(), o.get_parent_id()))
+                .collect::<Vec<_>>(),
+            vec![(1, s("changed"), Some(0)), (10, s("face"), Some(0))]
+        );
+        assert_eq!(
+            restored_update.get_frame_attributes().len(),
+            update.get_frame_attributes().len()
+        );
+
+        frame.update(&restored_update)?;
+        assert!(frame.get_attribute("meta", "deleted").is_none());
+        assert!(frame.get_object(2).is_none());
+        assert_eq!(frame.get_object(1).unwrap().get_label(), "changed");
+        assert_eq!(frame.get_object(10).unwrap().get_parent_id(), Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_update_field() {
+        let mut update = VideoFrameUpdate::default();
+        update.add_deleted_object(1);
+        let mut generated_update = generated::VideoFrameUpdate::from(&update);
+        generated_update.frame_attributes[0] = generated::Attribute::from(&Attribute::persistent(
+            "savant.update",
+            "deleted_objects",
+            vec![AttributeValue::string("[\"a\"]", None)],
+            &None,
+            true,
+        ));
+        assert!(matches!(
+            VideoFrameUpdate::try_from(&generated_update),
+            Err(serialize::Error::InvalidUpdateField(name, _)) if name == "deleted_objects"
+        ));
     }
 }
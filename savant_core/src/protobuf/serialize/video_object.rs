@@ -1,14 +1,26 @@
 use crate::primitives::object::{ObjectOperations, VideoObject};
 use crate::primitives::{Attribute, RBBox, WithAttributes};
 use crate::protobuf::serialize;
+use crate::protobuf::serialize::hidden_attribute::{
+    json_attribute, parse_json_attribute, OBJECT_NAMESPACE,
+};
 use savant_protobuf::generated;
 
+/// The protobuf schema has no fields for the keypoints, so they are transferred as the JSON
+/// hidden object attribute.
+const KEYPOINTS_NAME: &str = "keypoints";
+
 impl From<&VideoObject> for generated::VideoObject {
     fn from(vop: &VideoObject) -> Self {
         let attributes = vop
             .get_attributes()
             .iter()
             .map(|(ns, l)| generated::Attribute::from(&vop.get_attribute(ns, l).unwrap()))
+            .chain(
+                vop.keypoints
+                    .as_ref()
+                    .map(|k| json_attribute(OBJECT_NAMESPACE, KEYPOINTS_NAME, k)),
+            )
             .collect();
 
         generated::VideoObject {
@@ -45,12 +57,23 @@ impl From<&(VideoObject, Option<i64>)> for GeneratedVideoObjectWithForeignParent
 impl TryFrom<&generated::VideoObject> for VideoObject {
     type Error = serialize::Error;
     fn try_from(obj: &generated::VideoObject) -> Result<Self, Self::Error> {
-        let attributes = obj
-            .attributes
-            .iter()
-            .filter(|a| a.is_persistent)
-            .map(Attribute::try_from)
-            .collect::<Result<Vec<Attribute>, _>>()?;
+        let mut keypoints = None;
+        let mut attributes = Vec::with_capacity(obj.attributes.len());
+        for attribute in obj.attributes.iter().filter(|a| a.is_persistent) {
+            let attribute = Attribute::try_from(attribute)?;
+            if attribute.get_namespace() != OBJECT_NAMESPACE {
+                attributes.push(attribute);
+                continue;
+            }
+            let invalid =
+                |e| serialize::Error::InvalidObjectField(attribute.get_name().to_string(), e);
+            match attribute.get_name() {
+                KEYPOINTS_NAME => {
+                    keypoints = Some(parse_json_attribute(&attribute).map_err(invalid)?)
+                }
+                _ => attributes.push(attribute),
+            }
+        }
 
         Ok(VideoObject {
             id: obj.id,
@@ -65,6 +88,9 @@ impl TryFrom<&generated::VideoObject> for VideoObject {
             track_id: obj.track_id,
             namespace_id: None,
             label_id: None,
+            track_history: None,
+            keypoints,
+            journal: None,
             frame: None,
         })
     }
@@ -73,9 +99,11 @@ impl TryFrom<&generated::VideoObject> for VideoObject {
 #[cfg(test)]
 mod tests {
     use crate::json_api::ToSerdeJsonValue;
+    use crate::primitives::keypoints::{KeyPoint, KeyPoints};
     use crate::primitives::object::VideoObject;
     use crate::primitives::rust::AttributeValue;
     use crate::primitives::{Attribute, WithAttributes};
+    use crate::protobuf::serialize;
     use crate::test::gen_object;
     use savant_protobuf::generated;
 
@@ -117,4 +145,41 @@ mod tests {
             persistent_attr
         );
     }
+
+    #[test]
+    fn test_object_with_keypoints() {
+        let mut obj = gen_object(1);
+        let keypoints = KeyPoints::new(
+            vec![
+                KeyPoint::new("left_eye", 10.0, 10.0, Some(0.9)),
+                KeyPoint::new("nose", 15.0, 15.0, None),
+            ],
+            vec![(0, 1)],
+        )
+        .unwrap();
+        obj.keypoints = Some(keypoints.clone());
+        let mut serialized = generated::VideoObject::from(&obj);
+        let deserialized = VideoObject::try_from(&serialized).unwrap();
+        assert_eq!(deserialized.keypoints, Some(keypoints));
+        assert!(deserialized
+            .get_attribute("savant.object", "keypoints")
+            .is_none());
+
+        // the edges are validated
+        let keypoints = serialized.attributes.last_mut().unwrap();
+        *keypoints = generated::Attribute::from(&Attribute::persistent(
+            "savant.object",
+            "keypoints",
+            vec![AttributeValue::string(
+                r#"{"points":[],"edges":[[0,1]]}"#,
+                None,
+            )],
+            &None,
+            true,
+        ));
+        assert!(matches!(
+            VideoObject::try_from(&serialized),
+            Err(serialize::Error::InvalidObjectField(name, _)) if name == "keypoints"
+        ));
+    }
 }
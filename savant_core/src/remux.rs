@@ -0,0 +1,597 @@
+use crate::json_api::ToSerdeJsonValue;
+use crate::primitives::attribute::WithAttributes;
+use crate::primitives::frame::{VideoFrameContent, VideoFrameProxy, VideoFrameTranscodingMethod};
+use anyhow::bail;
+use hashbrown::HashMap;
+use log::{info, warn};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const EBML_HEADER: u32 = 0x1A45DFA3;
+const EBML_VERSION: u32 = 0x4286;
+const EBML_READ_VERSION: u32 = 0x42F7;
+const EBML_MAX_ID_LENGTH: u32 = 0x42F2;
+const EBML_MAX_SIZE_LENGTH: u32 = 0x42F3;
+const DOC_TYPE: u32 = 0x4282;
+const DOC_TYPE_VERSION: u32 = 0x4287;
+const DOC_TYPE_READ_VERSION: u32 = 0x4285;
+const SEGMENT: u32 = 0x18538067;
+const INFO: u32 = 0x1549A966;
+const TIMESTAMP_SCALE: u32 = 0x2AD7B1;
+const MUXING_APP: u32 = 0x4D80;
+const WRITING_APP: u32 = 0x5741;
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_NUMBER: u32 = 0xD7;
+const TRACK_UID: u32 = 0x73C5;
+const TRACK_TYPE: u32 = 0x83;
+const CODEC_ID: u32 = 0x86;
+const CODEC_PRIVATE: u32 = 0x63A2;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const CLUSTER: u32 = 0x1F43B675;
+const CLUSTER_TIMESTAMP: u32 = 0xE7;
+const SIMPLE_BLOCK: u32 = 0xA3;
+
+/// The size of the live-streamed master elements, the readers find their ends by the next
+/// element.
+///
+const UNKNOWN_SIZE: &[u8] = &[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+const MAX_BLOCK_OFFSET: i64 = i16::MAX as i64;
+const APP_NAME: &str = "savant-rs";
+
+fn element_id(id: u32) -> Vec<u8> {
+    let bytes = id.to_be_bytes();
+    let skip = bytes.iter().take_while(|b| **b == 0).count();
+    bytes[skip..].to_vec()
+}
+
+fn element_size(size: usize) -> Vec<u8> {
+    let size = size as u64;
+    let len = (1..=8).find(|n| size < (1u64 << (7 * n)) - 1).unwrap_or(8);
+    let mut bytes = size.to_be_bytes()[8 - len..].to_vec();
+    bytes[0] |= 0x80 >> (len - 1);
+    bytes
+}
+
+fn element(id: u32, data: &[u8]) -> Vec<u8> {
+    let mut bytes = element_id(id);
+    bytes.extend(element_size(data.len()));
+    bytes.extend(data);
+    bytes
+}
+
+fn uint_element(id: u32, value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = bytes.iter().take_while(|b| **b == 0).count().min(7);
+    element(id, &bytes[skip..])
+}
+
+fn master_element(id: u32, children: &[Vec<u8>]) -> Vec<u8> {
+    element(id, &children.concat())
+}
+
+/// Splits the Annex B byte stream into the NAL units without the start codes.
+///
+fn split_annex_b(data: &[u8]) -> anyhow::Result<Vec<&[u8]>> {
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i] == 0 && data[i + 1] == 0 && data[i + 2] == 1 {
+            starts.push((i, i + 3));
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    if starts
+        .first()
+        .map(|(s, _)| data[..*s].iter().all(|b| *b == 0))
+        != Some(true)
+    {
+        bail!("The frame content is not an Annex B byte stream.");
+    }
+    let mut units = Vec::with_capacity(starts.len());
+    for (n, (_, begin)) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).map(|(s, _)| *s).unwrap_or(data.len());
+        let unit = &data[*begin..end];
+        // the trailing zero belongs to the next 4-byte start code
+        let unit = match unit.iter().rposition(|b| *b != 0) {
+            Some(last) => &unit[..=last],
+            None => continue,
+        };
+        units.push(unit);
+    }
+    Ok(units)
+}
+
+fn remove_emulation_prevention(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut zeros = 0;
+    for b in data {
+        if zeros >= 2 && *b == 3 {
+            zeros = 0;
+            continue;
+        }
+        zeros = if *b == 0 { zeros + 1 } else { 0 };
+        result.push(*b);
+    }
+    result
+}
+
+/// The video codecs the muxer supports.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipCodec {
+    H264,
+    Hevc,
+}
+
+impl ClipCodec {
+    fn from_frame(frame: &VideoFrameProxy) -> anyhow::Result<Self> {
+        match frame.get_codec_info().map(|c| c.name) {
+            Some(name) if name == "h264" => Ok(Self::H264),
+            Some(name) if name == "hevc" => Ok(Self::Hevc),
+            other => bail!("Codec {:?} is not supported by the muxer.", other),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::H264 => "h264",
+            Self::Hevc => "hevc",
+        }
+    }
+
+    fn matroska_id(&self) -> &'static str {
+        match self {
+            Self::H264 => "V_MPEG4/ISO/AVC",
+            Self::Hevc => "V_MPEGH/ISO/HEVC",
+        }
+    }
+
+    fn nal_type(&self, unit: &[u8]) -> u8 {
+        match self {
+            Self::H264 => unit[0] & 0x1F,
+            Self::Hevc => (unit[0] >> 1) & 0x3F,
+        }
+    }
+
+    fn is_keyframe(&self, units: &[&[u8]]) -> bool {
+        units.iter().any(|u| match self {
+            Self::H264 => self.nal_type(u) == 5,
+            Self::Hevc => (16..=21).contains(&self.nal_type(u)),
+        })
+    }
+
+    fn is_parameter_set(&self, unit: &[u8]) -> bool {
+        match self {
+            Self::H264 => matches!(self.nal_type(unit), 7 | 8),
+            Self::Hevc => matches!(self.nal_type(unit), 32..=34),
+        }
+    }
+
+    /// Builds the decoder configuration record (`avcC` or `hvcC`) from the parameter sets of
+    /// the keyframe, `None` when the keyframe does not carry them.
+    ///
+    fn codec_private(&self, units: &[&[u8]]) -> Option<Vec<u8>> {
+        let of_type = |t: u8| {
+            units
+                .iter()
+                .filter(|u| self.nal_type(u) == t)
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        let push_units = |record: &mut Vec<u8>, units: &[&[u8]]| {
+            for unit in units {
+                record.extend((unit.len() as u16).to_be_bytes());
+                record.extend(*unit);
+            }
+        };
+        match self {
+            Self::H264 => {
+                let (sps, pps) = (of_type(7), of_type(8));
+                let first = sps.first().filter(|s| s.len() >= 4)?;
+                if pps.is_empty() {
+                    return None;
+                }
+                let mut record = vec![1, first[1], first[2], first[3], 0xFF];
+                record.push(0xE0 | sps.len() as u8);
+                push_units(&mut record, &sps);
+                record.push(pps.len() as u8);
+                push_units(&mut record, &pps);
+                Some(record)
+            }
+            Self::Hevc => {
+                let (vps, sps, pps) = (of_type(32), of_type(33), of_type(34));
+                if vps.is_empty() || pps.is_empty() {
+                    return None;
+                }
+                // the general profile, tier and level follow the NAL unit header and the
+                // parameter set id byte
+                let profile = remove_emulation_prevention(sps.first()?);
+                let profile = profile.get(3..15)?;
+                let mut record = vec![1];
+                record.extend(profile.get(..11)?);
+                record.push(profile[11]);
+                record.extend([0xF0, 0x00, 0xFC, 0xFD, 0xF8, 0xF8, 0x00, 0x00, 0x0F, 3]);
+                for (nal_type, units) in [(32u8, vps), (33, sps), (34, pps)] {
+                    record.push(0x80 | nal_type);
+                    record.extend((units.len() as u16).to_be_bytes());
+                    push_units(&mut record, &units);
+                }
+                Some(record)
+            }
+        }
+    }
+}
+
+struct ClipSegment {
+    path: PathBuf,
+    codec: ClipCodec,
+    file: BufWriter<File>,
+    start_ms: i64,
+    cluster_ms: Option<i64>,
+    frames: Vec<Value>,
+}
+
+impl ClipSegment {
+    fn create(
+        path: PathBuf,
+        codec: ClipCodec,
+        codec_private: &[u8],
+        width: i64,
+        height: i64,
+        start_ms: i64,
+    ) -> anyhow::Result<Self> {
+        let mut file = BufWriter::new(File::create(&path)?);
+        file.write_all(&master_element(
+            EBML_HEADER,
+            &[
+                uint_element(EBML_VERSION, 1),
+                uint_element(EBML_READ_VERSION, 1),
+                uint_element(EBML_MAX_ID_LENGTH, 4),
+                uint_element(EBML_MAX_SIZE_LENGTH, 8),
+                element(DOC_TYPE, b"matroska"),
+                uint_element(DOC_TYPE_VERSION, 4),
+                uint_element(DOC_TYPE_READ_VERSION, 2),
+            ],
+        ))?;
+        file.write_all(&element_id(SEGMENT))?;
+        file.write_all(UNKNOWN_SIZE)?;
+        file.write_all(&master_element(
+            INFO,
+            &[
+                uint_element(TIMESTAMP_SCALE, 1_000_000),
+                element(MUXING_APP, APP_NAME.as_bytes()),
+                element(WRITING_APP, APP_NAME.as_bytes()),
+            ],
+        ))?;
+        file.write_all(&master_element(
+            TRACKS,
+            &[master_element(
+                TRACK_ENTRY,
+                &[
+                    uint_element(TRACK_NUMBER, 1),
+                    uint_element(TRACK_UID, 1),
+                    uint_element(TRACK_TYPE, 1),
+                    element(CODEC_ID, codec.matroska_id().as_bytes()),
+                    element(CODEC_PRIVATE, codec_private),
+                    master_element(
+                        VIDEO,
+                        &[
+                            uint_element(PIXEL_WIDTH, width as u64),
+                            uint_element(PIXEL_HEIGHT, height as u64),
+                        ],
+                    ),
+                ],
+            )],
+        ))?;
+        Ok(Self {
+            path,
+            codec,
+            file,
+            start_ms,
+            cluster_ms: None,
+            frames: Vec::new(),
+        })
+    }
+
+    fn write_block(&mut self, ms: i64, keyframe: bool, units: &[&[u8]]) -> anyhow::Result<()> {
+        let ms = ms - self.start_ms;
+        let cluster_ms = match self.cluster_ms {
+            Some(c) if !keyframe && (0..=MAX_BLOCK_OFFSET).contains(&(ms - c)) => c,
+            _ => {
+                self.file.write_all(&element_id(CLUSTER))?;
+                self.file.write_all(UNKNOWN_SIZE)?;
+                self.file
+                    .write_all(&uint_element(CLUSTER_TIMESTAMP, ms.max(0) as u64))?;
+                self.cluster_ms = Some(ms.max(0));
+                ms.max(0)
+            }
+        };
+        // the track number, the offset from the cluster timestamp and the flags
+        let mut block = vec![0x81];
+        block.extend(((ms - cluster_ms) as i16).to_be_bytes());
+        block.push(if keyframe { 0x80 } else { 0 });
+        for unit in units.iter().filter(|u| !self.codec.is_parameter_set(u)) {
+            block.extend((unit.len() as u32).to_be_bytes());
+            block.extend(*unit);
+        }
+        self.file.write_all(&element(SIMPLE_BLOCK, &block))?;
+        Ok(())
+    }
+
+    fn sidecar_path(&self) -> PathBuf {
+        self.path.with_extension("json")
+    }
+
+    fn close(mut self, source_id: &str) -> anyhow::Result<PathBuf> {
+        self.file.flush()?;
+        let sidecar = serde_json::json!({
+            "source_id": source_id,
+            "codec": self.codec.name(),
+            "segment": self.path.file_name().map(|n| n.to_string_lossy()),
+            "frames": self.frames,
+        });
+        std::fs::write(self.sidecar_path(), serde_json::to_vec_pretty(&sidecar)?)?;
+        info!(
+            target: "savant_rs::remux",
+            "Closed segment {:?} with {} frames", self.path, self.frames.len()
+        );
+        Ok(self.path)
+    }
+}
+
+fn pts_to_ms(pts: i64, time_base: (i32, i32)) -> i64 {
+    (pts as i128 * time_base.0 as i128 * 1000 / time_base.1.max(1) as i128) as i64
+}
+
+fn sanitize(source_id: &str) -> String {
+    source_id
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Writes the encoded H264/HEVC frames with the internal content to the Matroska segments, one
+/// directory per source id. A segment starts with a keyframe carrying the parameter sets and is
+/// closed at the first keyframe after the segment duration; the frames before the first
+/// keyframe are skipped.
+///
+/// Every segment gets the JSON sidecar with the pts, the uuid and the visible attributes of its
+/// frames.
+///
+pub struct ClipMuxer {
+    directory: PathBuf,
+    segment_duration: Duration,
+    segments: HashMap<String, ClipSegment>,
+}
+
+impl ClipMuxer {
+    pub fn new(directory: &Path, segment_duration: Duration) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(directory)?;
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            segment_duration,
+            segments: HashMap::new(),
+        })
+    }
+
+    /// Returns `false` when the frame is skipped while waiting for a keyframe.
+    ///
+    pub fn add_frame(&mut self, frame: &VideoFrameProxy) -> anyhow::Result<bool> {
+        if frame.get_transcoding_method() != VideoFrameTranscodingMethod::Encoded {
+            bail!("Only encoded frames can be muxed.");
+        }
+        let content = frame.get_content();
+        let VideoFrameContent::Internal(data) = content.as_ref() else {
+            bail!("Only frames with internal content can be muxed.");
+        };
+        let codec = ClipCodec::from_frame(frame)?;
+        let units = split_annex_b(data)?;
+        let keyframe = frame
+            .get_keyframe()
+            .unwrap_or_else(|| codec.is_keyframe(&units));
+        let source_id = frame.get_source_id();
+        let ms = pts_to_ms(frame.get_pts(), frame.get_time_base());
+
+        let rotate = match self.segments.get(&source_id) {
+            Some(segment) => {
+                keyframe
+                    && (segment.codec != codec
+                        || ms - segment.start_ms >= self.segment_duration.as_millis() as i64
+                        || ms < segment.start_ms)
+            }
+            None => keyframe,
+        };
+        if rotate {
+            match codec.codec_private(&units) {
+                Some(codec_private) => self.start_segment(frame, codec, &codec_private, ms)?,
+                None => warn!(
+                    target: "savant_rs::remux",
+                    "Keyframe of {} has no parameter sets, the segment is not started", source_id
+                ),
+            }
+        }
+        if !self.segments.contains_key(&source_id) {
+            return Ok(false);
+        }
+        self.write(frame, ms, keyframe, &units)
+    }
+
+    fn start_segment(
+        &mut self,
+        frame: &VideoFrameProxy,
+        codec: ClipCodec,
+        codec_private: &[u8],
+        ms: i64,
+    ) -> anyhow::Result<()> {
+        let source_id = frame.get_source_id();
+        self.close_source(&source_id)?;
+        let directory = self.directory.join(sanitize(&source_id));
+        std::fs::create_dir_all(&directory)?;
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let segment = ClipSegment::create(
+            directory.join(format!("{}-{}.mkv", created, ms)),
+            codec,
+            codec_private,
+            frame.get_width(),
+            frame.get_height(),
+            ms,
+        )?;
+        self.segments.insert(source_id, segment);
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        frame: &VideoFrameProxy,
+        ms: i64,
+        keyframe: bool,
+        units: &[&[u8]],
+    ) -> anyhow::Result<bool> {
+        let segment = self.segments.get_mut(&frame.get_source_id()).unwrap();
+        segment.write_block(ms, keyframe, units)?;
+        let attributes = frame.with_attributes_ref(|attributes| {
+            attributes
+                .iter()
+                .filter(|a| !a.is_hidden)
+                .map(|a| a.to_serde_json_value())
+                .collect::<Vec<_>>()
+        });
+        segment.frames.push(serde_json::json!({
+            "pts": frame.get_pts(),
+            "uuid": frame.get_uuid_as_string(),
+            "keyframe": keyframe,
+            "attributes": attributes,
+        }));
+        Ok(true)
+    }
+
+    /// Closes the current segment of the source, returns its path.
+    ///
+    pub fn close_source(&mut self, source_id: &str) -> anyhow::Result<Option<PathBuf>> {
+        self.segments
+            .remove(source_id)
+            .map(|segment| segment.close(source_id))
+            .transpose()
+    }
+
+    pub fn close(&mut self) -> anyhow::Result<Vec<PathBuf>> {
+        let sources = self.segments.keys().cloned().collect::<Vec<_>>();
+        let mut closed = Vec::with_capacity(sources.len());
+        for source_id in sources {
+            closed.extend(self.close_source(&source_id)?);
+        }
+        Ok(closed)
+    }
+}
+
+impl Drop for ClipMuxer {
+    fn drop(&mut self) {
+        if let Err(e) = self.close() {
+            warn!(target: "savant_rs::remux", "Failed to close the segments: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::gen_empty_frame;
+
+    const SPS: &[u8] = &[0x67, 0x42, 0x00, 0x1E, 0x95, 0xA8, 0x28];
+    const PPS: &[u8] = &[0x68, 0xCE, 0x3C, 0x80];
+    const IDR: &[u8] = &[0x65, 0x88, 0x84, 0x00, 0x33];
+    const NON_IDR: &[u8] = &[0x41, 0x9A, 0x02, 0x04];
+
+    fn annex_b(units: &[&[u8]]) -> Vec<u8> {
+        units
+            .iter()
+            .flat_map(|u| [&[0u8, 0, 0, 1][..], u].concat())
+            .collect()
+    }
+
+    fn frame(pts: i64, keyframe: bool) -> VideoFrameProxy {
+        let mut frame = gen_empty_frame();
+        frame.set_time_base((1, 1000));
+        frame.set_pts(pts);
+        frame.set_codec(Some("h264".to_string()));
+        frame.set_transcoding_method(VideoFrameTranscodingMethod::Encoded);
+        frame.set_keyframe(Some(keyframe));
+        let units = if keyframe {
+            annex_b(&[SPS, PPS, IDR])
+        } else {
+            annex_b(&[NON_IDR])
+        };
+        frame.set_content(VideoFrameContent::internal(units));
+        frame
+    }
+
+    #[test]
+    fn test_element_size() {
+        assert_eq!(element_size(0), vec![0x80]);
+        assert_eq!(element_size(126), vec![0xFE]);
+        assert_eq!(element_size(127), vec![0x40, 0x7F]);
+        assert_eq!(element_id(SEGMENT), vec![0x18, 0x53, 0x80, 0x67]);
+    }
+
+    #[test]
+    fn test_split_annex_b() -> anyhow::Result<()> {
+        let data = [&[0u8, 0, 1][..], SPS, &[0, 0, 0, 1], PPS].concat();
+        assert_eq!(split_annex_b(&data)?, vec![SPS, PPS]);
+        assert!(split_annex_b(IDR).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_avc_config() -> anyhow::Result<()> {
+        let record = ClipCodec::H264.codec_private(&[SPS, PPS, IDR]).unwrap();
+        assert_eq!(&record[..6], &[1, 0x42, 0x00, 0x1E, 0xFF, 0xE1]);
+        assert!(ClipCodec::H264.codec_private(&[IDR]).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_muxer() -> anyhow::Result<()> {
+        let directory = Path::new("/tmp/test/remux");
+        std::fs::remove_dir_all(directory).unwrap_or_default();
+        let mut muxer = ClipMuxer::new(directory, Duration::from_millis(100))?;
+        assert!(!muxer.add_frame(&frame(0, false))?);
+        for pts in (40..=200).step_by(40) {
+            assert!(muxer.add_frame(&frame(pts, pts == 40 || pts == 160))?);
+        }
+        let closed = muxer.close()?;
+        assert_eq!(closed.len(), 1);
+
+        // the keyframe at 160 ms starts the second segment, each segment has the sidecar
+        assert_eq!(std::fs::read_dir(directory.join("test"))?.count(), 4);
+        let data = std::fs::read(&closed[0])?;
+        assert_eq!(&data[..4], &[0x1A, 0x45, 0xDF, 0xA3]);
+        let sidecar: Value =
+            serde_json::from_slice(&std::fs::read(closed[0].with_extension("json"))?)?;
+        assert_eq!(sidecar["source_id"], "test");
+        assert_eq!(sidecar["frames"].as_array().unwrap().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_frame() {
+        let mut frame = frame(0, true);
+        frame.set_transcoding_method(VideoFrameTranscodingMethod::Copy);
+        let mut muxer =
+            ClipMuxer::new(Path::new("/tmp/test/remux-unsupported"), Duration::ZERO).unwrap();
+        assert!(muxer.add_frame(&frame).is_err());
+    }
+}
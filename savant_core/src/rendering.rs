@@ -0,0 +1,577 @@
+//! Rasterization of the draw specifications onto RGB and RGBA frame buffers.
+//!
+//! The shapes are drawn onto a transparent overlay with [`tiny_skia`] and composited onto the
+//! buffer when the [`Canvas`] is finished, the blur is applied to the buffer immediately. Labels
+//! are rendered with the font passed to [`Renderer::new`].
+//!
+use crate::draw::{ColorDraw, DrawSpecRegistry, LabelDraw, LabelPositionKind, ObjectDraw};
+use crate::primitives::frame::VideoFrameProxy;
+use crate::primitives::object::ObjectOperations;
+use crate::primitives::{PolygonalArea, RBBox};
+use ab_glyph::{point, Font, FontArc, PxScale, ScaleFont};
+use anyhow::{bail, Result};
+use tiny_skia::{
+    FillRule, Paint, Path, PathBuilder, Pixmap, PremultipliedColorU8, Rect, Stroke, Transform,
+};
+
+/// The font size in pixels of the labels with the font scale 1.0.
+///
+const BASE_FONT_SIZE: f32 = 24.0;
+
+/// The frame buffer being drawn on. The drawings are visible in the buffer after
+/// [`Canvas::finish`] is called.
+///
+pub struct Canvas<'a> {
+    buffer: &'a mut [u8],
+    width: u32,
+    height: u32,
+    channels: u32,
+    overlay: Pixmap,
+}
+
+impl<'a> Canvas<'a> {
+    /// Wraps the row-major buffer of 8-bit RGB (3 channels) or RGBA (4 channels) pixels.
+    ///
+    pub fn new(buffer: &'a mut [u8], width: u32, height: u32, channels: u32) -> Result<Self> {
+        if channels != 3 && channels != 4 {
+            bail!("The number of channels must be 3 or 4, got {}", channels);
+        }
+        let expected = width as usize * height as usize * channels as usize;
+        if buffer.len() != expected {
+            bail!(
+                "The buffer size {} does not match the frame {}x{}x{}",
+                buffer.len(),
+                width,
+                height,
+                channels
+            );
+        }
+        let Some(overlay) = Pixmap::new(width, height) else {
+            bail!("Unable to create the overlay of {}x{}", width, height);
+        };
+        Ok(Self {
+            buffer,
+            width,
+            height,
+            channels,
+            overlay,
+        })
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Composites the overlay onto the buffer.
+    ///
+    pub fn finish(self) {
+        let channels = self.channels as usize;
+        for (pixel, src) in self
+            .buffer
+            .chunks_exact_mut(channels)
+            .zip(self.overlay.pixels())
+        {
+            let alpha = src.alpha();
+            if alpha == 0 {
+                continue;
+            }
+            let inverse = 255 - alpha as u32;
+            let blend = |s: u8, d: u8| (s as u32 + (d as u32 * inverse + 127) / 255) as u8;
+            pixel[0] = blend(src.red(), pixel[0]);
+            pixel[1] = blend(src.green(), pixel[1]);
+            pixel[2] = blend(src.blue(), pixel[2]);
+            if channels == 4 {
+                pixel[3] = blend(alpha, pixel[3]);
+            }
+        }
+    }
+
+    fn fill(&mut self, path: &Path, color: &ColorDraw) {
+        if color.alpha == 0 {
+            return;
+        }
+        self.overlay.fill_path(
+            path,
+            &paint(color),
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn stroke(&mut self, path: &Path, color: &ColorDraw, thickness: i64) {
+        if color.alpha == 0 || thickness == 0 {
+            return;
+        }
+        let stroke = Stroke {
+            width: thickness as f32,
+            ..Stroke::default()
+        };
+        self.overlay
+            .stroke_path(path, &paint(color), &stroke, Transform::identity(), None);
+    }
+
+    /// Blends the color with the coverage into the overlay pixel.
+    ///
+    fn blend(&mut self, x: i32, y: i32, color: &ColorDraw, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let alpha = color.alpha as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+        let index = y as usize * self.width as usize + x as usize;
+        let dst = self.overlay.pixels_mut()[index];
+        let mix = |s: i64, d: u8| (s as f32 * alpha + d as f32 * (1.0 - alpha)).round() as u8;
+        let (r, g, b, a) = (
+            mix(color.red, dst.red()),
+            mix(color.green, dst.green()),
+            mix(color.blue, dst.blue()),
+            mix(255, dst.alpha()),
+        );
+        if let Some(c) = PremultipliedColorU8::from_rgba(r, g, b, a) {
+            self.overlay.pixels_mut()[index] = c;
+        }
+    }
+
+    /// Blurs the buffer area covered by the box with the box filter applied twice.
+    ///
+    fn blur(&mut self, bbox: &RBBox) {
+        let wrapping = bbox.get_wrapping_bbox();
+        let (xc, yc, w, h) = wrapping.as_xcycwh();
+        let clip = |v: f32, max: u32| v.round().clamp(0.0, max as f32) as usize;
+        let (left, right) = (
+            clip(xc - w / 2.0, self.width),
+            clip(xc + w / 2.0, self.width),
+        );
+        let (top, bottom) = (
+            clip(yc - h / 2.0, self.height),
+            clip(yc + h / 2.0, self.height),
+        );
+        if right <= left || bottom <= top {
+            return;
+        }
+        let radius = ((right - left).min(bottom - top) / 8).max(1);
+        let channels = self.channels as usize;
+        let stride = self.width as usize * channels;
+        for _ in 0..2 {
+            for y in top..bottom {
+                let row = &mut self.buffer[y * stride..(y + 1) * stride];
+                box_filter(row, channels, left, right, radius);
+            }
+            let mut column = vec![0; (bottom - top) * channels];
+            for x in left..right {
+                for y in top..bottom {
+                    let (src, dst) = (y * stride + x * channels, (y - top) * channels);
+                    column[dst..dst + channels].copy_from_slice(&self.buffer[src..src + channels]);
+                }
+                box_filter(&mut column, channels, 0, bottom - top, radius);
+                for y in top..bottom {
+                    let (dst, src) = (y * stride + x * channels, (y - top) * channels);
+                    self.buffer[dst..dst + channels].copy_from_slice(&column[src..src + channels]);
+                }
+            }
+        }
+    }
+}
+
+/// Averages the pixels in `[start, end)` of the line over the window of `2 * radius + 1`.
+///
+fn box_filter(line: &mut [u8], channels: usize, start: usize, end: usize, radius: usize) {
+    let source = line[start * channels..end * channels].to_vec();
+    let len = end - start;
+    for c in 0..channels {
+        let mut prefix = vec![0u32; len + 1];
+        for i in 0..len {
+            prefix[i + 1] = prefix[i] + source[i * channels + c] as u32;
+        }
+        for i in 0..len {
+            let (from, to) = (i.saturating_sub(radius), (i + radius + 1).min(len));
+            let sum = prefix[to] - prefix[from];
+            line[(start + i) * channels + c] = (sum / (to - from) as u32) as u8;
+        }
+    }
+}
+
+fn paint(color: &ColorDraw) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(
+        color.red as u8,
+        color.green as u8,
+        color.blue as u8,
+        color.alpha as u8,
+    );
+    paint.anti_alias = true;
+    paint
+}
+
+fn polygon_path(vertices: impl IntoIterator<Item = (f32, f32)>) -> Option<Path> {
+    let mut builder = PathBuilder::new();
+    for (i, (x, y)) in vertices.into_iter().enumerate() {
+        if i == 0 {
+            builder.move_to(x, y);
+        } else {
+            builder.line_to(x, y);
+        }
+    }
+    builder.close();
+    builder.finish()
+}
+
+/// Draws objects, labels and polygons according to the draw specifications.
+///
+#[derive(Clone, Default)]
+pub struct Renderer {
+    font: Option<FontArc>,
+}
+
+impl Renderer {
+    /// Creates the renderer with the TrueType or OpenType font used for labels. The labels with
+    /// text cannot be drawn without the font.
+    ///
+    pub fn new(font: Option<Vec<u8>>) -> Result<Self> {
+        let font = match font {
+            Some(data) => match FontArc::try_from_vec(data) {
+                Ok(font) => Some(font),
+                Err(e) => bail!("Unable to load the font: {}", e),
+            },
+            None => None,
+        };
+        Ok(Self { font })
+    }
+
+    pub fn has_font(&self) -> bool {
+        self.font.is_some()
+    }
+
+    /// Draws the box, the central dot and the label of the object, blurring the box area first
+    /// when requested. The label lines are drawn in the order given.
+    ///
+    pub fn draw_object(
+        &self,
+        canvas: &mut Canvas,
+        bbox: &RBBox,
+        spec: &ObjectDraw,
+        label: &[String],
+    ) -> Result<()> {
+        if spec.blur {
+            canvas.blur(bbox);
+        }
+        let mut visual = bbox.clone();
+        if let Some(b) = &spec.bounding_box {
+            visual = bbox.new_padded(&b.padding);
+            if let Some(path) = polygon_path(visual.get_vertices()) {
+                canvas.fill(&path, &b.background_color);
+                canvas.stroke(&path, &b.border_color, b.thickness);
+            }
+        }
+        if let Some(d) = &spec.central_dot {
+            if let Some(path) =
+                PathBuilder::from_circle(bbox.get_xc(), bbox.get_yc(), d.radius as f32)
+            {
+                canvas.fill(&path, &d.color);
+            }
+        }
+        if let Some(l) = &spec.label {
+            self.draw_label(canvas, &visual, l, label)?;
+        }
+        Ok(())
+    }
+
+    /// Draws the polygon border and, optionally, its fill.
+    ///
+    pub fn draw_polygon(
+        &self,
+        canvas: &mut Canvas,
+        area: &PolygonalArea,
+        color: &ColorDraw,
+        thickness: i64,
+        fill: Option<&ColorDraw>,
+    ) -> Result<()> {
+        if !(0..=500).contains(&thickness) {
+            bail!("thickness must be >= 0 and <= 500")
+        }
+        let Some(path) = polygon_path(area.get_vertices().iter().map(|p| (p.x, p.y))) else {
+            bail!("The polygon must have at least two vertices");
+        };
+        if let Some(fill) = fill {
+            canvas.fill(&path, fill);
+        }
+        canvas.stroke(&path, color, thickness);
+        Ok(())
+    }
+
+    /// Draws the objects of the frame having specifications in the registry. The track box is
+    /// drawn for the tracked objects, the detection box otherwise.
+    ///
+    pub fn draw_frame(
+        &self,
+        canvas: &mut Canvas,
+        frame: &VideoFrameProxy,
+        registry: &DrawSpecRegistry,
+    ) -> Result<()> {
+        for object in frame.get_all_objects() {
+            let Some(spec) = registry.lookup(&object.get_namespace(), &object.get_label()) else {
+                continue;
+            };
+            let label = spec
+                .label
+                .as_ref()
+                .map(|l| l.render(&object))
+                .unwrap_or_default();
+            let bbox = object
+                .get_track_box()
+                .unwrap_or_else(|| object.get_detection_box());
+            self.draw_object(canvas, &bbox, spec, &label)?;
+        }
+        Ok(())
+    }
+
+    fn draw_label(
+        &self,
+        canvas: &mut Canvas,
+        bbox: &RBBox,
+        spec: &LabelDraw,
+        lines: &[String],
+    ) -> Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let Some(font) = &self.font else {
+            bail!("The font is required to draw labels");
+        };
+        let font = font.as_scaled(PxScale::from(BASE_FONT_SIZE * spec.font_scale as f32));
+        let line_height = font.height() + font.line_gap();
+        let text_width = lines
+            .iter()
+            .map(|l| {
+                l.chars()
+                    .map(|c| font.h_advance(font.glyph_id(c)))
+                    .sum::<f32>()
+            })
+            .fold(0.0, f32::max);
+        let text_height = line_height * lines.len() as f32 - font.line_gap();
+
+        let (padding, border) = (&spec.padding, spec.thickness as f32);
+        let block_width = text_width + (padding.left + padding.right) as f32 + 2.0 * border;
+        let block_height = text_height + (padding.top + padding.bottom) as f32 + 2.0 * border;
+        let wrapping = bbox.get_wrapping_bbox();
+        let (xc, yc, w, h) = wrapping.as_xcycwh();
+        let (mx, my) = (spec.position.margin_x as f32, spec.position.margin_y as f32);
+        let (left, top) = match spec.position.position {
+            LabelPositionKind::TopLeftInside => (xc - w / 2.0 + mx, yc - h / 2.0 + my),
+            LabelPositionKind::TopLeftOutside => {
+                (xc - w / 2.0 + mx, yc - h / 2.0 + my - block_height)
+            }
+            LabelPositionKind::Center => {
+                (xc + mx - block_width / 2.0, yc + my - block_height / 2.0)
+            }
+        };
+
+        if let Some(rect) = Rect::from_xywh(left, top, block_width, block_height) {
+            let path = PathBuilder::from_rect(rect);
+            canvas.fill(&path, &spec.background_color);
+            if border > 0.0 {
+                if let Some(inner) = Rect::from_xywh(
+                    left + border / 2.0,
+                    top + border / 2.0,
+                    block_width - border,
+                    block_height - border,
+                ) {
+                    canvas.stroke(
+                        &PathBuilder::from_rect(inner),
+                        &spec.border_color,
+                        spec.thickness,
+                    );
+                }
+            }
+        }
+
+        let text_left = left + border + padding.left as f32;
+        let mut baseline = top + border + padding.top as f32 + font.ascent();
+        for line in lines {
+            let mut caret = text_left;
+            let mut previous = None;
+            for c in line.chars() {
+                let id = font.glyph_id(c);
+                if let Some(previous) = previous {
+                    caret += font.kern(previous, id);
+                }
+                let glyph = id.with_scale_and_position(font.scale(), point(caret, baseline));
+                caret += font.h_advance(id);
+                previous = Some(id);
+                if let Some(outlined) = font.outline_glyph(glyph) {
+                    let bounds = outlined.px_bounds();
+                    outlined.draw(|x, y, coverage| {
+                        canvas.blend(
+                            bounds.min.x as i32 + x as i32,
+                            bounds.min.y as i32 + y as i32,
+                            &spec.font_color,
+                            coverage,
+                        );
+                    });
+                }
+            }
+            baseline += line_height;
+        }
+        Ok(())
+    }
+
+    /// Draws the objects of the frame onto the buffer, see [`Canvas::new`] and
+    /// [`Renderer::draw_frame`].
+    ///
+    pub fn render_frame(
+        &self,
+        buffer: &mut [u8],
+        width: u32,
+        height: u32,
+        channels: u32,
+        frame: &VideoFrameProxy,
+        registry: &DrawSpecRegistry,
+    ) -> Result<()> {
+        let mut canvas = Canvas::new(buffer, width, height, channels)?;
+        self.draw_frame(&mut canvas, frame, registry)?;
+        canvas.finish();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::{BoundingBoxDraw, DotDraw, LabelPosition, PaddingDraw};
+    use crate::primitives::object::IdCollisionResolutionPolicy;
+    use crate::primitives::Point;
+    use crate::test::{gen_empty_frame, gen_object};
+
+    const WIDTH: u32 = 40;
+    const HEIGHT: u32 = 30;
+
+    fn pixel(buffer: &[u8], channels: u32, x: u32, y: u32) -> &[u8] {
+        let index = ((y * WIDTH + x) * channels) as usize;
+        &buffer[index..index + channels as usize]
+    }
+
+    fn box_spec() -> ObjectDraw {
+        ObjectDraw::new(
+            Some(
+                BoundingBoxDraw::new(
+                    ColorDraw::new(255, 0, 0, 255).unwrap(),
+                    ColorDraw::transparent().unwrap(),
+                    2,
+                    PaddingDraw::default_padding(),
+                )
+                .unwrap(),
+            ),
+            Some(DotDraw::new(ColorDraw::new(0, 0, 255, 255).unwrap(), 2).unwrap()),
+            None,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_draw_object() {
+        for channels in [3, 4] {
+            let mut buffer = vec![0; (WIDTH * HEIGHT * channels) as usize];
+            let renderer = Renderer::default();
+            let mut canvas = Canvas::new(&mut buffer, WIDTH, HEIGHT, channels).unwrap();
+            renderer
+                .draw_object(
+                    &mut canvas,
+                    &RBBox::ltrb(10.0, 10.0, 30.0, 20.0),
+                    &box_spec(),
+                    &[],
+                )
+                .unwrap();
+            canvas.finish();
+            assert_eq!(&pixel(&buffer, channels, 10, 15)[..3], &[255, 0, 0]);
+            assert_eq!(&pixel(&buffer, channels, 20, 15)[..3], &[0, 0, 255]);
+            assert_eq!(&pixel(&buffer, channels, 15, 13)[..3], &[0, 0, 0]);
+            assert_eq!(&pixel(&buffer, channels, 2, 2)[..3], &[0, 0, 0]);
+            if channels == 4 {
+                assert_eq!(pixel(&buffer, channels, 10, 15)[3], 255);
+            }
+        }
+    }
+
+    #[test]
+    fn test_blur_and_polygon() {
+        let mut buffer = (0..WIDTH * HEIGHT * 3)
+            .map(|i| if (i / 3) % 2 == 0 { 200 } else { 0 })
+            .collect::<Vec<u8>>();
+        let renderer = Renderer::default();
+        let mut canvas = Canvas::new(&mut buffer, WIDTH, HEIGHT, 3).unwrap();
+        let spec = ObjectDraw::new(None, None, None, true);
+        renderer
+            .draw_object(&mut canvas, &RBBox::ltrb(0.0, 0.0, 20.0, 20.0), &spec, &[])
+            .unwrap();
+        let area = PolygonalArea::new(
+            vec![
+                Point::new(25.0, 5.0),
+                Point::new(35.0, 5.0),
+                Point::new(35.0, 15.0),
+                Point::new(25.0, 15.0),
+            ],
+            None,
+        );
+        let green = ColorDraw::new(0, 255, 0, 255).unwrap();
+        renderer
+            .draw_polygon(&mut canvas, &area, &green, 1, Some(&green))
+            .unwrap();
+        canvas.finish();
+        // the stripes are averaged inside the box and left intact outside
+        let blurred = pixel(&buffer, 3, 10, 10)[0];
+        assert!((50..150).contains(&blurred), "{}", blurred);
+        assert_eq!(pixel(&buffer, 3, 30, 25)[0], 200);
+        assert_eq!(pixel(&buffer, 3, 30, 10), &[0, 255, 0]);
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let frame = gen_empty_frame();
+        let mut object = gen_object(1);
+        object.detection_box = RBBox::ltrb(10.0, 10.0, 30.0, 20.0);
+        object.track_box = None;
+        let (namespace, label) = (object.namespace.clone(), object.label.clone());
+        frame
+            .add_object(object, IdCollisionResolutionPolicy::Error)
+            .unwrap();
+
+        let mut registry = DrawSpecRegistry::default();
+        let renderer = Renderer::default();
+        let mut buffer = vec![0; (WIDTH * HEIGHT * 3) as usize];
+        renderer
+            .render_frame(&mut buffer, WIDTH, HEIGHT, 3, &frame, &registry)
+            .unwrap();
+        assert!(buffer.iter().all(|v| *v == 0));
+
+        registry.insert(&namespace, DrawSpecRegistry::WILDCARD, box_spec());
+        renderer
+            .render_frame(&mut buffer, WIDTH, HEIGHT, 3, &frame, &registry)
+            .unwrap();
+        assert_eq!(pixel(&buffer, 3, 10, 15), &[255, 0, 0]);
+
+        let mut spec = box_spec();
+        spec.label = Some(
+            LabelDraw::new(
+                ColorDraw::new(255, 255, 255, 255).unwrap(),
+                ColorDraw::transparent().unwrap(),
+                ColorDraw::transparent().unwrap(),
+                0.5,
+                1,
+                LabelPosition::default_position().unwrap(),
+                PaddingDraw::default_padding(),
+                vec!["{label}".to_string()],
+            )
+            .unwrap(),
+        );
+        registry.insert(&namespace, &label, spec);
+        assert!(renderer
+            .render_frame(&mut buffer, WIDTH, HEIGHT, 3, &frame, &registry)
+            .is_err());
+        assert!(Canvas::new(&mut buffer, WIDTH, HEIGHT, 4).is_err());
+        assert!(Renderer::new(Some(vec![0, 1, 2])).is_err());
+    }
+}
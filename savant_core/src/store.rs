@@ -0,0 +1,535 @@
+use crate::message::Message;
+use crate::protobuf::{deserialize, serialize};
+use crate::transport::zeromq::{ReaderResult, StatsCollector, TransportStats};
+use crate::transport::TransportReader;
+use anyhow::{bail, Context};
+use parking_lot::Mutex;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const LOG_MAGIC: &[u8; 4] = b"SVST";
+const LOG_VERSION: u8 = 1;
+const LOG_HEADER_SIZE: u64 = 5;
+const INDEX_ENTRY_SIZE: usize = 16;
+
+/// The record of the message log: the time the message was captured, the topic (source id), the
+/// message and the extra frames.
+///
+#[derive(Debug, Clone)]
+pub struct StoreRecord {
+    pub timestamp: SystemTime,
+    pub topic: Vec<u8>,
+    pub message: Message,
+    pub data: Vec<Vec<u8>>,
+}
+
+fn index_path(path: &Path) -> PathBuf {
+    let mut index = path.as_os_str().to_owned();
+    index.push(".idx");
+    PathBuf::from(index)
+}
+
+fn micros(timestamp: SystemTime) -> u64 {
+    timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
+
+fn encode_record(
+    timestamp: SystemTime,
+    topic: &[u8],
+    message: &[u8],
+    data: &[&[u8]],
+) -> anyhow::Result<Vec<u8>> {
+    if topic.len() > u16::MAX as usize || data.len() > u16::MAX as usize {
+        bail!("The topic or the number of the extra frames is too large to store.");
+    }
+    let mut body = Vec::with_capacity(message.len() + topic.len() + 32);
+    body.extend(micros(timestamp).to_le_bytes());
+    body.extend((topic.len() as u16).to_le_bytes());
+    body.extend(topic);
+    body.extend((message.len() as u32).to_le_bytes());
+    body.extend(message);
+    body.extend((data.len() as u16).to_le_bytes());
+    for part in data {
+        body.extend((part.len() as u32).to_le_bytes());
+        body.extend(*part);
+    }
+    let mut record = Vec::with_capacity(body.len() + 8);
+    record.extend((body.len() as u32).to_le_bytes());
+    record.extend(crc32fast::hash(&body).to_le_bytes());
+    record.extend(body);
+    Ok(record)
+}
+
+struct RecordCursor<'a>(&'a [u8]);
+
+impl<'a> RecordCursor<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        if self.0.len() < n {
+            bail!("The store record is truncated.");
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> anyhow::Result<usize> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into()?) as usize)
+    }
+
+    fn u32(&mut self) -> anyhow::Result<usize> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?) as usize)
+    }
+
+    fn u64(&mut self) -> anyhow::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+}
+
+fn decode_record(body: &[u8]) -> anyhow::Result<StoreRecord> {
+    let mut cursor = RecordCursor(body);
+    let timestamp = UNIX_EPOCH + Duration::from_micros(cursor.u64()?);
+    let topic_len = cursor.u16()?;
+    let topic = cursor.take(topic_len)?.to_vec();
+    let message_len = cursor.u32()?;
+    let message = deserialize(cursor.take(message_len)?)?;
+    let count = cursor.u16()?;
+    let mut data = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = cursor.u32()?;
+        data.push(cursor.take(len)?.to_vec());
+    }
+    Ok(StoreRecord {
+        timestamp,
+        topic,
+        message,
+        data,
+    })
+}
+
+/// Appends the messages to the length-prefixed log, the records are protected with CRC32. The
+/// index of the record offsets and timestamps is kept in the `.idx` file next to the log.
+///
+pub struct StoreWriter {
+    log: BufWriter<File>,
+    index: BufWriter<File>,
+    offset: u64,
+    records: usize,
+}
+
+impl StoreWriter {
+    /// Creates the log, the existing log and its index are truncated.
+    ///
+    pub fn create(path: &Path) -> anyhow::Result<Self> {
+        let mut log = BufWriter::new(File::create(path)?);
+        log.write_all(LOG_MAGIC)?;
+        log.write_all(&[LOG_VERSION])?;
+        let index = BufWriter::new(File::create(index_path(path))?);
+        Ok(Self {
+            log,
+            index,
+            offset: LOG_HEADER_SIZE,
+            records: 0,
+        })
+    }
+
+    /// Opens the log for appending, the index is rebuilt when it does not match the log.
+    ///
+    pub fn append_to(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Self::create(path);
+        }
+        let reader = StoreReader::open(path)?;
+        let offset = reader.end_offset;
+        let records = reader.len();
+        let mut index = BufWriter::new(File::create(index_path(path))?);
+        for (timestamp, record_offset) in &reader.index {
+            index.write_all(&timestamp.to_le_bytes())?;
+            index.write_all(&record_offset.to_le_bytes())?;
+        }
+        let log_file = OpenOptions::new().write(true).open(path)?;
+        // the incomplete tail record is overwritten
+        log_file.set_len(offset)?;
+        let mut log = BufWriter::new(log_file);
+        log.seek(SeekFrom::Start(offset))?;
+        Ok(Self {
+            log,
+            index,
+            offset,
+            records,
+        })
+    }
+
+    pub fn append(&mut self, topic: &str, message: &Message, data: &[&[u8]]) -> anyhow::Result<()> {
+        self.append_at(SystemTime::now(), topic.as_bytes(), message, data)
+    }
+
+    pub fn append_at(
+        &mut self,
+        timestamp: SystemTime,
+        topic: &[u8],
+        message: &Message,
+        data: &[&[u8]],
+    ) -> anyhow::Result<()> {
+        let record = encode_record(timestamp, topic, &serialize(message)?, data)?;
+        self.log.write_all(&record)?;
+        self.index.write_all(&micros(timestamp).to_le_bytes())?;
+        self.index.write_all(&self.offset.to_le_bytes())?;
+        self.offset += record.len() as u64;
+        self.records += 1;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records == 0
+    }
+
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        self.log.flush()?;
+        self.index.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for StoreWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Reads the records of the log by position or by time. The index is loaded from the `.idx` file
+/// or rebuilt by scanning the log when the file is missing or does not match the log; a
+/// truncated tail record (e.g. after a crash) is ignored.
+///
+pub struct StoreReader {
+    log: BufReader<File>,
+    index: Vec<(u64, u64)>,
+    end_offset: u64,
+}
+
+impl StoreReader {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut log = BufReader::new(
+            File::open(path).with_context(|| format!("Failed to open store {:?}", path))?,
+        );
+        let mut header = [0u8; LOG_HEADER_SIZE as usize];
+        log.read_exact(&mut header)?;
+        if &header[..4] != LOG_MAGIC {
+            bail!("{:?} is not a message store.", path);
+        }
+        if header[4] != LOG_VERSION {
+            bail!("Unsupported message store version {}.", header[4]);
+        }
+        let log_len = log.get_ref().metadata()?.len();
+        let mut reader = Self {
+            log,
+            index: Vec::new(),
+            end_offset: LOG_HEADER_SIZE,
+        };
+        if !reader.load_index(&index_path(path), log_len)? {
+            reader.scan(log_len)?;
+        }
+        Ok(reader)
+    }
+
+    fn load_index(&mut self, path: &Path, log_len: u64) -> anyhow::Result<bool> {
+        let Ok(bytes) = std::fs::read(path) else {
+            return Ok(false);
+        };
+        let index = bytes
+            .chunks_exact(INDEX_ENTRY_SIZE)
+            .map(|entry| {
+                let (timestamp, offset) = entry.split_at(8);
+                (
+                    u64::from_le_bytes(timestamp.try_into().unwrap()),
+                    u64::from_le_bytes(offset.try_into().unwrap()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let end_offset = match index.last() {
+            Some((_, offset)) => match self.record_len(*offset) {
+                Ok(len) => offset + len,
+                Err(_) => return Ok(false),
+            },
+            None => LOG_HEADER_SIZE,
+        };
+        if end_offset > log_len || (end_offset != log_len && !self.is_truncated(end_offset)?) {
+            return Ok(false);
+        }
+        self.index = index;
+        self.end_offset = end_offset;
+        Ok(true)
+    }
+
+    fn record_len(&mut self, offset: u64) -> anyhow::Result<u64> {
+        self.log.seek(SeekFrom::Start(offset))?;
+        let mut len = [0u8; 4];
+        self.log.read_exact(&mut len)?;
+        Ok(u32::from_le_bytes(len) as u64 + 8)
+    }
+
+    fn read_body(&mut self, offset: u64) -> anyhow::Result<Vec<u8>> {
+        self.log.seek(SeekFrom::Start(offset))?;
+        let mut prefix = [0u8; 8];
+        self.log.read_exact(&mut prefix)?;
+        let len = u32::from_le_bytes(prefix[..4].try_into()?) as usize;
+        let crc = u32::from_le_bytes(prefix[4..].try_into()?);
+        let remaining = self
+            .log
+            .get_ref()
+            .metadata()?
+            .len()
+            .saturating_sub(offset + 8);
+        if len as u64 > remaining {
+            bail!(
+                "The store record at offset {} of {} bytes exceeds the remaining {} bytes.",
+                offset,
+                len,
+                remaining
+            );
+        }
+        let mut body = vec![0u8; len];
+        self.log.read_exact(&mut body)?;
+        if crc32fast::hash(&body) != crc {
+            bail!("The store record at offset {} is corrupted.", offset);
+        }
+        Ok(body)
+    }
+
+    fn is_truncated(&mut self, offset: u64) -> anyhow::Result<bool> {
+        Ok(self.read_body(offset).is_err())
+    }
+
+    fn scan(&mut self, log_len: u64) -> anyhow::Result<()> {
+        let mut offset = LOG_HEADER_SIZE;
+        let mut index = Vec::new();
+        while offset < log_len {
+            let Ok(body) = self.read_body(offset) else {
+                break;
+            };
+            let timestamp = RecordCursor(&body).u64()?;
+            index.push((timestamp, offset));
+            offset += body.len() as u64 + 8;
+        }
+        self.index = index;
+        self.end_offset = offset;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn read(&mut self, position: usize) -> anyhow::Result<StoreRecord> {
+        let Some((_, offset)) = self.index.get(position).copied() else {
+            bail!("Store record {} is out of range.", position);
+        };
+        decode_record(&self.read_body(offset)?)
+    }
+
+    /// Returns the position of the first record captured at or after the time.
+    ///
+    pub fn position_at(&self, timestamp: SystemTime) -> usize {
+        let timestamp = micros(timestamp);
+        self.index.partition_point(|(t, _)| *t < timestamp)
+    }
+}
+
+/// Defines how fast the records are replayed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// The intervals between the records are the same as when they were captured.
+    Paced,
+    /// The records are returned as fast as they are read.
+    MaxSpeed,
+}
+
+struct ReplayState {
+    reader: StoreReader,
+    position: usize,
+    started: Option<(Instant, u64)>,
+}
+
+/// Replays the log through the transport reader interface, so the pipelines can be tested with
+/// the captured traffic. The reader stops when all the records are returned; after that
+/// [`TransportReader::receive`] returns [`ReaderResult::Timeout`].
+///
+pub struct StoreReplayer {
+    state: Mutex<ReplayState>,
+    mode: ReplayMode,
+    running: AtomicBool,
+    stats: StatsCollector,
+}
+
+impl StoreReplayer {
+    pub fn new(path: &Path, mode: ReplayMode) -> anyhow::Result<Self> {
+        Self::starting_at(path, mode, 0)
+    }
+
+    pub fn starting_at(path: &Path, mode: ReplayMode, position: usize) -> anyhow::Result<Self> {
+        let reader = StoreReader::open(path)?;
+        let running = position < reader.len();
+        Ok(Self {
+            state: Mutex::new(ReplayState {
+                reader,
+                position,
+                started: None,
+            }),
+            mode,
+            running: AtomicBool::new(running),
+            stats: StatsCollector::new("replayer", &path.to_string_lossy(), false),
+        })
+    }
+}
+
+impl TransportReader for StoreReplayer {
+    fn receive(&self) -> anyhow::Result<ReaderResult> {
+        let mut state = self.state.lock();
+        if !self.is_started() || state.position >= state.reader.len() {
+            self.running.store(false, Ordering::Relaxed);
+            return Ok(ReaderResult::Timeout);
+        }
+        let position = state.position;
+        let record = state.reader.read(position)?;
+        state.position += 1;
+        if state.position >= state.reader.len() {
+            self.running.store(false, Ordering::Relaxed);
+        }
+        if self.mode == ReplayMode::Paced {
+            let captured = micros(record.timestamp);
+            let (start, first) = *state.started.get_or_insert((Instant::now(), captured));
+            let due = start + Duration::from_micros(captured.saturating_sub(first));
+            let now = Instant::now();
+            if due > now {
+                std::thread::sleep(due - now);
+            }
+        }
+        self.stats
+            .record_bytes(record.topic.len() + record.data.iter().map(Vec::len).sum::<usize>());
+        self.stats.record_message();
+        Ok(ReaderResult::message(
+            record.message,
+            &record.topic,
+            &None,
+            &record.data,
+        ))
+    }
+
+    fn is_started(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats.snapshot()
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        self.running.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::userdata::UserData;
+
+    fn write_log(path: &Path, count: u64) -> anyhow::Result<SystemTime> {
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        let start = SystemTime::now();
+        let mut writer = StoreWriter::create(path)?;
+        for i in 0..count {
+            writer.append_at(
+                start + Duration::from_millis(i * 50),
+                format!("source-{}", i).as_bytes(),
+                &Message::user_data(UserData::new("test")),
+                &[b"payload"],
+            )?;
+        }
+        writer.flush()?;
+        Ok(start)
+    }
+
+    #[test]
+    fn test_write_read() -> anyhow::Result<()> {
+        let path = Path::new("/tmp/test/store/write-read.log");
+        let start = write_log(path, 3)?;
+        let mut reader = StoreReader::open(path)?;
+        assert_eq!(reader.len(), 3);
+        let record = reader.read(1)?;
+        assert_eq!(record.topic, b"source-1");
+        assert_eq!(record.data, vec![b"payload".to_vec()]);
+        assert!(record.message.is_user_data());
+        assert_eq!(reader.position_at(start + Duration::from_millis(60)), 2);
+        assert!(reader.read(3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rebuild_index() -> anyhow::Result<()> {
+        let path = Path::new("/tmp/test/store/rebuild-index.log");
+        write_log(path, 3)?;
+        std::fs::remove_file(index_path(path))?;
+        // simulate the crash in the middle of the record
+        let file = OpenOptions::new().write(true).open(path)?;
+        file.set_len(file.metadata()?.len() - 3)?;
+        assert_eq!(StoreReader::open(path)?.len(), 2);
+
+        let mut writer = StoreWriter::append_to(path)?;
+        writer.append("source-3", &Message::user_data(UserData::new("test")), &[])?;
+        drop(writer);
+        let mut reader = StoreReader::open(path)?;
+        assert_eq!(reader.len(), 3);
+        assert_eq!(reader.read(2)?.topic, b"source-3");
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupted_length() -> anyhow::Result<()> {
+        let path = Path::new("/tmp/test/store/corrupted-length.log");
+        write_log(path, 2)?;
+        std::fs::remove_file(index_path(path))?;
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        file.seek(SeekFrom::Start(LOG_HEADER_SIZE))?;
+        file.write_all(&u32::MAX.to_le_bytes())?;
+        drop(file);
+        let mut reader = StoreReader::open(path)?;
+        assert_eq!(reader.len(), 0);
+        assert!(reader.read_body(LOG_HEADER_SIZE).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay() -> anyhow::Result<()> {
+        let path = Path::new("/tmp/test/store/replay.log");
+        write_log(path, 3)?;
+        for (mode, min_duration) in [(ReplayMode::MaxSpeed, 0), (ReplayMode::Paced, 100)] {
+            let replayer = StoreReplayer::new(path, mode)?;
+            let start = Instant::now();
+            let mut received = 0;
+            while replayer.is_started() {
+                if let ReaderResult::Message { .. } = replayer.receive()? {
+                    received += 1;
+                }
+            }
+            assert_eq!(received, 3);
+            assert!(start.elapsed() >= Duration::from_millis(min_duration));
+            assert!(matches!(replayer.receive()?, ReaderResult::Timeout));
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,24 @@
 use crate::get_or_init_async_runtime;
+use crate::otlp::current_context;
+use anyhow::{anyhow, bail, Context as _};
 use log::error;
-use opentelemetry::global;
+use opentelemetry::logs::{AnyValue, LogRecord, Logger as _, LoggerProvider as _, Severity};
+use opentelemetry::{global, KeyValue, Value};
 use opentelemetry_jaeger_propagator::Propagator;
-use opentelemetry_otlp::{SpanExporterBuilder, WithExportConfig};
+use opentelemetry_otlp::{
+    HttpExporterBuilder, LogExporterBuilder, SpanExporterBuilder, TonicExporterBuilder,
+    WithExportConfig,
+};
+use opentelemetry_sdk::logs::{Logger, LoggerProvider};
 use opentelemetry_sdk::propagation::TraceContextPropagator;
-use opentelemetry_sdk::trace::{Config, TracerProvider};
+use opentelemetry_sdk::trace::{Config, Sampler, TracerProvider};
 use opentelemetry_sdk::{runtime, Resource};
 use opentelemetry_semantic_conventions::resource::{SERVICE_NAME, SERVICE_NAMESPACE};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, MutexGuard, RwLock};
 use serde::{Deserialize, Serialize};
-use std::cell::OnceCell;
+use std::cell::{Cell, OnceCell};
 use std::fs;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum ContextPropagationFormat {
@@ -53,6 +60,30 @@ pub struct ClientTlsConfig {
     pub identity: Option<Identity>,
 }
 
+/// The head-based sampling: the ratio of the sampled root traces and whether the spans with a
+/// parent follow the sampling decision of the parent.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplerConfiguration {
+    pub ratio: f64,
+    pub parent_based: bool,
+}
+
+impl SamplerConfiguration {
+    pub fn sampler(&self) -> Sampler {
+        let root = match self.ratio {
+            r if r >= 1.0 => Sampler::AlwaysOn,
+            r if r <= 0.0 => Sampler::AlwaysOff,
+            r => Sampler::TraceIdRatioBased(r),
+        };
+        if self.parent_based {
+            Sampler::ParentBased(Box::new(root))
+        } else {
+            root
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TracerConfiguration {
     pub service_name: String,
@@ -60,12 +91,30 @@ pub struct TracerConfiguration {
     pub endpoint: String,
     pub tls: Option<ClientTlsConfig>,
     pub timeout: Option<Duration>,
+    /// All the traces are sampled when not set.
+    #[serde(default)]
+    pub sampler: Option<SamplerConfiguration>,
+}
+
+/// The export of the log records over OTLP, the records carry the trace and the span ids of
+/// the current context, so the backend correlates them with the traces.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggerConfiguration {
+    pub service_name: String,
+    pub protocol: Protocol,
+    pub endpoint: String,
+    pub tls: Option<ClientTlsConfig>,
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TelemetryConfiguration {
     pub context_propagation_format: Option<ContextPropagationFormat>,
     pub tracer: Option<TracerConfiguration>,
+    /// The logs are not exported when not set.
+    #[serde(default)]
+    pub logger: Option<LoggerConfiguration>,
 }
 
 impl TelemetryConfiguration {
@@ -73,140 +122,173 @@ impl TelemetryConfiguration {
         Self {
             context_propagation_format: Some(ContextPropagationFormat::W3C),
             tracer: None,
+            logger: None,
+        }
+    }
+}
+
+/// Builds the OTLP exporter of the traces or the logs, the TLS files are loaded from the
+/// configured paths.
+///
+fn exporter_builder<B>(
+    protocol: &Protocol,
+    endpoint: &str,
+    tls: &Option<ClientTlsConfig>,
+    timeout: Option<Duration>,
+) -> anyhow::Result<B>
+where
+    B: From<TonicExporterBuilder> + From<HttpExporterBuilder>,
+{
+    match protocol {
+        Protocol::Grpc => {
+            let mut builder = opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint.to_string());
+
+            builder = if let Some(timeout) = timeout {
+                builder.with_timeout(timeout)
+            } else {
+                builder
+            };
+
+            let mut tonic_tls_config =
+                tonic::transport::ClientTlsConfig::new().with_enabled_roots();
+
+            tonic_tls_config = if let Some(tls_config) = tls.as_ref() {
+                tonic_tls_config = if let Some(root_certificate) = tls_config.certificate.as_ref() {
+                    let buf = fs::read(root_certificate).with_context(|| {
+                        format!("Failed to load root certificate {}", root_certificate)
+                    })?;
+                    let cert = tonic::transport::Certificate::from_pem(buf);
+
+                    tonic_tls_config.ca_certificate(cert)
+                } else {
+                    tonic_tls_config
+                };
+
+                tonic_tls_config = if let Some(identity_conf) = tls_config.identity.as_ref() {
+                    let cert = fs::read(&identity_conf.certificate).with_context(|| {
+                        format!(
+                            "Failed to load identity certificate {}",
+                            identity_conf.certificate
+                        )
+                    })?;
+                    let key = fs::read(&identity_conf.key).with_context(|| {
+                        format!("Failed to load identity key {}", identity_conf.key)
+                    })?;
+                    let identity = tonic::transport::Identity::from_pem(cert, key);
+
+                    tonic_tls_config.identity(identity)
+                } else {
+                    tonic_tls_config
+                };
+
+                tonic_tls_config
+            } else {
+                tonic_tls_config
+            };
+            builder = builder.with_tls_config(tonic_tls_config);
+
+            Ok(builder.into())
+        }
+        Protocol::HttpBinary | Protocol::HttpJson => {
+            let mut builder = opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(endpoint.to_string())
+                .with_protocol(protocol.clone().into());
+
+            builder = if let Some(timeout) = timeout {
+                builder.with_timeout(timeout)
+            } else {
+                builder
+            };
+
+            let mut client_builder = reqwest::Client::builder()
+                .tls_built_in_root_certs(true)
+                .use_rustls_tls();
+            client_builder = if let Some(tls_config) = tls.as_ref() {
+                client_builder = if let Some(certificate) = tls_config.certificate.as_ref() {
+                    let buf = fs::read(certificate).with_context(|| {
+                        format!("Failed to read root certificate {}", certificate)
+                    })?;
+                    let cert = reqwest::Certificate::from_pem(&buf).with_context(|| {
+                        format!("Failed to load root certificate {}", certificate)
+                    })?;
+
+                    client_builder.add_root_certificate(cert)
+                } else {
+                    client_builder
+                };
+
+                client_builder = if let Some(identity) = tls_config.identity.as_ref() {
+                    let mut buf = Vec::new();
+                    buf.append(&mut fs::read(&identity.key).with_context(|| {
+                        format!("Failed to read identity key {}", identity.key)
+                    })?);
+                    buf.append(&mut fs::read(&identity.certificate).with_context(|| {
+                        format!(
+                            "Failed to read identity certificate {}",
+                            identity.certificate
+                        )
+                    })?);
+
+                    let identity =
+                        reqwest::Identity::from_pem(&buf).context("Failed to load identity")?;
+
+                    client_builder.identity(identity)
+                } else {
+                    client_builder
+                };
+                client_builder
+            } else {
+                client_builder
+            };
+
+            let client = client_builder
+                .build()
+                .context("Failed to create a client")?;
+            builder = builder.with_http_client(client);
+            Ok(builder.into())
         }
     }
 }
 
-pub struct Configurator;
+pub struct Configurator {
+    tracer_provider: TracerProvider,
+    logger_provider: Option<LoggerProvider>,
+}
 
 impl Configurator {
-    /// Initializes global tracer provider, propagator and error handler.
+    /// Initializes global tracer provider, propagator and error handler. The providers are built
+    /// before anything is installed globally, so on error the current configuration stays in
+    /// effect.
     ///
     /// # Panics
     /// This will panic if called outside the context of a Tokio runtime when tracer is configured.
-    pub fn new(service_namespace: &str, config: &TelemetryConfiguration) -> Self {
+    pub fn new(service_namespace: &str, config: &TelemetryConfiguration) -> anyhow::Result<Self> {
         let tracer_provider = match config.tracer.as_ref() {
             Some(tracer_config) => {
-                let exporter: SpanExporterBuilder = match tracer_config.protocol {
-                    Protocol::Grpc => {
-                        let mut builder = opentelemetry_otlp::new_exporter()
-                            .tonic()
-                            .with_endpoint(tracer_config.endpoint.clone());
-
-                        builder = if let Some(timeout) = tracer_config.timeout {
-                            builder.with_timeout(timeout)
-                        } else {
-                            builder
-                        };
-
-                        let mut tonic_tls_config =
-                            tonic::transport::ClientTlsConfig::new().with_enabled_roots();
-
-                        tonic_tls_config = if let Some(tls_config) = tracer_config.tls.as_ref() {
-                            tonic_tls_config =
-                                if let Some(root_certificate) = tls_config.certificate.as_ref() {
-                                    let buf = fs::read(root_certificate)
-                                        .expect("Failed to load root certificate");
-                                    let cert = tonic::transport::Certificate::from_pem(buf);
-
-                                    tonic_tls_config.ca_certificate(cert)
-                                } else {
-                                    tonic_tls_config
-                                };
-
-                            tonic_tls_config =
-                                if let Some(identity_conf) = tls_config.identity.as_ref() {
-                                    let cert = fs::read(&identity_conf.certificate)
-                                        .expect("Failed to load identity certificate");
-                                    let key = fs::read(&identity_conf.key)
-                                        .expect("Failed to load identity key");
-                                    let identity = tonic::transport::Identity::from_pem(cert, key);
-
-                                    tonic_tls_config.identity(identity)
-                                } else {
-                                    tonic_tls_config
-                                };
-
-                            tonic_tls_config
-                        } else {
-                            tonic_tls_config
-                        };
-                        builder = builder.with_tls_config(tonic_tls_config);
-
-                        builder.into()
-                    }
-                    Protocol::HttpBinary | Protocol::HttpJson => {
-                        let mut builder = opentelemetry_otlp::new_exporter()
-                            .http()
-                            .with_endpoint(tracer_config.endpoint.clone())
-                            .with_protocol(tracer_config.protocol.clone().into());
-
-                        builder = if let Some(timeout) = tracer_config.timeout {
-                            builder.with_timeout(timeout)
-                        } else {
-                            builder
-                        };
-
-                        let mut client_builder = reqwest::Client::builder()
-                            .tls_built_in_root_certs(true)
-                            .use_rustls_tls();
-                        client_builder = if let Some(tls_config) = tracer_config.tls.as_ref() {
-                            client_builder =
-                                if let Some(certificate) = tls_config.certificate.as_ref() {
-                                    let buf = fs::read(certificate)
-                                        .expect("Failed to read root certificate");
-                                    let cert = reqwest::Certificate::from_pem(&buf)
-                                        .expect("Failed to load root certificate");
-
-                                    client_builder.add_root_certificate(cert)
-                                } else {
-                                    client_builder
-                                };
-
-                            client_builder = if let Some(identity) = tls_config.identity.as_ref() {
-                                let mut buf = Vec::new();
-                                buf.append(
-                                    &mut fs::read(&identity.key)
-                                        .expect("Failed to read identity key"),
-                                );
-                                buf.append(
-                                    &mut fs::read(&identity.certificate)
-                                        .expect("Failed to read identity certificate"),
-                                );
-
-                                let identity = reqwest::Identity::from_pem(&buf)
-                                    .expect("Failed to load identity");
-
-                                client_builder.identity(identity)
-                            } else {
-                                client_builder
-                            };
-                            client_builder
-                        } else {
-                            client_builder
-                        };
-
-                        let client = client_builder.build().expect("Failed to create a client");
-                        builder = builder.with_http_client(client);
-                        builder.into()
-                    }
-                };
+                let exporter: SpanExporterBuilder = exporter_builder(
+                    &tracer_config.protocol,
+                    &tracer_config.endpoint,
+                    &tracer_config.tls,
+                    tracer_config.timeout,
+                )?;
+
+                let mut trace_config = Config::default().with_resource(Resource::new(vec![
+                    opentelemetry::KeyValue::new(SERVICE_NAME, tracer_config.service_name.clone()),
+                    opentelemetry::KeyValue::new(SERVICE_NAMESPACE, service_namespace.to_string()),
+                ]));
+                if let Some(sampler) = tracer_config.sampler.as_ref() {
+                    trace_config = trace_config.with_sampler(sampler.sampler());
+                }
 
                 opentelemetry_otlp::new_pipeline()
                     .tracing()
                     .with_exporter(exporter)
-                    .with_trace_config(Config::default().with_resource(Resource::new(vec![
-                        opentelemetry::KeyValue::new(
-                            SERVICE_NAME,
-                            tracer_config.service_name.clone(),
-                        ),
-                        opentelemetry::KeyValue::new(
-                            SERVICE_NAMESPACE,
-                            service_namespace.to_string(),
-                        ),
-                    ])))
+                    .with_trace_config(trace_config)
                     .install_batch(runtime::Tokio)
-                    .expect("Failed to install OpenTelemetry tracer globally")
+                    .map_err(|e| anyhow!("Failed to install OpenTelemetry tracer: {}", e))?
             }
             None => {
                 let exporter = opentelemetry_stdout::SpanExporter::builder()
@@ -217,7 +299,24 @@ impl Configurator {
                     .build()
             }
         };
-        global::set_tracer_provider(tracer_provider);
+
+        let logger_provider = config
+            .logger
+            .as_ref()
+            .map(|logger_config| Self::logger_provider(service_namespace, logger_config))
+            .transpose();
+        let logger_provider = match logger_provider {
+            Ok(logger_provider) => logger_provider,
+            Err(e) => {
+                if let Err(e) = tracer_provider.shutdown() {
+                    error!(target: "opentelemetry", "Failed to shut the tracer provider down: {}", e);
+                }
+                return Err(e);
+            }
+        };
+
+        global::set_tracer_provider(tracer_provider.clone());
+        *LOGGER.write() = logger_provider.as_ref().map(|p| p.logger("savant"));
 
         match config.context_propagation_format {
             None | Some(ContextPropagationFormat::Jaeger) => {
@@ -231,32 +330,131 @@ impl Configurator {
         global::set_error_handler(|e| {
             error!(target: "opentelemetry", "{}", e);
         })
-        .expect("Failed to set OpenTelemetry error handler");
+        .map_err(|e| anyhow!("Failed to set OpenTelemetry error handler: {}", e))?;
 
-        Self
+        Ok(Self {
+            tracer_provider,
+            logger_provider,
+        })
+    }
+
+    fn logger_provider(
+        service_namespace: &str,
+        logger_config: &LoggerConfiguration,
+    ) -> anyhow::Result<LoggerProvider> {
+        let exporter: LogExporterBuilder = exporter_builder(
+            &logger_config.protocol,
+            &logger_config.endpoint,
+            &logger_config.tls,
+            logger_config.timeout,
+        )?;
+
+        opentelemetry_otlp::new_pipeline()
+            .logging()
+            .with_exporter(exporter)
+            .with_resource(Resource::new(vec![
+                opentelemetry::KeyValue::new(SERVICE_NAME, logger_config.service_name.clone()),
+                opentelemetry::KeyValue::new(SERVICE_NAMESPACE, service_namespace.to_string()),
+            ]))
+            .install_batch(runtime::Tokio)
+            .map_err(|e| anyhow!("Failed to install OpenTelemetry logger: {}", e))
     }
 
     pub fn shutdown(&mut self) {
         global::shutdown_tracer_provider();
+        if let Some(logger_provider) = self.logger_provider.take() {
+            *LOGGER.write() = None;
+            if let Err(e) = logger_provider.shutdown() {
+                error!(target: "opentelemetry", "Failed to shut the logger provider down: {}", e);
+            }
+        }
+    }
+
+    /// Exports the pending spans of the provider replaced by a new configuration. The provider
+    /// shuts down when the last tracer created from it is dropped.
+    ///
+    fn retire(self) {
+        for result in self.tracer_provider.force_flush() {
+            if let Err(e) = result {
+                error!(target: "opentelemetry", "Failed to flush the replaced tracer provider: {}", e);
+            }
+        }
+        if let Some(logger_provider) = self.logger_provider {
+            for result in logger_provider.force_flush() {
+                if let Err(e) = result {
+                    error!(target: "opentelemetry", "Failed to flush the replaced logger provider: {}", e);
+                }
+            }
+        }
     }
 }
 
 static CONFIGURATOR: Mutex<OnceCell<Configurator>> = Mutex::new(OnceCell::new());
+static SCOPE_LOCK: Mutex<()> = Mutex::new(());
+static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+fn create_configurator(config: &TelemetryConfiguration) -> anyhow::Result<Configurator> {
+    let runtime = get_or_init_async_runtime();
+    runtime.block_on(async { Configurator::new("savant-core", config) })
+}
+
+fn configure(
+    configurator: &OnceCell<Configurator>,
+    config: &TelemetryConfiguration,
+) -> anyhow::Result<()> {
+    install(configurator, create_configurator(config)?);
+    Ok(())
+}
 
-pub fn init(config: &TelemetryConfiguration) {
+fn install(configurator: &OnceCell<Configurator>, c: Configurator) {
+    let result = configurator.set(c);
+    if result.is_err() {
+        // should not happen
+        panic!("Failed to configure OpenTelemetry");
+    }
+}
+
+/// Initializes OpenTelemetry. Fails when it is already configured, use [`reconfigure`] to
+/// replace the configuration.
+///
+pub fn init(config: &TelemetryConfiguration) -> anyhow::Result<()> {
     let configurator = CONFIGURATOR.lock();
-    match configurator.get() {
-        Some(_) => panic!("Open Telemetry has been configured"),
-        None => {
-            let runtime = get_or_init_async_runtime();
-            let c = runtime.block_on(async { Configurator::new("savant-core", config) });
-            let result = configurator.set(c);
-            if result.is_err() {
-                // should not happen
-                panic!("Failed to configure OpenTelemetry");
-            }
-        }
+    if configurator.get().is_some() {
+        bail!("OpenTelemetry has been configured, use reconfigure to replace the configuration");
+    }
+    configure(&configurator, config)
+}
+
+/// Initializes OpenTelemetry unless it is already configured. Returns `true` when the call
+/// configured it.
+///
+pub fn try_init(config: &TelemetryConfiguration) -> anyhow::Result<bool> {
+    let configurator = CONFIGURATOR.lock();
+    if configurator.get().is_some() {
+        return Ok(false);
+    }
+    configure(&configurator, config)?;
+    Ok(true)
+}
+
+/// Replaces the configuration at runtime, e.g. to raise the sampling ratio during an incident,
+/// or configures OpenTelemetry when it is not configured yet. The new tracer provider is
+/// installed before the old one is flushed, so the spans are not lost in between. When the new
+/// configuration fails, the current one stays in effect.
+///
+pub fn reconfigure(config: &TelemetryConfiguration) -> anyhow::Result<()> {
+    let mut configurator = CONFIGURATOR.lock();
+    let c = create_configurator(config)?;
+    let previous = configurator.take();
+    install(&configurator, c);
+    if let Some(previous) = previous {
+        previous.retire();
     }
+    Ok(())
+}
+
+pub fn is_initialized() -> bool {
+    CONFIGURATOR.lock().get().is_some()
 }
 
 pub fn shutdown() {
@@ -265,3 +463,346 @@ pub fn shutdown() {
         c.shutdown()
     }
 }
+
+/// Returns `true` when the log records are exported over OTLP.
+///
+pub fn log_export_enabled() -> bool {
+    LOGGER.read().is_some()
+}
+
+fn severity(level: log::Level) -> Severity {
+    match level {
+        log::Level::Error => Severity::Error,
+        log::Level::Warn => Severity::Warn,
+        log::Level::Info => Severity::Info,
+        log::Level::Debug => Severity::Debug,
+        log::Level::Trace => Severity::Trace,
+    }
+}
+
+fn any_value(value: &Value) -> AnyValue {
+    match value {
+        Value::Bool(v) => AnyValue::Boolean(*v),
+        Value::I64(v) => AnyValue::Int(*v),
+        Value::F64(v) => AnyValue::Double(*v),
+        Value::String(v) => AnyValue::String(v.clone()),
+        Value::Array(_) => AnyValue::String(value.to_string().into()),
+    }
+}
+
+/// Exports the structured log record over OTLP when the logger is configured, does nothing
+/// otherwise. The record is bound to the current Savant context, so it carries the trace and
+/// the span ids of the frame or the message being processed.
+///
+pub fn emit_log(level: log::Level, target: &str, message: &str, attributes: &[KeyValue]) {
+    let logger = LOGGER.read();
+    let Some(logger) = logger.as_ref() else {
+        return;
+    };
+    export_log(logger, level, target, message, attributes);
+}
+
+fn export_log(
+    logger: &Logger,
+    level: log::Level,
+    target: &str,
+    message: &str,
+    attributes: &[KeyValue],
+) {
+    let _guard = current_context().attach();
+    let mut record = logger.create_log_record();
+    record.set_timestamp(SystemTime::now());
+    record.set_severity_number(severity(level));
+    record.set_severity_text(level.as_str().into());
+    record.set_body(message.to_string().into());
+    record.add_attribute("log.target", target.to_string());
+    record.add_attributes(
+        attributes
+            .iter()
+            .map(|kv| (kv.key.clone(), any_value(&kv.value))),
+    );
+    logger.emit(record);
+}
+
+/// The crates of the exporter internals, together with their `_`-suffixed companions like
+/// `opentelemetry_sdk` or `hyper_util`: their records are not exported, otherwise every export
+/// would produce more records to export.
+///
+const UNEXPORTED_LOG_TARGETS: [&str; 6] =
+    ["opentelemetry", "h2", "hyper", "tonic", "tower", "reqwest"];
+
+thread_local! {
+    static EXPORTING_LOG: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The `log` logger which passes the records to the wrapped logger and exports them over OTLP
+/// like [`emit_log`] does, so the records of the Rust code carry the current span context too.
+///
+pub struct LogBridge<L> {
+    inner: L,
+}
+
+impl<L: log::Log> LogBridge<L> {
+    pub fn new(inner: L) -> Self {
+        Self { inner }
+    }
+
+    fn export(&self, record: &log::Record) {
+        let target = record.target();
+        let krate = target.split("::").next().unwrap_or(target);
+        let unexported = UNEXPORTED_LOG_TARGETS.iter().any(|t| {
+            krate
+                .strip_prefix(t)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('_'))
+        });
+        if unexported || EXPORTING_LOG.get() {
+            return;
+        }
+        let logger = LOGGER.read();
+        let Some(logger) = logger.as_ref() else {
+            return;
+        };
+        let mut attributes = Vec::with_capacity(3);
+        if let Some(module_path) = record.module_path() {
+            attributes.push(KeyValue::new("code.namespace", module_path.to_string()));
+        }
+        if let Some(file) = record.file() {
+            attributes.push(KeyValue::new("code.filepath", file.to_string()));
+        }
+        if let Some(line) = record.line() {
+            attributes.push(KeyValue::new("code.lineno", i64::from(line)));
+        }
+        EXPORTING_LOG.set(true);
+        export_log(
+            logger,
+            record.level(),
+            target,
+            &record.args().to_string(),
+            &attributes,
+        );
+        EXPORTING_LOG.set(false);
+    }
+}
+
+impl<L: log::Log> log::Log for LogBridge<L> {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner.enabled(metadata) || log_export_enabled()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+        self.export(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Installs [`LogBridge`] over `inner` as the global `log` logger.
+///
+pub fn install_log_bridge<L: log::Log + 'static>(
+    inner: L,
+    max_level: log::LevelFilter,
+) -> anyhow::Result<()> {
+    log::set_boxed_logger(Box::new(LogBridge::new(inner)))
+        .map_err(|e| anyhow!("Failed to install the logger: {}", e))?;
+    log::set_max_level(max_level);
+    Ok(())
+}
+
+/// Telemetry configured for the lifetime of the guard, meant for tests. Creating the scope
+/// replaces the current configuration, dropping it shuts the telemetry down. Scopes are
+/// exclusive: a second scope waits until the first one is dropped, so the tests of one binary
+/// which use them do not see each other's providers.
+///
+pub struct TelemetryScope {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TelemetryScope {
+    pub fn new(config: &TelemetryConfiguration) -> Self {
+        let lock = SCOPE_LOCK.lock();
+        let mut configurator = CONFIGURATOR.lock();
+        if let Some(mut c) = configurator.take() {
+            c.shutdown()
+        }
+        configure(&configurator, config).expect("Failed to configure OpenTelemetry");
+        Self { _lock: lock }
+    }
+}
+
+impl Drop for TelemetryScope {
+    fn drop(&mut self) {
+        shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_scopes() {
+        for _ in 0..3 {
+            let scope = TelemetryScope::new(&TelemetryConfiguration::no_op());
+            assert!(is_initialized());
+            assert!(!try_init(&TelemetryConfiguration::no_op()).unwrap());
+            assert!(init(&TelemetryConfiguration::no_op()).is_err());
+            drop(scope);
+        }
+    }
+
+    #[test]
+    fn test_reconfigure() -> anyhow::Result<()> {
+        let scope = TelemetryScope::new(&TelemetryConfiguration::no_op());
+        reconfigure(&TelemetryConfiguration::no_op())?;
+        assert!(is_initialized());
+        reconfigure(&TelemetryConfiguration::no_op())?;
+        assert!(is_initialized());
+        drop(scope);
+        assert!(!is_initialized());
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_reconfigure_keeps_configuration() {
+        let scope = TelemetryScope::new(&TelemetryConfiguration::no_op());
+        let tls = Some(ClientTlsConfig {
+            certificate: Some("/nonexistent/ca.pem".to_string()),
+            identity: None,
+        });
+        let mut config = TelemetryConfiguration::no_op();
+        config.logger = Some(LoggerConfiguration {
+            service_name: "test".to_string(),
+            protocol: Protocol::HttpBinary,
+            endpoint: "http://localhost:4318".to_string(),
+            tls: tls.clone(),
+            timeout: None,
+        });
+        let error = reconfigure(&config).unwrap_err();
+        assert!(error.to_string().contains("/nonexistent/ca.pem"));
+        assert!(is_initialized());
+        assert!(!log_export_enabled());
+
+        config.logger = None;
+        config.tracer = Some(TracerConfiguration {
+            service_name: "test".to_string(),
+            protocol: Protocol::Grpc,
+            endpoint: "http://localhost:4317".to_string(),
+            tls,
+            timeout: None,
+            sampler: None,
+        });
+        assert!(reconfigure(&config).is_err());
+        assert!(is_initialized());
+        drop(scope);
+    }
+
+    #[test]
+    fn test_log_export_disabled() {
+        let scope = TelemetryScope::new(&TelemetryConfiguration::no_op());
+        assert!(!log_export_enabled());
+        emit_log(log::Level::Info, "savant_rs::test", "not exported", &[]);
+        assert_eq!(severity(log::Level::Warn), Severity::Warn);
+        assert!(matches!(any_value(&Value::from(5_i64)), AnyValue::Int(5)));
+        drop(scope);
+    }
+
+    struct CountingLogger(std::sync::atomic::AtomicUsize);
+
+    impl log::Log for CountingLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, _: &log::Record) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_log_bridge() {
+        use crate::get_tracer;
+        use crate::otlp::{pop_context, push_context};
+        use log::Log as _;
+        use opentelemetry::trace::{Span as _, TraceContextExt, Tracer};
+        use opentelemetry::Context;
+        use opentelemetry_sdk::testing::logs::InMemoryLogsExporter;
+
+        let scope = TelemetryScope::new(&TelemetryConfiguration::no_op());
+        let exporter = InMemoryLogsExporter::default();
+        let provider = LoggerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        *LOGGER.write() = Some(provider.logger("savant"));
+
+        let bridge = LogBridge::new(CountingLogger(Default::default()));
+        let span = get_tracer().start("test");
+        let span_context = span.span_context().clone();
+        push_context(Context::current_with_span(span));
+        bridge.log(
+            &log::Record::builder()
+                .args(format_args!("frame {}", 42))
+                .level(log::Level::Info)
+                .target("savant_rs::test")
+                .line(Some(7))
+                .build(),
+        );
+        bridge.log(
+            &log::Record::builder()
+                .args(format_args!("export failed"))
+                .level(log::Level::Error)
+                .target("opentelemetry_sdk::logs")
+                .build(),
+        );
+        bridge.log(
+            &log::Record::builder()
+                .args(format_args!("connection closed"))
+                .level(log::Level::Info)
+                .target("hyper_util::client::legacy")
+                .build(),
+        );
+        pop_context();
+
+        assert_eq!(bridge.inner.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let logs = exporter.get_emitted_logs().unwrap();
+        assert_eq!(logs.len(), 1);
+        let record = &logs[0].record;
+        assert_eq!(record.severity_number, Some(Severity::Info));
+        assert!(matches!(&record.body, Some(AnyValue::String(s)) if s.as_str() == "frame 42"));
+        let trace_context = record.trace_context.as_ref().unwrap();
+        assert_eq!(trace_context.trace_id, span_context.trace_id());
+        assert_eq!(trace_context.span_id, span_context.span_id());
+
+        *LOGGER.write() = None;
+        drop(scope);
+    }
+
+    #[test]
+    fn test_sampler() {
+        let sampler = |ratio, parent_based| {
+            format!(
+                "{:?}",
+                SamplerConfiguration {
+                    ratio,
+                    parent_based
+                }
+                .sampler()
+            )
+        };
+        assert_eq!(sampler(1.5, false), format!("{:?}", Sampler::AlwaysOn));
+        assert_eq!(sampler(0.0, false), format!("{:?}", Sampler::AlwaysOff));
+        assert_eq!(
+            sampler(0.25, true),
+            format!(
+                "{:?}",
+                Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(0.25)))
+            )
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod fixture;
+
 use crate::primitives::any_object::AnyObject;
 use crate::primitives::attribute_value::AttributeValue;
 use crate::primitives::frame::{
@@ -0,0 +1,460 @@
+use crate::primitives::attribute_value::AttributeValue;
+use crate::primitives::frame::{VideoFrameContent, VideoFrameProxy, VideoFrameTranscodingMethod};
+use crate::primitives::object::{IdCollisionResolutionPolicy, VideoObjectBuilder};
+use crate::primitives::{RBBox, WithAttributes};
+use anyhow::bail;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+const TIME_BASE: (i64, i64) = (1, 1_000_000);
+const TRACK_BIRTH_PROBABILITY: f64 = 0.1;
+const MIN_OBJECT_SIZE: f32 = 16.0;
+
+#[derive(Debug, Clone)]
+pub struct LabelSpec {
+    pub namespace: String,
+    pub label: String,
+    /// The relative frequency of the label among the generated objects.
+    pub weight: u32,
+}
+
+impl LabelSpec {
+    pub fn new(namespace: &str, label: &str, weight: u32) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            label: label.to_string(),
+            weight,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum AttributeValueTemplate {
+    /// A uniformly distributed integer from the inclusive range.
+    Integer(i64, i64),
+    /// A uniformly distributed float from the half-open range.
+    Float(f64, f64),
+    /// One of the strings, picked uniformly.
+    String(Vec<String>),
+}
+
+/// Describes a persistent attribute added to every generated frame or object.
+///
+#[derive(Debug, Clone)]
+pub struct AttributeTemplate {
+    pub namespace: String,
+    pub name: String,
+    pub value: AttributeValueTemplate,
+}
+
+impl AttributeTemplate {
+    pub fn new(namespace: &str, name: &str, value: AttributeValueTemplate) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        match &self.value {
+            AttributeValueTemplate::Integer(min, max) if min > max => bail!(
+                "Attribute {}/{}: the min {} must not exceed the max {}",
+                self.namespace,
+                self.name,
+                min,
+                max
+            ),
+            AttributeValueTemplate::Float(min, max)
+                if !(min.is_finite() && max.is_finite() && min < max) =>
+            {
+                bail!(
+                    "Attribute {}/{}: the range {}..{} must be finite and not empty",
+                    self.namespace,
+                    self.name,
+                    min,
+                    max
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn apply<W: WithAttributes>(&self, target: &mut W, rng: &mut StdRng) {
+        let value = match &self.value {
+            AttributeValueTemplate::Integer(min, max) => {
+                AttributeValue::integer(rng.gen_range(*min..=*max), None)
+            }
+            AttributeValueTemplate::Float(min, max) => {
+                AttributeValue::float(rng.gen_range(*min..*max), None)
+            }
+            AttributeValueTemplate::String(choices) => {
+                AttributeValue::string(choices.choose(rng).map_or("", |s| s.as_str()), None)
+            }
+        };
+        target.set_persistent_attribute(&self.namespace, &self.name, &None, false, vec![value]);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SimulatedObject {
+    label: usize,
+    track_id: Option<i64>,
+    xc: f32,
+    yc: f32,
+    width: f32,
+    height: f32,
+    dx: f32,
+    dy: f32,
+}
+
+/// Generates reproducible frames for tests and benchmarks: the same seed and settings produce
+/// the same objects, attributes and tracks. Frame UUIDs are not covered by the seed.
+///
+/// With the track simulation enabled, the objects move across the frame, keep their track ids
+/// and die randomly, new tracks appear to keep the object count within the configured range.
+///
+pub struct FrameFixtureGenerator {
+    rng: StdRng,
+    source_id: String,
+    width: i64,
+    height: i64,
+    fps: i64,
+    objects_per_frame: (usize, usize),
+    labels: Vec<LabelSpec>,
+    frame_attributes: Vec<AttributeTemplate>,
+    object_attributes: Vec<AttributeTemplate>,
+    track_death_probability: Option<f64>,
+    tracks: Vec<SimulatedObject>,
+    next_track_id: i64,
+    frame_counter: i64,
+}
+
+impl FrameFixtureGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            source_id: "test".to_string(),
+            width: 1280,
+            height: 720,
+            fps: 30,
+            objects_per_frame: (0, 10),
+            labels: vec![
+                LabelSpec::new("detector", "person", 3),
+                LabelSpec::new("detector", "car", 1),
+            ],
+            frame_attributes: Vec::new(),
+            object_attributes: Vec::new(),
+            track_death_probability: None,
+            tracks: Vec::new(),
+            next_track_id: 0,
+            frame_counter: 0,
+        }
+    }
+
+    pub fn source_id(mut self, source_id: &str) -> Self {
+        self.source_id = source_id.to_string();
+        self
+    }
+
+    pub fn resolution(mut self, width: i64, height: i64) -> anyhow::Result<Self> {
+        if width <= 0 || height <= 0 {
+            bail!("Resolution must be positive, got {}x{}", width, height);
+        }
+        self.width = width;
+        self.height = height;
+        Ok(self)
+    }
+
+    pub fn fps(mut self, fps: i64) -> anyhow::Result<Self> {
+        if fps <= 0 {
+            bail!("FPS must be positive, got {}", fps);
+        }
+        self.fps = fps;
+        Ok(self)
+    }
+
+    pub fn objects_per_frame(mut self, min: usize, max: usize) -> anyhow::Result<Self> {
+        if min > max {
+            bail!("Min object count {} must not exceed max {}", min, max);
+        }
+        self.objects_per_frame = (min, max);
+        Ok(self)
+    }
+
+    pub fn labels(mut self, labels: Vec<LabelSpec>) -> anyhow::Result<Self> {
+        if !labels.iter().any(|l| l.weight > 0) {
+            bail!("At least one label must have positive weight");
+        }
+        self.labels = labels;
+        Ok(self)
+    }
+
+    pub fn frame_attribute(mut self, template: AttributeTemplate) -> anyhow::Result<Self> {
+        template.validate()?;
+        self.frame_attributes.push(template);
+        Ok(self)
+    }
+
+    pub fn object_attribute(mut self, template: AttributeTemplate) -> anyhow::Result<Self> {
+        template.validate()?;
+        self.object_attributes.push(template);
+        Ok(self)
+    }
+
+    /// Enables the track simulation; every frame a track dies with the probability, which must
+    /// be within `[0, 1]`.
+    ///
+    pub fn simulate_tracks(mut self, death_probability: f64) -> anyhow::Result<Self> {
+        if !(0.0..=1.0).contains(&death_probability) {
+            bail!(
+                "Track death probability must be within [0, 1], got {}",
+                death_probability
+            );
+        }
+        self.track_death_probability = Some(death_probability);
+        Ok(self)
+    }
+
+    fn spawn(&mut self, with_track: bool) -> SimulatedObject {
+        let weights = WeightedIndex::new(self.labels.iter().map(|l| l.weight)).unwrap();
+        let (fw, fh) = (self.width as f32, self.height as f32);
+        let width = self
+            .rng
+            .gen_range(MIN_OBJECT_SIZE..(fw / 4.0).max(MIN_OBJECT_SIZE + 1.0));
+        let height = self
+            .rng
+            .gen_range(MIN_OBJECT_SIZE..(fh / 4.0).max(MIN_OBJECT_SIZE + 1.0));
+        let track_id = with_track.then(|| {
+            self.next_track_id += 1;
+            self.next_track_id
+        });
+        SimulatedObject {
+            label: weights.sample(&mut self.rng),
+            track_id,
+            xc: self.rng.gen_range(0.0..fw.max(1.0)),
+            yc: self.rng.gen_range(0.0..fh.max(1.0)),
+            width,
+            height,
+            dx: self.rng.gen_range(-fw / 100.0..=fw / 100.0),
+            dy: self.rng.gen_range(-fh / 100.0..=fh / 100.0),
+        }
+    }
+
+    fn step_tracks(&mut self, death_probability: f64) {
+        let (fw, fh) = (self.width as f32, self.height as f32);
+        let rng = &mut self.rng;
+        self.tracks.retain(|_| !rng.gen_bool(death_probability));
+        for t in self.tracks.iter_mut() {
+            t.xc += t.dx;
+            t.yc += t.dy;
+            if t.xc < 0.0 || t.xc > fw {
+                t.dx = -t.dx;
+                t.xc = t.xc.clamp(0.0, fw);
+            }
+            if t.yc < 0.0 || t.yc > fh {
+                t.dy = -t.dy;
+                t.yc = t.yc.clamp(0.0, fh);
+            }
+        }
+        let (min, max) = self.objects_per_frame;
+        while self.tracks.len() < min {
+            let track = self.spawn(true);
+            self.tracks.push(track);
+        }
+        if self.tracks.len() < max && self.rng.gen_bool(TRACK_BIRTH_PROBABILITY) {
+            let track = self.spawn(true);
+            self.tracks.push(track);
+        }
+    }
+
+    /// Generates the next frame of the stream.
+    ///
+    pub fn next_frame(&mut self) -> VideoFrameProxy {
+        let objects = match self.track_death_probability {
+            Some(p) => {
+                self.step_tracks(p);
+                self.tracks.clone()
+            }
+            None => {
+                let (min, max) = self.objects_per_frame;
+                let count = self.rng.gen_range(min..=max);
+                (0..count).map(|_| self.spawn(false)).collect()
+            }
+        };
+
+        let pts = self.frame_counter * TIME_BASE.1 / self.fps;
+        self.frame_counter += 1;
+        let mut frame = VideoFrameProxy::new(
+            &self.source_id,
+            &format!("{}/1", self.fps),
+            self.width,
+            self.height,
+            VideoFrameContent::None,
+            VideoFrameTranscodingMethod::Copy,
+            &None,
+            None,
+            TIME_BASE,
+            pts,
+            None,
+            Some(TIME_BASE.1 / self.fps),
+        );
+        for template in &self.frame_attributes {
+            template.apply(&mut frame, &mut self.rng);
+        }
+
+        for (id, o) in objects.iter().enumerate() {
+            let label = &self.labels[o.label];
+            let bbox = RBBox::new(o.xc, o.yc, o.width, o.height, None);
+            let mut object = VideoObjectBuilder::default()
+                .id(id as i64)
+                .namespace(label.namespace.clone())
+                .label(label.label.clone())
+                .confidence(Some(self.rng.gen_range(0.3..1.0)))
+                .track_id(o.track_id)
+                .track_box(o.track_id.map(|_| bbox.copy()))
+                .detection_box(bbox)
+                .attributes(Vec::default())
+                .build()
+                .unwrap();
+            for template in &self.object_attributes {
+                template.apply(&mut object, &mut self.rng);
+            }
+            frame
+                .add_object(object, IdCollisionResolutionPolicy::Error)
+                .unwrap();
+        }
+        frame
+    }
+
+    /// Generates the next `n` frames of the stream.
+    ///
+    pub fn frames(&mut self, n: usize) -> Vec<VideoFrameProxy> {
+        (0..n).map(|_| self.next_frame()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::object::ObjectOperations;
+
+    fn describe(frame: &VideoFrameProxy) -> Vec<(i64, String, Option<i64>, f32, f32)> {
+        let mut objects = frame
+            .get_all_objects()
+            .iter()
+            .map(|o| {
+                let bbox = o.get_detection_box();
+                (
+                    o.get_id(),
+                    o.get_label(),
+                    o.get_track_id(),
+                    bbox.get_xc(),
+                    bbox.get_yc(),
+                )
+            })
+            .collect::<Vec<_>>();
+        objects.sort_by_key(|o| o.0);
+        objects
+    }
+
+    fn generator(seed: u64) -> FrameFixtureGenerator {
+        FrameFixtureGenerator::new(seed)
+            .objects_per_frame(2, 5)
+            .unwrap()
+            .object_attribute(AttributeTemplate::new(
+                "fixture",
+                "color",
+                AttributeValueTemplate::String(vec!["red".to_string(), "blue".to_string()]),
+            ))
+            .unwrap()
+            .simulate_tracks(0.05)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let a = generator(42).frames(20);
+        let b = generator(42).frames(20);
+        for (fa, fb) in a.iter().zip(b.iter()) {
+            assert_eq!(describe(fa), describe(fb));
+            assert_eq!(fa.get_pts(), fb.get_pts());
+        }
+        assert!(a
+            .iter()
+            .flat_map(|f| f.get_all_objects())
+            .all(|o| o.get_attribute("fixture", "color").is_some()));
+    }
+
+    #[test]
+    fn test_tracks() {
+        let frames = generator(7).frames(50);
+        for f in &frames {
+            let count = f.get_object_count();
+            assert!((2..=5).contains(&count));
+            assert!(f
+                .get_all_objects()
+                .iter()
+                .all(|o| o.get_track_id().is_some()));
+        }
+        // the tracks live longer than one frame
+        let first = describe(&frames[0]);
+        let second = describe(&frames[1]);
+        assert!(first
+            .iter()
+            .any(|o| second.iter().any(|p| p.2 == o.2 && p.1 == o.1)));
+    }
+
+    #[test]
+    fn test_no_tracks() {
+        let frames = FrameFixtureGenerator::new(1)
+            .objects_per_frame(3, 3)
+            .unwrap()
+            .frames(3);
+        for f in &frames {
+            assert_eq!(f.get_object_count(), 3);
+            assert!(f
+                .get_all_objects()
+                .iter()
+                .all(|o| o.get_track_id().is_none()));
+        }
+        assert_eq!(frames[2].get_pts(), 2 * TIME_BASE.1 / 30);
+    }
+
+    #[test]
+    fn test_resolution() {
+        assert!(FrameFixtureGenerator::new(1).resolution(0, 720).is_err());
+        assert!(FrameFixtureGenerator::new(1).resolution(1280, -1).is_err());
+        let frame = FrameFixtureGenerator::new(1)
+            .resolution(640, 480)
+            .unwrap()
+            .next_frame();
+        assert_eq!((frame.get_width(), frame.get_height()), (640, 480));
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let generator = || FrameFixtureGenerator::new(1);
+        assert!(generator().fps(0).is_err());
+        assert!(generator().objects_per_frame(3, 2).is_err());
+        assert!(generator()
+            .labels(vec![LabelSpec::new("detector", "person", 0)])
+            .is_err());
+        assert!(generator().simulate_tracks(f64::NAN).is_err());
+        assert!(generator().simulate_tracks(1.5).is_err());
+        let template = |value| AttributeTemplate::new("fixture", "value", value);
+        assert!(generator()
+            .frame_attribute(template(AttributeValueTemplate::Integer(2, 1)))
+            .is_err());
+        assert!(generator()
+            .object_attribute(template(AttributeValueTemplate::Float(1.0, 1.0)))
+            .is_err());
+        assert!(generator()
+            .object_attribute(template(AttributeValueTemplate::Float(0.0, f64::INFINITY)))
+            .is_err());
+        assert!(generator()
+            .frame_attribute(template(AttributeValueTemplate::Integer(1, 1)))
+            .is_ok());
+    }
+}
@@ -1 +1,129 @@
+pub mod channel;
+#[cfg(feature = "kafka")]
+pub mod kafka;
 pub mod zeromq;
+
+use crate::message::Message;
+use crate::primitives::eos::EndOfStream;
+use crate::transport::channel::{ChannelReader, ChannelWriter, CHANNEL_SCHEME};
+#[cfg(feature = "kafka")]
+use crate::transport::kafka::{KafkaReader, KafkaWriter};
+use crate::transport::zeromq::{
+    ReaderConfig, ReaderResult, SyncReader, SyncWriter, TransportStats, WriterConfig, WriterResult,
+};
+#[cfg(not(feature = "kafka"))]
+use anyhow::bail;
+
+/// The endpoint scheme of the Kafka transport, available with the `kafka` feature, e.g.
+/// `kafka://broker:9092?group.id=analytics`.
+///
+pub const KAFKA_SCHEME: &str = "kafka://";
+
+/// The receiving side of a transport. The results are the same as for the ZeroMQ reader, the
+/// backends which do not have routing ids always report `None`.
+///
+pub trait TransportReader: Send + Sync {
+    fn receive(&self) -> anyhow::Result<ReaderResult>;
+    fn is_started(&self) -> bool;
+    fn stats(&self) -> TransportStats;
+    fn shutdown(&self) -> anyhow::Result<()>;
+}
+
+/// The sending side of a transport.
+///
+pub trait TransportWriter: Send + Sync {
+    fn send_message(
+        &self,
+        topic: &str,
+        message: &Message,
+        data: &[&[u8]],
+    ) -> anyhow::Result<WriterResult>;
+
+    fn send_eos(&self, topic: &str) -> anyhow::Result<WriterResult> {
+        let message = Message::end_of_stream(EndOfStream::new(topic.to_string()));
+        self.send_message(topic, &message, &[])
+    }
+
+    fn is_started(&self) -> bool;
+    fn stats(&self) -> TransportStats;
+    fn shutdown(&self) -> anyhow::Result<()>;
+}
+
+impl TransportReader for SyncReader {
+    fn receive(&self) -> anyhow::Result<ReaderResult> {
+        SyncReader::receive(self)
+    }
+
+    fn is_started(&self) -> bool {
+        SyncReader::is_started(self)
+    }
+
+    fn stats(&self) -> TransportStats {
+        SyncReader::stats(self)
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        SyncReader::shutdown(self)
+    }
+}
+
+impl TransportWriter for SyncWriter {
+    fn send_message(
+        &self,
+        topic: &str,
+        message: &Message,
+        data: &[&[u8]],
+    ) -> anyhow::Result<WriterResult> {
+        SyncWriter::send_message(self, topic, message, data)
+    }
+
+    fn send_eos(&self, topic: &str) -> anyhow::Result<WriterResult> {
+        SyncWriter::send_eos(self, topic)
+    }
+
+    fn is_started(&self) -> bool {
+        SyncWriter::is_started(self)
+    }
+
+    fn stats(&self) -> TransportStats {
+        SyncWriter::stats(self)
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        SyncWriter::shutdown(self)
+    }
+}
+
+/// Creates the reader of the backend selected by the endpoint scheme: `inproc-chan://` endpoints
+/// are served by the in-process [`channel`] transport, `kafka://` endpoints by Kafka, the rest
+/// by ZeroMQ.
+///
+pub fn open_reader(config: &ReaderConfig) -> anyhow::Result<Box<dyn TransportReader>> {
+    if config.endpoint().starts_with(KAFKA_SCHEME) {
+        #[cfg(feature = "kafka")]
+        return Ok(Box::new(KafkaReader::new(config)?));
+        #[cfg(not(feature = "kafka"))]
+        bail!("Kafka transport requires the kafka feature");
+    }
+    if config.endpoint().starts_with(CHANNEL_SCHEME) {
+        Ok(Box::new(ChannelReader::new(config)?))
+    } else {
+        Ok(Box::new(SyncReader::new(config)?))
+    }
+}
+
+/// Creates the writer of the backend selected by the endpoint scheme, see [`open_reader`].
+///
+pub fn open_writer(config: &WriterConfig) -> anyhow::Result<Box<dyn TransportWriter>> {
+    if config.endpoint().starts_with(KAFKA_SCHEME) {
+        #[cfg(feature = "kafka")]
+        return Ok(Box::new(KafkaWriter::new(config)?));
+        #[cfg(not(feature = "kafka"))]
+        bail!("Kafka transport requires the kafka feature");
+    }
+    if config.endpoint().starts_with(CHANNEL_SCHEME) {
+        Ok(Box::new(ChannelWriter::new(config)?))
+    } else {
+        Ok(Box::new(SyncWriter::new(config)?))
+    }
+}
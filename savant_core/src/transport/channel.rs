@@ -0,0 +1,341 @@
+use crate::message::validation::{MessageValidator, ValidationMode};
+use crate::message::Message;
+use crate::protobuf::{deserialize, serialize};
+use crate::transport::zeromq::{
+    ReaderConfig, ReaderResult, StatsCollector, TransportStats, WriterConfig, WriterResult,
+};
+use crate::transport::{TransportReader, TransportWriter};
+use anyhow::bail;
+use crossbeam::channel::{bounded, Receiver, RecvTimeoutError, SendTimeoutError, Sender};
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use log::{info, warn};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// The endpoint scheme of the in-process channel transport, e.g. `inproc-chan://video`.
+///
+pub const CHANNEL_SCHEME: &str = "inproc-chan://";
+
+struct Envelope {
+    topic: Vec<u8>,
+    message: Vec<u8>,
+    data: Vec<Vec<u8>>,
+}
+
+type Channel = (Sender<Envelope>, Receiver<Envelope>);
+
+lazy_static! {
+    static ref CHANNELS: Mutex<HashMap<String, Channel>> = Mutex::new(HashMap::new());
+}
+
+fn channel_name(endpoint: &str) -> anyhow::Result<&str> {
+    match endpoint.strip_prefix(CHANNEL_SCHEME) {
+        Some(name) if !name.is_empty() => Ok(name),
+        _ => bail!("Invalid channel endpoint: {}", endpoint),
+    }
+}
+
+/// Returns the channel with the name, the first reader or writer opening the channel defines its
+/// capacity with the high water mark.
+///
+fn open_channel(endpoint: &str, capacity: i32) -> anyhow::Result<Channel> {
+    let name = channel_name(endpoint)?;
+    let mut channels = CHANNELS.lock();
+    let channel = channels
+        .entry(name.to_string())
+        .or_insert_with(|| bounded(capacity.max(1) as usize));
+    Ok(channel.clone())
+}
+
+/// Removes the channel from the registry, the messages not received yet are dropped when the
+/// readers and the writers of the channel are shut down. Returns `false` when there is no such
+/// channel.
+///
+pub fn remove_channel(name: &str) -> bool {
+    CHANNELS.lock().remove(name).is_some()
+}
+
+/// The writer of the in-process channel transport. The messages are serialized just like for
+/// ZeroMQ, so the reader never shares the objects with the writer. The socket type and the bind
+/// options of the config are ignored; the send timeout and the high water mark are respected.
+///
+/// The statistics are not exported to the metrics registry.
+///
+pub struct ChannelWriter {
+    endpoint: String,
+    sender: Sender<Envelope>,
+    send_timeout: Duration,
+    started: AtomicBool,
+    stats: StatsCollector,
+}
+
+impl ChannelWriter {
+    pub fn new(config: &WriterConfig) -> anyhow::Result<Self> {
+        let (sender, _) = open_channel(config.endpoint(), *config.send_hwm())?;
+        info!(
+            target: "savant_rs::channel::writer",
+            "Opened channel writer for endpoint {}",
+            config.endpoint()
+        );
+        Ok(Self {
+            endpoint: config.endpoint().clone(),
+            sender,
+            send_timeout: Duration::from_millis(*config.send_timeout() as u64),
+            started: AtomicBool::new(true),
+            stats: StatsCollector::new("writer", config.endpoint(), false),
+        })
+    }
+}
+
+impl TransportWriter for ChannelWriter {
+    fn send_message(
+        &self,
+        topic: &str,
+        message: &Message,
+        data: &[&[u8]],
+    ) -> anyhow::Result<WriterResult> {
+        if !self.is_started() {
+            bail!(
+                "Channel writer for endpoint {} is shut down.",
+                self.endpoint
+            );
+        }
+        let start = Instant::now();
+        let envelope = Envelope {
+            topic: topic.as_bytes().to_vec(),
+            message: serialize(message)?,
+            data: data.iter().map(|part| part.to_vec()).collect(),
+        };
+        let bytes = envelope.topic.len()
+            + envelope.message.len()
+            + envelope.data.iter().map(Vec::len).sum::<usize>();
+        match self.sender.send_timeout(envelope, self.send_timeout) {
+            Ok(()) => {
+                self.stats.record_bytes(bytes);
+                self.stats.record_message();
+                Ok(WriterResult::Success {
+                    retries_spent: 0,
+                    time_spent: start.elapsed().as_millis(),
+                })
+            }
+            Err(SendTimeoutError::Timeout(_)) => {
+                warn!(
+                    target: "savant_rs::channel::writer",
+                    "Channel {} is full, the message is dropped", self.endpoint
+                );
+                self.stats.record_hwm_hit();
+                self.stats.record_drop();
+                Ok(WriterResult::SendTimeout)
+            }
+            Err(SendTimeoutError::Disconnected(_)) => {
+                bail!("Channel for endpoint {} is disconnected.", self.endpoint)
+            }
+        }
+    }
+
+    fn is_started(&self) -> bool {
+        self.started.load(Ordering::Relaxed)
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats.snapshot()
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        self.started.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// The reader of the in-process channel transport. Several readers of the same channel compete
+/// for the messages, like the ZeroMQ readers sharing a connecting dealer. The topic prefix, the
+/// source filter and the validation of the config are applied, the source blacklist is not.
+///
+pub struct ChannelReader {
+    config: ReaderConfig,
+    receiver: Receiver<Envelope>,
+    validator: MessageValidator,
+    started: AtomicBool,
+    stats: StatsCollector,
+}
+
+impl ChannelReader {
+    pub fn new(config: &ReaderConfig) -> anyhow::Result<Self> {
+        let (_, receiver) = open_channel(config.endpoint(), *config.receive_hwm())?;
+        info!(
+            target: "savant_rs::channel::reader",
+            "Opened channel reader for endpoint {}",
+            config.endpoint()
+        );
+        Ok(Self {
+            config: config.clone(),
+            receiver,
+            validator: MessageValidator::new(*config.validation_mode()),
+            started: AtomicBool::new(true),
+            stats: StatsCollector::new("reader", config.endpoint(), false),
+        })
+    }
+
+    fn read_message(&self, envelope: Envelope) -> anyhow::Result<ReaderResult> {
+        let Envelope {
+            topic,
+            message,
+            data,
+        } = envelope;
+        if !self.config.topic_prefix_spec().matches(&topic) {
+            return Ok(ReaderResult::prefix_mismatch(&topic, &None));
+        }
+        if !self.config.source_filter().accepts(&topic) {
+            return Ok(ReaderResult::SourceDenied {
+                topic,
+                routing_id: None,
+            });
+        }
+        let message = deserialize(&message)?;
+        let violations = self.validator.validate(&message);
+        if !violations.is_empty() && self.validator.mode() == ValidationMode::Strict {
+            return Ok(ReaderResult::Invalid {
+                message: Box::new(message),
+                topic,
+                routing_id: None,
+                violations,
+            });
+        }
+        Ok(ReaderResult::message(message, &topic, &None, &data))
+    }
+}
+
+impl TransportReader for ChannelReader {
+    fn receive(&self) -> anyhow::Result<ReaderResult> {
+        if !self.is_started() {
+            bail!(
+                "Channel reader for endpoint {} is shut down.",
+                self.config.endpoint()
+            );
+        }
+        let timeout = Duration::from_millis(*self.config.receive_timeout() as u64);
+        let envelope = match self.receiver.recv_timeout(timeout) {
+            Ok(envelope) => envelope,
+            Err(RecvTimeoutError::Timeout) => return Ok(ReaderResult::Timeout),
+            Err(RecvTimeoutError::Disconnected) => {
+                bail!(
+                    "Channel for endpoint {} is disconnected.",
+                    self.config.endpoint()
+                )
+            }
+        };
+        self.stats.record_bytes(
+            envelope.topic.len()
+                + envelope.message.len()
+                + envelope.data.iter().map(Vec::len).sum::<usize>(),
+        );
+        let result = self.read_message(envelope)?;
+        self.stats.record_reader_result(&result);
+        Ok(result)
+    }
+
+    fn is_started(&self) -> bool {
+        self.started.load(Ordering::Relaxed)
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats.snapshot()
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        self.started.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::TopicPrefixSpec;
+    use crate::transport::{open_reader, open_writer};
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        let reader = open_reader(
+            &ReaderConfig::new()
+                .url("inproc-chan://test-round-trip")?
+                .with_receive_timeout(100)?
+                .build()?,
+        )?;
+        let writer = open_writer(
+            &WriterConfig::new()
+                .url("inproc-chan://test-round-trip")?
+                .build()?,
+        )?;
+        let message = Message::user_data(UserData::new("test"));
+        let res = writer.send_message("test", &message, &[b"payload"])?;
+        assert!(matches!(res, WriterResult::Success { .. }));
+        writer.send_eos("test")?;
+        let res = reader.receive()?;
+        assert!(
+            matches!(res, ReaderResult::Message { message: m, data, routing_id: None, .. } if m.is_user_data() && data == [b"payload".as_slice()])
+        );
+        let res = reader.receive()?;
+        assert!(matches!(res, ReaderResult::Message { message: m, .. } if m.is_end_of_stream()));
+        assert!(matches!(reader.receive()?, ReaderResult::Timeout));
+        assert_eq!((reader.stats().messages, writer.stats().messages), (2, 2));
+
+        reader.shutdown()?;
+        assert!(!reader.is_started());
+        assert!(reader.receive().is_err());
+        assert!(remove_channel("test-round-trip"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_channel() -> anyhow::Result<()> {
+        let writer = ChannelWriter::new(
+            &WriterConfig::new()
+                .url("inproc-chan://test-full-channel")?
+                .with_send_hwm(1)?
+                .with_send_timeout(10)?
+                .build()?,
+        )?;
+        let message = Message::user_data(UserData::new("test"));
+        writer.send_message("test", &message, &[])?;
+        let res = writer.send_message("test", &message, &[])?;
+        assert!(matches!(res, WriterResult::SendTimeout));
+        let stats = writer.stats();
+        assert_eq!((stats.messages, stats.hwm_hits, stats.drops), (1, 1, 1));
+        remove_channel("test-full-channel");
+        Ok(())
+    }
+
+    #[test]
+    fn test_topic_prefix() -> anyhow::Result<()> {
+        let reader = ChannelReader::new(
+            &ReaderConfig::new()
+                .url("inproc-chan://test-topic-prefix")?
+                .with_topic_prefix_spec(TopicPrefixSpec::source_id("test"))?
+                .build()?,
+        )?;
+        let writer = ChannelWriter::new(
+            &WriterConfig::new()
+                .url("inproc-chan://test-topic-prefix")?
+                .build()?,
+        )?;
+        writer.send_message("other", &Message::user_data(UserData::new("test")), &[])?;
+        assert!(matches!(
+            reader.receive()?,
+            ReaderResult::PrefixMismatch { .. }
+        ));
+        assert_eq!(reader.stats().drops, 1);
+        remove_channel("test-topic-prefix");
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_endpoint() -> anyhow::Result<()> {
+        let config = ReaderConfig::new().url("inproc-chan://")?.build()?;
+        assert!(ChannelReader::new(&config).is_err());
+        Ok(())
+    }
+}
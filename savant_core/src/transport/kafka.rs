@@ -0,0 +1,371 @@
+use crate::message::validation::{MessageValidator, ValidationMode};
+use crate::message::Message;
+use crate::otlp::PropagatedContext;
+use crate::protobuf::{deserialize, serialize};
+use crate::transport::zeromq::{
+    ReaderConfig, ReaderResult, StatsCollector, TopicPrefixSpec, TransportStats, WriterConfig,
+    WriterResult,
+};
+use crate::transport::{TransportReader, TransportWriter, KAFKA_SCHEME};
+use anyhow::bail;
+use log::{info, warn};
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::{Header, Headers, Message as _, OwnedHeaders};
+use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+const DATA_HEADER_PREFIX: &str = "savant-data-";
+const DEFAULT_GROUP_ID: &str = "savant";
+
+/// The brokers and the client properties of a Kafka endpoint, e.g.
+/// `kafka://broker-1:9092,broker-2:9092?group.id=analytics&auto.offset.reset=earliest`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaEndpoint {
+    pub brokers: String,
+    pub properties: Vec<(String, String)>,
+}
+
+impl KafkaEndpoint {
+    pub fn parse(endpoint: &str) -> anyhow::Result<Self> {
+        let Some(rest) = endpoint.strip_prefix(KAFKA_SCHEME) else {
+            bail!("Invalid Kafka endpoint: {}", endpoint);
+        };
+        let (brokers, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if brokers.is_empty() {
+            bail!("Kafka endpoint {} has no brokers", endpoint);
+        }
+        let properties = query
+            .split('&')
+            .filter(|p| !p.is_empty())
+            .map(|p| match p.split_once('=') {
+                Some((k, v)) if !k.is_empty() => Ok((k.to_string(), v.to_string())),
+                _ => bail!("Invalid Kafka client property: {}", p),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            brokers: brokers.to_string(),
+            properties,
+        })
+    }
+
+    fn client_config(&self) -> ClientConfig {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", &self.brokers);
+        for (k, v) in &self.properties {
+            config.set(k, v);
+        }
+        config
+    }
+}
+
+/// The Kafka topics the reader subscribes to for the topic prefix spec, the prefixes are
+/// subscribed to with the regular expression patterns.
+///
+fn subscription(spec: &TopicPrefixSpec) -> String {
+    match spec {
+        TopicPrefixSpec::SourceId(source_id) => source_id.clone(),
+        TopicPrefixSpec::Prefix(prefix) => format!("^{}.*", regex::escape(prefix)),
+        TopicPrefixSpec::None => "^.*".to_string(),
+    }
+}
+
+/// The writer publishing the messages to the Kafka topic named after the source id. The extra
+/// frames and the trace propagation context of the message are passed as the record headers.
+///
+/// The send timeout limits the waiting for the space in the producer queue, the send retries
+/// limit the attempts; the end-of-stream messages flush the producer.
+///
+pub struct KafkaWriter {
+    config: WriterConfig,
+    producer: BaseProducer,
+    started: AtomicBool,
+    stats: StatsCollector,
+}
+
+impl KafkaWriter {
+    pub fn new(config: &WriterConfig) -> anyhow::Result<Self> {
+        let endpoint = KafkaEndpoint::parse(config.endpoint())?;
+        let producer: BaseProducer = endpoint.client_config().create()?;
+        info!(
+            target: "savant_rs::kafka::writer",
+            "Created Kafka producer for brokers {}", endpoint.brokers
+        );
+        Ok(Self {
+            config: config.clone(),
+            producer,
+            started: AtomicBool::new(true),
+            stats: StatsCollector::new("writer", config.endpoint(), false),
+        })
+    }
+
+    fn headers(message: &Message, data: &[&[u8]]) -> OwnedHeaders {
+        let mut headers = OwnedHeaders::new();
+        for (key, value) in &message.get_span_context().0 {
+            headers = headers.insert(Header {
+                key,
+                value: Some(value.as_bytes()),
+            });
+        }
+        for (i, part) in data.iter().enumerate() {
+            headers = headers.insert(Header {
+                key: &format!("{}{}", DATA_HEADER_PREFIX, i),
+                value: Some(*part),
+            });
+        }
+        headers
+    }
+}
+
+impl TransportWriter for KafkaWriter {
+    fn send_message(
+        &self,
+        topic: &str,
+        message: &Message,
+        data: &[&[u8]],
+    ) -> anyhow::Result<WriterResult> {
+        if !self.is_started() {
+            bail!(
+                "Kafka writer for endpoint {} is shut down.",
+                self.config.endpoint()
+            );
+        }
+        let start = Instant::now();
+        let payload = serialize(message)?;
+        let mut record = BaseRecord::to(topic)
+            .key(topic.as_bytes())
+            .payload(&payload)
+            .headers(Self::headers(message, data));
+        let timeout = Duration::from_millis(*self.config.send_timeout() as u64);
+        let mut retries = *self.config.send_retries();
+        loop {
+            match self.producer.send(record) {
+                Ok(()) => break,
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), r)) => {
+                    self.stats.record_hwm_hit();
+                    if retries == 0 {
+                        warn!(
+                            target: "savant_rs::kafka::writer",
+                            "Kafka producer queue is full, the message for topic {} is dropped",
+                            topic
+                        );
+                        self.stats.record_drop();
+                        return Ok(WriterResult::SendTimeout);
+                    }
+                    retries -= 1;
+                    record = r;
+                    self.producer.poll(timeout);
+                }
+                Err((e, _)) => return Err(e.into()),
+            }
+        }
+        self.producer.poll(Duration::ZERO);
+        if message.is_end_of_stream() {
+            self.producer.flush(timeout)?;
+        }
+        self.stats.record_bytes(
+            topic.len() + payload.len() + data.iter().map(|p| p.len()).sum::<usize>(),
+        );
+        self.stats.record_message();
+        Ok(WriterResult::Success {
+            retries_spent: *self.config.send_retries() - retries,
+            time_spent: start.elapsed().as_millis(),
+        })
+    }
+
+    fn is_started(&self) -> bool {
+        self.started.load(Ordering::Relaxed)
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats.snapshot()
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        if self.started.swap(false, Ordering::Relaxed) {
+            let timeout = Duration::from_millis(*self.config.send_timeout() as u64);
+            self.producer.flush(timeout)?;
+        }
+        Ok(())
+    }
+}
+
+/// The reader consuming the Kafka topics selected by the topic prefix spec of the config, the
+/// consumer group is `savant` unless `group.id` is set in the endpoint. The trace propagation
+/// context is restored from the record headers when the message does not carry it.
+///
+pub struct KafkaReader {
+    config: ReaderConfig,
+    consumer: BaseConsumer,
+    validator: MessageValidator,
+    started: AtomicBool,
+    stats: StatsCollector,
+}
+
+impl KafkaReader {
+    pub fn new(config: &ReaderConfig) -> anyhow::Result<Self> {
+        let endpoint = KafkaEndpoint::parse(config.endpoint())?;
+        let mut client_config = endpoint.client_config();
+        if !endpoint.properties.iter().any(|(k, _)| k == "group.id") {
+            client_config.set("group.id", DEFAULT_GROUP_ID);
+        }
+        let consumer: BaseConsumer = client_config.create()?;
+        let subscription = subscription(config.topic_prefix_spec());
+        consumer.subscribe(&[&subscription])?;
+        info!(
+            target: "savant_rs::kafka::reader",
+            "Subscribed to Kafka topics {} on brokers {}", subscription, endpoint.brokers
+        );
+        Ok(Self {
+            config: config.clone(),
+            consumer,
+            validator: MessageValidator::new(*config.validation_mode()),
+            started: AtomicBool::new(true),
+            stats: StatsCollector::new("reader", config.endpoint(), false),
+        })
+    }
+
+    fn read_message(
+        &self,
+        topic: Vec<u8>,
+        payload: &[u8],
+        headers: Vec<(String, Vec<u8>)>,
+    ) -> anyhow::Result<ReaderResult> {
+        if !self.config.source_filter().accepts(&topic) {
+            return Ok(ReaderResult::SourceDenied {
+                topic,
+                routing_id: None,
+            });
+        }
+        let mut message = deserialize(payload)?;
+        let mut data = Vec::new();
+        let mut context = PropagatedContext::default();
+        for (key, value) in headers {
+            if key.starts_with(DATA_HEADER_PREFIX) {
+                data.push(value);
+            } else {
+                context
+                    .0
+                    .insert(key, String::from_utf8_lossy(&value).into_owned());
+            }
+        }
+        if message.get_span_context().0.is_empty() && !context.0.is_empty() {
+            message.set_span_context(context);
+        }
+        let violations = self.validator.validate(&message);
+        if !violations.is_empty() && self.validator.mode() == ValidationMode::Strict {
+            return Ok(ReaderResult::Invalid {
+                message: Box::new(message),
+                topic,
+                routing_id: None,
+                violations,
+            });
+        }
+        Ok(ReaderResult::message(message, &topic, &None, &data))
+    }
+}
+
+impl TransportReader for KafkaReader {
+    fn receive(&self) -> anyhow::Result<ReaderResult> {
+        if !self.is_started() {
+            bail!(
+                "Kafka reader for endpoint {} is shut down.",
+                self.config.endpoint()
+            );
+        }
+        let timeout = Duration::from_millis(*self.config.receive_timeout() as u64);
+        let record = match self.consumer.poll(timeout) {
+            None => return Ok(ReaderResult::Timeout),
+            Some(record) => record?,
+        };
+        let topic = record.topic().as_bytes().to_vec();
+        let payload = record.payload().unwrap_or_default();
+        let headers = record
+            .headers()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .map(|h| (h.key.to_string(), h.value.unwrap_or_default().to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        self.stats.record_bytes(
+            topic.len() + payload.len() + headers.iter().map(|(_, v)| v.len()).sum::<usize>(),
+        );
+        let result = self.read_message(topic, payload, headers)?;
+        self.stats.record_reader_result(&result);
+        Ok(result)
+    }
+
+    fn is_started(&self) -> bool {
+        self.started.load(Ordering::Relaxed)
+    }
+
+    fn stats(&self) -> TransportStats {
+        self.stats.snapshot()
+    }
+
+    fn shutdown(&self) -> anyhow::Result<()> {
+        if self.started.swap(false, Ordering::Relaxed) {
+            self.consumer.unsubscribe();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() -> anyhow::Result<()> {
+        let endpoint = KafkaEndpoint::parse("kafka://b1:9092,b2:9092?group.id=g&acks=all")?;
+        assert_eq!(endpoint.brokers, "b1:9092,b2:9092");
+        assert_eq!(
+            endpoint.properties,
+            vec![
+                ("group.id".to_string(), "g".to_string()),
+                ("acks".to_string(), "all".to_string())
+            ]
+        );
+        assert!(KafkaEndpoint::parse("kafka://b1:9092")?
+            .properties
+            .is_empty());
+        assert!(KafkaEndpoint::parse("kafka://").is_err());
+        assert!(KafkaEndpoint::parse("kafka://b1:9092?=x").is_err());
+        assert!(KafkaEndpoint::parse("tcp://b1:9092").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_subscription() {
+        assert_eq!(subscription(&TopicPrefixSpec::source_id("cam-1")), "cam-1");
+        assert_eq!(subscription(&TopicPrefixSpec::prefix("cam.")), r"^cam\..*");
+        assert_eq!(subscription(&TopicPrefixSpec::none()), "^.*");
+    }
+
+    #[test]
+    fn test_headers() {
+        let mut message = Message::unknown("test".to_string());
+        let mut context = PropagatedContext::default();
+        context
+            .0
+            .insert("traceparent".to_string(), "00-abc-def-01".to_string());
+        message.set_span_context(context);
+        let headers = KafkaWriter::headers(&message, &[b"payload"]);
+        let headers = headers
+            .iter()
+            .map(|h| (h.key.to_string(), h.value.unwrap_or_default().to_vec()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            headers,
+            vec![
+                ("traceparent".to_string(), b"00-abc-def-01".to_vec()),
+                ("savant-data-0".to_string(), b"payload".to_vec())
+            ]
+        );
+    }
+}
@@ -1,22 +1,46 @@
+use crate::transport::channel::CHANNEL_SCHEME;
+use crate::transport::KAFKA_SCHEME;
 use anyhow::bail;
 use lazy_static::lazy_static;
 use log::debug;
 use lru::LruCache;
 use std::num::NonZeroUsize;
+use std::sync::Arc;
 
+mod async_reader;
+mod async_writer;
+mod compression;
+mod curve;
+mod multi_reader;
 mod nonblocking_reader;
 mod nonblocking_writer;
 pub mod reader;
 mod reader_config;
+mod reconnect;
+mod router;
+mod source_filter;
+mod spooling_writer;
+mod stats;
 mod sync_reader;
 mod sync_writer;
 mod writer;
 mod writer_config;
 
+pub use async_reader::AsyncReader;
+pub use async_writer::AsyncWriter;
+pub use compression::CompressionCodec;
+pub use curve::{CurveConfig, CurveKeyPair};
+pub use multi_reader::{MultiReader, MultiReaderResult};
 pub use nonblocking_reader::NonBlockingReader;
 pub use nonblocking_writer::{NonBlockingWriter, WriteOperationResult};
 pub use reader::{Reader, ReaderResult};
 pub use reader_config::{ReaderConfig, ReaderConfigBuilder};
+pub use reconnect::{ConnectionCallback, ConnectionEvent, ReconnectPolicy};
+pub use router::{RouteMode, RouteRule, Router, RouterResult, RoutingTable};
+pub use source_filter::{SourceFilter, SourcePattern};
+pub use spooling_writer::{Spool, SpoolingWriter, SpoolingWriterResult};
+pub(crate) use stats::StatsCollector;
+pub use stats::TransportStats;
 use std::mem;
 use std::os::unix::fs::PermissionsExt;
 pub use sync_reader::SyncReader;
@@ -86,6 +110,16 @@ pub struct ZmqSocketUri {
 }
 
 pub fn parse_zmq_socket_uri(uri: String) -> anyhow::Result<ZmqSocketUri> {
+    // the in-process channels and Kafka are served by the other transports, the options do not
+    // apply
+    if uri.starts_with(CHANNEL_SCHEME) || uri.starts_with(KAFKA_SCHEME) {
+        return Ok(ZmqSocketUri {
+            endpoint: uri,
+            source: None,
+            bind: None,
+            socket_type: None,
+        });
+    }
     let source;
     let mut socket_type = None;
     let mut bind = None;
@@ -372,6 +406,59 @@ impl<C: MockSocketResponder> Socket<C> {
         }
     }
 
+    fn set_reconnect_policy(&self, policy: &ReconnectPolicy) -> anyhow::Result<()> {
+        match self {
+            Socket::ZmqSocket(socket) => {
+                socket.set_reconnect_ivl(policy.interval)?;
+                socket.set_reconnect_ivl_max(policy.max_interval)?;
+                Ok(())
+            }
+            Socket::MockSocket(_, _) => Ok(()),
+        }
+    }
+
+    fn start_monitor(
+        &self,
+        context: &zmq::Context,
+        endpoint: &str,
+        policy: &ReconnectPolicy,
+        callback: &Option<ConnectionCallback>,
+        stats: &Arc<stats::StatsCollector>,
+    ) -> anyhow::Result<Option<reconnect::SocketMonitor>> {
+        match self {
+            Socket::ZmqSocket(socket) => Ok(Some(reconnect::SocketMonitor::start(
+                context,
+                socket,
+                endpoint,
+                policy,
+                callback.clone(),
+                stats.clone(),
+            )?)),
+            Socket::MockSocket(_, _) => Ok(None),
+        }
+    }
+
+    fn set_curve(
+        &self,
+        context: &zmq::Context,
+        curve: &Option<CurveConfig>,
+    ) -> anyhow::Result<Option<curve::CurveAuthenticator>> {
+        let (Socket::ZmqSocket(socket), Some(curve)) = (self, curve) else {
+            return Ok(None);
+        };
+        curve.apply(socket)?;
+        match curve {
+            CurveConfig::Server {
+                allowed_clients: Some(allowed_clients),
+                ..
+            } => Ok(Some(curve::CurveAuthenticator::start(
+                context,
+                allowed_clients,
+            )?)),
+            _ => Ok(None),
+        }
+    }
+
     fn take_buffer(&mut self) -> Vec<Vec<u8>> {
         match self {
             Socket::ZmqSocket(_) => unreachable!("Cannot take buffer from ZMQ socket. The function is implemented only for testing purposes."),
@@ -428,6 +515,15 @@ mod tests {
         assert!(res.socket_type.is_none());
     }
 
+    #[test]
+    fn test_parse_channel_uri() {
+        let uri = "inproc-chan://video".to_string();
+        let res = parse_zmq_socket_uri(uri).unwrap();
+        assert_eq!(res.endpoint, "inproc-chan://video");
+        assert!(res.bind.is_none());
+        assert!(res.socket_type.is_none());
+    }
+
     #[test]
     fn test_parse_uri_with_options() {
         let uri = "pub+bind:tcp://1.1.1.1:1234".to_string();
@@ -0,0 +1,172 @@
+use crate::transport::zeromq::reader::ReaderResult;
+use crate::transport::zeromq::{ReaderConfig, SyncReader};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::mpsc::{channel, Receiver};
+
+/// The reader for the tokio-based services. The socket is served by a dedicated I/O thread
+/// which passes the results through the bounded channel, so awaiting a message does not block
+/// the runtime threads.
+///
+pub struct AsyncReader {
+    config: ReaderConfig,
+    thread: Option<std::thread::JoinHandle<()>>,
+    receiver: Option<Receiver<anyhow::Result<ReaderResult>>>,
+    is_started: OnceLock<()>,
+    is_shutdown: Arc<OnceLock<()>>,
+    results_queue_size: usize,
+    reader: Option<SyncReader>,
+}
+
+impl AsyncReader {
+    pub fn new(config: &ReaderConfig, results_queue_size: usize) -> anyhow::Result<Self> {
+        if results_queue_size == 0 {
+            anyhow::bail!("Results queue size must be greater than 0.");
+        }
+        Ok(Self {
+            config: config.clone(),
+            thread: None,
+            receiver: None,
+            is_started: OnceLock::new(),
+            is_shutdown: Arc::new(OnceLock::new()),
+            results_queue_size,
+            reader: None,
+        })
+    }
+
+    pub fn start(&mut self) -> anyhow::Result<()> {
+        if self.is_shutdown() {
+            anyhow::bail!("Reader is shutdown.");
+        }
+        if self.is_started() {
+            anyhow::bail!("Reader is already started.");
+        }
+        _ = self.is_started.set(());
+        let (sender, receiver) = channel(self.results_queue_size);
+        let reader = SyncReader::new(&self.config)?;
+        self.reader = Some(reader.clone());
+        let is_shutdown = self.is_shutdown.clone();
+        let thread = std::thread::spawn(move || loop {
+            let res = reader.receive();
+            if sender.blocking_send(res).is_err() || is_shutdown.get().is_some() {
+                _ = is_shutdown.set(());
+                break;
+            }
+        });
+        self.thread = Some(thread);
+        self.receiver = Some(receiver);
+        Ok(())
+    }
+
+    pub fn enqueued_results(&self) -> usize {
+        if let Some(receiver) = &self.receiver {
+            receiver.len()
+        } else {
+            0
+        }
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.is_shutdown.get().is_some()
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.is_started.get().is_some()
+    }
+
+    /// Stops the I/O thread, the results not received yet are dropped.
+    ///
+    pub async fn shutdown(&mut self) -> anyhow::Result<()> {
+        if self.is_shutdown() {
+            anyhow::bail!("Reader is shutdown.");
+        }
+        if !self.is_started() {
+            anyhow::bail!("Reader is not started.");
+        }
+        if let Some(thread) = self.thread.take() {
+            _ = self.is_shutdown.set(());
+            // the thread blocked on the full queue is released by the closed channel
+            self.receiver.take();
+            tokio::task::spawn_blocking(move || thread.join())
+                .await?
+                .map_err(|_| anyhow::anyhow!("Failed to join thread."))?;
+        } else {
+            anyhow::bail!("Reader is not running.");
+        }
+        Ok(())
+    }
+
+    pub async fn receive(&mut self) -> anyhow::Result<ReaderResult> {
+        if !self.is_started() {
+            anyhow::bail!("Reader is not started.");
+        }
+        if self.is_shutdown() {
+            anyhow::bail!("Reader is shutdown.");
+        }
+        if let Some(receiver) = &mut self.receiver {
+            receiver
+                .recv()
+                .await
+                .ok_or_else(|| anyhow::anyhow!("Failed to receive message: channel is closed."))?
+        } else {
+            anyhow::bail!("Reader is not running.");
+        }
+    }
+
+    pub fn try_receive(&mut self) -> Option<anyhow::Result<ReaderResult>> {
+        if !self.is_started() {
+            return Some(Err(anyhow::anyhow!("Reader is not started.")));
+        }
+        if self.is_shutdown() {
+            return Some(Err(anyhow::anyhow!("Reader is shutdown.")));
+        }
+        let receiver = self.receiver.as_mut()?;
+        match receiver.try_recv() {
+            Ok(res) => Some(res),
+            Err(tokio::sync::mpsc::error::TryRecvError::Empty) => None,
+            Err(e) => Some(Err(anyhow::anyhow!("Failed to receive message: {:?}", e))),
+        }
+    }
+
+    pub fn blacklist_source(&self, source_id: &[u8]) {
+        if let Some(reader) = &self.reader {
+            reader.blacklist_source(source_id);
+        }
+    }
+
+    pub fn is_blacklisted(&self, source_id: &[u8]) -> bool {
+        if let Some(reader) = &self.reader {
+            reader.is_blacklisted(source_id)
+        } else {
+            unreachable!("Reader is not started.")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transport::zeromq::reader::ReaderResult;
+    use crate::transport::zeromq::{AsyncReader, ReaderConfig, TopicPrefixSpec};
+
+    #[test]
+    fn test_async_idling() -> anyhow::Result<()> {
+        let conf = ReaderConfig::new()
+            .url("router+bind:ipc:///tmp/test/async-reader-idling")?
+            .with_topic_prefix_spec(TopicPrefixSpec::SourceId("topic".into()))?
+            .with_receive_timeout(100)?
+            .build()?;
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(async {
+            let mut reader = AsyncReader::new(&conf, 1)?;
+            assert!(reader.receive().await.is_err());
+            reader.start()?;
+            assert!(reader.start().is_err());
+            assert!(reader.try_receive().is_none());
+            let recv = reader.receive().await?;
+            assert!(matches!(recv, ReaderResult::Timeout));
+            reader.shutdown().await?;
+            assert!(reader.shutdown().await.is_err());
+            assert!(reader.receive().await.is_err());
+            Ok(())
+        })
+    }
+}
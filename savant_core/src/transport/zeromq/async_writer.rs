@@ -0,0 +1,212 @@
+use crate::message::Message;
+use crate::primitives::eos::EndOfStream;
+use crate::transport::zeromq::{SyncWriter, WriterConfig, WriterResult};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::mpsc::{channel, Sender};
+use tokio::sync::oneshot;
+
+enum Command {
+    Message(
+        String,
+        Box<Message>,
+        Vec<Vec<u8>>,
+        oneshot::Sender<anyhow::Result<WriterResult>>,
+    ),
+    Shutdown,
+}
+
+/// The writer for the tokio-based services. The socket is served by a dedicated I/O thread,
+/// the send operations are queued to it and their results are awaited without blocking the
+/// runtime threads.
+///
+pub struct AsyncWriter {
+    config: WriterConfig,
+    max_inflight_messages: usize,
+    thread: Option<std::thread::JoinHandle<()>>,
+    ops_queue: Option<Sender<Command>>,
+    is_started: OnceLock<()>,
+    is_shutdown: Arc<OnceLock<()>>,
+}
+
+impl AsyncWriter {
+    pub fn new(config: &WriterConfig, max_inflight_messages: usize) -> anyhow::Result<Self> {
+        if max_inflight_messages == 0 {
+            anyhow::bail!("Max inflight messages must be greater than 0.");
+        }
+        Ok(Self {
+            config: config.clone(),
+            max_inflight_messages,
+            thread: None,
+            ops_queue: None,
+            is_started: OnceLock::new(),
+            is_shutdown: Arc::new(OnceLock::new()),
+        })
+    }
+
+    pub fn inflight_messages(&self) -> usize {
+        self.ops_queue
+            .as_ref()
+            .map_or(0, |q| self.max_inflight_messages - q.capacity())
+    }
+
+    pub fn has_capacity(&self) -> bool {
+        self.ops_queue.as_ref().is_some_and(|q| q.capacity() > 0)
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.is_started.get().is_some()
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.is_shutdown.get().is_some()
+    }
+
+    pub fn start(&mut self) -> anyhow::Result<()> {
+        if self.is_shutdown() {
+            anyhow::bail!("Writer is shutdown.");
+        }
+        if self.is_started() {
+            anyhow::bail!("Writer is already started.");
+        }
+        _ = self.is_started.set(());
+        let (sender, mut receiver) = channel(self.max_inflight_messages);
+        let writer = SyncWriter::new(&self.config)?;
+        let thread = std::thread::spawn(move || {
+            while let Some(command) = receiver.blocking_recv() {
+                match command {
+                    Command::Message(topic, message, payload, resp_channel) => {
+                        let _ = resp_channel.send(writer.send_message(
+                            &topic,
+                            &message,
+                            &payload.iter().map(|e| e.as_slice()).collect::<Vec<_>>(),
+                        ));
+                    }
+                    Command::Shutdown => break,
+                }
+            }
+        });
+        self.thread = Some(thread);
+        self.ops_queue = Some(sender);
+        Ok(())
+    }
+
+    /// Stops the I/O thread after the queued operations are completed.
+    ///
+    pub async fn shutdown(&mut self) -> anyhow::Result<()> {
+        if self.is_shutdown() {
+            anyhow::bail!("Writer is shutdown.");
+        }
+        if !self.is_started() {
+            anyhow::bail!("Writer is not started.");
+        }
+        let Some(thread) = self.thread.take() else {
+            anyhow::bail!("Writer is not running.");
+        };
+        _ = self.is_shutdown.set(());
+        if let Some(queue) = self.ops_queue.take() {
+            queue
+                .send(Command::Shutdown)
+                .await
+                .map_err(|_| anyhow::anyhow!("Failed to send shutdown command to channel."))?;
+        }
+        tokio::task::spawn_blocking(move || thread.join())
+            .await?
+            .map_err(|_| anyhow::anyhow!("Failed to join thread."))?;
+        Ok(())
+    }
+
+    async fn enqueue(
+        &self,
+        topic: &str,
+        message: Message,
+        payload: Vec<Vec<u8>>,
+    ) -> anyhow::Result<WriterResult> {
+        if !self.is_started() {
+            anyhow::bail!("Writer is not started.");
+        }
+        let Some(queue) = &self.ops_queue else {
+            anyhow::bail!("Writer is shutdown.");
+        };
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        queue
+            .send(Command::Message(
+                topic.to_string(),
+                Box::new(message),
+                payload,
+                resp_sender,
+            ))
+            .await
+            .map_err(|_| anyhow::anyhow!("Failed to send message to channel."))?;
+        resp_receiver.await?
+    }
+
+    pub async fn send_eos(&self, topic: &str) -> anyhow::Result<WriterResult> {
+        let eos = Message::end_of_stream(EndOfStream::new(topic.to_string()));
+        self.enqueue(topic, eos, vec![]).await
+    }
+
+    pub async fn send_message(
+        &self,
+        topic: &str,
+        message: &Message,
+        payload: &[&[u8]],
+    ) -> anyhow::Result<WriterResult> {
+        let payload = payload.iter().map(|e| e.to_vec()).collect();
+        self.enqueue(topic, message.clone(), payload).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::message::Message;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::reader::ReaderResult;
+    use crate::transport::zeromq::{
+        AsyncReader, AsyncWriter, ReaderConfig, WriterConfig, WriterResult,
+    };
+
+    #[test]
+    fn test_send_message_to_reader() -> anyhow::Result<()> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(async {
+            let mut reader = AsyncReader::new(
+                &ReaderConfig::new()
+                    .url("rep+bind:ipc:///tmp/test/async-req-rep")?
+                    .build()?,
+                1,
+            )?;
+            reader.start()?;
+            let mut writer = AsyncWriter::new(
+                &WriterConfig::new()
+                    .url("req+connect:ipc:///tmp/test/async-req-rep")?
+                    .with_send_timeout(100)?
+                    .with_receive_timeout(100)?
+                    .with_send_retries(1)?
+                    .with_receive_retries(1)?
+                    .build()?,
+                1,
+            )?;
+            assert!(writer.send_eos("test").await.is_err());
+            writer.start()?;
+            assert!(writer.has_capacity());
+            let result = writer
+                .send_message(
+                    "test",
+                    &Message::user_data(UserData::new("test")),
+                    &[b"test"],
+                )
+                .await?;
+            assert!(matches!(result, WriterResult::Ack { .. }));
+            let reader_result = reader.receive().await?;
+            assert!(
+                matches!(reader_result, ReaderResult::Message {message,topic,routing_id,data}
+                    if message.is_user_data() && topic == b"test" && routing_id.is_none() && data == vec![b"test".to_vec()]
+                )
+            );
+            writer.shutdown().await?;
+            assert!(writer.send_eos("test").await.is_err());
+            reader.shutdown().await?;
+            Ok(())
+        })
+    }
+}
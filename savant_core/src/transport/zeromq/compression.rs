@@ -0,0 +1,210 @@
+use std::io::Read;
+
+use anyhow::bail;
+
+/// The prefix of the message frame marking the compressed multipart messages. The byte after the
+/// prefix identifies the codec. Protobuf messages never start with the prefix, so the uncompressed
+/// messages of the older writers are still accepted.
+///
+const COMPRESSION_HEADER: &[u8] = b"\xffSVZ";
+const LZ4_ID: u8 = 1;
+const ZSTD_ID: u8 = 2;
+const ZSTD_LEVEL: i32 = 3;
+/// The default limit of the decompressed size of a message frame, see
+/// [`ReaderConfigBuilder::with_max_decompressed_size`](super::ReaderConfigBuilder::with_max_decompressed_size).
+pub(crate) const MAX_DECOMPRESSED_SIZE: usize = 256 * 1024 * 1024;
+
+/// The codec the writer uses to compress the message and the extra frames. The reader detects the
+/// codec by the frame header and decompresses the frames transparently.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompressionCodec {
+    #[default]
+    None,
+    Lz4,
+    Zstd,
+}
+
+impl CompressionCodec {
+    fn id(&self) -> Option<u8> {
+        match self {
+            CompressionCodec::None => None,
+            CompressionCodec::Lz4 => Some(LZ4_ID),
+            CompressionCodec::Zstd => Some(ZSTD_ID),
+        }
+    }
+
+    fn from_id(id: u8) -> anyhow::Result<Self> {
+        match id {
+            LZ4_ID => Ok(CompressionCodec::Lz4),
+            ZSTD_ID => Ok(CompressionCodec::Zstd),
+            _ => bail!("Unknown compression codec id: {}", id),
+        }
+    }
+
+    pub fn compress(&self, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            CompressionCodec::None => data.to_vec(),
+            CompressionCodec::Lz4 => lz4_flex::compress_prepend_size(data),
+            CompressionCodec::Zstd => zstd::bulk::compress(data, ZSTD_LEVEL)?,
+        })
+    }
+
+    /// Decompresses the data, failing when the decompressed data is larger than `max_size`
+    /// bytes. The limit is checked before the memory is allocated, so a crafted frame cannot
+    /// exhaust the memory of the reader.
+    ///
+    pub fn decompress(&self, data: &[u8], max_size: usize) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            CompressionCodec::None => data.to_vec(),
+            CompressionCodec::Lz4 => {
+                let Some(prefix) = data.get(..4) else {
+                    bail!("Lz4 frame is truncated.");
+                };
+                let size = u32::from_le_bytes(prefix.try_into()?) as usize;
+                if size > max_size {
+                    bail!(
+                        "Decompressed size {} exceeds the limit of {} bytes.",
+                        size,
+                        max_size
+                    );
+                }
+                lz4_flex::decompress_size_prepended(data)?
+            }
+            CompressionCodec::Zstd => {
+                let mut data = zstd::stream::read::Decoder::new(data)?.take(max_size as u64 + 1);
+                let mut decompressed = Vec::new();
+                data.read_to_end(&mut decompressed)?;
+                if decompressed.len() > max_size {
+                    bail!("Decompressed size exceeds the limit of {} bytes.", max_size);
+                }
+                decompressed
+            }
+        })
+    }
+}
+
+/// Compresses the serialized message and the extra frames, the message frame gets the header.
+/// Returns `None` when the codec is [`CompressionCodec::None`].
+///
+pub(crate) fn compress_parts(
+    codec: CompressionCodec,
+    message: &[u8],
+    extra: &[&[u8]],
+) -> anyhow::Result<Option<(Vec<u8>, Vec<Vec<u8>>)>> {
+    let Some(id) = codec.id() else {
+        return Ok(None);
+    };
+    let mut frame = COMPRESSION_HEADER.to_vec();
+    frame.push(id);
+    frame.extend(codec.compress(message)?);
+    let extra = extra
+        .iter()
+        .map(|part| codec.compress(part))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(Some((frame, extra)))
+}
+
+/// Decompresses the message frame and the extra frames when the message frame has the header,
+/// each of the frames is limited to `max_size` bytes. Returns `None` for the uncompressed
+/// messages.
+///
+pub(crate) fn decompress_parts(
+    message: &[u8],
+    extra: &[Vec<u8>],
+    max_size: usize,
+) -> anyhow::Result<Option<(Vec<u8>, Vec<Vec<u8>>)>> {
+    let Some(rest) = message.strip_prefix(COMPRESSION_HEADER) else {
+        return Ok(None);
+    };
+    let Some((id, compressed)) = rest.split_first() else {
+        bail!("Compressed message frame is truncated.");
+    };
+    let codec = CompressionCodec::from_id(*id)?;
+    let extra = extra
+        .iter()
+        .map(|part| codec.decompress(part, max_size))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(Some((codec.decompress(compressed, max_size)?, extra)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::{
+        ReaderConfig, ReaderResult, SyncReader, SyncWriter, WriterConfig,
+    };
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        let message = vec![7u8; 1024];
+        let extra: Vec<&[u8]> = vec![b"payload", b""];
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let (frame, parts) = compress_parts(codec, &message, &extra)?.unwrap();
+            assert!(frame.len() < message.len());
+            let (restored, restored_extra) =
+                decompress_parts(&frame, &parts, MAX_DECOMPRESSED_SIZE)?.unwrap();
+            assert_eq!(restored, message);
+            assert_eq!(restored_extra, vec![b"payload".to_vec(), vec![]]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_uncompressed() -> anyhow::Result<()> {
+        assert!(compress_parts(CompressionCodec::None, b"message", &[])?.is_none());
+        assert!(decompress_parts(b"message", &[], MAX_DECOMPRESSED_SIZE)?.is_none());
+        let mut frame = COMPRESSION_HEADER.to_vec();
+        frame.push(42);
+        assert!(decompress_parts(&frame, &[], MAX_DECOMPRESSED_SIZE).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompressed_size_limit() -> anyhow::Result<()> {
+        let message = vec![0u8; 64 * 1024];
+        for codec in [CompressionCodec::Lz4, CompressionCodec::Zstd] {
+            let (frame, parts) = compress_parts(codec, &message, &[&message])?.unwrap();
+            assert!(decompress_parts(&frame, &parts, message.len()).is_ok());
+            assert!(decompress_parts(&frame, &parts, message.len() - 1).is_err());
+            assert!(decompress_parts(&frame, &[], 1024).is_err());
+        }
+
+        // a forged lz4 size prefix is rejected before the memory is allocated
+        let mut forged = lz4_flex::compress_prepend_size(b"message");
+        forged[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let error = CompressionCodec::Lz4
+            .decompress(&forged, MAX_DECOMPRESSED_SIZE)
+            .unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_compressed_transport() -> anyhow::Result<()> {
+        let path = "/tmp/test/compressed-transport";
+        std::fs::remove_dir_all(path).unwrap_or_default();
+        let reader = SyncReader::new(
+            &ReaderConfig::new()
+                .url(&format!("router+bind:ipc://{}", path))?
+                .with_receive_timeout(1000)?
+                .build()?,
+        )?;
+        let writer = SyncWriter::new(
+            &WriterConfig::new()
+                .url(&format!("dealer+connect:ipc://{}", path))?
+                .with_compression(CompressionCodec::Lz4)?
+                .build()?,
+        )?;
+        let payload = vec![1u8; 4096];
+        let message = Message::user_data(UserData::new("test"));
+        writer.send_message("test", &message, &[&payload])?;
+        let res = reader.receive()?;
+        assert!(
+            matches!(res, ReaderResult::Message { message: m, data, .. } if m.is_user_data() && data == [payload.as_slice()])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,294 @@
+use anyhow::{anyhow, bail};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const CURVE_KEY_SIZE: usize = 32;
+const KEY_FILE_PERMISSIONS: u32 = 0o600;
+const ZAP_ENDPOINT: &str = "inproc://zeromq.zap.01";
+const ZAP_POLL_TIMEOUT: i32 = 100;
+
+fn decode_key(key: &str) -> anyhow::Result<Vec<u8>> {
+    let decoded = zmq::z85_decode(key).map_err(|e| anyhow!("Invalid CURVE key: {:?}", e))?;
+    if decoded.len() != CURVE_KEY_SIZE {
+        bail!(
+            "Invalid CURVE key length: expected {} bytes, got {}",
+            CURVE_KEY_SIZE,
+            decoded.len()
+        );
+    }
+    Ok(decoded)
+}
+
+fn encode_key(key: &[u8]) -> anyhow::Result<String> {
+    zmq::z85_encode(key).map_err(|e| anyhow!("Failed to encode CURVE key: {:?}", e))
+}
+
+/// The CURVE keypair in the Z85 text encoding.
+///
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurveKeyPair {
+    public_key: String,
+    secret_key: String,
+}
+
+impl std::fmt::Debug for CurveKeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CurveKeyPair")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CurveKeyPair {
+    pub fn generate() -> anyhow::Result<Self> {
+        if zmq::has("curve") != Some(true) {
+            bail!("ZeroMQ library is built without CURVE support.");
+        }
+        let pair = zmq::CurveKeyPair::new()?;
+        Ok(Self {
+            public_key: encode_key(&pair.public_key)?,
+            secret_key: encode_key(&pair.secret_key)?,
+        })
+    }
+
+    pub fn new(public_key: &str, secret_key: &str) -> anyhow::Result<Self> {
+        decode_key(public_key)?;
+        decode_key(secret_key)?;
+        Ok(Self {
+            public_key: public_key.to_string(),
+            secret_key: secret_key.to_string(),
+        })
+    }
+
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    pub fn secret_key(&self) -> &str {
+        &self.secret_key
+    }
+
+    /// Saves the keypair as YAML to a new file, which is created readable only by the owner, so
+    /// the secret key is never exposed. An existing file is not overwritten.
+    ///
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let yaml = serde_yaml::to_string(self)?;
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(KEY_FILE_PERMISSIONS)
+            .open(path)?;
+        file.write_all(yaml.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let pair: Self = serde_yaml::from_str(&fs::read_to_string(path)?)?;
+        Self::new(&pair.public_key, &pair.secret_key)
+    }
+}
+
+/// The CURVE security settings of a socket. The server role is independent of binding: the server
+/// owns the keypair known to the clients in advance, the clients authenticate the server with its
+/// public key.
+///
+/// When `allowed_clients` is set, the server accepts only the clients with the listed public keys,
+/// otherwise any client is accepted and only the encryption is provided.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CurveConfig {
+    Server {
+        keypair: CurveKeyPair,
+        allowed_clients: Option<Vec<String>>,
+    },
+    Client {
+        keypair: CurveKeyPair,
+        server_public_key: String,
+    },
+}
+
+impl CurveConfig {
+    pub fn server(
+        keypair: CurveKeyPair,
+        allowed_clients: Option<Vec<String>>,
+    ) -> anyhow::Result<Self> {
+        for key in allowed_clients.iter().flatten() {
+            decode_key(key)?;
+        }
+        Ok(Self::Server {
+            keypair,
+            allowed_clients,
+        })
+    }
+
+    pub fn client(keypair: CurveKeyPair, server_public_key: &str) -> anyhow::Result<Self> {
+        decode_key(server_public_key)?;
+        Ok(Self::Client {
+            keypair,
+            server_public_key: server_public_key.to_string(),
+        })
+    }
+
+    pub(crate) fn apply(&self, socket: &zmq::Socket) -> anyhow::Result<()> {
+        match self {
+            CurveConfig::Server { keypair, .. } => {
+                socket.set_curve_server(true)?;
+                socket.set_curve_secretkey(&decode_key(keypair.secret_key())?)?;
+            }
+            CurveConfig::Client {
+                keypair,
+                server_public_key,
+            } => {
+                socket.set_curve_serverkey(&decode_key(server_public_key)?)?;
+                socket.set_curve_publickey(&decode_key(keypair.public_key())?)?;
+                socket.set_curve_secretkey(&decode_key(keypair.secret_key())?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The ZAP handler accepting only the CURVE clients with the allowed public keys. The handler
+/// serves all the sockets of the context and stops when the context is terminated or the
+/// authenticator is dropped.
+///
+pub(crate) struct CurveAuthenticator {
+    stopped: Arc<AtomicBool>,
+}
+
+impl CurveAuthenticator {
+    pub fn start(context: &zmq::Context, allowed_clients: &[String]) -> anyhow::Result<Self> {
+        let allowed = allowed_clients
+            .iter()
+            .map(|key| decode_key(key))
+            .collect::<anyhow::Result<HashSet<_>>>()?;
+        let handler = context.socket(zmq::REP)?;
+        handler.set_rcvtimeo(ZAP_POLL_TIMEOUT)?;
+        handler.set_linger(0)?;
+        handler.bind(ZAP_ENDPOINT)?;
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_stopped = stopped.clone();
+        std::thread::spawn(move || {
+            while !thread_stopped.load(Ordering::Relaxed) {
+                let request = match handler.recv_multipart(0) {
+                    Ok(request) => request,
+                    Err(zmq::Error::EAGAIN) => continue,
+                    Err(_) => break,
+                };
+                // version, request id, domain, address, identity, mechanism, credentials
+                if request.len() < 6 {
+                    warn!(
+                        target: "savant_rs::zeromq::curve",
+                        "Malformed ZAP request with {} frames", request.len()
+                    );
+                    continue;
+                }
+                let accepted = request[5] == b"CURVE"
+                    && request.get(6).is_some_and(|key| allowed.contains(key));
+                let client = request.get(6).and_then(|key| encode_key(key).ok());
+                debug!(
+                    target: "savant_rs::zeromq::curve",
+                    "CURVE client {:?} from {}: accepted = {}",
+                    client,
+                    String::from_utf8_lossy(&request[3]),
+                    accepted
+                );
+                let (status, text) = if accepted {
+                    (&b"200"[..], &b"OK"[..])
+                } else {
+                    (&b"400"[..], &b"Client key is not allowed"[..])
+                };
+                let user_id = client.unwrap_or_default();
+                let reply: [&[u8]; 6] =
+                    [b"1.0", &request[1], status, text, user_id.as_bytes(), b""];
+                if handler.send_multipart(reply, 0).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self { stopped })
+    }
+}
+
+impl Drop for CurveAuthenticator {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::{
+        ReaderConfig, ReaderResult, SyncReader, SyncWriter, WriterConfig, WriterResult,
+    };
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_keypair() -> anyhow::Result<()> {
+        let pair = CurveKeyPair::generate()?;
+        assert_eq!(pair.public_key().len(), 40);
+        let path = Path::new("/tmp/test/curve-keypair.yml");
+        fs::create_dir_all(path.parent().unwrap())?;
+        let _ = fs::remove_file(path);
+        pair.save(path)?;
+        assert_eq!(
+            fs::metadata(path)?.permissions().mode() & 0o777,
+            KEY_FILE_PERMISSIONS
+        );
+        assert_eq!(CurveKeyPair::load(path)?, pair);
+        assert!(pair.save(path).is_err());
+        assert!(CurveKeyPair::new("short", pair.secret_key()).is_err());
+        assert!(!format!("{:?}", pair).contains(pair.secret_key()));
+        Ok(())
+    }
+
+    fn run(allowed: bool) -> anyhow::Result<bool> {
+        let server = CurveKeyPair::generate()?;
+        let client = CurveKeyPair::generate()?;
+        let allowed_clients = if allowed {
+            vec![client.public_key().to_string()]
+        } else {
+            vec![CurveKeyPair::generate()?.public_key().to_string()]
+        };
+        let path = format!("/tmp/test/curve-{}", allowed);
+        let reader = SyncReader::new(
+            &ReaderConfig::new()
+                .url(&format!("router+bind:ipc://{}", path))?
+                .with_receive_timeout(1000)?
+                .with_curve(CurveConfig::server(server.clone(), Some(allowed_clients))?)?
+                .build()?,
+        )?;
+        let writer = SyncWriter::new(
+            &WriterConfig::new()
+                .url(&format!("dealer+connect:ipc://{}", path))?
+                .with_send_timeout(1000)?
+                .with_send_retries(0)?
+                .with_curve(CurveConfig::client(client, server.public_key())?)?
+                .build()?,
+        )?;
+        let message = Message::user_data(UserData::new("test"));
+        let res = writer.send_message("test", &message, &[])?;
+        if matches!(res, WriterResult::SendTimeout) {
+            return Ok(false);
+        }
+        Ok(matches!(reader.receive()?, ReaderResult::Message { .. }))
+    }
+
+    #[test]
+    fn test_curve_transport() -> anyhow::Result<()> {
+        assert!(run(true)?);
+        assert!(!run(false)?);
+        Ok(())
+    }
+}
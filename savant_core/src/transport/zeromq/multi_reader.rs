@@ -0,0 +1,195 @@
+use crate::transport::zeromq::reader::{poll_readers, ReaderResult};
+use crate::transport::zeromq::{NoopResponder, Reader, ReaderConfig, ZmqSocketProvider};
+use std::cmp::Reverse;
+
+/// The result of [`MultiReader::receive`] tagged by the endpoint it was read from. The endpoint
+/// is not set for [`ReaderResult::Timeout`].
+///
+#[derive(Debug)]
+pub struct MultiReaderResult {
+    pub endpoint: Option<String>,
+    pub result: ReaderResult,
+}
+
+struct Source {
+    endpoint: String,
+    reader: Reader<NoopResponder, ZmqSocketProvider>,
+    weight: i64,
+    current_weight: i64,
+}
+
+/// Reads several endpoints from a single thread polling their sockets. When several endpoints
+/// have messages, they are read with the smooth weighted round-robin, so an endpoint with the
+/// weight `w` is read `w` times more often than an endpoint with the weight 1 and none of them
+/// starves.
+///
+pub struct MultiReader {
+    sources: Vec<Source>,
+    receive_timeout: i64,
+}
+
+impl MultiReader {
+    /// Creates the readers for the configurations with their weights. The receive timeout (in
+    /// milliseconds) is the time to wait for a message from any of the endpoints.
+    ///
+    pub fn new(configs: &[(ReaderConfig, u32)], receive_timeout: i64) -> anyhow::Result<Self> {
+        if configs.is_empty() {
+            anyhow::bail!("At least one reader configuration is required.");
+        }
+        if receive_timeout < 0 {
+            anyhow::bail!("Receive timeout must be greater than or equal to 0.");
+        }
+        let mut sources = Vec::with_capacity(configs.len());
+        for (config, weight) in configs {
+            if *weight == 0 {
+                anyhow::bail!(
+                    "Weight of endpoint {} must be greater than 0.",
+                    config.endpoint()
+                );
+            }
+            sources.push(Source {
+                endpoint: config.endpoint().clone(),
+                reader: Reader::new(config)?,
+                weight: *weight as i64,
+                current_weight: 0,
+            });
+        }
+        Ok(Self {
+            sources,
+            receive_timeout,
+        })
+    }
+
+    pub fn endpoints(&self) -> Vec<String> {
+        self.sources.iter().map(|s| s.endpoint.clone()).collect()
+    }
+
+    /// Waits for a message from any of the endpoints and reads the one chosen by the weights.
+    ///
+    pub fn receive(&mut self) -> anyhow::Result<MultiReaderResult> {
+        let readers = self.sources.iter().map(|s| &s.reader).collect::<Vec<_>>();
+        let ready = poll_readers(&readers, self.receive_timeout)?;
+        if ready.is_empty() {
+            return Ok(MultiReaderResult {
+                endpoint: None,
+                result: ReaderResult::Timeout,
+            });
+        }
+        let total_weight = ready.iter().map(|i| self.sources[*i].weight).sum::<i64>();
+        for i in &ready {
+            let source = &mut self.sources[*i];
+            source.current_weight += source.weight;
+        }
+        let chosen = *ready
+            .iter()
+            .max_by_key(|i| (self.sources[**i].current_weight, Reverse(**i)))
+            .unwrap();
+        let source = &mut self.sources[chosen];
+        source.current_weight -= total_weight;
+        Ok(MultiReaderResult {
+            endpoint: Some(source.endpoint.clone()),
+            result: source.reader.receive()?,
+        })
+    }
+
+    /// Blacklists the source for all the endpoints.
+    ///
+    pub fn blacklist_source(&self, source_id: &[u8]) {
+        for source in &self.sources {
+            source.reader.blacklist_source(source_id);
+        }
+    }
+
+    pub fn is_blacklisted(&self, source_id: &[u8]) -> bool {
+        self.sources
+            .iter()
+            .any(|s| s.reader.is_blacklisted(source_id))
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.sources.iter().all(|s| s.reader.is_alive())
+    }
+
+    pub fn destroy(&self) -> anyhow::Result<()> {
+        for source in &self.sources {
+            source.reader.destroy()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::message::Message;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::{
+        MultiReader, NoopResponder, ReaderConfig, ReaderResult, Writer, WriterConfig,
+        ZmqSocketProvider,
+    };
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_weighted_fairness() -> anyhow::Result<()> {
+        let paths = ["/tmp/test/multi-reader-a", "/tmp/test/multi-reader-b"];
+        let mut writers = Vec::new();
+        for path in paths {
+            std::fs::remove_dir_all(path).unwrap_or_default();
+            writers.push(Writer::<NoopResponder, ZmqSocketProvider>::new(
+                &WriterConfig::new()
+                    .url(&format!("pub+bind:ipc://{}", path))?
+                    .build()?,
+            )?);
+        }
+        let configs = paths
+            .iter()
+            .zip([2, 1])
+            .map(|(path, weight)| {
+                Ok((
+                    ReaderConfig::new()
+                        .url(&format!("sub+connect:ipc://{}", path))?
+                        .build()?,
+                    weight,
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut reader = MultiReader::new(&configs, 100)?;
+        thread::sleep(Duration::from_millis(1000));
+
+        let res = reader.receive()?;
+        assert!(res.endpoint.is_none() && matches!(res.result, ReaderResult::Timeout));
+
+        for writer in &mut writers {
+            for _ in 0..6 {
+                writer.send_message("test", &Message::user_data(UserData::new("test")), &[])?;
+            }
+        }
+        thread::sleep(Duration::from_millis(100));
+
+        let endpoints = reader.endpoints();
+        let mut counts = [0, 0];
+        for _ in 0..6 {
+            let res = reader.receive()?;
+            assert!(matches!(res.result, ReaderResult::Message { .. }));
+            let index = endpoints
+                .iter()
+                .position(|e| Some(e) == res.endpoint.as_ref());
+            counts[index.unwrap()] += 1;
+        }
+        assert_eq!(counts, [4, 2]);
+
+        // the rest is read from the remaining endpoint
+        for _ in 0..6 {
+            let res = reader.receive()?;
+            assert!(matches!(res.result, ReaderResult::Message { .. }));
+        }
+        let res = reader.receive()?;
+        assert!(matches!(res.result, ReaderResult::Timeout));
+
+        assert!(MultiReader::new(&[], 100).is_err());
+        assert!(MultiReader::new(&[(configs[0].0.clone(), 0)], 100).is_err());
+        reader.destroy()?;
+        assert!(reader.receive().is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::transport::zeromq::reader::ReaderResult;
-use crate::transport::zeromq::{ReaderConfig, SyncReader};
+use crate::transport::zeromq::{ReaderConfig, SyncReader, TransportStats};
 use crossbeam::channel::Receiver;
 use std::sync::{Arc, OnceLock};
 
@@ -51,6 +51,12 @@ impl NonBlockingReader {
         Ok(())
     }
 
+    /// Returns the counters of the reader, all zeros if the reader is not started.
+    ///
+    pub fn stats(&self) -> TransportStats {
+        self.reader.as_ref().map(|r| r.stats()).unwrap_or_default()
+    }
+
     pub fn enqueued_results(&self) -> usize {
         if let Some(receiver) = &self.receiver {
             receiver.len()
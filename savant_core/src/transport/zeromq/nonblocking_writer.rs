@@ -1,6 +1,6 @@
 use crate::message::Message;
 use crate::primitives::eos::EndOfStream;
-use crate::transport::zeromq::{SyncWriter, WriterConfig, WriterResult};
+use crate::transport::zeromq::{SyncWriter, TransportStats, WriterConfig, WriterResult};
 use crossbeam::channel::{Receiver, Sender, TryRecvError};
 use std::cell::OnceCell;
 use std::sync::{Arc, OnceLock};
@@ -49,6 +49,7 @@ pub struct NonBlockingWriter {
     ops_queue: Option<Sender<Command>>,
     is_started: OnceCell<()>,
     is_shutdown: Arc<OnceLock<()>>,
+    writer: Option<SyncWriter>,
 }
 
 impl NonBlockingWriter {
@@ -60,6 +61,7 @@ impl NonBlockingWriter {
             ops_queue: None,
             is_started: OnceCell::new(),
             is_shutdown: Arc::new(OnceLock::new()),
+            writer: None,
         })
     }
 
@@ -70,6 +72,12 @@ impl NonBlockingWriter {
         self.ops_queue.as_ref().unwrap().len() < self.max_inflight_messages
     }
 
+    /// Returns the counters of the writer, all zeros if the writer is not started.
+    ///
+    pub fn stats(&self) -> TransportStats {
+        self.writer.as_ref().map(|w| w.stats()).unwrap_or_default()
+    }
+
     pub fn is_started(&self) -> bool {
         self.is_started.get().is_some()
     }
@@ -112,6 +120,7 @@ impl NonBlockingWriter {
         let (sender, receiver) = crossbeam::channel::bounded(self.max_inflight_messages);
         let is_shutdown = self.is_shutdown.clone();
         let writer = SyncWriter::new(&self.config)?;
+        self.writer = Some(writer.clone());
         let thread = std::thread::spawn(move || {
             loop {
                 let command = receiver.recv()?;
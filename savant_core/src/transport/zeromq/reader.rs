@@ -4,12 +4,18 @@ use lru::LruCache;
 use parking_lot::Mutex;
 use std::num::NonZeroUsize;
 use std::str::from_utf8;
+use std::sync::Arc;
 use zmq::Context;
 
+use crate::message::validation::{MessageValidator, ValidationMode, Violation};
 use crate::message::Message;
+use crate::transport::zeromq::compression::decompress_parts;
+use crate::transport::zeromq::curve::CurveAuthenticator;
+use crate::transport::zeromq::reconnect::SocketMonitor;
+use crate::transport::zeromq::stats::StatsCollector;
 use crate::transport::zeromq::{
     create_ipc_dirs, set_ipc_permissions, MockSocketResponder, ReaderConfig, ReaderSocketType,
-    RoutingIdFilter, Socket, SocketProvider, CONFIRMATION_MESSAGE, ZMQ_LINGER,
+    RoutingIdFilter, Socket, SocketProvider, TransportStats, CONFIRMATION_MESSAGE, ZMQ_LINGER,
 };
 use crate::utils::bytes_to_hex_string;
 
@@ -17,8 +23,12 @@ pub struct Reader<R: MockSocketResponder, P: SocketProvider<R>> {
     context: Mutex<Option<Context>>,
     config: ReaderConfig,
     socket: Mutex<Option<Socket<R>>>,
+    monitor: Mutex<Option<SocketMonitor>>,
+    authenticator: Mutex<Option<CurveAuthenticator>>,
+    stats: Arc<StatsCollector>,
     routing_id_filter: Mutex<RoutingIdFilter>,
     source_blacklist_cache: Mutex<LruCache<Vec<u8>, u64>>,
+    validator: MessageValidator,
     phony: std::marker::PhantomData<P>,
 }
 
@@ -51,6 +61,18 @@ pub enum ReaderResult {
     },
     TooShort(Vec<Vec<u8>>),
     Blacklisted(Vec<u8>),
+    /// The source id is rejected by the [`SourceFilter`](crate::transport::zeromq::SourceFilter).
+    SourceDenied {
+        topic: Vec<u8>,
+        routing_id: Option<Vec<u8>>,
+    },
+    /// The message is rejected by the strict validation, see [`ValidationMode`].
+    Invalid {
+        message: Box<Message>,
+        topic: Vec<u8>,
+        routing_id: Option<Vec<u8>>,
+        violations: Vec<Violation>,
+    },
 }
 
 impl ReaderResult {
@@ -83,6 +105,49 @@ impl ReaderResult {
     }
 }
 
+/// Waits up to the timeout (in milliseconds) until any of the readers has an incoming message,
+/// returns the indices of the readers which can be read without blocking.
+///
+pub(crate) fn poll_readers<R: MockSocketResponder, P: SocketProvider<R>>(
+    readers: &[&Reader<R, P>],
+    timeout: i64,
+) -> anyhow::Result<Vec<usize>> {
+    let guards = readers.iter().map(|r| r.socket.lock()).collect::<Vec<_>>();
+    let mut ready = Vec::new();
+    let mut items = Vec::new();
+    let mut polled = Vec::new();
+    for (i, guard) in guards.iter().enumerate() {
+        match &**guard {
+            Some(Socket::ZmqSocket(socket)) => {
+                items.push(socket.as_poll_item(zmq::POLLIN));
+                polled.push(i);
+            }
+            Some(Socket::MockSocket(data, _)) => {
+                if !data.is_empty() {
+                    ready.push(i);
+                }
+            }
+            None => bail!(
+                "ZeroMQ socket for endpoint {} is no longer available, because it was destroyed.",
+                readers[i].config.endpoint()
+            ),
+        }
+    }
+    if !items.is_empty() {
+        let timeout = if ready.is_empty() { timeout } else { 0 };
+        zmq::poll(&mut items, timeout)?;
+        ready.extend(
+            items
+                .iter()
+                .zip(polled)
+                .filter(|(item, _)| item.is_readable())
+                .map(|(_, i)| i),
+        );
+        ready.sort_unstable();
+    }
+    Ok(ready)
+}
+
 impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
     pub fn new(config: &ReaderConfig) -> anyhow::Result<Self> {
         let context = Context::new();
@@ -92,6 +157,20 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
         socket.set_rcvhwm(*config.receive_hwm())?;
         socket.set_rcvtimeo(*config.receive_timeout())?;
         socket.set_linger(ZMQ_LINGER)?;
+        socket.set_reconnect_policy(config.reconnect_policy())?;
+        let stats = Arc::new(StatsCollector::new(
+            "reader",
+            config.endpoint(),
+            *config.export_metrics(),
+        ));
+        let monitor = socket.start_monitor(
+            &context,
+            config.endpoint(),
+            config.reconnect_policy(),
+            config.connection_callback(),
+            &stats,
+        )?;
+        let authenticator = socket.set_curve(&context, config.curve())?;
 
         if config.socket_type() == &ReaderSocketType::Sub {
             socket.set_subscribe(config.topic_prefix_spec().get().as_bytes())?;
@@ -117,12 +196,16 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
             context: Mutex::new(Some(context)),
             config: config.clone(),
             socket: Mutex::new(Some(socket)),
+            monitor: Mutex::new(monitor),
+            authenticator: Mutex::new(authenticator),
+            stats,
             routing_id_filter: Mutex::new(RoutingIdFilter::new(*config.routing_cache_size())?),
             source_blacklist_cache: Mutex::new(LruCache::new(
                 NonZeroUsize::new(*config.source_blacklist_size() as usize).ok_or(
                     anyhow::anyhow!("Source blacklist cache size must be greater than 0"),
                 )?,
             )),
+            validator: MessageValidator::new(*config.validation_mode()),
             phony: std::marker::PhantomData,
         })
     }
@@ -134,6 +217,8 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
             self.config.endpoint()
         );
         self.socket.lock().take();
+        self.monitor.lock().take();
+        self.authenticator.lock().take();
         self.context.lock().take();
         info!(
             target: "savant_rs::zeromq::reader",
@@ -147,6 +232,10 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
         self.socket.lock().is_some()
     }
 
+    pub fn stats(&self) -> TransportStats {
+        self.stats.snapshot()
+    }
+
     pub fn blacklist_source(&self, source: &[u8]) {
         info!(
             target: "savant_rs::zeromq::reader",
@@ -195,12 +284,26 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
     }
 
     pub fn receive(&self) -> anyhow::Result<ReaderResult> {
+        let res = self.read_message();
+        if let Ok(res) = &res {
+            self.stats.record_reader_result(res);
+        }
+        res
+    }
+
+    fn read_message(&self) -> anyhow::Result<ReaderResult> {
         if self.socket.lock().is_none() {
             bail!(
                 "ZeroMQ socket for endpoint {} is no longer available, because it was destroyed.",
                 self.config.endpoint()
             );
         }
+        if self.monitor.lock().as_ref().is_some_and(|m| m.gave_up()) {
+            bail!(
+                "ZeroMQ socket for endpoint {} gave up reconnecting to the peer",
+                self.config.endpoint()
+            );
+        }
         debug!(
             target: "savant_rs::zeromq::reader",
             "Waiting for message from ZeroMQ socket for endpoint {}",
@@ -238,6 +341,8 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
         }
 
         let parts = parts.unwrap();
+        self.stats
+            .record_bytes(parts.iter().map(|p| p.len()).sum::<usize>());
 
         let min_required_parts = match self.config.socket_type() {
             ReaderSocketType::Sub => 2,
@@ -282,9 +387,34 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
             return Ok(ReaderResult::Blacklisted(topic.clone()));
         }
 
+        if !self.config.source_filter().accepts(topic) {
+            debug!(
+                target: "savant_rs::zeromq::reader",
+                "Received message from denied source {} from ZeroMQ socket for endpoint {}",
+                from_utf8(topic).unwrap_or(&bytes_to_hex_string(topic)),
+                self.config.endpoint()
+            );
+            if self.config.socket_type() == &ReaderSocketType::Rep {
+                let mut bind = self.socket.lock();
+                let socket = bind.as_mut().unwrap();
+                socket.send(CONFIRMATION_MESSAGE, 0)?;
+            }
+            return Ok(ReaderResult::SourceDenied {
+                topic: topic.clone(),
+                routing_id: routing_id.cloned(),
+            });
+        }
+
+        let decompressed = decompress_parts(command, extra, *self.config.max_decompressed_size())?;
+        let (command, extra) = match &decompressed {
+            Some((command, extra)) => (command, extra.as_slice()),
+            None => (command, extra),
+        };
         let message = Box::new(crate::protobuf::deserialize(command)?);
 
         if message.is_end_of_stream() {
+            // resets the tracked PTS of the source
+            self.validator.validate(&message);
             if self.config.socket_type() != &ReaderSocketType::Sub {
                 debug!(
                     target: "savant_rs::zeromq::reader",
@@ -335,6 +465,24 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
         }
 
         if self.routing_id_filter.lock().allow(topic, &routing_id) {
+            // only the accepted messages are validated, so the filtered ones do not affect the
+            // tracked PTS of the sources
+            let violations = self.validator.validate(&message);
+            if !violations.is_empty() && self.validator.mode() == ValidationMode::Strict {
+                warn!(
+                    target: "savant_rs::zeromq::reader",
+                    "Rejected invalid message from ZeroMQ socket for endpoint {}, topic = {}: {}",
+                    self.config.endpoint(),
+                    from_utf8(topic).unwrap_or(&bytes_to_hex_string(topic)),
+                    violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("; ")
+                );
+                return Ok(ReaderResult::Invalid {
+                    message,
+                    topic: topic.clone(),
+                    routing_id: routing_id.cloned(),
+                    violations,
+                });
+            }
             Ok(ReaderResult::Message {
                 message,
                 topic: topic.clone(),
@@ -358,14 +506,16 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Reader<R, P> {
 #[cfg(test)]
 mod tests {
     mod router_tests {
+        use crate::message::validation::ValidationMode;
         use crate::message::Message;
         use crate::primitives::eos::EndOfStream;
         use crate::primitives::userdata::UserData;
         use crate::protobuf::serialize;
+        use crate::test::gen_frame;
         use crate::transport::zeromq::reader::ReaderResult;
         use crate::transport::zeromq::{
-            MockSocketProvider, NoopResponder, Reader, ReaderConfig, TopicPrefixSpec,
-            CONFIRMATION_MESSAGE,
+            MockSocketProvider, NoopResponder, Reader, ReaderConfig, SourceFilter, SourcePattern,
+            TopicPrefixSpec, CONFIRMATION_MESSAGE,
         };
 
         #[test]
@@ -402,6 +552,107 @@ mod tests {
             );
             Ok(())
         }
+
+        #[test]
+        fn test_source_filter() -> anyhow::Result<()> {
+            let conf = ReaderConfig::new()
+                .url("router+bind:ipc:///tmp/test")?
+                .with_source_filter(
+                    SourceFilter::default()
+                        .allow(SourcePattern::glob("tenant-a/*")?)
+                        .deny(SourcePattern::regex("tenant-a/internal.*")?),
+                )?
+                .build()?;
+
+            let reader = Reader::<NoopResponder, MockSocketProvider>::new(&conf)?;
+            let message = Message::user_data(UserData::new("test"));
+            let binary = crate::message::save_message(&message)?;
+            for (topic, accepted) in [
+                (&b"tenant-a/cam-1"[..], true),
+                (b"tenant-a/internal-1", false),
+                (b"tenant-b/cam-1", false),
+            ] {
+                reader
+                    .socket
+                    .lock()
+                    .as_mut()
+                    .unwrap()
+                    .send_multipart(&[b"routing-id", topic, &binary], 0)?;
+                let res = reader.receive()?;
+                if accepted {
+                    assert!(matches!(res, ReaderResult::Message { .. }));
+                } else {
+                    assert!(
+                        matches!(res, ReaderResult::SourceDenied { topic: t, .. } if t == topic)
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn test_strict_validation() -> anyhow::Result<()> {
+            let conf = ReaderConfig::new()
+                .url("router+bind:ipc:///tmp/test")?
+                .with_validation_mode(ValidationMode::Strict)?
+                .build()?;
+
+            let reader = Reader::<NoopResponder, MockSocketProvider>::new(&conf)?;
+            let mut frame = gen_frame();
+            frame.set_pts(10);
+            let binary = crate::message::save_message(&Message::video_frame(&frame))?;
+            reader
+                .socket
+                .lock()
+                .as_mut()
+                .unwrap()
+                .send_multipart(&[b"routing-id", b"topic", &binary], 0)?;
+            assert!(matches!(reader.receive()?, ReaderResult::Message { .. }));
+
+            frame.set_pts(5);
+            let binary = crate::message::save_message(&Message::video_frame(&frame))?;
+            reader
+                .socket
+                .lock()
+                .as_mut()
+                .unwrap()
+                .send_multipart(&[b"routing-id", b"topic", &binary], 0)?;
+            assert!(matches!(
+                reader.receive()?,
+                ReaderResult::Invalid { violations, .. } if violations.len() == 1
+            ));
+            Ok(())
+        }
+
+        #[test]
+        fn test_filtered_messages_are_not_validated() -> anyhow::Result<()> {
+            let conf = ReaderConfig::new()
+                .url("router+bind:ipc:///tmp/test")?
+                .with_validation_mode(ValidationMode::Strict)?
+                .with_topic_prefix_spec(TopicPrefixSpec::Prefix("topic2".into()))?
+                .build()?;
+
+            let reader = Reader::<NoopResponder, MockSocketProvider>::new(&conf)?;
+            let mut frame = gen_frame();
+            for (topic, pts) in [(b"topic2", 10), (b"topic0", 20), (b"topic2", 15)] {
+                frame.set_pts(pts);
+                let binary = crate::message::save_message(&Message::video_frame(&frame))?;
+                reader
+                    .socket
+                    .lock()
+                    .as_mut()
+                    .unwrap()
+                    .send_multipart(&[b"routing-id", topic, &binary], 0)?;
+                let res = reader.receive()?;
+                if topic == b"topic2" {
+                    assert!(matches!(res, ReaderResult::Message { .. }));
+                } else {
+                    assert!(matches!(res, ReaderResult::PrefixMismatch { .. }));
+                }
+            }
+            Ok(())
+        }
+
         #[test]
         fn test_empty_multipart() -> anyhow::Result<()> {
             let conf = ReaderConfig::new()
@@ -1,8 +1,10 @@
+use super::compression::MAX_DECOMPRESSED_SIZE;
 use super::{
-    parse_zmq_socket_uri, ReaderSocketType, SocketType, TopicPrefixSpec, IPC_PERMISSIONS,
-    RECEIVE_HWM, RECEIVE_TIMEOUT, ROUTING_ID_CACHE_SIZE, SOURCE_BLACKLIST_CACHE_EXPIRATION,
-    SOURCE_BLACKLIST_CACHE_SIZE,
+    parse_zmq_socket_uri, ConnectionCallback, CurveConfig, ReaderSocketType, ReconnectPolicy,
+    SocketType, TopicPrefixSpec, IPC_PERMISSIONS, RECEIVE_HWM, RECEIVE_TIMEOUT,
+    ROUTING_ID_CACHE_SIZE, SOURCE_BLACKLIST_CACHE_EXPIRATION, SOURCE_BLACKLIST_CACHE_SIZE,
 };
+use crate::message::validation::ValidationMode;
 use crate::utils::default_once::DefaultOnceCell;
 use anyhow::bail;
 use std::num::NonZeroU64;
@@ -55,6 +57,34 @@ impl ReaderConfig {
     pub fn source_blacklist_ttl(&self) -> &u64 {
         self.0.source_blacklist_ttl.get_or_init()
     }
+
+    pub fn validation_mode(&self) -> &ValidationMode {
+        self.0.validation_mode.get_or_init()
+    }
+
+    pub fn reconnect_policy(&self) -> &ReconnectPolicy {
+        self.0.reconnect_policy.get_or_init()
+    }
+
+    pub fn connection_callback(&self) -> &Option<ConnectionCallback> {
+        self.0.connection_callback.get_or_init()
+    }
+
+    pub fn curve(&self) -> &Option<CurveConfig> {
+        self.0.curve.get_or_init()
+    }
+
+    pub fn source_filter(&self) -> &SourceFilter {
+        self.0.source_filter.get_or_init()
+    }
+
+    pub fn export_metrics(&self) -> &bool {
+        self.0.export_metrics.get_or_init()
+    }
+
+    pub fn max_decompressed_size(&self) -> &usize {
+        self.0.max_decompressed_size.get_or_init()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -69,6 +99,13 @@ pub struct ReaderConfigBuilder {
     fix_ipc_permissions: DefaultOnceCell<Option<u32>>,
     source_blacklist_size: DefaultOnceCell<u64>,
     source_blacklist_ttl: DefaultOnceCell<u64>,
+    validation_mode: DefaultOnceCell<ValidationMode>,
+    reconnect_policy: DefaultOnceCell<ReconnectPolicy>,
+    connection_callback: DefaultOnceCell<Option<ConnectionCallback>>,
+    curve: DefaultOnceCell<Option<CurveConfig>>,
+    source_filter: DefaultOnceCell<SourceFilter>,
+    export_metrics: DefaultOnceCell<bool>,
+    max_decompressed_size: DefaultOnceCell<usize>,
 }
 
 impl Default for ReaderConfigBuilder {
@@ -84,6 +121,13 @@ impl Default for ReaderConfigBuilder {
             fix_ipc_permissions: DefaultOnceCell::new(Some(IPC_PERMISSIONS)),
             source_blacklist_size: DefaultOnceCell::new(SOURCE_BLACKLIST_CACHE_SIZE),
             source_blacklist_ttl: DefaultOnceCell::new(SOURCE_BLACKLIST_CACHE_EXPIRATION),
+            validation_mode: DefaultOnceCell::new(ValidationMode::Disabled),
+            reconnect_policy: DefaultOnceCell::new(ReconnectPolicy::default()),
+            connection_callback: DefaultOnceCell::new(None),
+            curve: DefaultOnceCell::new(None),
+            source_filter: DefaultOnceCell::new(SourceFilter::default()),
+            export_metrics: DefaultOnceCell::new(false),
+            max_decompressed_size: DefaultOnceCell::new(MAX_DECOMPRESSED_SIZE),
         }
     }
 }
@@ -163,6 +207,53 @@ impl ReaderConfigBuilder {
         self.source_blacklist_ttl.set(ttl.get())?;
         Ok(self)
     }
+
+    pub fn with_validation_mode(self, mode: ValidationMode) -> anyhow::Result<Self> {
+        self.validation_mode.set(mode)?;
+        Ok(self)
+    }
+
+    pub fn with_reconnect_policy(self, policy: ReconnectPolicy) -> anyhow::Result<Self> {
+        self.reconnect_policy.set(policy)?;
+        Ok(self)
+    }
+
+    pub fn with_connection_callback(
+        self,
+        callback: Option<ConnectionCallback>,
+    ) -> anyhow::Result<Self> {
+        self.connection_callback.set(callback)?;
+        Ok(self)
+    }
+
+    pub fn with_curve(self, curve: CurveConfig) -> anyhow::Result<Self> {
+        self.curve.set(Some(curve))?;
+        Ok(self)
+    }
+
+    pub fn with_source_filter(self, filter: SourceFilter) -> anyhow::Result<Self> {
+        self.source_filter.set(filter)?;
+        Ok(self)
+    }
+
+    /// Mirrors the transport counters to the metrics registry, the metrics are labeled with the
+    /// endpoint.
+    ///
+    pub fn with_export_metrics(self, export: bool) -> anyhow::Result<Self> {
+        self.export_metrics.set(export)?;
+        Ok(self)
+    }
+
+    /// Limits the size of every decompressed frame of the compressed messages, the messages
+    /// exceeding the limit are rejected with an error.
+    ///
+    pub fn with_max_decompressed_size(self, size: usize) -> anyhow::Result<Self> {
+        if size == 0 {
+            bail!("Max decompressed size must be positive.");
+        }
+        self.max_decompressed_size.set(size)?;
+        Ok(self)
+    }
 }
 
 #[cfg(test)]
@@ -178,6 +269,15 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_max_decompressed_size() -> anyhow::Result<()> {
+        let config = ReaderConfig::new().url("tcp://1.1.1.1:1234")?;
+        assert!(config.clone().with_max_decompressed_size(0).is_err());
+        let config = config.with_max_decompressed_size(1024)?.build()?;
+        assert_eq!(config.max_decompressed_size(), &1024);
+        Ok(())
+    }
+
     #[test]
     fn test_duplicate_configuration_fails() -> anyhow::Result<()> {
         let config = ReaderConfig::new().url("tcp://1.1.1.1:1234")?;
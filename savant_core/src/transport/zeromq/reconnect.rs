@@ -0,0 +1,243 @@
+use crate::transport::zeromq::stats::StatsCollector;
+use log::{debug, warn};
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const MONITOR_POLL_TIMEOUT: i32 = 100;
+
+/// Defines how a connecting socket restores the connection to a restarted peer. The interval
+/// between the attempts starts from `interval` and doubles on every failure up to
+/// `max_interval`; the backoff is disabled when `max_interval` is 0. The intervals are in
+/// milliseconds.
+///
+/// When `max_retries` is set, the socket gives up after the number of consecutive failed
+/// attempts: the reader and the writer return errors until the peer is connected again.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    pub interval: i32,
+    pub max_interval: i32,
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            interval: 100,
+            max_interval: 0,
+            max_retries: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub fn new(interval: i32, max_interval: i32, max_retries: Option<u32>) -> anyhow::Result<Self> {
+        if interval <= 0 {
+            anyhow::bail!("Reconnect interval must be greater than 0.");
+        }
+        if max_interval != 0 && max_interval < interval {
+            anyhow::bail!("Max reconnect interval must be 0 or not less than the interval.");
+        }
+        Ok(Self {
+            interval,
+            max_interval,
+            max_retries,
+        })
+    }
+}
+
+/// The connection state changes reported by the socket monitor.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    Listening,
+    Accepted,
+    Connected,
+    Disconnected,
+    ConnectRetried {
+        attempt: u32,
+    },
+    /// The number of the consecutive failed attempts exceeded
+    /// [`ReconnectPolicy::max_retries`].
+    GaveUp {
+        attempts: u32,
+    },
+    Closed,
+}
+
+/// The callback receiving the endpoint of the socket and the connection event. It is called
+/// from the monitoring thread.
+///
+#[derive(Clone)]
+pub struct ConnectionCallback(pub Arc<dyn Fn(&str, &ConnectionEvent) + Send + Sync>);
+
+impl ConnectionCallback {
+    pub fn new<F: Fn(&str, &ConnectionEvent) + Send + Sync + 'static>(f: F) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl Debug for ConnectionCallback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConnectionCallback")
+    }
+}
+
+/// Watches the socket events in a dedicated thread, tracks the give-up state of the reconnect
+/// policy, counts the reconnects and calls the connection callback. The thread stops when the
+/// socket is closed or the monitor is dropped.
+///
+pub(crate) struct SocketMonitor {
+    gave_up: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl SocketMonitor {
+    pub fn start(
+        context: &zmq::Context,
+        socket: &zmq::Socket,
+        endpoint: &str,
+        policy: &ReconnectPolicy,
+        callback: Option<ConnectionCallback>,
+        stats: Arc<StatsCollector>,
+    ) -> anyhow::Result<Self> {
+        let address = format!("inproc://monitor-{}", uuid::Uuid::now_v7());
+        socket.monitor(&address, zmq::SocketEvent::ALL as i32)?;
+        let pair = context.socket(zmq::PAIR)?;
+        pair.set_rcvtimeo(MONITOR_POLL_TIMEOUT)?;
+        pair.connect(&address)?;
+
+        let gave_up = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let (thread_gave_up, thread_stopped) = (gave_up.clone(), stopped.clone());
+        let (endpoint, max_retries) = (endpoint.to_string(), policy.max_retries);
+        std::thread::spawn(move || {
+            let notify = |event: ConnectionEvent| {
+                debug!(
+                    target: "savant_rs::zeromq::monitor",
+                    "Endpoint {}: {:?}", endpoint, event
+                );
+                if let Some(callback) = &callback {
+                    (callback.0)(&endpoint, &event);
+                }
+            };
+            let mut attempts = 0;
+            let mut disconnected = false;
+            while !thread_stopped.load(Ordering::Relaxed) {
+                let parts = match pair.recv_multipart(0) {
+                    Ok(parts) => parts,
+                    Err(zmq::Error::EAGAIN) => continue,
+                    Err(e) => {
+                        warn!(
+                            target: "savant_rs::zeromq::monitor",
+                            "Failed to receive the socket event for {}: {:?}", endpoint, e
+                        );
+                        break;
+                    }
+                };
+                let Some(raw) = parts.first().filter(|p| p.len() >= 2) else {
+                    continue;
+                };
+                let event = zmq::SocketEvent::from_raw(u16::from_ne_bytes([raw[0], raw[1]]));
+                match event {
+                    zmq::SocketEvent::LISTENING => notify(ConnectionEvent::Listening),
+                    zmq::SocketEvent::ACCEPTED | zmq::SocketEvent::CONNECTED => {
+                        if std::mem::take(&mut disconnected) {
+                            stats.record_reconnect();
+                        }
+                        if matches!(event, zmq::SocketEvent::ACCEPTED) {
+                            notify(ConnectionEvent::Accepted);
+                        } else {
+                            attempts = 0;
+                            thread_gave_up.store(false, Ordering::Relaxed);
+                            notify(ConnectionEvent::Connected);
+                        }
+                    }
+                    zmq::SocketEvent::DISCONNECTED => {
+                        disconnected = true;
+                        notify(ConnectionEvent::Disconnected);
+                    }
+                    zmq::SocketEvent::CONNECT_RETRIED => {
+                        attempts += 1;
+                        notify(ConnectionEvent::ConnectRetried { attempt: attempts });
+                        if max_retries.is_some_and(|max| attempts > max)
+                            && !thread_gave_up.swap(true, Ordering::Relaxed)
+                        {
+                            notify(ConnectionEvent::GaveUp { attempts });
+                        }
+                    }
+                    zmq::SocketEvent::CLOSED => notify(ConnectionEvent::Closed),
+                    zmq::SocketEvent::MONITOR_STOPPED => break,
+                    _ => {}
+                }
+            }
+        });
+        Ok(Self { gave_up, stopped })
+    }
+
+    pub fn gave_up(&self) -> bool {
+        self.gave_up.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for SocketMonitor {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::{
+        NoopResponder, Writer, WriterConfig, WriterResult, ZmqSocketProvider,
+    };
+    use parking_lot::Mutex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_policy_validation() {
+        assert!(ReconnectPolicy::new(0, 0, None).is_err());
+        assert!(ReconnectPolicy::new(100, 50, None).is_err());
+        assert!(ReconnectPolicy::new(100, 0, Some(3)).is_ok());
+    }
+
+    #[test]
+    fn test_give_up() -> anyhow::Result<()> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        let mut writer = Writer::<NoopResponder, ZmqSocketProvider>::new(
+            &WriterConfig::new()
+                .url("dealer+connect:tcp://127.0.0.1:1")?
+                .with_send_timeout(100)?
+                .with_send_retries(0)?
+                .with_reconnect_policy(ReconnectPolicy::new(10, 0, Some(2))?)?
+                .with_connection_callback(Some(ConnectionCallback::new(move |_, e| {
+                    events_clone.lock().push(e.clone());
+                })))?
+                .build()?,
+        )?;
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(events
+            .lock()
+            .iter()
+            .any(|e| matches!(e, ConnectionEvent::GaveUp { attempts: 3 })));
+        let res = writer.send_message("test", &Message::user_data(UserData::new("test")), &[]);
+        assert!(res.is_err());
+        writer.destroy()?;
+
+        let mut writer = Writer::<NoopResponder, ZmqSocketProvider>::new(
+            &WriterConfig::new()
+                .url("dealer+connect:tcp://127.0.0.1:1")?
+                .with_send_timeout(100)?
+                .with_send_retries(0)?
+                .build()?,
+        )?;
+        let res = writer.send_message("test", &Message::user_data(UserData::new("test")), &[])?;
+        assert!(matches!(res, WriterResult::SendTimeout));
+        Ok(())
+    }
+}
@@ -0,0 +1,325 @@
+use crate::match_query::MatchQuery;
+use crate::message::Message;
+use crate::metrics::{get_or_create_counter_family, SharedCounterFamily};
+use crate::primitives::frame::VideoFrameProxy;
+use crate::transport::zeromq::{
+    ReaderConfig, ReaderResult, SyncReader, SyncWriter, WriterConfig, WriterResult,
+};
+use anyhow::bail;
+use hashbrown::HashMap;
+use log::{debug, warn};
+
+const ROUTED_MESSAGES_METRIC_NAME: &str = "router_routed_messages";
+const DROPPED_MESSAGES_METRIC_NAME: &str = "router_dropped_messages";
+const FAILED_MESSAGES_METRIC_NAME: &str = "router_failed_messages";
+
+/// Defines what happens to a message after it matched a rule.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteMode {
+    /// The message is forwarded and the following rules are evaluated too.
+    Copy,
+    /// The message is forwarded and the evaluation stops.
+    Move,
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteRule {
+    pub name: String,
+    /// When set, the rule matches the frames (or batches) having at least one object matching
+    /// the query; other messages never match such a rule. When not set, every message matches.
+    pub query: Option<MatchQuery>,
+    pub targets: Vec<String>,
+    pub mode: RouteMode,
+}
+
+impl RouteRule {
+    pub fn new(name: &str, query: Option<MatchQuery>, targets: &[&str], mode: RouteMode) -> Self {
+        Self {
+            name: name.to_string(),
+            query,
+            targets: targets.iter().map(|t| t.to_string()).collect(),
+            mode,
+        }
+    }
+
+    fn matches(&self, message: &Message) -> bool {
+        let Some(query) = &self.query else {
+            return true;
+        };
+        let frame_matches = |f: &VideoFrameProxy| !f.access_objects(query).is_empty();
+        if let Some(frame) = message.as_video_frame() {
+            frame_matches(&frame)
+        } else if let Some(batch) = message.as_video_frame_batch() {
+            batch.frames().values().any(frame_matches)
+        } else {
+            false
+        }
+    }
+}
+
+/// The ordered list of the rules deciding where the messages go.
+///
+#[derive(Debug, Clone, Default)]
+pub struct RoutingTable {
+    rules: Vec<RouteRule>,
+}
+
+impl RoutingTable {
+    pub fn add_rule(&mut self, rule: RouteRule) -> anyhow::Result<()> {
+        if self.rules.iter().any(|r| r.name == rule.name) {
+            bail!("Rule {} is already defined", rule.name);
+        }
+        if rule.targets.is_empty() {
+            bail!("Rule {} has no targets", rule.name);
+        }
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    pub fn get_rules(&self) -> &[RouteRule] {
+        &self.rules
+    }
+
+    /// Evaluates the rules in order and returns the `(rule, target)` pairs the message must be
+    /// delivered to. A target is delivered to once, by the first rule which selected it.
+    ///
+    pub fn route(&self, message: &Message) -> Vec<(String, String)> {
+        let mut routes: Vec<(String, String)> = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.matches(message)) {
+            for target in &rule.targets {
+                if !routes.iter().any(|(_, t)| t == target) {
+                    routes.push((rule.name.clone(), target.clone()));
+                }
+            }
+            if rule.mode == RouteMode::Move {
+                break;
+            }
+        }
+        routes
+    }
+}
+
+#[derive(Debug)]
+pub enum RouterResult {
+    /// The message was forwarded; the results are reported per target.
+    Routed {
+        topic: Vec<u8>,
+        results: Vec<(String, anyhow::Result<WriterResult>)>,
+    },
+    /// The message matched no rule.
+    Dropped { topic: Vec<u8> },
+    /// The reader returned something other than a message, it is not forwarded.
+    NotRouted(ReaderResult),
+}
+
+/// Receives the messages with a reader and forwards them to the named writers according to the
+/// routing table. Every delivery is counted in the `router_routed_messages` metric labelled with
+/// the router, rule and target names.
+///
+pub struct Router {
+    name: String,
+    reader: SyncReader,
+    writers: HashMap<String, SyncWriter>,
+    table: RoutingTable,
+    routed: SharedCounterFamily,
+    dropped: SharedCounterFamily,
+    failed: SharedCounterFamily,
+}
+
+impl Router {
+    pub fn new(name: &str, reader_config: &ReaderConfig) -> anyhow::Result<Self> {
+        let reader = SyncReader::new(reader_config)?;
+        Ok(Self {
+            name: name.to_string(),
+            reader,
+            writers: HashMap::new(),
+            table: RoutingTable::default(),
+            routed: get_or_create_counter_family(
+                ROUTED_MESSAGES_METRIC_NAME,
+                Some("Number of messages forwarded by the router"),
+                &["router", "rule", "target"],
+                None,
+            ),
+            dropped: get_or_create_counter_family(
+                DROPPED_MESSAGES_METRIC_NAME,
+                Some("Number of messages matching no router rule"),
+                &["router"],
+                None,
+            ),
+            failed: get_or_create_counter_family(
+                FAILED_MESSAGES_METRIC_NAME,
+                Some("Number of messages the router failed to forward"),
+                &["router", "target"],
+                None,
+            ),
+        })
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn add_writer(&mut self, name: &str, config: &WriterConfig) -> anyhow::Result<()> {
+        if self.writers.contains_key(name) {
+            bail!("Writer {} is already defined", name);
+        }
+        let writer = SyncWriter::new(config)?;
+        self.writers.insert(name.to_string(), writer);
+        Ok(())
+    }
+
+    /// Adds the rule to the end of the routing table; all its targets must be defined writers.
+    ///
+    pub fn add_rule(&mut self, rule: RouteRule) -> anyhow::Result<()> {
+        if let Some(target) = rule.targets.iter().find(|t| !self.writers.contains_key(*t)) {
+            bail!("Rule {} refers to unknown writer {}", rule.name, target);
+        }
+        self.table.add_rule(rule)
+    }
+
+    pub fn get_routing_table(&self) -> &RoutingTable {
+        &self.table
+    }
+
+    /// Receives the next message and forwards it. Send failures do not stop the router, they are
+    /// reported in the result.
+    ///
+    pub fn route_next(&self) -> anyhow::Result<RouterResult> {
+        let (message, topic, data) = match self.reader.receive()? {
+            ReaderResult::Message {
+                message,
+                topic,
+                data,
+                ..
+            } => (message, topic, data),
+            other => return Ok(RouterResult::NotRouted(other)),
+        };
+        let routes = self.table.route(&message);
+        if routes.is_empty() {
+            debug!(
+                target: "savant_rs::zeromq::router",
+                "Router {} dropped the message with topic {}", self.name,
+                String::from_utf8_lossy(&topic));
+            self.dropped.lock().inc(1, &[&self.name])?;
+            return Ok(RouterResult::Dropped { topic });
+        }
+        let topic_str = String::from_utf8_lossy(&topic);
+        let data = data.iter().map(|d| d.as_slice()).collect::<Vec<_>>();
+        let mut results = Vec::with_capacity(routes.len());
+        for (rule, target) in routes {
+            let res = self.writers[&target].send_message(&topic_str, &message, &data);
+            match &res {
+                Ok(WriterResult::SendTimeout) | Ok(WriterResult::AckTimeout(_)) | Err(_) => {
+                    warn!(
+                        target: "savant_rs::zeromq::router",
+                        "Router {} failed to forward the message to {}: {:?}",
+                        self.name, target, res);
+                    self.failed.lock().inc(1, &[&self.name, &target])?;
+                }
+                Ok(_) => {
+                    self.routed.lock().inc(1, &[&self.name, &rule, &target])?;
+                }
+            }
+            results.push((target, res));
+        }
+        Ok(RouterResult::Routed { topic, results })
+    }
+
+    pub fn shutdown(&self) -> anyhow::Result<()> {
+        self.reader.shutdown()?;
+        for writer in self.writers.values() {
+            writer.shutdown()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_query::{eq, MatchQuery};
+    use crate::primitives::eos::EndOfStream;
+    use crate::test::gen_frame;
+
+    fn targets(routes: Vec<(String, String)>) -> Vec<String> {
+        routes.into_iter().map(|(_, t)| t).collect()
+    }
+
+    #[test]
+    fn test_routing_table() -> anyhow::Result<()> {
+        let mut table = RoutingTable::default();
+        table.add_rule(RouteRule::new(
+            "persons",
+            Some(MatchQuery::Label(eq("person"))),
+            &["persons"],
+            RouteMode::Move,
+        ))?;
+        table.add_rule(RouteRule::new(
+            "test",
+            Some(MatchQuery::Namespace(eq("test"))),
+            &["archive", "test"],
+            RouteMode::Copy,
+        ))?;
+        table.add_rule(RouteRule::new(
+            "all",
+            None,
+            &["archive", "all"],
+            RouteMode::Move,
+        ))?;
+        table.add_rule(RouteRule::new(
+            "unreachable",
+            None,
+            &["unreachable"],
+            RouteMode::Copy,
+        ))?;
+        assert!(table
+            .add_rule(RouteRule::new("all", None, &["all"], RouteMode::Copy))
+            .is_err());
+        assert!(table
+            .add_rule(RouteRule::new("empty", None, &[], RouteMode::Copy))
+            .is_err());
+
+        let routes = table.route(&Message::video_frame(&gen_frame()));
+        assert_eq!(
+            routes,
+            vec![
+                ("test".to_string(), "archive".to_string()),
+                ("test".to_string(), "test".to_string()),
+                ("all".to_string(), "all".to_string()),
+            ]
+        );
+
+        let eos = Message::end_of_stream(EndOfStream::new("test".to_string()));
+        assert_eq!(targets(table.route(&eos)), vec!["archive", "all"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_target() -> anyhow::Result<()> {
+        let mut router = Router::new(
+            "test",
+            &ReaderConfig::new()
+                .url("router+bind:ipc:///tmp/test/router-unknown-target")?
+                .build()?,
+        )?;
+        router.add_writer(
+            "out",
+            &WriterConfig::new()
+                .url("dealer+connect:ipc:///tmp/test/router-unknown-target-out")?
+                .build()?,
+        )?;
+        assert!(router
+            .add_rule(RouteRule::new(
+                "r",
+                None,
+                &["out", "other"],
+                RouteMode::Copy
+            ))
+            .is_err());
+        router.add_rule(RouteRule::new("r", None, &["out"], RouteMode::Copy))?;
+        assert_eq!(router.get_routing_table().get_rules().len(), 1);
+        router.shutdown()?;
+        Ok(())
+    }
+}
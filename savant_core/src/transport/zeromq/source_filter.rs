@@ -0,0 +1,104 @@
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+
+/// The pattern matching the source ids (topics) of the received messages.
+///
+#[derive(Debug, Clone)]
+pub enum SourcePattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl SourcePattern {
+    pub fn glob(pattern: &str) -> anyhow::Result<Self> {
+        Ok(Self::Glob(Glob::new(pattern)?.compile_matcher()))
+    }
+
+    /// The regular expression must match the whole source id.
+    ///
+    pub fn regex(pattern: &str) -> anyhow::Result<Self> {
+        Ok(Self::Regex(Regex::new(&format!("^(?:{})$", pattern))?))
+    }
+
+    pub fn matches(&self, source_id: &str) -> bool {
+        match self {
+            Self::Glob(glob) => glob.is_match(source_id),
+            Self::Regex(regex) => regex.is_match(source_id),
+        }
+    }
+}
+
+/// The allowlist and the denylist of the source ids evaluated by the reader before the message is
+/// deserialized. A message is dropped when its source id matches any denied pattern, or when the
+/// allowlist is not empty and the source id matches none of the allowed patterns.
+///
+#[derive(Debug, Clone, Default)]
+pub struct SourceFilter {
+    allow: Vec<SourcePattern>,
+    deny: Vec<SourcePattern>,
+}
+
+impl SourceFilter {
+    pub fn new(allow: Vec<SourcePattern>, deny: Vec<SourcePattern>) -> Self {
+        Self { allow, deny }
+    }
+
+    pub fn allow(mut self, pattern: SourcePattern) -> Self {
+        self.allow.push(pattern);
+        self
+    }
+
+    pub fn deny(mut self, pattern: SourcePattern) -> Self {
+        self.deny.push(pattern);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    pub fn accepts(&self, topic: &[u8]) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let source_id = String::from_utf8_lossy(topic);
+        if self.deny.iter().any(|p| p.matches(&source_id)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|p| p.matches(&source_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_filter() {
+        assert!(SourceFilter::default().accepts(b"any"));
+    }
+
+    #[test]
+    fn test_allow_deny() -> anyhow::Result<()> {
+        let filter = SourceFilter::default()
+            .allow(SourcePattern::glob("tenant-a/*")?)
+            .allow(SourcePattern::regex(r"cam-\d+")?)
+            .deny(SourcePattern::glob("tenant-a/internal*")?);
+        assert!(filter.accepts(b"tenant-a/cam-1"));
+        assert!(filter.accepts(b"cam-42"));
+        assert!(!filter.accepts(b"cam-42x"));
+        assert!(!filter.accepts(b"tenant-a/internal-1"));
+        assert!(!filter.accepts(b"tenant-b/cam-1"));
+
+        let filter = SourceFilter::default().deny(SourcePattern::regex("test.*")?);
+        assert!(filter.accepts(b"prod"));
+        assert!(!filter.accepts(b"test-1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        assert!(SourcePattern::glob("a[").is_err());
+        assert!(SourcePattern::regex("a(").is_err());
+    }
+}
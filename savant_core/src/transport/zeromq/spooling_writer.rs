@@ -0,0 +1,463 @@
+use crate::message::Message;
+use crate::primitives::eos::EndOfStream;
+use crate::protobuf::{deserialize, serialize};
+use crate::transport::zeromq::{
+    NoopResponder, Writer, WriterConfig, WriterResult, ZmqSocketProvider,
+};
+use anyhow::bail;
+use log::{debug, warn};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SPOOL_EXTENSION: &str = "spool";
+const TMP_EXTENSION: &str = "tmp";
+/// The subdirectory the undecodable messages are moved to, so they do not block the spool.
+const CORRUPT_DIRECTORY: &str = "corrupt";
+/// The interval between the attempts to drain the spool while the peer does not accept the
+/// messages, doubled after every failed attempt up to the maximum.
+const MIN_DRAIN_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_DRAIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The messages persisted to a directory, one file per message, in the FIFO order. The total
+/// size of the files is limited by the quota. The messages left in the directory are restored
+/// when the spool is reopened, the partially written files left by a crash are removed.
+///
+pub struct Spool {
+    directory: PathBuf,
+    quota: u64,
+    used: u64,
+    /// The sequence numbers and the sizes of the spooled messages.
+    entries: VecDeque<(u64, u64)>,
+    next_seq: u64,
+}
+
+type SpooledMessage = (String, Message, Vec<Vec<u8>>);
+
+fn write_chunk(buf: &mut Vec<u8>, chunk: &[u8]) {
+    buf.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+    buf.extend_from_slice(chunk);
+}
+
+fn read_chunk<'a>(buf: &mut &'a [u8]) -> anyhow::Result<&'a [u8]> {
+    if buf.len() < 4 {
+        bail!("Spooled message is truncated.");
+    }
+    let len = u32::from_le_bytes(buf[..4].try_into()?) as usize;
+    if buf.len() < 4 + len {
+        bail!("Spooled message is truncated.");
+    }
+    let chunk = &buf[4..4 + len];
+    *buf = &buf[4 + len..];
+    Ok(chunk)
+}
+
+fn decode_message(data: &[u8]) -> anyhow::Result<SpooledMessage> {
+    let mut buf = data;
+    let topic = String::from_utf8(read_chunk(&mut buf)?.to_vec())?;
+    let message = deserialize(read_chunk(&mut buf)?)?;
+    let mut payload = Vec::new();
+    while !buf.is_empty() {
+        payload.push(read_chunk(&mut buf)?.to_vec());
+    }
+    Ok((topic, message, payload))
+}
+
+impl Spool {
+    pub fn open(directory: &Path, quota: u64) -> anyhow::Result<Self> {
+        fs::create_dir_all(directory)?;
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            let extension = path.extension().and_then(|e| e.to_str());
+            if extension == Some(TMP_EXTENSION) && path.is_file() {
+                warn!(
+                    target: "savant_rs::zeromq::spooling_writer",
+                    "Removing the partially written spool file {}",
+                    path.display()
+                );
+                fs::remove_file(&path)?;
+                continue;
+            }
+            if extension != Some(SPOOL_EXTENSION) {
+                continue;
+            }
+            let seq = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse::<u64>().ok());
+            if let Some(seq) = seq {
+                entries.push((seq, fs::metadata(&path)?.len()));
+            }
+        }
+        entries.sort_unstable();
+        let used = entries.iter().map(|(_, size)| size).sum();
+        let next_seq = entries.last().map_or(0, |(seq, _)| seq + 1);
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            quota,
+            used,
+            entries: entries.into(),
+            next_seq,
+        })
+    }
+
+    fn path(&self, seq: u64) -> PathBuf {
+        self.directory
+            .join(format!("{:020}.{}", seq, SPOOL_EXTENSION))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The total size of the spooled messages in bytes.
+    ///
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    pub fn quota(&self) -> u64 {
+        self.quota
+    }
+
+    /// Appends the message, returns `false` when the message does not fit the quota.
+    ///
+    pub fn push(
+        &mut self,
+        topic: &str,
+        message: &Message,
+        payload: &[&[u8]],
+    ) -> anyhow::Result<bool> {
+        let mut buf = Vec::new();
+        write_chunk(&mut buf, topic.as_bytes());
+        write_chunk(&mut buf, &serialize(message)?);
+        for part in payload {
+            write_chunk(&mut buf, part);
+        }
+        let size = buf.len() as u64;
+        if self.used + size > self.quota {
+            return Ok(false);
+        }
+        let path = self.path(self.next_seq);
+        // the complete file is renamed, so a crash never leaves a partial message
+        let tmp_path = path.with_extension(TMP_EXTENSION);
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(&buf)?;
+        file.sync_data()?;
+        fs::rename(&tmp_path, &path)?;
+        self.entries.push_back((self.next_seq, size));
+        self.next_seq += 1;
+        self.used += size;
+        Ok(true)
+    }
+
+    /// Reads the oldest message without removing it. The messages which cannot be decoded are
+    /// moved to the `corrupt` subdirectory and skipped.
+    ///
+    pub fn peek(&mut self) -> anyhow::Result<Option<SpooledMessage>> {
+        while let Some((seq, size)) = self.entries.front().copied() {
+            let path = self.path(seq);
+            let data = match fs::read(&path) {
+                Ok(data) => data,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    warn!(
+                        target: "savant_rs::zeromq::spooling_writer",
+                        "Spooled message {} is missing, skipping it",
+                        path.display()
+                    );
+                    self.entries.pop_front();
+                    self.used -= size;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            match decode_message(&data) {
+                Ok(message) => return Ok(Some(message)),
+                Err(e) => {
+                    let corrupt = self.directory.join(CORRUPT_DIRECTORY);
+                    fs::create_dir_all(&corrupt)?;
+                    fs::rename(&path, corrupt.join(path.file_name().unwrap()))?;
+                    warn!(
+                        target: "savant_rs::zeromq::spooling_writer",
+                        "Spooled message {} cannot be decoded, moved to {}: {:?}",
+                        path.display(),
+                        corrupt.display(),
+                        e
+                    );
+                    self.entries.pop_front();
+                    self.used -= size;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Removes the oldest message.
+    ///
+    pub fn pop(&mut self) -> anyhow::Result<()> {
+        if let Some((seq, size)) = self.entries.pop_front() {
+            fs::remove_file(self.path(seq))?;
+            self.used -= size;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum SpoolingWriterResult {
+    /// The message is sent, the spool is empty.
+    Sent(WriterResult),
+    /// The message is spooled because the peer does not accept messages or the spool is not
+    /// drained yet.
+    Spooled { spooled_messages: usize },
+    /// The message is dropped because it does not fit the spool quota.
+    Dropped,
+}
+
+/// The writer which spills the messages to the [`Spool`] when the socket does not accept them
+/// (the send timeout is reached, e.g. because of the high water mark) instead of dropping them
+/// or blocking the pipeline. The spooled messages are sent first, in the original order, as soon
+/// as the peer recovers.
+///
+/// While the peer is down, the new messages are spooled without waiting for the send timeout;
+/// the spool is drained by the sends with the growing interval between the attempts. When no
+/// messages are sent, the spool is drained only by the explicit [`SpoolingWriter::drain`] calls,
+/// e.g. from a timer.
+///
+pub struct SpoolingWriter {
+    writer: Writer<NoopResponder, ZmqSocketProvider>,
+    spool: Spool,
+    drain_interval: Duration,
+    next_drain: Option<Instant>,
+}
+
+impl SpoolingWriter {
+    pub fn new(config: &WriterConfig, spool_directory: &Path, quota: u64) -> anyhow::Result<Self> {
+        Ok(Self {
+            writer: Writer::new(config)?,
+            spool: Spool::open(spool_directory, quota)?,
+            drain_interval: MIN_DRAIN_INTERVAL,
+            next_drain: None,
+        })
+    }
+
+    /// Delays the next drain attempt after the peer did not accept a message.
+    ///
+    fn postpone_drain(&mut self) {
+        self.next_drain = Some(Instant::now() + self.drain_interval);
+        self.drain_interval = (self.drain_interval * 2).min(MAX_DRAIN_INTERVAL);
+    }
+
+    fn is_drain_due(&self) -> bool {
+        self.next_drain.is_none_or(|t| Instant::now() >= t)
+    }
+
+    pub fn spooled_messages(&self) -> usize {
+        self.spool.len()
+    }
+
+    pub fn spooled_bytes(&self) -> u64 {
+        self.spool.used()
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.writer.is_started()
+    }
+
+    pub fn destroy(&mut self) -> anyhow::Result<()> {
+        self.writer.destroy()
+    }
+
+    /// Sends the spooled messages until the spool is empty or the peer stops accepting them.
+    /// Returns the number of the messages sent. The attempt is made regardless of the drain
+    /// interval.
+    ///
+    pub fn drain(&mut self) -> anyhow::Result<usize> {
+        let mut sent = 0;
+        while let Some((topic, message, payload)) = self.spool.peek()? {
+            let parts = payload.iter().map(|p| p.as_slice()).collect::<Vec<_>>();
+            match self.writer.send_message(&topic, &message, &parts)? {
+                WriterResult::SendTimeout => {
+                    debug!(
+                        target: "savant_rs::zeromq::spooling_writer",
+                        "Peer does not accept spooled messages, {} left",
+                        self.spool.len()
+                    );
+                    self.postpone_drain();
+                    return Ok(sent);
+                }
+                _ => {
+                    self.spool.pop()?;
+                    sent += 1;
+                }
+            }
+        }
+        self.drain_interval = MIN_DRAIN_INTERVAL;
+        self.next_drain = None;
+        Ok(sent)
+    }
+
+    pub fn send_eos(&mut self, topic: &str) -> anyhow::Result<SpoolingWriterResult> {
+        let m = Message::end_of_stream(EndOfStream::new(topic.to_string()));
+        self.send_message(topic, &m, &[])
+    }
+
+    pub fn send_message(
+        &mut self,
+        topic: &str,
+        message: &Message,
+        payload: &[&[u8]],
+    ) -> anyhow::Result<SpoolingWriterResult> {
+        if !self.spool.is_empty() && self.is_drain_due() {
+            self.drain()?;
+        }
+        if self.spool.is_empty() {
+            let res = self.writer.send_message(topic, message, payload)?;
+            if !matches!(res, WriterResult::SendTimeout) {
+                return Ok(SpoolingWriterResult::Sent(res));
+            }
+            self.postpone_drain();
+        }
+        if self.spool.push(topic, message, payload)? {
+            Ok(SpoolingWriterResult::Spooled {
+                spooled_messages: self.spool.len(),
+            })
+        } else {
+            warn!(
+                target: "savant_rs::zeromq::spooling_writer",
+                "Spool quota of {} bytes is exhausted, message is dropped",
+                self.spool.quota()
+            );
+            Ok(SpoolingWriterResult::Dropped)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::{ReaderConfig, ReaderResult, SyncReader};
+    use std::thread;
+
+    #[test]
+    fn test_spool() -> anyhow::Result<()> {
+        let directory = Path::new("/tmp/test/spool");
+        fs::remove_dir_all(directory).unwrap_or_default();
+        let mut spool = Spool::open(directory, 1024)?;
+        let message = Message::user_data(UserData::new("test"));
+        assert!(spool.push("a", &message, &[b"payload"])?);
+        assert!(spool.push("b", &message, &[])?);
+        assert_eq!(spool.len(), 2);
+
+        // the messages survive reopening
+        let mut spool = Spool::open(directory, 1024)?;
+        assert_eq!(spool.len(), 2);
+        let (topic, restored, payload) = spool.peek()?.unwrap();
+        assert_eq!(topic, "a");
+        assert!(restored.is_user_data());
+        assert_eq!(payload, vec![b"payload".to_vec()]);
+        spool.pop()?;
+        assert_eq!(spool.peek()?.unwrap().0, "b");
+        spool.pop()?;
+        assert!(spool.peek()?.is_none());
+        assert_eq!(spool.used(), 0);
+
+        let mut spool = Spool::open(directory, 10)?;
+        assert!(!spool.push("a", &message, &[])?);
+        assert!(spool.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_spool_files() -> anyhow::Result<()> {
+        let directory = Path::new("/tmp/test/spool-corrupt");
+        fs::remove_dir_all(directory).unwrap_or_default();
+        let mut spool = Spool::open(directory, 1024)?;
+        let message = Message::user_data(UserData::new("test"));
+        for topic in ["a", "b", "c"] {
+            assert!(spool.push(topic, &message, &[b"payload"])?);
+        }
+        let truncated = spool.path(1);
+        let data = fs::read(&truncated)?;
+        fs::write(&truncated, &data[..data.len() - 3])?;
+        // the file left by a crash while pushing
+        fs::write(spool.path(3).with_extension(TMP_EXTENSION), b"partial")?;
+
+        let mut spool = Spool::open(directory, 1024)?;
+        assert_eq!(spool.len(), 3);
+        assert!(!spool.path(3).with_extension(TMP_EXTENSION).exists());
+        assert_eq!(spool.peek()?.unwrap().0, "a");
+        spool.pop()?;
+        // the truncated message is moved aside and the next one is returned
+        assert_eq!(spool.peek()?.unwrap().0, "c");
+        assert_eq!(spool.len(), 1);
+        assert!(directory
+            .join(CORRUPT_DIRECTORY)
+            .join(truncated.file_name().unwrap())
+            .exists());
+        spool.pop()?;
+        assert!(spool.peek()?.is_none());
+        assert_eq!(spool.used(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_spooling_writer() -> anyhow::Result<()> {
+        let path = "/tmp/test/spooling-writer";
+        let spool_directory = Path::new("/tmp/test/spooling-writer-spool");
+        fs::remove_dir_all(path).unwrap_or_default();
+        fs::remove_dir_all(spool_directory).unwrap_or_default();
+
+        let mut writer = SpoolingWriter::new(
+            &WriterConfig::new()
+                .url(&format!("dealer+bind:ipc://{}", path))?
+                .with_send_timeout(100)?
+                .with_send_retries(1)?
+                .build()?,
+            spool_directory,
+            1024 * 1024,
+        )?;
+        let messages = (0..3)
+            .map(|i| Message::user_data(UserData::new(&format!("test-{}", i))))
+            .collect::<Vec<_>>();
+        for (i, m) in messages.iter().take(2).enumerate() {
+            let started = Instant::now();
+            let res = writer.send_message("test", m, &[])?;
+            assert!(
+                matches!(res, SpoolingWriterResult::Spooled { spooled_messages } if spooled_messages == i + 1)
+            );
+            if i > 0 {
+                // the drain is postponed, so the message is spooled without the send attempt
+                assert!(started.elapsed() < Duration::from_millis(100));
+            }
+        }
+
+        let reader = SyncReader::new(
+            &ReaderConfig::new()
+                .url(&format!("router+connect:ipc://{}", path))?
+                .with_receive_timeout(1000)?
+                .build()?,
+        )?;
+        thread::sleep(Duration::from_millis(500));
+
+        // the spooled messages are sent before the new one
+        let res = writer.send_message("test", &messages[2], &[])?;
+        assert!(matches!(res, SpoolingWriterResult::Sent(_)));
+        assert_eq!(writer.spooled_messages(), 0);
+        for m in &messages {
+            let res = reader.receive()?;
+            assert!(
+                matches!(res, ReaderResult::Message { message, .. } if message.meta.seq_id == m.meta.seq_id)
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use crate::metrics::{get_or_create_counter_family, SharedCounterFamily};
+use crate::transport::zeromq::ReaderResult;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The snapshot of the transport counters of a reader or a writer.
+///
+/// * `messages` - the messages received or sent;
+/// * `bytes` - the size of all the frames received or sent;
+/// * `drops` - the received messages rejected by the reader (blacklisted, filtered, malformed,
+///   invalid) or the messages the writer failed to send in time;
+/// * `hwm_hits` - the send attempts failed because the peer did not accept the message (the high
+///   water mark is reached or there is no peer);
+/// * `reconnects` - the connections restored after the peer disconnected;
+/// * `last_activity` - the time the last message was received or sent.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransportStats {
+    pub messages: u64,
+    pub bytes: u64,
+    pub drops: u64,
+    pub hwm_hits: u64,
+    pub reconnects: u64,
+    pub last_activity: Option<SystemTime>,
+}
+
+struct StatsExporter {
+    endpoint: String,
+    messages: SharedCounterFamily,
+    bytes: SharedCounterFamily,
+    drops: SharedCounterFamily,
+    hwm_hits: SharedCounterFamily,
+    reconnects: SharedCounterFamily,
+}
+
+impl StatsExporter {
+    fn new(role: &str, endpoint: &str) -> Self {
+        let family = |name: &str, description: &str| {
+            get_or_create_counter_family(
+                &format!("zeromq_{}_{}", role, name),
+                Some(description),
+                &["endpoint"],
+                None,
+            )
+        };
+        Self {
+            endpoint: endpoint.to_string(),
+            messages: family("messages", "Number of messages"),
+            bytes: family("bytes", "Number of bytes in message frames"),
+            drops: family("drops", "Number of dropped messages"),
+            hwm_hits: family("hwm_hits", "Number of send attempts rejected by the socket"),
+            reconnects: family("reconnects", "Number of restored connections"),
+        }
+    }
+
+    fn inc(&self, family: &SharedCounterFamily, value: u64) {
+        // the labels always match the family, so the update never fails
+        let _ = family.lock().inc(value, &[&self.endpoint]);
+    }
+}
+
+/// Collects the transport counters, optionally mirroring them to the metrics registry, so they
+/// are exported along with the other pipeline metrics.
+///
+pub(crate) struct StatsCollector {
+    messages: AtomicU64,
+    bytes: AtomicU64,
+    drops: AtomicU64,
+    hwm_hits: AtomicU64,
+    reconnects: AtomicU64,
+    /// Milliseconds since the epoch, 0 means no activity.
+    last_activity: AtomicU64,
+    exporter: Option<StatsExporter>,
+}
+
+impl StatsCollector {
+    pub fn new(role: &str, endpoint: &str, export: bool) -> Self {
+        Self {
+            messages: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            drops: AtomicU64::new(0),
+            hwm_hits: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            last_activity: AtomicU64::new(0),
+            exporter: export.then(|| StatsExporter::new(role, endpoint)),
+        }
+    }
+
+    fn touch(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.last_activity.store(now, Ordering::Relaxed);
+    }
+
+    /// Records the frames received from or sent to the socket.
+    ///
+    pub fn record_bytes(&self, bytes: usize) {
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+        if let Some(e) = &self.exporter {
+            e.inc(&e.bytes, bytes as u64);
+        }
+    }
+
+    pub fn record_message(&self) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        if let Some(e) = &self.exporter {
+            e.inc(&e.messages, 1);
+        }
+    }
+
+    pub fn record_drop(&self) {
+        self.drops.fetch_add(1, Ordering::Relaxed);
+        if let Some(e) = &self.exporter {
+            e.inc(&e.drops, 1);
+        }
+    }
+
+    pub fn record_hwm_hit(&self) {
+        self.hwm_hits.fetch_add(1, Ordering::Relaxed);
+        if let Some(e) = &self.exporter {
+            e.inc(&e.hwm_hits, 1);
+        }
+    }
+
+    pub fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+        if let Some(e) = &self.exporter {
+            e.inc(&e.reconnects, 1);
+        }
+    }
+
+    pub fn record_reader_result(&self, result: &ReaderResult) {
+        match result {
+            ReaderResult::Message { .. } => self.record_message(),
+            ReaderResult::Timeout => {}
+            _ => self.record_drop(),
+        }
+    }
+
+    pub fn snapshot(&self) -> TransportStats {
+        let last_activity = self.last_activity.load(Ordering::Relaxed);
+        TransportStats {
+            messages: self.messages.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            drops: self.drops.load(Ordering::Relaxed),
+            hwm_hits: self.hwm_hits.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            last_activity: (last_activity > 0)
+                .then(|| UNIX_EPOCH + Duration::from_millis(last_activity)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use crate::metrics::get_counter_family;
+    use crate::primitives::userdata::UserData;
+    use crate::transport::zeromq::{ReaderConfig, SyncReader, SyncWriter, WriterConfig};
+
+    #[test]
+    fn test_collector() -> anyhow::Result<()> {
+        let collector = StatsCollector::new("reader", "ipc:///tmp/test/stats", true);
+        assert_eq!(collector.snapshot(), TransportStats::default());
+        collector.record_bytes(10);
+        collector.record_reader_result(&ReaderResult::Timeout);
+        collector.record_reader_result(&ReaderResult::Blacklisted(b"topic".to_vec()));
+        collector.record_message();
+        collector.record_reconnect();
+        let stats = collector.snapshot();
+        assert_eq!(
+            (stats.messages, stats.bytes, stats.drops, stats.reconnects),
+            (1, 10, 1, 1)
+        );
+        assert!(stats.last_activity.is_some());
+        let exported = get_counter_family("zeromq_reader_bytes").unwrap();
+        assert_eq!(exported.lock().get(&["ipc:///tmp/test/stats"])?, Some(10));
+        Ok(())
+    }
+
+    #[test]
+    fn test_transport_stats() -> anyhow::Result<()> {
+        let path = "/tmp/test/transport-stats";
+        std::fs::remove_dir_all(path).unwrap_or_default();
+        let reader = SyncReader::new(
+            &ReaderConfig::new()
+                .url(&format!("router+bind:ipc://{}", path))?
+                .with_receive_timeout(1000)?
+                .build()?,
+        )?;
+        let writer = SyncWriter::new(
+            &WriterConfig::new()
+                .url(&format!("dealer+connect:ipc://{}", path))?
+                .build()?,
+        )?;
+        let message = Message::user_data(UserData::new("test"));
+        writer.send_message("test", &message, &[b"payload"])?;
+        reader.receive()?;
+        let (reader_stats, writer_stats) = (reader.stats(), writer.stats());
+        assert_eq!((reader_stats.messages, writer_stats.messages), (1, 1));
+        // the router prepends the routing id frame
+        assert!(reader_stats.bytes > writer_stats.bytes);
+        assert!(reader_stats.last_activity.is_some());
+        Ok(())
+    }
+}
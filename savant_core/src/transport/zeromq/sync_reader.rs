@@ -1,5 +1,7 @@
 use crate::transport::zeromq::reader::ReaderResult;
-use crate::transport::zeromq::{NoopResponder, Reader, ReaderConfig, ZmqSocketProvider};
+use crate::transport::zeromq::{
+    NoopResponder, Reader, ReaderConfig, TransportStats, ZmqSocketProvider,
+};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -18,6 +20,10 @@ impl SyncReader {
         self.0.is_alive()
     }
 
+    pub fn stats(&self) -> TransportStats {
+        self.0.stats()
+    }
+
     pub fn shutdown(&self) -> anyhow::Result<()> {
         self.0.destroy()
     }
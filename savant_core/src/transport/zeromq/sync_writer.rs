@@ -1,15 +1,21 @@
+use crate::transport::zeromq::stats::StatsCollector;
 use crate::transport::zeromq::{
-    NoopResponder, Writer, WriterConfig, WriterResult, ZmqSocketProvider,
+    NoopResponder, TransportStats, Writer, WriterConfig, WriterResult, ZmqSocketProvider,
 };
 use parking_lot::Mutex;
 use std::sync::Arc;
 
 #[derive(Clone)]
-pub struct SyncWriter(Arc<Mutex<Writer<NoopResponder, ZmqSocketProvider>>>);
+pub struct SyncWriter(
+    Arc<Mutex<Writer<NoopResponder, ZmqSocketProvider>>>,
+    Arc<StatsCollector>,
+);
 
 impl SyncWriter {
     pub fn new(config: &WriterConfig) -> anyhow::Result<Self> {
-        Ok(Self(Arc::new(Mutex::new(Writer::new(config)?))))
+        let writer = Writer::new(config)?;
+        let stats = writer.stats_collector();
+        Ok(Self(Arc::new(Mutex::new(writer)), stats))
     }
 
     pub fn send_eos(&self, topic: &str) -> anyhow::Result<WriterResult> {
@@ -27,6 +33,12 @@ impl SyncWriter {
         writer.send_message(topic, message, data)
     }
 
+    /// Returns the counters without waiting for the message being sent.
+    ///
+    pub fn stats(&self) -> TransportStats {
+        self.1.snapshot()
+    }
+
     pub fn is_started(&self) -> bool {
         let writer = self.0.lock();
         writer.is_started()
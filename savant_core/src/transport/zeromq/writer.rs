@@ -1,19 +1,27 @@
 use crate::message::Message;
 use crate::primitives::eos::EndOfStream;
 use crate::protobuf::{deserialize, serialize};
+use crate::transport::zeromq::compression::compress_parts;
+use crate::transport::zeromq::curve::CurveAuthenticator;
+use crate::transport::zeromq::reconnect::SocketMonitor;
+use crate::transport::zeromq::stats::StatsCollector;
 use crate::transport::zeromq::{
     create_ipc_dirs, set_ipc_permissions, MockSocketResponder, Socket, SocketProvider,
-    WriterConfig, WriterSocketType, CONFIRMATION_MESSAGE, ZMQ_LINGER,
+    TransportStats, WriterConfig, WriterSocketType, CONFIRMATION_MESSAGE, ZMQ_LINGER,
 };
 use crate::utils::bytes_to_hex_string;
 use anyhow::bail;
 use log::{debug, info, warn};
 use std::str::from_utf8;
+use std::sync::Arc;
 
 pub struct Writer<R: MockSocketResponder, P: SocketProvider<R>> {
     context: Option<zmq::Context>,
     config: WriterConfig,
     socket: Option<Socket<R>>,
+    monitor: Option<SocketMonitor>,
+    authenticator: Option<CurveAuthenticator>,
+    stats: Arc<StatsCollector>,
     phony: std::marker::PhantomData<P>,
 }
 
@@ -72,6 +80,20 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Writer<R, P> {
         socket.set_sndhwm(*config.send_hwm())?;
         socket.set_sndtimeo(*config.send_timeout())?;
         socket.set_linger(ZMQ_LINGER)?;
+        socket.set_reconnect_policy(config.reconnect_policy())?;
+        let stats = Arc::new(StatsCollector::new(
+            "writer",
+            config.endpoint(),
+            *config.export_metrics(),
+        ));
+        let monitor = socket.start_monitor(
+            &context,
+            config.endpoint(),
+            config.reconnect_policy(),
+            config.connection_callback(),
+            &stats,
+        )?;
+        let authenticator = socket.set_curve(&context, config.curve())?;
 
         if *config.socket_type() != WriterSocketType::Pub {
             socket.set_rcvtimeo(*config.receive_timeout())?;
@@ -98,6 +120,9 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Writer<R, P> {
             context: Some(context),
             config: config.clone(),
             socket: Some(socket),
+            monitor,
+            authenticator,
+            stats,
             phony: std::marker::PhantomData,
         })
     }
@@ -109,6 +134,8 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Writer<R, P> {
             self.config.endpoint()
         );
         self.socket.take();
+        self.monitor.take();
+        self.authenticator.take();
         self.context.take();
         info!(
             target: "savant_rs::zeromq::writer",
@@ -122,6 +149,14 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Writer<R, P> {
         self.socket.is_some()
     }
 
+    pub fn stats(&self) -> TransportStats {
+        self.stats.snapshot()
+    }
+
+    pub(crate) fn stats_collector(&self) -> Arc<StatsCollector> {
+        self.stats.clone()
+    }
+
     pub fn send_eos(&mut self, topic: &str) -> anyhow::Result<WriterResult> {
         let m = Message::end_of_stream(EndOfStream::new(topic.to_string()));
         self.send(topic.as_bytes(), &m, &[])
@@ -145,13 +180,26 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Writer<R, P> {
         if self.socket.is_none() {
             bail!("ZeroMQ socket is no longer alive");
         }
+        if self.monitor.as_ref().is_some_and(|m| m.gave_up()) {
+            bail!(
+                "ZeroMQ socket for endpoint {} gave up reconnecting to the peer",
+                self.config.endpoint()
+            );
+        }
         let socket = self.socket.as_mut().unwrap();
-        let extra_parts_iter = extra_parts.iter().cloned();
         let serialized_message = serialize(m)?;
-        let parts = vec![topic, &serialized_message]
-            .into_iter()
-            .chain(extra_parts_iter)
-            .collect::<Vec<_>>();
+        let compressed =
+            compress_parts(*self.config.compression(), &serialized_message, extra_parts)?;
+        let parts = match &compressed {
+            Some((message, extra)) => vec![topic, message.as_slice()]
+                .into_iter()
+                .chain(extra.iter().map(|e| e.as_slice()))
+                .collect::<Vec<_>>(),
+            None => vec![topic, serialized_message.as_slice()]
+                .into_iter()
+                .chain(extra_parts.iter().cloned())
+                .collect::<Vec<_>>(),
+        };
         debug!(
             target: "savant_rs::zeromq::writer",
             "Sending message to ZeroMQ socket: {} {:?}",
@@ -165,6 +213,7 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Writer<R, P> {
                     target: "savant_rs::zeromq::writer",
                     "Failed to send message to ZeroMQ socket. Error is [{}] {:?}", e.to_raw(), e);
                 if let zmq::Error::EAGAIN = e {
+                    self.stats.record_hwm_hit();
                     warn!(
                         target: "savant_rs::zeromq::writer",
                         "Retrying to send message to ZeroMQ socket, retries left: {}",
@@ -189,8 +238,12 @@ impl<R: MockSocketResponder, P: SocketProvider<R> + Default> Writer<R, P> {
                 "Failed to send message to ZeroMQ socket. Send retries spent: {}",
                 *self.config.send_retries()
             );
+            self.stats.record_drop();
             return Ok(WriterResult::SendTimeout);
         }
+        self.stats.record_message();
+        self.stats
+            .record_bytes(parts.iter().map(|p| p.len()).sum::<usize>());
 
         let start = std::time::Instant::now();
         if self.config.socket_type() == &WriterSocketType::Req
@@ -1,6 +1,7 @@
 use super::{
-    parse_zmq_socket_uri, SocketType, WriterSocketType, ACK_RECEIVE_RETRIES, IPC_PERMISSIONS,
-    RECEIVE_HWM, SENDER_RECEIVE_TIMEOUT, SEND_HWM, SEND_RETRIES, SEND_TIMEOUT,
+    parse_zmq_socket_uri, CompressionCodec, ConnectionCallback, CurveConfig, ReconnectPolicy,
+    SocketType, WriterSocketType, ACK_RECEIVE_RETRIES, IPC_PERMISSIONS, RECEIVE_HWM,
+    SENDER_RECEIVE_TIMEOUT, SEND_HWM, SEND_RETRIES, SEND_TIMEOUT,
 };
 use crate::utils::default_once::DefaultOnceCell;
 use anyhow::bail;
@@ -53,6 +54,26 @@ impl WriterConfig {
     pub fn fix_ipc_permissions(&self) -> &Option<u32> {
         self.0.fix_ipc_permissions.get_or_init()
     }
+
+    pub fn reconnect_policy(&self) -> &ReconnectPolicy {
+        self.0.reconnect_policy.get_or_init()
+    }
+
+    pub fn connection_callback(&self) -> &Option<ConnectionCallback> {
+        self.0.connection_callback.get_or_init()
+    }
+
+    pub fn compression(&self) -> &CompressionCodec {
+        self.0.compression.get_or_init()
+    }
+
+    pub fn curve(&self) -> &Option<CurveConfig> {
+        self.0.curve.get_or_init()
+    }
+
+    pub fn export_metrics(&self) -> &bool {
+        self.0.export_metrics.get_or_init()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -67,6 +88,11 @@ pub struct WriterConfigBuilder {
     send_hwm: DefaultOnceCell<i32>,
     receive_hwm: DefaultOnceCell<i32>,
     fix_ipc_permissions: DefaultOnceCell<Option<u32>>,
+    reconnect_policy: DefaultOnceCell<ReconnectPolicy>,
+    connection_callback: DefaultOnceCell<Option<ConnectionCallback>>,
+    compression: DefaultOnceCell<CompressionCodec>,
+    curve: DefaultOnceCell<Option<CurveConfig>>,
+    export_metrics: DefaultOnceCell<bool>,
 }
 
 impl Default for WriterConfigBuilder {
@@ -82,6 +108,11 @@ impl Default for WriterConfigBuilder {
             send_hwm: DefaultOnceCell::new(SEND_HWM),
             receive_hwm: DefaultOnceCell::new(RECEIVE_HWM),
             fix_ipc_permissions: DefaultOnceCell::new(Some(IPC_PERMISSIONS)),
+            reconnect_policy: DefaultOnceCell::new(ReconnectPolicy::default()),
+            connection_callback: DefaultOnceCell::new(None),
+            compression: DefaultOnceCell::new(CompressionCodec::None),
+            curve: DefaultOnceCell::new(None),
+            export_metrics: DefaultOnceCell::new(false),
         }
     }
 }
@@ -173,6 +204,37 @@ impl WriterConfigBuilder {
         self.fix_ipc_permissions.set(permissions)?;
         Ok(self)
     }
+
+    pub fn with_reconnect_policy(self, policy: ReconnectPolicy) -> anyhow::Result<Self> {
+        self.reconnect_policy.set(policy)?;
+        Ok(self)
+    }
+
+    pub fn with_connection_callback(
+        self,
+        callback: Option<ConnectionCallback>,
+    ) -> anyhow::Result<Self> {
+        self.connection_callback.set(callback)?;
+        Ok(self)
+    }
+
+    pub fn with_compression(self, codec: CompressionCodec) -> anyhow::Result<Self> {
+        self.compression.set(codec)?;
+        Ok(self)
+    }
+
+    pub fn with_curve(self, curve: CurveConfig) -> anyhow::Result<Self> {
+        self.curve.set(Some(curve))?;
+        Ok(self)
+    }
+
+    /// Mirrors the transport counters to the metrics registry, the metrics are labeled with the
+    /// endpoint.
+    ///
+    pub fn with_export_metrics(self, export: bool) -> anyhow::Result<Self> {
+        self.export_metrics.set(export)?;
+        Ok(self)
+    }
 }
 
 #[cfg(test)]
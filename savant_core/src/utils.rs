@@ -1,6 +1,8 @@
 pub mod default_once;
 pub mod iter;
+pub mod spsc;
 pub mod uuid_v7;
+pub mod watchable_cell;
 use std::fmt::Write;
 
 pub fn bytes_to_hex_string(bytes: &[u8]) -> String {
@@ -0,0 +1,336 @@
+use crossbeam::utils::{Backoff, CachePadded};
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const PARK_TIMEOUT: Duration = Duration::from_micros(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TryPushError<T> {
+    #[error("The ring buffer is full")]
+    Full(T),
+    #[error("The consumer is dropped")]
+    Disconnected(T),
+}
+
+impl<T> TryPushError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            TryPushError::Full(v) | TryPushError::Disconnected(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TryPopError {
+    #[error("The ring buffer is empty")]
+    Empty,
+    #[error("The producer is dropped and the ring buffer is empty")]
+    Disconnected,
+}
+
+// The positions run over `0..2 * capacity`, so they never overflow and a full ring is told
+// apart from an empty one.
+struct Ring<T> {
+    // the next position to read, written only by the consumer
+    head: CachePadded<AtomicUsize>,
+    // the next position to write, written only by the producer
+    tail: CachePadded<AtomicUsize>,
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+}
+
+unsafe impl<T: Send> Sync for Ring<T> {}
+unsafe impl<T: Send> Send for Ring<T> {}
+
+impl<T> Ring<T> {
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn slot(&self, pos: usize) -> *mut MaybeUninit<T> {
+        self.slots[pos % self.slots.len()].get()
+    }
+
+    fn next(&self, pos: usize) -> usize {
+        if pos + 1 == 2 * self.slots.len() {
+            0
+        } else {
+            pos + 1
+        }
+    }
+
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + 2 * self.slots.len() - head
+        }
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let mut head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        while head != tail {
+            unsafe { (*self.slot(head)).assume_init_drop() };
+            head = self.next(head);
+        }
+    }
+}
+
+/// Creates a bounded lock-free single-producer/single-consumer ring buffer.
+///
+pub fn spsc_ring<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "Capacity must be greater than zero");
+    let slots = (0..capacity)
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect::<Vec<_>>()
+        .into_boxed_slice();
+    let ring = Arc::new(Ring {
+        head: CachePadded::new(AtomicUsize::new(0)),
+        tail: CachePadded::new(AtomicUsize::new(0)),
+        slots,
+    });
+    (Producer { ring: ring.clone() }, Consumer { ring })
+}
+
+/// The writing half of [`spsc_ring`].
+///
+pub struct Producer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+impl<T> Producer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        let head = self.ring.head.load(Ordering::Acquire);
+        self.ring.distance(head, tail)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        Arc::strong_count(&self.ring) == 1
+    }
+
+    pub fn try_push(&mut self, value: T) -> Result<(), TryPushError<T>> {
+        if self.is_disconnected() {
+            return Err(TryPushError::Disconnected(value));
+        }
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        let head = self.ring.head.load(Ordering::Acquire);
+        if self.ring.distance(head, tail) == self.ring.capacity() {
+            return Err(TryPushError::Full(value));
+        }
+        unsafe { (*self.ring.slot(tail)).write(value) };
+        self.ring
+            .tail
+            .store(self.ring.next(tail), Ordering::Release);
+        Ok(())
+    }
+
+    /// Waits until there is free space, fails only when the consumer is dropped.
+    ///
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        self.push_deadline(value, None)
+            .map_err(TryPushError::into_inner)
+    }
+
+    pub fn push_timeout(&mut self, value: T, timeout: Duration) -> Result<(), TryPushError<T>> {
+        self.push_deadline(value, Some(Instant::now() + timeout))
+    }
+
+    fn push_deadline(
+        &mut self,
+        mut value: T,
+        deadline: Option<Instant>,
+    ) -> Result<(), TryPushError<T>> {
+        let backoff = Backoff::new();
+        loop {
+            match self.try_push(value) {
+                Err(TryPushError::Full(v)) => value = v,
+                res => return res,
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return Err(TryPushError::Full(value));
+            }
+            wait(&backoff);
+        }
+    }
+}
+
+/// The reading half of [`spsc_ring`].
+///
+pub struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+}
+
+impl<T> Consumer<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let tail = self.ring.tail.load(Ordering::Acquire);
+        self.ring.distance(head, tail)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        Arc::strong_count(&self.ring) == 1
+    }
+
+    pub fn try_pop(&mut self) -> Result<T, TryPopError> {
+        // the disconnection is checked before reading the tail, so the elements pushed
+        // right before the producer is dropped are not lost
+        let disconnected = self.is_disconnected();
+        fence(Ordering::Acquire);
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let tail = self.ring.tail.load(Ordering::Acquire);
+        if head == tail {
+            return Err(if disconnected {
+                TryPopError::Disconnected
+            } else {
+                TryPopError::Empty
+            });
+        }
+        let value = unsafe { (*self.ring.slot(head)).assume_init_read() };
+        self.ring
+            .head
+            .store(self.ring.next(head), Ordering::Release);
+        Ok(value)
+    }
+
+    /// Waits for an element, returns `None` when the producer is dropped and the buffer is
+    /// drained.
+    ///
+    pub fn pop(&mut self) -> Option<T> {
+        self.pop_deadline(None).ok()
+    }
+
+    pub fn pop_timeout(&mut self, timeout: Duration) -> Result<T, TryPopError> {
+        self.pop_deadline(Some(Instant::now() + timeout))
+    }
+
+    fn pop_deadline(&mut self, deadline: Option<Instant>) -> Result<T, TryPopError> {
+        let backoff = Backoff::new();
+        loop {
+            match self.try_pop() {
+                Err(TryPopError::Empty) => {}
+                res => return res,
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return Err(TryPopError::Empty);
+            }
+            wait(&backoff);
+        }
+    }
+}
+
+fn wait(backoff: &Backoff) {
+    if backoff.is_completed() {
+        thread::park_timeout(PARK_TIMEOUT);
+    } else {
+        backoff.snooze();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_push_pop() {
+        let (mut p, mut c) = spsc_ring(2);
+        assert_eq!(c.try_pop(), Err(TryPopError::Empty));
+        assert!(p.try_push(1).is_ok());
+        assert!(p.try_push(2).is_ok());
+        assert_eq!(p.try_push(3), Err(TryPushError::Full(3)));
+        assert_eq!(c.len(), 2);
+        assert_eq!(c.try_pop(), Ok(1));
+        assert!(p.try_push(3).is_ok());
+        assert_eq!(c.try_pop(), Ok(2));
+        assert_eq!(c.try_pop(), Ok(3));
+        assert!(c.is_empty());
+        drop(c);
+        assert_eq!(p.try_push(4), Err(TryPushError::Disconnected(4)));
+    }
+
+    #[test]
+    fn test_positions_wrap_around() {
+        let (mut p, mut c) = spsc_ring(3);
+        for i in 0..20 {
+            p.try_push(i).unwrap();
+            p.try_push(i + 100).unwrap();
+            assert_eq!(p.len(), 2);
+            assert_eq!(c.try_pop(), Ok(i));
+            assert_eq!(c.try_pop(), Ok(i + 100));
+            assert!(c.is_empty());
+        }
+        for i in 0..3 {
+            p.try_push(i).unwrap();
+        }
+        assert_eq!(p.try_push(3), Err(TryPushError::Full(3)));
+        assert_eq!(c.len(), 3);
+    }
+
+    #[test]
+    fn test_drain_after_producer_dropped() {
+        let (mut p, mut c) = spsc_ring(4);
+        p.push(Arc::new(1)).unwrap();
+        p.push(Arc::new(2)).unwrap();
+        drop(p);
+        assert_eq!(c.pop().as_deref(), Some(&1));
+        assert_eq!(c.try_pop().as_deref(), Ok(&2));
+        assert_eq!(c.try_pop(), Err(TryPopError::Disconnected));
+        assert!(c.pop().is_none());
+    }
+
+    #[test]
+    fn test_drop_remaining() {
+        let value = Arc::new(1);
+        let (mut p, c) = spsc_ring(4);
+        p.push(value.clone()).unwrap();
+        p.push(value.clone()).unwrap();
+        assert_eq!(Arc::strong_count(&value), 3);
+        drop(p);
+        drop(c);
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_blocking_threads() {
+        const N: usize = 100_000;
+        let (mut p, mut c) = spsc_ring(16);
+        let producer = thread::spawn(move || {
+            for i in 0..N {
+                p.push(i).unwrap();
+            }
+        });
+        let mut expected = 0;
+        while let Some(v) = c.pop() {
+            assert_eq!(v, expected);
+            expected += 1;
+        }
+        producer.join().unwrap();
+        assert_eq!(expected, N);
+        assert_eq!(
+            c.pop_timeout(Duration::from_millis(1)),
+            Err(TryPopError::Disconnected)
+        );
+    }
+}
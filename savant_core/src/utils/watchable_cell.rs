@@ -0,0 +1,170 @@
+use crate::utils::default_once::DefaultOnceCell;
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use parking_lot::{Mutex, RwLock};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A `(version, value)` pair describing a change of a [`WatchableCell`].
+///
+pub type VersionedValue<T> = (u64, Arc<T>);
+
+/// A configuration cell which, unlike [`DefaultOnceCell`], can be updated at runtime. Readers get
+/// a cheap snapshot of the current value, subscribers are notified about every change.
+///
+#[derive(Debug)]
+pub struct WatchableCell<T: Clone + Debug> {
+    value: RwLock<VersionedValue<T>>,
+    default: T,
+    subscribers: Mutex<Vec<Sender<VersionedValue<T>>>>,
+}
+
+/// Receives the changes of a [`WatchableCell`] as `(version, value)` pairs.
+///
+#[derive(Debug)]
+pub struct WatchableCellSubscription<T> {
+    receiver: Receiver<VersionedValue<T>>,
+}
+
+impl<T> WatchableCellSubscription<T> {
+    /// Returns the next change, if any.
+    ///
+    pub fn try_changed(&self) -> Option<VersionedValue<T>> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Waits for the next change, returns `None` on timeout or when the cell is dropped.
+    ///
+    pub fn changed(&self, timeout: Duration) -> Option<VersionedValue<T>> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// Skips the intermediate changes and returns the most recent one, if any.
+    ///
+    pub fn latest(&self) -> Option<VersionedValue<T>> {
+        self.receiver.try_iter().last()
+    }
+}
+
+impl<T> WatchableCell<T>
+where
+    T: Clone + Debug,
+{
+    pub fn new(default: T) -> Self {
+        Self {
+            value: RwLock::new((0, Arc::new(default.clone()))),
+            default,
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn get(&self) -> Arc<T> {
+        self.value.read().1.clone()
+    }
+
+    pub fn get_versioned(&self) -> VersionedValue<T> {
+        let v = self.value.read();
+        (v.0, v.1.clone())
+    }
+
+    pub fn version(&self) -> u64 {
+        self.value.read().0
+    }
+
+    pub fn get_default(&self) -> T {
+        self.default.clone()
+    }
+
+    /// Replaces the value and notifies the subscribers. Returns the new version.
+    ///
+    pub fn set(&self, value: T) -> u64 {
+        self.update(|_| value)
+    }
+
+    /// Atomically computes the new value from the current one and notifies the subscribers.
+    /// Returns the new version.
+    ///
+    pub fn update<F>(&self, f: F) -> u64
+    where
+        F: FnOnce(&T) -> T,
+    {
+        let mut subscribers = self.subscribers.lock();
+        let (version, value) = {
+            let mut current = self.value.write();
+            let value = Arc::new(f(&current.1));
+            *current = (current.0 + 1, value.clone());
+            (current.0, value)
+        };
+        subscribers.retain(|s| s.send((version, value.clone())).is_ok());
+        version
+    }
+
+    pub fn reset(&self) -> u64 {
+        self.set(self.default.clone())
+    }
+
+    pub fn subscribe(&self) -> WatchableCellSubscription<T> {
+        let (sender, receiver) = unbounded();
+        self.subscribers.lock().push(sender);
+        WatchableCellSubscription { receiver }
+    }
+}
+
+impl<T> From<DefaultOnceCell<T>> for WatchableCell<T>
+where
+    T: Clone + Debug,
+{
+    fn from(cell: DefaultOnceCell<T>) -> Self {
+        let watchable = Self::new(cell.get_default());
+        let current = cell.get_or_init().clone();
+        *watchable.value.write() = (0, Arc::new(current));
+        watchable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchable_cell() {
+        let cell = WatchableCell::new(1);
+        assert_eq!(*cell.get(), 1);
+        let sub = cell.subscribe();
+        assert!(sub.try_changed().is_none());
+
+        assert_eq!(cell.set(2), 1);
+        assert_eq!(cell.update(|v| v + 1), 2);
+        assert_eq!(*cell.get(), 3);
+        assert_eq!(sub.try_changed().map(|(v, x)| (v, *x)), Some((1, 2)));
+        assert_eq!(sub.latest().map(|(v, x)| (v, *x)), Some((2, 3)));
+
+        drop(sub);
+        assert_eq!(cell.reset(), 3);
+        assert_eq!(cell.get_versioned().1.as_ref(), &1);
+        assert!(cell.subscribers.lock().is_empty());
+    }
+
+    #[test]
+    fn test_from_default_once_cell() {
+        let once = DefaultOnceCell::new(1);
+        once.set(5).unwrap();
+        let cell = WatchableCell::from(once);
+        assert_eq!(*cell.get(), 5);
+        assert_eq!(cell.get_default(), 1);
+        assert_eq!(cell.version(), 0);
+    }
+
+    #[test]
+    fn test_changed_across_threads() {
+        let cell = Arc::new(WatchableCell::new(String::from("a")));
+        let sub = cell.subscribe();
+        let writer = {
+            let cell = cell.clone();
+            std::thread::spawn(move || cell.set(String::from("b")))
+        };
+        let (version, value) = sub.changed(Duration::from_secs(5)).unwrap();
+        assert_eq!((version, value.as_str()), (1, "b"));
+        writer.join().unwrap();
+    }
+}
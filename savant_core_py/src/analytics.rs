@@ -0,0 +1,331 @@
+use crate::match_query::MatchQuery;
+use crate::primitives::attribute_value::AttributeValue;
+use crate::primitives::bbox::{BBoxMetricType, RBBox};
+use crate::primitives::frame::VideoFrame;
+use crate::primitives::objects_view::VideoObjectsView;
+use crate::release_gil;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use savant_core::analytics::association;
+use savant_core::analytics::track_manager as rust;
+
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackEventKind {
+    Born,
+    Died,
+}
+
+/// The lifecycle state of a track.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackState {
+    Tentative,
+    Confirmed,
+    Lost,
+}
+
+impl From<rust::TrackState> for TrackState {
+    fn from(state: rust::TrackState) -> Self {
+        match state {
+            rust::TrackState::Tentative => TrackState::Tentative,
+            rust::TrackState::Confirmed => TrackState::Confirmed,
+            rust::TrackState::Lost => TrackState::Lost,
+        }
+    }
+}
+
+/// The values of an object attribute accumulated over the observations of a track.
+///
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct TrackAttribute(rust::TrackAttribute);
+
+#[pymethods]
+impl TrackAttribute {
+    #[getter]
+    fn namespace(&self) -> String {
+        self.0.namespace.clone()
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.0.name.clone()
+    }
+
+    /// The pts and the values of the latest observations, from the oldest to the newest.
+    ///
+    #[getter]
+    fn observations(&self) -> Vec<(i64, Vec<AttributeValue>)> {
+        self.0
+            .observations
+            .iter()
+            .map(|(pts, values)| (*pts, values.iter().cloned().map(AttributeValue).collect()))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+impl From<rust::TrackEventKind> for TrackEventKind {
+    fn from(kind: rust::TrackEventKind) -> Self {
+        match kind {
+            rust::TrackEventKind::Born => TrackEventKind::Born,
+            rust::TrackEventKind::Died => TrackEventKind::Died,
+        }
+    }
+}
+
+/// The aggregated information about a track.
+///
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct TrackAggregate(rust::TrackAggregate);
+
+#[pymethods]
+impl TrackAggregate {
+    #[getter]
+    fn source_id(&self) -> String {
+        self.0.source_id.clone()
+    }
+
+    #[getter]
+    fn track_id(&self) -> i64 {
+        self.0.track_id
+    }
+
+    #[getter]
+    fn namespace(&self) -> String {
+        self.0.namespace.clone()
+    }
+
+    #[getter]
+    fn label(&self) -> String {
+        self.0.label.clone()
+    }
+
+    #[getter]
+    fn first_seen_pts(&self) -> i64 {
+        self.0.first_seen_pts
+    }
+
+    #[getter]
+    fn last_seen_pts(&self) -> i64 {
+        self.0.last_seen_pts
+    }
+
+    #[getter]
+    fn hits(&self) -> u64 {
+        self.0.hits
+    }
+
+    #[getter]
+    fn confirmed(&self) -> bool {
+        self.0.confirmed
+    }
+
+    #[getter]
+    fn state(&self) -> TrackState {
+        self.0.state.into()
+    }
+
+    #[getter]
+    fn attributes(&self) -> Vec<TrackAttribute> {
+        self.0
+            .attributes
+            .iter()
+            .cloned()
+            .map(TrackAttribute)
+            .collect()
+    }
+
+    #[getter]
+    fn max_confidence(&self) -> Option<f32> {
+        self.0.max_confidence
+    }
+
+    /// The detection box of the observation with the highest confidence.
+    ///
+    #[getter]
+    fn best_bbox(&self) -> RBBox {
+        RBBox(self.0.best_bbox.copy())
+    }
+
+    #[getter]
+    fn best_bbox_pts(&self) -> i64 {
+        self.0.best_bbox_pts
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct TrackEvent(rust::TrackEvent);
+
+#[pymethods]
+impl TrackEvent {
+    #[getter]
+    fn kind(&self) -> TrackEventKind {
+        self.0.kind.into()
+    }
+
+    #[getter]
+    fn track(&self) -> TrackAggregate {
+        TrackAggregate(self.0.track.clone())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+fn events(events: Vec<rust::TrackEvent>) -> Vec<TrackEvent> {
+    events.into_iter().map(TrackEvent).collect()
+}
+
+/// Follows the tracks reported by an external tracker, produces the birth and death events and
+/// keeps the per-track aggregates.
+///
+/// Parameters
+/// ----------
+/// min_hits : int
+///   The number of observations after which a track is born.
+/// max_missed : int
+///   The number of consecutive frames without observations after which a track dies.
+/// query : Optional[MatchQuery]
+///   Only the objects matching the query are tracked.
+/// history : Optional[int]
+///   When set, the latest ``history`` boxes of every track are attached to the tracked objects,
+///   enabling the ``track_speed``, ``track_direction`` and ``track_age`` queries.
+/// max_age : Optional[int]
+///   When set, a track dies after ``max_age`` frames since its first observation.
+/// attributes : List[Tuple[str, str]]
+///   The object attributes accumulated in the track aggregates.
+/// attribute_capacity : int
+///   The number of the latest observations kept for every accumulated attribute.
+///
+#[pyclass]
+pub struct TrackManager(rust::TrackManager);
+
+#[pymethods]
+impl TrackManager {
+    #[new]
+    #[pyo3(signature = (
+        min_hits=3,
+        max_missed=30,
+        query=None,
+        history=None,
+        max_age=None,
+        attributes=vec![],
+        attribute_capacity=16
+    ))]
+    fn new(
+        min_hits: u64,
+        max_missed: u64,
+        query: Option<MatchQuery>,
+        history: Option<usize>,
+        max_age: Option<u64>,
+        attributes: Vec<(String, String)>,
+        attribute_capacity: usize,
+    ) -> PyResult<Self> {
+        if attribute_capacity == 0 {
+            return Err(PyValueError::new_err("Attribute capacity must be positive"));
+        }
+        let manager = rust::TrackManager::new(rust::TrackManagerConfig {
+            min_hits,
+            max_missed,
+        });
+        let manager = match query {
+            Some(q) => manager.with_query(q.0),
+            None => manager,
+        };
+        let manager = match max_age {
+            Some(age) => manager.with_max_age(age),
+            None => manager,
+        };
+        let manager = manager.with_attributes(attributes, attribute_capacity);
+        Ok(Self(match history {
+            Some(0) => return Err(PyValueError::new_err("History capacity must be positive")),
+            Some(capacity) => manager.with_history(capacity),
+            None => manager,
+        }))
+    }
+
+    fn process_frame(&mut self, frame: &VideoFrame) -> Vec<TrackEvent> {
+        events(self.0.process_frame(&frame.0))
+    }
+
+    /// Finishes the stream: all its confirmed tracks die, the state is dropped.
+    ///
+    fn finish_stream(&mut self, source_id: &str) -> Vec<TrackEvent> {
+        events(self.0.finish_stream(source_id))
+    }
+
+    fn get_track(&self, source_id: &str, track_id: i64) -> Option<TrackAggregate> {
+        self.0.get_track(source_id, track_id).map(TrackAggregate)
+    }
+
+    #[pyo3(signature = (source_id, confirmed_only=true))]
+    fn get_tracks(&self, source_id: &str, confirmed_only: bool) -> Vec<TrackAggregate> {
+        self.0
+            .get_tracks(source_id, confirmed_only)
+            .into_iter()
+            .map(TrackAggregate)
+            .collect()
+    }
+}
+
+/// Associates the new objects with the previous ones by their detection boxes with the Hungarian
+/// algorithm, maximizing the total metric over the pairs with the metric above the threshold.
+///
+/// Parameters
+/// ----------
+/// prev_objects : VideoObjectsView
+///   The previous objects, e.g. the tracked objects of the previous frame.
+/// new_objects : VideoObjectsView
+///   The new objects, e.g. the detections of the current frame.
+/// metric : BBoxMetricType
+///   The metric of the box pairs.
+/// threshold : float
+///   The pairs with the metric not above the threshold are never associated.
+/// no_gil : bool
+///   Whether to release the GIL while associating.
+///
+/// Returns
+/// -------
+/// List[Tuple[Optional[int], int]]
+///   The id of the associated previous object (if any) and the id of every new object, in the
+///   order of the new objects.
+///
+#[pyfunction]
+#[pyo3(signature = (prev_objects, new_objects, metric, threshold, no_gil = true))]
+pub fn associate(
+    prev_objects: &VideoObjectsView,
+    new_objects: &VideoObjectsView,
+    metric: BBoxMetricType,
+    threshold: f32,
+    no_gil: bool,
+) -> Vec<(Option<i64>, i64)> {
+    let prev = prev_objects
+        .0
+        .iter()
+        .map(|o| o.0.clone())
+        .collect::<Vec<_>>();
+    let new = new_objects
+        .0
+        .iter()
+        .map(|o| o.0.clone())
+        .collect::<Vec<_>>();
+    release_gil!(no_gil, || association::associate(
+        &prev,
+        &new,
+        metric.into(),
+        threshold
+    ))
+}
@@ -1,3 +1,4 @@
+use crate::primitives::object::BorrowedVideoObject;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use savant_core::draw as rust;
@@ -151,7 +152,7 @@ impl PaddingDraw {
 ///
 #[pyclass]
 #[derive(Clone, Copy, Debug)]
-pub struct ColorDraw(rust::ColorDraw);
+pub struct ColorDraw(pub(crate) rust::ColorDraw);
 
 #[pymethods]
 impl ColorDraw {
@@ -627,7 +628,7 @@ impl LabelDraw {
     ///
     #[getter]
     pub fn format(&self) -> Vec<String> {
-        self.0.format.clone()
+        self.0.get_format()
     }
 
     #[getter]
@@ -639,13 +640,28 @@ impl LabelDraw {
     pub fn padding(&self) -> PaddingDraw {
         PaddingDraw(self.0.padding)
     }
+
+    /// Renders the format lines for the object. The placeholders are ``{namespace}`` (or
+    /// ``{model}``), ``{label}``, ``{draw_label}``, ``{id}``, ``{parent_id}``, ``{track_id}``,
+    /// ``{confidence}`` and ``{attr[namespace/name]}`` (or ``{attr[namespace/name][index]}``).
+    /// Floats accept the precision, e.g. ``{confidence:.2f}``. The lines with the other format
+    /// specs, e.g. ``{confidence:.0%}``, are returned as is.
+    ///
+    /// Returns
+    /// -------
+    /// List[str]
+    ///   The rendered lines
+    ///
+    pub fn render(&self, object: &BorrowedVideoObject) -> Vec<String> {
+        self.0.render(&object.0)
+    }
 }
 
 /// Represents the draw specification for an object.
 ///
 #[pyclass]
 #[derive(Clone, Debug)]
-pub struct ObjectDraw(rust::ObjectDraw);
+pub struct ObjectDraw(pub(crate) rust::ObjectDraw);
 
 #[pymethods]
 impl ObjectDraw {
@@ -711,6 +727,143 @@ impl ObjectDraw {
     pub fn label(&self) -> Option<LabelDraw> {
         self.0.label.clone().map(LabelDraw)
     }
+
+    /// Dumps the specification to JSON string.
+    ///
+    #[getter]
+    pub fn json(&self) -> String {
+        self.0.to_json()
+    }
+
+    /// Dumps the specification to YAML string.
+    ///
+    #[getter]
+    pub fn yaml(&self) -> String {
+        self.0.to_yaml()
+    }
+
+    /// Loads the specification from JSON string, raises ValueError if the string or the values
+    /// are invalid.
+    ///
+    #[staticmethod]
+    pub fn from_json(json: &str) -> PyResult<Self> {
+        rust::ObjectDraw::from_json(json)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(format!("Invalid JSON: {}", e)))
+    }
+
+    /// Loads the specification from YAML string, raises ValueError if the string or the values
+    /// are invalid.
+    ///
+    #[staticmethod]
+    pub fn from_yaml(yaml: &str) -> PyResult<Self> {
+        rust::ObjectDraw::from_yaml(yaml)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(format!("Invalid YAML: {}", e)))
+    }
+}
+
+/// The draw specifications of objects keyed by the namespace and the label. The lookup falls
+/// back from ``(namespace, label)`` to ``(namespace, "*")``, then to ``("*", label)`` and finally
+/// to the default specification.
+///
+/// .. code-block:: python
+///
+///   from savant_rs.draw_spec import DrawSpecRegistry, ObjectDraw
+///   registry = DrawSpecRegistry(default=ObjectDraw(blur=True))
+///   registry.insert("detector", "*", ObjectDraw())
+///   spec = registry.lookup("detector", "person")
+///
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct DrawSpecRegistry(pub(crate) rust::DrawSpecRegistry);
+
+#[pymethods]
+impl DrawSpecRegistry {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    #[classattr]
+    const WILDCARD: &'static str = rust::DrawSpecRegistry::WILDCARD;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    #[new]
+    #[pyo3(signature = (default = None))]
+    pub fn new(default: Option<ObjectDraw>) -> Self {
+        Self(rust::DrawSpecRegistry::new(default.map(|d| d.0)))
+    }
+
+    #[getter]
+    pub fn get_default(&self) -> Option<ObjectDraw> {
+        self.0.get_default().cloned().map(ObjectDraw)
+    }
+
+    #[setter]
+    pub fn set_default(&mut self, default: Option<ObjectDraw>) {
+        self.0.set_default(default.map(|d| d.0));
+    }
+
+    /// Sets the specification for the pair, either of them may be ``"*"``. Returns the
+    /// replaced specification.
+    ///
+    pub fn insert(&mut self, namespace: &str, label: &str, spec: ObjectDraw) -> Option<ObjectDraw> {
+        self.0.insert(namespace, label, spec.0).map(ObjectDraw)
+    }
+
+    pub fn remove(&mut self, namespace: &str, label: &str) -> Option<ObjectDraw> {
+        self.0.remove(namespace, label).map(ObjectDraw)
+    }
+
+    /// Returns the specification set for the pair exactly, without the fallbacks.
+    ///
+    pub fn get(&self, namespace: &str, label: &str) -> Option<ObjectDraw> {
+        self.0.get(namespace, label).cloned().map(ObjectDraw)
+    }
+
+    /// Returns the specification for the object with the fallbacks applied.
+    ///
+    pub fn lookup(&self, namespace: &str, label: &str) -> Option<ObjectDraw> {
+        self.0.lookup(namespace, label).cloned().map(ObjectDraw)
+    }
+
+    pub fn keys(&self) -> Vec<(String, String)> {
+        self.0.keys()
+    }
+
+    #[getter]
+    pub fn json(&self) -> String {
+        self.0.to_json()
+    }
+
+    #[getter]
+    pub fn yaml(&self) -> String {
+        self.0.to_yaml()
+    }
+
+    #[staticmethod]
+    pub fn from_json(json: &str) -> PyResult<Self> {
+        rust::DrawSpecRegistry::from_json(json)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(format!("Invalid JSON: {}", e)))
+    }
+
+    #[staticmethod]
+    pub fn from_yaml(yaml: &str) -> PyResult<Self> {
+        rust::DrawSpecRegistry::from_yaml(yaml)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(format!("Invalid YAML: {}", e)))
+    }
 }
 
 #[pyclass]
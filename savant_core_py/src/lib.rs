@@ -1,3 +1,4 @@
+pub mod analytics;
 pub mod atomic_counter;
 pub mod capi;
 /// The draw specification used to draw objects on the frame when they are visualized.
@@ -9,6 +10,8 @@ pub mod pipeline;
 /// # Basic objects
 ///
 pub mod primitives;
+/// Rasterization of the draw specifications onto frames.
+pub mod rendering;
 pub mod telemetry;
 pub mod test;
 /// # Utility functions
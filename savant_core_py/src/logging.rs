@@ -5,6 +5,7 @@ use opentelemetry::KeyValue;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use savant_core::otlp::with_current_context;
+use savant_core::telemetry;
 
 /// Allows defining a log level for a logging subsystem
 ///
@@ -53,6 +54,19 @@ impl From<LogLevel> for log::LevelFilter {
     }
 }
 
+impl LogLevel {
+    fn level(&self) -> Option<log::Level> {
+        match self {
+            LogLevel::Trace => Some(log::Level::Trace),
+            LogLevel::Debug => Some(log::Level::Debug),
+            LogLevel::Info => Some(log::Level::Info),
+            LogLevel::Warning => Some(log::Level::Warn),
+            LogLevel::Error => Some(log::Level::Error),
+            LogLevel::Off => None,
+        }
+    }
+}
+
 impl From<log::LevelFilter> for LogLevel {
     fn from(level: log::LevelFilter) -> Self {
         match level {
@@ -115,7 +129,9 @@ pub fn log_level_enabled(level: LogLevel) -> bool {
     log::max_level().ge(&log::LevelFilter::from(level))
 }
 
-/// Logs a message.
+/// Logs a message. When the export of the logs is configured with
+/// :py:class:`savant_rs.telemetry.LoggerConfiguration`, the message is also
+/// exported over OTLP with the parameters as the attributes.
 ///
 /// GIL Management: This function releases the GIL optionally.
 ///
@@ -201,6 +217,17 @@ pub fn log_message(level: LogLevel, target: &str, message: &str, params: Option<
                 LogLevel::Off => {}
             }
 
+            if let Some(level) = level.level() {
+                if telemetry::log_export_enabled() {
+                    telemetry::emit_log(
+                        level,
+                        target,
+                        message,
+                        params.as_deref().unwrap_or_default(),
+                    );
+                }
+            }
+
             let mut params = params.unwrap_or_default();
             params.extend(vec![
                 KeyValue::new("log.level".to_string(), level.__str__()),
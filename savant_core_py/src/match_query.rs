@@ -1,4 +1,7 @@
 use crate::primitives::bbox::{BBoxMetricType, RBBox};
+use crate::primitives::point::Point;
+use crate::primitives::polygonal_area::PolygonalArea;
+use crate::primitives::segment::Segment;
 
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
@@ -687,6 +690,41 @@ impl StringExpression {
         StringExpression(rust::StringExpression::EndsWith(v))
     }
 
+    /// Regular expression match; the pattern matches any part of the string, use ``^...$``
+    /// to match the whole string.
+    ///
+    /// In JSON/YAML: matches
+    ///
+    /// Parameters
+    /// ----------
+    /// v: str
+    ///   Regular expression
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`StringExpression`
+    ///   String expression
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the pattern is not a valid regular expression
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: python
+    ///
+    ///    from savant_rs.match_query import StringExpression as SE
+    ///    SE.matches("^person_(adult|child)$")
+    ///
+    #[staticmethod]
+    fn matches(v: String) -> PyResult<StringExpression> {
+        let pattern = rust::RegexPattern::try_from(v)
+            .map_err(|e| PyValueError::new_err(format!("{:#}", e)))?;
+        Ok(StringExpression(rust::StringExpression::Matches(pattern)))
+    }
+
     /// One of expression
     ///
     /// In JSON/YAML: one_of
@@ -1154,6 +1192,211 @@ impl MatchQuery {
         })
     }
 
+    /// True if the object's detection box lies entirely within the area.
+    ///
+    /// In JSON/YAML: bbox.within_polygon
+    ///
+    /// Parameters
+    /// ----------
+    /// area: :py:class:`savant_rs.primitives.geometry.PolygonalArea`
+    ///   The area to check
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn box_within_polygon(area: &PolygonalArea) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::BoxWithinPolygon(area.0.clone()))
+    }
+
+    /// True if the object's detection box intersects the area.
+    ///
+    /// In JSON/YAML: bbox.intersects_polygon
+    ///
+    /// Parameters
+    /// ----------
+    /// area: :py:class:`savant_rs.primitives.geometry.PolygonalArea`
+    ///   The area to check
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn box_intersects_polygon(area: &PolygonalArea) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::BoxIntersectsPolygon(area.0.clone()))
+    }
+
+    /// True if the distance between the object's detection box center and the point matches
+    /// the expression.
+    ///
+    /// In JSON/YAML: bbox.center.distance_to_point
+    ///
+    /// Parameters
+    /// ----------
+    /// point: :py:class:`savant_rs.primitives.geometry.Point`
+    ///   The point to measure the distance to
+    /// e: :py:class:`FloatExpression`
+    ///   Float expression to compare the distance with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn box_center_distance_to_point(point: &Point, e: FloatExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::BoxCenterDistanceToPoint {
+            point: point.0.clone(),
+            threshold_expr: e.0,
+        })
+    }
+
+    /// True if the object's detection box crosses or touches the segment.
+    ///
+    /// In JSON/YAML: bbox.crosses_segment
+    ///
+    /// Parameters
+    /// ----------
+    /// segment: :py:class:`savant_rs.primitives.geometry.Segment`
+    ///   The segment to check, e.g. a counting line
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn box_crosses_segment(segment: &Segment) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::BoxCrossesSegment {
+            segment: segment.0.clone(),
+        })
+    }
+
+    /// True if the object's track box is defined and lies entirely within the area.
+    ///
+    /// In JSON/YAML: track.bbox.within_polygon
+    ///
+    /// Parameters
+    /// ----------
+    /// area: :py:class:`savant_rs.primitives.geometry.PolygonalArea`
+    ///   The area to check
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn track_box_within_polygon(area: &PolygonalArea) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::TrackBoxWithinPolygon(area.0.clone()))
+    }
+
+    /// True if the object has the track history with at least two observations and the speed
+    /// of the box center (pixels per observation) matches the expression.
+    ///
+    /// In JSON/YAML: track.speed
+    ///
+    /// Parameters
+    /// ----------
+    /// e: :py:class:`FloatExpression`
+    ///   Float expression to compare the speed with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn track_speed(e: FloatExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::TrackSpeed(e.0))
+    }
+
+    /// True if the object has the track history with at least two observations and the
+    /// movement direction matches the expression. The direction is in degrees within
+    /// ``(-180, 180]``, 0 is along the X axis, 90 is downwards.
+    ///
+    /// In JSON/YAML: track.direction
+    ///
+    /// Parameters
+    /// ----------
+    /// e: :py:class:`FloatExpression`
+    ///   Float expression to compare the direction with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn track_direction(e: FloatExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::TrackDirection(e.0))
+    }
+
+    /// True if the object has the track history and the number of the track observations
+    /// matches the expression.
+    ///
+    /// In JSON/YAML: track.age
+    ///
+    /// Parameters
+    /// ----------
+    /// e: :py:class:`IntExpression`
+    ///   Int expression to compare the age with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn track_age(e: IntExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::TrackAge(e.0))
+    }
+
+    /// True if the object has the keypoint with the name.
+    ///
+    /// In JSON/YAML: keypoint.defined
+    ///
+    /// Parameters
+    /// ----------
+    /// name: str
+    ///   Keypoint name
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn keypoint_defined(name: String) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::KeyPointDefined(name))
+    }
+
+    /// True if the object has the keypoint with the name and its confidence matches the
+    /// expression.
+    ///
+    /// In JSON/YAML: keypoint.confidence
+    ///
+    /// Parameters
+    /// ----------
+    /// name: str
+    ///   Keypoint name
+    /// e: :py:class:`FloatExpression`
+    ///   Float expression to compare the confidence with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    #[staticmethod]
+    fn keypoint_confidence(name: String, e: FloatExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::KeyPointConfidence(name, e.0))
+    }
+
     /// True if object's namespace matches the given string expression.
     ///
     /// In JSON/YAML: namespace
@@ -2012,6 +2255,108 @@ impl MatchQuery {
         MatchQuery(rust::MatchQuery::AttributeExists(namespace, label))
     }
 
+    /// True if any float value of the object's attribute matches the expression.
+    ///
+    /// In JSON/YAML: attribute.float
+    ///
+    /// Parameters
+    /// ----------
+    /// namespace: str
+    ///   Attribute namespace
+    /// label: str
+    ///   Attribute label
+    /// e: :py:class:`FloatExpression`
+    ///   Expression to compare the values with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: python
+    ///
+    ///    from savant_rs.match_query import MatchQuery as MQ
+    ///    from savant_rs.match_query import FloatExpression as FE
+    ///
+    ///    q = MQ.attribute_float("classifier", "score", FE.gt(0.5))
+    ///    print(q.yaml, "\n", q.json)
+    ///
+    #[staticmethod]
+    fn attribute_float(namespace: String, label: String, e: FloatExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::AttributeFloat(namespace, label, e.0))
+    }
+
+    /// True if any integer value of the object's attribute matches the expression.
+    ///
+    /// In JSON/YAML: attribute.int
+    ///
+    /// Parameters
+    /// ----------
+    /// namespace: str
+    ///   Attribute namespace
+    /// label: str
+    ///   Attribute label
+    /// e: :py:class:`IntExpression`
+    ///   Expression to compare the values with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: python
+    ///
+    ///    from savant_rs.match_query import MatchQuery as MQ
+    ///    from savant_rs.match_query import IntExpression as IE
+    ///
+    ///    q = MQ.attribute_int("classifier", "age", IE.ge(18))
+    ///    print(q.yaml, "\n", q.json)
+    ///
+    #[staticmethod]
+    fn attribute_int(namespace: String, label: String, e: IntExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::AttributeInt(namespace, label, e.0))
+    }
+
+    /// True if any string value of the object's attribute matches the expression.
+    ///
+    /// In JSON/YAML: attribute.string
+    ///
+    /// Parameters
+    /// ----------
+    /// namespace: str
+    ///   Attribute namespace
+    /// label: str
+    ///   Attribute label
+    /// e: :py:class:`StringExpression`
+    ///   Expression to compare the values with
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: python
+    ///
+    ///    from savant_rs.match_query import MatchQuery as MQ
+    ///    from savant_rs.match_query import StringExpression as SE
+    ///
+    ///    q = MQ.attribute_string("lpr", "plate", SE.starts_with("A"))
+    ///    print(q.yaml, "\n", q.json)
+    ///
+    #[staticmethod]
+    fn attribute_string(namespace: String, label: String, e: StringExpression) -> MatchQuery {
+        MatchQuery(rust::MatchQuery::AttributeString(namespace, label, e.0))
+    }
+
     /// True if frame source_id matches the given string expression.
     ///
     /// In JSON/YAML: frame.source_id
@@ -2296,6 +2641,19 @@ impl MatchQuery {
         self.0.to_yaml()
     }
 
+    /// Returns an equivalent query which is cheaper to execute: nested ``and_``/``or_`` are
+    /// flattened, ``idle`` and double negations are removed and cheap predicates are evaluated
+    /// before expensive ones.
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Optimized query
+    ///
+    fn optimize(&self) -> MatchQuery {
+        MatchQuery(self.0.optimize())
+    }
+
     /// Loads query from JSON string.
     ///
     /// Parameters
@@ -2366,4 +2724,40 @@ impl MatchQuery {
             |e| PyValueError::new_err(format!("Invalid YAML: {}", e)),
         )?))
     }
+
+    /// Parses query from the compact expression syntax: comparisons like ``label == 'person'``,
+    /// ``id in [1, 2]``, ``confidence between [0.5, 1]`` or ``namespace ~ '^peo'``, flags like
+    /// ``track.defined`` and attribute checks like ``attribute.float('ns', 'name') > 0.5``,
+    /// combined with ``!``, ``&&``, ``||`` and parentheses.
+    ///
+    /// Parameters
+    /// ----------
+    /// expr: str
+    ///   Expression
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`MatchQuery`
+    ///   Query
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the expression is invalid
+    ///
+    /// Example
+    /// -------
+    ///
+    /// .. code-block:: python
+    ///
+    ///    from savant_rs.match_query import MatchQuery as MQ
+    ///
+    ///    q = MQ.from_expr("label == 'person' && confidence > 0.5 && bbox.area > 100")
+    ///
+    #[staticmethod]
+    fn from_expr(expr: String) -> PyResult<MatchQuery> {
+        Ok(MatchQuery(rust::MatchQuery::from_expr(&expr).map_err(
+            |e| PyValueError::new_err(format!("Invalid expression: {}", e)),
+        )?))
+    }
 }
@@ -1,10 +1,17 @@
 use parking_lot::Mutex;
 use std::collections::HashMap;
+use std::time::Duration;
 
 use pyo3::exceptions::{PySystemError, PyValueError};
 use pyo3::prelude::*;
 
+use savant_core::pipeline::ordered_egress::OrderedEgress as RustOrderedEgress;
 use savant_core::pipeline::stage_function_loader::load_stage_function_plugin as rust_load_stage_function_plugin;
+use savant_core::pipeline::stage_function_loader::{
+    create_registered_stage_function as rust_create_registered_stage_function,
+    list_registered_plugins as rust_list_registered_plugins,
+    register_plugins_from_dir as rust_register_plugins_from_dir,
+};
 use savant_core::pipeline::PipelineStageFunction as RustPipelineStageFunction;
 use savant_core::pipeline::PluginParams;
 use savant_core::rust;
@@ -65,6 +72,61 @@ pub fn load_stage_function_plugin(
         .map_err(|e| PySystemError::new_err(e.to_string()))
 }
 
+/// Scans the directory for plugin manifests (JSON or YAML) and registers the plugins found.
+/// When a manifest is invalid or incompatible, none of the plugins is registered.
+///
+/// Parameters
+/// ----------
+/// dir : str
+///   The directory with manifests.
+///
+/// Returns
+/// -------
+/// List[str]
+///   The names of the registered plugins.
+///
+#[pyfunction]
+pub fn register_plugins_from_dir(dir: &str) -> PyResult<Vec<String>> {
+    rust_register_plugins_from_dir(dir).map_err(|e| PySystemError::new_err(e.to_string()))
+}
+
+/// Lists the registered plugins as (name, version, library, function names) tuples.
+///
+#[pyfunction]
+#[allow(clippy::type_complexity)]
+pub fn list_registered_plugins() -> Vec<(String, Option<String>, String, Vec<String>)> {
+    rust_list_registered_plugins()
+        .into_iter()
+        .map(|m| {
+            (
+                m.name,
+                m.version,
+                m.library,
+                m.functions.into_iter().map(|f| f.name).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Creates a stage function provided by a registered plugin.
+///
+#[pyfunction]
+pub fn create_registered_stage_function(
+    plugin: &str,
+    function: &str,
+    params: HashMap<String, AttributeValue>,
+) -> PyResult<StageFunction> {
+    let params = params
+        .into_iter()
+        .map(|(k, v)| (k, v.0))
+        .collect::<hashbrown::HashMap<_, _>>();
+    let params = PluginParams { params };
+
+    rust_create_registered_stage_function(plugin, function, params)
+        .map(|f| StageFunction(Mutex::new(Some(f))))
+        .map_err(|e| PySystemError::new_err(e.to_string()))
+}
+
 /// Defines which type of payload a stage handles.
 ///
 #[pyclass(eq, eq_int)]
@@ -161,6 +223,112 @@ impl StageLatencyStat {
             .collect()
     }
 }
+/// What happens to the payloads entering a full stage.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StageOverflowPolicy {
+    Block,
+    DropOldest,
+    Reject,
+}
+
+impl From<StageOverflowPolicy> for rust::StageOverflowPolicy {
+    fn from(p: StageOverflowPolicy) -> Self {
+        match p {
+            StageOverflowPolicy::Block => rust::StageOverflowPolicy::Block,
+            StageOverflowPolicy::DropOldest => rust::StageOverflowPolicy::DropOldest,
+            StageOverflowPolicy::Reject => rust::StageOverflowPolicy::Reject,
+        }
+    }
+}
+
+#[pyclass]
+pub struct StageOccupancy(rust::StageOccupancy);
+
+#[pymethods]
+impl StageOccupancy {
+    #[getter]
+    fn stage_name(&self) -> String {
+        self.0.stage_name.clone()
+    }
+
+    #[getter]
+    fn queue_len(&self) -> usize {
+        self.0.queue_len
+    }
+
+    #[getter]
+    fn capacity(&self) -> Option<usize> {
+        self.0.capacity
+    }
+
+    #[getter]
+    fn dropped(&self) -> u64 {
+        self.0.dropped
+    }
+
+    #[getter]
+    fn rejected(&self) -> u64 {
+        self.0.rejected
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+
+    fn __str__(&self) -> String {
+        format!("{:#?}", self.0)
+    }
+}
+
+/// The time the payloads spend in a stage, aggregated over the most recent
+/// payloads which left the stage.
+///
+#[pyclass]
+pub struct StageResidencyStats(rust::StageResidencyStats);
+
+#[pymethods]
+impl StageResidencyStats {
+    #[getter]
+    fn stage_name(&self) -> String {
+        self.0.stage_name.clone()
+    }
+
+    #[getter]
+    fn queue_depth(&self) -> usize {
+        self.0.queue_depth
+    }
+
+    #[getter]
+    fn exited(&self) -> u64 {
+        self.0.exited
+    }
+
+    #[getter]
+    fn p50_latency_micros(&self) -> u128 {
+        self.0.p50_latency.as_micros()
+    }
+
+    #[getter]
+    fn p95_latency_micros(&self) -> u128 {
+        self.0.p95_latency.as_micros()
+    }
+
+    #[getter]
+    fn throughput(&self) -> f64 {
+        self.0.throughput
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+
+    fn __str__(&self) -> String {
+        format!("{:#?}", self.0)
+    }
+}
+
 #[pyclass]
 pub struct StageProcessingStat(rust::StageProcessingStat);
 
@@ -267,6 +435,124 @@ impl From<rust::PipelineStagePayloadType> for VideoPipelineStagePayloadType {
     }
 }
 
+/// Releases the frames leaving the pipeline in the order they were added to
+/// it, per source. A frame is held until the previous frame of the same source
+/// is released, or until it waits for longer than the gap timeout.
+///
+/// Parameters
+/// ----------
+/// gap_timeout_ms : int
+///   How long a frame waits for the previous frame of the source.
+///
+#[pyclass]
+#[derive(Debug)]
+pub struct OrderedEgress(RustOrderedEgress);
+
+#[pymethods]
+impl OrderedEgress {
+    #[new]
+    fn new(gap_timeout_ms: u64) -> Self {
+        Self(RustOrderedEgress::new(Duration::from_millis(
+            gap_timeout_ms,
+        )))
+    }
+
+    /// Buffers the frame leaving the pipeline.
+    ///
+    /// Parameters
+    /// ----------
+    /// frame_id : int
+    ///   The id of the frame in the pipeline.
+    /// frame : :py:class:`savant_rs.primitives.VideoFrame`
+    ///   The frame.
+    ///
+    /// Returns
+    /// -------
+    /// List[Tuple[int, :py:class:`savant_rs.primitives.VideoFrame`]]
+    ///   The frames which can be released now, in order.
+    ///
+    fn push(&mut self, frame_id: i64, frame: VideoFrame) -> Vec<(i64, VideoFrame)> {
+        self.0
+            .push(frame_id, frame.0)
+            .into_iter()
+            .map(|(id, f)| (id, VideoFrame(f)))
+            .collect()
+    }
+
+    /// Releases the frames which waited for longer than the gap timeout. Must
+    /// be called periodically when no frames are pushed.
+    ///
+    /// Returns
+    /// -------
+    /// List[Tuple[int, :py:class:`savant_rs.primitives.VideoFrame`]]
+    ///   The released frames.
+    ///
+    fn poll(&mut self) -> Vec<(i64, VideoFrame)> {
+        self.0
+            .poll()
+            .into_iter()
+            .map(|(id, f)| (id, VideoFrame(f)))
+            .collect()
+    }
+
+    /// Releases all the buffered frames of the source, e.g. at the end of the
+    /// stream. The last released frame is remembered, so the frames of the
+    /// resumed stream are not held.
+    ///
+    /// Parameters
+    /// ----------
+    /// source_id : str
+    ///   The source.
+    ///
+    /// Returns
+    /// -------
+    /// List[Tuple[int, :py:class:`savant_rs.primitives.VideoFrame`]]
+    ///   The released frames.
+    ///
+    fn flush(&mut self, source_id: &str) -> Vec<(i64, VideoFrame)> {
+        self.0
+            .flush(source_id)
+            .into_iter()
+            .map(|(id, f)| (id, VideoFrame(f)))
+            .collect()
+    }
+
+    /// Treats the frames of the source up to the id as released, so the
+    /// following frames do not wait for them, e.g. when the first frames of the
+    /// source never pass through the buffer.
+    ///
+    /// Parameters
+    /// ----------
+    /// source_id : str
+    ///   The source.
+    /// frame_id : int
+    ///   The id of the last frame in the pipeline to skip.
+    ///
+    /// Returns
+    /// -------
+    /// List[Tuple[int, :py:class:`savant_rs.primitives.VideoFrame`]]
+    ///   The frames which can be released now, in order.
+    ///
+    fn skip_until(&mut self, source_id: &str, frame_id: i64) -> Vec<(i64, VideoFrame)> {
+        self.0
+            .skip_until(source_id, frame_id)
+            .into_iter()
+            .map(|(id, f)| (id, VideoFrame(f)))
+            .collect()
+    }
+
+    /// The number of the frames released without their previous frame.
+    ///
+    #[getter]
+    fn gaps(&self) -> u64 {
+        self.0.gaps()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+}
+
 /// A video pipeline.
 ///
 #[pyclass]
@@ -317,6 +603,76 @@ impl PipelineConfiguration {
         self.0.collection_history = v;
     }
 
+    #[setter]
+    pub fn stage_function_quarantine_threshold(&mut self, v: Option<usize>) {
+        self.0.stage_function_quarantine_threshold = v;
+    }
+
+    #[setter]
+    pub fn object_count_metrics_stage(&mut self, v: Option<String>) {
+        self.0.object_count_metrics_stage = v;
+    }
+
+    #[setter]
+    pub fn stage_residency_spans(&mut self, v: bool) {
+        self.0.stage_residency_spans = v;
+    }
+
+    /// Limits the number of payloads (frames or batches) the stage holds.
+    ///
+    /// Parameters
+    /// ----------
+    /// stage_name : str
+    ///   The name of the stage.
+    /// capacity : int
+    ///   The maximum number of payloads, must be greater than 0.
+    /// policy : :py:class:`StageOverflowPolicy`
+    ///   What happens to the payloads entering the full stage.
+    /// block_timeout_ms : Optional[int]
+    ///   With :py:attr:`StageOverflowPolicy.Block`, the operation fails after the timeout.
+    ///   When not set, the operation waits indefinitely.
+    ///
+    #[pyo3(signature = (stage_name, capacity, policy, block_timeout_ms=None))]
+    pub fn set_stage_capacity(
+        &mut self,
+        stage_name: String,
+        capacity: usize,
+        policy: StageOverflowPolicy,
+        block_timeout_ms: Option<u64>,
+    ) {
+        self.0.stage_capacity.insert(
+            stage_name,
+            rust::StageCapacity {
+                capacity,
+                policy: policy.into(),
+                block_timeout: block_timeout_ms.map(Duration::from_millis),
+            },
+        );
+    }
+
+    /// Adds a route to the stage, see :py:meth:`VideoPipeline.route`. The
+    /// routes are checked in the order they are added.
+    ///
+    /// Parameters
+    /// ----------
+    /// stage_name : str
+    ///   The name of the stage.
+    /// destination : str
+    ///   The name of the destination stage, must follow the stage.
+    /// query : :py:class:`savant_rs.match_query.MatchQuery`
+    ///   The query matching the objects of the routed frames.
+    ///
+    pub fn add_stage_route(&mut self, stage_name: String, destination: String, query: &MatchQuery) {
+        self.0
+            .stage_routes
+            .entry(stage_name)
+            .or_default()
+            .push(rust::StageRoute {
+                destination,
+                query: query.0.clone(),
+            });
+    }
+
     fn __repr__(&self) -> String {
         format!("{:?}", self.0)
     }
@@ -354,6 +710,41 @@ impl Pipeline {
         Ok(Self(p))
     }
 
+    /// Creates the pipeline from the JSON or YAML specification defining the
+    /// stages, their payload types, queue limits, stage functions of the
+    /// registered plugins and routes.
+    ///
+    /// Parameters
+    /// ----------
+    /// spec : str
+    ///   The specification.
+    /// configuration : Optional[:py:class:`VideoPipelineConfiguration`]
+    ///   The configuration, the queue limits and routes of the specification
+    ///   replace the ones set in it.
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`VideoPipeline`
+    ///   The pipeline.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the specification is malformed or invalid, the message names the
+    ///   offending stage.
+    ///
+    #[staticmethod]
+    #[pyo3(signature = (spec, configuration = None))]
+    fn from_spec(spec: &str, configuration: Option<PipelineConfiguration>) -> PyResult<Self> {
+        let configuration = match configuration {
+            Some(c) => c,
+            None => PipelineConfiguration::new()?,
+        };
+        let p = rust::Pipeline::from_spec(spec, configuration.0)
+            .map_err(|e| PyValueError::new_err(format!("Failed to create pipeline: {}", e)))?;
+        Ok(Self(p))
+    }
+
     pub fn get_keyframe_history(&self, f: &VideoFrame) -> Option<Vec<(u128, i64)>> {
         self.0.get_keyframe_history(&f.0)
     }
@@ -533,9 +924,12 @@ impl Pipeline {
     ///   If the stage does not exist or is not of type independent frames.
     ///
     fn add_frame(&self, stage_name: &str, frame: VideoFrame) -> PyResult<i64> {
-        self.0
-            .add_frame(stage_name, frame.0)
-            .map_err(|e| PyValueError::new_err(e.to_string()))
+        // the stage may block until the payloads are removed by other threads
+        release_gil!(true, || {
+            self.0
+                .add_frame(stage_name, frame.0)
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })
     }
 
     /// Adds a frame to the stage with an OTLP parent context.
@@ -567,9 +961,12 @@ impl Pipeline {
         frame: VideoFrame,
         parent_span: &TelemetrySpan,
     ) -> PyResult<i64> {
-        self.0
-            .add_frame_with_telemetry(stage_name, frame.0, parent_span.0.clone())
-            .map_err(|e| PyValueError::new_err(e.to_string()))
+        let parent_ctx = parent_span.0.clone();
+        release_gil!(true, || {
+            self.0
+                .add_frame_with_telemetry(stage_name, frame.0, parent_ctx)
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })
     }
 
     /// Deletes a frame or a batch from the stage.
@@ -625,6 +1022,71 @@ impl Pipeline {
             .get_stage_queue_len(stage_name)
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
+
+    /// Retrieves the occupancy of a stage: the queue length, the capacity and
+    /// the number of the dropped and the rejected payloads.
+    ///
+    /// Parameters
+    /// ----------
+    /// stage_name : str
+    ///   The name of the stage.
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`StageOccupancy`
+    ///   The occupancy of the stage.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the stage does not exist.
+    ///
+    fn get_stage_occupancy(&self, stage_name: &str) -> PyResult<StageOccupancy> {
+        self.0
+            .get_stage_occupancy(stage_name)
+            .map(StageOccupancy)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Retrieves the residency statistics of a stage: the p50 and p95 time the
+    /// payloads spend in the stage, the throughput and the queue depth.
+    ///
+    /// Parameters
+    /// ----------
+    /// stage_name : str
+    ///   The name of the stage.
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`StageResidencyStats`
+    ///   The statistics of the stage.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the stage does not exist.
+    ///
+    fn stage_stats(&self, stage_name: &str) -> PyResult<StageResidencyStats> {
+        self.0
+            .stage_stats(stage_name)
+            .map(StageResidencyStats)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Retrieves the occupancy of all the stages in the pipeline order.
+    ///
+    /// Returns
+    /// -------
+    /// List[:py:class:`StageOccupancy`]
+    ///   The occupancy of the stages.
+    ///
+    fn get_occupancy(&self) -> Vec<StageOccupancy> {
+        self.0
+            .get_occupancy()
+            .into_iter()
+            .map(StageOccupancy)
+            .collect()
+    }
     /// Retrieves an independent frame from a specified stage.
     ///
     /// GIL management: the function is GIL-free.
@@ -884,4 +1346,94 @@ impl Pipeline {
                 .map_err(|e| PyValueError::new_err(e.to_string()))
         })
     }
+
+    /// Saves the frames and batches in processing, with their pending
+    /// updates, to the file, so a restarted module can restore them with
+    /// :py:meth:`VideoPipeline.restore_snapshot`.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///   The path of the snapshot file.
+    /// no_gil : bool
+    ///   Whether to release the GIL.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the file cannot be written.
+    ///
+    #[pyo3(name = "save_snapshot")]
+    #[pyo3(signature = (path, no_gil = true))]
+    fn save_snapshot_gil(&self, path: &str, no_gil: bool) -> PyResult<()> {
+        release_gil!(no_gil, || {
+            self.0
+                .save_snapshot(path)
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })
+    }
+
+    /// Restores the frames and batches saved with
+    /// :py:meth:`VideoPipeline.save_snapshot` to the stages with the same
+    /// names, keeping their ids. The temporary attributes, the track
+    /// histories of the objects and the change journals are not kept in the
+    /// snapshot.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///   The path of the snapshot file.
+    /// no_gil : bool
+    ///   Whether to release the GIL.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///   The number of the restored frames.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the pipeline is not empty, the snapshot cannot be read or does not
+    ///   match the stages of the pipeline.
+    ///
+    #[pyo3(name = "restore_snapshot")]
+    #[pyo3(signature = (path, no_gil = true))]
+    fn restore_snapshot_gil(&self, path: &str, no_gil: bool) -> PyResult<usize> {
+        release_gil!(no_gil, || {
+            self.0
+                .restore_snapshot(path)
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })
+    }
+
+    /// Finds the destination of the frame or the batch with the routes of its
+    /// stage: the first route whose query matches any object wins.
+    ///
+    /// Parameters
+    /// ----------
+    /// id : int
+    ///   The id of the frame or the batch.
+    /// no_gil : bool
+    ///   Whether to release the GIL.
+    ///
+    /// Returns
+    /// -------
+    /// Optional[str]
+    ///   The name of the destination stage or None when no route matches.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the object does not exist.
+    ///
+    #[pyo3(name = "route")]
+    #[pyo3(signature = (id, no_gil = true))]
+    fn route_gil(&self, id: i64, no_gil: bool) -> PyResult<Option<String>> {
+        release_gil!(no_gil, || {
+            self.0
+                .route(id)
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        })
+    }
 }
@@ -6,9 +6,12 @@ pub mod batch;
 /// Here are decleared bounding boxes
 ///
 pub mod bbox;
+pub mod codec;
 pub mod eos;
 pub mod frame;
 pub mod frame_update;
+pub mod journal;
+pub mod keypoints;
 pub mod message;
 pub mod object;
 pub mod objects_view;
@@ -18,6 +21,7 @@ pub mod point;
 pub mod polygonal_area;
 /// Implementation for Python attributes in VideoObject and VideoFrame.
 pub mod pyobject;
+pub mod redaction;
 /// A line consisting of two points.
 pub mod segment;
 pub mod shutdown;
@@ -1,17 +1,100 @@
 use crate::primitives::segment::Intersection;
 use crate::primitives::{Point, PolygonalArea, RBBox};
 use crate::with_gil;
-use pyo3::exceptions::{PyIndexError, PyValueError};
-use pyo3::types::{PyBytes, PyBytesMethods};
-use pyo3::{pyclass, pymethods, Bound, Py, PyAny, PyObject, PyResult};
+use pyo3::buffer::{Element, PyBuffer};
+use pyo3::exceptions::{PyBufferError, PyIndexError, PyValueError};
+use pyo3::types::{PyBytes, PyBytesMethods, PyMemoryView};
+use pyo3::{ffi, pyclass, pymethods, Bound, Py, PyAny, PyObject, PyResult};
 use savant_core::primitives::any_object::AnyObject;
-use savant_core::primitives::attribute_value::AttributeValueVariant;
+use savant_core::primitives::attribute_value::{AttributeValueVariant, TensorElement};
 use savant_core::primitives::rust;
 use std::collections::hash_map::DefaultHasher;
+use std::ffi::{c_int, c_void, CStr};
 use std::hash::{Hash, Hasher};
 use std::mem;
+use std::ptr;
 use std::sync::Arc;
 
+/// The element type of a tensor attribute value.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TensorDType {
+    UInt8,
+    Int8,
+    UInt16,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+}
+
+impl From<TensorDType> for rust::TensorDType {
+    fn from(value: TensorDType) -> Self {
+        match value {
+            TensorDType::UInt8 => rust::TensorDType::UInt8,
+            TensorDType::Int8 => rust::TensorDType::Int8,
+            TensorDType::UInt16 => rust::TensorDType::UInt16,
+            TensorDType::Int16 => rust::TensorDType::Int16,
+            TensorDType::Int32 => rust::TensorDType::Int32,
+            TensorDType::Int64 => rust::TensorDType::Int64,
+            TensorDType::Float32 => rust::TensorDType::Float32,
+            TensorDType::Float64 => rust::TensorDType::Float64,
+        }
+    }
+}
+
+impl From<rust::TensorDType> for TensorDType {
+    fn from(value: rust::TensorDType) -> Self {
+        match value {
+            rust::TensorDType::UInt8 => TensorDType::UInt8,
+            rust::TensorDType::Int8 => TensorDType::Int8,
+            rust::TensorDType::UInt16 => TensorDType::UInt16,
+            rust::TensorDType::Int16 => TensorDType::Int16,
+            rust::TensorDType::Int32 => TensorDType::Int32,
+            rust::TensorDType::Int64 => TensorDType::Int64,
+            rust::TensorDType::Float32 => TensorDType::Float32,
+            rust::TensorDType::Float64 => TensorDType::Float64,
+        }
+    }
+}
+
+/// The buffer protocol format of the tensor elements.
+///
+fn tensor_format(dtype: rust::TensorDType) -> &'static CStr {
+    match dtype {
+        rust::TensorDType::UInt8 => c"B",
+        rust::TensorDType::Int8 => c"b",
+        rust::TensorDType::UInt16 => c"H",
+        rust::TensorDType::Int16 => c"h",
+        rust::TensorDType::Int32 => c"i",
+        rust::TensorDType::Int64 => c"q",
+        rust::TensorDType::Float32 => c"f",
+        rust::TensorDType::Float64 => c"d",
+    }
+}
+
+/// Reads the array if its elements are of type `T`.
+///
+fn read_tensor<T: Element + TensorElement>(
+    array: &Bound<'_, PyAny>,
+) -> Option<PyResult<rust::Tensor>> {
+    let buffer = PyBuffer::<T>::get(array).ok()?;
+    Some(buffer.to_vec(array.py()).and_then(|values| {
+        rust::Tensor::from_values(buffer.shape().to_vec(), &values)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }))
+}
+
+/// The state of an exported tensor buffer, it keeps the data alive until the buffer is released.
+///
+struct TensorBuffer {
+    data: Arc<Vec<u8>>,
+    shape: Vec<ffi::Py_ssize_t>,
+    strides: Vec<ffi::Py_ssize_t>,
+}
+
 #[pyclass]
 #[derive(Debug, Clone)]
 pub struct AttributeValue(pub rust::AttributeValue);
@@ -76,6 +159,7 @@ impl AttributeValue {
             AttributeValueVariant::Polygon(_) => AttributeValueType::Polygon,
             AttributeValueVariant::PolygonVector(_) => AttributeValueType::PolygonList,
             AttributeValueVariant::Intersection(_) => AttributeValueType::Intersection,
+            AttributeValueVariant::Tensor(_) => AttributeValueType::Tensor,
             AttributeValueVariant::None => AttributeValueType::None_,
             AttributeValueVariant::TemporaryValue(_) => AttributeValueType::TemporaryValue,
         }
@@ -104,6 +188,144 @@ impl AttributeValue {
         })
     }
 
+    /// Creates a new tensor attribute value from an array exported through the buffer protocol,
+    /// e.g. a numpy array. The data is copied once, the shape and the element type are kept.
+    ///
+    /// Parameters
+    /// ----------
+    /// array : numpy.ndarray
+    ///   The array of ``uint8``, ``int8``, ``uint16``, ``int16``, ``int32``, ``int64``,
+    ///   ``float32`` or ``float64`` elements.
+    /// confidence : float, optional
+    ///   The confidence of the attribute value.
+    ///
+    /// Returns
+    /// -------
+    /// :class:`AttributeValue`
+    ///   The attribute value.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the array does not support the buffer protocol or the element type is not supported.
+    ///
+    #[staticmethod]
+    #[pyo3(signature = (array, confidence = None))]
+    pub fn tensor(array: &Bound<'_, PyAny>, confidence: Option<f32>) -> PyResult<Self> {
+        let tensor = read_tensor::<f32>(array)
+            .or_else(|| read_tensor::<f64>(array))
+            .or_else(|| read_tensor::<i64>(array))
+            .or_else(|| read_tensor::<i32>(array))
+            .or_else(|| read_tensor::<i16>(array))
+            .or_else(|| read_tensor::<u16>(array))
+            .or_else(|| read_tensor::<i8>(array))
+            .or_else(|| read_tensor::<u8>(array))
+            .unwrap_or_else(|| {
+                Err(PyValueError::new_err(
+                    "The array must support the buffer protocol and have a supported element type",
+                ))
+            })?;
+        Ok(Self(rust::AttributeValue::new(
+            AttributeValueVariant::Tensor(tensor),
+            confidence,
+        )))
+    }
+
+    /// Creates a new tensor attribute value from the raw native-endian data.
+    ///
+    /// Parameters
+    /// ----------
+    /// dims : List[int]
+    ///   The dimensions of the tensor.
+    /// dtype : :class:`TensorDType`
+    ///   The element type.
+    /// blob : bytes
+    ///   The data, its length must match the dimensions and the element type.
+    /// confidence : float, optional
+    ///   The confidence of the attribute value.
+    ///
+    /// Returns
+    /// -------
+    /// :class:`AttributeValue`
+    ///   The attribute value.
+    ///
+    #[staticmethod]
+    #[pyo3(signature = (dims, dtype, blob, confidence = None))]
+    pub fn tensor_from_bytes(
+        dims: Vec<usize>,
+        dtype: TensorDType,
+        blob: &Bound<'_, PyBytes>,
+        confidence: Option<f32>,
+    ) -> PyResult<Self> {
+        rust::AttributeValue::tensor(dims, dtype.into(), blob.as_bytes().to_vec(), confidence)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Exposes the tensor value through the buffer protocol without copying, so
+    /// ``numpy.asarray(value)`` is a zero-copy view of the tensor. The buffer is read-only.
+    ///
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("The view is null"));
+        }
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("The tensor is read-only"));
+        }
+        let tensor = match &slf.borrow().0.value {
+            AttributeValueVariant::Tensor(t) => t.clone(),
+            _ => return Err(PyBufferError::new_err("The value is not a tensor")),
+        };
+        let item_size = tensor.get_dtype().item_size() as ffi::Py_ssize_t;
+        let shape = tensor
+            .get_dims()
+            .iter()
+            .map(|d| *d as ffi::Py_ssize_t)
+            .collect::<Vec<_>>();
+        let mut strides = vec![item_size; shape.len()];
+        for i in (0..shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1];
+        }
+        let mut state = Box::new(TensorBuffer {
+            data: tensor.get_shared_data(),
+            shape,
+            strides,
+        });
+
+        (*view).obj = slf.clone().into_any().into_ptr();
+        (*view).buf = state.data.as_ptr() as *mut c_void;
+        (*view).len = state.data.len() as ffi::Py_ssize_t;
+        (*view).readonly = 1;
+        (*view).itemsize = item_size;
+        (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+            tensor_format(tensor.get_dtype()).as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        (*view).ndim = state.shape.len() as c_int;
+        (*view).shape = if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+            state.shape.as_mut_ptr()
+        } else {
+            ptr::null_mut()
+        };
+        (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+            state.strides.as_mut_ptr()
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = Box::into_raw(state) as *mut c_void;
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {
+        drop(Box::from_raw((*view).internal as *mut TensorBuffer));
+    }
+
     /// Creates a new attribute value of type None
     ///
     /// Returns
@@ -526,6 +748,31 @@ impl AttributeValue {
         }
     }
 
+    /// Returns the tensor value as a read-only ``memoryview`` sharing the data with the
+    /// attribute value, or None if not a tensor type. Use ``numpy.asarray`` to get the array.
+    ///
+    /// Returns
+    /// -------
+    /// Optional[memoryview]
+    ///   The zero-copy view of the tensor.
+    ///
+    pub fn as_tensor<'py>(slf: &Bound<'py, Self>) -> PyResult<Option<Bound<'py, PyMemoryView>>> {
+        if !matches!(slf.borrow().0.value, AttributeValueVariant::Tensor(_)) {
+            return Ok(None);
+        }
+        PyMemoryView::from(slf.as_any()).map(Some)
+    }
+
+    /// Returns the element type of the tensor value or None if not a tensor type.
+    ///
+    #[getter]
+    pub fn get_tensor_dtype(&self) -> Option<TensorDType> {
+        match &self.0.value {
+            AttributeValueVariant::Tensor(t) => Some(t.get_dtype().into()),
+            _ => None,
+        }
+    }
+
     /// Returns the value of attribute as an :class:`savant_rs.primitives.geometry.Intersection` or None if not an intersection type.
     ///
     /// Returns
@@ -794,6 +1041,7 @@ pub enum AttributeValueType {
     Polygon,
     PolygonList,
     Intersection,
+    Tensor,
     TemporaryValue,
     None_,
 }
@@ -1,9 +1,12 @@
 use crate::match_query::MatchQuery;
+use crate::primitives::bbox::VideoObjectBBoxTransformation;
 use crate::primitives::frame::VideoFrame;
+use crate::primitives::frame_update::VideoFrameUpdate;
 use crate::primitives::object::BorrowedVideoObject;
 use crate::primitives::objects_view::VideoObjectsView;
+use crate::primitives::redaction::RedactionSpec;
 use crate::{release_gil, with_gil};
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::types::{PyBytes, PyBytesMethods};
 use pyo3::{pyclass, pymethods, Bound, PyObject, PyResult};
 use savant_core::primitives::rust;
@@ -62,12 +65,67 @@ impl VideoFrameBatch {
         })
     }
 
+    /// Transforms the geometry of the objects of the frames in parallel, see
+    /// :py:meth:`VideoFrame.transform_geometry`.
+    ///
+    #[pyo3(name = "transform_geometry")]
+    #[pyo3(signature = (ops, no_gil = true))]
+    pub fn transform_geometry_gil(&self, ops: Vec<VideoObjectBBoxTransformation>, no_gil: bool) {
+        release_gil!(no_gil, || {
+            let ops_ref = ops.iter().map(|op| op.0).collect();
+            self.0.transform_geometry(&ops_ref);
+        })
+    }
+
+    /// Applies the updates to the frames with the corresponding ids in parallel, see
+    /// :py:meth:`VideoFrame.update`.
+    ///
+    /// Parameters
+    /// ----------
+    /// updates : Dict[int, VideoFrameUpdate]
+    ///   The updates by the frame ids
+    /// no_gil : bool
+    ///   Whether to release the GIL while applying the updates
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If a frame is missing in the batch or an update cannot be applied
+    ///
+    #[pyo3(name = "update_all")]
+    #[pyo3(signature = (updates, no_gil = true))]
+    pub fn update_all_gil(
+        &self,
+        updates: HashMap<i64, VideoFrameUpdate>,
+        no_gil: bool,
+    ) -> PyResult<()> {
+        let updates = updates
+            .into_iter()
+            .map(|(id, u)| (id, u.0))
+            .collect::<hashbrown::HashMap<_, _>>();
+        release_gil!(no_gil, || self.0.update_all(&updates))
+            .map_err(|e| PyValueError::new_err(format!("{:#}", e)))
+    }
+
     #[pyo3(name = "delete_objects")]
     #[pyo3(signature = (q, no_gil = true))]
     pub fn delete_objects_gil(&mut self, q: &MatchQuery, no_gil: bool) {
         release_gil!(no_gil, || self.0.delete_objects(&q.0))
     }
 
+    /// Produces a copy of the batch with every frame redacted, see :py:meth:`VideoFrame.redact`.
+    ///
+    #[pyo3(name = "redact")]
+    #[pyo3(signature = (q, spec, no_gil = true))]
+    pub fn redact_gil(
+        &self,
+        q: &MatchQuery,
+        spec: &RedactionSpec,
+        no_gil: bool,
+    ) -> VideoFrameBatch {
+        release_gil!(no_gil, || VideoFrameBatch(self.0.redact(&q.0, &spec.0)))
+    }
+
     #[pyo3(name = "to_protobuf")]
     #[pyo3(signature = (no_gil = true))]
     fn to_protobuf_gil(&self, no_gil: bool) -> PyResult<PyObject> {
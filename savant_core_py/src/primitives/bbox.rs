@@ -1,3 +1,4 @@
+pub mod kalman_filter;
 pub mod utils;
 
 use crate::draw_spec::PaddingDraw;
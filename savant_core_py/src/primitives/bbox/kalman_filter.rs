@@ -0,0 +1,120 @@
+use crate::primitives::bbox::RBBox;
+use crate::release_gil;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use savant_core::primitives::bbox::kalman_filter as rust;
+
+/// The estimation of a box by :py:class:`KalmanBBoxFilter`.
+///
+#[pyclass]
+#[derive(Debug, Clone, Copy)]
+pub struct KalmanBBoxState(rust::KalmanBBoxState);
+
+#[pymethods]
+impl KalmanBBoxState {
+    /// The estimated box, the angle is set when the filter was initiated with an oriented box.
+    ///
+    #[getter]
+    fn bbox(&self) -> RBBox {
+        RBBox(self.0.bbox())
+    }
+
+    /// The per-frame velocities of xc, yc, width, height and angle.
+    ///
+    #[getter]
+    fn velocity(&self) -> (f32, f32, f32, f32, f32) {
+        let [xc, yc, width, height, angle] = self.0.velocity();
+        (xc, yc, width, height, angle)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// The Kalman filter of boxes with the constant velocity model over the center, the size and the
+/// angle.
+///
+/// Parameters
+/// ----------
+/// std_position : float
+///   The deviation of the center and the size relative to the box height.
+/// std_velocity : float
+///   The deviation of the center and the size velocities relative to the box height.
+/// std_angle : float
+///   The deviation of the angle in degrees.
+/// std_angle_velocity : float
+///   The deviation of the angle velocity in degrees.
+///
+#[pyclass]
+#[derive(Debug, Clone, Copy)]
+pub struct KalmanBBoxFilter(rust::KalmanBBoxFilter);
+
+#[pymethods]
+impl KalmanBBoxFilter {
+    #[new]
+    #[pyo3(signature = (
+        std_position = 1.0 / 20.0,
+        std_velocity = 1.0 / 160.0,
+        std_angle = 1.0,
+        std_angle_velocity = 0.1
+    ))]
+    fn new(std_position: f32, std_velocity: f32, std_angle: f32, std_angle_velocity: f32) -> Self {
+        Self(rust::KalmanBBoxFilter::new(rust::KalmanBBoxFilterConfig {
+            std_position,
+            std_velocity,
+            std_angle,
+            std_angle_velocity,
+        }))
+    }
+
+    /// Creates the state from the first observation of the box.
+    ///
+    fn initiate(&self, bbox: &RBBox) -> KalmanBBoxState {
+        KalmanBBoxState(self.0.initiate(&bbox.0))
+    }
+
+    /// Predicts the state for the next frame.
+    ///
+    fn predict(&self, state: &KalmanBBoxState) -> KalmanBBoxState {
+        KalmanBBoxState(self.0.predict(&state.0))
+    }
+
+    /// Corrects the state with the observed box.
+    ///
+    fn update(&self, state: &KalmanBBoxState, bbox: &RBBox) -> KalmanBBoxState {
+        KalmanBBoxState(self.0.update(&state.0, &bbox.0))
+    }
+
+    #[pyo3(signature = (states, no_gil = true))]
+    fn predict_batch(&self, states: Vec<KalmanBBoxState>, no_gil: bool) -> Vec<KalmanBBoxState> {
+        let states = states.into_iter().map(|s| s.0).collect::<Vec<_>>();
+        release_gil!(no_gil, || self
+            .0
+            .predict_batch(&states)
+            .into_iter()
+            .map(KalmanBBoxState)
+            .collect())
+    }
+
+    /// Corrects the states with the corresponding boxes.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the numbers of the states and the boxes differ.
+    ///
+    #[pyo3(signature = (states, bboxes, no_gil = true))]
+    fn update_batch(
+        &self,
+        states: Vec<KalmanBBoxState>,
+        bboxes: Vec<RBBox>,
+        no_gil: bool,
+    ) -> PyResult<Vec<KalmanBBoxState>> {
+        let states = states.into_iter().map(|s| s.0).collect::<Vec<_>>();
+        let bboxes = bboxes.into_iter().map(|b| b.0).collect::<Vec<_>>();
+        release_gil!(no_gil, || self.0.update_batch(&states, &bboxes))
+            .map(|states| states.into_iter().map(KalmanBBoxState).collect())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
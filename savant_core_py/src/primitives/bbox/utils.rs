@@ -1,6 +1,9 @@
 use crate::primitives::bbox::{BBoxMetricType, RBBox};
-use crate::with_gil;
+use crate::utils::read_float_array;
+use crate::{release_gil, with_gil};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use savant_core::primitives::rust;
 use std::collections::HashMap;
 
 #[pyfunction]
@@ -27,3 +30,101 @@ pub fn associate_bboxes(
         )
     })
 }
+
+/// Reads the boxes from an array of the shape ``(N, 4)`` with the rows ``xc, yc, width, height``
+/// or ``(N, 5)`` with the angle in degrees added.
+///
+fn read_boxes(boxes: &Bound<'_, PyAny>) -> PyResult<Vec<rust::RBBox>> {
+    let (shape, values) = read_float_array(boxes)?;
+    match shape.as_slice() {
+        [_, 4] => Ok(values
+            .chunks_exact(4)
+            .map(|r| rust::RBBox::new(r[0], r[1], r[2], r[3], None))
+            .collect()),
+        [_, 5] => Ok(values
+            .chunks_exact(5)
+            .map(|r| rust::RBBox::new(r[0], r[1], r[2], r[3], Some(r[4])))
+            .collect()),
+        _ => Err(PyValueError::new_err(format!(
+            "The boxes must have the shape (N, 4) or (N, 5), got {:?}",
+            shape
+        ))),
+    }
+}
+
+/// The non-maximum suppression of the boxes, the rotated boxes are compared by their polygons.
+///
+/// Parameters
+/// ----------
+/// boxes : numpy.ndarray
+///   The float array of the shape ``(N, 4)`` with the rows ``xc, yc, width, height`` or
+///   ``(N, 5)`` with the angle in degrees added.
+/// scores : numpy.ndarray
+///   The float array of the shape ``(N,)`` with the box scores.
+/// iou_threshold : float
+///   The box is suppressed when its IoU with a kept box with a higher score exceeds the threshold.
+/// no_gil : bool
+///   Whether to release the GIL while computing.
+///
+/// Returns
+/// -------
+/// List[int]
+///   The indices of the kept boxes in the order of the decreasing scores.
+///
+#[pyfunction]
+#[pyo3(signature = (boxes, scores, iou_threshold, no_gil = true))]
+pub fn nms(
+    boxes: &Bound<'_, PyAny>,
+    scores: &Bound<'_, PyAny>,
+    iou_threshold: f32,
+    no_gil: bool,
+) -> PyResult<Vec<usize>> {
+    let boxes = read_boxes(boxes)?;
+    let (shape, scores) = read_float_array(scores)?;
+    if shape.len() != 1 {
+        return Err(PyValueError::new_err(format!(
+            "The scores must have the shape (N,), got {:?}",
+            shape
+        )));
+    }
+    release_gil!(no_gil, || {
+        let boxes = boxes.iter().collect::<Vec<_>>();
+        savant_core::primitives::bbox::utils::nms(&boxes, &scores, iou_threshold)
+    })
+    .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Groups the boxes transitively connected by the metric above the threshold.
+///
+/// Parameters
+/// ----------
+/// boxes : numpy.ndarray
+///   The float array of the shape ``(N, 4)`` with the rows ``xc, yc, width, height`` or
+///   ``(N, 5)`` with the angle in degrees added.
+/// metric : BBoxMetricType
+///   The metric, the asymmetric ones connect the boxes when the threshold is exceeded in either
+///   direction.
+/// threshold : float
+///   The metric threshold.
+/// no_gil : bool
+///   Whether to release the GIL while computing.
+///
+/// Returns
+/// -------
+/// List[List[int]]
+///   The clusters of the box indices ordered by their first indices.
+///
+#[pyfunction]
+#[pyo3(signature = (boxes, metric, threshold, no_gil = true))]
+pub fn cluster_boxes(
+    boxes: &Bound<'_, PyAny>,
+    metric: BBoxMetricType,
+    threshold: f32,
+    no_gil: bool,
+) -> PyResult<Vec<Vec<usize>>> {
+    let boxes = read_boxes(boxes)?;
+    Ok(release_gil!(no_gil, || {
+        let boxes = boxes.iter().collect::<Vec<_>>();
+        savant_core::primitives::bbox::utils::cluster_boxes(&boxes, metric.into(), threshold)
+    }))
+}
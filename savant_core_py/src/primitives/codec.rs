@@ -0,0 +1,118 @@
+use crate::primitives::frame::VideoFrame;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pyfunction, pymethods, Py, PyAny, PyResult};
+use savant_core::primitives::codec as rust;
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct CodecInfo(pub(crate) rust::CodecInfo);
+
+#[pymethods]
+impl CodecInfo {
+    #[new]
+    #[pyo3(signature = (name, mime_type, aliases=vec![], fourcc=None, requires_keyframes=false, is_raw=false))]
+    pub fn new(
+        name: &str,
+        mime_type: &str,
+        aliases: Vec<String>,
+        fourcc: Option<&str>,
+        requires_keyframes: bool,
+        is_raw: bool,
+    ) -> Self {
+        let aliases = aliases.iter().map(String::as_str).collect::<Vec<_>>();
+        Self(rust::CodecInfo::new(
+            name,
+            &aliases,
+            fourcc,
+            mime_type,
+            requires_keyframes,
+            is_raw,
+        ))
+    }
+
+    #[getter]
+    pub fn name(&self) -> String {
+        self.0.name.clone()
+    }
+
+    #[getter]
+    pub fn aliases(&self) -> Vec<String> {
+        self.0.aliases.clone()
+    }
+
+    #[getter]
+    pub fn fourcc(&self) -> Option<String> {
+        self.0.fourcc.clone()
+    }
+
+    #[getter]
+    pub fn mime_type(&self) -> String {
+        self.0.mime_type.clone()
+    }
+
+    #[getter]
+    pub fn requires_keyframes(&self) -> bool {
+        self.0.requires_keyframes
+    }
+
+    #[getter]
+    pub fn is_raw(&self) -> bool {
+        self.0.is_raw
+    }
+
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// Adds a codec to the registry or replaces the one with the same name.
+///
+/// Raises
+/// ------
+/// ValueError
+///   If the name or an alias of the codec is already used by another codec.
+///
+#[pyfunction]
+pub fn register_codec(info: CodecInfo) -> PyResult<()> {
+    rust::register_codec(info.0).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Looks up the codec by its name or alias, case-insensitively.
+///
+#[pyfunction]
+pub fn find_codec(name: &str) -> Option<CodecInfo> {
+    rust::find_codec(name).map(CodecInfo)
+}
+
+#[pyfunction]
+pub fn find_codec_by_fourcc(fourcc: &str) -> Option<CodecInfo> {
+    rust::find_codec_by_fourcc(fourcc).map(CodecInfo)
+}
+
+#[pyfunction]
+pub fn find_codecs_by_mime_type(mime_type: &str) -> Vec<CodecInfo> {
+    rust::find_codecs_by_mime_type(mime_type)
+        .into_iter()
+        .map(CodecInfo)
+        .collect()
+}
+
+#[pyfunction]
+pub fn known_codecs() -> Vec<CodecInfo> {
+    rust::known_codecs().into_iter().map(CodecInfo).collect()
+}
+
+/// Decides if the content of the source frame must be transcoded to be delivered as the
+/// target frame.
+///
+#[pyfunction]
+pub fn transcoding_required(source: &VideoFrame, target: &VideoFrame) -> bool {
+    rust::transcoding_required(&source.0, &target.0)
+}
@@ -3,23 +3,183 @@ use crate::match_query::MatchQuery;
 use crate::primitives::attribute::Attribute;
 use crate::primitives::attribute_value::AttributeValue;
 use crate::primitives::bbox::{RBBox, VideoObjectBBoxTransformation};
+use crate::primitives::codec::CodecInfo;
 use crate::primitives::frame_update::VideoFrameUpdate;
+use crate::primitives::journal::{to_records, JournalRecord};
 use crate::primitives::message::Message;
 use crate::primitives::object::{BorrowedVideoObject, IdCollisionResolutionPolicy, VideoObject};
 use crate::primitives::objects_view::VideoObjectsView;
+use crate::primitives::redaction::RedactionSpec;
 use crate::release_gil;
+use crate::utils::otlp::{PropagatedContext, TelemetrySpan};
 use crate::with_gil;
-use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::buffer::{Element, PyBuffer};
+use pyo3::exceptions::{PyBufferError, PyRuntimeError, PyValueError};
 use pyo3::types::{PyBytes, PyBytesMethods};
-use pyo3::{pyclass, pymethods, Bound, Py, PyAny, PyObject, PyResult};
+use pyo3::{ffi, pyclass, pymethods, Bound, Py, PyAny, PyErr, PyObject, PyResult};
 use savant_core::json_api::ToSerdeJsonValue;
 use savant_core::primitives::object::ObjectOperations;
 use savant_core::primitives::{rust, WithAttributes};
 use savant_core::protobuf::{from_pb, ToProtobuf};
 use serde_json::Value;
+use std::ffi::{c_int, c_void};
 use std::fmt::Debug;
 use std::mem;
 
+/// The memory the external frame resides in.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExternalMemoryType {
+    Host,
+    CudaDevice,
+    CudaPinned,
+    CudaUnified,
+    Nvmm,
+    DmaBuf,
+    VaSurface,
+}
+
+impl From<ExternalMemoryType> for rust::ExternalMemoryType {
+    fn from(value: ExternalMemoryType) -> Self {
+        match value {
+            ExternalMemoryType::Host => rust::ExternalMemoryType::Host,
+            ExternalMemoryType::CudaDevice => rust::ExternalMemoryType::CudaDevice,
+            ExternalMemoryType::CudaPinned => rust::ExternalMemoryType::CudaPinned,
+            ExternalMemoryType::CudaUnified => rust::ExternalMemoryType::CudaUnified,
+            ExternalMemoryType::Nvmm => rust::ExternalMemoryType::Nvmm,
+            ExternalMemoryType::DmaBuf => rust::ExternalMemoryType::DmaBuf,
+            ExternalMemoryType::VaSurface => rust::ExternalMemoryType::VaSurface,
+        }
+    }
+}
+
+impl From<rust::ExternalMemoryType> for ExternalMemoryType {
+    fn from(value: rust::ExternalMemoryType) -> Self {
+        match value {
+            rust::ExternalMemoryType::Host => ExternalMemoryType::Host,
+            rust::ExternalMemoryType::CudaDevice => ExternalMemoryType::CudaDevice,
+            rust::ExternalMemoryType::CudaPinned => ExternalMemoryType::CudaPinned,
+            rust::ExternalMemoryType::CudaUnified => ExternalMemoryType::CudaUnified,
+            rust::ExternalMemoryType::Nvmm => ExternalMemoryType::Nvmm,
+            rust::ExternalMemoryType::DmaBuf => ExternalMemoryType::DmaBuf,
+            rust::ExternalMemoryType::VaSurface => ExternalMemoryType::VaSurface,
+        }
+    }
+}
+
+/// The layout of an external frame surface.
+///
+/// Parameters
+/// ----------
+/// memory_type : ExternalMemoryType
+///   The memory the frame resides in.
+/// fourcc : str
+///   The pixel format as 4 ASCII characters, e.g. ``NV12``.
+/// plane_strides : List[int]
+///   The stride of every plane in bytes.
+/// plane_offsets : List[int]
+///   The offset of every plane in bytes, may be empty.
+/// device_id : Optional[int]
+///   The GPU the frame resides on.
+/// handle : Optional[int]
+///   The producer-specific handle of the surface, e.g. the DMA-BUF descriptor or the VA surface
+///   id.
+///
+/// Raises
+/// ------
+/// ValueError
+///   If the fourcc is malformed or the offsets do not match the planes.
+///
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ExternalFrameDescriptor(pub(crate) rust::ExternalFrameDescriptor);
+
+#[pymethods]
+impl ExternalFrameDescriptor {
+    #[new]
+    #[pyo3(signature = (
+        memory_type,
+        fourcc,
+        plane_strides,
+        plane_offsets=vec![],
+        device_id=None,
+        handle=None
+    ))]
+    pub fn new(
+        memory_type: ExternalMemoryType,
+        fourcc: &str,
+        plane_strides: Vec<u32>,
+        plane_offsets: Vec<u64>,
+        device_id: Option<u32>,
+        handle: Option<u64>,
+    ) -> PyResult<Self> {
+        rust::ExternalFrameDescriptor::new(
+            memory_type.into(),
+            device_id,
+            fourcc,
+            plane_strides,
+            plane_offsets,
+            handle,
+        )
+        .map(Self)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    pub fn memory_type(&self) -> ExternalMemoryType {
+        self.0.get_memory_type().into()
+    }
+
+    #[getter]
+    pub fn device_id(&self) -> Option<u32> {
+        self.0.get_device_id()
+    }
+
+    #[getter]
+    pub fn fourcc(&self) -> String {
+        self.0.get_fourcc()
+    }
+
+    /// The fourcc as the little-endian code, as defined by V4L2 and DRM.
+    ///
+    #[getter]
+    pub fn fourcc_code(&self) -> u32 {
+        self.0.get_fourcc_code()
+    }
+
+    #[getter]
+    pub fn num_planes(&self) -> usize {
+        self.0.get_num_planes()
+    }
+
+    #[getter]
+    pub fn plane_strides(&self) -> Vec<u32> {
+        self.0.get_plane_strides().to_vec()
+    }
+
+    #[getter]
+    pub fn plane_offsets(&self) -> Vec<u64> {
+        self.0.get_plane_offsets().to_vec()
+    }
+
+    #[getter]
+    pub fn handle(&self) -> Option<u64> {
+        self.0.get_handle()
+    }
+
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
 #[pyclass]
 pub struct ExternalFrame(pub(crate) rust::ExternalFrame);
 
@@ -63,6 +223,60 @@ impl ExternalFrame {
     }
 }
 
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ClockSyncInfo(pub(crate) rust::ClockSyncInfo);
+
+#[pymethods]
+impl ClockSyncInfo {
+    #[new]
+    #[pyo3(signature = (capture_time_ns, ntp_offset_ns=None, clock_domain=None))]
+    pub fn new(
+        capture_time_ns: i64,
+        ntp_offset_ns: Option<i64>,
+        clock_domain: Option<&str>,
+    ) -> Self {
+        Self(rust::ClockSyncInfo::new(
+            capture_time_ns,
+            ntp_offset_ns,
+            clock_domain,
+        ))
+    }
+
+    #[getter]
+    pub fn capture_time_ns(&self) -> i64 {
+        self.0.capture_time_ns
+    }
+
+    #[getter]
+    pub fn ntp_offset_ns(&self) -> Option<i64> {
+        self.0.ntp_offset_ns
+    }
+
+    #[getter]
+    pub fn clock_domain(&self) -> Option<String> {
+        self.0.clock_domain.clone()
+    }
+
+    /// The capture time adjusted to the NTP reference.
+    ///
+    #[getter]
+    pub fn corrected_capture_time_ns(&self) -> i64 {
+        self.0.corrected_capture_time_ns()
+    }
+
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
 impl ToSerdeJsonValue for ExternalFrame {
     fn to_serde_json_value(&self) -> Value {
         self.0.to_serde_json_value()
@@ -89,18 +303,23 @@ impl VideoFrameContent {
     }
 
     #[staticmethod]
-    #[pyo3(signature = (method, location=None))]
-    pub fn external(method: String, location: Option<String>) -> Self {
+    #[pyo3(signature = (method, location=None, descriptor=None))]
+    pub fn external(
+        method: String,
+        location: Option<String>,
+        descriptor: Option<ExternalFrameDescriptor>,
+    ) -> Self {
         Self(rust::VideoFrameContent::External(rust::ExternalFrame {
             method,
             location,
+            descriptor: descriptor.map(|d| d.0),
         }))
     }
 
     #[staticmethod]
     pub fn internal(data: &Bound<'_, PyBytes>) -> Self {
         let bytes = data.as_bytes();
-        Self(rust::VideoFrameContent::Internal(bytes.to_vec()))
+        Self(rust::VideoFrameContent::internal(bytes.to_vec()))
     }
 
     #[staticmethod]
@@ -150,6 +369,38 @@ impl VideoFrameContent {
         }
     }
 
+    /// Exposes the internal video data through the buffer protocol without copying, so
+    /// ``memoryview(content)[a:b]`` is a zero-copy slice of the data. The buffer is read-only.
+    ///
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        let (ptr, len) = match &slf.borrow().0 {
+            rust::VideoFrameContent::Internal(data) => (data.as_ptr(), data.len()),
+            _ => {
+                return Err(PyBufferError::new_err(
+                    "Video data is not stored internally",
+                ))
+            }
+        };
+        // the content is immutable in Python and the buffer keeps a reference to it, so the
+        // shared data outlives the view
+        if ffi::PyBuffer_FillInfo(
+            view,
+            slf.as_ptr(),
+            ptr as *mut c_void,
+            len as ffi::Py_ssize_t,
+            1,
+            flags,
+        ) == -1
+        {
+            return Err(PyErr::fetch(slf.py()));
+        }
+        Ok(())
+    }
+
     /// Returns the method for external video data if the content is external,
     /// otherwise results in the TypeError exception.
     ///
@@ -193,6 +444,31 @@ impl VideoFrameContent {
             )),
         }
     }
+
+    /// Returns the descriptor of the external video data if the content is external,
+    /// otherwise results in the TypeError exception. The descriptor is transferred with
+    /// protobuf as a hidden frame attribute.
+    ///
+    /// Returns
+    /// -------
+    /// Optional[ExternalFrameDescriptor]
+    ///   The descriptor of the external video data.
+    ///
+    /// Raises
+    /// ------
+    /// TypeError
+    ///   If the content is not external.
+    ///
+    pub fn get_descriptor(&self) -> PyResult<Option<ExternalFrameDescriptor>> {
+        match &self.0 {
+            rust::VideoFrameContent::External(data) => {
+                Ok(data.descriptor.clone().map(ExternalFrameDescriptor))
+            }
+            _ => Err(pyo3::exceptions::PyTypeError::new_err(
+                "Video data is not stored externally",
+            )),
+        }
+    }
 }
 
 /// Represents the structure for accessing primary video content encoding information
@@ -231,6 +507,24 @@ impl ToSerdeJsonValue for VideoFrameTranscodingMethod {
     }
 }
 
+/// Defines how :py:meth:`VideoFrame.crop` treats the objects crossing the region border.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CropObjectPolicy {
+    Clip,
+    Drop,
+}
+
+impl From<CropObjectPolicy> for rust::CropObjectPolicy {
+    fn from(value: CropObjectPolicy) -> Self {
+        match value {
+            CropObjectPolicy::Clip => rust::CropObjectPolicy::Clip,
+            CropObjectPolicy::Drop => rust::CropObjectPolicy::Drop,
+        }
+    }
+}
+
 /// Represents the structure for accessing/defining video frame transformation information.
 ///
 #[pyclass]
@@ -328,6 +622,30 @@ impl VideoFrameTransformation {
         ))
     }
 
+    /// Defines the crop operation on the frame.
+    ///
+    /// Parameters
+    /// ----------
+    /// left : int
+    ///   The left border of the region.
+    /// top : int
+    ///   The top border of the region.
+    /// width : int
+    ///   The width of the region.
+    /// height : int
+    ///   The height of the region.
+    ///
+    #[staticmethod]
+    pub fn crop(left: i64, top: i64, width: i64, height: i64) -> Self {
+        assert!(left >= 0 && top >= 0 && width > 0 && height > 0);
+        Self(rust::VideoFrameTransformation::Crop(
+            u64::try_from(left).unwrap(),
+            u64::try_from(top).unwrap(),
+            u64::try_from(width).unwrap(),
+            u64::try_from(height).unwrap(),
+        ))
+    }
+
     /// Returns true if the transformation is initial size, otherwise false.
     ///
     /// Returns
@@ -376,6 +694,18 @@ impl VideoFrameTransformation {
         matches!(self.0, rust::VideoFrameTransformation::ResultingSize(_, _))
     }
 
+    /// Returns true if the transformation is crop, otherwise false.
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///   True if the transformation is crop, otherwise false.
+    ///
+    #[getter]
+    pub fn is_crop(&self) -> bool {
+        matches!(self.0, rust::VideoFrameTransformation::Crop(_, _, _, _))
+    }
+
     /// Returns the transformation as initial size if it is initial size, otherwise None.
     ///
     /// Returns
@@ -435,6 +765,42 @@ impl VideoFrameTransformation {
             _ => None,
         }
     }
+
+    /// Returns the transformation as crop if it is crop, otherwise None.
+    ///
+    /// Returns
+    /// -------
+    /// Optional[Tuple[int, int, int, int]]
+    ///   The crop region as left, top, width and height if it is crop, otherwise None.
+    ///
+    #[getter]
+    pub fn as_crop(&self) -> Option<(u64, u64, u64, u64)> {
+        match &self.0 {
+            rust::VideoFrameTransformation::Crop(l, t, w, h) => Some((*l, *t, *w, *h)),
+            _ => None,
+        }
+    }
+}
+
+/// Copies a contiguous array exported through the buffer protocol (e.g. a numpy array), the
+/// element type and the shape must match exactly.
+///
+fn read_array<T: Element>(
+    array: &Bound<'_, PyAny>,
+    name: &str,
+    shape: &[usize],
+) -> PyResult<Vec<T>> {
+    let buffer = PyBuffer::<T>::get(array)
+        .map_err(|e| PyValueError::new_err(format!("Invalid {} array: {}", name, e)))?;
+    if buffer.shape() != shape {
+        return Err(PyValueError::new_err(format!(
+            "The {} array must have the shape {:?}, got {:?}",
+            name,
+            shape,
+            buffer.shape()
+        )));
+    }
+    buffer.to_vec(array.py())
 }
 
 #[pyclass]
@@ -673,6 +1039,65 @@ impl VideoFrame {
         self.0.set_codec(codec)
     }
 
+    /// Sets the codec, rejecting the ones missing in the codec registry.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the codec is unknown.
+    ///
+    pub fn try_set_codec(&mut self, codec: Option<String>) -> PyResult<()> {
+        self.0
+            .try_set_codec(codec)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    pub fn get_codec_info(&self) -> Option<CodecInfo> {
+        self.0.get_codec_info().map(CodecInfo)
+    }
+
+    /// Wall-clock synchronization data of the frame.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the underlying attribute is malformed.
+    ///
+    #[getter]
+    pub fn get_clock_sync(&self) -> PyResult<Option<ClockSyncInfo>> {
+        self.0
+            .get_clock_sync()
+            .map(|info| info.map(ClockSyncInfo))
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[setter]
+    pub fn set_clock_sync(&mut self, clock_sync: Option<ClockSyncInfo>) {
+        self.0.set_clock_sync(clock_sync.as_ref().map(|c| &c.0))
+    }
+
+    /// The propagation context attached to the frame with
+    /// :py:meth:`attach_trace_context`, use
+    /// :py:meth:`PropagatedContext.nested_span` to continue the trace.
+    ///
+    #[getter]
+    pub fn get_trace_context(&self) -> Option<PropagatedContext> {
+        self.0.get_trace_context().map(PropagatedContext)
+    }
+
+    /// Attaches the context of the span to the frame as a hidden persistent
+    /// attribute, so the trace follows the frame to the other processes.
+    ///
+    /// Parameters
+    /// ----------
+    /// span: :py:class:`savant_rs.utils.TelemetrySpan`
+    ///   The span to continue in the other processes.
+    ///
+    pub fn attach_trace_context(&mut self, span: &TelemetrySpan) {
+        self.0.attach_trace_context(&span.0)
+    }
+
     #[getter]
     pub fn get_keyframe(&self) -> Option<bool> {
         self.0.get_keyframe()
@@ -739,8 +1164,15 @@ impl VideoFrame {
     /// ----------
     /// transformation : :py:class:`savant_rs.primitives.VideoFrameTransformation`
     ///
-    pub fn add_transformation(&mut self, transformation: VideoFrameTransformation) {
-        self.0.add_transformation(transformation.0)
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the transformation has a zero size
+    ///
+    pub fn add_transformation(&mut self, transformation: VideoFrameTransformation) -> PyResult<()> {
+        self.0
+            .add_transformation(transformation.0)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
     /// Returns the list of transformations
@@ -759,6 +1191,57 @@ impl VideoFrame {
         }
     }
 
+    /// Maps the box in the frame coordinates to the coordinates of the source frame, undoing the
+    /// recorded scaling, padding and cropping. The resulting size trims the frame and does not
+    /// change the coordinates.
+    ///
+    /// Parameters
+    /// ----------
+    /// bbox : :py:class:`savant_rs.primitives.geometry.RBBox`
+    ///   The box, it is not modified
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`savant_rs.primitives.geometry.RBBox`
+    ///   The mapped box
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the size of the frame before a scaling is unknown
+    ///
+    pub fn map_box_to_source(&self, bbox: &RBBox) -> PyResult<RBBox> {
+        self.0
+            .map_box_to_source(&bbox.0)
+            .map(RBBox)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Maps the box in the coordinates of the source frame to the frame coordinates, applying
+    /// the recorded scaling, padding and cropping.
+    ///
+    /// Parameters
+    /// ----------
+    /// bbox : :py:class:`savant_rs.primitives.geometry.RBBox`
+    ///   The box, it is not modified
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`savant_rs.primitives.geometry.RBBox`
+    ///   The mapped box
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the size of the frame before a scaling is unknown
+    ///
+    pub fn map_box_from_source(&self, bbox: &RBBox) -> PyResult<RBBox> {
+        self.0
+            .map_box_from_source(&bbox.0)
+            .map(RBBox)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
     /// Returns the list of attributes
     ///
     /// Returns
@@ -938,6 +1421,118 @@ impl VideoFrame {
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
+    /// Adds the objects under a single frame lock. Either all the objects are added or none.
+    ///
+    /// Parameters
+    /// ----------
+    /// objects : List[:py:class:`VideoObject`]
+    ///   The objects to add, a parent may be one of the objects preceding it in the list.
+    /// policy : :py:class:`IdCollisionResolutionPolicy`
+    ///   The policy applied when an object ID is already taken.
+    /// no_gil : bool
+    ///   Whether to release the GIL while adding the objects.
+    ///
+    /// Returns
+    /// -------
+    /// List[:py:class:`BorrowedVideoObject`]
+    ///   The added objects in the order of the list.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If a parent does not exist or an ID collides with the ``Error`` policy.
+    ///
+    #[pyo3(name = "add_objects")]
+    #[pyo3(signature = (objects, policy, no_gil = true))]
+    pub fn add_objects_gil(
+        &self,
+        objects: Vec<VideoObject>,
+        policy: IdCollisionResolutionPolicy,
+        no_gil: bool,
+    ) -> PyResult<Vec<BorrowedVideoObject>> {
+        let objects = objects.into_iter().map(|o| o.0).collect::<Vec<_>>();
+        release_gil!(no_gil, || self.0.add_objects(objects, policy.into()))
+            .map(|objs| objs.into_iter().map(BorrowedVideoObject).collect())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Creates the objects from the detection arrays in one pass, the objects are assigned new
+    /// IDs.
+    ///
+    /// Parameters
+    /// ----------
+    /// namespace : str
+    ///   The namespace of the objects.
+    /// labels : List[str]
+    ///   The labels of the objects, one per row.
+    /// boxes : numpy.ndarray
+    ///   The ``float32`` array of the shape ``[N, 5]``, the rows are ``[xc, yc, width, height,
+    ///   angle]``, ``NaN`` angle stands for an unrotated box.
+    /// confidences : numpy.ndarray
+    ///   The ``float32`` array of the shape ``[N]``.
+    /// track_ids : Optional[numpy.ndarray]
+    ///   The ``int64`` array of the shape ``[N]``, the track boxes are set to the detection boxes.
+    /// no_gil : bool
+    ///   Whether to release the GIL while creating the objects.
+    ///
+    /// Returns
+    /// -------
+    /// List[:py:class:`BorrowedVideoObject`]
+    ///   The created objects in the order of the rows.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If an array has a wrong type or shape.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(name = "create_objects_from_arrays")]
+    #[pyo3(signature = (namespace, labels, boxes, confidences, track_ids = None, no_gil = true))]
+    pub fn create_objects_from_arrays_gil(
+        &self,
+        namespace: &str,
+        labels: Vec<String>,
+        boxes: &Bound<'_, PyAny>,
+        confidences: &Bound<'_, PyAny>,
+        track_ids: Option<&Bound<'_, PyAny>>,
+        no_gil: bool,
+    ) -> PyResult<Vec<BorrowedVideoObject>> {
+        let n = labels.len();
+        let boxes = read_array::<f32>(boxes, "boxes", &[n, 5])?;
+        let confidences = read_array::<f32>(confidences, "confidences", &[n])?;
+        let track_ids = track_ids
+            .map(|t| read_array::<i64>(t, "track_ids", &[n]))
+            .transpose()?;
+        release_gil!(no_gil, || {
+            let first_id = self.0.get_max_object_id() + 1;
+            let objects = labels
+                .into_iter()
+                .zip(boxes.chunks_exact(5))
+                .enumerate()
+                .map(|(i, (label, b))| {
+                    let angle = (!b[4].is_nan()).then_some(b[4]);
+                    let detection_box = rust::RBBox::new(b[0], b[1], b[2], b[3], angle);
+                    let track_id = track_ids.as_ref().map(|t| t[i]);
+                    rust::VideoObjectBuilder::default()
+                        .id(first_id + i as i64)
+                        .namespace(namespace.to_string())
+                        .label(label)
+                        .track_box(track_id.map(|_| detection_box.copy()))
+                        .detection_box(detection_box)
+                        .attributes(Vec::new())
+                        .confidence(Some(confidences[i]))
+                        .track_id(track_id)
+                        .build()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            self.0
+                .add_objects(objects, IdCollisionResolutionPolicy::GenerateNewId.into())
+        })
+        .map(|objs| objs.into_iter().map(BorrowedVideoObject).collect())
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
     #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (namespace, label, parent_id=None, confidence=None, detection_box=None, track_id=None, track_box=None, attributes=None))]
     pub fn create_object(
@@ -1064,12 +1659,99 @@ impl VideoFrame {
         self.0.get_children(id).into()
     }
 
+    /// Starts recording the changes made through the setters of the frame and its current
+    /// objects, see :py:class:`JournalRecord`.
+    ///
+    pub fn enable_journal(&self) {
+        self.0.enable_journal()
+    }
+
+    /// Stops recording the changes of the frame and its objects and drops the records.
+    ///
+    pub fn disable_journal(&self) {
+        self.0.disable_journal()
+    }
+
+    /// Returns the recorded frame changes, ``None`` when the journal is disabled. The object
+    /// changes are available with :py:meth:`BorrowedVideoObject.get_journal`.
+    ///
+    pub fn get_journal(&self) -> Option<Vec<JournalRecord>> {
+        to_records(self.0.get_journal())
+    }
+
+    /// Returns the recorded frame changes and clears the journal, which stays enabled.
+    ///
+    pub fn take_journal(&self) -> Option<Vec<JournalRecord>> {
+        to_records(self.0.take_journal())
+    }
+
     #[pyo3(name = "copy")]
     #[pyo3(signature = (no_gil = true))]
     pub fn copy_gil(&self, no_gil: bool) -> VideoFrame {
         release_gil!(no_gil, || VideoFrame(self.0.smart_copy()))
     }
 
+    /// Produces a privacy-safe copy of the frame: the attributes of the objects matching the
+    /// query are removed or masked according to the spec. The frame itself is not changed.
+    ///
+    /// Parameters
+    /// ----------
+    /// q : :py:class:`savant_rs.match_query.MatchQuery`
+    ///   The query selecting the objects to redact.
+    /// spec : :py:class:`RedactionSpec`
+    ///   The redaction rules.
+    /// no_gil : bool
+    ///   Whether to release the GIL while redacting.
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`VideoFrame`
+    ///   The redacted copy.
+    ///
+    #[pyo3(name = "redact")]
+    #[pyo3(signature = (q, spec, no_gil = true))]
+    pub fn redact_gil(&self, q: &MatchQuery, spec: &RedactionSpec, no_gil: bool) -> VideoFrame {
+        release_gil!(no_gil, || VideoFrame(self.0.redact(&q.0, &spec.0)))
+    }
+
+    /// Produces the copy of the frame limited to the axis-aligned region. The objects are
+    /// translated to the region coordinates, the ones outside the region are dropped and the ones
+    /// crossing its border are clipped or dropped according to the policy. The region is recorded
+    /// as the crop transformation, so the boxes can be mapped back with
+    /// :py:meth:`VideoFrame.map_box_to_source`. The content is not cropped.
+    ///
+    /// Parameters
+    /// ----------
+    /// roi : :py:class:`RBBox`
+    ///   The region, clamped by the frame size and rounded to whole pixels.
+    /// policy : :py:class:`CropObjectPolicy`
+    ///   How to treat the objects crossing the region border.
+    /// no_gil : bool
+    ///   Whether to release the GIL while cropping.
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`VideoFrame`
+    ///   The cropped copy.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the region is rotated or outside the frame.
+    ///
+    #[pyo3(name = "crop")]
+    #[pyo3(signature = (roi, policy = CropObjectPolicy::Clip, no_gil = true))]
+    pub fn crop_gil(
+        &self,
+        roi: &RBBox,
+        policy: CropObjectPolicy,
+        no_gil: bool,
+    ) -> PyResult<VideoFrame> {
+        release_gil!(no_gil, || self.0.crop(&roi.0, policy.into()))
+            .map(VideoFrame)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
     /// Updates the frame with the given update. The function is GIL-free.
     ///
     /// The order of execution:
@@ -1098,6 +1780,28 @@ impl VideoFrame {
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
+    /// Computes the update turning this frame into the other one with :py:meth:`update`. The
+    /// missing attributes and objects are deleted, the added or changed attributes are replaced
+    /// and the added or changed objects are placed with their ids. The deletions and the placed
+    /// objects are transferred with protobuf as hidden frame attributes.
+    ///
+    /// Parameters
+    /// ----------
+    /// other: :py:class:`VideoFrame`
+    ///   The frame to compare with
+    /// no_gil: bool
+    ///   Whether to release the GIL while computing the difference
+    ///
+    /// Returns
+    /// -------
+    /// :py:class:`savant_rs.primitives.VideoFrameUpdate`
+    ///   The update
+    ///
+    #[pyo3(signature = (other, no_gil = true))]
+    pub fn diff(&self, other: &VideoFrame, no_gil: bool) -> VideoFrameUpdate {
+        release_gil!(no_gil, || VideoFrameUpdate(self.0.diff(&other.0)))
+    }
+
     #[pyo3(name = "to_protobuf")]
     #[pyo3(signature = (no_gil = true))]
     fn to_protobuf_gil(&self, no_gil: bool) -> PyResult<PyObject> {
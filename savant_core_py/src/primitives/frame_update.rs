@@ -90,6 +90,120 @@ impl From<rust::AttributeUpdatePolicy> for AttributeUpdatePolicy {
     }
 }
 
+/// Defines how a field of an existing object is merged with the field of the matching object
+/// from :class:`VideoFrameUpdate`.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergePolicy {
+    KeepOriginal,
+    ReplaceWithUpdate,
+    HighestConfidence,
+    UnionAttributes,
+}
+
+impl From<MergePolicy> for rust::MergePolicy {
+    fn from(p: MergePolicy) -> Self {
+        match p {
+            MergePolicy::KeepOriginal => rust::MergePolicy::KeepOriginal,
+            MergePolicy::ReplaceWithUpdate => rust::MergePolicy::ReplaceWithUpdate,
+            MergePolicy::HighestConfidence => rust::MergePolicy::HighestConfidence,
+            MergePolicy::UnionAttributes => rust::MergePolicy::UnionAttributes,
+        }
+    }
+}
+
+impl From<rust::MergePolicy> for MergePolicy {
+    fn from(p: rust::MergePolicy) -> Self {
+        match p {
+            rust::MergePolicy::KeepOriginal => MergePolicy::KeepOriginal,
+            rust::MergePolicy::ReplaceWithUpdate => MergePolicy::ReplaceWithUpdate,
+            rust::MergePolicy::HighestConfidence => MergePolicy::HighestConfidence,
+            rust::MergePolicy::UnionAttributes => MergePolicy::UnionAttributes,
+        }
+    }
+}
+
+/// The per-field merge policies applied when an object from :class:`VideoFrameUpdate` matches an
+/// existing object of the same namespace: by the track id when the update object is tracked,
+/// otherwise by the object id. The unmatched objects are processed according to
+/// :class:`ObjectUpdatePolicy`. The policies are transferred with protobuf as a hidden frame
+/// attribute.
+///
+/// Parameters
+/// ----------
+/// label: :py:class:`MergePolicy`
+///   The policy for the label.
+/// detection_box: :py:class:`MergePolicy`
+///   The policy for the detection box and the confidence.
+/// track: :py:class:`MergePolicy`
+///   The policy for the track id and the track box.
+/// attributes: :py:class:`MergePolicy`
+///   The policy for the attributes.
+///
+/// Raises
+/// ------
+/// ValueError
+///   If ``UnionAttributes`` is used for a field other than the attributes.
+///
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct ObjectMergePolicy(pub(crate) rust::ObjectMergePolicy);
+
+#[pymethods]
+impl ObjectMergePolicy {
+    #[new]
+    #[pyo3(signature = (
+        label = MergePolicy::KeepOriginal,
+        detection_box = MergePolicy::KeepOriginal,
+        track = MergePolicy::KeepOriginal,
+        attributes = MergePolicy::KeepOriginal
+    ))]
+    pub fn new(
+        label: MergePolicy,
+        detection_box: MergePolicy,
+        track: MergePolicy,
+        attributes: MergePolicy,
+    ) -> PyResult<Self> {
+        rust::ObjectMergePolicy::new(
+            label.into(),
+            detection_box.into(),
+            track.into(),
+            attributes.into(),
+        )
+        .map(Self)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    #[getter]
+    pub fn label(&self) -> MergePolicy {
+        self.0.label.into()
+    }
+
+    #[getter]
+    pub fn detection_box(&self) -> MergePolicy {
+        self.0.detection_box.into()
+    }
+
+    #[getter]
+    pub fn track(&self) -> MergePolicy {
+        self.0.track.into()
+    }
+
+    #[getter]
+    pub fn attributes(&self) -> MergePolicy {
+        self.0.attributes.into()
+    }
+}
+
 /// A video frame update object is used to update state of a frame from external source.
 ///
 /// It contains a list of attributes and a list of objects.
@@ -207,6 +321,30 @@ impl VideoFrameUpdate {
         self.0.set_object_policy(p.into());
     }
 
+    /// Gets the merge policies for the objects matching the existing ones, ``None`` when the
+    /// objects are not merged.
+    ///
+    /// Returns
+    /// -------
+    /// Optional[:py:class:`ObjectMergePolicy`]
+    ///
+    #[getter]
+    pub fn get_object_merge_policy(&self) -> Option<ObjectMergePolicy> {
+        self.0.get_object_merge_policy().map(ObjectMergePolicy)
+    }
+
+    /// Sets the merge policies for the objects matching the existing ones.
+    ///
+    /// Parameters
+    /// ----------
+    /// p: Optional[:py:class:`ObjectMergePolicy`]
+    ///   The policies, ``None`` disables merging.
+    ///
+    #[setter]
+    pub fn set_object_merge_policy(&mut self, p: Option<ObjectMergePolicy>) {
+        self.0.set_object_merge_policy(p.map(|p| p.0));
+    }
+
     /// Adds an object to the frame update.
     ///
     /// Parameters
@@ -241,6 +379,68 @@ impl VideoFrameUpdate {
             .collect()
     }
 
+    /// Requests the deletion of the frame attribute, the deletions are applied first. The
+    /// deletions are transferred with protobuf as a hidden frame attribute.
+    ///
+    /// Parameters
+    /// ----------
+    /// namespace: str
+    ///   The attribute namespace
+    /// name: str
+    ///   The attribute name
+    ///
+    pub fn add_deleted_frame_attribute(&mut self, namespace: &str, name: &str) {
+        self.0.add_deleted_frame_attribute(namespace, name);
+    }
+
+    #[getter]
+    pub fn get_deleted_frame_attributes(&self) -> Vec<(String, String)> {
+        self.0.get_deleted_frame_attributes().clone()
+    }
+
+    /// Requests the deletion of the object, the deletions are applied first. The deletions are
+    /// transferred with protobuf as a hidden frame attribute.
+    ///
+    /// Parameters
+    /// ----------
+    /// object_id: int
+    ///   The object id
+    ///
+    pub fn add_deleted_object(&mut self, object_id: i64) {
+        self.0.add_deleted_object(object_id);
+    }
+
+    #[getter]
+    pub fn get_deleted_objects(&self) -> Vec<i64> {
+        self.0.get_deleted_objects().clone()
+    }
+
+    /// Adds the object placed into the frame with its id and parent, replacing the existing
+    /// object with the same id. The placed objects are transferred with protobuf as a hidden
+    /// frame attribute.
+    ///
+    /// Parameters
+    /// ----------
+    /// object: :py:class:`savant_rs.primitives.VideoObject`
+    ///   The object to place
+    ///
+    pub fn add_replaced_object(&mut self, object: VideoObject) {
+        self.0.add_replaced_object(object.0);
+    }
+
+    #[getter]
+    pub fn get_replaced_objects(&self) -> Vec<VideoObject> {
+        self.0
+            .get_replaced_objects()
+            .iter()
+            .map(|o| VideoObject(o.clone()))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     #[getter]
     pub fn json(&self) -> PyResult<String> {
         release_gil!(true, || self
@@ -0,0 +1,80 @@
+use pyo3::{pyclass, pyfunction, pymethods, Py, PyAny};
+use savant_core::primitives::journal as rust;
+
+/// A change of a frame or object field recorded by the change journal.
+///
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct JournalRecord(pub(crate) rust::JournalRecord);
+
+#[pymethods]
+impl JournalRecord {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    /// The stage set with :py:func:`set_journal_stage` by the thread which made the change.
+    ///
+    #[getter]
+    pub fn stage(&self) -> Option<String> {
+        self.0.stage.clone()
+    }
+
+    #[getter]
+    pub fn timestamp_ns(&self) -> u64 {
+        self.0.timestamp_ns
+    }
+
+    #[getter]
+    pub fn field(&self) -> String {
+        self.0.field.clone()
+    }
+
+    /// The previous value as JSON.
+    ///
+    #[getter]
+    pub fn old_json(&self) -> String {
+        self.0.old.to_string()
+    }
+
+    /// The new value as JSON.
+    ///
+    #[getter]
+    pub fn new_json(&self) -> String {
+        self.0.new.to_string()
+    }
+
+    #[getter]
+    pub fn json(&self) -> String {
+        serde_json::to_string(&self.0).unwrap()
+    }
+}
+
+/// Sets the stage the changes made by the current thread are attributed to in the journals.
+///
+/// Parameters
+/// ----------
+/// stage : Optional[str]
+///   The stage name, ``None`` resets it.
+///
+#[pyfunction]
+#[pyo3(signature = (stage = None))]
+pub fn set_journal_stage(stage: Option<&str>) {
+    rust::set_current_stage(stage);
+}
+
+#[pyfunction]
+pub fn get_journal_stage() -> Option<String> {
+    rust::get_current_stage()
+}
+
+pub(crate) fn to_records(records: Option<Vec<rust::JournalRecord>>) -> Option<Vec<JournalRecord>> {
+    records.map(|r| r.into_iter().map(JournalRecord).collect())
+}
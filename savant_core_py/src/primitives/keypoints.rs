@@ -0,0 +1,118 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, Py, PyAny, PyResult};
+use savant_core::primitives::rust;
+
+/// A named point of an object skeleton, e.g. ``left_eye``.
+///
+#[pyclass]
+#[derive(Debug, PartialEq, Clone)]
+pub struct KeyPoint(pub(crate) rust::KeyPoint);
+
+#[pymethods]
+impl KeyPoint {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    #[new]
+    #[pyo3(signature = (name, x, y, confidence=None))]
+    pub fn new(name: &str, x: f32, y: f32, confidence: Option<f32>) -> Self {
+        Self(rust::KeyPoint::new(name, x, y, confidence))
+    }
+
+    #[getter]
+    fn get_name(&self) -> String {
+        self.0.name.clone()
+    }
+
+    #[getter]
+    fn get_x(&self) -> f32 {
+        self.0.x
+    }
+
+    #[getter]
+    fn get_y(&self) -> f32 {
+        self.0.y
+    }
+
+    #[getter]
+    fn get_confidence(&self) -> Option<f32> {
+        self.0.confidence
+    }
+}
+
+/// The keypoints of an object together with the skeleton connectivity.
+///
+/// Parameters
+/// ----------
+/// points : List[KeyPoint]
+///   The points, the names must be unique.
+/// edges : List[Tuple[int, int]]
+///   The pairs of the connected point indices.
+///
+/// Raises
+/// ------
+/// ValueError
+///   If the names are not unique or the edges reference missing points.
+///
+#[pyclass]
+#[derive(Debug, PartialEq, Clone)]
+pub struct KeyPoints(pub(crate) rust::KeyPoints);
+
+#[pymethods]
+impl KeyPoints {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    #[new]
+    #[pyo3(signature = (points, edges=vec![]))]
+    pub fn new(points: Vec<KeyPoint>, edges: Vec<(usize, usize)>) -> PyResult<Self> {
+        rust::KeyPoints::new(points.into_iter().map(|p| p.0).collect(), edges)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<KeyPoint> {
+        self.0.get(name).cloned().map(KeyPoint)
+    }
+
+    #[getter]
+    fn get_points(&self) -> Vec<KeyPoint> {
+        self.0.get_points().iter().cloned().map(KeyPoint).collect()
+    }
+
+    #[getter]
+    fn get_edges(&self) -> Vec<(usize, usize)> {
+        self.0.get_edges().to_vec()
+    }
+
+    /// The edges as the pairs of the connected points.
+    ///
+    #[getter]
+    fn get_segments(&self) -> Vec<(KeyPoint, KeyPoint)> {
+        self.0
+            .get_segments()
+            .into_iter()
+            .map(|(a, b)| (KeyPoint(a.clone()), KeyPoint(b.clone())))
+            .collect()
+    }
+}
@@ -1,5 +1,7 @@
 use crate::primitives::attribute_value::AttributeValue;
 use crate::primitives::bbox::VideoObjectBBoxTransformation;
+use crate::primitives::journal::{to_records, JournalRecord};
+use crate::primitives::keypoints::KeyPoints;
 use crate::primitives::{Attribute, RBBox};
 use crate::{release_gil, with_gil};
 use pyo3::exceptions::PyRuntimeError;
@@ -136,6 +138,16 @@ impl VideoObject {
         self.0.get_confidence()
     }
 
+    #[getter]
+    fn get_keypoints(&self) -> Option<KeyPoints> {
+        self.0.get_keypoints().map(KeyPoints)
+    }
+
+    #[setter]
+    fn set_keypoints(&mut self, keypoints: Option<KeyPoints>) {
+        self.0.set_keypoints(keypoints.map(|k| k.0));
+    }
+
     #[getter]
     fn attributes(&self) -> Vec<(String, String)> {
         self.0.get_attributes()
@@ -531,6 +543,42 @@ impl BorrowedVideoObject {
         self.0.clear_track_info()
     }
 
+    /// The skeleton keypoints of the object, transferred with protobuf as a hidden attribute.
+    ///
+    #[getter]
+    pub fn get_keypoints(&self) -> Option<KeyPoints> {
+        self.0.get_keypoints().map(KeyPoints)
+    }
+
+    #[setter]
+    pub fn set_keypoints(&mut self, keypoints: Option<KeyPoints>) {
+        self.0.set_keypoints(keypoints.map(|k| k.0));
+    }
+
+    /// Starts recording the changes made through the setters, see :py:class:`JournalRecord`.
+    ///
+    pub fn enable_journal(&mut self) {
+        self.0.enable_journal()
+    }
+
+    /// Stops recording the changes and drops the records.
+    ///
+    pub fn disable_journal(&mut self) {
+        self.0.disable_journal()
+    }
+
+    /// Returns the recorded changes, ``None`` when the journal is disabled.
+    ///
+    pub fn get_journal(&self) -> Option<Vec<JournalRecord>> {
+        to_records(self.0.get_journal())
+    }
+
+    /// Returns the recorded changes and clears the journal, which stays enabled.
+    ///
+    pub fn take_journal(&mut self) -> Option<Vec<JournalRecord>> {
+        to_records(self.0.take_journal())
+    }
+
     fn transform_geometry(&mut self, ops: Vec<VideoObjectBBoxTransformation>) {
         let inner_ops = ops.iter().map(|op| op.0).collect::<Vec<_>>();
         self.0.transform_geometry(&inner_ops);
@@ -1,7 +1,7 @@
 use crate::match_query::MatchQuery;
 use crate::primitives::object::BorrowedVideoObject;
 use crate::release_gil;
-use pyo3::exceptions::PyIndexError;
+use pyo3::exceptions::{PyIndexError, PyValueError};
 use pyo3::prelude::*;
 use savant_core::match_query::*;
 use std::collections::HashMap;
@@ -181,4 +181,88 @@ impl QueryFunctions {
             (a.into(), b.into())
         })
     }
+
+    /// Same as :py:meth:`filter`, but the objects are processed in chunks of ``chunk_size`` in
+    /// parallel.
+    ///
+    /// Parameters
+    /// ----------
+    /// v: :py:class:`VideoObjectsView`
+    ///   The objects to filter.
+    /// q: :py:class:`savant_rs.match_query.MatchQuery`
+    ///   The query.
+    /// chunk_size: int
+    ///   The number of objects processed by a task.
+    /// num_threads: Optional[int]
+    ///   The number of threads. When not set, the global pool is used, its size is the number
+    ///   of CPUs or the ``RAYON_NUM_THREADS`` environment variable. Otherwise, the pool with
+    ///   the number of threads is created on the first call and shared by the following calls.
+    /// no_gil: bool
+    ///   Whether to release the GIL.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If ``num_threads`` is zero or the pool cannot be created.
+    ///
+    #[staticmethod]
+    #[pyo3(name = "filter_parallel")]
+    #[pyo3(signature = (v, q, chunk_size = 1024, num_threads = None, no_gil = true))]
+    pub(crate) fn filter_parallel_gil(
+        v: &VideoObjectsView,
+        q: &MatchQuery,
+        chunk_size: usize,
+        num_threads: Option<usize>,
+        no_gil: bool,
+    ) -> PyResult<VideoObjectsView> {
+        release_gil!(no_gil, || {
+            let objs = v.0.iter().map(|o| o.0.clone()).collect::<Vec<_>>();
+            let res = match num_threads {
+                Some(n) => {
+                    let pool = shared_pool(n).map_err(|e| PyValueError::new_err(e.to_string()))?;
+                    filter_parallel_in(&pool, &objs, &q.0, chunk_size)
+                }
+                None => filter_parallel(&objs, &q.0, chunk_size),
+            };
+            Ok(VideoObjectsView::from(res))
+        })
+    }
+
+    /// Same as :py:meth:`partition`, but the objects are processed in chunks of ``chunk_size``
+    /// in parallel. The parameters are the same as in :py:meth:`filter_parallel`.
+    ///
+    #[staticmethod]
+    #[pyo3(name = "partition_parallel")]
+    #[pyo3(signature = (v, q, chunk_size = 1024, num_threads = None, no_gil = true))]
+    pub(crate) fn partition_parallel_gil(
+        v: &VideoObjectsView,
+        q: &MatchQuery,
+        chunk_size: usize,
+        num_threads: Option<usize>,
+        no_gil: bool,
+    ) -> PyResult<(VideoObjectsView, VideoObjectsView)> {
+        release_gil!(no_gil, || {
+            let objs = v.0.iter().map(|o| o.0.clone()).collect::<Vec<_>>();
+            let (a, b) = match num_threads {
+                Some(n) => {
+                    let pool = shared_pool(n).map_err(|e| PyValueError::new_err(e.to_string()))?;
+                    partition_parallel_in(&pool, &objs, &q.0, chunk_size)
+                }
+                None => partition_parallel(&objs, &q.0, chunk_size),
+            };
+            Ok((a.into(), b.into()))
+        })
+    }
+
+    /// Counts the objects matching the query without building the filtered view.
+    ///
+    #[staticmethod]
+    #[pyo3(name = "count")]
+    #[pyo3(signature = (v, q, no_gil = true))]
+    pub(crate) fn count_gil(v: &VideoObjectsView, q: &MatchQuery, no_gil: bool) -> usize {
+        release_gil!(no_gil, || {
+            let objs = v.0.iter().map(|o| o.0.clone()).collect::<Vec<_>>();
+            count(&objs, &q.0)
+        })
+    }
 }
@@ -1,11 +1,24 @@
+use crate::primitives::bbox::RBBox;
 use crate::primitives::point::Point;
 use crate::primitives::{Intersection, Segment};
 use crate::release_gil;
+use crate::utils::read_float_array;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use savant_core::primitives::rust;
 use std::mem;
 
+fn read_rows(array: &Bound<'_, PyAny>, columns: usize) -> PyResult<Vec<f32>> {
+    let (shape, values) = read_float_array(array)?;
+    if shape.len() != 2 || shape[1] != columns {
+        return Err(PyValueError::new_err(format!(
+            "The array must have the shape (N, {}), got {:?}",
+            columns, shape
+        )));
+    }
+    Ok(values)
+}
+
 #[pyclass]
 #[derive(Debug, PartialEq, Clone)]
 pub struct PolygonalArea(pub(crate) rust::PolygonalArea);
@@ -53,6 +66,123 @@ impl PolygonalArea {
             .map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
+    #[getter]
+    pub fn area(&self) -> f64 {
+        self.0.area()
+    }
+
+    /// The center of mass of the area, ``None`` for the degenerate areas.
+    ///
+    #[getter]
+    pub fn centroid(&self) -> Option<Point> {
+        self.0.centroid().map(Point)
+    }
+
+    /// Whether the box lies within the area entirely.
+    ///
+    pub fn contains_bbox(&self, bbox: &RBBox) -> bool {
+        self.0.contains_bbox(&bbox.0)
+    }
+
+    /// The union of the areas, the separate parts of the result are returned as separate areas.
+    /// The holes and the edge tags are not kept.
+    ///
+    pub fn union(&self, other: &PolygonalArea) -> Vec<PolygonalArea> {
+        self.0.union(&other.0).into_iter().map(Self).collect()
+    }
+
+    /// The intersection of the areas, the separate parts of the result are returned as separate
+    /// areas. The holes and the edge tags are not kept.
+    ///
+    pub fn intersection(&self, other: &PolygonalArea) -> Vec<PolygonalArea> {
+        self.0
+            .intersection(&other.0)
+            .into_iter()
+            .map(Self)
+            .collect()
+    }
+
+    /// Grows the area by the distance or shrinks it when the distance is negative.
+    ///
+    /// Parameters
+    /// ----------
+    /// distance : float
+    ///   The distance, negative to shrink the area.
+    /// segments : int
+    ///   The number of segments per full circle approximating the rounded corners.
+    ///
+    /// Returns
+    /// -------
+    /// List[PolygonalArea]
+    ///   The separate parts of the result, the holes and the edge tags are not kept.
+    ///
+    #[pyo3(signature = (distance, segments=16))]
+    pub fn buffer(&self, distance: f32, segments: usize) -> Vec<PolygonalArea> {
+        self.0
+            .buffer(distance, segments)
+            .into_iter()
+            .map(Self)
+            .collect()
+    }
+
+    /// Simplifies the area with the Ramer-Douglas-Peucker algorithm, removing the vertices
+    /// closer than ``epsilon`` to the simplified edges. The edge tags are not kept.
+    ///
+    pub fn simplify(&self, epsilon: f32) -> PolygonalArea {
+        Self(self.0.simplify(epsilon))
+    }
+
+    /// Tests the points against the area in parallel.
+    ///
+    /// Parameters
+    /// ----------
+    /// points : numpy.ndarray
+    ///   The float32 or float64 array of the shape ``(N, 2)`` with the point coordinates.
+    /// no_gil : bool
+    ///   Whether to release the GIL while testing.
+    ///
+    /// Returns
+    /// -------
+    /// List[bool]
+    ///   Whether every point is inside the area.
+    ///
+    #[pyo3(signature = (points, no_gil=true))]
+    pub fn contains_many(&self, points: &Bound<'_, PyAny>, no_gil: bool) -> PyResult<Vec<bool>> {
+        let points = read_rows(points, 2)?
+            .chunks_exact(2)
+            .map(|c| rust::Point::new(c[0], c[1]))
+            .collect::<Vec<_>>();
+        Ok(release_gil!(no_gil, || self.0.contains_many(&points)))
+    }
+
+    /// Tests the segments against the area in parallel.
+    ///
+    /// Parameters
+    /// ----------
+    /// segments : numpy.ndarray
+    ///   The float32 or float64 array of the shape ``(N, 4)``, every row is ``x1, y1, x2, y2``.
+    /// no_gil : bool
+    ///   Whether to release the GIL while testing.
+    ///
+    /// Returns
+    /// -------
+    /// List[Intersection]
+    ///   The intersection of every segment with the area.
+    ///
+    #[pyo3(signature = (segments, no_gil=true))]
+    pub fn crossed_by_many(
+        &self,
+        segments: &Bound<'_, PyAny>,
+        no_gil: bool,
+    ) -> PyResult<Vec<Intersection>> {
+        let segments = read_rows(segments, 4)?
+            .chunks_exact(4)
+            .map(|c| rust::Segment::new(rust::Point::new(c[0], c[1]), rust::Point::new(c[2], c[3])))
+            .collect::<Vec<_>>();
+        let intersections = release_gil!(no_gil, || self.0.crossed_by_many(&segments));
+        Ok(intersections.into_iter().map(Intersection).collect())
+    }
+
     #[staticmethod]
     #[pyo3(name = "points_positions")]
     #[pyo3(signature = (polys, points, no_gil=false))]
@@ -0,0 +1,54 @@
+use pyo3::{pyclass, pymethods};
+use savant_core::primitives::redaction as rust;
+
+/// Defines how the attributes are redacted by :py:meth:`VideoFrame.redact` and
+/// :py:meth:`VideoFrameBatch.redact`.
+///
+/// Parameters
+/// ----------
+/// remove : List[Tuple[str, Optional[str]]]
+///   The ``(namespace, name)`` selectors of the attributes removed from the selected objects,
+///   ``None`` name selects the whole namespace.
+/// mask : List[Tuple[str, Optional[str]]]
+///   The selectors of the attributes whose values are dropped and hint is set to ``redacted``.
+/// strip_hidden : bool
+///   Whether the hidden attributes of the frame and all its objects are removed.
+/// include_frame_attributes : bool
+///   Whether ``remove`` and ``mask`` apply to the frame attributes too.
+///
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct RedactionSpec(pub(crate) rust::RedactionSpec);
+
+#[pymethods]
+impl RedactionSpec {
+    #[new]
+    #[pyo3(signature = (remove=vec![], mask=vec![], strip_hidden=false, include_frame_attributes=false))]
+    pub fn new(
+        remove: Vec<(String, Option<String>)>,
+        mask: Vec<(String, Option<String>)>,
+        strip_hidden: bool,
+        include_frame_attributes: bool,
+    ) -> Self {
+        let spec = remove
+            .iter()
+            .fold(rust::RedactionSpec::new(), |spec, (ns, name)| {
+                spec.remove(ns, name.as_deref())
+            });
+        let spec = mask
+            .iter()
+            .fold(spec, |spec, (ns, name)| spec.mask(ns, name.as_deref()));
+        Self(
+            spec.strip_hidden(strip_hidden)
+                .include_frame_attributes(include_frame_attributes),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", &self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
@@ -0,0 +1,157 @@
+use crate::draw_spec::{ColorDraw, DrawSpecRegistry, ObjectDraw};
+use crate::primitives::bbox::RBBox;
+use crate::primitives::frame::VideoFrame;
+use crate::primitives::polygonal_area::PolygonalArea;
+use crate::{release_gil, with_gil};
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use savant_core::rendering as rust;
+
+/// The writable HxWxC uint8 frame buffer (e.g. a numpy array) with 3 or 4 channels.
+///
+struct FrameBuffer {
+    buffer: PyBuffer<u8>,
+    width: u32,
+    height: u32,
+    channels: u32,
+}
+
+impl FrameBuffer {
+    fn new(array: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let buffer = PyBuffer::<u8>::get(array).map_err(|_| {
+            PyValueError::new_err("The frame must support the buffer protocol and hold uint8")
+        })?;
+        if buffer.readonly() {
+            return Err(PyValueError::new_err("The frame must be writable"));
+        }
+        if !buffer.is_c_contiguous() {
+            return Err(PyValueError::new_err("The frame must be C-contiguous"));
+        }
+        let [height, width, channels] = buffer.shape() else {
+            return Err(PyValueError::new_err("The frame must have the shape HxWxC"));
+        };
+        Ok(Self {
+            width: *width as u32,
+            height: *height as u32,
+            channels: *channels as u32,
+            buffer,
+        })
+    }
+
+    fn draw<F>(&self, no_gil: bool, f: F) -> PyResult<()>
+    where
+        F: FnOnce(&mut rust::Canvas) -> anyhow::Result<()> + Send,
+    {
+        // the buffer is checked to be writable and contiguous, it is kept alive by `self`
+        let data = unsafe {
+            std::slice::from_raw_parts_mut(
+                self.buffer.buf_ptr() as *mut u8,
+                self.buffer.len_bytes(),
+            )
+        };
+        let (width, height, channels) = (self.width, self.height, self.channels);
+        release_gil!(no_gil, || {
+            let mut canvas = rust::Canvas::new(data, width, height, channels)?;
+            f(&mut canvas)?;
+            canvas.finish();
+            Ok::<(), anyhow::Error>(())
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+/// Draws objects, labels and polygons onto RGB or RGBA frames according to the draw
+/// specifications. The frames are HxWxC uint8 arrays modified in place.
+///
+/// Labels are rendered with the TrueType or OpenType font loaded from ``font_path``, drawing a
+/// label without the font raises ValueError.
+///
+/// .. code-block:: python
+///
+///   import numpy as np
+///   from savant_rs.draw_spec import DrawSpecRegistry, ObjectDraw, Renderer
+///   registry = DrawSpecRegistry(default=ObjectDraw(blur=True))
+///   image = np.zeros((720, 1280, 3), dtype=np.uint8)
+///   Renderer().render_frame(image, frame, registry)
+///
+#[pyclass]
+#[derive(Clone)]
+pub struct Renderer(rust::Renderer);
+
+#[pymethods]
+impl Renderer {
+    #[new]
+    #[pyo3(signature = (font_path = None))]
+    fn new(font_path: Option<&str>) -> PyResult<Self> {
+        let font = font_path
+            .map(std::fs::read)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("Unable to read the font: {}", e)))?;
+        rust::Renderer::new(font)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[getter]
+    fn has_font(&self) -> bool {
+        self.0.has_font()
+    }
+
+    /// Draws the objects of the frame having specifications in the registry.
+    ///
+    #[pyo3(signature = (image, frame, registry, no_gil = true))]
+    fn render_frame(
+        &self,
+        image: &Bound<'_, PyAny>,
+        frame: &VideoFrame,
+        registry: &DrawSpecRegistry,
+        no_gil: bool,
+    ) -> PyResult<()> {
+        let buffer = FrameBuffer::new(image)?;
+        buffer.draw(no_gil, |canvas| {
+            self.0.draw_frame(canvas, &frame.0, &registry.0)
+        })
+    }
+
+    /// Draws the box with the specification and the label lines given.
+    ///
+    #[pyo3(signature = (image, bbox, spec, label = vec![], no_gil = true))]
+    fn render_object(
+        &self,
+        image: &Bound<'_, PyAny>,
+        bbox: &RBBox,
+        spec: &ObjectDraw,
+        label: Vec<String>,
+        no_gil: bool,
+    ) -> PyResult<()> {
+        let buffer = FrameBuffer::new(image)?;
+        buffer.draw(no_gil, |canvas| {
+            self.0.draw_object(canvas, &bbox.0, &spec.0, &label)
+        })
+    }
+
+    /// Draws the polygon border and, optionally, its fill.
+    ///
+    #[pyo3(signature = (image, area, color, thickness = 2, fill = None, no_gil = true))]
+    fn render_polygon(
+        &self,
+        image: &Bound<'_, PyAny>,
+        area: &PolygonalArea,
+        color: &ColorDraw,
+        thickness: i64,
+        fill: Option<ColorDraw>,
+        no_gil: bool,
+    ) -> PyResult<()> {
+        let buffer = FrameBuffer::new(image)?;
+        buffer.draw(no_gil, |canvas| {
+            self.0.draw_polygon(
+                canvas,
+                &area.0,
+                &color.0,
+                thickness,
+                fill.as_ref().map(|f| &f.0),
+            )
+        })
+    }
+}
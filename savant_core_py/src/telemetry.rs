@@ -1,4 +1,5 @@
-use pyo3::{pyclass, pyfunction, pymethods};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::{pyclass, pyfunction, pymethods, PyResult};
 use savant_core::telemetry;
 use std::time::Duration;
 
@@ -45,6 +46,31 @@ impl ClientTlsConfig {
     }
 }
 
+/// The head-based sampling configuration.
+///
+/// Params
+/// ------
+/// ratio: float
+///   The ratio of the sampled root traces, from 0.0 to 1.0.
+/// parent_based: bool
+///   When True, the spans with a parent follow the sampling decision of the parent.
+///
+#[pyclass]
+#[derive(Clone)]
+pub struct SamplerConfiguration(telemetry::SamplerConfiguration);
+
+#[pymethods]
+impl SamplerConfiguration {
+    #[new]
+    #[pyo3(signature = (ratio=1.0, parent_based=true))]
+    pub fn new(ratio: f64, parent_based: bool) -> Self {
+        Self(telemetry::SamplerConfiguration {
+            ratio,
+            parent_based,
+        })
+    }
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub struct TracerConfiguration(telemetry::TracerConfiguration);
@@ -52,13 +78,14 @@ pub struct TracerConfiguration(telemetry::TracerConfiguration);
 #[pymethods]
 impl TracerConfiguration {
     #[new]
-    #[pyo3(signature = (service_name, protocol, endpoint, tls=None, timeout=None))]
+    #[pyo3(signature = (service_name, protocol, endpoint, tls=None, timeout=None, sampler=None))]
     pub fn new(
         service_name: String,
         protocol: Protocol,
         endpoint: String,
         tls: Option<ClientTlsConfig>,
         timeout: Option<u64>,
+        sampler: Option<SamplerConfiguration>,
     ) -> Self {
         Self(telemetry::TracerConfiguration {
             service_name,
@@ -66,6 +93,49 @@ impl TracerConfiguration {
             endpoint,
             tls: tls.map(|e| e.0),
             timeout: timeout.map(Duration::from_millis),
+            sampler: sampler.map(|e| e.0),
+        })
+    }
+}
+
+/// The export of the log records over OTLP. The records written with
+/// :py:func:`savant_rs.logging.log` carry the trace and the span ids of the
+/// current context.
+///
+/// Params
+/// ------
+/// service_name: str
+///   The name of the service.
+/// protocol: :py:class:`Protocol`
+///   The protocol of the exporter.
+/// endpoint: str
+///   The endpoint of the collector.
+/// tls: Optional[:py:class:`ClientTlsConfig`]
+///   The TLS configuration.
+/// timeout: Optional[int]
+///   The export timeout in milliseconds.
+///
+#[pyclass]
+#[derive(Clone)]
+pub struct LoggerConfiguration(telemetry::LoggerConfiguration);
+
+#[pymethods]
+impl LoggerConfiguration {
+    #[new]
+    #[pyo3(signature = (service_name, protocol, endpoint, tls=None, timeout=None))]
+    pub fn new(
+        service_name: String,
+        protocol: Protocol,
+        endpoint: String,
+        tls: Option<ClientTlsConfig>,
+        timeout: Option<u64>,
+    ) -> Self {
+        Self(telemetry::LoggerConfiguration {
+            service_name,
+            protocol: protocol.into(),
+            endpoint,
+            tls: tls.map(|e| e.0),
+            timeout: timeout.map(Duration::from_millis),
         })
     }
 }
@@ -76,14 +146,16 @@ pub struct TelemetryConfiguration(telemetry::TelemetryConfiguration);
 #[pymethods]
 impl TelemetryConfiguration {
     #[new]
-    #[pyo3(signature = (context_propagation_format=None, tracer=None))]
+    #[pyo3(signature = (context_propagation_format=None, tracer=None, logger=None))]
     pub fn new(
         context_propagation_format: Option<ContextPropagationFormat>,
         tracer: Option<TracerConfiguration>,
+        logger: Option<LoggerConfiguration>,
     ) -> Self {
         Self(telemetry::TelemetryConfiguration {
             context_propagation_format: context_propagation_format.map(|e| e.into()),
             tracer: tracer.map(|e| e.0),
+            logger: logger.map(|e| e.0),
         })
     }
 
@@ -119,9 +191,33 @@ impl From<Protocol> for telemetry::Protocol {
 /// config: :py:class:`TelemetryConfiguration`
 ///   The configuration for OpenTelemetry
 ///
+/// Raises
+/// ------
+/// RuntimeError
+///   If OpenTelemetry is already initialized or the exporters cannot be created, e.g. the TLS
+///   files cannot be loaded
+///
 #[pyfunction]
-pub fn init(config: &TelemetryConfiguration) {
-    telemetry::init(&config.0);
+pub fn init(config: &TelemetryConfiguration) -> PyResult<()> {
+    telemetry::init(&config.0).map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Replaces the OpenTelemetry configuration at runtime, e.g. to change the
+/// endpoint or the sampling ratio, or initializes it when it is not initialized.
+///
+/// Params
+/// ------
+/// config: :py:class:`TelemetryConfiguration`
+///   The new configuration for OpenTelemetry
+///
+/// Raises
+/// ------
+/// RuntimeError
+///   If the new configuration fails, the current one stays in effect
+///
+#[pyfunction]
+pub fn reconfigure(config: &TelemetryConfiguration) -> PyResult<()> {
+    telemetry::reconfigure(&config.0).map_err(|e| PyRuntimeError::new_err(e.to_string()))
 }
 
 /// Shuts down OpenTelemetry.
@@ -129,3 +225,14 @@ pub fn init(config: &TelemetryConfiguration) {
 pub fn shutdown() {
     telemetry::shutdown()
 }
+
+/// Installs `inner` as the global `log` logger which also exports the records over OTLP
+/// when the logger is configured.
+///
+pub fn install_log_bridge<L: log::Log + 'static>(
+    inner: L,
+    max_level: log::LevelFilter,
+) -> PyResult<()> {
+    telemetry::install_log_bridge(inner, max_level)
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
@@ -1,7 +1,9 @@
 pub mod utils {
     use crate::primitives::frame::VideoFrame;
     use crate::primitives::object::VideoObject;
-    use pyo3::pyfunction;
+    use pyo3::exceptions::PyValueError;
+    use pyo3::{pyclass, pyfunction, pymethods, PyResult};
+    use savant_core::test::fixture::{FrameFixtureGenerator as RustGenerator, LabelSpec};
 
     #[pyfunction]
     pub fn gen_empty_frame() -> VideoFrame {
@@ -17,6 +19,88 @@ pub mod utils {
         VideoObject(savant_core::test::gen_object(id))
     }
 
+    /// Generates reproducible frames for tests and benchmarks: the same seed and settings
+    /// produce the same objects and tracks.
+    ///
+    /// Parameters
+    /// ----------
+    /// seed : int
+    ///   The seed of the random generator.
+    /// source_id : str
+    ///   The source id of the frames.
+    /// width : int
+    ///   The width of the frames.
+    /// height : int
+    ///   The height of the frames.
+    /// fps : int
+    ///   The frame rate, defines the pts of the frames.
+    /// min_objects : int
+    ///   The minimum number of objects in a frame.
+    /// max_objects : int
+    ///   The maximum number of objects in a frame.
+    /// labels : Optional[List[Tuple[str, str, int]]]
+    ///   The ``(namespace, label, weight)`` distribution of the object labels.
+    /// track_death_probability : Optional[float]
+    ///   When set, the objects are tracked across the frames and every frame a track dies with
+    ///   the probability.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the resolution or the fps is not positive, the object count range is empty, no
+    ///   label has a positive weight or the probability is not within ``[0, 1]``.
+    ///
+    #[pyclass]
+    pub struct FrameFixtureGenerator(RustGenerator);
+
+    #[pymethods]
+    impl FrameFixtureGenerator {
+        #[new]
+        #[allow(clippy::too_many_arguments)]
+        #[pyo3(signature = (seed, source_id="test", width=1280, height=720, fps=30, min_objects=0, max_objects=10, labels=None, track_death_probability=None))]
+        fn new(
+            seed: u64,
+            source_id: &str,
+            width: i64,
+            height: i64,
+            fps: i64,
+            min_objects: usize,
+            max_objects: usize,
+            labels: Option<Vec<(String, String, u32)>>,
+            track_death_probability: Option<f64>,
+        ) -> PyResult<Self> {
+            let to_py_err = |e: anyhow::Error| PyValueError::new_err(e.to_string());
+            let mut generator = RustGenerator::new(seed)
+                .source_id(source_id)
+                .resolution(width, height)
+                .and_then(|g| g.fps(fps))
+                .and_then(|g| g.objects_per_frame(min_objects, max_objects))
+                .map_err(to_py_err)?;
+            if let Some(labels) = labels {
+                generator = generator
+                    .labels(
+                        labels
+                            .iter()
+                            .map(|(ns, label, weight)| LabelSpec::new(ns, label, *weight))
+                            .collect(),
+                    )
+                    .map_err(to_py_err)?;
+            }
+            if let Some(p) = track_death_probability {
+                generator = generator.simulate_tracks(p).map_err(to_py_err)?;
+            }
+            Ok(Self(generator))
+        }
+
+        fn next_frame(&mut self) -> VideoFrame {
+            VideoFrame(self.0.next_frame())
+        }
+
+        fn frames(&mut self, n: usize) -> Vec<VideoFrame> {
+            self.0.frames(n).into_iter().map(VideoFrame).collect()
+        }
+    }
+
     #[inline(always)]
     pub fn s(a: &str) -> String {
         a.to_string()
@@ -1,4 +1,5 @@
 use evalexpr::Value;
+use pyo3::buffer::PyBuffer;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
@@ -11,6 +12,23 @@ pub mod otlp;
 pub mod python;
 pub mod symbol_mapper;
 
+/// Reads a float32 or float64 array supporting the buffer protocol (e.g. a numpy array),
+/// returns its shape and its values in the row-major order.
+///
+pub(crate) fn read_float_array(array: &Bound<'_, PyAny>) -> PyResult<(Vec<usize>, Vec<f32>)> {
+    if let Ok(buffer) = PyBuffer::<f32>::get(array) {
+        return Ok((buffer.shape().to_vec(), buffer.to_vec(array.py())?));
+    }
+    let buffer = PyBuffer::<f64>::get(array).map_err(|_| {
+        PyValueError::new_err("The array must support the buffer protocol and hold floats")
+    })?;
+    let values = buffer.to_vec(array.py())?;
+    Ok((
+        buffer.shape().to_vec(),
+        values.into_iter().map(|v| v as f32).collect(),
+    ))
+}
+
 #[pyfunction]
 #[inline]
 pub fn round_2_digits(v: f32) -> f32 {
@@ -1,6 +1,9 @@
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
+use savant_core::eval_resolvers::FileResolverConfig;
+use savant_core::layered_config::{self, ConfigLayer, EnvSource, FileSource};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Returns the system name of utility resolver.
 ///
@@ -50,6 +53,42 @@ pub fn etcd_resolver_name() -> &'static str {
     savant_core::eval_resolvers::etcd_resolver_name()
 }
 
+/// Returns the system name of redis resolver.
+///
+/// Returns
+/// -------
+/// str
+///   The name of the redis resolver.
+///
+#[pyfunction]
+pub fn redis_resolver_name() -> &'static str {
+    savant_core::eval_resolvers::redis_resolver_name()
+}
+
+/// Returns the system name of http resolver.
+///
+/// Returns
+/// -------
+/// str
+///   The name of the http resolver.
+///
+#[pyfunction]
+pub fn http_resolver_name() -> &'static str {
+    savant_core::eval_resolvers::http_resolver_name()
+}
+
+/// Returns the system name of file resolver.
+///
+/// Returns
+/// -------
+/// str
+///   The name of the file resolver.
+///
+#[pyfunction]
+pub fn file_resolver_name() -> &'static str {
+    savant_core::eval_resolvers::file_resolver_name()
+}
+
 #[derive(Clone)]
 #[pyclass]
 pub struct EtcdCredentials(savant_core::eval_resolvers::EtcdCredentials);
@@ -62,6 +101,20 @@ impl EtcdCredentials {
     }
 }
 
+/// The TLS configuration of the Etcd connection.
+///
+/// Parameters
+/// ----------
+/// ca_cert: Optional[str]
+///   The PEM CA certificate verifying the server.
+///   Default is None.
+/// client_cert: Optional[str]
+///   The PEM client certificate for mTLS, requires ``client_key``.
+///   Default is None.
+/// client_key: Optional[str]
+///   The PEM client key for mTLS, requires ``client_cert``.
+///   Default is None.
+///
 #[derive(Clone)]
 #[pyclass]
 pub struct TlsConfig(savant_core::eval_resolvers::TlsConfig);
@@ -69,12 +122,22 @@ pub struct TlsConfig(savant_core::eval_resolvers::TlsConfig);
 #[pymethods]
 impl TlsConfig {
     #[new]
-    fn new(ca_cert: String, client_cert: String, client_key: String) -> Self {
-        TlsConfig(savant_core::eval_resolvers::TlsConfig {
+    #[pyo3(signature = (ca_cert = None, client_cert = None, client_key = None))]
+    fn new(
+        ca_cert: Option<String>,
+        client_cert: Option<String>,
+        client_key: Option<String>,
+    ) -> PyResult<Self> {
+        if client_cert.is_some() != client_key.is_some() {
+            return Err(PyValueError::new_err(
+                "client_cert and client_key must be set together",
+            ));
+        }
+        Ok(TlsConfig(savant_core::eval_resolvers::TlsConfig {
             ca_cert,
             client_cert,
             client_key,
-        })
+        }))
     }
 }
 
@@ -125,6 +188,130 @@ pub fn register_etcd_resolver(
     .map_err(|e| PyRuntimeError::new_err(e.to_string()))
 }
 
+/// Registers the Redis resolver in the system runtime. The resolver exports
+/// ``redis(key, default)`` which reads ``prefix:key`` from Redis.
+///
+/// Parameters
+/// ----------
+/// url: str
+///   The Redis connection url.
+///   Default is "redis://127.0.0.1:6379/0".
+/// prefix: str
+///   The prefix prepended to the keys, empty string disables the prefix.
+///   Default is "savant".
+/// pool_size: int
+///   The maximum number of pooled connections.
+///   Default is 4.
+/// connect_timeout: int
+///   The timeout to connect to the Redis server. In seconds.
+///   Default is 5 seconds.
+/// cache_ttl: int
+///   How long the resolved values are cached locally. In milliseconds, 0 disables caching.
+///   Default is 1000 ms.
+/// cache_size: int
+///   The maximum number of locally cached keys.
+///   Default is 1024.
+///
+#[pyfunction]
+#[pyo3(signature = (url = "redis://127.0.0.1:6379/0", prefix = "savant", pool_size = 4, connect_timeout = 5, cache_ttl = 1000, cache_size = 1024))]
+pub fn register_redis_resolver(
+    url: &str,
+    prefix: &str,
+    pool_size: u32,
+    connect_timeout: u64,
+    cache_ttl: u64,
+    cache_size: usize,
+) -> PyResult<()> {
+    savant_core::eval_resolvers::register_redis_resolver(
+        &savant_core::eval_resolvers::RedisResolverConfig {
+            url: url.to_string(),
+            prefix: prefix.to_string(),
+            pool_size,
+            connect_timeout,
+            cache_ttl,
+            cache_size,
+        },
+    )
+    .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Registers the HTTP resolver in the system runtime. The resolver exports
+/// ``http(url_template, json_pointer[, default])`` which fetches a JSON document
+/// and returns the value addressed by the JSON pointer.
+///
+/// Parameters
+/// ----------
+/// timeout: int
+///   The request timeout. In milliseconds.
+///   Default is 5000 ms.
+/// cache_ttl: int
+///   How long the fetched documents are cached locally. In milliseconds, 0 disables caching.
+///   Default is 1000 ms.
+/// cache_size: int
+///   The maximum number of locally cached documents.
+///   Default is 1024.
+/// variables: Optional[Dict[str, str]]
+///   The values substituted for ``{name}`` placeholders in url templates.
+///   Default is None.
+/// headers: Optional[Dict[str, str]]
+///   The headers sent with every request.
+///   Default is None.
+///
+#[pyfunction]
+#[pyo3(signature = (timeout = 5000, cache_ttl = 1000, cache_size = 1024, variables = None, headers = None))]
+pub fn register_http_resolver(
+    timeout: u64,
+    cache_ttl: u64,
+    cache_size: usize,
+    variables: Option<HashMap<String, String>>,
+    headers: Option<HashMap<String, String>>,
+) -> PyResult<()> {
+    savant_core::eval_resolvers::register_http_resolver(
+        &savant_core::eval_resolvers::HttpResolverConfig {
+            timeout,
+            cache_ttl,
+            cache_size,
+            variables: variables.unwrap_or_default().into_iter().collect(),
+            headers: headers.unwrap_or_default().into_iter().collect(),
+        },
+    )
+    .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Registers the File resolver in the system runtime. The resolver exports
+/// ``file(key, default)`` which reads values from a JSON or YAML file. The key
+/// is either a JSON pointer (``/a/b``) or a dotted path (``a.b``). The file is
+/// reloaded automatically when its modification time changes.
+///
+/// Parameters
+/// ----------
+/// path: str
+///   The path to the JSON or YAML file.
+/// check_interval: int
+///   How often the modification time is checked. In milliseconds, 0 checks on every call.
+///   Default is 1000 ms.
+///
+#[pyfunction]
+#[pyo3(signature = (path, check_interval = 1000))]
+pub fn register_file_resolver(path: String, check_interval: u64) -> PyResult<()> {
+    savant_core::eval_resolvers::register_file_resolver(
+        &savant_core::eval_resolvers::FileResolverConfig {
+            path,
+            check_interval,
+        },
+    )
+    .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Forces the File resolver to reload the file, e.g. from a SIGHUP handler.
+/// Does nothing when the resolver is not registered.
+///
+#[pyfunction]
+pub fn reload_file_resolver() -> PyResult<()> {
+    savant_core::eval_resolvers::reload_file_resolver()
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
 /// Registers the Env resolver in the system runtime.
 ///
 #[pyfunction]
@@ -165,6 +352,139 @@ pub fn update_config_resolver(symbols: HashMap<String, String>) {
     savant_core::eval_resolvers::update_config_resolver(symbols);
 }
 
+/// The configuration resolved through the stack of layers: defaults < file < env.
+/// The value of a key comes from the highest layer defining it.
+///
+/// Parameters
+/// ----------
+/// defaults: Optional[Dict[str, str]]
+///   The default values.
+///   Default is None.
+/// file: Optional[str]
+///   The JSON or YAML file, reloaded when modified.
+///   Default is None.
+/// file_check_interval: int
+///   How often the file modification time is checked. In milliseconds.
+///   Default is 1000 ms.
+/// env_prefix: Optional[str]
+///   The prefix of the environment variables, ``detector.threshold`` is read
+///   from ``{PREFIX}_DETECTOR_THRESHOLD``. None disables the env layer.
+///   Default is None.
+///
+#[pyclass]
+#[derive(Clone)]
+pub struct LayeredConfig(Arc<layered_config::LayeredConfig>);
+
+#[pymethods]
+impl LayeredConfig {
+    #[new]
+    #[pyo3(signature = (defaults = None, file = None, file_check_interval = 1000, env_prefix = None))]
+    fn new(
+        defaults: Option<HashMap<String, String>>,
+        file: Option<String>,
+        file_check_interval: u64,
+        env_prefix: Option<String>,
+    ) -> PyResult<Self> {
+        let config =
+            layered_config::LayeredConfig::new(defaults.unwrap_or_default().into_iter().collect());
+        if let Some(path) = file {
+            let source = FileSource::new(&FileResolverConfig {
+                path,
+                check_interval: file_check_interval,
+            })
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            config.add_source(ConfigLayer::File, Box::new(source));
+        }
+        if let Some(prefix) = env_prefix {
+            config.add_source(ConfigLayer::Env, Box::new(EnvSource::new(&prefix)));
+        }
+        Ok(Self(Arc::new(config)))
+    }
+
+    fn set_default(&self, key: &str, value: &str) {
+        self.0.set_default(key, value);
+    }
+
+    /// Returns the value as a string, None when no layer defines it.
+    ///
+    fn get(&self, key: &str) -> Option<String> {
+        self.0.get_string(key)
+    }
+
+    /// Returns the name of the layer defining the key: defaults, file or env.
+    ///
+    fn layer(&self, key: &str) -> Option<String> {
+        self.0
+            .resolve(key)
+            .map(|(layer, _)| format!("{:?}", layer).to_lowercase())
+    }
+
+    fn get_int(&self, key: &str) -> PyResult<Option<i64>> {
+        self.0
+            .get(key)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn get_float(&self, key: &str) -> PyResult<Option<f64>> {
+        self.0
+            .get(key)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn get_bool(&self, key: &str) -> PyResult<Option<bool>> {
+        self.0
+            .get(key)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Calls ``callback(key, value)`` from :py:meth:`refresh` when the value
+    /// of the key changes.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///   The id of the watch.
+    ///
+    fn watch(&self, key: &str, callback: PyObject) -> u64 {
+        self.0.watch(key, move |key, value| {
+            Python::with_gil(|py| {
+                if let Err(e) = callback.call1(py, (key, value)) {
+                    e.print(py);
+                }
+            })
+        })
+    }
+
+    fn unwatch(&self, id: u64) -> bool {
+        self.0.unwatch(id)
+    }
+
+    /// Resolves the watched keys again and calls the callbacks of the changed ones.
+    ///
+    /// Returns
+    /// -------
+    /// int
+    ///   The number of the called callbacks.
+    ///
+    fn refresh(&self, py: Python) -> usize {
+        py.allow_threads(|| self.0.refresh())
+    }
+}
+
+/// Registers the Config resolver reading ``config(key, default)`` from the
+/// layered configuration first, then from the symbols set by
+/// :py:func:`update_config_resolver`.
+///
+/// Parameters
+/// ----------
+/// config: LayeredConfig
+///   The layered configuration.
+///
+#[pyfunction]
+pub fn register_layered_config_resolver(config: &LayeredConfig) {
+    savant_core::eval_resolvers::register_layered_config_resolver(config.0.clone());
+}
+
 /// Unregisters the resolver from the system runtime.
 ///
 /// Parameters
@@ -176,3 +496,50 @@ pub fn update_config_resolver(symbols: HashMap<String, String>) {
 pub fn unregister_resolver(name: &str) {
     savant_core::eval_resolvers::unregister_resolver(name);
 }
+
+/// Configures the result cache shared by all resolvers.
+///
+/// Parameters
+/// ----------
+/// max_entries: int
+///   The maximum number of cached results.
+/// default_ttl: int
+///   The TTL for the symbols without an explicit TTL. In milliseconds, 0 disables caching.
+///
+#[pyfunction]
+#[pyo3(signature = (max_entries = 4096, default_ttl = 0))]
+pub fn configure_resolver_cache(max_entries: usize, default_ttl: u64) {
+    savant_core::eval_cache::configure_resolver_cache(max_entries, default_ttl);
+}
+
+/// Sets the cache TTL for the resolver symbol (e.g. ``etcd``).
+///
+/// Parameters
+/// ----------
+/// symbol: str
+///   The resolver function name.
+/// ttl: int
+///   The TTL. In milliseconds, 0 disables caching for the symbol.
+///
+#[pyfunction]
+pub fn set_resolver_cache_ttl(symbol: &str, ttl: u64) {
+    savant_core::eval_cache::set_resolver_cache_ttl(symbol, ttl);
+}
+
+/// Removes the cached resolver results whose keys start with the prefix.
+/// The keys have the form ``symbol:key``.
+///
+/// Parameters
+/// ----------
+/// prefix: str
+///   The key prefix, e.g. ``etcd:`` or ``etcd:thresholds/``.
+///
+/// Returns
+/// -------
+/// int
+///   The number of removed entries.
+///
+#[pyfunction]
+pub fn invalidate_resolver_cache(prefix: &str) -> usize {
+    savant_core::eval_cache::invalidate_resolver_cache(prefix)
+}
@@ -1,7 +1,9 @@
-use pyo3::{pyclass, pymethods, Py, PyAny};
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, Py, PyAny, PyResult};
 use savant_core::transport::zeromq;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::path::Path;
 
 /// Represents a socket type for a writer socket.
 ///
@@ -77,6 +79,100 @@ impl ReaderSocketType {
     }
 }
 
+/// Defines how a reader validates received frames.
+///
+/// * ``Disabled`` - frames are not validated;
+/// * ``Lenient`` - fixable violations (e.g. boxes out of the frame) are fixed, all violations
+///   are logged and stored in the ``savant/validation_violations`` temporary frame attribute;
+/// * ``Strict`` - messages with violations are returned as :py:class:`ReaderResultInvalid`.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq)]
+pub enum ValidationMode {
+    Disabled,
+    Lenient,
+    Strict,
+}
+
+#[pymethods]
+impl ValidationMode {
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+impl From<ValidationMode> for savant_core::message::validation::ValidationMode {
+    fn from(mode: ValidationMode) -> Self {
+        match mode {
+            ValidationMode::Disabled => Self::Disabled,
+            ValidationMode::Lenient => Self::Lenient,
+            ValidationMode::Strict => Self::Strict,
+        }
+    }
+}
+
+/// Defines the compression of the messages sent by a writer. Readers detect the codec
+/// automatically, so only the writer needs to be configured.
+///
+/// * ``Disabled`` - messages are sent as is;
+/// * ``Lz4`` - fast compression with a moderate ratio;
+/// * ``Zstd`` - better ratio for slower links.
+///
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq)]
+pub enum CompressionCodec {
+    Disabled,
+    Lz4,
+    Zstd,
+}
+
+#[pymethods]
+impl CompressionCodec {
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+impl From<CompressionCodec> for zeromq::CompressionCodec {
+    fn from(codec: CompressionCodec) -> Self {
+        match codec {
+            CompressionCodec::Disabled => Self::None,
+            CompressionCodec::Lz4 => Self::Lz4,
+            CompressionCodec::Zstd => Self::Zstd,
+        }
+    }
+}
+
+impl From<zeromq::CompressionCodec> for CompressionCodec {
+    fn from(codec: zeromq::CompressionCodec) -> Self {
+        match codec {
+            zeromq::CompressionCodec::None => Self::Disabled,
+            zeromq::CompressionCodec::Lz4 => Self::Lz4,
+            zeromq::CompressionCodec::Zstd => Self::Zstd,
+        }
+    }
+}
+
 impl From<zeromq::ReaderSocketType> for ReaderSocketType {
     fn from(socket_type: zeromq::ReaderSocketType) -> Self {
         match socket_type {
@@ -148,3 +244,283 @@ impl TopicPrefixSpec {
         Self(zeromq::TopicPrefixSpec::None)
     }
 }
+
+/// The allowlist and the denylist of the source ids (topics) evaluated by a reader before the
+/// messages are deserialized. A message is dropped when its source id matches any denied pattern,
+/// or when the allowlist is not empty and the source id matches none of the allowed patterns.
+/// The dropped messages are returned as :py:class:`ReaderResultPrefixMismatch`.
+///
+#[pyclass]
+#[derive(Debug, Clone, Default)]
+pub struct SourceFilter(pub(crate) zeromq::SourceFilter);
+
+impl SourceFilter {
+    fn add(&mut self, pattern: anyhow::Result<zeromq::SourcePattern>, allow: bool) -> PyResult<()> {
+        let pattern = pattern
+            .map_err(|e| PyValueError::new_err(format!("Invalid source pattern: {:?}", e)))?;
+        let filter = std::mem::take(&mut self.0);
+        self.0 = if allow {
+            filter.allow(pattern)
+        } else {
+            filter.deny(pattern)
+        };
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl SourceFilter {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    /// Allows the source ids matching the glob pattern
+    ///
+    /// Parameters
+    /// ----------
+    /// pattern: str
+    ///   The glob pattern, e.g. ``tenant-a/*``
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the pattern is invalid
+    ///
+    pub fn allow_glob(&mut self, pattern: &str) -> PyResult<()> {
+        self.add(zeromq::SourcePattern::glob(pattern), true)
+    }
+
+    /// Allows the source ids fully matching the regular expression
+    ///
+    /// Parameters
+    /// ----------
+    /// pattern: str
+    ///   The regular expression
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the pattern is invalid
+    ///
+    pub fn allow_regex(&mut self, pattern: &str) -> PyResult<()> {
+        self.add(zeromq::SourcePattern::regex(pattern), true)
+    }
+
+    /// Denies the source ids matching the glob pattern
+    ///
+    /// Parameters
+    /// ----------
+    /// pattern: str
+    ///   The glob pattern
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the pattern is invalid
+    ///
+    pub fn deny_glob(&mut self, pattern: &str) -> PyResult<()> {
+        self.add(zeromq::SourcePattern::glob(pattern), false)
+    }
+
+    /// Denies the source ids fully matching the regular expression
+    ///
+    /// Parameters
+    /// ----------
+    /// pattern: str
+    ///   The regular expression
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the pattern is invalid
+    ///
+    pub fn deny_regex(&mut self, pattern: &str) -> PyResult<()> {
+        self.add(zeromq::SourcePattern::regex(pattern), false)
+    }
+
+    /// Checks whether the messages from the source are accepted
+    ///
+    /// Parameters
+    /// ----------
+    /// source_id: str
+    ///   The source id
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///
+    pub fn accepts(&self, source_id: &str) -> bool {
+        self.0.accepts(source_id.as_bytes())
+    }
+}
+
+/// The CURVE keypair used to encrypt and authenticate the connections between readers and
+/// writers. The keys are in the Z85 text encoding.
+///
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct CurveKeyPair(pub(crate) zeromq::CurveKeyPair);
+
+#[pymethods]
+impl CurveKeyPair {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    /// Creates the keypair from the existing keys
+    ///
+    /// Parameters
+    /// ----------
+    /// public_key: str
+    ///   The public key
+    /// secret_key: str
+    ///   The secret key
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the keys are invalid
+    ///
+    #[new]
+    pub fn new(public_key: &str, secret_key: &str) -> PyResult<Self> {
+        zeromq::CurveKeyPair::new(public_key, secret_key)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(format!("Invalid CURVE keypair: {:?}", e)))
+    }
+
+    /// Generates a new keypair
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If ZeroMQ is built without CURVE support
+    ///
+    #[staticmethod]
+    pub fn generate() -> PyResult<Self> {
+        zeromq::CurveKeyPair::generate().map(Self).map_err(|e| {
+            PyValueError::new_err(format!("Failed to generate CURVE keypair: {:?}", e))
+        })
+    }
+
+    /// Loads the keypair saved with :py:meth:`save`
+    ///
+    /// Parameters
+    /// ----------
+    /// path: str
+    ///   The path to the keypair file
+    ///
+    #[staticmethod]
+    pub fn load(path: &str) -> PyResult<Self> {
+        zeromq::CurveKeyPair::load(Path::new(path))
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(format!("Failed to load CURVE keypair: {:?}", e)))
+    }
+
+    /// Saves the keypair to a new file readable only by the owner, an existing file is not
+    /// overwritten
+    ///
+    /// Parameters
+    /// ----------
+    /// path: str
+    ///   The path to the keypair file
+    ///
+    pub fn save(&self, path: &str) -> PyResult<()> {
+        self.0
+            .save(Path::new(path))
+            .map_err(|e| PyValueError::new_err(format!("Failed to save CURVE keypair: {:?}", e)))
+    }
+
+    #[getter]
+    pub fn public_key(&self) -> String {
+        self.0.public_key().to_string()
+    }
+
+    #[getter]
+    pub fn secret_key(&self) -> String {
+        self.0.secret_key().to_string()
+    }
+}
+
+/// The snapshot of the transport counters of a reader or a writer.
+///
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct TransportStats(pub(crate) zeromq::TransportStats);
+
+#[pymethods]
+impl TransportStats {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+
+    /// The number of messages received or sent.
+    ///
+    #[getter]
+    pub fn messages(&self) -> u64 {
+        self.0.messages
+    }
+
+    /// The size of all the frames received or sent, in bytes.
+    ///
+    #[getter]
+    pub fn bytes(&self) -> u64 {
+        self.0.bytes
+    }
+
+    /// The number of messages rejected by the reader or not sent by the writer in time.
+    ///
+    #[getter]
+    pub fn drops(&self) -> u64 {
+        self.0.drops
+    }
+
+    /// The number of send attempts rejected because the peer did not accept the message.
+    ///
+    #[getter]
+    pub fn hwm_hits(&self) -> u64 {
+        self.0.hwm_hits
+    }
+
+    /// The number of connections restored after the peer disconnected.
+    ///
+    #[getter]
+    pub fn reconnects(&self) -> u64 {
+        self.0.reconnects
+    }
+
+    /// The UNIX time in seconds the last message was received or sent.
+    ///
+    #[getter]
+    pub fn last_activity(&self) -> Option<f64> {
+        self.0
+            .last_activity
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::primitives::message::Message;
 use crate::release_gil;
+use crate::zmq::basic_types::TransportStats;
 use crate::zmq::configs::{ReaderConfig, WriterConfig};
 use crate::zmq::results;
 use pyo3::exceptions::PyRuntimeError;
@@ -47,6 +48,20 @@ impl BlockingWriter {
         Ok(())
     }
 
+    /// Returns the transport counters. If the writer is not started, returns an error.
+    ///
+    /// Returns
+    /// -------
+    /// TransportStats
+    ///
+    pub fn stats(&self) -> PyResult<TransportStats> {
+        let writer = self
+            .0
+            .as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Writer is not started."))?;
+        Ok(TransportStats(writer.stats()))
+    }
+
     /// Shuts down the writer. If the writer is not started, returns an error.
     ///
     pub fn shutdown(&mut self) -> PyResult<()> {
@@ -179,6 +194,20 @@ impl BlockingReader {
         reader.is_started()
     }
 
+    /// Returns the transport counters. If the reader is not started, returns an error.
+    ///
+    /// Returns
+    /// -------
+    /// TransportStats
+    ///
+    pub fn stats(&self) -> PyResult<TransportStats> {
+        let reader = self
+            .0
+            .as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Reader is not started."))?;
+        Ok(TransportStats(reader.stats()))
+    }
+
     /// Shuts down the reader. If the reader is not started, returns an error.
     ///
     pub fn shutdown(&mut self) -> PyResult<()> {
@@ -1,9 +1,38 @@
-use crate::zmq::basic_types::{ReaderSocketType, TopicPrefixSpec, WriterSocketType};
+use crate::zmq::basic_types::{
+    CompressionCodec, CurveKeyPair, ReaderSocketType, SourceFilter, TopicPrefixSpec,
+    ValidationMode, WriterSocketType,
+};
 use pyo3::exceptions::PyValueError;
-use pyo3::{pyclass, pymethods, Py, PyAny, PyResult};
+use pyo3::{pyclass, pymethods, Py, PyAny, PyResult, Python};
 use savant_core::transport::zeromq;
 use std::num::NonZeroU64;
 
+fn py_connection_callback(callback: Py<PyAny>) -> zeromq::ConnectionCallback {
+    zeromq::ConnectionCallback::new(move |endpoint, event| {
+        let (name, attempts) = match event {
+            zeromq::ConnectionEvent::Listening => ("listening", None),
+            zeromq::ConnectionEvent::Accepted => ("accepted", None),
+            zeromq::ConnectionEvent::Connected => ("connected", None),
+            zeromq::ConnectionEvent::Disconnected => ("disconnected", None),
+            zeromq::ConnectionEvent::ConnectRetried { attempt } => {
+                ("connect_retried", Some(*attempt))
+            }
+            zeromq::ConnectionEvent::GaveUp { attempts } => ("gave_up", Some(*attempts)),
+            zeromq::ConnectionEvent::Closed => ("closed", None),
+        };
+        Python::with_gil(|py| {
+            if let Err(e) = callback.call1(py, (endpoint, name, attempts)) {
+                log::error!(
+                    target: "savant_rs::zeromq::monitor",
+                    "Connection callback failed for endpoint {}: {:?}",
+                    endpoint,
+                    e
+                );
+            }
+        });
+    })
+}
+
 /// Creates a new configuration builder based on the provided URL.
 /// The URL can have the following formats:
 ///
@@ -86,6 +115,11 @@ impl WriterConfig {
         *self.0.fix_ipc_permissions()
     }
 
+    #[getter]
+    fn compression(&self) -> CompressionCodec {
+        (*self.0.compression()).into()
+    }
+
     #[classattr]
     const __hash__: Option<Py<PyAny>> = None;
 
@@ -316,6 +350,186 @@ impl WriterConfigBuilder {
         Ok(())
     }
 
+    /// Sets the reconnect policy used when the socket connects to a peer. The interval between
+    /// the reconnect attempts doubles on every failure up to ``max_interval``.
+    ///
+    /// Parameters
+    /// ----------
+    /// interval: int
+    ///   The initial reconnect interval in milliseconds, defaults to ``100``.
+    /// max_interval: int
+    ///   The maximum reconnect interval in milliseconds, ``0`` disables the backoff.
+    /// max_retries: Optional[int]
+    ///   The number of consecutive failed attempts after which the socket gives up and
+    ///   the operations fail until the peer is connected again. ``None`` retries forever.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the policy is invalid or double set
+    ///
+    #[pyo3(signature = (interval=100, max_interval=0, max_retries=None))]
+    pub fn with_reconnect_policy(
+        &mut self,
+        interval: i32,
+        max_interval: i32,
+        max_retries: Option<u32>,
+    ) -> PyResult<()> {
+        let policy = zeromq::ReconnectPolicy::new(interval, max_interval, max_retries)
+            .and_then(|policy| self.0.take().unwrap().with_reconnect_policy(policy));
+        self.0 = Some(policy.map_err(|e| {
+            PyValueError::new_err(format!(
+                "Failed to set ZeroMQ socket reconnect policy: {:?}",
+                e
+            ))
+        })?);
+        Ok(())
+    }
+
+    /// Sets the callback receiving the connection state changes of the socket. The callback is
+    /// called from a background thread as ``callback(endpoint, event, attempts)``, where
+    /// ``event`` is one of ``listening``, ``accepted``, ``connected``, ``disconnected``,
+    /// ``connect_retried``, ``gave_up``, ``closed`` and ``attempts`` is set for the
+    /// ``connect_retried`` and ``gave_up`` events.
+    ///
+    /// Parameters
+    /// ----------
+    /// callback: Callable[[str, str, Optional[int]], None]
+    ///   The callback
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the callback is double set
+    ///
+    pub fn with_connection_callback(&mut self, callback: Py<PyAny>) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_connection_callback(Some(py_connection_callback(callback)))
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ socket connection callback: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
+
+    /// Sets the compression codec for the messages and the extra frames.
+    ///
+    /// Parameters
+    /// ----------
+    /// codec: CompressionCodec
+    ///   The codec, defaults to ``CompressionCodec.Disabled``.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the codec is double set
+    ///
+    pub fn with_compression(&mut self, codec: CompressionCodec) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_compression(codec.into())
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ socket compression: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
+
+    /// Enables CURVE encryption, the socket acts as the CURVE server.
+    ///
+    /// Parameters
+    /// ----------
+    /// keypair: CurveKeyPair
+    ///   The server keypair, the clients use its public key
+    /// allowed_clients: Optional[List[str]]
+    ///   The public keys of the clients allowed to connect, any client is accepted when not set
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the keys are invalid or CURVE is double set
+    ///
+    #[pyo3(signature = (keypair, allowed_clients=None))]
+    pub fn with_curve_server(
+        &mut self,
+        keypair: &CurveKeyPair,
+        allowed_clients: Option<Vec<String>>,
+    ) -> PyResult<()> {
+        let curve = zeromq::CurveConfig::server(keypair.0.clone(), allowed_clients)
+            .and_then(|curve| self.0.take().unwrap().with_curve(curve));
+        self.0 = Some(curve.map_err(|e| {
+            PyValueError::new_err(format!("Failed to set ZeroMQ socket CURVE server: {:?}", e))
+        })?);
+        Ok(())
+    }
+
+    /// Enables CURVE encryption, the socket acts as the CURVE client.
+    ///
+    /// Parameters
+    /// ----------
+    /// keypair: CurveKeyPair
+    ///   The client keypair
+    /// server_public_key: str
+    ///   The public key of the server
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the keys are invalid or CURVE is double set
+    ///
+    pub fn with_curve_client(
+        &mut self,
+        keypair: &CurveKeyPair,
+        server_public_key: &str,
+    ) -> PyResult<()> {
+        let curve = zeromq::CurveConfig::client(keypair.0.clone(), server_public_key)
+            .and_then(|curve| self.0.take().unwrap().with_curve(curve));
+        self.0 = Some(curve.map_err(|e| {
+            PyValueError::new_err(format!("Failed to set ZeroMQ socket CURVE client: {:?}", e))
+        })?);
+        Ok(())
+    }
+
+    /// Mirrors the transport counters to the metrics registry, so they are exported along with
+    /// the other metrics. The metrics are labeled with the endpoint.
+    ///
+    /// Parameters
+    /// ----------
+    /// export: bool
+    ///   Whether to export the metrics, defaults to ``False``.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the option is double set
+    ///
+    pub fn with_export_metrics(&mut self, export: bool) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_export_metrics(export)
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ socket metrics export: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
+
     /// Builds the configuration
     ///
     /// Returns
@@ -629,6 +843,187 @@ impl ReaderConfigBuilder {
         Ok(())
     }
 
+    /// Sets the reconnect policy used when the socket connects to a peer. The interval between
+    /// the reconnect attempts doubles on every failure up to ``max_interval``.
+    ///
+    /// Parameters
+    /// ----------
+    /// interval: int
+    ///   The initial reconnect interval in milliseconds, defaults to ``100``.
+    /// max_interval: int
+    ///   The maximum reconnect interval in milliseconds, ``0`` disables the backoff.
+    /// max_retries: Optional[int]
+    ///   The number of consecutive failed attempts after which the socket gives up and
+    ///   the operations fail until the peer is connected again. ``None`` retries forever.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the policy is invalid or double set
+    ///
+    #[pyo3(signature = (interval=100, max_interval=0, max_retries=None))]
+    pub fn with_reconnect_policy(
+        &mut self,
+        interval: i32,
+        max_interval: i32,
+        max_retries: Option<u32>,
+    ) -> PyResult<()> {
+        let policy = zeromq::ReconnectPolicy::new(interval, max_interval, max_retries)
+            .and_then(|policy| self.0.take().unwrap().with_reconnect_policy(policy));
+        self.0 = Some(policy.map_err(|e| {
+            PyValueError::new_err(format!(
+                "Failed to set ZeroMQ socket reconnect policy: {:?}",
+                e
+            ))
+        })?);
+        Ok(())
+    }
+
+    /// Sets the callback receiving the connection state changes of the socket. The callback is
+    /// called from a background thread as ``callback(endpoint, event, attempts)``, where
+    /// ``event`` is one of ``listening``, ``accepted``, ``connected``, ``disconnected``,
+    /// ``connect_retried``, ``gave_up``, ``closed`` and ``attempts`` is set for the
+    /// ``connect_retried`` and ``gave_up`` events.
+    ///
+    /// Parameters
+    /// ----------
+    /// callback: Callable[[str, str, Optional[int]], None]
+    ///   The callback
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the callback is double set
+    ///
+    pub fn with_connection_callback(&mut self, callback: Py<PyAny>) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_connection_callback(Some(py_connection_callback(callback)))
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ socket connection callback: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
+
+    /// Enables CURVE encryption, the socket acts as the CURVE server.
+    ///
+    /// Parameters
+    /// ----------
+    /// keypair: CurveKeyPair
+    ///   The server keypair, the clients use its public key
+    /// allowed_clients: Optional[List[str]]
+    ///   The public keys of the clients allowed to connect, any client is accepted when not set
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the keys are invalid or CURVE is double set
+    ///
+    #[pyo3(signature = (keypair, allowed_clients=None))]
+    pub fn with_curve_server(
+        &mut self,
+        keypair: &CurveKeyPair,
+        allowed_clients: Option<Vec<String>>,
+    ) -> PyResult<()> {
+        let curve = zeromq::CurveConfig::server(keypair.0.clone(), allowed_clients)
+            .and_then(|curve| self.0.take().unwrap().with_curve(curve));
+        self.0 = Some(curve.map_err(|e| {
+            PyValueError::new_err(format!("Failed to set ZeroMQ socket CURVE server: {:?}", e))
+        })?);
+        Ok(())
+    }
+
+    /// Enables CURVE encryption, the socket acts as the CURVE client.
+    ///
+    /// Parameters
+    /// ----------
+    /// keypair: CurveKeyPair
+    ///   The client keypair
+    /// server_public_key: str
+    ///   The public key of the server
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the keys are invalid or CURVE is double set
+    ///
+    pub fn with_curve_client(
+        &mut self,
+        keypair: &CurveKeyPair,
+        server_public_key: &str,
+    ) -> PyResult<()> {
+        let curve = zeromq::CurveConfig::client(keypair.0.clone(), server_public_key)
+            .and_then(|curve| self.0.take().unwrap().with_curve(curve));
+        self.0 = Some(curve.map_err(|e| {
+            PyValueError::new_err(format!("Failed to set ZeroMQ socket CURVE client: {:?}", e))
+        })?);
+        Ok(())
+    }
+
+    /// Mirrors the transport counters to the metrics registry, so they are exported along with
+    /// the other metrics. The metrics are labeled with the endpoint.
+    ///
+    /// Parameters
+    /// ----------
+    /// export: bool
+    ///   Whether to export the metrics, defaults to ``False``.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the option is double set
+    ///
+    pub fn with_export_metrics(&mut self, export: bool) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_export_metrics(export)
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ socket metrics export: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
+
+    /// Limits the size of every decompressed frame of the compressed messages, the messages
+    /// exceeding the limit are rejected.
+    ///
+    /// Parameters
+    /// ----------
+    /// size: int
+    ///   The limit in bytes, defaults to 256 MiB.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the option is double set or the size is 0
+    ///
+    pub fn with_max_decompressed_size(&mut self, size: usize) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_max_decompressed_size(size)
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ socket max decompressed size: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
+
     /// Builds the configuration
     ///
     /// Returns
@@ -711,4 +1106,60 @@ impl ReaderConfigBuilder {
         );
         Ok(())
     }
+
+    /// Sets the allowlist and the denylist of the source ids.
+    ///
+    /// Parameters
+    /// ----------
+    /// filter: SourceFilter
+    ///   The filter, all sources are accepted by default.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the filter is double set
+    ///
+    pub fn with_source_filter(&mut self, filter: &SourceFilter) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_source_filter(filter.0.clone())
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ reader source filter: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
+
+    /// Sets the validation mode for received frames.
+    ///
+    /// Parameters
+    /// ----------
+    /// mode: ValidationMode
+    ///  The validation mode, defaults to ``ValidationMode.Disabled``.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///  If the validation mode is double set
+    ///
+    pub fn with_validation_mode(&mut self, mode: ValidationMode) -> PyResult<()> {
+        self.0 = Some(
+            self.0
+                .take()
+                .unwrap()
+                .with_validation_mode(mode.into())
+                .map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to set ZeroMQ reader validation mode: {:?}",
+                        e
+                    ))
+                })?,
+        );
+        Ok(())
+    }
 }
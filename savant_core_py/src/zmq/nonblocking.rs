@@ -1,5 +1,6 @@
 use crate::primitives::message::Message;
 use crate::release_gil;
+use crate::zmq::basic_types::TransportStats;
 use crate::zmq::configs::{ReaderConfig, WriterConfig};
 use crate::zmq::results;
 use parking_lot::{Mutex, MutexGuard};
@@ -58,6 +59,16 @@ impl NonBlockingReader {
         self.0.is_started()
     }
 
+    /// Returns the transport counters, all zeros if the reader is not started.
+    ///
+    /// Returns
+    /// -------
+    /// TransportStats
+    ///
+    pub fn stats(&self) -> TransportStats {
+        TransportStats(self.0.stats())
+    }
+
     /// Returns `true` if the reader is shutdown.
     ///
     pub fn is_shutdown(&self) -> bool {
@@ -209,6 +220,16 @@ impl NonBlockingWriter {
         self.locked().is_shutdown()
     }
 
+    /// Returns the transport counters, all zeros if the writer is not started.
+    ///
+    /// Returns
+    /// -------
+    /// TransportStats
+    ///
+    pub fn stats(&self) -> TransportStats {
+        TransportStats(self.locked().stats())
+    }
+
     /// Returns the number of inflight messages.
     pub fn inflight_messages(&self) -> usize {
         self.locked().inflight_messages()
@@ -191,6 +191,43 @@ impl ReaderResultMessage {
     }
 }
 
+/// Returned when a reader rejected a message because it violates the semantic
+/// invariants checked by ``ValidationMode.Strict``.
+///
+#[pyclass]
+#[derive(Clone)]
+pub struct ReaderResultInvalid {
+    /// The :py:class:`savant_rs.utils.serialization.Message` received.
+    #[pyo3(get)]
+    pub message: Message,
+    /// The topic of the message.
+    #[pyo3(get)]
+    pub topic: Vec<u8>,
+    /// The routing id of the message. The field is only filled for Router socket.
+    #[pyo3(get)]
+    pub routing_id: Option<Vec<u8>>,
+    /// The descriptions of the violations found.
+    #[pyo3(get)]
+    pub violations: Vec<String>,
+}
+
+#[pymethods]
+impl ReaderResultInvalid {
+    #[classattr]
+    const __hash__: Option<Py<PyAny>> = None;
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ReaderResultInvalid [ message = {:?}, topic = {:?}, routing_id = {:?}, violations = {:?} ]",
+            &self.message.0, &self.topic, &self.routing_id, &self.violations
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
 /// Returned when a reader is unable to receive a message due to a timeout on ZMQ.
 ///
 #[pyclass]
@@ -344,7 +381,27 @@ pub(crate) fn process_reader_result(res: zeromq::ReaderResult) -> PyResult<PyObj
                     .into_any()
                     .unbind()
             }
+            zeromq::ReaderResult::SourceDenied { topic, routing_id } => {
+                ReaderResultPrefixMismatch { topic, routing_id }
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind()
+            }
             zeromq::ReaderResult::TooShort(data) => data.into_pyobject(py)?.into_any().unbind(),
+            zeromq::ReaderResult::Invalid {
+                message,
+                topic,
+                routing_id,
+                violations,
+            } => ReaderResultInvalid {
+                message: Message(*message),
+                topic,
+                routing_id,
+                violations: violations.iter().map(|v| v.to_string()).collect(),
+            }
+            .into_pyobject(py)?
+            .into_any()
+            .unbind(),
         })
     })
 }
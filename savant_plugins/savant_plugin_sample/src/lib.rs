@@ -10,6 +10,8 @@ use savant_core_py::primitives::frame::VideoFrame;
 use savant_core_py::primitives::object::BorrowedVideoObject;
 use std::collections::HashMap;
 
+savant_core::export_plugin_abi!();
+
 #[no_mangle]
 pub fn init_plugin(_: &str, pp: PluginParams) -> *mut dyn PipelineStageFunction {
     let plugin = Plugin {
@@ -0,0 +1,104 @@
+from enum import Enum
+from typing import List, Optional, Tuple
+
+from savant_rs.match_query import MatchQuery
+from savant_rs.primitives import AttributeValue, VideoFrame, VideoObjectsView
+from savant_rs.primitives.geometry import RBBox
+from savant_rs.utils import BBoxMetricType
+
+
+class TrackEventKind(Enum):
+    Born: ...
+    Died: ...
+
+
+class TrackState(Enum):
+    Tentative: ...
+    Confirmed: ...
+    Lost: ...
+
+
+class TrackAttribute:
+    @property
+    def namespace(self) -> str: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def observations(self) -> List[Tuple[int, List[AttributeValue]]]: ...
+
+
+class TrackAggregate:
+    @property
+    def source_id(self) -> str: ...
+
+    @property
+    def track_id(self) -> int: ...
+
+    @property
+    def namespace(self) -> str: ...
+
+    @property
+    def label(self) -> str: ...
+
+    @property
+    def first_seen_pts(self) -> int: ...
+
+    @property
+    def last_seen_pts(self) -> int: ...
+
+    @property
+    def hits(self) -> int: ...
+
+    @property
+    def confirmed(self) -> bool: ...
+
+    @property
+    def state(self) -> TrackState: ...
+
+    @property
+    def attributes(self) -> List[TrackAttribute]: ...
+
+    @property
+    def max_confidence(self) -> Optional[float]: ...
+
+    @property
+    def best_bbox(self) -> RBBox: ...
+
+    @property
+    def best_bbox_pts(self) -> int: ...
+
+
+class TrackEvent:
+    @property
+    def kind(self) -> TrackEventKind: ...
+
+    @property
+    def track(self) -> TrackAggregate: ...
+
+
+class TrackManager:
+    def __init__(self,
+                 min_hits: int = 3,
+                 max_missed: int = 30,
+                 query: Optional[MatchQuery] = None,
+                 history: Optional[int] = None,
+                 max_age: Optional[int] = None,
+                 attributes: List[Tuple[str, str]] = [],
+                 attribute_capacity: int = 16): ...
+
+    def process_frame(self, frame: VideoFrame) -> List[TrackEvent]: ...
+
+    def finish_stream(self, source_id: str) -> List[TrackEvent]: ...
+
+    def get_track(self, source_id: str, track_id: int) -> Optional[TrackAggregate]: ...
+
+    def get_tracks(self, source_id: str, confirmed_only: bool = True) -> List[TrackAggregate]: ...
+
+
+def associate(prev_objects: VideoObjectsView,
+              new_objects: VideoObjectsView,
+              metric: BBoxMetricType,
+              threshold: float,
+              no_gil: bool = True) -> List[Tuple[Optional[int], int]]: ...
@@ -1,5 +1,8 @@
 from enum import Enum
-from typing import Tuple, Optional
+from typing import Any, List, Tuple, Optional
+
+from savant_rs.primitives import BorrowedVideoObject, VideoFrame
+from savant_rs.primitives.geometry import PolygonalArea, RBBox
 
 
 class ColorDraw:
@@ -161,6 +164,8 @@ class LabelDraw:
     @property
     def format(self) -> list[str]: ...
 
+    def render(self, object: BorrowedVideoObject) -> List[str]: ...
+
 
 class ObjectDraw:
     def copy(self) -> ObjectDraw: ...
@@ -184,6 +189,53 @@ class ObjectDraw:
     @property
     def blur(self) -> bool: ...
 
+    @property
+    def json(self) -> str: ...
+
+    @property
+    def yaml(self) -> str: ...
+
+    @staticmethod
+    def from_json(json: str) -> ObjectDraw: ...
+
+    @staticmethod
+    def from_yaml(yaml: str) -> ObjectDraw: ...
+
+
+class DrawSpecRegistry:
+    WILDCARD: str
+
+    def __init__(self, default: Optional[ObjectDraw] = None): ...
+
+    def __len__(self) -> int: ...
+
+    @property
+    def default(self) -> Optional[ObjectDraw]: ...
+
+    @default.setter
+    def default(self, default: Optional[ObjectDraw]): ...
+
+    def insert(self, namespace: str, label: str, spec: ObjectDraw) -> Optional[ObjectDraw]: ...
+
+    def remove(self, namespace: str, label: str) -> Optional[ObjectDraw]: ...
+
+    def get(self, namespace: str, label: str) -> Optional[ObjectDraw]: ...
+
+    def lookup(self, namespace: str, label: str) -> Optional[ObjectDraw]: ...
+
+    def keys(self) -> List[Tuple[str, str]]: ...
+
+    @property
+    def json(self) -> str: ...
+
+    @property
+    def yaml(self) -> str: ...
+
+    @staticmethod
+    def from_json(json: str) -> DrawSpecRegistry: ...
+
+    @staticmethod
+    def from_yaml(yaml: str) -> DrawSpecRegistry: ...
 
 class SetDrawLabelKind:
     @classmethod
@@ -196,4 +248,32 @@ class SetDrawLabelKind:
 
     def is_parent_label(self) -> bool: ...
 
-    def get_label(self) -> str: ...
\ No newline at end of file
+    def get_label(self) -> str: ...
+
+
+class Renderer:
+    def __init__(self, font_path: Optional[str] = None): ...
+
+    @property
+    def has_font(self) -> bool: ...
+
+    def render_frame(self,
+                     image: Any,
+                     frame: VideoFrame,
+                     registry: DrawSpecRegistry,
+                     no_gil: bool = True): ...
+
+    def render_object(self,
+                      image: Any,
+                      bbox: RBBox,
+                      spec: ObjectDraw,
+                      label: List[str] = [],
+                      no_gil: bool = True): ...
+
+    def render_polygon(self,
+                       image: Any,
+                       area: PolygonalArea,
+                       color: ColorDraw,
+                       thickness: int = 2,
+                       fill: Optional[ColorDraw] = None,
+                       no_gil: bool = True): ...
@@ -1,6 +1,6 @@
-from typing import List, Optional, Dict
+from typing import Callable, Dict, List, Optional
 
-from savant_rs.primitives.geometry import RBBox
+from savant_rs.primitives.geometry import Point, PolygonalArea, RBBox, Segment
 from savant_rs.utils import BBoxMetricType
 
 class FloatExpression:
@@ -53,6 +53,8 @@ class StringExpression:
     @classmethod
     def ends_with(cls, arg: str) -> StringExpression: ...
     @classmethod
+    def matches(cls, arg: str) -> StringExpression: ...
+    @classmethod
     def one_of(cls, *args: str) -> StringExpression: ...
 
 class MatchQuery:
@@ -81,6 +83,28 @@ class MatchQuery:
         cls, bbox: RBBox, metric_type: BBoxMetricType, e: FloatExpression
     ) -> MatchQuery: ...
     @classmethod
+    def box_within_polygon(cls, area: PolygonalArea) -> MatchQuery: ...
+    @classmethod
+    def box_intersects_polygon(cls, area: PolygonalArea) -> MatchQuery: ...
+    @classmethod
+    def box_center_distance_to_point(
+        cls, point: Point, e: FloatExpression
+    ) -> MatchQuery: ...
+    @classmethod
+    def box_crosses_segment(cls, segment: Segment) -> MatchQuery: ...
+    @classmethod
+    def track_box_within_polygon(cls, area: PolygonalArea) -> MatchQuery: ...
+    @classmethod
+    def track_speed(cls, e: FloatExpression) -> MatchQuery: ...
+    @classmethod
+    def track_direction(cls, e: FloatExpression) -> MatchQuery: ...
+    @classmethod
+    def track_age(cls, e: IntExpression) -> MatchQuery: ...
+    @classmethod
+    def keypoint_defined(cls, name: str) -> MatchQuery: ...
+    @classmethod
+    def keypoint_confidence(cls, name: str, e: FloatExpression) -> MatchQuery: ...
+    @classmethod
     def namespace(cls, e: StringExpression) -> MatchQuery: ...
     @classmethod
     def label(cls, e: StringExpression) -> MatchQuery: ...
@@ -141,6 +165,18 @@ class MatchQuery:
     @classmethod
     def attribute_defined(cls, namespace: str, label: str) -> MatchQuery: ...
     @classmethod
+    def attribute_float(
+        cls, namespace: str, label: str, e: FloatExpression
+    ) -> MatchQuery: ...
+    @classmethod
+    def attribute_int(
+        cls, namespace: str, label: str, e: IntExpression
+    ) -> MatchQuery: ...
+    @classmethod
+    def attribute_string(
+        cls, namespace: str, label: str, e: StringExpression
+    ) -> MatchQuery: ...
+    @classmethod
     def frame_source_id(cls, e: StringExpression) -> MatchQuery: ...
     @classmethod
     def frame_is_key_frame(cls) -> MatchQuery: ...
@@ -164,21 +200,50 @@ class MatchQuery:
     def json_pretty(self) -> str: ...
     @property
     def yaml(self) -> str: ...
+    def optimize(self) -> MatchQuery: ...
     @classmethod
     def from_json(cls, json_str: str) -> MatchQuery: ...
     @classmethod
     def from_yaml(cls, yaml_str: str) -> MatchQuery: ...
+    @classmethod
+    def from_expr(cls, expr: str) -> MatchQuery: ...
 
 class TlsConfig:
-    def __init__(self, ca: str, cert: str, key: str): ...
+    def __init__(
+        self,
+        ca_cert: Optional[str] = None,
+        client_cert: Optional[str] = None,
+        client_key: Optional[str] = None,
+    ): ...
 
 class EtcdCredentials:
     def __init__(self, username: str, password: str): ...
 
+class LayeredConfig:
+    def __init__(
+        self,
+        defaults: Optional[Dict[str, str]] = None,
+        file: Optional[str] = None,
+        file_check_interval: int = 1000,
+        env_prefix: Optional[str] = None,
+    ): ...
+    def set_default(self, key: str, value: str): ...
+    def get(self, key: str) -> Optional[str]: ...
+    def layer(self, key: str) -> Optional[str]: ...
+    def get_int(self, key: str) -> Optional[int]: ...
+    def get_float(self, key: str) -> Optional[float]: ...
+    def get_bool(self, key: str) -> Optional[bool]: ...
+    def watch(self, key: str, callback: Callable[[str, Optional[str]], None]) -> int: ...
+    def unwatch(self, id: int) -> bool: ...
+    def refresh(self) -> int: ...
+
 def utility_resolver_name() -> str: ...
 def etcd_resolver_name() -> str: ...
 def env_resolver_name() -> str: ...
 def config_resolver_name() -> str: ...
+def redis_resolver_name() -> str: ...
+def http_resolver_name() -> str: ...
+def file_resolver_name() -> str: ...
 def register_utility_resolver(): ...
 def register_env_resolver(): ...
 def register_etcd_resolver(
@@ -189,6 +254,27 @@ def register_etcd_resolver(
     connect_timeout: int = 5,
     watch_path_wait_timeout: int = 5,
 ): ...
+def register_redis_resolver(
+    url: str = "redis://127.0.0.1:6379/0",
+    prefix: str = "savant",
+    pool_size: int = 4,
+    connect_timeout: int = 5,
+    cache_ttl: int = 1000,
+    cache_size: int = 1024,
+): ...
+def register_http_resolver(
+    timeout: int = 5000,
+    cache_ttl: int = 1000,
+    cache_size: int = 1024,
+    variables: Optional[Dict[str, str]] = None,
+    headers: Optional[Dict[str, str]] = None,
+): ...
+def register_file_resolver(path: str, check_interval: int = 1000): ...
+def reload_file_resolver(): ...
 def register_config_resolver(params: Dict[str, str]): ...
 def update_config_resolver(params: Dict[str, str]): ...
+def register_layered_config_resolver(config: LayeredConfig): ...
 def unregister_resolver(name: str): ...
+def configure_resolver_cache(max_entries: int = 4096, default_ttl: int = 0): ...
+def set_resolver_cache_ttl(symbol: str, ttl: int): ...
+def invalidate_resolver_cache(prefix: str) -> int: ...
@@ -1,6 +1,7 @@
 from enum import Enum
-from typing import Optional, Tuple, List
+from typing import Any, Optional, Tuple, List
 from savant_rs.draw_spec import PaddingDraw
+from savant_rs.utils import BBoxMetricType
 
 class Point:
     x: float
@@ -41,6 +42,17 @@ class PolygonalArea:
     def contains(self, point: Point) -> bool: ...
     def build_polygon(self): ...
     def get_tag(self, edge: int) -> Optional[str]: ...
+    @property
+    def area(self) -> float: ...
+    @property
+    def centroid(self) -> Optional[Point]: ...
+    def contains_bbox(self, bbox: RBBox) -> bool: ...
+    def contains_many(self, points: Any, no_gil: bool = True) -> List[bool]: ...
+    def crossed_by_many(self, segments: Any, no_gil: bool = True) -> List[Intersection]: ...
+    def union(self, other: PolygonalArea) -> List[PolygonalArea]: ...
+    def intersection(self, other: PolygonalArea) -> List[PolygonalArea]: ...
+    def buffer(self, distance: float, segments: int = 16) -> List[PolygonalArea]: ...
+    def simplify(self, epsilon: float) -> PolygonalArea: ...
     @classmethod
     def points_positions(
         cls, polys: List[PolygonalArea], points: List[Point], no_gil: bool = False
@@ -159,3 +171,32 @@ def solely_owned_areas(bboxes: List[RBBox], parallel: bool) -> List[float]: ...
 def associate_bboxes(
     candidates: List[RBBox], owners: List[RBBox], metric: str, threshold: float
 ) -> dict[int, list[tuple[int, float]]]: ...
+def nms(
+    boxes: Any, scores: Any, iou_threshold: float, no_gil: bool = True
+) -> List[int]: ...
+def cluster_boxes(
+    boxes: Any, metric: BBoxMetricType, threshold: float, no_gil: bool = True
+) -> List[List[int]]: ...
+
+class KalmanBBoxState:
+    @property
+    def bbox(self) -> RBBox: ...
+    @property
+    def velocity(self) -> Tuple[float, float, float, float, float]: ...
+
+class KalmanBBoxFilter:
+    def __init__(self,
+                 std_position: float = 1.0 / 20.0,
+                 std_velocity: float = 1.0 / 160.0,
+                 std_angle: float = 1.0,
+                 std_angle_velocity: float = 0.1): ...
+    def initiate(self, bbox: RBBox) -> KalmanBBoxState: ...
+    def predict(self, state: KalmanBBoxState) -> KalmanBBoxState: ...
+    def update(self, state: KalmanBBoxState, bbox: RBBox) -> KalmanBBoxState: ...
+    def predict_batch(self,
+                      states: List[KalmanBBoxState],
+                      no_gil: bool = True) -> List[KalmanBBoxState]: ...
+    def update_batch(self,
+                     states: List[KalmanBBoxState],
+                     bboxes: List[RBBox],
+                     no_gil: bool = True) -> List[KalmanBBoxState]: ...
@@ -1,10 +1,10 @@
 from enum import Enum
-from typing import Optional
+from typing import Any, Optional
 
 from savant_rs.draw_spec import SetDrawLabelKind
 from savant_rs.match_query import MatchQuery
 from savant_rs.primitives.geometry import Intersection, RBBox, Point, PolygonalArea
-from savant_rs.utils import VideoObjectBBoxTransformation
+from savant_rs.utils import PropagatedContext, TelemetrySpan, VideoObjectBBoxTransformation
 from savant_rs.utils.serialization import Message
 
 
@@ -25,10 +25,22 @@ class AttributeValueType(Enum):
     Polygon: ...
     PolygonList: ...
     Intersection: ...
+    Tensor: ...
     TemporaryValue: ...
     None_: ...
 
 
+class TensorDType(Enum):
+    UInt8: ...
+    Int8: ...
+    UInt16: ...
+    Int16: ...
+    Int32: ...
+    Int64: ...
+    Float32: ...
+    Float64: ...
+
+
 class AttributeValue:
     confidence: Optional[float]
 
@@ -42,6 +54,25 @@ class AttributeValue:
     @classmethod
     def none(cls) -> AttributeValue: ...
 
+    @classmethod
+    def tensor(cls,
+               array: Any,
+               confidence: Optional[float] = None) -> AttributeValue: ...
+
+    @classmethod
+    def tensor_from_bytes(cls,
+                          dims: list[int],
+                          dtype: TensorDType,
+                          blob: bytes,
+                          confidence: Optional[float] = None) -> AttributeValue: ...
+
+    def __buffer__(self, flags: int) -> memoryview: ...
+
+    def as_tensor(self) -> Optional[memoryview]: ...
+
+    @property
+    def tensor_dtype(self) -> Optional[TensorDType]: ...
+
     @classmethod
     def temporary_python_object(cls,
                                 python_object: object,
@@ -249,6 +280,33 @@ class ObjectUpdatePolicy(Enum):
     ReplaceSameLabelObjects: ...
 
 
+class MergePolicy(Enum):
+    KeepOriginal: ...
+    ReplaceWithUpdate: ...
+    HighestConfidence: ...
+    UnionAttributes: ...
+
+
+class ObjectMergePolicy:
+    def __init__(self,
+                 label: MergePolicy = MergePolicy.KeepOriginal,
+                 detection_box: MergePolicy = MergePolicy.KeepOriginal,
+                 track: MergePolicy = MergePolicy.KeepOriginal,
+                 attributes: MergePolicy = MergePolicy.KeepOriginal): ...
+
+    @property
+    def label(self) -> MergePolicy: ...
+
+    @property
+    def detection_box(self) -> MergePolicy: ...
+
+    @property
+    def track(self) -> MergePolicy: ...
+
+    @property
+    def attributes(self) -> MergePolicy: ...
+
+
 class EndOfStream:
     def __init__(self, source_id: str): ...
 
@@ -350,9 +408,56 @@ class ExternalFrame:
                  location: Optional[str]): ...
 
 
+class ExternalMemoryType(Enum):
+    Host: ...
+    CudaDevice: ...
+    CudaPinned: ...
+    CudaUnified: ...
+    Nvmm: ...
+    DmaBuf: ...
+    VaSurface: ...
+
+
+class ExternalFrameDescriptor:
+    def __init__(self,
+                 memory_type: ExternalMemoryType,
+                 fourcc: str,
+                 plane_strides: list[int],
+                 plane_offsets: list[int] = [],
+                 device_id: Optional[int] = None,
+                 handle: Optional[int] = None): ...
+
+    @property
+    def memory_type(self) -> ExternalMemoryType: ...
+
+    @property
+    def device_id(self) -> Optional[int]: ...
+
+    @property
+    def fourcc(self) -> str: ...
+
+    @property
+    def fourcc_code(self) -> int: ...
+
+    @property
+    def num_planes(self) -> int: ...
+
+    @property
+    def plane_strides(self) -> list[int]: ...
+
+    @property
+    def plane_offsets(self) -> list[int]: ...
+
+    @property
+    def handle(self) -> Optional[int]: ...
+
+
 class VideoFrameContent:
     @classmethod
-    def external(cls, method: str, location: Optional[str]) -> VideoFrameContent: ...
+    def external(cls,
+                 method: str,
+                 location: Optional[str] = None,
+                 descriptor: Optional[ExternalFrameDescriptor] = None) -> VideoFrameContent: ...
 
     @classmethod
     def internal(cls, data: bytes) -> VideoFrameContent: ...
@@ -372,12 +477,21 @@ class VideoFrameContent:
 
     def get_location(self) -> Optional[str]: ...
 
+    def get_descriptor(self) -> Optional[ExternalFrameDescriptor]: ...
+
+    def __buffer__(self, flags: int) -> memoryview: ...
+
 
 class VideoFrameTranscodingMethod(Enum):
     Copy: ...
     Encoded: ...
 
 
+class CropObjectPolicy(Enum):
+    Clip: ...
+    Drop: ...
+
+
 class VideoFrameTransformation:
     @classmethod
     def initial_size(cls, width: int, height: int) -> VideoFrameTransformation: ...
@@ -391,6 +505,9 @@ class VideoFrameTransformation:
     @classmethod
     def padding(cls, left: int, top: int, right: int, bottom: int) -> VideoFrameTransformation: ...
 
+    @classmethod
+    def crop(cls, left: int, top: int, width: int, height: int) -> VideoFrameTransformation: ...
+
     @property
     def is_initial_size(self) -> bool: ...
 
@@ -403,6 +520,9 @@ class VideoFrameTransformation:
     @property
     def is_resulting_size(self) -> bool: ...
 
+    @property
+    def is_crop(self) -> bool: ...
+
     @property
     def as_initial_size(self) -> Optional[tuple[int, int]]: ...
 
@@ -415,6 +535,135 @@ class VideoFrameTransformation:
     @property
     def as_padding(self) -> Optional[tuple[int, int, int, int]]: ...
 
+    @property
+    def as_crop(self) -> Optional[tuple[int, int, int, int]]: ...
+
+
+class CodecInfo:
+    def __init__(self,
+                 name: str,
+                 mime_type: str,
+                 aliases: list[str] = [],
+                 fourcc: Optional[str] = None,
+                 requires_keyframes: bool = False,
+                 is_raw: bool = False): ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def aliases(self) -> list[str]: ...
+
+    @property
+    def fourcc(self) -> Optional[str]: ...
+
+    @property
+    def mime_type(self) -> str: ...
+
+    @property
+    def requires_keyframes(self) -> bool: ...
+
+    @property
+    def is_raw(self) -> bool: ...
+
+
+def register_codec(info: CodecInfo): ...
+
+def find_codec(name: str) -> Optional[CodecInfo]: ...
+
+def find_codec_by_fourcc(fourcc: str) -> Optional[CodecInfo]: ...
+
+def find_codecs_by_mime_type(mime_type: str) -> list[CodecInfo]: ...
+
+def known_codecs() -> list[CodecInfo]: ...
+
+def transcoding_required(source: VideoFrame, target: VideoFrame) -> bool: ...
+
+
+class RedactionSpec:
+    def __init__(self,
+                 remove: list[tuple[str, Optional[str]]] = [],
+                 mask: list[tuple[str, Optional[str]]] = [],
+                 strip_hidden: bool = False,
+                 include_frame_attributes: bool = False): ...
+
+
+class JournalRecord:
+    @property
+    def stage(self) -> Optional[str]: ...
+
+    @property
+    def timestamp_ns(self) -> int: ...
+
+    @property
+    def field(self) -> str: ...
+
+    @property
+    def old_json(self) -> str: ...
+
+    @property
+    def new_json(self) -> str: ...
+
+    @property
+    def json(self) -> str: ...
+
+
+def set_journal_stage(stage: Optional[str] = None): ...
+
+def get_journal_stage() -> Optional[str]: ...
+
+
+class KeyPoint:
+    def __init__(self, name: str, x: float, y: float, confidence: Optional[float] = None): ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def x(self) -> float: ...
+
+    @property
+    def y(self) -> float: ...
+
+    @property
+    def confidence(self) -> Optional[float]: ...
+
+
+class KeyPoints:
+    def __init__(self, points: list[KeyPoint], edges: list[tuple[int, int]] = []): ...
+
+    def __len__(self) -> int: ...
+
+    def get(self, name: str) -> Optional[KeyPoint]: ...
+
+    @property
+    def points(self) -> list[KeyPoint]: ...
+
+    @property
+    def edges(self) -> list[tuple[int, int]]: ...
+
+    @property
+    def segments(self) -> list[tuple[KeyPoint, KeyPoint]]: ...
+
+
+class ClockSyncInfo:
+    def __init__(self,
+                 capture_time_ns: int,
+                 ntp_offset_ns: Optional[int] = None,
+                 clock_domain: Optional[str] = None): ...
+
+    @property
+    def capture_time_ns(self) -> int: ...
+
+    @property
+    def ntp_offset_ns(self) -> Optional[int]: ...
+
+    @property
+    def clock_domain(self) -> Optional[str]: ...
+
+    @property
+    def corrected_capture_time_ns(self) -> int: ...
+
 
 class VideoFrame:
     source_id: str
@@ -430,6 +679,17 @@ class VideoFrame:
     transcoding_method: VideoFrameTranscodingMethod
     codec: Optional[str]
     content: VideoFrameContent
+    clock_sync: Optional[ClockSyncInfo]
+
+    @property
+    def codec_info(self) -> Optional[CodecInfo]: ...
+
+    @property
+    def trace_context(self) -> Optional[PropagatedContext]: ...
+
+    def attach_trace_context(self, span: TelemetrySpan): ...
+
+    def try_set_codec(self, codec: Optional[str]): ...
 
     @classmethod
     def transform_geometry(cls,
@@ -472,6 +732,10 @@ class VideoFrame:
     @property
     def transformations(self) -> list[VideoFrameTransformation]: ...
 
+    def map_box_to_source(self, bbox: RBBox) -> RBBox: ...
+
+    def map_box_from_source(self, bbox: RBBox) -> RBBox: ...
+
     @property
     def attributes(self) -> list[(str, str)]: ...
 
@@ -522,6 +786,19 @@ class VideoFrame:
 
     def add_object(self, object: VideoObject, policy: IdCollisionResolutionPolicy): ...
 
+    def add_objects(self,
+                    objects: list[VideoObject],
+                    policy: IdCollisionResolutionPolicy,
+                    no_gil: bool = True) -> list[BorrowedVideoObject]: ...
+
+    def create_objects_from_arrays(self,
+                                   namespace: str,
+                                   labels: list[str],
+                                   boxes: Any,
+                                   confidences: Any,
+                                   track_ids: Optional[Any] = None,
+                                   no_gil: bool = True) -> list[BorrowedVideoObject]: ...
+
     def create_object(self,
                       namespace: str,
                       label: str,
@@ -567,8 +844,28 @@ class VideoFrame:
 
     def copy(self, no_gil: bool = True) -> VideoFrame: ...
 
+    def enable_journal(self): ...
+
+    def disable_journal(self): ...
+
+    def get_journal(self) -> Optional[list[JournalRecord]]: ...
+
+    def take_journal(self) -> Optional[list[JournalRecord]]: ...
+
+    def crop(self,
+             roi: RBBox,
+             policy: CropObjectPolicy = CropObjectPolicy.Clip,
+             no_gil: bool = True) -> VideoFrame: ...
+
+    def redact(self,
+               q: MatchQuery,
+               spec: RedactionSpec,
+               no_gil: bool = True) -> VideoFrame: ...
+
     def update(self, update: VideoFrameUpdate, no_gil: bool = True): ...
 
+    def diff(self, other: VideoFrame, no_gil: bool = True) -> VideoFrameUpdate: ...
+
     def to_protobuf(self, no_gil: bool = True) -> bytes: ...
 
     @classmethod
@@ -592,6 +889,19 @@ class VideoFrameBatch:
 
     def delete_objects(self, q: MatchQuery, no_gil: bool = True): ...
 
+    def transform_geometry(self,
+                           ops: list[VideoObjectBBoxTransformation],
+                           no_gil: bool = True): ...
+
+    def update_all(self,
+                   updates: dict[int, VideoFrameUpdate],
+                   no_gil: bool = True): ...
+
+    def redact(self,
+               q: MatchQuery,
+               spec: RedactionSpec,
+               no_gil: bool = True) -> VideoFrameBatch: ...
+
     def to_protobuf(self, no_gil: bool = True) -> bytes: ...
 
     @classmethod
@@ -604,6 +914,7 @@ class VideoFrameUpdate:
     frame_attribute_policy: AttributeUpdatePolicy
     object_attribute_policy: AttributeUpdatePolicy
     object_policy: ObjectUpdatePolicy
+    object_merge_policy: Optional[ObjectMergePolicy]
 
     def __init__(self): ...
 
@@ -615,6 +926,23 @@ class VideoFrameUpdate:
 
     def get_objects(self) -> list[tuple[VideoObject, Optional[int]]]: ...
 
+    def add_deleted_frame_attribute(self, namespace: str, name: str): ...
+
+    @property
+    def deleted_frame_attributes(self) -> list[tuple[str, str]]: ...
+
+    def add_deleted_object(self, object_id: int): ...
+
+    @property
+    def deleted_objects(self) -> list[int]: ...
+
+    def add_replaced_object(self, object: VideoObject): ...
+
+    @property
+    def replaced_objects(self) -> list[VideoObject]: ...
+
+    def is_empty(self) -> bool: ...
+
     @property
     def json(self) -> str: ...
 
@@ -643,6 +971,7 @@ class BorrowedVideoObject:
     detection_box: RBBox
     track_id: Optional[int]
     track_box: Optional[RBBox]
+    keypoints: Optional[KeyPoints]
 
     @property
     def memory_handle(self) -> int: ...
@@ -696,6 +1025,14 @@ class BorrowedVideoObject:
 
     def clear_track_info(self): ...
 
+    def enable_journal(self): ...
+
+    def disable_journal(self): ...
+
+    def get_journal(self) -> Optional[list[JournalRecord]]: ...
+
+    def take_journal(self) -> Optional[list[JournalRecord]]: ...
+
     def transform_geometry(self,
                            ops: list[VideoObjectBBoxTransformation]): ...
 
@@ -711,6 +1048,7 @@ class VideoObject:
     track_box: Optional[RBBox]
     track_id: Optional[int]
     draw_label: str
+    keypoints: Optional[KeyPoints]
 
     def __init__(self,
                  id: int,
@@ -808,3 +1146,25 @@ class QueryFunctions:
                   q: MatchQuery,
                   no_gil: bool = True) -> tuple[VideoObjectsView, VideoObjectsView]: ...
 
+    @classmethod
+    def filter_parallel(cls,
+                        v: VideoObjectsView,
+                        q: MatchQuery,
+                        chunk_size: int = 1024,
+                        num_threads: Optional[int] = None,
+                        no_gil: bool = True) -> VideoObjectsView: ...
+
+    @classmethod
+    def partition_parallel(cls,
+                           v: VideoObjectsView,
+                           q: MatchQuery,
+                           chunk_size: int = 1024,
+                           num_threads: Optional[int] = None,
+                           no_gil: bool = True) -> tuple[VideoObjectsView, VideoObjectsView]: ...
+
+    @classmethod
+    def count(cls,
+              v: VideoObjectsView,
+              q: MatchQuery,
+              no_gil: bool = True) -> int: ...
+
@@ -16,15 +16,23 @@ class Identity:
 class ClientTlsConfig:
     def __init__(self, certificate: Optional[str]=None, identity: Optional[Identity]=None): ...
 
+class SamplerConfiguration:
+    def __init__(self, ratio: float=1.0, parent_based: bool=True): ...
+
 class TracerConfiguration:
+    def __init__(self, service_name: str, protocol: Protocol, endpoint: str, tls: Optional[ClientTlsConfig]=None, timeout: Optional[int]=None, sampler: Optional[SamplerConfiguration]=None): ...
+
+class LoggerConfiguration:
     def __init__(self, service_name: str, protocol: Protocol, endpoint: str, tls: Optional[ClientTlsConfig]=None, timeout: Optional[int]=None): ...
 
 class TelemetryConfiguration:
-    def __init__(self, context_propagation_format: Optional[ContextPropagationFormat]=None, tracer: Optional[TracerConfiguration]=None): ...
+    def __init__(self, context_propagation_format: Optional[ContextPropagationFormat]=None, tracer: Optional[TracerConfiguration]=None, logger: Optional[LoggerConfiguration]=None): ...
 
     @classmethod
     def no_op(cls) -> TelemetryConfiguration: ...
 
 def init(config: TelemetryConfiguration) -> None: ...
 
+def reconfigure(config: TelemetryConfiguration) -> None: ...
+
 def shutdown() -> None: ...
@@ -13,6 +13,23 @@ def gen_frame() -> VideoFrame: ...
 def gen_empty_frame() -> VideoFrame: ...
 
 
+class FrameFixtureGenerator:
+    def __init__(self,
+                 seed: int,
+                 source_id: str = "test",
+                 width: int = 1280,
+                 height: int = 720,
+                 fps: int = 30,
+                 min_objects: int = 0,
+                 max_objects: int = 10,
+                 labels: Optional[list[tuple[str, str, int]]] = None,
+                 track_death_probability: Optional[float] = None): ...
+
+    def next_frame(self) -> VideoFrame: ...
+
+    def frames(self, n: int) -> list[VideoFrame]: ...
+
+
 def round_2_digits(num: float) -> float: ...
 
 
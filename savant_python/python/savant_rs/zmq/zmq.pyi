@@ -1,5 +1,5 @@
 from enum import Enum
-from typing import Optional, Union
+from typing import Callable, List, Optional, Union
 
 from savant_rs.utils.serialization import Message
 
@@ -16,6 +16,70 @@ class ReaderSocketType(Enum):
     Rep: int
 
 
+class CompressionCodec(Enum):
+    Disabled: int
+    Lz4: int
+    Zstd: int
+
+
+class TransportStats:
+    @property
+    def messages(self) -> int: ...
+
+    @property
+    def bytes(self) -> int: ...
+
+    @property
+    def drops(self) -> int: ...
+
+    @property
+    def hwm_hits(self) -> int: ...
+
+    @property
+    def reconnects(self) -> int: ...
+
+    @property
+    def last_activity(self) -> Optional[float]: ...
+
+
+class SourceFilter:
+    def __init__(self): ...
+
+    def allow_glob(self, pattern: str): ...
+
+    def allow_regex(self, pattern: str): ...
+
+    def deny_glob(self, pattern: str): ...
+
+    def deny_regex(self, pattern: str): ...
+
+    def accepts(self, source_id: str) -> bool: ...
+
+
+class CurveKeyPair:
+    def __init__(self, public_key: str, secret_key: str): ...
+
+    @staticmethod
+    def generate() -> CurveKeyPair: ...
+
+    @staticmethod
+    def load(path: str) -> CurveKeyPair: ...
+
+    def save(self, path: str): ...
+
+    @property
+    def public_key(self) -> str: ...
+
+    @property
+    def secret_key(self) -> str: ...
+
+
+class ValidationMode(Enum):
+    Disabled: int
+    Lenient: int
+    Strict: int
+
+
 class TopicPrefixSpec:
     @staticmethod
     def source_id(topic: str) -> TopicPrefixSpec: ...
@@ -58,6 +122,9 @@ class WriterConfig:
     @property
     def fix_ipc_permissions(self) -> Optional[bool]: ...
 
+    @property
+    def compression(self) -> CompressionCodec: ...
+
 
 class WriterConfigBuilder:
     def __init__(self, url: str): ...
@@ -80,6 +147,19 @@ class WriterConfigBuilder:
 
     def with_fix_ipc_permissions(self, fix_ipc_permissions: Optional[bool]): ...
 
+    def with_reconnect_policy(self, interval: int = 100, max_interval: int = 0,
+                              max_retries: Optional[int] = None): ...
+
+    def with_connection_callback(self, callback: Callable[[str, str, Optional[int]], None]): ...
+
+    def with_compression(self, codec: CompressionCodec): ...
+
+    def with_export_metrics(self, export: bool): ...
+
+    def with_curve_server(self, keypair: CurveKeyPair, allowed_clients: Optional[List[str]] = None): ...
+
+    def with_curve_client(self, keypair: CurveKeyPair, server_public_key: str): ...
+
     def build(self) -> WriterConfig: ...
 
 
@@ -126,6 +206,23 @@ class ReaderConfigBuilder:
 
     def with_fix_ipc_permissions(self, fix_ipc_permissions: Optional[bool]): ...
 
+    def with_validation_mode(self, mode: ValidationMode): ...
+
+    def with_source_filter(self, filter: SourceFilter): ...
+
+    def with_export_metrics(self, export: bool): ...
+
+    def with_max_decompressed_size(self, size: int): ...
+
+    def with_reconnect_policy(self, interval: int = 100, max_interval: int = 0,
+                              max_retries: Optional[int] = None): ...
+
+    def with_connection_callback(self, callback: Callable[[str, str, Optional[int]], None]): ...
+
+    def with_curve_server(self, keypair: CurveKeyPair, allowed_clients: Optional[List[str]] = None): ...
+
+    def with_curve_client(self, keypair: CurveKeyPair, server_public_key: str): ...
+
     def build(self) -> ReaderConfig: ...
 
 
@@ -172,11 +269,20 @@ class ReaderResultPrefixMismatch:
     routing_id: Optional[bytes]
 
 
+class ReaderResultInvalid:
+    message: Message
+    topic: bytes
+    routing_id: Optional[bytes]
+    violations: List[str]
+
+
 class BlockingWriter:
     def __init__(self, config: WriterConfig): ...
 
     def is_started(self) -> bool: ...
 
+    def stats(self) -> TransportStats: ...
+
     def start(self) -> None: ...
 
     def shutdown(self) -> None: ...
@@ -192,12 +298,15 @@ class BlockingReader:
 
     def is_started(self) -> bool: ...
 
+    def stats(self) -> TransportStats: ...
+
     def start(self) -> None: ...
 
     def shutdown(self) -> None: ...
 
     def receive(self) -> Union[
-        ReaderResultMessage, ReaderResultEndOfStream, ReaderResultTimeout, ReaderResultPrefixMismatch]: ...
+        ReaderResultMessage, ReaderResultEndOfStream, ReaderResultTimeout, ReaderResultPrefixMismatch,
+        ReaderResultInvalid]: ...
 
 
 class WriteOperationResult:
@@ -212,6 +321,8 @@ class NonBlockingWriter:
 
     def is_started(self) -> bool: ...
 
+    def stats(self) -> TransportStats: ...
+
     def is_shutdown(self) -> bool: ...
 
     def start(self) -> None: ...
@@ -230,6 +341,8 @@ class NonBlockingReader:
 
     def is_started(self) -> bool: ...
 
+    def stats(self) -> TransportStats: ...
+
     def is_shutdown(self) -> bool: ...
 
     def start(self) -> None: ...
@@ -237,9 +350,11 @@ class NonBlockingReader:
     def shutdown(self) -> None: ...
 
     def receive(self) -> Union[
-        ReaderResultMessage, ReaderResultEndOfStream, ReaderResultTimeout, ReaderResultPrefixMismatch]: ...
+        ReaderResultMessage, ReaderResultEndOfStream, ReaderResultTimeout, ReaderResultPrefixMismatch,
+        ReaderResultInvalid]: ...
 
     def try_receive(self) -> Optional[
-        Union[ReaderResultMessage, ReaderResultEndOfStream, ReaderResultTimeout, ReaderResultPrefixMismatch]]: ...
+        Union[ReaderResultMessage, ReaderResultEndOfStream, ReaderResultTimeout, ReaderResultPrefixMismatch,
+        ReaderResultInvalid]]: ...
 
     def enqueued_results(self) -> int: ...
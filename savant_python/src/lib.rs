@@ -3,32 +3,42 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use pyo3::wrap_pymodule;
 
+use savant_core_py::analytics::{
+    associate, TrackAggregate, TrackAttribute, TrackEvent, TrackEventKind, TrackManager, TrackState,
+};
 use savant_core_py::atomic_counter::AtomicCounter;
 use savant_core_py::draw_spec::*;
 use savant_core_py::logging::*;
 use savant_core_py::match_query::*;
 use savant_core_py::metrics::*;
 use savant_core_py::pipeline::{
-    load_stage_function_plugin, FrameProcessingStatRecord, FrameProcessingStatRecordType, Pipeline,
-    PipelineConfiguration, StageFunction, StageLatencyMeasurements, StageLatencyStat,
-    StageProcessingStat, VideoPipelineStagePayloadType,
+    create_registered_stage_function, list_registered_plugins, load_stage_function_plugin,
+    register_plugins_from_dir, FrameProcessingStatRecord, FrameProcessingStatRecordType,
+    OrderedEgress, Pipeline, PipelineConfiguration, StageFunction, StageLatencyMeasurements,
+    StageLatencyStat, StageOccupancy, StageOverflowPolicy, StageProcessingStat,
+    StageResidencyStats, VideoPipelineStagePayloadType,
 };
 use savant_core_py::primitives::attribute::Attribute;
 use savant_core_py::primitives::attribute_value::{
-    AttributeValue, AttributeValueType, AttributeValuesView,
+    AttributeValue, AttributeValueType, AttributeValuesView, TensorDType,
 };
 use savant_core_py::primitives::batch::VideoFrameBatch;
+use savant_core_py::primitives::bbox::kalman_filter::{KalmanBBoxFilter, KalmanBBoxState};
 use savant_core_py::primitives::bbox::utils::*;
 use savant_core_py::primitives::bbox::{
     BBox, BBoxMetricType, RBBox, VideoObjectBBoxTransformation,
 };
+use savant_core_py::primitives::codec::*;
 use savant_core_py::primitives::eos::EndOfStream;
 use savant_core_py::primitives::frame::{
-    VideoFrame, VideoFrameContent, VideoFrameTranscodingMethod, VideoFrameTransformation,
+    ClockSyncInfo, CropObjectPolicy, ExternalFrameDescriptor, ExternalMemoryType, VideoFrame,
+    VideoFrameContent, VideoFrameTranscodingMethod, VideoFrameTransformation,
 };
 use savant_core_py::primitives::frame_update::{
-    AttributeUpdatePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
+    AttributeUpdatePolicy, MergePolicy, ObjectMergePolicy, ObjectUpdatePolicy, VideoFrameUpdate,
 };
+use savant_core_py::primitives::journal::{get_journal_stage, set_journal_stage, JournalRecord};
+use savant_core_py::primitives::keypoints::{KeyPoint, KeyPoints};
 use savant_core_py::primitives::message::loader::*;
 use savant_core_py::primitives::message::saver::*;
 use savant_core_py::primitives::message::*;
@@ -40,9 +50,11 @@ use savant_core_py::primitives::objects_view::{
 };
 use savant_core_py::primitives::point::Point;
 use savant_core_py::primitives::polygonal_area::PolygonalArea;
+use savant_core_py::primitives::redaction::RedactionSpec;
 use savant_core_py::primitives::segment::{Intersection, IntersectionKind, Segment};
 use savant_core_py::primitives::shutdown::Shutdown;
 use savant_core_py::primitives::user_data::UserData;
+use savant_core_py::rendering::Renderer;
 use savant_core_py::telemetry::*;
 use savant_core_py::test::utils::*;
 use savant_core_py::utils::byte_buffer::ByteBuffer;
@@ -52,17 +64,33 @@ use savant_core_py::utils::symbol_mapper::*;
 use savant_core_py::utils::*;
 use savant_core_py::webserver::kvs::*;
 use savant_core_py::webserver::*;
-use savant_core_py::zmq::basic_types::{ReaderSocketType, TopicPrefixSpec, WriterSocketType};
+use savant_core_py::zmq::basic_types::{
+    CompressionCodec, CurveKeyPair, ReaderSocketType, SourceFilter, TopicPrefixSpec,
+    TransportStats, ValidationMode, WriterSocketType,
+};
 use savant_core_py::zmq::configs::{
     ReaderConfig, ReaderConfigBuilder, WriterConfig, WriterConfigBuilder,
 };
 use savant_core_py::zmq::results::{
-    ReaderResultBlacklisted, ReaderResultMessage, ReaderResultPrefixMismatch, ReaderResultTimeout,
-    WriterResultAck, WriterResultAckTimeout, WriterResultSendTimeout, WriterResultSuccess,
+    ReaderResultBlacklisted, ReaderResultInvalid, ReaderResultMessage, ReaderResultPrefixMismatch,
+    ReaderResultTimeout, WriterResultAck, WriterResultAckTimeout, WriterResultSendTimeout,
+    WriterResultSuccess,
 };
 use savant_core_py::zmq::{blocking, nonblocking};
 use savant_core_py::*;
 
+#[pymodule(gil_used = false)]
+pub fn analytics(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<TrackEventKind>()?; // PYI
+    m.add_class::<TrackState>()?; // PYI
+    m.add_class::<TrackAttribute>()?; // PYI
+    m.add_class::<TrackAggregate>()?; // PYI
+    m.add_class::<TrackEvent>()?; // PYI
+    m.add_class::<TrackManager>()?; // PYI
+    m.add_function(wrap_pyfunction!(associate, m)?)?; // PYI
+    Ok(())
+}
+
 #[pymodule(gil_used = false)]
 pub fn metrics(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<CounterFamily>()?;
@@ -100,6 +128,9 @@ pub fn kvs(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 #[pymodule(gil_used = false)]
 pub fn zmq(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<WriterSocketType>()?; // PYI
+    m.add_class::<CompressionCodec>()?; // PYI
+    m.add_class::<CurveKeyPair>()?; // PYI
+    m.add_class::<TransportStats>()?; // PYI
     m.add_class::<WriterConfigBuilder>()?; // PYI
     m.add_class::<WriterConfig>()?; // PYI
     m.add_class::<WriterResultSendTimeout>()?; // PYI
@@ -113,12 +144,15 @@ pub fn zmq(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     m.add_class::<ReaderSocketType>()?; // PYI
     m.add_class::<TopicPrefixSpec>()?; // PYI
+    m.add_class::<SourceFilter>()?; // PYI
+    m.add_class::<ValidationMode>()?; // PYI
     m.add_class::<ReaderConfigBuilder>()?; // PYI
     m.add_class::<ReaderConfig>()?; // PYI
     m.add_class::<ReaderResultMessage>()?; // PYI
     m.add_class::<ReaderResultBlacklisted>()?;
     m.add_class::<ReaderResultTimeout>()?; // PYI
     m.add_class::<ReaderResultPrefixMismatch>()?; // PYI
+    m.add_class::<ReaderResultInvalid>()?; // PYI
 
     m.add_class::<blocking::BlockingReader>()?; // PYI
     m.add_class::<nonblocking::NonBlockingReader>()?;
@@ -168,6 +202,7 @@ pub fn serialization(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 pub fn utils(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(eval_expr, m)?)?; // PYI
     m.add_function(wrap_pyfunction!(gen_frame, m)?)?; // PYI
+    m.add_class::<FrameFixtureGenerator>()?; // PYI
     m.add_function(wrap_pyfunction!(gen_empty_frame, m)?)?; // PYI
                                                             // utility
     m.add_function(wrap_pyfunction!(round_2_digits, m)?)?; // PYI
@@ -199,9 +234,13 @@ pub fn geometry(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PolygonalArea>()?;
     m.add_class::<RBBox>()?;
     m.add_class::<BBox>()?;
+    m.add_class::<KalmanBBoxFilter>()?;
+    m.add_class::<KalmanBBoxState>()?;
 
     m.add_function(wrap_pyfunction!(solely_owned_areas, m)?)?;
     m.add_function(wrap_pyfunction!(associate_bboxes, m)?)?;
+    m.add_function(wrap_pyfunction!(nms, m)?)?;
+    m.add_function(wrap_pyfunction!(cluster_boxes, m)?)?;
 
     Ok(())
 }
@@ -211,19 +250,26 @@ pub fn primitives(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Attribute>()?; // PYI
     m.add_class::<AttributeUpdatePolicy>()?; // PYI
     m.add_class::<ObjectUpdatePolicy>()?; // PYI
+    m.add_class::<MergePolicy>()?; // PYI
+    m.add_class::<ObjectMergePolicy>()?; // PYI
     m.add_class::<AttributeValue>()?; // PYI
     m.add_class::<AttributeValueType>()?; // PYI
     m.add_class::<AttributeValuesView>()?; // PYI
+    m.add_class::<TensorDType>()?; // PYI
     m.add_class::<EndOfStream>()?; // PYI
     m.add_class::<Shutdown>()?; // PYI
     m.add_class::<UserData>()?; // PYI
 
     m.add_class::<VideoFrame>()?; // PYI
+    m.add_class::<ClockSyncInfo>()?; // PYI
     m.add_class::<VideoFrameBatch>()?; // PYI
     m.add_class::<VideoFrameContent>()?; // PYI
+    m.add_class::<ExternalMemoryType>()?; // PYI
+    m.add_class::<ExternalFrameDescriptor>()?; // PYI
     m.add_class::<VideoFrameTranscodingMethod>()?; // PYI
     m.add_class::<VideoFrameUpdate>()?; // PYI
     m.add_class::<VideoFrameTransformation>()?; // PYI
+    m.add_class::<CropObjectPolicy>()?; // PYI
 
     m.add_class::<BorrowedVideoObject>()?; // PYI
     m.add_class::<VideoObject>()?; // PYI
@@ -231,6 +277,23 @@ pub fn primitives(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     m.add_class::<IdCollisionResolutionPolicy>()?; // PYI
 
+    m.add_class::<CodecInfo>()?; // PYI
+    m.add_function(wrap_pyfunction!(register_codec, m)?)?; // PYI
+    m.add_function(wrap_pyfunction!(find_codec, m)?)?; // PYI
+    m.add_function(wrap_pyfunction!(find_codec_by_fourcc, m)?)?; // PYI
+    m.add_function(wrap_pyfunction!(find_codecs_by_mime_type, m)?)?; // PYI
+    m.add_function(wrap_pyfunction!(known_codecs, m)?)?; // PYI
+    m.add_function(wrap_pyfunction!(transcoding_required, m)?)?; // PYI
+
+    m.add_class::<RedactionSpec>()?; // PYI
+
+    m.add_class::<JournalRecord>()?; // PYI
+    m.add_function(wrap_pyfunction!(set_journal_stage, m)?)?; // PYI
+    m.add_function(wrap_pyfunction!(get_journal_stage, m)?)?; // PYI
+
+    m.add_class::<KeyPoint>()?; // PYI
+    m.add_class::<KeyPoints>()?; // PYI
+
     m.add_wrapped(wrap_pymodule!(self::geometry))?;
     Ok(())
 }
@@ -246,7 +309,14 @@ pub(crate) fn pipeline(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<StageLatencyMeasurements>()?;
     m.add_class::<FrameProcessingStatRecordType>()?;
     m.add_class::<StageFunction>()?;
+    m.add_class::<StageOverflowPolicy>()?;
+    m.add_class::<StageOccupancy>()?;
+    m.add_class::<OrderedEgress>()?;
+    m.add_class::<StageResidencyStats>()?;
     m.add_function(wrap_pyfunction!(load_stage_function_plugin, m)?)?;
+    m.add_function(wrap_pyfunction!(register_plugins_from_dir, m)?)?;
+    m.add_function(wrap_pyfunction!(list_registered_plugins, m)?)?;
+    m.add_function(wrap_pyfunction!(create_registered_stage_function, m)?)?;
     Ok(())
 }
 
@@ -259,19 +329,32 @@ pub fn match_query(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<QueryFunctions>()?;
     m.add_class::<EtcdCredentials>()?;
     m.add_class::<TlsConfig>()?;
+    m.add_class::<LayeredConfig>()?;
 
     m.add_function(wrap_pyfunction!(utility_resolver_name, m)?)?;
     m.add_function(wrap_pyfunction!(etcd_resolver_name, m)?)?;
     m.add_function(wrap_pyfunction!(env_resolver_name, m)?)?;
     m.add_function(wrap_pyfunction!(config_resolver_name, m)?)?;
+    m.add_function(wrap_pyfunction!(redis_resolver_name, m)?)?;
+    m.add_function(wrap_pyfunction!(http_resolver_name, m)?)?;
+    m.add_function(wrap_pyfunction!(file_resolver_name, m)?)?;
 
     m.add_function(wrap_pyfunction!(register_utility_resolver, m)?)?;
     m.add_function(wrap_pyfunction!(register_env_resolver, m)?)?;
     m.add_function(wrap_pyfunction!(register_etcd_resolver, m)?)?;
+    m.add_function(wrap_pyfunction!(register_redis_resolver, m)?)?;
+    m.add_function(wrap_pyfunction!(register_http_resolver, m)?)?;
+    m.add_function(wrap_pyfunction!(register_file_resolver, m)?)?;
+    m.add_function(wrap_pyfunction!(reload_file_resolver, m)?)?;
     m.add_function(wrap_pyfunction!(register_config_resolver, m)?)?;
     m.add_function(wrap_pyfunction!(update_config_resolver, m)?)?;
+    m.add_function(wrap_pyfunction!(register_layered_config_resolver, m)?)?;
 
     m.add_function(wrap_pyfunction!(unregister_resolver, m)?)?;
+
+    m.add_function(wrap_pyfunction!(configure_resolver_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(set_resolver_cache_ttl, m)?)?;
+    m.add_function(wrap_pyfunction!(invalidate_resolver_cache, m)?)?;
     Ok(())
 }
 
@@ -295,6 +378,8 @@ pub fn draw_spec(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<LabelPosition>()?; // PYI
     m.add_class::<PaddingDraw>()?; // PYI
     m.add_class::<ObjectDraw>()?; // PYI
+    m.add_class::<DrawSpecRegistry>()?; // PYI
+    m.add_class::<Renderer>()?; // PYI
     m.add_class::<SetDrawLabelKind>()?;
     Ok(())
 }
@@ -305,9 +390,12 @@ pub fn telemetry(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Protocol>()?; // PYI
     m.add_class::<Identity>()?; // PYI
     m.add_class::<ClientTlsConfig>()?; // PYI
+    m.add_class::<SamplerConfiguration>()?; // PYI
     m.add_class::<TracerConfiguration>()?; // PYI
+    m.add_class::<LoggerConfiguration>()?; // PYI
     m.add_class::<TelemetryConfiguration>()?; // PYI
     m.add_function(wrap_pyfunction!(init, m)?)?; // PYI
+    m.add_function(wrap_pyfunction!(reconfigure, m)?)?; // PYI
     m.add_function(wrap_pyfunction!(shutdown, m)?)?; // PYI
     Ok(())
 }
@@ -321,7 +409,11 @@ fn savant_rs(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
             std::env::set_var(log_env_var_name, log_env_var_level);
         }
     }
-    pretty_env_logger::try_init_custom_env(log_env_var_name)
+    let logger = pretty_env_logger::formatted_builder()
+        .parse_env(log_env_var_name)
+        .build();
+    let max_level = logger.filter();
+    install_log_bridge(logger, max_level)
         .map_err(|_| PyRuntimeError::new_err("Failed to initialize logger"))?;
     set_log_level(LogLevel::Error);
 
@@ -341,6 +433,7 @@ fn savant_rs(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_wrapped(wrap_pymodule!(self::webserver))?; // PYI
     m.add_wrapped(wrap_pymodule!(self::metrics))?; // PYI
     m.add_wrapped(wrap_pymodule!(self::kvs))?; // PYI
+    m.add_wrapped(wrap_pymodule!(self::analytics))?; // PYI
 
     let sys = PyModule::import(py, "sys")?;
     let sys_modules_bind = sys.as_ref().getattr("modules")?;
@@ -360,6 +453,7 @@ fn savant_rs(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     sys_modules.set_item("savant_rs.webserver", m.getattr("webserver")?)?;
     sys_modules.set_item("savant_rs.webserver.kvs", m.getattr("kvs")?)?;
     sys_modules.set_item("savant_rs.metrics", m.getattr("metrics")?)?;
+    sys_modules.set_item("savant_rs.analytics", m.getattr("analytics")?)?;
 
     sys_modules.set_item("savant_rs.utils.symbol_mapper", m.getattr("symbol_mapper")?)?;
 